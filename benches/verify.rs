@@ -0,0 +1,239 @@
+//! Regression baseline for the cost of verification.
+//!
+//! `cargo bench`, or `cargo bench -- --save-baseline <name>` and
+//! `cargo bench -- --baseline <name>` to compare releases.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use polkadot_sig_verifier::{bench, AddressInfo, VALID};
+use sp_core::crypto::Ss58Codec;
+use sp_core::{sr25519, Pair};
+use std::ffi::{c_char, c_void, CString};
+
+// The C API, called as a C host calls it
+extern "C" {
+  fn cache_init(capacity: usize);
+  fn validate_ss58_address(address_ptr: *const c_char, out: *mut AddressInfo) -> i32;
+  fn verifier_new(address_ptr: *const c_char) -> *mut c_void;
+  fn verifier_verify(verifier: *mut c_void, signature_ptr: *const c_char, message_ptr: *const u8, message_len: usize)
+    -> i32;
+  fn verifier_free(verifier: *mut c_void);
+  fn verify_batch(
+    addresses: *const *const c_char,
+    signatures: *const *const c_char,
+    messages: *const *const c_char,
+    count: usize,
+    results: *mut i32,
+  ) -> i32;
+  fn verify_batch_parallel(
+    addresses: *const *const c_char,
+    signatures: *const *const c_char,
+    messages: *const *const c_char,
+    count: usize,
+    results: *mut i32,
+    num_threads: u32,
+  ) -> i32;
+  fn verify_by_account_id(account_id: *const u8, signature: *const u8, message: *const u8, message_len: usize) -> i32;
+  fn verify_polkadot_signature_bytes(
+    address_ptr: *const c_char,
+    signature_ptr: *const c_char,
+    message_ptr: *const u8,
+    message_len: usize,
+  ) -> i32;
+  fn verify_polkadot_signature_v2(address_ptr: *const c_char, signature_ptr: *const c_char, message_ptr: *const c_char)
+    -> i32;
+}
+
+/// An Alice address, a signature by it and the signed message.
+fn signed(message: &str) -> (CString, CString, CString) {
+  let pair = sr25519::Pair::from_string("//Alice", None).unwrap();
+  let signature = format!("0x{}", hex::encode(pair.sign(message.as_bytes())));
+  let address = CString::new(pair.public().to_ss58check()).unwrap();
+  (address, CString::new(signature).unwrap(), CString::new(message).unwrap())
+}
+
+/// Parallel arrays of pointers into `items`, as `verify_batch` takes them.
+fn pointers(items: &[(CString, CString, CString)]) -> (Vec<*const c_char>, Vec<*const c_char>, Vec<*const c_char>) {
+  let addresses = items.iter().map(|(address, _, _)| address.as_ptr()).collect();
+  let signatures = items.iter().map(|(_, signature, _)| signature.as_ptr()).collect();
+  let messages = items.iter().map(|(_, _, message)| message.as_ptr()).collect();
+  (addresses, signatures, messages)
+}
+
+fn single_verify(c: &mut Criterion) {
+  let (address, signature, message) = signed("<Bytes>hello</Bytes>");
+  assert_eq!(unsafe { verify_polkadot_signature_v2(address.as_ptr(), signature.as_ptr(), message.as_ptr()) }, VALID);
+  c.bench_function("verify_polkadot_signature_v2", |b| {
+    b.iter(|| unsafe {
+      verify_polkadot_signature_v2(black_box(address.as_ptr()), signature.as_ptr(), message.as_ptr())
+    })
+  });
+}
+
+fn batch_verify(c: &mut Criterion) {
+  let mut group = c.benchmark_group("verify_batch");
+  for count in [1, 16, 256] {
+    let items: Vec<_> = (0..count).map(|i| signed(&format!("message {i}"))).collect();
+    let (addresses, signatures, messages) = pointers(&items);
+    let mut results = vec![0; count];
+    group.throughput(Throughput::Elements(count as u64));
+    group.bench_function(BenchmarkId::from_parameter(count), |b| {
+      b.iter(|| {
+        let (addresses, signatures, messages) = (addresses.as_ptr(), signatures.as_ptr(), messages.as_ptr());
+        let failed = unsafe { verify_batch(addresses, signatures, messages, count, results.as_mut_ptr()) };
+        assert_eq!(failed, 0);
+      })
+    });
+  }
+  group.finish();
+}
+
+/// The same sr25519 items verified one call at a time and in one
+/// `verify_batch`, which checks them with schnorrkel's batch verification.
+fn sr25519_batching(c: &mut Criterion) {
+  const COUNT: usize = 1024;
+  let items: Vec<_> = (0..COUNT).map(|i| signed(&format!("message {i}"))).collect();
+  let (addresses, signatures, messages) = pointers(&items);
+  let mut results = vec![0; COUNT];
+  let mut group = c.benchmark_group("sr25519_batching");
+  group.throughput(Throughput::Elements(COUNT as u64));
+  group.bench_function("one_by_one", |b| {
+    b.iter(|| {
+      for (address, signature, message) in &items {
+        let code = unsafe { verify_polkadot_signature_v2(address.as_ptr(), signature.as_ptr(), message.as_ptr()) };
+        assert_eq!(code, VALID);
+      }
+    })
+  });
+  group.bench_function("batched", |b| {
+    b.iter(|| {
+      let (addresses, signatures, messages) = (addresses.as_ptr(), signatures.as_ptr(), messages.as_ptr());
+      let failed = unsafe { verify_batch(addresses, signatures, messages, COUNT, results.as_mut_ptr()) };
+      assert_eq!(failed, 0);
+    })
+  });
+  group.finish();
+}
+
+/// `verify_batch_parallel` on one thread up to one per core.
+fn parallel_scaling(c: &mut Criterion) {
+  const COUNT: usize = 4096;
+  let items: Vec<_> = (0..COUNT).map(|i| signed(&format!("message {i}"))).collect();
+  let (addresses, signatures, messages) = pointers(&items);
+  let mut results = vec![0; COUNT];
+  let cores = std::thread::available_parallelism().map_or(1, |cores| cores.get()) as u32;
+  let mut group = c.benchmark_group("verify_batch_parallel");
+  group.throughput(Throughput::Elements(COUNT as u64));
+  for num_threads in (0..).map(|power| 1 << power).take_while(|&n| n <= cores) {
+    group.bench_function(BenchmarkId::from_parameter(num_threads), |b| {
+      b.iter(|| {
+        let (addresses, signatures, messages) = (addresses.as_ptr(), signatures.as_ptr(), messages.as_ptr());
+        let results = results.as_mut_ptr();
+        let failed = unsafe { verify_batch_parallel(addresses, signatures, messages, COUNT, results, num_threads) };
+        assert_eq!(failed, 0);
+      })
+    });
+  }
+  group.finish();
+}
+
+/// Repeated checks against one address, parsing it every call or once in
+/// a verifier handle.
+fn verifier(c: &mut Criterion) {
+  let (address, signature, message) = signed("hello");
+  let message = message.as_bytes();
+  let verifier = unsafe { verifier_new(address.as_ptr()) };
+  let mut group = c.benchmark_group("verifier");
+  group.bench_function("stateless", |b| {
+    b.iter(|| {
+      let (address, signature) = (address.as_ptr(), signature.as_ptr());
+      let code = unsafe { verify_polkadot_signature_bytes(address, signature, message.as_ptr(), message.len()) };
+      assert_eq!(code, VALID);
+    })
+  });
+  group.bench_function("handle", |b| {
+    b.iter(|| {
+      let code = unsafe { verifier_verify(verifier, signature.as_ptr(), message.as_ptr(), message.len()) };
+      assert_eq!(code, VALID);
+    })
+  });
+  group.finish();
+  unsafe { verifier_free(verifier) };
+}
+
+/// The string entry point against `verify_by_account_id`, which skips
+/// SS58 and hex decoding.
+fn account_id(c: &mut Criterion) {
+  let public = sr25519::Pair::from_string("//Alice", None).unwrap().public();
+  let account_id: &[u8] = public.as_ref();
+  let (address, signature, message) = signed("hello");
+  let signature_bytes = hex::decode(&signature.to_str().unwrap()[2..]).unwrap();
+  let message_bytes = message.as_bytes();
+  let mut group = c.benchmark_group("account_id");
+  group.bench_function("strings", |b| {
+    b.iter(|| {
+      let code = unsafe { verify_polkadot_signature_v2(address.as_ptr(), signature.as_ptr(), message.as_ptr()) };
+      assert_eq!(code, VALID);
+    })
+  });
+  group.bench_function("raw", |b| {
+    b.iter(|| {
+      let (signature, message) = (signature_bytes.as_ptr(), message_bytes.as_ptr());
+      assert_eq!(unsafe { verify_by_account_id(account_id.as_ptr(), signature, message, message_bytes.len()) }, VALID);
+    })
+  });
+  group.finish();
+}
+
+/// `verify_polkadot_signature_v2` with the address cache off and on.
+fn address_cache(c: &mut Criterion) {
+  let (address, signature, message) = signed("hello");
+  let mut group = c.benchmark_group("address_cache");
+  for (name, capacity) in [("uncached", 0), ("cached", 1024)] {
+    unsafe { cache_init(capacity) };
+    group.bench_function(name, |b| {
+      b.iter(|| {
+        let code = unsafe { verify_polkadot_signature_v2(address.as_ptr(), signature.as_ptr(), message.as_ptr()) };
+        assert_eq!(code, VALID);
+      })
+    });
+  }
+  group.finish();
+  unsafe { cache_init(0) };
+}
+
+/// Decoding a hex signature into a fresh `Vec`, as every verify call once
+/// did, against the stack-only decoding used now.
+fn signature_decoding(c: &mut Criterion) {
+  let (_, signature, _) = signed("hello");
+  let signature = signature.to_str().unwrap();
+  let mut group = c.benchmark_group("decode_signature");
+  group.bench_function("allocating", |b| {
+    b.iter(|| {
+      let bytes = hex::decode(black_box(signature).trim_start_matches("0x")).unwrap();
+      <[u8; 64]>::try_from(bytes.as_slice()).unwrap()
+    })
+  });
+  group.bench_function("on_the_stack", |b| b.iter(|| bench::decode_signature(black_box(signature)).unwrap()));
+  group.finish();
+}
+
+fn address_parsing(c: &mut Criterion) {
+  let (address, _, _) = signed("");
+  c.bench_function("validate_ss58_address", |b| {
+    b.iter(|| unsafe { validate_ss58_address(black_box(address.as_ptr()), std::ptr::null_mut()) })
+  });
+}
+
+criterion_group!(
+  benches,
+  single_verify,
+  batch_verify,
+  sr25519_batching,
+  parallel_scaling,
+  verifier,
+  account_id,
+  address_cache,
+  signature_decoding,
+  address_parsing
+);
+criterion_main!(benches);
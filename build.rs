@@ -0,0 +1,34 @@
+//! Record what `get_build_info` reports: the enabled features, the target
+//! triple and the git commit.
+
+use std::env;
+use std::path::Path;
+use std::process::Command;
+
+fn main() {
+  let mut features: Vec<String> = env::vars()
+    .filter_map(|(name, _)| name.strip_prefix("CARGO_FEATURE_").map(|feature| feature.to_lowercase().replace('_', "-")))
+    .collect();
+  features.sort();
+  println!("cargo:rustc-env=PSV_BUILD_FEATURES={}", features.join(","));
+  println!("cargo:rustc-env=PSV_BUILD_TARGET={}", env::var("TARGET").unwrap_or_else(|_| "unknown".to_owned()));
+
+  // Building from a crate tarball or without git installed is not an error
+  let commit = Command::new("git")
+    .args(["describe", "--always", "--dirty"])
+    .output()
+    .ok()
+    .filter(|output| output.status.success())
+    .and_then(|output| String::from_utf8(output.stdout).ok())
+    .map(|describe| describe.trim().to_owned())
+    .filter(|describe| !describe.is_empty())
+    .unwrap_or_else(|| "unknown".to_owned());
+  println!("cargo:rustc-env=PSV_BUILD_COMMIT={commit}");
+
+  println!("cargo:rerun-if-changed=build.rs");
+  for git_file in [".git/HEAD", ".git/index"] {
+    if Path::new(git_file).exists() {
+      println!("cargo:rerun-if-changed={git_file}");
+    }
+  }
+}
@@ -0,0 +1,176 @@
+//! Return codes and option flags of the main crate's C API, defined here
+//! so the browser build reports the same values.
+//!
+//! Non-negative codes are verdicts, negative codes say why no verdict
+//! could be reached. Bindings should mirror these values verbatim.
+
+/// The signature is valid for the given address and message.
+pub const VALID: i32 = 1;
+/// The inputs were well formed but the signature does not verify.
+pub const INVALID: i32 = 0;
+
+/// One of the pointer arguments was NULL.
+pub const ERR_NULL_POINTER: i32 = -1;
+/// A string argument was not valid UTF-8.
+pub const ERR_INVALID_UTF8: i32 = -2;
+/// The address could not be decoded as SS58 for a reason not covered by
+/// the more specific `ERR_SS58_*` codes.
+pub const ERR_BAD_SS58: i32 = -3;
+/// The signature was not valid hex.
+pub const ERR_BAD_HEX: i32 = -4;
+/// The signature decoded to the wrong number of bytes.
+pub const ERR_BAD_SIGNATURE_LENGTH: i32 = -5;
+/// The library panicked internally; `get_last_error` has the panic message.
+pub const ERR_PANIC: i32 = -6;
+/// The address decoded but its SS58 prefix is not in the allowed list.
+pub const ERR_WRONG_NETWORK: i32 = -7;
+/// The address contains characters outside the base58 alphabet.
+pub const ERR_SS58_BAD_BASE58: i32 = -8;
+/// The address decoded to a payload of the wrong length.
+pub const ERR_SS58_BAD_LENGTH: i32 = -9;
+/// The address checksum does not match, usually a mistyped character.
+pub const ERR_SS58_BAD_CHECKSUM: i32 = -10;
+/// The signature length does not belong to any supported scheme, or
+/// belongs to one that cannot verify the kind of key the address holds.
+pub const ERR_NO_MATCHING_SCHEME: i32 = -11;
+/// A SCALE-encoded `MultiSignature` had an unknown variant tag or the
+/// wrong payload length for its variant.
+pub const ERR_BAD_MULTISIGNATURE: i32 = -12;
+/// A 65-byte signature started with a byte that is not a known scheme tag.
+pub const ERR_BAD_SIGNATURE_PREFIX: i32 = -13;
+/// An Ethereum-style address was not 20 hex-encoded bytes.
+pub const ERR_BAD_ETH_ADDRESS: i32 = -14;
+/// A mixed-case Ethereum-style address failed its EIP-55 checksum.
+pub const ERR_ETH_ADDRESS_CHECKSUM: i32 = -15;
+/// A scheme name or number did not match any `SignatureScheme`.
+pub const ERR_UNKNOWN_SCHEME: i32 = -16;
+/// The signature is a malleated, non-canonical encoding: an ECDSA
+/// signature with a high `s`, or an sr25519 signature whose `s` is not
+/// reduced.
+pub const ERR_NON_CANONICAL_SIGNATURE: i32 = -17;
+/// `OPT_MESSAGE_IS_HEX` was set but the message was not valid hex.
+pub const ERR_BAD_MESSAGE_HEX: i32 = -18;
+/// A base64 signature or message was not valid base64.
+pub const ERR_BAD_BASE64: i32 = -19;
+/// `VerifyOptions` combined flags that contradict each other.
+pub const ERR_INVALID_OPTIONS: i32 = -20;
+/// `VerifyOptions::strict` was set and an input would have needed
+/// normalizing, such as a 0x prefix on hex.
+pub const ERR_NOT_STRICT: i32 = -21;
+/// A stream, nonce cache or keypair handle was NULL, unknown or already
+/// finalized or freed.
+pub const ERR_INVALID_HANDLE: i32 = -22;
+/// The file to verify does not exist.
+pub const ERR_FILE_NOT_FOUND: i32 = -23;
+/// The file to verify exists but may not be read.
+pub const ERR_FILE_PERMISSION_DENIED: i32 = -24;
+/// Reading the file to verify failed for another reason.
+pub const ERR_FILE_IO: i32 = -25;
+/// A wide string argument was not valid UTF-16, such as a lone surrogate.
+pub const ERR_INVALID_UTF16: i32 = -26;
+/// A JSON request was malformed or missed a required field.
+pub const ERR_BAD_JSON: i32 = -27;
+/// A Sign-In with Substrate message could not be parsed.
+pub const ERR_SIWS_MALFORMED: i32 = -28;
+/// The address in a Sign-In with Substrate message is not the expected one.
+pub const ERR_SIWS_ADDRESS_MISMATCH: i32 = -29;
+/// The domain in a Sign-In with Substrate message is not the expected one.
+pub const ERR_SIWS_DOMAIN_MISMATCH: i32 = -30;
+/// The nonce in a Sign-In with Substrate message is not the expected one.
+pub const ERR_SIWS_NONCE_MISMATCH: i32 = -31;
+/// The signed message has expired.
+pub const ERR_EXPIRED: i32 = -32;
+/// The signed message is not valid yet, such as one issued in the future.
+pub const ERR_NOT_YET_VALID: i32 = -33;
+/// The message does not contain a timestamp in the expected format.
+pub const ERR_TIMESTAMP_NOT_FOUND: i32 = -34;
+/// The signature verified, but its nonce was already used by the same
+/// address within the nonce cache's TTL.
+pub const ERR_REPLAYED: i32 = -35;
+/// A public key given as hex, in place of an address or to
+/// `public_key_to_ss58`, was not 32 bytes long.
+pub const ERR_BAD_PUBLIC_KEY_LENGTH: i32 = -36;
+/// `addresses_equal`: the first address did not decode, see
+/// `get_last_error` for why.
+pub const ERR_BAD_FIRST_ADDRESS: i32 = -37;
+/// `addresses_equal`: the second address did not decode.
+pub const ERR_BAD_SECOND_ADDRESS: i32 = -38;
+/// A derivation path has a hard junction (`//`), which needs the secret
+/// key and cannot be applied to a public key.
+pub const ERR_HARD_DERIVATION: i32 = -39;
+/// A derivation path is not a sequence of `/soft` or `//hard` junctions.
+pub const ERR_BAD_DERIVATION_PATH: i32 = -40;
+/// A `did:key` names a key type other than sr25519, ed25519 or secp256k1.
+pub const ERR_UNSUPPORTED_KEY_TYPE: i32 = -41;
+/// A `did:key` is not `did:key:z` followed by base58btc of a multicodec
+/// key type and a public key of the matching length.
+pub const ERR_BAD_DID_KEY: i32 = -42;
+/// An SS58 address with a valid checksum holds a payload of a length no
+/// supported key type has, such as a 20-byte `AccountId20`.
+pub const ERR_WRONG_KEY_LENGTH: i32 = -43;
+/// The seed passed to `sign_sr25519` is not 32 bytes.
+pub const ERR_BAD_SEED_LENGTH: i32 = -44;
+/// A mnemonic does not have 12, 15, 18, 21 or 24 words.
+pub const ERR_BAD_MNEMONIC: i32 = -45;
+/// A mnemonic has a word missing from the English BIP39 word list.
+pub const ERR_UNKNOWN_MNEMONIC_WORD: i32 = -46;
+/// The words of a mnemonic are all known but its BIP39 checksum does not
+/// match, as when a word was swapped or mistyped as another valid word.
+pub const ERR_BAD_MNEMONIC_CHECKSUM: i32 = -47;
+/// A dev account name is not one of Alice, Bob, Charlie, Dave, Eve or
+/// Ferdie.
+pub const ERR_UNKNOWN_DEV_ACCOUNT: i32 = -48;
+/// A 32-byte seed is not a valid secret key, such as zero or a value
+/// above the secp256k1 group order for `sign_ecdsa`.
+pub const ERR_BAD_SEED: i32 = -49;
+/// The threads for `verify_batch_parallel` or `verify_async` could not be
+/// started.
+pub const ERR_THREAD_POOL: i32 = -50;
+/// An asynchronous job was abandoned by `async_shutdown` before it ran.
+pub const ERR_JOB_CANCELLED: i32 = -51;
+/// The signature scheme was left out of this build by its cargo feature,
+/// see `get_supported_schemes`. Also returned by key generation, signing
+/// and derivation in builds without the `sp-core` feature.
+pub const ERR_SCHEME_NOT_SUPPORTED: i32 = -52;
+/// The extrinsic given to `verify_extrinsic` is unsigned, so there is
+/// no signature to verify.
+pub const ERR_NOT_SIGNED: i32 = -53;
+/// The extrinsic given to `verify_extrinsic` could not be SCALE-decoded
+/// as a v4 signed extrinsic, or its signer is not an account ID.
+pub const ERR_BAD_EXTRINSIC: i32 = -54;
+/// The era given to `verify_signing_payload` is not a SCALE-encoded
+/// `Era`: one zero byte for an immortal era or two bytes for a mortal one.
+pub const ERR_BAD_ERA: i32 = -55;
+/// A fixed-length hex argument other than the signature, such as a block
+/// hash or VRF proof, decoded to the wrong number of bytes.
+pub const ERR_BAD_HEX_LENGTH: i32 = -56;
+
+/// `VerifyOptions::flags`: wrap the message in `<Bytes>...</Bytes>` before
+/// verifying, unless it is already wrapped.
+pub const OPT_WRAP_BYTES: u32 = 1 << 0;
+/// `VerifyOptions::flags`: accept 65-byte signatures whose first byte is a
+/// `MultiSignature` tag (0x00 ed25519, 0x01 sr25519) and verify the rest.
+pub const OPT_STRIP_SCHEME_PREFIX: u32 = 1 << 1;
+/// `VerifyOptions::flags`: accept high-s ECDSA signatures from legacy
+/// wallets instead of failing with `ERR_NON_CANONICAL_SIGNATURE`.
+pub const OPT_ALLOW_NON_CANONICAL_ECDSA: u32 = 1 << 2;
+/// `VerifyOptions::flags`: the message argument is hex, with or without 0x
+/// prefix, and the signature is over the decoded bytes. Combined with
+/// `OPT_WRAP_BYTES`, the decoded bytes are wrapped.
+pub const OPT_MESSAGE_IS_HEX: u32 = 1 << 3;
+/// `VerifyOptions::flags`: the signature argument is base64 instead of hex.
+/// Both the standard and the URL-safe alphabet are accepted, padded or not.
+pub const OPT_SIGNATURE_BASE64: u32 = 1 << 4;
+/// `VerifyOptions::flags`: the message argument is base64, like
+/// `OPT_SIGNATURE_BASE64`, and the signature is over the decoded bytes.
+/// Cannot be combined with `OPT_MESSAGE_IS_HEX`.
+pub const OPT_MESSAGE_BASE64: u32 = 1 << 5;
+/// `VerifyOptions::flags`: verify messages longer than 256 bytes directly.
+/// By default they are verified as their `blake2_256` hash, the way
+/// polkadot-js signs them. The length is taken after `<Bytes>` wrapping.
+pub const OPT_SKIP_LONG_MESSAGE_HASH: u32 = 1 << 6;
+/// `VerifyOptions::flags`: NFC-normalize UTF-8 messages before verifying,
+/// inside any `<Bytes>` wrapper and before wrapping or hashing. In strict
+/// mode a message that normalization would change fails with
+/// `ERR_NOT_STRICT` instead.
+pub const OPT_NFC_NORMALIZE: u32 = 1 << 7;
@@ -0,0 +1,252 @@
+//! The sr25519 verification path of `polkadot_sig_verifier`, without the
+//! C API.
+//!
+//! With the default `std` feature off this crate is `#![no_std]` and needs
+//! only `alloc`, so it builds for WASM runtime modules and embedded
+//! targets:
+//!
+//! ```text
+//! cargo check -p polkadot_sig_verifier_core --no-default-features --target thumbv7em-none-eabihf
+//! ```
+//!
+//! [`codes`] holds the main crate's return codes and option flags, and
+//! with the `serde` feature [`types`] its JSON request and outcome types.
+//! With the `wasm` feature, [`wasm`] exposes verification to JavaScript
+//! through `wasm-bindgen`.
+//!
+//! SS58 addresses are decoded with `bs58` and `blake2`, and signatures
+//! checked with `schnorrkel`, as `sp_core` does underneath. The main crate's
+//! `minimal` feature verifies through here, and its tests check the
+//! results against `sp_core`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub mod codes;
+#[cfg(feature = "serde")]
+pub mod types;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+use alloc::format;
+use alloc::string::String;
+use blake2::digest::consts::U32;
+use blake2::{Blake2b, Blake2b512, Digest};
+use core::fmt;
+
+/// The signing context `sr25519::Pair` signs and verifies under.
+pub const SIGNING_CONTEXT: &[u8] = b"substrate";
+
+/// Messages longer than this are verified as their `blake2_256` hash,
+/// following polkadot-js, whose `signRaw` hashes them before signing.
+/// `sr25519::Pair::sign` does not: it always signs the bytes it is given.
+pub const MAX_UNHASHED_MESSAGE_LEN: usize = 256;
+
+const CHECKSUM_LEN: usize = 2;
+
+/// Why an SS58 address could not be decoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ss58Error {
+  BadBase58,
+  /// Also reported for a 33-byte ECDSA payload, which is not an account.
+  BadLength,
+  BadChecksum,
+  BadPrefix,
+  /// Prefixes 46 and 47 are reserved.
+  FormatNotAllowed,
+}
+
+impl fmt::Display for Ss58Error {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(match self {
+      Ss58Error::BadBase58 => "address is not valid base58",
+      Ss58Error::BadLength => "SS58 payload has the wrong length",
+      Ss58Error::BadChecksum => "SS58 checksum mismatch",
+      Ss58Error::BadPrefix => "SS58 prefix is invalid",
+      Ss58Error::FormatNotAllowed => "SS58 decode failed: FormatNotAllowed",
+    })
+  }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Ss58Error {}
+
+/// Why [`verify`] could not check a signature.
+// Not `Eq`, as `hex::FromHexError` is not
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VerifyError {
+  Ss58(Ss58Error),
+  /// The signature is not hex after its 0x prefixes, as
+  /// [`signature_hex_digits`] strips them.
+  SignatureHex(hex::FromHexError),
+  /// The signature decoded to `found` bytes rather than 64.
+  SignatureLength { found: usize },
+}
+
+impl fmt::Display for VerifyError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      VerifyError::Ss58(error) => error.fmt(f),
+      VerifyError::SignatureHex(error) => f.write_str(&hex_reason(*error, "signature")),
+      VerifyError::SignatureLength { found } => write!(f, "signature is {found} bytes, expected 64"),
+    }
+  }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for VerifyError {}
+
+impl From<Ss58Error> for VerifyError {
+  fn from(error: Ss58Error) -> Self {
+    VerifyError::Ss58(error)
+  }
+}
+
+/// Decode an SS58 address whose payload is a 32-byte account id into the
+/// id and the network prefix.
+///
+/// Decoded step by step as `Ss58Codec::from_ss58check_with_version` does,
+/// so the first problem found is the one `sp_core` reports.
+pub fn decode_ss58_account(address: &str) -> Result<([u8; 32], u16), Ss58Error> {
+  let data = bs58::decode(address).into_vec().map_err(|_| Ss58Error::BadBase58)?;
+  if data.len() < 2 {
+    return Err(Ss58Error::BadLength);
+  }
+  let (prefix_len, prefix) = match data[0] {
+    0..=63 => (1, u16::from(data[0])),
+    64..=127 => {
+      let lower = (data[0] << 2) | (data[1] >> 6);
+      let upper = data[1] & 0b0011_1111;
+      (2, u16::from(lower) | (u16::from(upper) << 8))
+    }
+    _ => return Err(Ss58Error::BadPrefix),
+  };
+  if data.len() != prefix_len + 32 + CHECKSUM_LEN {
+    return Err(Ss58Error::BadLength);
+  }
+  if matches!(prefix, 46 | 47) {
+    return Err(Ss58Error::FormatNotAllowed);
+  }
+  let (body, checksum) = data.split_at(prefix_len + 32);
+  let hash = Blake2b512::new().chain_update(b"SS58PRE").chain_update(body).finalize();
+  if hash[..CHECKSUM_LEN] != *checksum {
+    return Err(Ss58Error::BadChecksum);
+  }
+  let mut account_id = [0u8; 32];
+  account_id.copy_from_slice(&body[prefix_len..]);
+  Ok((account_id, prefix))
+}
+
+/// Check a 64-byte sr25519 signature over `message` by `public_key`, under
+/// [`SIGNING_CONTEXT`].
+///
+/// Keys and signatures schnorrkel cannot decode do not verify.
+pub fn verify_sr25519(signature: &[u8; 64], message: &[u8], public_key: &[u8; 32]) -> bool {
+  let Ok(signature) = schnorrkel::Signature::from_bytes(signature) else { return false };
+  let Ok(public_key) = schnorrkel::PublicKey::from_bytes(public_key) else { return false };
+  public_key.verify_simple(SIGNING_CONTEXT, message, &signature).is_ok()
+}
+
+/// The 32-byte BLAKE2b hash of `data`.
+pub fn blake2_256(data: &[u8]) -> [u8; 32] {
+  Blake2b::<U32>::digest(data).into()
+}
+
+/// The hex digits of `signature`, after any number of 0x prefixes, as the
+/// main crate decodes signatures.
+pub fn signature_hex_digits(signature: &str) -> &str {
+  signature.trim_start_matches("0x")
+}
+
+/// Why the hex of argument `name` did not decode, in the words the main
+/// crate's `get_last_error` uses.
+pub fn hex_reason(error: hex::FromHexError, name: &str) -> String {
+  match error {
+    hex::FromHexError::OddLength => format!("{name} hex had odd length"),
+    hex::FromHexError::InvalidHexCharacter { c, index } => {
+      format!("{name} hex has invalid character {c:?} at position {index}")
+    }
+    other => format!("{name} hex is invalid: {other}"),
+  }
+}
+
+/// Verify a Polkadot SR25519 signature, as the main crate's `api::verify`
+/// does for SS58 addresses
+///
+/// `signature_hex` may have a 0x prefix. Messages over
+/// [`MAX_UNHASHED_MESSAGE_LEN`] bytes are verified as their `blake2_256`
+/// hash.
+///
+/// Returns whether the signature verifies, or why it could not be checked.
+pub fn verify(address: &str, signature_hex: &str, message: &[u8]) -> Result<bool, VerifyError> {
+  let (public_key, _) = decode_ss58_account(address)?;
+  let signature = hex::decode(signature_hex_digits(signature_hex)).map_err(VerifyError::SignatureHex)?;
+  let signature: [u8; 64] =
+    signature.as_slice().try_into().map_err(|_| VerifyError::SignatureLength { found: signature.len() })?;
+  Ok(if message.len() > MAX_UNHASHED_MESSAGE_LEN {
+    verify_sr25519(&signature, &blake2_256(message), &public_key)
+  } else {
+    verify_sr25519(&signature, message, &public_key)
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use alloc::string::ToString;
+
+  const ADDRESS: &str = "5HbqogsYaK54pN3QAgSi4t3Asqditt1X4P7cSBuDt2hPF2BR";
+  const SIGNATURE: &str = "0xf8ce1b80e76bf48a30610d0d3c2c9c8dbfdcb28a9aa8f56ac038d520ff17445ff3ce94809f8479a1224ef0d823d035022a9db2e72bd6c0d2f0244f766dec908b";
+  const MESSAGE: &[u8] = b"<Bytes>c15335d817e3d3d912d30f5a18a30c30162abc364cd0876d3a94af568d3c8c14</Bytes>";
+  /// Alice's 33-byte compressed ECDSA public key, rather than its account id.
+  const ECDSA_PUBLIC_KEY_ADDRESS: &str = "KW39r9CJjAVzmkf9zQ4YDb2hqfAVGdRqn53eRqyruqpxAP5YL";
+
+  #[test]
+  fn test_verify() {
+    assert_eq!(verify(ADDRESS, SIGNATURE, MESSAGE), Ok(true));
+    assert_eq!(verify(ADDRESS, &SIGNATURE[2..], MESSAGE), Ok(true));
+    assert_eq!(verify(ADDRESS, SIGNATURE, b"tampered"), Ok(false));
+    let bad_hex = hex::FromHexError::InvalidHexCharacter { c: 'z', index: 0 };
+    assert_eq!(verify(ADDRESS, "0xzz", MESSAGE), Err(VerifyError::SignatureHex(bad_hex)));
+    assert_eq!(verify(ADDRESS, &format!("0x{SIGNATURE}"), MESSAGE), Ok(true));
+    assert_eq!(verify(ADDRESS, &SIGNATURE[..128], MESSAGE), Err(VerifyError::SignatureLength { found: 63 }));
+    let corrupted = ADDRESS.replace("hPF2BR", "hPF2BS");
+    assert_eq!(verify(&corrupted, SIGNATURE, MESSAGE), Err(VerifyError::Ss58(Ss58Error::BadChecksum)));
+    assert_eq!(verify(ECDSA_PUBLIC_KEY_ADDRESS, SIGNATURE, MESSAGE), Err(VerifyError::Ss58(Ss58Error::BadLength)));
+    assert_eq!(verify("0OIl", SIGNATURE, MESSAGE), Err(VerifyError::Ss58(Ss58Error::BadBase58)));
+  }
+
+  #[test]
+  fn test_long_messages_are_hashed() {
+    let keypair = schnorrkel::Keypair::generate();
+    let address = ss58_encode(42, &keypair.public.to_bytes());
+    let long = [7u8; MAX_UNHASHED_MESSAGE_LEN + 1];
+    let signed = |message: &[u8]| {
+      format!("0x{}", hex::encode(keypair.sign_simple(SIGNING_CONTEXT, message).to_bytes()))
+    };
+
+    assert_eq!(verify(&address, &signed(&blake2_256(&long)), &long), Ok(true));
+    assert_eq!(verify(&address, &signed(&long), &long), Ok(false));
+    let short = &long[..MAX_UNHASHED_MESSAGE_LEN];
+    assert_eq!(verify(&address, &signed(short), short), Ok(true));
+    assert_eq!(decode_ss58_account(&address), Ok((keypair.public.to_bytes(), 42)));
+  }
+
+  #[test]
+  fn test_messages() {
+    assert_eq!(VerifyError::Ss58(Ss58Error::BadChecksum).to_string(), "SS58 checksum mismatch");
+    assert_eq!(VerifyError::SignatureLength { found: 63 }.to_string(), "signature is 63 bytes, expected 64");
+    let odd = VerifyError::SignatureHex(hex::FromHexError::OddLength);
+    assert_eq!(odd.to_string(), "signature hex had odd length");
+  }
+
+  /// SS58 with a one-byte prefix, as `Ss58Codec::to_ss58check_with_version`.
+  fn ss58_encode(prefix: u8, account_id: &[u8; 32]) -> alloc::string::String {
+    let mut data = alloc::vec![prefix];
+    data.extend_from_slice(account_id);
+    let hash = Blake2b512::new().chain_update(b"SS58PRE").chain_update(&data).finalize();
+    data.extend_from_slice(&hash[..CHECKSUM_LEN]);
+    bs58::encode(data).into_string()
+  }
+}
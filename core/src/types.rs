@@ -0,0 +1,210 @@
+//! Request and outcome shapes of the main crate's JSON entry points, built
+//! with the `serde` feature.
+//!
+//! The main crate's `verify_json`, `verify_ndjson` and `verify_request`
+//! and the browser's `wasm::verify_json` all read
+//! [`VerificationRequest`] and report [`VerificationOutcome`], so their
+//! schemas cannot drift apart. Unknown fields are ignored when reading, and
+//! fields at their defaults are left out when writing. With the `schema`
+//! feature both derive `schemars::JsonSchema`.
+
+use crate::codes::{
+  OPT_ALLOW_NON_CANONICAL_ECDSA, OPT_MESSAGE_BASE64, OPT_MESSAGE_IS_HEX, OPT_NFC_NORMALIZE, OPT_SIGNATURE_BASE64,
+  OPT_SKIP_LONG_MESSAGE_HASH, OPT_STRIP_SCHEME_PREFIX, OPT_WRAP_BYTES,
+};
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+fn is_false(value: &bool) -> bool {
+  !value
+}
+
+/// A signature to verify.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct VerificationRequest {
+  /// SS58-encoded address.
+  pub address: String,
+  /// Hex-encoded signature, or base64 with `signature_base64`.
+  pub signature: String,
+  /// The signed message.
+  pub message: String,
+  /// Behavior tweaks, all off when omitted.
+  #[serde(default, skip_serializing_if = "RequestOptions::is_default")]
+  pub options: RequestOptions,
+}
+
+/// Per-request options, mirroring the `OPT_*` flags of `VerifyOptions`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct RequestOptions {
+  /// Scheme name as accepted by `parse_scheme`, sr25519 when omitted.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub scheme: Option<String>,
+  /// See `OPT_WRAP_BYTES`.
+  #[serde(skip_serializing_if = "is_false")]
+  pub wrap_bytes: bool,
+  /// See `OPT_STRIP_SCHEME_PREFIX`.
+  #[serde(skip_serializing_if = "is_false")]
+  pub strip_scheme_prefix: bool,
+  /// See `OPT_ALLOW_NON_CANONICAL_ECDSA`.
+  #[serde(skip_serializing_if = "is_false")]
+  pub allow_non_canonical_ecdsa: bool,
+  /// See `OPT_MESSAGE_IS_HEX`.
+  #[serde(skip_serializing_if = "is_false")]
+  pub message_is_hex: bool,
+  /// See `OPT_SIGNATURE_BASE64`.
+  #[serde(skip_serializing_if = "is_false")]
+  pub signature_base64: bool,
+  /// See `OPT_MESSAGE_BASE64`.
+  #[serde(skip_serializing_if = "is_false")]
+  pub message_base64: bool,
+  /// See `OPT_SKIP_LONG_MESSAGE_HASH`.
+  #[serde(skip_serializing_if = "is_false")]
+  pub skip_long_message_hash: bool,
+  /// See `OPT_NFC_NORMALIZE`.
+  #[serde(skip_serializing_if = "is_false")]
+  pub nfc_normalize: bool,
+  /// SS58 prefixes accepted for the address, any when omitted.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub allowed_prefixes: Option<Vec<u16>>,
+  /// See `VerifyOptions::strict`.
+  #[serde(skip_serializing_if = "is_false")]
+  pub strict: bool,
+}
+
+impl RequestOptions {
+  fn is_default(&self) -> bool {
+    *self == RequestOptions::default()
+  }
+
+  /// The `OPT_*` flags these options set.
+  pub fn flags(&self) -> u32 {
+    [
+      (self.wrap_bytes, OPT_WRAP_BYTES),
+      (self.strip_scheme_prefix, OPT_STRIP_SCHEME_PREFIX),
+      (self.allow_non_canonical_ecdsa, OPT_ALLOW_NON_CANONICAL_ECDSA),
+      (self.message_is_hex, OPT_MESSAGE_IS_HEX),
+      (self.signature_base64, OPT_SIGNATURE_BASE64),
+      (self.message_base64, OPT_MESSAGE_BASE64),
+      (self.skip_long_message_hash, OPT_SKIP_LONG_MESSAGE_HASH),
+      (self.nfc_normalize, OPT_NFC_NORMALIZE),
+    ]
+    .into_iter()
+    .filter(|&(set, _)| set)
+    .fold(0, |flags, (_, flag)| flags | flag)
+  }
+}
+
+/// The outcome of a [`VerificationRequest`], such as
+/// `{"valid":true,"scheme":"sr25519"}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct VerificationOutcome {
+  /// Whether the signature verified.
+  pub valid: bool,
+  /// Name of the scheme verified under, absent if the request was unusable.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub scheme: Option<String>,
+  /// One of the `ERR_*` codes, absent if the request could be verified.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub error_code: Option<i32>,
+  /// Human-readable description of `error_code`.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub error_message: Option<String>,
+}
+
+impl VerificationOutcome {
+  /// A request verified under `scheme`.
+  pub fn verified(scheme: &str, valid: bool) -> Self {
+    VerificationOutcome { valid, scheme: Some(scheme.to_owned()), error_code: None, error_message: None }
+  }
+
+  /// A request that failed with `code`, under `scheme` if it got that far.
+  pub fn failed(scheme: Option<&str>, code: i32, message: String) -> Self {
+    VerificationOutcome {
+      valid: false,
+      scheme: scheme.map(str::to_owned),
+      error_code: Some(code),
+      error_message: Some(message),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::codes::{ERR_BAD_JSON, ERR_UNKNOWN_SCHEME};
+  use alloc::vec;
+  use serde_json::json;
+
+  #[test]
+  fn test_round_trip() {
+    let request = VerificationRequest {
+      address: "5HbqogsYaK54pN3QAgSi4t3Asqditt1X4P7cSBuDt2hPF2BR".to_owned(),
+      signature: "0x00".to_owned(),
+      message: "hello".to_owned(),
+      options: RequestOptions {
+        scheme: Some("ed25519".to_owned()),
+        wrap_bytes: true,
+        allowed_prefixes: Some(vec![0, 42]),
+        ..RequestOptions::default()
+      },
+    };
+    let json = serde_json::to_string(&request).unwrap();
+    assert_eq!(serde_json::from_str::<VerificationRequest>(&json).unwrap(), request);
+
+    let outcomes = [
+      VerificationOutcome::verified("ecdsa", true),
+      VerificationOutcome::failed(Some("sr25519"), ERR_UNKNOWN_SCHEME, "unknown".to_owned()),
+      VerificationOutcome::failed(None, ERR_BAD_JSON, "invalid request".to_owned()),
+    ];
+    for outcome in outcomes {
+      let json = serde_json::to_string(&outcome).unwrap();
+      assert_eq!(serde_json::from_str::<VerificationOutcome>(&json).unwrap(), outcome);
+    }
+  }
+
+  #[test]
+  fn test_minimal_output() {
+    let request = VerificationRequest {
+      address: "a".to_owned(),
+      signature: "s".to_owned(),
+      message: "m".to_owned(),
+      options: RequestOptions::default(),
+    };
+    let value = serde_json::to_value(&request).unwrap();
+    assert_eq!(value, json!({ "address": "a", "signature": "s", "message": "m" }));
+    let options = RequestOptions { strict: true, ..RequestOptions::default() };
+    assert_eq!(serde_json::to_value(options).unwrap(), json!({ "strict": true }));
+
+    let value = serde_json::to_value(VerificationOutcome::verified("sr25519", false)).unwrap();
+    assert_eq!(value, json!({ "valid": false, "scheme": "sr25519" }));
+  }
+
+  #[test]
+  fn test_unknown_fields_are_ignored() {
+    let request: VerificationRequest = serde_json::from_value(json!({
+      "address": "a",
+      "signature": "s",
+      "message": "m",
+      "client": "deno",
+      "options": { "future_flag": 1, "nfc_normalize": true }
+    }))
+    .unwrap();
+    assert!(request.options.nfc_normalize);
+
+    let outcome: VerificationOutcome = serde_json::from_value(json!({ "valid": true, "latency_ms": 3 })).unwrap();
+    assert_eq!(outcome, VerificationOutcome { valid: true, scheme: None, error_code: None, error_message: None });
+  }
+
+  #[test]
+  fn test_flags() {
+    let options = RequestOptions { wrap_bytes: true, nfc_normalize: true, strict: true, ..RequestOptions::default() };
+    assert_eq!(options.flags(), OPT_WRAP_BYTES | OPT_NFC_NORMALIZE);
+    assert_eq!(RequestOptions::default().flags(), 0);
+  }
+}
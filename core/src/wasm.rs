@@ -0,0 +1,162 @@
+//! Browser bindings, built with the `wasm` feature:
+//!
+//! ```text
+//! wasm-pack build core --target web -- --features wasm
+//! ```
+//!
+//! The same sr25519 path the main crate's `minimal` backend verifies
+//! through, so a page checks signatures exactly as the server does rather
+//! than trusting a JavaScript implementation to agree with it.
+
+use crate::codes::{
+  ERR_BAD_HEX, ERR_BAD_JSON, ERR_BAD_SIGNATURE_LENGTH, ERR_BAD_SS58, ERR_INVALID_OPTIONS, ERR_SCHEME_NOT_SUPPORTED,
+  ERR_SS58_BAD_BASE58, ERR_SS58_BAD_CHECKSUM, ERR_SS58_BAD_LENGTH,
+};
+use crate::types::{VerificationOutcome, VerificationRequest};
+use crate::{Ss58Error, VerifyError};
+use alloc::format;
+use alloc::string::{String, ToString};
+use serde_json::Value;
+use wasm_bindgen::prelude::*;
+
+/// Verify a Polkadot SR25519 signature, as [`crate::verify`] does.
+///
+/// Returns whether the signature verifies, and throws the reason as a
+/// string if it could not be checked.
+#[wasm_bindgen]
+pub fn verify_signature(address: &str, signature: &str, message: &[u8]) -> Result<bool, JsValue> {
+  crate::verify(address, signature, message).map_err(|error| JsValue::from_str(&error.to_string()))
+}
+
+/// Verify a signature described by a JSON request, as the main crate's
+/// `verify_json` does.
+///
+/// Takes a [`VerificationRequest`] such as `{"address": ..., "signature":
+/// ..., "message": ...}` and returns its [`VerificationOutcome`], such as
+/// `{"valid":true,"scheme":"sr25519"}`. Only sr25519 without options is
+/// verified here: any other scheme fails with `ERR_SCHEME_NOT_SUPPORTED`
+/// and any option with `ERR_INVALID_OPTIONS`.
+#[wasm_bindgen]
+pub fn verify_json(request: &str) -> String {
+  let outcome = match serde_json::from_str::<VerificationRequest>(request) {
+    Ok(request) => verify_request(&request),
+    Err(error) => VerificationOutcome::failed(None, ERR_BAD_JSON, format!("invalid request: {error}")),
+  };
+  serde_json::to_string(&outcome).expect("outcomes always serialize")
+}
+
+fn verify_request(request: &VerificationRequest) -> VerificationOutcome {
+  if let Some(scheme) = request.options.scheme.as_deref().filter(|scheme| !scheme.eq_ignore_ascii_case("sr25519")) {
+    let message = format!("scheme {scheme:?} is not supported by the browser build");
+    return VerificationOutcome::failed(None, ERR_SCHEME_NOT_SUPPORTED, message);
+  }
+  // Options at their defaults are not serialized, so any left are set
+  if let Value::Object(options) = serde_json::to_value(&request.options).expect("options always serialize") {
+    if let Some(name) = options.keys().find(|name| *name != "scheme") {
+      let message = format!("option {name} is not supported by the browser build");
+      return VerificationOutcome::failed(None, ERR_INVALID_OPTIONS, message);
+    }
+  }
+
+  match crate::verify(&request.address, &request.signature, request.message.as_bytes()) {
+    Ok(valid) => VerificationOutcome::verified("sr25519", valid),
+    Err(error) => VerificationOutcome::failed(Some("sr25519"), error_code(error), error.to_string()),
+  }
+}
+
+fn error_code(error: VerifyError) -> i32 {
+  match error {
+    VerifyError::Ss58(Ss58Error::BadBase58) => ERR_SS58_BAD_BASE58,
+    VerifyError::Ss58(Ss58Error::BadLength) => ERR_SS58_BAD_LENGTH,
+    VerifyError::Ss58(Ss58Error::BadChecksum) => ERR_SS58_BAD_CHECKSUM,
+    VerifyError::Ss58(Ss58Error::BadPrefix | Ss58Error::FormatNotAllowed) => ERR_BAD_SS58,
+    VerifyError::SignatureHex(_) => ERR_BAD_HEX,
+    VerifyError::SignatureLength { .. } => ERR_BAD_SIGNATURE_LENGTH,
+  }
+}
+
+/// Run headlessly with
+/// `wasm-pack test --headless --firefox core -- --features wasm`.
+#[cfg(all(test, target_arch = "wasm32"))]
+mod tests {
+  use super::*;
+  use serde_json::json;
+  use wasm_bindgen_test::*;
+
+  wasm_bindgen_test_configure!(run_in_browser);
+
+  const ADDRESS: &str = "5HbqogsYaK54pN3QAgSi4t3Asqditt1X4P7cSBuDt2hPF2BR";
+  const SIGNATURE: &str = "0xf8ce1b80e76bf48a30610d0d3c2c9c8dbfdcb28a9aa8f56ac038d520ff17445ff3ce94809f8479a1224ef0d823d035022a9db2e72bd6c0d2f0244f766dec908b";
+  const MESSAGE: &str = "<Bytes>c15335d817e3d3d912d30f5a18a30c30162abc364cd0876d3a94af568d3c8c14</Bytes>";
+
+  fn verify(request: Value) -> Value {
+    serde_json::from_str(&verify_json(&request.to_string())).unwrap()
+  }
+
+  #[wasm_bindgen_test]
+  fn test_verify_signature() {
+    assert_eq!(verify_signature(ADDRESS, SIGNATURE, MESSAGE.as_bytes()), Ok(true));
+    assert_eq!(verify_signature(ADDRESS, SIGNATURE, b"tampered"), Ok(false));
+    let error = verify_signature(ADDRESS, "0xzz", MESSAGE.as_bytes()).unwrap_err();
+    assert_eq!(error.as_string().unwrap(), "signature hex has invalid character 'z' at position 0");
+  }
+
+  /// Outcomes as the main crate's tests/fixtures/verification.json pins
+  /// them.
+  #[wasm_bindgen_test]
+  fn test_verify_json() {
+    let request = json!({ "address": ADDRESS, "signature": SIGNATURE, "message": MESSAGE });
+    assert_eq!(verify(request), json!({ "valid": true, "scheme": "sr25519" }));
+
+    let request = json!({ "address": ADDRESS, "signature": SIGNATURE, "message": "tampered" });
+    assert_eq!(verify(request), json!({ "valid": false, "scheme": "sr25519" }));
+
+    let request = json!({ "address": ADDRESS, "signature": &SIGNATURE[..128], "message": MESSAGE });
+    assert_eq!(verify(request)["error_code"], ERR_BAD_SIGNATURE_LENGTH);
+
+    let corrupted = ADDRESS.replace("hPF2BR", "hPF2BS");
+    let request = json!({ "address": corrupted, "signature": SIGNATURE, "message": MESSAGE });
+    assert_eq!(verify(request)["error_code"], ERR_SS58_BAD_CHECKSUM);
+
+    let outcome: Value = serde_json::from_str(&verify_json("{")).unwrap();
+    assert_eq!(outcome["error_code"], ERR_BAD_JSON);
+    assert!(outcome.get("scheme").is_none());
+  }
+
+  #[wasm_bindgen_test]
+  fn test_unsupported_options() {
+    let request = |options: Value| {
+      json!({ "address": ADDRESS, "signature": SIGNATURE, "message": MESSAGE, "options": options })
+    };
+
+    let outcome = verify(request(json!({ "scheme": "SR25519", "wrap_bytes": false })));
+    assert_eq!(outcome, json!({ "valid": true, "scheme": "sr25519" }));
+    assert_eq!(verify(request(json!({ "scheme": "ed25519" })))["error_code"], ERR_SCHEME_NOT_SUPPORTED);
+    assert_eq!(verify(request(json!({ "wrap_bytes": true })))["error_code"], ERR_INVALID_OPTIONS);
+  }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod host_tests {
+  use super::*;
+  use crate::types::RequestOptions;
+
+  /// The main crate's pinned outcomes, for every case this build verifies
+  /// rather than rejecting its options.
+  #[test]
+  fn test_matches_main_crate_fixture() {
+    let fixture: Value = serde_json::from_str(include_str!("../../tests/fixtures/verification.json")).unwrap();
+    let mut checked = 0;
+    for case in fixture["cases"].as_array().unwrap() {
+      let request: VerificationRequest = serde_json::from_value(case["request"].clone()).unwrap();
+      let outcome: Value = serde_json::from_str(&verify_json(&case["request"].to_string())).unwrap();
+      if request.options != RequestOptions::default() {
+        assert!(outcome["error_code"] == ERR_INVALID_OPTIONS || outcome["error_code"] == ERR_SCHEME_NOT_SUPPORTED);
+        continue;
+      }
+      assert_eq!(outcome, case["outcome"], "{}", case["name"]);
+      checked += 1;
+    }
+    assert!(checked >= 3, "only {checked} fixture cases are verified by the browser build");
+  }
+}
@@ -0,0 +1,91 @@
+defmodule PolkadotSigVerifier do
+  @moduledoc """
+  Polkadot SR25519 signature verification, as a NIF over the Rust library.
+  """
+
+  @typedoc "Why a signature did not verify, one atom per `api::VerifyError` variant."
+  @type reason ::
+          :invalid_ss58
+          | :invalid_address
+          | :prefix_out_of_range
+          | :wrong_key_length
+          | :invalid_first_address
+          | :invalid_second_address
+          | :wrong_public_key_length
+          | :invalid_signature_hex
+          | :invalid_hex
+          | :invalid_message_hex
+          | :invalid_base64
+          | :wrong_signature_length
+          | :wrong_hex_length
+          | :invalid_signature_prefix
+          | :invalid_multi_signature
+          | :non_canonical_signature
+          | :signature_mismatch
+          | :disallowed_network
+          | :conflicting_options
+          | :invalid_option
+          | :not_strict
+          | :unknown_scheme
+          | :scheme_not_supported
+          | :no_matching_scheme
+          | :invalid_ethereum_address
+          | :ethereum_checksum_mismatch
+          | :invalid_did_key
+          | :unsupported_key_type
+          | :invalid_json
+          | :siws_malformed
+          | :siws_address_mismatch
+          | :siws_domain_mismatch
+          | :siws_nonce_mismatch
+          | :expired
+          | :not_yet_valid
+          | :timestamp_not_found
+          | :replayed
+          | :hard_derivation
+          | :invalid_derivation_path
+          | :wrong_seed_length
+          | :invalid_seed
+          | :invalid_mnemonic
+          | :unknown_mnemonic_word
+          | :mnemonic_checksum_mismatch
+          | :unknown_dev_account
+          | :not_signed
+          | :invalid_extrinsic
+          | :invalid_era
+          | :null_pointer
+          | :missing_argument
+          | :invalid_utf8
+          | :invalid_utf16
+          | :invalid_handle
+          | :file_not_found
+          | :file_permission_denied
+          | :file_io
+          | :thread_pool
+          | :panic
+
+  @doc """
+  Verify a hex `signature`, with or without `0x`, by `address` over the
+  `message` binary as given, without `<Bytes>` wrapping.
+  """
+  @spec verify(String.t(), String.t(), binary()) :: :ok | {:error, reason()}
+  defdelegate verify(address, signature, message), to: PolkadotSigVerifier.Native
+
+  @doc """
+  Verify many `{address, signature, message}` tuples, returning what
+  `verify/3` does for each, in order.
+
+  Runs on a dirty CPU scheduler, so long batches do not block the normal
+  schedulers.
+  """
+  @spec verify_batch([{String.t(), String.t(), binary()}]) :: [:ok | {:error, reason()}]
+  defdelegate verify_batch(items), to: PolkadotSigVerifier.Native
+end
+
+defmodule PolkadotSigVerifier.Native do
+  @moduledoc false
+  use Rustler, otp_app: :polkadot_sig_verifier, crate: "polkadot_sig_verifier_nif"
+
+  def verify(_address, _signature, _message), do: :erlang.nif_error(:nif_not_loaded)
+  def verify_batch(_items), do: :erlang.nif_error(:nif_not_loaded)
+end
@@ -0,0 +1,21 @@
+defmodule PolkadotSigVerifier.MixProject do
+  use Mix.Project
+
+  def project do
+    [
+      app: :polkadot_sig_verifier,
+      version: "0.1.0",
+      elixir: "~> 1.14",
+      start_permanent: Mix.env() == :prod,
+      deps: deps()
+    ]
+  end
+
+  def application do
+    [extra_applications: [:logger]]
+  end
+
+  defp deps do
+    [{:rustler, "~> 0.35", runtime: false}]
+  end
+end
@@ -0,0 +1,169 @@
+//! The `PolkadotSigVerifier.Native` NIF, built with rustler by the mix
+//! project in `elixir/`:
+//!
+//! ```text
+//! cd elixir && mix deps.get && mix test
+//! ```
+//!
+//! A wrapper over the crate's Rust `api`. A signature that does not verify
+//! is `{:error, reason}`, with one reason atom per `api::VerifyError`
+//! variant, such as `:signature_mismatch` or `:wrong_signature_length`.
+
+use polkadot_sig_verifier::api;
+use rustler::{Atom, Binary, Encoder, Env, Term};
+
+mod atoms {
+  rustler::atoms! {
+    ok,
+    error,
+    invalid_ss58,
+    invalid_address,
+    prefix_out_of_range,
+    wrong_key_length,
+    invalid_first_address,
+    invalid_second_address,
+    wrong_public_key_length,
+    invalid_signature_hex,
+    invalid_hex,
+    invalid_message_hex,
+    invalid_base64,
+    wrong_signature_length,
+    wrong_hex_length,
+    invalid_signature_prefix,
+    invalid_multi_signature,
+    non_canonical_signature,
+    signature_mismatch,
+    disallowed_network,
+    conflicting_options,
+    invalid_option,
+    not_strict,
+    unknown_scheme,
+    scheme_not_supported,
+    no_matching_scheme,
+    invalid_ethereum_address,
+    ethereum_checksum_mismatch,
+    invalid_did_key,
+    unsupported_key_type,
+    invalid_json,
+    siws_malformed,
+    siws_address_mismatch,
+    siws_domain_mismatch,
+    siws_nonce_mismatch,
+    expired,
+    not_yet_valid,
+    timestamp_not_found,
+    replayed,
+    hard_derivation,
+    invalid_derivation_path,
+    wrong_seed_length,
+    invalid_seed,
+    invalid_mnemonic,
+    unknown_mnemonic_word,
+    mnemonic_checksum_mismatch,
+    unknown_dev_account,
+    not_signed,
+    invalid_extrinsic,
+    invalid_era,
+    null_pointer,
+    missing_argument,
+    invalid_utf8,
+    invalid_utf16,
+    invalid_handle,
+    file_not_found,
+    file_permission_denied,
+    file_io,
+    thread_pool,
+    panic,
+  }
+}
+
+fn reason(error: &api::VerifyError) -> Atom {
+  match error {
+    api::VerifyError::InvalidSs58 { .. } => atoms::invalid_ss58(),
+    api::VerifyError::InvalidAddress { .. } => atoms::invalid_address(),
+    api::VerifyError::PrefixOutOfRange { .. } => atoms::prefix_out_of_range(),
+    api::VerifyError::WrongKeyLength { .. } => atoms::wrong_key_length(),
+    api::VerifyError::InvalidFirstAddress { .. } => atoms::invalid_first_address(),
+    api::VerifyError::InvalidSecondAddress { .. } => atoms::invalid_second_address(),
+    api::VerifyError::WrongPublicKeyLength { .. } => atoms::wrong_public_key_length(),
+    api::VerifyError::InvalidSignatureHex { .. } => atoms::invalid_signature_hex(),
+    api::VerifyError::InvalidHex { .. } => atoms::invalid_hex(),
+    api::VerifyError::InvalidMessageHex { .. } => atoms::invalid_message_hex(),
+    api::VerifyError::InvalidBase64 { .. } => atoms::invalid_base64(),
+    api::VerifyError::WrongSignatureLength { .. } => atoms::wrong_signature_length(),
+    api::VerifyError::WrongHexLength { .. } => atoms::wrong_hex_length(),
+    api::VerifyError::InvalidSignaturePrefix { .. } => atoms::invalid_signature_prefix(),
+    api::VerifyError::InvalidMultiSignature { .. } => atoms::invalid_multi_signature(),
+    api::VerifyError::NonCanonicalSignature { .. } => atoms::non_canonical_signature(),
+    api::VerifyError::SignatureMismatch => atoms::signature_mismatch(),
+    api::VerifyError::DisallowedNetwork { .. } => atoms::disallowed_network(),
+    api::VerifyError::ConflictingOptions { .. } => atoms::conflicting_options(),
+    api::VerifyError::InvalidOption { .. } => atoms::invalid_option(),
+    api::VerifyError::NotStrict { .. } => atoms::not_strict(),
+    api::VerifyError::UnknownScheme { .. } => atoms::unknown_scheme(),
+    api::VerifyError::SchemeNotSupported { .. } => atoms::scheme_not_supported(),
+    api::VerifyError::NoMatchingScheme { .. } => atoms::no_matching_scheme(),
+    api::VerifyError::InvalidEthereumAddress { .. } => atoms::invalid_ethereum_address(),
+    api::VerifyError::EthereumChecksumMismatch => atoms::ethereum_checksum_mismatch(),
+    api::VerifyError::InvalidDidKey { .. } => atoms::invalid_did_key(),
+    api::VerifyError::UnsupportedKeyType { .. } => atoms::unsupported_key_type(),
+    api::VerifyError::InvalidJson { .. } => atoms::invalid_json(),
+    api::VerifyError::SiwsMalformed { .. } => atoms::siws_malformed(),
+    api::VerifyError::SiwsAddressMismatch { .. } => atoms::siws_address_mismatch(),
+    api::VerifyError::SiwsDomainMismatch { .. } => atoms::siws_domain_mismatch(),
+    api::VerifyError::SiwsNonceMismatch { .. } => atoms::siws_nonce_mismatch(),
+    api::VerifyError::Expired { .. } => atoms::expired(),
+    api::VerifyError::NotYetValid { .. } => atoms::not_yet_valid(),
+    api::VerifyError::TimestampNotFound { .. } => atoms::timestamp_not_found(),
+    api::VerifyError::Replayed { .. } => atoms::replayed(),
+    api::VerifyError::HardDerivation { .. } => atoms::hard_derivation(),
+    api::VerifyError::InvalidDerivationPath { .. } => atoms::invalid_derivation_path(),
+    api::VerifyError::WrongSeedLength { .. } => atoms::wrong_seed_length(),
+    api::VerifyError::InvalidSeed { .. } => atoms::invalid_seed(),
+    api::VerifyError::InvalidMnemonic { .. } => atoms::invalid_mnemonic(),
+    api::VerifyError::UnknownMnemonicWord { .. } => atoms::unknown_mnemonic_word(),
+    api::VerifyError::MnemonicChecksumMismatch => atoms::mnemonic_checksum_mismatch(),
+    api::VerifyError::UnknownDevAccount { .. } => atoms::unknown_dev_account(),
+    api::VerifyError::NotSigned => atoms::not_signed(),
+    api::VerifyError::InvalidExtrinsic { .. } => atoms::invalid_extrinsic(),
+    api::VerifyError::InvalidEra { .. } => atoms::invalid_era(),
+    api::VerifyError::NullPointer { .. } => atoms::null_pointer(),
+    api::VerifyError::MissingArgument { .. } => atoms::missing_argument(),
+    api::VerifyError::InvalidUtf8 { .. } => atoms::invalid_utf8(),
+    api::VerifyError::InvalidUtf16 { .. } => atoms::invalid_utf16(),
+    api::VerifyError::InvalidHandle { .. } => atoms::invalid_handle(),
+    api::VerifyError::FileNotFound { .. } => atoms::file_not_found(),
+    api::VerifyError::FilePermissionDenied { .. } => atoms::file_permission_denied(),
+    api::VerifyError::FileIo { .. } => atoms::file_io(),
+    api::VerifyError::ThreadPool { .. } => atoms::thread_pool(),
+    api::VerifyError::Panic { .. } => atoms::panic(),
+  }
+}
+
+/// `:ok`, or `{:error, reason}`.
+fn encode<'a>(env: Env<'a>, result: Result<(), api::VerifyError>) -> Term<'a> {
+  match result {
+    Ok(()) => atoms::ok().encode(env),
+    Err(error) => (atoms::error(), reason(&error)).encode(env),
+  }
+}
+
+/// Verify a Polkadot SR25519 signature, as `api::verify` does, over the
+/// message binary as given, without `<Bytes>` wrapping.
+#[rustler::nif]
+fn verify<'a>(env: Env<'a>, address: &str, signature: &str, message: Binary<'a>) -> Term<'a> {
+  encode(env, api::verify(address, signature, message.as_slice()))
+}
+
+/// Verify `{address, signature, message}` tuples, returning what `verify`
+/// does for each, in order.
+///
+/// Runs on a dirty CPU scheduler, so a long batch does not hold up the
+/// normal schedulers.
+#[rustler::nif(schedule = "DirtyCpu")]
+fn verify_batch<'a>(env: Env<'a>, items: Vec<(&str, &str, Binary<'a>)>) -> Vec<Term<'a>> {
+  let results = items.into_iter().map(|(address, signature, message)| api::verify(address, signature, &message));
+  results.map(|result| encode(env, result)).collect()
+}
+
+rustler::init!("Elixir.PolkadotSigVerifier.Native");
@@ -0,0 +1,52 @@
+defmodule PolkadotSigVerifierTest do
+  use ExUnit.Case
+
+  @address "5HbqogsYaK54pN3QAgSi4t3Asqditt1X4P7cSBuDt2hPF2BR"
+  @signature "0xf8ce1b80e76bf48a30610d0d3c2c9c8dbfdcb28a9aa8f56ac038d520ff17445ff3ce94809f8479a1224ef0d823d035022a9db2e72bd6c0d2f0244f766dec908b"
+  @message "<Bytes>c15335d817e3d3d912d30f5a18a30c30162abc364cd0876d3a94af568d3c8c14</Bytes>"
+
+  test "verify" do
+    assert PolkadotSigVerifier.verify(@address, @signature, @message) == :ok
+    assert PolkadotSigVerifier.verify(@address, @signature, "tampered") == {:error, :signature_mismatch}
+
+    short = binary_part(@signature, 0, 128)
+    assert PolkadotSigVerifier.verify(@address, short, @message) == {:error, :wrong_signature_length}
+    assert PolkadotSigVerifier.verify(@address, "0xzz", @message) == {:error, :invalid_signature_hex}
+
+    corrupted = String.replace(@address, "hPF2BR", "hPF2BS")
+    assert PolkadotSigVerifier.verify(corrupted, @signature, @message) == {:error, :invalid_ss58}
+  end
+
+  test "verify_batch" do
+    items = [
+      {@address, @signature, @message},
+      {@address, @signature, "tampered"},
+      {@address, binary_part(@signature, 0, 128), @message}
+    ]
+
+    assert PolkadotSigVerifier.verify_batch(items) ==
+             [:ok, {:error, :signature_mismatch}, {:error, :wrong_signature_length}]
+
+    assert PolkadotSigVerifier.verify_batch([]) == []
+  end
+
+  # With a single normal scheduler, the test process only gets the tick
+  # before the batch finishes if the batch is not running on that scheduler.
+  test "verify_batch runs on a dirty scheduler" do
+    items = List.duplicate({@address, @signature, @message}, 5_000)
+    previous = :erlang.system_flag(:schedulers_online, 1)
+
+    try do
+      parent = self()
+      spawn(fn -> send(parent, {:batch, PolkadotSigVerifier.verify_batch(items)}) end)
+      Process.send_after(self(), :tick, 10)
+
+      assert_receive :tick, 5_000
+      refute_received {:batch, _}
+      assert_receive {:batch, results}, 60_000
+      assert Enum.all?(results, &(&1 == :ok))
+    after
+      :erlang.system_flag(:schedulers_online, previous)
+    end
+  end
+end
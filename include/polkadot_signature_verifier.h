@@ -0,0 +1,2260 @@
+#ifndef POLKADOT_SIGNATURE_VERIFIER_H
+#define POLKADOT_SIGNATURE_VERIFIER_H
+
+/* Generated by `cargo xtask header` from the Rust sources. Do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// ABI revision of the exported functions and the structs below.
+//
+// Bumped whenever an exported signature changes or a struct layout changes
+// in a way older callers cannot handle. Appending fields to
+// `VerifyOptions` does not need a bump, since it carries its own size.
+#define ABI_VERSION 2
+
+// `AddressInfo::kind`: a 32-byte account id. This is the public key for
+// sr25519 and ed25519 and its `blake2_256` hash for ECDSA.
+#define ADDRESS_KIND_ACCOUNT_ID 1
+
+// `AddressInfo::kind`: a 33-byte compressed ECDSA public key.
+#define ADDRESS_KIND_ECDSA_PUBLIC_KEY 2
+
+// `AddressInfo::kind` of an address that failed to decode.
+#define ADDRESS_KIND_NONE 0
+
+// A base64 signature or message was not valid base64.
+#define ERR_BAD_BASE64 -19
+
+// A derivation path is not a sequence of `/soft` or `//hard` junctions.
+#define ERR_BAD_DERIVATION_PATH -40
+
+// A `did:key` is not `did:key:z` followed by base58btc of a multicodec
+// key type and a public key of the matching length.
+#define ERR_BAD_DID_KEY -42
+
+// The era given to `verify_signing_payload` is not a SCALE-encoded
+// `Era`: one zero byte for an immortal era or two bytes for a mortal one.
+#define ERR_BAD_ERA -55
+
+// An Ethereum-style address was not 20 hex-encoded bytes.
+#define ERR_BAD_ETH_ADDRESS -14
+
+// The extrinsic given to `verify_extrinsic` could not be SCALE-decoded
+// as a v4 signed extrinsic, or its signer is not an account ID.
+#define ERR_BAD_EXTRINSIC -54
+
+// `addresses_equal`: the first address did not decode, see
+// `get_last_error` for why.
+#define ERR_BAD_FIRST_ADDRESS -37
+
+// The signature was not valid hex.
+#define ERR_BAD_HEX -4
+
+// A fixed-length hex argument other than the signature, such as a block
+// hash or VRF proof, decoded to the wrong number of bytes.
+#define ERR_BAD_HEX_LENGTH -56
+
+// A JSON request was malformed or missed a required field.
+#define ERR_BAD_JSON -27
+
+// `OPT_MESSAGE_IS_HEX` was set but the message was not valid hex.
+#define ERR_BAD_MESSAGE_HEX -18
+
+// A mnemonic does not have 12, 15, 18, 21 or 24 words.
+#define ERR_BAD_MNEMONIC -45
+
+// The words of a mnemonic are all known but its BIP39 checksum does not
+// match, as when a word was swapped or mistyped as another valid word.
+#define ERR_BAD_MNEMONIC_CHECKSUM -47
+
+// A SCALE-encoded `MultiSignature` had an unknown variant tag or the
+// wrong payload length for its variant.
+#define ERR_BAD_MULTISIGNATURE -12
+
+// A public key given as hex, in place of an address or to
+// `public_key_to_ss58`, was not 32 bytes long.
+#define ERR_BAD_PUBLIC_KEY_LENGTH -36
+
+// `addresses_equal`: the second address did not decode.
+#define ERR_BAD_SECOND_ADDRESS -38
+
+// A 32-byte seed is not a valid secret key, such as zero or a value
+// above the secp256k1 group order for `sign_ecdsa`.
+#define ERR_BAD_SEED -49
+
+// The seed passed to `sign_sr25519` is not 32 bytes.
+#define ERR_BAD_SEED_LENGTH -44
+
+// The signature decoded to the wrong number of bytes.
+#define ERR_BAD_SIGNATURE_LENGTH -5
+
+// A 65-byte signature started with a byte that is not a known scheme tag.
+#define ERR_BAD_SIGNATURE_PREFIX -13
+
+// The address could not be decoded as SS58 for a reason not covered by
+// the more specific `ERR_SS58_*` codes.
+#define ERR_BAD_SS58 -3
+
+// A mixed-case Ethereum-style address failed its EIP-55 checksum.
+#define ERR_ETH_ADDRESS_CHECKSUM -15
+
+// The signed message has expired.
+#define ERR_EXPIRED -32
+
+// Reading the file to verify failed for another reason.
+#define ERR_FILE_IO -25
+
+// The file to verify does not exist.
+#define ERR_FILE_NOT_FOUND -23
+
+// The file to verify exists but may not be read.
+#define ERR_FILE_PERMISSION_DENIED -24
+
+// A derivation path has a hard junction (`//`), which needs the secret
+// key and cannot be applied to a public key.
+#define ERR_HARD_DERIVATION -39
+
+// A stream, nonce cache or keypair handle was NULL, unknown or already
+// finalized or freed.
+#define ERR_INVALID_HANDLE -22
+
+// `VerifyOptions` combined flags that contradict each other.
+#define ERR_INVALID_OPTIONS -20
+
+// A wide string argument was not valid UTF-16, such as a lone surrogate.
+#define ERR_INVALID_UTF16 -26
+
+// A string argument was not valid UTF-8.
+#define ERR_INVALID_UTF8 -2
+
+// An asynchronous job was abandoned by `async_shutdown` before it ran.
+#define ERR_JOB_CANCELLED -51
+
+// The signature is a malleated, non-canonical encoding: an ECDSA
+// signature with a high `s`, or an sr25519 signature whose `s` is not
+// reduced.
+#define ERR_NON_CANONICAL_SIGNATURE -17
+
+// The extrinsic given to `verify_extrinsic` is unsigned, so there is
+// no signature to verify.
+#define ERR_NOT_SIGNED -53
+
+// `VerifyOptions::strict` was set and an input would have needed
+// normalizing, such as a 0x prefix on hex.
+#define ERR_NOT_STRICT -21
+
+// The signed message is not valid yet, such as one issued in the future.
+#define ERR_NOT_YET_VALID -33
+
+// The signature length does not belong to any supported scheme, or
+// belongs to one that cannot verify the kind of key the address holds.
+#define ERR_NO_MATCHING_SCHEME -11
+
+// One of the pointer arguments was NULL.
+#define ERR_NULL_POINTER -1
+
+// The library panicked internally; `get_last_error` has the panic message.
+#define ERR_PANIC -6
+
+// The signature verified, but its nonce was already used by the same
+// address within the nonce cache's TTL.
+#define ERR_REPLAYED -35
+
+// The signature scheme was left out of this build by its cargo feature,
+// see `get_supported_schemes`. Also returned by key generation, signing
+// and derivation in builds without the `sp-core` feature.
+#define ERR_SCHEME_NOT_SUPPORTED -52
+
+// The address in a Sign-In with Substrate message is not the expected one.
+#define ERR_SIWS_ADDRESS_MISMATCH -29
+
+// The domain in a Sign-In with Substrate message is not the expected one.
+#define ERR_SIWS_DOMAIN_MISMATCH -30
+
+// A Sign-In with Substrate message could not be parsed.
+#define ERR_SIWS_MALFORMED -28
+
+// The nonce in a Sign-In with Substrate message is not the expected one.
+#define ERR_SIWS_NONCE_MISMATCH -31
+
+// The address contains characters outside the base58 alphabet.
+#define ERR_SS58_BAD_BASE58 -8
+
+// The address checksum does not match, usually a mistyped character.
+#define ERR_SS58_BAD_CHECKSUM -10
+
+// The address decoded to a payload of the wrong length.
+#define ERR_SS58_BAD_LENGTH -9
+
+// The threads for `verify_batch_parallel` or `verify_async` could not be
+// started.
+#define ERR_THREAD_POOL -50
+
+// The message does not contain a timestamp in the expected format.
+#define ERR_TIMESTAMP_NOT_FOUND -34
+
+// A dev account name is not one of Alice, Bob, Charlie, Dave, Eve or
+// Ferdie.
+#define ERR_UNKNOWN_DEV_ACCOUNT -48
+
+// A mnemonic has a word missing from the English BIP39 word list.
+#define ERR_UNKNOWN_MNEMONIC_WORD -46
+
+// A scheme name or number did not match any `SignatureScheme`.
+#define ERR_UNKNOWN_SCHEME -16
+
+// A `did:key` names a key type other than sr25519, ed25519 or secp256k1.
+#define ERR_UNSUPPORTED_KEY_TYPE -41
+
+// An SS58 address with a valid checksum holds a payload of a length no
+// supported key type has, such as a 20-byte `AccountId20`.
+#define ERR_WRONG_KEY_LENGTH -43
+
+// The address decoded but its SS58 prefix is not in the allowed list.
+#define ERR_WRONG_NETWORK -7
+
+// The inputs were well formed but the signature does not verify.
+#define INVALID 0
+
+// ed25519 over `blake2_256` of the message, used by Ledger app versions
+// whose buffer cannot hold the full payload.
+#define LEDGER_BLAKE2_256 2
+
+// No Ledger convention verified.
+#define LEDGER_NONE 0
+
+// ed25519 over the raw message bytes, without `<Bytes>` wrapping.
+#define LEDGER_RAW 1
+
+// Intermediate parsing steps.
+#define LOG_DEBUG 4
+
+// Unexpected failures.
+#define LOG_ERROR 1
+
+// Verification verdicts.
+#define LOG_INFO 3
+
+// Rejected inputs.
+#define LOG_WARN 2
+
+// Seconds a challenge may be issued ahead of the verifier's clock before
+// it counts as future-dated.
+#define MAX_CLOCK_SKEW_SECS 30
+
+// The message verified exactly as given.
+#define MESSAGE_AS_GIVEN 1
+
+// No form of the message verified.
+#define MESSAGE_NONE 0
+
+// The message verified after stripping its `<Bytes>...</Bytes>` wrapper.
+#define MESSAGE_UNWRAPPED 3
+
+// The message verified after wrapping it in `<Bytes>...</Bytes>`.
+#define MESSAGE_WRAPPED 2
+
+// `verify_extrinsic` and `verify_signing_payload`: the runtime has no
+// `CheckMetadataHash` signed extension.
+#define METADATA_HASH_ABSENT 0
+
+// `verify_extrinsic` only: try the layout without `CheckMetadataHash`,
+// then the one with the mode the extrinsic carries.
+#define METADATA_HASH_DETECT 3
+
+// `CheckMetadataHash` in mode 0: the signer committed to no metadata hash.
+#define METADATA_HASH_DISABLED 1
+
+// `CheckMetadataHash` in mode 1: the signer committed to the metadata hash
+// passed alongside.
+#define METADATA_HASH_ENABLED 2
+
+// Returned by `verify_against_addresses` when no candidate verifies.
+#define NO_MATCHING_ADDRESS -1
+
+// `VerifyOptions::flags`: accept high-s ECDSA signatures from legacy
+// wallets instead of failing with `ERR_NON_CANONICAL_SIGNATURE`.
+#define OPT_ALLOW_NON_CANONICAL_ECDSA (1 << 2)
+
+// `VerifyOptions::flags`: the message argument is base64, like
+// `OPT_SIGNATURE_BASE64`, and the signature is over the decoded bytes.
+// Cannot be combined with `OPT_MESSAGE_IS_HEX`.
+#define OPT_MESSAGE_BASE64 (1 << 5)
+
+// `VerifyOptions::flags`: the message argument is hex, with or without 0x
+// prefix, and the signature is over the decoded bytes. Combined with
+// `OPT_WRAP_BYTES`, the decoded bytes are wrapped.
+#define OPT_MESSAGE_IS_HEX (1 << 3)
+
+// `VerifyOptions::flags`: NFC-normalize UTF-8 messages before verifying,
+// inside any `<Bytes>` wrapper and before wrapping or hashing. In strict
+// mode a message that normalization would change fails with
+// `ERR_NOT_STRICT` instead.
+#define OPT_NFC_NORMALIZE (1 << 7)
+
+// `VerifyOptions::flags`: the signature argument is base64 instead of hex.
+// Both the standard and the URL-safe alphabet are accepted, padded or not.
+#define OPT_SIGNATURE_BASE64 (1 << 4)
+
+// `VerifyOptions::flags`: verify messages longer than 256 bytes directly.
+// By default they are verified as their `blake2_256` hash, the way
+// polkadot-js signs them. The length is taken after `<Bytes>` wrapping.
+#define OPT_SKIP_LONG_MESSAGE_HASH (1 << 6)
+
+// `VerifyOptions::flags`: accept 65-byte signatures whose first byte is a
+// `MultiSignature` tag (0x00 ed25519, 0x01 sr25519) and verify the rest.
+#define OPT_STRIP_SCHEME_PREFIX (1 << 1)
+
+// `VerifyOptions::flags`: wrap the message in `<Bytes>...</Bytes>` before
+// verifying, unless it is already wrapped.
+#define OPT_WRAP_BYTES (1 << 0)
+
+// `SignatureScheme::Ecdsa`.
+#define SCHEME_ECDSA 3
+
+// `SignatureScheme::Ed25519`.
+#define SCHEME_ED25519 2
+
+// `SignatureScheme::Ethereum`.
+#define SCHEME_ETHEREUM 4
+
+// No scheme, reported when nothing verified.
+#define SCHEME_NONE 0
+
+// `SignatureScheme::Sr25519`.
+#define SCHEME_SR25519 1
+
+// `verify_stream_new`: the signer signed the `blake2_256` hash of the
+// message, whatever its length.
+#define STREAM_PREHASH_ALWAYS 1
+
+// `verify_stream_new`: the signer signed messages up to 256 bytes
+// directly and longer ones as their `blake2_256` hash, like polkadot-js.
+#define STREAM_PREHASH_LONG 0
+
+// The signature is valid for the given address and message.
+#define VALID 1
+
+// A signature scheme, numbered like the `SCHEME_*` constants.
+//
+// In the bitmask of `get_supported_schemes`, each scheme is the bit
+// `1 << SCHEME_*`: 0x2 sr25519, 0x4 ed25519, 0x8 ecdsa, 0x10 ethereum.
+// Numbers and bits never change, and new schemes get new ones.
+enum SignatureScheme
+#ifdef __cplusplus
+  : int32_t
+#endif // __cplusplus
+ {
+  // Schnorrkel signatures over Ristretto25519, the Polkadot default.
+  SignatureScheme_Sr25519 = 1,
+  // Ed25519 signatures, used by Ledger and some hardware wallets.
+  SignatureScheme_Ed25519 = 2,
+  // Recoverable secp256k1 ECDSA signatures over blake2-256 hashed messages.
+  SignatureScheme_Ecdsa = 3,
+  // Ethereum `personal_sign` signatures for 20-byte addresses.
+  SignatureScheme_Ethereum = 4,
+};
+#ifndef __cplusplus
+typedef int32_t SignatureScheme;
+#endif // __cplusplus
+
+// Opaque handle returned by `keypair_from_seed`,
+// `keypair_from_seed_with_scheme` and `keypair_from_mnemonic`.
+typedef struct Keypair Keypair;
+
+// Opaque handle returned by `nonce_cache_new`.
+typedef struct NonceCache NonceCache;
+
+// Opaque handle returned by `signature_parse`.
+typedef struct ParsedSignature ParsedSignature;
+
+// Verifies signatures under fixed options, from [`Verifier::builder`].
+typedef struct Verifier Verifier;
+
+// Opaque handle returned by `verify_stream_new`.
+typedef struct VerifyStream VerifyStream;
+
+// Counters filled in by `get_stats`.
+typedef struct Stats {
+  // Signatures checked, counting each item of a batch.
+  uint64_t total_verifications;
+  // Checks that did not return `VALID`, whether the signature did not
+  // match or the inputs did not parse.
+  uint64_t failures;
+  // Wall-clock time spent in verifying calls, in nanoseconds. A batch
+  // adds its time once, however many threads it ran on.
+  uint64_t total_nanos;
+} Stats;
+
+// Detailed result of `validate_ss58_address`.
+typedef struct AddressInfo {
+  // SS58 network prefix of the address.
+  uint16_t ss58_prefix;
+  // Length of the payload: 32 or 33 bytes.
+  uint8_t payload_len;
+  // `ADDRESS_KIND_*` guess of what the payload holds, from its length.
+  int32_t kind;
+  // The payload, zero-padded after `payload_len` bytes.
+  uint8_t payload[33];
+} AddressInfo;
+
+// Detailed result of `verify_polkadot_signature_ex`.
+typedef struct VerifyOutcome {
+  // 1 if the signature is valid, 0 otherwise.
+  uint8_t valid;
+  // The same code the function returned.
+  int32_t error_code;
+  // Payload decoded from the address, zeroed if decoding failed. This is
+  // the public key for sr25519 and ed25519 and the account id, the
+  // blake2-256 hash of the public key, for ECDSA.
+  uint8_t public_key[32];
+  // SS58 network prefix of the address, zeroed if decoding failed or the
+  // address was a raw public key.
+  uint16_t ss58_prefix;
+} VerifyOutcome;
+
+// Options for `verify_polkadot_signature_with_options`.
+//
+// Zero means "default" for every field, so a zeroed struct with `size`
+// set behaves like `verify_polkadot_signature_v2`. New fields are only
+// ever appended; the library reads no further than `size` bytes.
+typedef struct VerifyOptions {
+  // `sizeof(VerifyOptions)` as compiled by the caller.
+  uint32_t size;
+  // Bitwise OR of `OPT_*` flags.
+  uint32_t flags;
+  // SS58 prefixes the address may use. NULL or empty accepts any prefix.
+  const uint16_t *allowed_prefixes;
+  // Number of entries in `allowed_prefixes`.
+  size_t allowed_prefixes_len;
+  // Nonzero verifies exactly the bytes given. Inputs that the default
+  // lenient mode would normalize, such as a 0x prefix on hex, fail with
+  // `ERR_NOT_STRICT`, and flags that normalize the message or signature
+  // (`OPT_WRAP_BYTES`, `OPT_STRIP_SCHEME_PREFIX`,
+  // `OPT_ALLOW_NON_CANONICAL_ECDSA`) fail with `ERR_INVALID_OPTIONS`.
+  uint8_t strict;
+} VerifyOptions;
+
+// What `verify_siws` checks a Sign-In with Substrate message against.
+//
+// Like `VerifyOptions`, it carries its own size and new fields are only
+// ever appended.
+typedef struct SiwsExpectations {
+  // `sizeof(SiwsExpectations)` as compiled by the caller.
+  uint32_t size;
+  // Current time in seconds since the Unix epoch, checked against the
+  // message's issued-at, not-before and expiration times.
+  int64_t now_unix;
+  // Domain the message must be for. NULL accepts any domain.
+  const char *domain;
+  // Nonce the message must carry. NULL accepts any nonce.
+  const char *nonce;
+  // SS58 address the message must be for, compared by public key so any
+  // network prefix matches. NULL accepts any address.
+  const char *address;
+} SiwsExpectations;
+
+// Receives a level (`LOG_*`) and a NUL-terminated message that is only
+// valid for the duration of the call.
+typedef void (*LogCallback)(int32_t level, const char *msg);
+
+// Receives the code `verify_polkadot_signature_v2` returned for a job, or
+// `ERR_JOB_CANCELLED`, and the `user_data` the job was submitted with.
+typedef void (*AsyncCallback)(int32_t result, void *user_data);
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Runtime-readable copy of [`ADDRESS_KIND_ACCOUNT_ID`].
+extern const int32_t PSV_ADDRESS_KIND_ACCOUNT_ID;
+
+// Runtime-readable copy of [`ADDRESS_KIND_ECDSA_PUBLIC_KEY`].
+extern const int32_t PSV_ADDRESS_KIND_ECDSA_PUBLIC_KEY;
+
+// Runtime-readable copy of [`ADDRESS_KIND_NONE`].
+extern const int32_t PSV_ADDRESS_KIND_NONE;
+
+// Runtime-readable copy of [`ERR_BAD_BASE64`].
+extern const int32_t PSV_ERR_BAD_BASE64;
+
+// Runtime-readable copy of [`ERR_BAD_DERIVATION_PATH`].
+extern const int32_t PSV_ERR_BAD_DERIVATION_PATH;
+
+// Runtime-readable copy of [`ERR_BAD_DID_KEY`].
+extern const int32_t PSV_ERR_BAD_DID_KEY;
+
+// Runtime-readable copy of [`ERR_BAD_ERA`].
+extern const int32_t PSV_ERR_BAD_ERA;
+
+// Runtime-readable copy of [`ERR_BAD_ETH_ADDRESS`].
+extern const int32_t PSV_ERR_BAD_ETH_ADDRESS;
+
+// Runtime-readable copy of [`ERR_BAD_EXTRINSIC`].
+extern const int32_t PSV_ERR_BAD_EXTRINSIC;
+
+// Runtime-readable copy of [`ERR_BAD_FIRST_ADDRESS`].
+extern const int32_t PSV_ERR_BAD_FIRST_ADDRESS;
+
+// Runtime-readable copy of [`ERR_BAD_HEX`].
+extern const int32_t PSV_ERR_BAD_HEX;
+
+// Runtime-readable copy of [`ERR_BAD_HEX_LENGTH`].
+extern const int32_t PSV_ERR_BAD_HEX_LENGTH;
+
+// Runtime-readable copy of [`ERR_BAD_JSON`].
+extern const int32_t PSV_ERR_BAD_JSON;
+
+// Runtime-readable copy of [`ERR_BAD_MESSAGE_HEX`].
+extern const int32_t PSV_ERR_BAD_MESSAGE_HEX;
+
+// Runtime-readable copy of [`ERR_BAD_MNEMONIC`].
+extern const int32_t PSV_ERR_BAD_MNEMONIC;
+
+// Runtime-readable copy of [`ERR_BAD_MNEMONIC_CHECKSUM`].
+extern const int32_t PSV_ERR_BAD_MNEMONIC_CHECKSUM;
+
+// Runtime-readable copy of [`ERR_BAD_MULTISIGNATURE`].
+extern const int32_t PSV_ERR_BAD_MULTISIGNATURE;
+
+// Runtime-readable copy of [`ERR_BAD_PUBLIC_KEY_LENGTH`].
+extern const int32_t PSV_ERR_BAD_PUBLIC_KEY_LENGTH;
+
+// Runtime-readable copy of [`ERR_BAD_SECOND_ADDRESS`].
+extern const int32_t PSV_ERR_BAD_SECOND_ADDRESS;
+
+// Runtime-readable copy of [`ERR_BAD_SEED`].
+extern const int32_t PSV_ERR_BAD_SEED;
+
+// Runtime-readable copy of [`ERR_BAD_SEED_LENGTH`].
+extern const int32_t PSV_ERR_BAD_SEED_LENGTH;
+
+// Runtime-readable copy of [`ERR_BAD_SIGNATURE_LENGTH`].
+extern const int32_t PSV_ERR_BAD_SIGNATURE_LENGTH;
+
+// Runtime-readable copy of [`ERR_BAD_SIGNATURE_PREFIX`].
+extern const int32_t PSV_ERR_BAD_SIGNATURE_PREFIX;
+
+// Runtime-readable copy of [`ERR_BAD_SS58`].
+extern const int32_t PSV_ERR_BAD_SS58;
+
+// Runtime-readable copy of [`ERR_ETH_ADDRESS_CHECKSUM`].
+extern const int32_t PSV_ERR_ETH_ADDRESS_CHECKSUM;
+
+// Runtime-readable copy of [`ERR_EXPIRED`].
+extern const int32_t PSV_ERR_EXPIRED;
+
+// Runtime-readable copy of [`ERR_FILE_IO`].
+extern const int32_t PSV_ERR_FILE_IO;
+
+// Runtime-readable copy of [`ERR_FILE_NOT_FOUND`].
+extern const int32_t PSV_ERR_FILE_NOT_FOUND;
+
+// Runtime-readable copy of [`ERR_FILE_PERMISSION_DENIED`].
+extern const int32_t PSV_ERR_FILE_PERMISSION_DENIED;
+
+// Runtime-readable copy of [`ERR_HARD_DERIVATION`].
+extern const int32_t PSV_ERR_HARD_DERIVATION;
+
+// Runtime-readable copy of [`ERR_INVALID_HANDLE`].
+extern const int32_t PSV_ERR_INVALID_HANDLE;
+
+// Runtime-readable copy of [`ERR_INVALID_OPTIONS`].
+extern const int32_t PSV_ERR_INVALID_OPTIONS;
+
+// Runtime-readable copy of [`ERR_INVALID_UTF16`].
+extern const int32_t PSV_ERR_INVALID_UTF16;
+
+// Runtime-readable copy of [`ERR_INVALID_UTF8`].
+extern const int32_t PSV_ERR_INVALID_UTF8;
+
+// Runtime-readable copy of [`ERR_JOB_CANCELLED`].
+extern const int32_t PSV_ERR_JOB_CANCELLED;
+
+// Runtime-readable copy of [`ERR_NON_CANONICAL_SIGNATURE`].
+extern const int32_t PSV_ERR_NON_CANONICAL_SIGNATURE;
+
+// Runtime-readable copy of [`ERR_NOT_SIGNED`].
+extern const int32_t PSV_ERR_NOT_SIGNED;
+
+// Runtime-readable copy of [`ERR_NOT_STRICT`].
+extern const int32_t PSV_ERR_NOT_STRICT;
+
+// Runtime-readable copy of [`ERR_NOT_YET_VALID`].
+extern const int32_t PSV_ERR_NOT_YET_VALID;
+
+// Runtime-readable copy of [`ERR_NO_MATCHING_SCHEME`].
+extern const int32_t PSV_ERR_NO_MATCHING_SCHEME;
+
+// Runtime-readable copy of [`ERR_NULL_POINTER`].
+extern const int32_t PSV_ERR_NULL_POINTER;
+
+// Runtime-readable copy of [`ERR_PANIC`].
+extern const int32_t PSV_ERR_PANIC;
+
+// Runtime-readable copy of [`ERR_REPLAYED`].
+extern const int32_t PSV_ERR_REPLAYED;
+
+// Runtime-readable copy of [`ERR_SCHEME_NOT_SUPPORTED`].
+extern const int32_t PSV_ERR_SCHEME_NOT_SUPPORTED;
+
+// Runtime-readable copy of [`ERR_SIWS_ADDRESS_MISMATCH`].
+extern const int32_t PSV_ERR_SIWS_ADDRESS_MISMATCH;
+
+// Runtime-readable copy of [`ERR_SIWS_DOMAIN_MISMATCH`].
+extern const int32_t PSV_ERR_SIWS_DOMAIN_MISMATCH;
+
+// Runtime-readable copy of [`ERR_SIWS_MALFORMED`].
+extern const int32_t PSV_ERR_SIWS_MALFORMED;
+
+// Runtime-readable copy of [`ERR_SIWS_NONCE_MISMATCH`].
+extern const int32_t PSV_ERR_SIWS_NONCE_MISMATCH;
+
+// Runtime-readable copy of [`ERR_SS58_BAD_BASE58`].
+extern const int32_t PSV_ERR_SS58_BAD_BASE58;
+
+// Runtime-readable copy of [`ERR_SS58_BAD_CHECKSUM`].
+extern const int32_t PSV_ERR_SS58_BAD_CHECKSUM;
+
+// Runtime-readable copy of [`ERR_SS58_BAD_LENGTH`].
+extern const int32_t PSV_ERR_SS58_BAD_LENGTH;
+
+// Runtime-readable copy of [`ERR_THREAD_POOL`].
+extern const int32_t PSV_ERR_THREAD_POOL;
+
+// Runtime-readable copy of [`ERR_TIMESTAMP_NOT_FOUND`].
+extern const int32_t PSV_ERR_TIMESTAMP_NOT_FOUND;
+
+// Runtime-readable copy of [`ERR_UNKNOWN_DEV_ACCOUNT`].
+extern const int32_t PSV_ERR_UNKNOWN_DEV_ACCOUNT;
+
+// Runtime-readable copy of [`ERR_UNKNOWN_MNEMONIC_WORD`].
+extern const int32_t PSV_ERR_UNKNOWN_MNEMONIC_WORD;
+
+// Runtime-readable copy of [`ERR_UNKNOWN_SCHEME`].
+extern const int32_t PSV_ERR_UNKNOWN_SCHEME;
+
+// Runtime-readable copy of [`ERR_UNSUPPORTED_KEY_TYPE`].
+extern const int32_t PSV_ERR_UNSUPPORTED_KEY_TYPE;
+
+// Runtime-readable copy of [`ERR_WRONG_KEY_LENGTH`].
+extern const int32_t PSV_ERR_WRONG_KEY_LENGTH;
+
+// Runtime-readable copy of [`ERR_WRONG_NETWORK`].
+extern const int32_t PSV_ERR_WRONG_NETWORK;
+
+// Runtime-readable copy of [`INVALID`].
+extern const int32_t PSV_INVALID;
+
+// Runtime-readable copy of [`LEDGER_BLAKE2_256`].
+extern const int32_t PSV_LEDGER_BLAKE2_256;
+
+// Runtime-readable copy of [`LEDGER_NONE`].
+extern const int32_t PSV_LEDGER_NONE;
+
+// Runtime-readable copy of [`LEDGER_RAW`].
+extern const int32_t PSV_LEDGER_RAW;
+
+// Runtime-readable copy of [`MESSAGE_AS_GIVEN`].
+extern const int32_t PSV_MESSAGE_AS_GIVEN;
+
+// Runtime-readable copy of [`MESSAGE_NONE`].
+extern const int32_t PSV_MESSAGE_NONE;
+
+// Runtime-readable copy of [`MESSAGE_UNWRAPPED`].
+extern const int32_t PSV_MESSAGE_UNWRAPPED;
+
+// Runtime-readable copy of [`MESSAGE_WRAPPED`].
+extern const int32_t PSV_MESSAGE_WRAPPED;
+
+// Runtime-readable copy of [`METADATA_HASH_ABSENT`].
+extern const int32_t PSV_METADATA_HASH_ABSENT;
+
+// Runtime-readable copy of [`METADATA_HASH_DETECT`].
+extern const int32_t PSV_METADATA_HASH_DETECT;
+
+// Runtime-readable copy of [`METADATA_HASH_DISABLED`].
+extern const int32_t PSV_METADATA_HASH_DISABLED;
+
+// Runtime-readable copy of [`METADATA_HASH_ENABLED`].
+extern const int32_t PSV_METADATA_HASH_ENABLED;
+
+// Runtime-readable copy of [`OPT_ALLOW_NON_CANONICAL_ECDSA`].
+extern const uint32_t PSV_OPT_ALLOW_NON_CANONICAL_ECDSA;
+
+// Runtime-readable copy of [`OPT_MESSAGE_BASE64`].
+extern const uint32_t PSV_OPT_MESSAGE_BASE64;
+
+// Runtime-readable copy of [`OPT_MESSAGE_IS_HEX`].
+extern const uint32_t PSV_OPT_MESSAGE_IS_HEX;
+
+// Runtime-readable copy of [`OPT_NFC_NORMALIZE`].
+extern const uint32_t PSV_OPT_NFC_NORMALIZE;
+
+// Runtime-readable copy of [`OPT_SIGNATURE_BASE64`].
+extern const uint32_t PSV_OPT_SIGNATURE_BASE64;
+
+// Runtime-readable copy of [`OPT_SKIP_LONG_MESSAGE_HASH`].
+extern const uint32_t PSV_OPT_SKIP_LONG_MESSAGE_HASH;
+
+// Runtime-readable copy of [`OPT_STRIP_SCHEME_PREFIX`].
+extern const uint32_t PSV_OPT_STRIP_SCHEME_PREFIX;
+
+// Runtime-readable copy of [`OPT_WRAP_BYTES`].
+extern const uint32_t PSV_OPT_WRAP_BYTES;
+
+// Runtime-readable copy of [`SCHEME_ECDSA`].
+extern const int32_t PSV_SCHEME_ECDSA;
+
+// Runtime-readable copy of [`SCHEME_ED25519`].
+extern const int32_t PSV_SCHEME_ED25519;
+
+// Runtime-readable copy of [`SCHEME_ETHEREUM`].
+extern const int32_t PSV_SCHEME_ETHEREUM;
+
+// Runtime-readable copy of [`SCHEME_NONE`].
+extern const int32_t PSV_SCHEME_NONE;
+
+// Runtime-readable copy of [`SCHEME_SR25519`].
+extern const int32_t PSV_SCHEME_SR25519;
+
+// Runtime-readable copy of [`STREAM_PREHASH_ALWAYS`].
+extern const int32_t PSV_STREAM_PREHASH_ALWAYS;
+
+// Runtime-readable copy of [`STREAM_PREHASH_LONG`].
+extern const int32_t PSV_STREAM_PREHASH_LONG;
+
+// Runtime-readable copy of [`VALID`].
+extern const int32_t PSV_VALID;
+
+// Derive the SS58 address of a BIP39 mnemonic without signing anything
+//
+// Lets support staff check that a mnemonic belongs to an address. Neither
+// the mnemonic nor the password is ever logged or echoed in
+// `get_last_error`, and the key derived to compute the address is zeroed
+// before returning.
+//
+// # Arguments
+// * `mnemonic_ptr` - English BIP39 mnemonic of 12 to 24 words
+// * `derivation_path_ptr` - Junctions such as `//Alice` or `//polkadot/0`,
+//   optionally followed by `///password`. May be empty for the root key.
+//   Soft junctions need `SCHEME_SR25519`.
+// * `scheme` - `SCHEME_SR25519`, `SCHEME_ED25519` or `SCHEME_ECDSA`. ECDSA
+//   addresses hold the `blake2_256` hash of the public key, as `subkey`
+//   prints them.
+// * `prefix` - SS58 network prefix, such as 0 for Polkadot or 42 for
+//   generic Substrate
+//
+// # Returns
+// * The SS58 address. Release it with `free_string`.
+// * NULL on failure, with `get_last_error` naming `ERR_UNKNOWN_MNEMONIC_WORD`,
+//   `ERR_BAD_MNEMONIC_CHECKSUM`, `ERR_BAD_MNEMONIC`,
+//   `ERR_BAD_DERIVATION_PATH`, `ERR_UNKNOWN_SCHEME` or `ERR_BAD_SS58` for
+//   a prefix above 16383
+char *address_from_mnemonic(const char *mnemonic_ptr,
+                            const char *derivation_path_ptr,
+                            int32_t scheme,
+                            uint16_t prefix);
+
+// Decode an address into the hex of its 32-byte account id
+//
+// # Arguments
+// * `address_ptr` - SS58-encoded address or hex public key
+//
+// # Returns
+// * `0x` and 64 lowercase hex digits, to be released with `free_string`.
+//   For sr25519 and ed25519 this is the public key. ECDSA accounts are
+//   identified by the `blake2_256` hash of the 33-byte compressed public
+//   key, which is not itself recoverable: addresses holding that hash
+//   return it unchanged, and addresses holding the 33-byte key return its
+//   hash.
+// * NULL if the address does not decode, see `get_last_error`
+char *address_to_public_key_hex(const char *address_ptr);
+
+// Check whether two addresses belong to the same account
+//
+// Addresses are compared by account id, so encodings of one key for
+// different networks are equal.
+//
+// # Arguments
+// * `a_ptr`, `b_ptr` - SS58-encoded addresses or hex public keys
+//
+// # Returns
+// * 1 if both decode to the same account id
+// * 0 if they differ
+// * `ERR_BAD_FIRST_ADDRESS` or `ERR_BAD_SECOND_ADDRESS` if that side does
+//   not decode, checking the first one first
+// * `ERR_NULL_POINTER` or `ERR_INVALID_UTF8` for bad arguments
+int32_t addresses_equal(const char *a_ptr, const char *b_ptr);
+
+// Stop the worker threads of `verify_async`
+//
+// Returns once no worker is running and no callback will be called
+// again, so the library can then be unloaded. Jobs submitted later start
+// a new pool. Does nothing if no pool is running.
+//
+// # Arguments
+// * `wait` - If true, queued jobs all run first. If false, jobs not yet
+//   started are cancelled: their callbacks get `ERR_JOB_CANCELLED` on
+//   this thread, before it returns. Jobs already running finish either
+//   way.
+//
+// Called from a callback, it returns without waiting for that callback's
+// own worker, which exits once the callback returns.
+void async_shutdown(bool wait);
+
+// Forget every cached address, keeping the cache on if it was
+void cache_clear(void);
+
+// Turn on the cache of parsed addresses, or resize or turn it off
+//
+// Verification functions that take an SS58 or hex address then look it up
+// here before parsing it, which saves the base58 decoding and checksum
+// for addresses seen recently. Results are the same either way. The cache
+// is shared by all threads.
+//
+// # Arguments
+// * `capacity` - Most addresses remembered, such as 1024. When full, the
+//   least recently used is forgotten. 0 turns the cache off and frees it,
+//   as it is before the first call.
+//
+// Any addresses already cached are forgotten.
+void cache_init(size_t capacity);
+
+// Forget the most recent failure on the calling thread.
+void clear_last_error(void);
+
+// Derive a soft child of an sr25519 public key
+//
+// # Arguments
+// * `parent_ptr` - SS58-encoded address or hex public key of the parent
+// * `path_ptr` - Soft junctions such as `/payments/0`. Numeric junctions
+//   are integers, as with `sp_core` and `subkey`. The empty path returns
+//   the parent.
+//
+// # Returns
+// * The child in the parent's form: an SS58 address with the same prefix,
+//   or `0x` and 64 hex digits. Release it with `free_string`.
+// * NULL if the parent does not decode, the path has a hard junction
+//   (`ERR_HARD_DERIVATION`) or is malformed (`ERR_BAD_DERIVATION_PATH`),
+//   see `get_last_error`
+char *derive_child_public_key(const char *parent_ptr, const char *path_ptr);
+
+// Address of a well-known dev account, for tests only
+//
+// # Arguments
+// * `name_ptr` - `Alice`, `Bob`, `Charlie`, `Dave`, `Eve` or `Ferdie`,
+//   ignoring case. The key is `Pair::from_string("//<name>", None)`.
+// * `scheme` - `SCHEME_SR25519`, `SCHEME_ED25519` or `SCHEME_ECDSA`
+// * `prefix` - SS58 network prefix, such as 42 for generic Substrate
+//
+// # Returns
+// * The SS58 address. Release it with `free_string`.
+// * NULL on failure, with `get_last_error` naming
+//   `ERR_UNKNOWN_DEV_ACCOUNT`, `ERR_UNKNOWN_SCHEME` or `ERR_BAD_SS58`
+char *dev_account_address(const char *name_ptr, int32_t scheme, uint16_t prefix);
+
+// Sign a message as a well-known dev account, for tests only
+//
+// # Arguments
+// * `name_ptr` - Dev account name, as for `dev_account_address`
+// * `scheme` - `SCHEME_SR25519`, `SCHEME_ED25519` or `SCHEME_ECDSA`
+// * `message_ptr` - The message to sign, signed as its UTF-8 bytes with
+//   no wrapping
+//
+// # Returns
+// * The 0x-prefixed hex signature, which `verify_signature_with_options`
+//   accepts for the same scheme. Release it with `free_string`.
+// * NULL on failure, see `get_last_error`
+char *dev_account_sign(const char *name_ptr, int32_t scheme, const char *message_ptr);
+
+// Describe a return code
+//
+// # Returns
+// A static, NUL-terminated English description. Never NULL, unknown codes
+// map to "unknown error". The pointer must not be freed.
+const char *error_code_to_string(int32_t code);
+
+// Release a string returned by this library, such as from `verify_json`
+// or `reencode_ss58_address`
+//
+// Every function that hands over a string says so with "release it with
+// `free_string`", whether it returns the string or writes it to an out
+// parameter. Those strings come from Rust's allocator, so the host's
+// `free()` must not be used on them: with another C runtime, as is common
+// on Windows, that corrupts the heap. Pointers that are not handed over,
+// such as from `get_last_error`, `get_version` or `scheme_to_string`,
+// must not be passed here. No function returns byte buffers, so there is
+// no `free_bytes`.
+//
+// NULL is ignored, so a failed call's NULL out parameter may be passed
+// without checking. A string must not be freed twice: this is not
+// detected, and is undefined behavior like a double `free()`. Set the
+// pointer to NULL after freeing it. The bytes are zeroed before the
+// memory is released, so a seed from `generate_sr25519_keypair` does not
+// outlive its string.
+void free_string(char *string);
+
+// Generate a new English BIP39 mnemonic
+//
+// The entropy comes from the operating system's CSPRNG, and is zeroed
+// before returning. The phrase is never logged.
+//
+// # Arguments
+// * `word_count` - 12, 15, 18, 21 or 24
+//
+// # Returns
+// * The mnemonic, words separated by single spaces. It is secret: release
+//   it with `free_string`, which zeroes it.
+// * NULL with `ERR_BAD_MNEMONIC` as the last error for any other word
+//   count
+char *generate_mnemonic(uint32_t word_count);
+
+// Generate a throwaway sr25519 keypair
+//
+// The seed comes from the operating system's CSPRNG. It is never logged,
+// and the library's own copies are zeroed before returning: only the
+// returned string holds it, and `free_string` zeroes that too.
+//
+// # Arguments
+// * `out_seed_hex` - Receives the 0x-prefixed hex 32-byte seed, which
+//   `sign_sr25519` accepts
+// * `out_public_hex` - Receives the 0x-prefixed hex 32-byte public key
+// * `out_ss58` - Receives the SS58 address of the public key under
+//   `prefix`
+// * `prefix` - SS58 network prefix of the address, such as 0 for Polkadot
+//   or 42 for generic Substrate
+//
+// All three out-pointers are required. On success each receives its own
+// allocated string, which the caller owns and must release separately
+// with `free_string`. On failure all three are set to NULL and nothing
+// needs releasing.
+//
+// # Returns
+// * `VALID` (1) if a keypair was generated
+// * `ERR_BAD_SS58` if `prefix` is above 16383
+// * `ERR_NULL_POINTER` if an out-pointer is NULL
+int32_t generate_sr25519_keypair(char **out_seed_hex,
+                                 char **out_public_hex,
+                                 char **out_ss58,
+                                 uint16_t prefix);
+
+// ABI revision this library was built with, see `ABI_VERSION`
+//
+// Loaders should refuse to use a library whose ABI version they do not
+// know.
+uint32_t get_abi_version(void);
+
+// Describe how the loaded library was built
+//
+// # Returns
+// A JSON object such as `{"version": "0.1.0", "features": ["default",
+// "ffi"], "target": "x86_64-unknown-linux-gnu", "commit": "79bfe07"}`.
+// `commit` is the output of `git describe --always --dirty`, or
+// `"unknown"` if git was not available at build time. Release it with
+// `free_string`. NULL is only returned if the library panicked.
+char *get_build_info(void);
+
+// Get the crate version as numbers
+//
+// Prefer `get_version_numbers`, which reports NULL pointers.
+//
+// # Arguments
+// * `major`, `minor`, `patch` - Receive the version components. Any of
+//   them may be NULL.
+void get_crate_version_numbers(uint32_t *major, uint32_t *minor, uint32_t *patch);
+
+// Describe the most recent failure on the calling thread
+//
+// # Returns
+// * A NUL-terminated message, valid until the next call into this library
+//   on the same thread. The library keeps ownership: do not free it.
+// * NULL if nothing failed since the last `clear_last_error`
+//
+// Like `errno`, the slot is not reset by successful calls.
+const char *get_last_error(void);
+
+// Report the network of an SS58 address
+//
+// # Arguments
+// * `address_ptr` - SS58-encoded address with a 32-byte payload, or a
+//   33-byte one such as a compressed ECDSA public key
+//
+// # Returns
+// * The SS58 prefix, e.g. 0 for Polkadot, 2 for Kusama or 42 for generic
+//   Substrate
+// * a negative `ERR_*` code if the address does not decode, such as
+//   `ERR_SS58_BAD_CHECKSUM`
+int32_t get_ss58_prefix(const char *address_ptr);
+
+// Read the verification counters
+//
+// # Arguments
+// * `out` - Receives the counts since the last `reset_stats`, or since
+//   the library was loaded. May be NULL, in which case nothing is written.
+//
+// The counters only move while counting is on, see `stats_init`.
+void get_stats(struct Stats *out);
+
+// List the schemes this build verifies
+//
+// # Returns
+// The bits `1 << SCHEME_*` of every supported scheme, see
+// `SignatureScheme`. Which schemes are supported depends on the cargo
+// features the library was built with.
+uint32_t get_supported_schemes(void);
+
+// Get the library version, such as "0.1.0", as set in Cargo.toml
+//
+// The string is static and NUL-terminated: nothing is allocated, so the
+// caller must not pass it to `free_string`, and it stays valid for the
+// life of the process.
+const char *get_version(void);
+
+// Get the library version as numbers, for hosts comparing versions
+//
+// # Arguments
+// * `major`, `minor`, `patch` - Receive the version components
+//
+// # Returns
+// * `VALID` (1) once all three are written
+// * `ERR_NULL_POINTER` if any pointer is NULL, in which case nothing is
+//   written
+int32_t get_version_numbers(uint32_t *major, uint32_t *minor, uint32_t *patch);
+
+// Release a keypair and zero its secret key
+//
+// NULL, unknown and already freed handles are ignored, so freeing twice is
+// harmless. Callers should still set their copy of the handle to NULL
+// after freeing it. Signatures still running on other threads finish
+// first; the secret is zeroed when the last of them returns.
+void keypair_free(struct Keypair *keypair);
+
+// Load an sr25519 keypair from a BIP39 mnemonic and derivation path
+//
+// # Arguments
+// * `mnemonic_ptr` - English BIP39 mnemonic of 12 to 24 words
+// * `derivation_path_ptr` - Junctions optionally followed by
+//   `///password`, as for `sign_with_mnemonic`. May be empty.
+//
+// # Returns
+// A handle for `keypair_sign`, to be released with `keypair_free`, or
+// NULL with the last error set to one of the codes `sign_with_mnemonic`
+// returns. Neither the mnemonic nor the password is echoed in it.
+struct Keypair *keypair_from_mnemonic(const char *mnemonic_ptr, const char *derivation_path_ptr);
+
+// Load an sr25519 keypair from a seed
+//
+// # Arguments
+// * `seed_hex_ptr` - Hex-encoded 32-byte mini-secret seed, with or without
+//   0x prefix, as for `sign_sr25519`
+//
+// # Returns
+// A handle for `keypair_sign`, to be released with `keypair_free`, or
+// NULL with `ERR_BAD_SEED_LENGTH`, `ERR_BAD_HEX`, `ERR_NULL_POINTER` or
+// `ERR_INVALID_UTF8` as the last error. The decoded seed is zeroed before
+// returning.
+struct Keypair *keypair_from_seed(const char *seed_hex_ptr);
+
+// Load a keypair of any signing scheme from a seed
+//
+// # Arguments
+// * `seed_hex_ptr` - Hex-encoded 32-byte seed, as for `sign_sr25519`,
+//   `sign_ed25519` or `sign_ecdsa`
+// * `scheme` - `SCHEME_SR25519`, `SCHEME_ED25519` or `SCHEME_ECDSA`
+//
+// # Returns
+// A handle, or NULL with the last error set as for `keypair_from_seed`,
+// or to `ERR_UNKNOWN_SCHEME` or `ERR_BAD_SEED` for a secp256k1 secret
+// out of range.
+struct Keypair *keypair_from_seed_with_scheme(const char *seed_hex_ptr, int32_t scheme);
+
+// Public key of a keypair
+//
+// # Returns
+// * The 0x-prefixed hex public key: 32 bytes, or 33 compressed bytes for
+//   ECDSA. Release it with `free_string`.
+// * NULL with `ERR_INVALID_HANDLE` as the last error if the handle is
+//   NULL, unknown or freed
+char *keypair_public_hex(struct Keypair *keypair);
+
+// Signing scheme of a keypair
+//
+// # Returns
+// * `SCHEME_SR25519`, `SCHEME_ED25519` or `SCHEME_ECDSA`
+// * `ERR_INVALID_HANDLE` if the handle is NULL, unknown or freed
+int32_t keypair_scheme(struct Keypair *keypair);
+
+// Sign bytes with a keypair
+//
+// Safe to call from several threads at once on the same handle.
+//
+// # Arguments
+// * `keypair` - Any keypair handle, signing with its own scheme
+// * `message_ptr` - The bytes to sign, signed as given with no wrapping,
+//   or as their `blake2_256` hash if over 256 bytes, the way verification
+//   checks them. May be NULL if `message_len` is 0.
+// * `message_len` - Number of bytes at `message_ptr`
+// * `out_signature_hex` - Receives the 0x-prefixed hex signature. Release
+//   it with `free_string`. Set to NULL on failure.
+//
+// # Returns
+// * `VALID` (1) if the message was signed
+// * `ERR_INVALID_HANDLE` if the handle is NULL, unknown or freed
+// * `ERR_NULL_POINTER` for a NULL output or message pointer
+int32_t keypair_sign(struct Keypair *keypair,
+                     const uint8_t *message_ptr,
+                     size_t message_len,
+                     char **out_signature_hex);
+
+// SS58 address of a keypair
+//
+// # Arguments
+// * `keypair` - Any keypair handle. ECDSA addresses hold the `blake2_256`
+//   hash of the public key, as for `address_from_mnemonic`.
+// * `prefix` - SS58 network prefix, such as 0 for Polkadot or 42 for
+//   generic Substrate
+//
+// # Returns
+// * The SS58 address. Release it with `free_string`.
+// * NULL with `ERR_INVALID_HANDLE`, or `ERR_BAD_SS58` for a prefix above
+//   16383, as the last error
+char *keypair_ss58(struct Keypair *keypair, uint16_t prefix);
+
+// Release a nonce cache
+//
+// NULL, unknown and already freed handles are ignored. Calls still
+// running on other threads finish with the cache first.
+void nonce_cache_free(struct NonceCache *cache);
+
+// Create a cache of used nonces
+//
+// # Arguments
+// * `capacity` - Most (address, nonce) pairs remembered at once. When
+//   full, the oldest pair is forgotten early.
+// * `ttl_secs` - How long a pair is remembered after it was used
+//
+// # Returns
+// A handle for `verify_with_nonce`, to be released with
+// `nonce_cache_free`, or NULL with `ERR_INVALID_OPTIONS` as the last error
+// if `capacity` is 0. The cache may be shared between threads.
+struct NonceCache *nonce_cache_new(size_t capacity, uint64_t ttl_secs);
+
+// Look up a scheme by name
+//
+// # Arguments
+// * `name` - Scheme name such as "sr25519" or "ECDSA", case-insensitive
+//
+// # Returns
+// * The `SCHEME_*` constant of the scheme
+// * `ERR_UNKNOWN_SCHEME` if no scheme has that name
+// * `ERR_NULL_POINTER` or `ERR_INVALID_UTF8` for bad arguments
+int32_t parse_scheme(const char *name);
+
+// Encode a public key as an SS58 address
+//
+// # Arguments
+// * `public_key_hex_ptr` - 32-byte public key or account id as hex, with
+//   or without `0x`
+// * `prefix` - SS58 prefix of the network, at most 16383
+//
+// # Returns
+// * The address, to be released with `free_string`
+// * NULL with `get_last_error` describing an `ERR_BAD_HEX`,
+//   `ERR_BAD_PUBLIC_KEY_LENGTH` or `ERR_BAD_SS58` (prefix out of range)
+//   failure
+char *public_key_to_ss58(const char *public_key_hex_ptr, uint16_t prefix);
+
+// Convert an SS58 address to another network's encoding of the same key
+//
+// # Arguments
+// * `address_ptr` - SS58-encoded address with a 32- or 33-byte payload
+// * `target_prefix` - SS58 prefix to encode with, at most 16383
+//
+// # Returns
+// * The re-encoded address, to be released with `free_string`
+// * NULL if the address does not decode or the prefix is out of range, see
+//   `get_last_error`
+char *reencode_ss58_address(const char *address_ptr, uint16_t target_prefix);
+
+// Set every verification counter back to 0
+void reset_stats(void);
+
+// Check whether this build verifies a scheme
+//
+// # Returns
+// * `VALID` (1) if `scheme` is supported
+// * `INVALID` (0) if it was left out of this build
+// * `ERR_UNKNOWN_SCHEME` if `scheme` is not a `SCHEME_*` constant
+int32_t scheme_supported(int32_t scheme);
+
+// Name a scheme
+//
+// # Returns
+// A static, NUL-terminated lowercase name that `parse_scheme` accepts, or
+// NULL if `scheme` is not a `SCHEME_*` constant. The pointer must not be
+// freed.
+const char *scheme_to_string(int32_t scheme);
+
+// Install or remove the log callback
+//
+// # Arguments
+// * `cb` - Called on the verifying thread at key points of every call.
+//   Pass NULL to disable logging.
+//
+// The callback may be invoked from several threads at once.
+void set_log_callback(LogCallback cb);
+
+// Sign a message with a secp256k1 ECDSA key
+//
+// Takes the same arguments as `sign_sr25519`, with the 32-byte secret key
+// as seed, and returns the same codes plus `ERR_BAD_SEED` for a secret key
+// outside the curve order. The signature is the 65-byte recoverable form
+// over the `blake2_256` hash of the message, after a message over 256
+// bytes is first replaced by its own `blake2_256` hash as for sr25519.
+// `verify_signature_with_options` checks it with `SCHEME_ECDSA` against
+// the address of the hashed public key. Release it with `free_string`.
+int32_t sign_ecdsa(const char *seed_hex_ptr, const char *message_ptr, char **out_signature_hex);
+
+// Sign a message with an ed25519 key
+//
+// Takes the same arguments as `sign_sr25519`, with the 32-byte ed25519
+// secret seed, and returns the same codes. Messages over 256 bytes are
+// signed as their `blake2_256` hash, as for sr25519. The signature is 64
+// bytes and deterministic. Release it with `free_string`.
+int32_t sign_ed25519(const char *seed_hex_ptr, const char *message_ptr, char **out_signature_hex);
+
+// Sign a message with an sr25519 key
+//
+// Meant for tests and tooling that need signatures this library can
+// verify. The seed is never logged or echoed in `get_last_error`, and its
+// decoded bytes and the key are zeroed before returning, on failure too.
+//
+// # Arguments
+// * `seed_hex_ptr` - Hex-encoded 32-byte mini-secret seed, with or without
+//   0x prefix, as printed by `subkey inspect` under "Secret seed"
+// * `message_ptr` - The message to sign, signed as its UTF-8 bytes with
+//   no wrapping, or as their `blake2_256` hash if over 256 bytes, the
+//   way `verify_polkadot_signature_v2` checks it
+// * `out_signature_hex` - Receives the 0x-prefixed hex signature. Release
+//   it with `free_string`. Set to NULL on failure.
+//
+// # Returns
+// * `VALID` (1) if the message was signed
+// * `ERR_BAD_SEED_LENGTH` if the seed is not 32 bytes
+// * `ERR_BAD_HEX`, `ERR_NULL_POINTER` or `ERR_INVALID_UTF8` for bad
+//   arguments
+//
+// Signatures are randomized, so signing twice gives different results
+// that both verify.
+int32_t sign_sr25519(const char *seed_hex_ptr, const char *message_ptr, char **out_signature_hex);
+
+// Sign a message with an sr25519 key derived from a BIP39 mnemonic
+//
+// The key is the one `subkey` and `sr25519::Pair::from_string` derive
+// from the mnemonic followed by the derivation path. Neither the mnemonic
+// nor the password is ever logged or echoed in `get_last_error`. The
+// entropy, seeds and keys computed along the way are zeroed before
+// returning, including when the path or mnemonic is rejected.
+//
+// # Arguments
+// * `mnemonic_ptr` - English BIP39 mnemonic of 12 to 24 words
+// * `derivation_path_ptr` - Junctions such as `//polkadot//0/1`,
+//   optionally followed by `///password`. May be empty for the root key.
+// * `message_ptr` - The message to sign, signed as `sign_sr25519` signs
+//   it
+// * `out_signature_hex` - Receives the 0x-prefixed hex signature. Release
+//   it with `free_string`. Set to NULL on failure.
+// * `out_address` - Receives the SS58 address of the derived key under the
+//   generic Substrate prefix 42, so the caller can check it is the
+//   expected signer. Release it with `free_string`. May be NULL if not
+//   wanted, and is set to NULL on failure.
+//
+// # Returns
+// * `VALID` (1) if the message was signed
+// * `ERR_UNKNOWN_MNEMONIC_WORD` for a word missing from the word list
+// * `ERR_BAD_MNEMONIC_CHECKSUM` for known words that fail the checksum
+// * `ERR_BAD_MNEMONIC` for a wrong number of words
+// * `ERR_BAD_DERIVATION_PATH` for a malformed path
+// * `ERR_NULL_POINTER` or `ERR_INVALID_UTF8` for bad arguments
+int32_t sign_with_mnemonic(const char *mnemonic_ptr,
+                           const char *derivation_path_ptr,
+                           const char *message_ptr,
+                           char **out_signature_hex,
+                           char **out_address);
+
+// Sign a message with an sr25519 key the way polkadot-js extensions do
+//
+// Takes the same arguments as `sign_sr25519` and returns the same codes,
+// so the signature is released with `free_string`, but signs the message
+// wrapped in `<Bytes>...</Bytes>`, and as its
+// `blake2_256` hash once wrapped it is longer than 256 bytes. Already
+// wrapped messages are not wrapped again. The signature verifies with
+// `verify_polkadot_signature_auto` and with `OPT_WRAP_BYTES`, since
+// signing and verification share one canonicalization.
+int32_t sign_wrapped(const char *seed_hex_ptr, const char *message_ptr, char **out_signature_hex);
+
+// Release a parsed signature
+//
+// NULL, unknown and already freed handles are ignored.
+void signature_free(struct ParsedSignature *signature);
+
+// Decode and check a signature once for repeated verification
+//
+// # Arguments
+// * `signature_ptr` - Hex-encoded SR25519 signature (with or without 0x
+//   prefix)
+//
+// # Returns
+// A handle for `signature_verify`, to be released with `signature_free`,
+// or NULL with the last error set to the code `verify_polkadot_signature_v2`
+// would give for the signature: `ERR_BAD_HEX`, `ERR_BAD_SIGNATURE_LENGTH`
+// or `ERR_NON_CANONICAL_SIGNATURE`. The handle may be shared between
+// threads.
+struct ParsedSignature *signature_parse(const char *signature_ptr);
+
+// Verify a parsed Polkadot SR25519 signature over a message
+//
+// Gives the same result as `verify_polkadot_signature_bytes` with the
+// signature the handle was parsed from, without decoding it again.
+//
+// # Arguments
+// * `signature` - From `signature_parse`
+// * `address_ptr` - SS58-encoded address, or the raw 32-byte public key
+//   as 64 hex digits, optionally 0x-prefixed
+// * `message_ptr` - The signed bytes. May be NULL if `message_len` is 0.
+// * `message_len` - Number of bytes at `message_ptr`
+//
+// # Returns
+// The same codes as `verify_polkadot_signature_v2`, except that the
+// signature cannot fail to decode, plus `ERR_INVALID_HANDLE` for NULL,
+// unknown or freed handles.
+int32_t signature_verify(struct ParsedSignature *signature,
+                         const char *address_ptr,
+                         const uint8_t *message_ptr,
+                         size_t message_len);
+
+// Name the network an SS58 prefix belongs to
+//
+// # Returns
+// A static, NUL-terminated registry name such as `polkadot`, `kusama` or
+// `substrate`. Never NULL: prefixes missing from the compiled-in registry
+// snapshot map to `unknown`. The pointer must not be freed.
+const char *ss58_network_name(uint16_t prefix);
+
+// Describe every network in the compiled-in registry snapshot
+//
+// # Returns
+// A JSON array ordered by prefix, such as
+// `[{"prefix": 0, "network": "polkadot", "displayName": "Polkadot Relay
+// Chain", "symbol": "DOT", "decimals": 10}, ...]`, with a `null` symbol
+// for networks without a token. Release it with `free_string`. NULL is
+// only returned if the library panicked.
+char *ss58_registry_json(void);
+
+// Turn verification counting on or off
+//
+// # Arguments
+// * `enabled` - Nonzero to count every verification from now on, 0 to
+//   stop. Counters keep their values either way; see `reset_stats`.
+void stats_init(uint8_t enabled);
+
+// Check a derivation path without deriving anything
+//
+// # Arguments
+// * `path_ptr` - Junctions such as `//polkadot/0` or `/payments/0`,
+//   optionally followed by `///password`, as accepted by
+//   `sign_with_mnemonic` and `address_from_mnemonic`. The empty path is
+//   valid.
+//
+// # Returns
+// * `VALID` (1) if the path parses
+// * `ERR_BAD_DERIVATION_PATH` if it does not, see `get_last_error`
+// * `ERR_NULL_POINTER` or `ERR_INVALID_UTF8` for bad arguments
+int32_t validate_derivation_path(const char *path_ptr);
+
+// Check a BIP39 mnemonic against the English word list and its checksum
+//
+// Neither the mnemonic nor any word of it is logged or echoed in
+// `get_last_error`, which names only the position of an unknown word.
+//
+// # Arguments
+// * `phrase_ptr` - The mnemonic, words separated by single spaces
+//
+// # Returns
+// * `VALID` (1) if the mnemonic is valid
+// * `ERR_UNKNOWN_MNEMONIC_WORD` if a word is not in the word list
+// * `ERR_BAD_MNEMONIC_CHECKSUM` if the words fail their checksum
+// * `ERR_BAD_MNEMONIC` if there are not 12, 15, 18, 21 or 24 words
+// * `ERR_NULL_POINTER` or `ERR_INVALID_UTF8` for a bad argument
+int32_t validate_mnemonic(const char *phrase_ptr);
+
+// Check an SS58 address without verifying anything
+//
+// # Arguments
+// * `address_ptr` - SS58-encoded address
+// * `out` - Receives the prefix, payload and kind of a valid address. May
+//   be NULL, in which case only the code is returned.
+//
+// # Returns
+// * 1 (`VALID`) if the address decodes
+// * `ERR_SS58_BAD_BASE58`, `ERR_SS58_BAD_LENGTH` or `ERR_SS58_BAD_CHECKSUM`
+//   for the corresponding corruption
+// * `ERR_WRONG_KEY_LENGTH` for an intact address whose payload is neither
+//   32 nor 33 bytes, such as a 20-byte `AccountId20`
+// * `ERR_BAD_SS58` for an invalid or reserved prefix
+// * `ERR_NULL_POINTER` or `ERR_INVALID_UTF8` for bad arguments
+//
+// When the code is negative `out` is zeroed.
+int32_t validate_ss58_address(const char *address_ptr, struct AddressInfo *out);
+
+// Check many SS58 addresses in one call
+//
+// Classifies each address like `validate_ss58_address`, without
+// allocating or touching `get_last_error`, and carries on past failures.
+//
+// # Arguments
+// * `addresses` - Array of `count` NUL-terminated addresses
+// * `count` - Number of addresses
+// * `results` - Array of `count` codes to fill: 1 (`VALID`) or the
+//   negative code `validate_ss58_address` would return, including
+//   `ERR_NULL_POINTER` for NULL entries
+//
+// # Returns
+// * The number of invalid addresses, so 0 means all are valid
+// * `ERR_NULL_POINTER` if `addresses` or `results` is NULL while `count`
+//   is not 0, in which case `results` is untouched
+int64_t validate_ss58_addresses(const char *const *addresses, size_t count, int32_t *results);
+
+// Release a verifier
+//
+// NULL, unknown and already freed handles are ignored. Calls still
+// running on other threads finish with the verifier first.
+void verifier_free(struct Verifier *verifier);
+
+// Parse an address once for repeated verification
+//
+// # Arguments
+// * `address_ptr` - SS58-encoded address, as for
+//   `verify_polkadot_signature_v2`
+//
+// # Returns
+// A handle for `verifier_verify`, to be released with `verifier_free`, or
+// NULL with the last error set to the code `verify_polkadot_signature_v2`
+// would give for the address, such as `ERR_SS58_BAD_CHECKSUM`. The handle
+// may be shared between threads.
+struct Verifier *verifier_new(const char *address_ptr);
+
+// Verify a Polkadot SR25519 signature by a verifier's address
+//
+// Gives the same result as `verify_polkadot_signature_bytes` with the
+// address the verifier was created from, without parsing it again.
+//
+// # Arguments
+// * `verifier` - From `verifier_new`
+// * `signature_ptr` - Hex-encoded signature (with or without 0x prefix)
+// * `message_ptr` - The signed bytes. May be NULL if `message_len` is 0.
+// * `message_len` - Number of bytes at `message_ptr`
+//
+// # Returns
+// The same codes as `verify_polkadot_signature_v2`, plus
+// `ERR_INVALID_HANDLE` for NULL, unknown or freed verifiers.
+int32_t verifier_verify(struct Verifier *verifier,
+                        const char *signature_ptr,
+                        const uint8_t *message_ptr,
+                        size_t message_len);
+
+// Find the address that made a Polkadot SR25519 signature
+//
+// # Arguments
+// * `addresses` - Array of `count` candidate SS58 addresses, or raw public
+//   keys in hex as for `verify_polkadot_signature`
+// * `count` - Number of candidates
+// * `signature_ptr` - Hex-encoded signature (with or without 0x prefix)
+// * `message_ptr` - The original message that was signed
+//
+// Candidates that are NULL or fail to parse are skipped with a
+// `LOG_WARN` message, so one malformed entry does not hide a match.
+//
+// # Returns
+// * The index of the first candidate the signature verifies against
+// * `NO_MATCHING_ADDRESS` (-1) if there is none
+// * One less than an `ERR_*` code if the signature or message is
+//   malformed, or `addresses` is NULL while `count` is not 0: -2 for
+//   `ERR_NULL_POINTER`, -5 for `ERR_BAD_HEX` and so on. `get_last_error`
+//   has the details.
+int64_t verify_against_addresses(const char *const *addresses,
+                                 size_t count,
+                                 const char *signature_ptr,
+                                 const char *message_ptr);
+
+// Verify a signature of unknown scheme
+//
+// Takes the same arguments as `verify_polkadot_signature`, plus:
+// * `detected_scheme_out` - Receives the `SCHEME_*` constant that
+//   verified, or `SCHEME_NONE`. May be NULL.
+//
+// Schemes are tried by signature length: 64 bytes as sr25519 then
+// ed25519, 65 bytes as ECDSA.
+//
+// # Returns
+// The same codes as `verify_polkadot_signature_v2`, plus
+// `ERR_NO_MATCHING_SCHEME` when the signature length fits no scheme.
+int32_t verify_any_signature(const char *address_ptr,
+                             const char *signature_ptr,
+                             const char *message_ptr,
+                             int32_t *detected_scheme_out);
+
+// Verify a Polkadot SR25519 signature on a worker thread
+//
+// Takes the arguments of `verify_polkadot_signature_v2`, which are copied
+// before returning, and queues the verification for a pool of one worker
+// thread per core. The pool starts with the first job.
+//
+// # Arguments
+// * `user_data` - Passed back to `callback` untouched
+// * `callback` - Called exactly once per job id returned, on a worker
+//   thread, with the code `verify_polkadot_signature_v2` would return,
+//   or with `ERR_JOB_CANCELLED` on the thread calling `async_shutdown` if
+//   the job was abandoned. Callbacks from different jobs may run at once
+//   and in any order.
+//
+// # Returns
+// The job id, or 0 with the last error set if `callback` is NULL
+// (`ERR_NULL_POINTER`) or the pool could not start (`ERR_THREAD_POOL`).
+// The callback is not called for jobs that were not queued.
+uint64_t verify_async(const char *address_ptr,
+                      const char *signature_ptr,
+                      const char *message_ptr,
+                      void *user_data,
+                      AsyncCallback callback);
+
+// Verify many Polkadot SR25519 signatures in one call
+//
+// Item `i` is verified like `verify_polkadot_signature_v2` with
+// `addresses[i]`, `signatures[i]` and `messages[i]`. Carries on past
+// failures, and leaves `get_last_error` alone for them, so a batch costs
+// one FFI call rather than one per item.
+//
+// Items are checked with schnorrkel's batch verification in chunks, which
+// is several times faster than one at a time while all are valid. A
+// chunk that fails is checked again one item at a time, so each result is
+// exactly what verifying that item alone gives.
+//
+// # Arguments
+// * `addresses` - Array of `count` SS58 addresses
+// * `signatures` - Array of `count` hex signatures
+// * `messages` - Array of `count` messages
+// * `count` - Number of items
+// * `results` - Array of `count` codes to fill with what
+//   `verify_polkadot_signature_v2` would return for each item, including
+//   `ERR_NULL_POINTER` for NULL entries
+//
+// # Returns
+// * The number of items whose result is not `VALID`, so 0 means every
+//   signature verified. Saturates at `i32::MAX`.
+// * `ERR_NULL_POINTER` if an array is NULL while `count` is not 0, in
+//   which case `results` is untouched
+int32_t verify_batch(const char *const *addresses,
+                     const char *const *signatures,
+                     const char *const *messages,
+                     size_t count,
+                     int32_t *results);
+
+// Verify many Polkadot SR25519 signatures across several threads
+//
+// Takes the arguments of `verify_batch`, fills `results` with the same
+// codes at the same indices, and returns the same count. Chunks of items
+// are shared out over at most `num_threads` threads of a pool of one
+// thread per core, started by the first call and reused by later ones.
+// Safe to call from several host threads at once, which then share the
+// pool.
+//
+// A log callback set with `set_log_callback` may be called from the pool
+// threads.
+//
+// # Arguments
+// * `num_threads` - Most threads to use, or 0 for one per core. Counts
+//   above the number of cores are clamped to it.
+//
+// # Returns
+// * As for `verify_batch`
+// * `ERR_THREAD_POOL` if the pool threads could not be started, in which
+//   case `results` is untouched
+int32_t verify_batch_parallel(const char *const *addresses,
+                              const char *const *signatures,
+                              const char *const *messages,
+                              size_t count,
+                              int32_t *results,
+                              uint32_t num_threads);
+
+// Verify a Polkadot SR25519 signature from raw bytes, without parsing
+//
+// For hot paths that already hold the account and signature bytes.
+//
+// # Arguments
+// * `account_id` - Exactly 32 bytes: the sr25519 public key
+// * `signature` - Exactly 64 bytes: the signature
+// * `message` - The signed bytes. May be NULL if `message_len` is 0.
+// * `message_len` - Number of bytes at `message`
+//
+// There are deliberately no length arguments for `account_id` and
+// `signature`: the library reads exactly 32 and 64 bytes from them, and
+// shorter buffers are undefined behavior. The message is verified as
+// given, without `<Bytes>` wrapping or hashing of long messages.
+//
+// # Returns
+// * `VALID` (1) or `INVALID` (0)
+// * `ERR_NULL_POINTER` if a pointer is NULL
+// * `ERR_NON_CANONICAL_SIGNATURE` for a malleated signature encoding
+int32_t verify_by_account_id(const uint8_t *account_id,
+                             const uint8_t *signature,
+                             const uint8_t *message,
+                             size_t message_len);
+
+// Verify a signature by the signer a `did:key` identifies
+//
+// # Arguments
+// * `did_key_ptr` - `did:key:z...` with an sr25519, ed25519 or secp256k1
+//   public key, optionally followed by a `#` fragment
+// * `signature_ptr` - Hex-encoded signature, with or without 0x prefix:
+//   64 bytes for sr25519 and ed25519, 65 recoverable bytes for secp256k1
+// * `message_ptr` - The original message that was signed
+//
+// The scheme follows from the key type, and the message is verified as
+// given, like `verify_polkadot_signature_v2`.
+//
+// # Returns
+// The same codes as `verify_polkadot_signature_v2`, with
+// `ERR_UNSUPPORTED_KEY_TYPE` for other key types and `ERR_BAD_DID_KEY` for
+// identifiers that do not decode.
+int32_t verify_by_did_key(const char *did_key_ptr,
+                          const char *signature_ptr,
+                          const char *message_ptr);
+
+// Verify an Ethereum-style `personal_sign` signature
+//
+// For AccountId20 chains such as Moonbeam.
+//
+// # Arguments
+// * `address_ptr` - 20-byte H160 address in hex, with or without 0x
+//   prefix. Case-insensitive, but mixed case must be valid EIP-55.
+// * `signature_ptr` - Hex-encoded 65-byte signature (r, s, v) with v as
+//   0/1 or 27/28
+// * `message_ptr` - The message passed to `personal_sign`, without the
+//   "\x19Ethereum Signed Message:\n" prefix, which is applied here
+//
+// # Returns
+// * `VALID` (1) if the signature recovers to the address
+// * `INVALID` (0) otherwise
+// * `ERR_NON_CANONICAL_SIGNATURE` for high-s signatures, rejected by EIP-2
+// * `ERR_BAD_ETH_ADDRESS` or `ERR_ETH_ADDRESS_CHECKSUM` for bad addresses
+// * `ERR_SCHEME_NOT_SUPPORTED` if built without the `ecdsa` feature
+// * the signature and pointer errors of `verify_polkadot_signature_v2`
+int32_t verify_ethereum_style_signature(const char *h160_address_ptr,
+                                        const char *signature_ptr,
+                                        const char *message_ptr);
+
+// Verify the signature of a signed extrinsic
+//
+// Decodes a v4 signed extrinsic, rebuilds the payload its signer signed,
+// hashed with `blake2_256` if over 256 bytes, and verifies the embedded
+// `MultiSignature` against the embedded signer.
+//
+// The signed extensions must be those of the Polkadot and Kusama relay
+// chains: an era, a compact nonce and a compact tip, followed on runtimes
+// since 2024 by the `CheckMetadataHash` mode byte. `metadata_hash_mode`
+// says which layout the runtime uses, or `METADATA_HASH_DETECT` tries
+// both for callers that do not know. Chains with other extensions, such
+// as `ChargeAssetTxPayment`, never verify.
+//
+// # Arguments
+// * `extrinsic_ptr` - Hex-encoded extrinsic, with or without 0x prefix,
+//   including its compact length prefix
+// * `genesis_hash_ptr` - Hex-encoded genesis hash of the chain
+// * `block_hash_ptr` - Hex-encoded hash of the block a mortal extrinsic's
+//   era starts at. May be NULL for immortal extrinsics, which commit to
+//   the genesis hash instead.
+// * `spec_version`, `transaction_version` - The runtime versions the
+//   extrinsic was signed for
+// * `metadata_hash_mode` - One of the `METADATA_HASH_*` modes. With
+//   `METADATA_HASH_DISABLED` or `METADATA_HASH_ENABLED`, the extrinsic
+//   must carry that mode.
+// * `metadata_hash_ptr` - Hex-encoded metadata hash, needed for
+//   `METADATA_HASH_ENABLED` and for detecting an extrinsic signed with it.
+//   May be NULL otherwise.
+//
+// # Returns
+// * `VALID` or `INVALID`
+// * `ERR_NOT_SIGNED` if the extrinsic is unsigned
+// * `ERR_BAD_EXTRINSIC` if it cannot be decoded, its signer is not an
+//   account ID, or it carries another `CheckMetadataHash` mode than
+//   `metadata_hash_mode`
+// * `ERR_INVALID_OPTIONS` for an unknown `metadata_hash_mode`
+// * `ERR_BAD_MULTISIGNATURE` for an unknown signature variant
+// * `ERR_NULL_POINTER` if the extrinsic is mortal and `block_hash_ptr` is
+//   NULL, or `METADATA_HASH_ENABLED` is given without a metadata hash
+// * `ERR_BAD_HEX`, or `ERR_BAD_HEX_LENGTH` for a hash that is not
+//   32 bytes
+int32_t verify_extrinsic(const char *extrinsic_ptr,
+                         const char *genesis_hash_ptr,
+                         const char *block_hash_ptr,
+                         uint32_t spec_version,
+                         uint32_t transaction_version,
+                         int32_t metadata_hash_mode,
+                         const char *metadata_hash_ptr);
+
+// Verify a signature described by a JSON request
+//
+// # Arguments
+// * `request` - NUL-terminated JSON object with `address`, `signature`
+//   and `message` strings and an optional `options` object whose fields
+//   mirror `VerifyOptions`, e.g. `{"scheme": "ed25519", "wrap_bytes": true}`.
+//   Unknown fields are ignored.
+//
+// # Returns
+// A JSON object such as `{"valid":true,"scheme":"sr25519"}` to be released
+// with `free_string`. Failures, including malformed JSON, add
+// `"error_code"`, one of the `ERR_*` codes, and `"error_message"`.
+// `scheme` is absent if the request could not be read. NULL is only
+// returned if the library panicked.
+char *verify_json(const char *request);
+
+// Verify an ed25519 signature from the Polkadot Ledger app
+//
+// Takes the same arguments as `verify_polkadot_signature`, plus:
+// * `convention_out` - Receives the `LEDGER_*` constant of the
+//   convention that verified, or `LEDGER_NONE`. May be NULL.
+//
+// Depending on its version, the Ledger app signs either the raw message
+// or, when the payload exceeds the device buffer, its `blake2_256` hash.
+// They are tried in that order, and neither wraps the message in
+// `<Bytes>`.
+//
+// # Returns
+// The same codes as `verify_polkadot_signature_v2`, or
+// `ERR_SCHEME_NOT_SUPPORTED` in builds without the `sp-core` feature.
+int32_t verify_ledger_signature(const char *address_ptr,
+                                const char *signature_ptr,
+                                const char *message_ptr,
+                                int32_t *convention_out);
+
+// Verify a SCALE-encoded `MultiSignature`
+//
+// # Arguments
+// * `address_ptr` - SS58-encoded address
+// * `multisignature_ptr` - Hex-encoded `MultiSignature`, with or without
+//   0x prefix: a variant tag (0x00 ed25519, 0x01 sr25519, 0x02 ecdsa)
+//   followed by the 64 or 65 signature bytes of that scheme
+// * `message_ptr` - The original message that was signed
+//
+// # Returns
+// The same codes as `verify_polkadot_signature_v2`, plus
+// `ERR_BAD_MULTISIGNATURE` for unknown tags or truncated payloads.
+int32_t verify_multisignature(const char *address_ptr,
+                              const char *multisignature_ptr,
+                              const char *message_ptr);
+
+// Verify requests given one per line, from a single buffer
+//
+// For hosts where passing arrays of pointers, as to `verify_batch`, is
+// awkward.
+//
+// # Arguments
+// * `input` - Newline-delimited JSON: one request per line, each an
+//   object as for `verify_json`. Lines may end in `\r\n`. Need not be
+//   NUL-terminated, and may be NULL if `input_len` is 0.
+// * `input_len` - Number of bytes at `input`
+//
+// Blank lines are skipped. A line that is not a valid request gets an
+// error result, and the lines after it are still verified.
+//
+// # Returns
+// One line per request, in input order, each ending in a newline: the
+// outcome `verify_json` gives, plus `index`, such as
+// `{"index":0,"valid":true,"scheme":"sr25519"}`. `index` counts requests,
+// skipping blank lines. To be released with
+// `free_string`. NULL with the last error set if `input` is NULL while
+// `input_len` is not 0.
+char *verify_ndjson(const char *input, size_t input_len);
+
+// Verify a Polkadot ECDSA (secp256k1) signature
+//
+// # Arguments
+// * `address_ptr` - SS58-encoded account id of an ECDSA key, or the
+//   33-byte compressed key itself SS58-encoded
+// * `signature_ptr` - Hex-encoded 65-byte recoverable signature (r, s,
+//   recovery id), with or without 0x prefix
+// * `message_ptr` - The original message that was signed
+//
+// An ECDSA account id is `blake2_256` of the compressed public key, not
+// the key itself. The key is recovered from the signature, hashed and
+// compared with the account id, which is first computed the same way for
+// addresses holding the key.
+//
+// # Returns
+// The same codes as `verify_polkadot_signature_v2`, plus
+// `ERR_NON_CANONICAL_SIGNATURE` for high-s signatures. Legacy wallets
+// producing those can be accepted through `verify_signature_with_options`
+// with `OPT_ALLOW_NON_CANONICAL_ECDSA`.
+int32_t verify_polkadot_ecdsa_signature(const char *address_ptr,
+                                        const char *signature_ptr,
+                                        const char *message_ptr);
+
+// Verify a Polkadot ED25519 signature
+//
+// Takes the same arguments and returns the same codes as
+// `verify_polkadot_signature_v2`. sr25519 and ed25519 keys encode to
+// identical SS58 strings, so the address does not reveal the scheme; this
+// function always verifies as ed25519. Builds without the `sp-core`
+// feature return `ERR_SCHEME_NOT_SUPPORTED`.
+int32_t verify_polkadot_ed25519_signature(const char *address_ptr,
+                                          const char *signature_ptr,
+                                          const char *message_ptr);
+
+// Verify a Polkadot SR25519 signature
+//
+// # Arguments
+// * `address_ptr` - SS58-encoded Polkadot address (e.g., "14zNh..."), or
+//   the raw 32-byte public key as 64 hex digits, optionally 0x-prefixed
+// * `signature_ptr` - Hex-encoded signature (with or without 0x prefix)
+// * `message_ptr` - The original message that was signed
+//
+// # Returns
+// * 1 if signature is valid
+// * 0 if signature is invalid or any error occurred
+//
+// Kept for backwards compatibility, see `verify_polkadot_signature_v2`
+// for a variant that reports why verification failed.
+uint8_t verify_polkadot_signature(const char *address_ptr,
+                                  const char *signature_ptr,
+                                  const char *message_ptr);
+
+// Verify a Polkadot SR25519 signature, with or without `<Bytes>` wrapping
+//
+// Takes the same arguments as `verify_polkadot_signature`, plus:
+// * `form_out` - Receives the `MESSAGE_*` constant of the form that
+//   verified, or `MESSAGE_NONE`. May be NULL.
+//
+// polkadot-js wraps raw messages in `<Bytes>...</Bytes>` before signing,
+// which callers easily get wrong. The message is tried as given first,
+// then with the wrapper removed if it has one, or added if it does not.
+//
+// # Returns
+// The same codes as `verify_polkadot_signature_v2`.
+int32_t verify_polkadot_signature_auto(const char *address_ptr,
+                                       const char *signature_ptr,
+                                       const char *message_ptr,
+                                       int32_t *form_out);
+
+// Verify a Polkadot SR25519 signature over binary data
+//
+// # Arguments
+// * `address_ptr` - SS58-encoded address
+// * `signature_ptr` - Hex-encoded signature (with or without 0x prefix)
+// * `message_ptr` - The signed bytes, which may contain NUL bytes and
+//   need not be UTF-8. May be NULL if `message_len` is 0.
+// * `message_len` - Number of bytes at `message_ptr`
+//
+// # Returns
+// The same codes as `verify_polkadot_signature_v2`.
+int32_t verify_polkadot_signature_bytes(const char *address_ptr,
+                                        const char *signature_ptr,
+                                        const uint8_t *message_ptr,
+                                        size_t message_len);
+
+// Verify a Polkadot SR25519 signature and describe the signer
+//
+// Takes the same arguments as `verify_polkadot_signature`, plus:
+// * `out` - Filled with the verdict, decoded public key and SS58 prefix.
+//   May be NULL, in which case only the code is returned.
+//
+// # Returns
+// The same codes as `verify_polkadot_signature_v2`. When the code is
+// negative `out` is zeroed apart from `error_code`, so callers never see
+// stale data.
+int32_t verify_polkadot_signature_ex(const char *address_ptr,
+                                     const char *signature_ptr,
+                                     const char *message_ptr,
+                                     struct VerifyOutcome *out);
+
+// Verify a Polkadot SR25519 signature over the contents of a file
+//
+// # Arguments
+// * `address_ptr` - SS58-encoded address
+// * `signature_ptr` - Hex-encoded signature (with or without 0x prefix)
+// * `path_ptr` - UTF-8 path of the file
+//
+// The file is read in chunks, never whole. Like
+// `verify_polkadot_signature_bytes`, files up to 256 bytes must be signed
+// directly and longer ones as the `blake2_256` hash of their contents.
+//
+// # Returns
+// The same codes as `verify_polkadot_signature_v2`, plus
+// `ERR_FILE_NOT_FOUND`, `ERR_FILE_PERMISSION_DENIED` or `ERR_FILE_IO` when
+// the file cannot be read.
+int32_t verify_polkadot_signature_file(const char *address_ptr,
+                                       const char *signature_ptr,
+                                       const char *path_ptr);
+
+// Verify a Polkadot SR25519 signature, tolerating a scheme byte
+//
+// Like `verify_polkadot_signature_v2`, but also accepts the 65-byte
+// signatures some wallets return from `signRaw`, where the first byte is
+// the `MultiSignature` tag: 0x01 verifies the remaining 64 bytes as
+// sr25519, 0x00 as ed25519.
+//
+// # Returns
+// The same codes as `verify_polkadot_signature_v2`, plus
+// `ERR_BAD_SIGNATURE_PREFIX` for 65-byte signatures with any other first
+// byte.
+int32_t verify_polkadot_signature_lenient(const char *address_ptr,
+                                          const char *signature_ptr,
+                                          const char *message_ptr);
+
+// Verify a Polkadot SR25519 signature from a single network
+//
+// Takes the same arguments as `verify_polkadot_signature`, plus:
+// * `prefix` - The only SS58 prefix the address may use, e.g. 0 for
+//   Polkadot or 2 for Kusama
+//
+// Shorthand for `verify_polkadot_signature_with_options` with
+// `allowed_prefixes` set to `prefix` alone.
+//
+// # Returns
+// The same codes as `verify_polkadot_signature_v2`, plus
+// `ERR_WRONG_NETWORK` when the address decodes but uses another prefix,
+// or is a raw public key, which has none.
+int32_t verify_polkadot_signature_strict_network(const char *address_ptr,
+                                                 const char *signature_ptr,
+                                                 const char *message_ptr,
+                                                 uint16_t prefix);
+
+// Verify a Polkadot SR25519 signature, reporting the failure class
+//
+// Takes the same arguments as `verify_polkadot_signature`.
+//
+// # Returns
+// * `VALID` (1) if signature is valid
+// * `INVALID` (0) if the inputs parsed but the signature does not verify
+// * a negative `ERR_*` code from [`codes`] if the inputs could not be parsed
+//
+// On failure `get_last_error` describes what went wrong.
+int32_t verify_polkadot_signature_v2(const char *address_ptr,
+                                     const char *signature_ptr,
+                                     const char *message_ptr);
+
+// Verify a Polkadot SR25519 signature given as UTF-16 strings
+//
+// For hosts such as .NET that marshal strings as UTF-16. Takes the same
+// arguments as `verify_polkadot_signature`, each a NUL-terminated UTF-16
+// string. The signature is checked over the UTF-8 encoding of the
+// message, which is what polkadot-js signs.
+//
+// # Returns
+// The same codes as `verify_polkadot_signature_v2`, plus
+// `ERR_INVALID_UTF16` if an argument contains a lone surrogate.
+int32_t verify_polkadot_signature_w(const uint16_t *address_ptr,
+                                    const uint16_t *signature_ptr,
+                                    const uint16_t *message_ptr);
+
+// Verify a Polkadot SR25519 signature with per-call options
+//
+// Takes the same arguments as `verify_polkadot_signature`, plus:
+// * `options` - Behavior tweaks, see `VerifyOptions`. NULL uses the
+//   defaults, which match `verify_polkadot_signature_v2`.
+//
+// # Returns
+// The same codes as `verify_polkadot_signature_v2`, plus
+// `ERR_WRONG_NETWORK` when the address prefix is not allowed.
+int32_t verify_polkadot_signature_with_options(const char *address_ptr,
+                                               const char *signature_ptr,
+                                               const char *message_ptr,
+                                               const struct VerifyOptions *options);
+
+// Verify a signature of a given scheme with per-call options
+//
+// Takes the same arguments as `verify_polkadot_signature_with_options`,
+// plus:
+// * `scheme` - `SCHEME_SR25519`, `SCHEME_ED25519` or `SCHEME_ECDSA`
+//
+// # Returns
+// The same codes as `verify_polkadot_signature_with_options`, plus
+// `ERR_UNKNOWN_SCHEME` for any other scheme, including
+// `SCHEME_ETHEREUM` whose addresses are not SS58.
+int32_t verify_signature_with_options(const char *address_ptr,
+                                      const char *signature_ptr,
+                                      const char *message_ptr,
+                                      int32_t scheme,
+                                      const struct VerifyOptions *options);
+
+// Verify a signature over a transaction's signing payload
+//
+// For when only the fields a wallet showed before signing are at hand,
+// not the extrinsic. The payload is assembled as `SignedPayload` encodes
+// it for the relay chains' signed extensions: the call, the era, the
+// compact nonce and tip, the spec and transaction versions, the genesis
+// hash and the block hash, then `CheckMetadataHash` if the runtime has
+// it: its mode byte after the tip and the optional metadata hash after the
+// block hash. It is verified as `blake2_256` of itself if over 256 bytes.
+//
+// # Arguments
+// * `address_ptr` - SS58-encoded address of the signer
+// * `signature_ptr` - Hex-encoded sr25519 signature, with or without 0x
+//   prefix. A `MultiSignature` as returned by `signer.signPayload`,
+//   tagged 0x01 for sr25519, 0x00 for ed25519 or 0x02 for ECDSA, is
+//   accepted too.
+// * `call_ptr` - Hex-encoded call, without a length prefix
+// * `era_ptr` - Hex-encoded `Era`: `00` for an immortal era, or its two
+//   bytes
+// * `nonce` - The account nonce
+// * `tip_ptr` - The tip as a decimal integer, since it can exceed 64 bits
+// * `spec_version`, `transaction_version` - The runtime versions
+// * `genesis_hash_ptr` - Hex-encoded genesis hash of the chain
+// * `block_hash_ptr` - Hex-encoded hash of the block a mortal era starts
+//   at. May be NULL for an immortal era.
+// * `metadata_hash_mode` - `METADATA_HASH_ABSENT`,
+//   `METADATA_HASH_DISABLED` or `METADATA_HASH_ENABLED`
+// * `metadata_hash_ptr` - Hex-encoded metadata hash, needed for
+//   `METADATA_HASH_ENABLED`. May be NULL otherwise.
+//
+// # Returns
+// The same codes as `verify_polkadot_signature_v2`, plus
+// * `ERR_BAD_ERA` if the era is malformed
+// * `ERR_INVALID_OPTIONS` for `METADATA_HASH_DETECT`, an unknown mode or
+//   a tip that is not a decimal `u128`
+// * `ERR_BAD_MULTISIGNATURE` for a 0x02-tagged signature when ECDSA
+//   support is not compiled in
+// * `ERR_NULL_POINTER` if the era is mortal and `block_hash_ptr` is NULL,
+//   or `METADATA_HASH_ENABLED` is given without a metadata hash
+// * `ERR_BAD_HEX`, or `ERR_BAD_HEX_LENGTH` for a hash that is not
+//   32 bytes
+int32_t verify_signing_payload(const char *address_ptr,
+                               const char *signature_ptr,
+                               const char *call_ptr,
+                               const char *era_ptr,
+                               uint64_t nonce,
+                               const char *tip_ptr,
+                               uint32_t spec_version,
+                               uint32_t transaction_version,
+                               const char *genesis_hash_ptr,
+                               const char *block_hash_ptr,
+                               int32_t metadata_hash_mode,
+                               const char *metadata_hash_ptr);
+
+// Verify the result of a polkadot-js `signRaw` call
+//
+// # Arguments
+// * `address_ptr` - SS58-encoded address
+// * `signraw_json_ptr` - The object `signer.signRaw` resolved with, as
+//   JSON, e.g. `{"id":1,"signature":"0x..."}`
+// * `payload_ptr` - The `data` passed to `signRaw`, which the extension
+//   wrapped in `<Bytes>...</Bytes>` before signing unless it already was
+//
+// Payloads over 256 bytes are verified as their `blake2_256` hash, as the
+// extension signs them.
+//
+// # Returns
+// The same codes as `verify_polkadot_signature_v2`, plus `ERR_BAD_JSON`
+// if the result is not JSON or `signature` is missing or not a string.
+int32_t verify_signraw_result(const char *address_ptr,
+                              const char *signraw_json_ptr,
+                              const char *payload_ptr);
+
+// Verify a Sign-In with Substrate message
+//
+// # Arguments
+// * `message_ptr` - The message text, with LF or CRLF line endings
+// * `signature_ptr` - Hex-encoded sr25519 signature by the address named
+//   in the message, over the message wrapped in `<Bytes>...</Bytes>`
+// * `expected` - What the message must match, see `SiwsExpectations`
+//
+// # Returns
+// * `VALID` (1) if the signature verifies and every expectation holds
+// * `INVALID` (0) if the signature does not verify
+// * `ERR_SIWS_MALFORMED` if the message cannot be parsed
+// * `ERR_SIWS_ADDRESS_MISMATCH`, `ERR_SIWS_DOMAIN_MISMATCH` or
+//   `ERR_SIWS_NONCE_MISMATCH` if a field differs from `expected`
+// * `ERR_NOT_YET_VALID` if the message was issued, or only becomes valid,
+//   after `now_unix`
+// * `ERR_EXPIRED` if its expiration time is at or before `now_unix`
+// * the parse errors of `verify_polkadot_signature_v2`
+int32_t verify_siws(const char *message_ptr,
+                    const char *signature_ptr,
+                    const struct SiwsExpectations *expected);
+
+// Verify the signature over everything fed so far and free the handle
+//
+// # Returns
+// The same codes as `verify_polkadot_signature_v2`, plus
+// `ERR_INVALID_HANDLE` for NULL, unknown or already finalized handles.
+int32_t verify_stream_finalize(struct VerifyStream *handle);
+
+// Free a handle without verifying
+//
+// For abandoning a stream, for example after an I/O error. NULL, unknown
+// and already finalized handles are ignored.
+void verify_stream_free(struct VerifyStream *handle);
+
+// Start verifying an sr25519 signature over a message fed in chunks
+//
+// # Arguments
+// * `address_ptr` - SS58-encoded address
+// * `signature_ptr` - Hex-encoded signature (with or without 0x prefix)
+// * `convention` - What the signer signed:
+//   * `STREAM_PREHASH_LONG` - like polkadot-js and
+//     `verify_polkadot_signature_bytes`: messages up to 256 bytes directly,
+//     longer ones as their `blake2_256` hash
+//   * `STREAM_PREHASH_ALWAYS` - the `blake2_256` hash of any message
+//
+// # Returns
+// A handle for `verify_stream_update` and `verify_stream_finalize`, or
+// NULL if an argument is invalid; `get_last_error` says which. Every
+// handle must be passed to exactly one of `verify_stream_finalize` or
+// `verify_stream_free`.
+struct VerifyStream *verify_stream_new(const char *address_ptr,
+                                       const char *signature_ptr,
+                                       int32_t convention);
+
+// Feed the next chunk of the message
+//
+// # Arguments
+// * `handle` - From `verify_stream_new`
+// * `chunk_ptr` - The next `chunk_len` bytes of the message. May be NULL
+//   if `chunk_len` is 0.
+//
+// # Returns
+// * 0 once the chunk is consumed
+// * `ERR_INVALID_HANDLE` for NULL, unknown or finalized handles
+// * `ERR_NULL_POINTER` for a NULL chunk with a nonzero length
+int32_t verify_stream_update(struct VerifyStream *handle,
+                             const uint8_t *chunk_ptr,
+                             size_t chunk_len);
+
+// Verify an sr25519 VRF proof
+//
+// # Arguments
+// * `address_ptr` - SS58-encoded address of the prover
+// * `vrf_output_ptr` - Hex-encoded 32-byte VRF pre-output
+// * `vrf_proof_ptr` - Hex-encoded 64-byte VRF proof
+// * `input_ptr` - The VRF input message
+// * `label_ptr` - Signing context label of the transcript, which differs
+//   per application
+// * `randomness_out` - Receives 32 bytes of randomness derived from the
+//   VRF output with the label as context, or zeroes if the proof does not
+//   verify. May be NULL.
+//
+// # Returns
+// The same codes as `verify_polkadot_signature_v2`, plus `ERR_BAD_HEX_LENGTH`
+// for an output or proof of the wrong length.
+int32_t verify_vrf(const char *address_ptr,
+                   const char *vrf_output_ptr,
+                   const char *vrf_proof_ptr,
+                   const char *input_ptr,
+                   const char *label_ptr,
+                   uint8_t *randomness_out);
+
+// Verify a Polkadot SR25519 signature made by a soft-derived child key
+//
+// # Arguments
+// * `root_address_ptr` - SS58-encoded address or hex public key of the
+//   registered root
+// * `soft_path_ptr` - Soft junctions leading from the root to the signer,
+//   such as `/payments/0`. The empty path verifies against the root
+//   exactly like `verify_polkadot_signature_v2`.
+// * `signature_ptr` - Hex-encoded signature, with or without 0x prefix
+// * `message_ptr` - The original message that was signed
+//
+// # Returns
+// The same codes as `verify_polkadot_signature_v2`, plus
+// `ERR_HARD_DERIVATION` or `ERR_BAD_DERIVATION_PATH` for paths that
+// cannot be applied to a public key.
+int32_t verify_with_derivation(const char *root_address_ptr,
+                               const char *soft_path_ptr,
+                               const char *signature_ptr,
+                               const char *message_ptr);
+
+// Verify a Polkadot SR25519 signature over a challenge that must be fresh
+//
+// Takes the same first three arguments as `verify_polkadot_signature`,
+// plus:
+// * `issued_at_format_ptr` - The challenge with `{issued_at}` in place of
+//   its decimal Unix timestamp and `{*}` in place of any other text, e.g.
+//   `login:{issued_at}:{*}`
+// * `max_age_secs` - How long after issue the challenge stays valid
+// * `now_unix` - Current time in seconds since the Unix epoch
+//
+// # Returns
+// The same codes as `verify_polkadot_signature_v2`, plus for validly
+// signed challenges:
+// * `ERR_EXPIRED` when `now_unix - issued_at > max_age_secs`
+// * `ERR_NOT_YET_VALID` when the challenge is dated more than
+//   `MAX_CLOCK_SKEW_SECS` after `now_unix`
+// * `ERR_TIMESTAMP_NOT_FOUND` when the message does not match the format
+int32_t verify_with_expiry(const char *address_ptr,
+                           const char *signature_ptr,
+                           const char *message_ptr,
+                           const char *issued_at_format_ptr,
+                           uint64_t max_age_secs,
+                           int64_t now_unix);
+
+// Verify a Polkadot SR25519 signature and reject replays of its nonce
+//
+// Takes the same arguments as `verify_polkadot_signature`, plus:
+// * `cache` - From `nonce_cache_new`
+// * `nonce_ptr` - The nonce of the challenge. It must be part of the
+//   signed message, or a replay could simply claim a fresh nonce.
+//
+// A valid signature records the (address, nonce) pair; checking for and
+// recording the pair is atomic, so of two concurrent calls with the same
+// pair only one succeeds.
+//
+// # Returns
+// The same codes as `verify_polkadot_signature_v2`, plus
+// `ERR_REPLAYED` if the pair was recorded within the TTL and
+// `ERR_INVALID_HANDLE` for NULL, unknown or freed caches.
+int32_t verify_with_nonce(struct NonceCache *cache,
+                          const char *address_ptr,
+                          const char *signature_ptr,
+                          const char *message_ptr,
+                          const char *nonce_ptr);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* POLKADOT_SIGNATURE_VERIFIER_H */
+
+#if defined(_WIN32) && (defined(_M_IX86) || defined(__i386__))
+#define PSV_SYSTEM __stdcall
+#else
+#define PSV_SYSTEM
+#endif
+
+#ifdef __cplusplus
+extern "C" {
+#endif  // __cplusplus
+
+size_t PSV_SYSTEM get_last_error_w(uint16_t *buf, size_t buf_len);
+
+int32_t PSV_SYSTEM verify_into_buffer(const char *address_ptr,
+                                      const char *signature_ptr,
+                                      const uint8_t *message_ptr,
+                                      size_t message_len,
+                                      uint16_t *err_buf,
+                                      size_t err_buf_len);
+
+int32_t PSV_SYSTEM verify_into_buffer_w(const uint16_t *address_ptr,
+                                        const uint16_t *signature_ptr,
+                                        const uint8_t *message_ptr,
+                                        size_t message_len,
+                                        uint16_t *err_buf,
+                                        size_t err_buf_len);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
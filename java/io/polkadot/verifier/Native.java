@@ -0,0 +1,27 @@
+package io.polkadot.verifier;
+
+/**
+ * The native methods of the library built with the {@code jni} feature.
+ *
+ * <p>Both return {@link #VALID}, {@link #INVALID} or a negative {@code ERR_*}
+ * code for a malformed address or signature, as the C functions do. A null
+ * argument throws {@link NullPointerException}, and a string with a lone
+ * surrogate or batch arrays of different lengths throw
+ * {@link IllegalArgumentException}.
+ */
+public final class Native {
+  public static final int VALID = 1;
+  public static final int INVALID = 0;
+
+  static {
+    System.loadLibrary("polkadot_sig_verifier");
+  }
+
+  private Native() {}
+
+  /** Verify a Polkadot SR25519 signature over {@code message}, without {@code <Bytes>} wrapping. */
+  public static native int verifySignature(String address, String signature, byte[] message);
+
+  /** Verify {@code addresses[i]}, {@code signatures[i]} and {@code messages[i]} for each item, in order. */
+  public static native int[] verifyBatch(String[] addresses, String[] signatures, byte[][] messages);
+}
@@ -0,0 +1,37 @@
+import assert from 'node:assert/strict'
+import { createRequire } from 'node:module'
+import test from 'node:test'
+
+const { verifySignature, verifySignatureAsync, verifyBatch } = createRequire(import.meta.url)('../index.js')
+
+const ADDRESS = '5HbqogsYaK54pN3QAgSi4t3Asqditt1X4P7cSBuDt2hPF2BR'
+const SIGNATURE = '0xf8ce1b80e76bf48a30610d0d3c2c9c8dbfdcb28a9aa8f56ac038d520ff17445ff3ce94809f8479a1224ef0d823d035022a9db2e72bd6c0d2f0244f766dec908b'
+const MESSAGE = Buffer.from('<Bytes>c15335d817e3d3d912d30f5a18a30c30162abc364cd0876d3a94af568d3c8c14</Bytes>')
+
+test('verifySignature', () => {
+  assert.equal(verifySignature(ADDRESS, SIGNATURE, MESSAGE), true)
+  assert.equal(verifySignature(ADDRESS, SIGNATURE.slice(2), MESSAGE), true)
+  assert.equal(verifySignature(ADDRESS, SIGNATURE, Buffer.from('tampered')), false)
+  assert.throws(() => verifySignature(ADDRESS, SIGNATURE.slice(0, -2), MESSAGE), {
+    code: 'ERR_BAD_SIGNATURE_LENGTH',
+    message: 'signature is 63 bytes, expected 64',
+  })
+})
+
+test('verifySignatureAsync', async () => {
+  assert.equal(await verifySignatureAsync(ADDRESS, SIGNATURE, MESSAGE), true)
+  assert.equal(await verifySignatureAsync(ADDRESS, SIGNATURE, Buffer.from('tampered')), false)
+  const corrupted = ADDRESS.replace('hPF2BR', 'hPF2BS')
+  await assert.rejects(verifySignatureAsync(corrupted, SIGNATURE, MESSAGE), { code: 'ERR_SS58_BAD_CHECKSUM' })
+})
+
+test('verifyBatch', async () => {
+  const codes = await verifyBatch([
+    { address: ADDRESS, signature: SIGNATURE, message: MESSAGE },
+    { address: ADDRESS, signature: SIGNATURE, message: Buffer.from('tampered') },
+    { address: ADDRESS, signature: '0x00', message: MESSAGE },
+  ])
+  assert.ok(codes instanceof Int32Array)
+  assert.deepEqual(Array.from(codes), [1, 0, -5])
+  assert.deepEqual(Array.from(await verifyBatch([])), [])
+})
@@ -0,0 +1,32 @@
+/* tslint:disable */
+/* eslint-disable */
+
+/* auto-generated by NAPI-RS */
+
+/**
+ * Verify a Polkadot SR25519 signature.
+ *
+ * The message is verified as the given bytes, without `<Bytes>` wrapping.
+ * Returns whether the signature matches, and throws if the address or
+ * signature is malformed.
+ */
+export function verifySignature(address: string, signature: string, message: Buffer): boolean
+/**
+ * Verify a Polkadot SR25519 signature on the libuv threadpool, as
+ * `verifySignature` does.
+ */
+export function verifySignatureAsync(address: string, signature: string, message: Buffer): Promise<boolean>
+/** One signature for `verifyBatch`. */
+export interface BatchItem {
+  address: string
+  signature: string
+  message: Buffer
+}
+/**
+ * Verify many SR25519 signatures on the libuv threadpool.
+ *
+ * Resolves to one code per item, in order, as the C `verify_batch` returns
+ * them: `1` if the signature is valid, `0` if it does not match, or a
+ * negative `ERR_*` code if the item is malformed.
+ */
+export function verifyBatch(items: Array<BatchItem>): Promise<Int32Array>
@@ -0,0 +1,111 @@
+//! The `polkadot-sig-verifier` Node.js addon, built with napi-rs:
+//!
+//! ```text
+//! cd node && npm install && npm run build && npm test
+//! ```
+//!
+//! A wrapper over the crate's Rust `api`. Errors are thrown, or reject the
+//! promise, as JS errors whose `code` is the name of the library's `ERR_*`
+//! code, such as `"ERR_BAD_SIGNATURE_LENGTH"`.
+
+use napi::bindgen_prelude::*;
+use napi::{Env, JsBoolean, Task};
+use napi_derive::napi;
+use polkadot_sig_verifier::{api, error_code_name, INVALID, VALID};
+
+/// Whether the signature matched, or the name of the error's code and its
+/// message.
+type Verified = std::result::Result<bool, (&'static str, String)>;
+
+fn verified(result: std::result::Result<(), api::VerifyError>) -> Verified {
+  match result {
+    Ok(()) => Ok(true),
+    Err(api::VerifyError::SignatureMismatch) => Ok(false),
+    Err(error) => Err((error_code_name(i32::from(&error)).unwrap_or("ERR_UNKNOWN"), error.to_string())),
+  }
+}
+
+/// Verify a Polkadot SR25519 signature.
+///
+/// The message is verified as the given bytes, without `<Bytes>` wrapping.
+/// Returns whether the signature matches, and throws if the address or
+/// signature is malformed.
+#[napi]
+pub fn verify_signature(address: String, signature: String, message: Buffer) -> Result<bool, &'static str> {
+  verified(api::verify(&address, &signature, &message)).map_err(|(code, message)| Error::new(code, message))
+}
+
+pub struct VerifyTask {
+  address: String,
+  signature: String,
+  message: Vec<u8>,
+}
+
+impl Task for VerifyTask {
+  type Output = Verified;
+  type JsValue = JsBoolean;
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    Ok(verified(api::verify(&self.address, &self.signature, &self.message)))
+  }
+
+  fn resolve(&mut self, env: Env, output: Self::Output) -> Result<Self::JsValue> {
+    match output {
+      Ok(valid) => env.get_boolean(valid),
+      Err((code, message)) => {
+        // Reject with the error object itself, so `code` is the name
+        let mut error = env.create_error(Error::from_reason(message))?;
+        error.set_named_property("code", env.create_string(code)?)?;
+        Err(Error::from(error.into_unknown()))
+      }
+    }
+  }
+}
+
+/// Verify a Polkadot SR25519 signature on the libuv threadpool, as
+/// `verifySignature` does.
+#[napi(ts_return_type = "Promise<boolean>")]
+pub fn verify_signature_async(address: String, signature: String, message: Buffer) -> AsyncTask<VerifyTask> {
+  AsyncTask::new(VerifyTask { address, signature, message: message.to_vec() })
+}
+
+/// One signature for `verifyBatch`.
+#[napi(object)]
+pub struct BatchItem {
+  pub address: String,
+  pub signature: String,
+  pub message: Buffer,
+}
+
+pub struct BatchTask {
+  items: Vec<(String, String, Vec<u8>)>,
+}
+
+impl Task for BatchTask {
+  type Output = Vec<i32>;
+  type JsValue = Int32Array;
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    let codes = self.items.iter().map(|(address, signature, message)| match api::verify(address, signature, message) {
+      Ok(()) => VALID,
+      Err(api::VerifyError::SignatureMismatch) => INVALID,
+      Err(error) => i32::from(&error),
+    });
+    Ok(codes.collect())
+  }
+
+  fn resolve(&mut self, _: Env, output: Self::Output) -> Result<Self::JsValue> {
+    Ok(Int32Array::new(output))
+  }
+}
+
+/// Verify many SR25519 signatures on the libuv threadpool.
+///
+/// Resolves to one code per item, in order, as the C `verify_batch` returns
+/// them: `1` if the signature is valid, `0` if it does not match, or a
+/// negative `ERR_*` code if the item is malformed.
+#[napi(ts_return_type = "Promise<Int32Array>")]
+pub fn verify_batch(items: Vec<BatchItem>) -> AsyncTask<BatchTask> {
+  let items = items.into_iter().map(|item| (item.address, item.signature, item.message.to_vec())).collect();
+  AsyncTask::new(BatchTask { items })
+}
@@ -0,0 +1,68 @@
+//! The `polkadot_sig_verifier` Python module, built with maturin:
+//!
+//! ```text
+//! cd python && maturin develop && pytest
+//! ```
+//!
+//! A PyO3 wrapper over the crate's Rust `api`, so Python callers get
+//! checked argument types instead of declaring the C ABI by hand with
+//! ctypes, as `polkadot_verifier.py` does.
+
+use polkadot_sig_verifier::api;
+use pyo3::create_exception;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+create_exception!(
+  polkadot_sig_verifier,
+  VerifyError,
+  PyValueError,
+  "Inputs that could not be verified. `code` is one of the library's negative `ERR_*` codes."
+);
+
+/// Return whether the signature matched, or raise a `VerifyError` with the
+/// code of `error` and its message after `context`.
+fn accepted(py: Python<'_>, result: Result<(), api::VerifyError>, context: &str) -> PyResult<bool> {
+  let error = match result {
+    Ok(()) => return Ok(true),
+    Err(api::VerifyError::SignatureMismatch) => return Ok(false),
+    Err(error) => error,
+  };
+  let exception = VerifyError::new_err(format!("{context}{error}"));
+  exception.value_bound(py).setattr("code", i32::from(&error))?;
+  Err(exception)
+}
+
+/// Verify a Polkadot SR25519 signature.
+///
+/// The message is verified as the given bytes, without `<Bytes>` wrapping.
+/// Returns whether the signature matches, and raises `VerifyError` if the
+/// address or signature is malformed.
+#[pyfunction]
+fn verify(py: Python<'_>, address: &str, signature: &str, message: &[u8]) -> PyResult<bool> {
+  accepted(py, api::verify(address, signature, message), "")
+}
+
+/// Verify many SR25519 signatures, each an `(address, signature, message)`
+/// tuple.
+///
+/// The GIL is released while verifying, so other Python threads keep
+/// running. Returns whether each signature matches, in order, and raises
+/// `VerifyError` naming the first malformed item.
+#[pyfunction]
+fn verify_batch(py: Python<'_>, items: Vec<(String, String, Vec<u8>)>) -> PyResult<Vec<bool>> {
+  let results: Vec<_> = py.allow_threads(|| {
+    items.iter().map(|(address, signature, message)| api::verify(address, signature, message)).collect()
+  });
+  results.into_iter().enumerate().map(|(index, result)| accepted(py, result, &format!("item {index}: "))).collect()
+}
+
+#[pymodule]
+#[pyo3(name = "polkadot_sig_verifier")]
+fn polkadot_sig_verifier_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+  m.add("VerifyError", m.py().get_type_bound::<VerifyError>())?;
+  m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+  m.add_function(wrap_pyfunction!(verify, m)?)?;
+  m.add_function(wrap_pyfunction!(verify_batch, m)?)?;
+  Ok(())
+}
@@ -0,0 +1,69 @@
+import threading
+import time
+
+import pytest
+
+from polkadot_sig_verifier import VerifyError, verify, verify_batch
+
+ADDRESS = "5HbqogsYaK54pN3QAgSi4t3Asqditt1X4P7cSBuDt2hPF2BR"
+SIGNATURE = "0xf8ce1b80e76bf48a30610d0d3c2c9c8dbfdcb28a9aa8f56ac038d520ff17445ff3ce94809f8479a1224ef0d823d035022a9db2e72bd6c0d2f0244f766dec908b"
+MESSAGE = b"<Bytes>c15335d817e3d3d912d30f5a18a30c30162abc364cd0876d3a94af568d3c8c14</Bytes>"
+
+ERR_BAD_SIGNATURE_LENGTH = -5
+ERR_SS58_BAD_CHECKSUM = -10
+
+
+def test_verify():
+    assert verify(ADDRESS, SIGNATURE, MESSAGE)
+    assert verify(ADDRESS, SIGNATURE[2:], MESSAGE)
+    assert not verify(ADDRESS, SIGNATURE, b"tampered")
+
+
+def test_verify_error_code():
+    with pytest.raises(VerifyError) as raised:
+        verify(ADDRESS, SIGNATURE[:-2], MESSAGE)
+    assert raised.value.code == ERR_BAD_SIGNATURE_LENGTH
+    assert str(raised.value) == "signature is 63 bytes, expected 64"
+
+    with pytest.raises(VerifyError) as raised:
+        verify(ADDRESS.replace("hPF2BR", "hPF2BS"), SIGNATURE, MESSAGE)
+    assert raised.value.code == ERR_SS58_BAD_CHECKSUM
+    assert isinstance(raised.value, ValueError)
+
+
+def test_verify_batch():
+    items = [(ADDRESS, SIGNATURE, MESSAGE), (ADDRESS, SIGNATURE, b"tampered")]
+    assert verify_batch(items) == [True, False]
+    assert verify_batch([]) == []
+
+    with pytest.raises(VerifyError, match="^item 2: ") as raised:
+        verify_batch(items + [(ADDRESS, "0x00", MESSAGE)])
+    assert raised.value.code == ERR_BAD_SIGNATURE_LENGTH
+
+
+def test_verify_batch_releases_the_gil():
+    items = [(ADDRESS, SIGNATURE, MESSAGE)] * 2000
+    ticks = 0
+    done = threading.Event()
+
+    def tick():
+        nonlocal ticks
+        while not done.is_set():
+            ticks += 1
+            time.sleep(0)
+
+    ticker = threading.Thread(target=tick)
+    ticker.start()
+    try:
+        # Holding the GIL for the whole batch would leave the ticker stalled
+        before = ticks
+        started = time.monotonic()
+        results = verify_batch(items)
+        elapsed = time.monotonic() - started
+        during = ticks - before
+    finally:
+        done.set()
+        ticker.join()
+
+    assert all(results)
+    assert during > 0, f"no ticks in {elapsed:.3f}s of verification"
@@ -0,0 +1,157 @@
+//! `#[repr(C)]` types exchanged with callers across the FFI boundary.
+
+use crate::ADDRESS_KIND_NONE;
+use std::ffi::c_char;
+
+/// ABI revision of the exported functions and the structs below.
+///
+/// Bumped whenever an exported signature changes or a struct layout changes
+/// in a way older callers cannot handle. Appending fields to
+/// `VerifyOptions` does not need a bump, since it carries its own size.
+pub const ABI_VERSION: u32 = 2;
+
+/// Detailed result of `verify_polkadot_signature_ex`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VerifyOutcome {
+  /// 1 if the signature is valid, 0 otherwise.
+  pub valid: u8,
+  /// The same code the function returned.
+  pub error_code: i32,
+  /// Payload decoded from the address, zeroed if decoding failed. This is
+  /// the public key for sr25519 and ed25519 and the account id, the
+  /// blake2-256 hash of the public key, for ECDSA.
+  pub public_key: [u8; 32],
+  /// SS58 network prefix of the address, zeroed if decoding failed or the
+  /// address was a raw public key.
+  pub ss58_prefix: u16,
+}
+
+/// Detailed result of `validate_ss58_address`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AddressInfo {
+  /// SS58 network prefix of the address.
+  pub ss58_prefix: u16,
+  /// Length of the payload: 32 or 33 bytes.
+  pub payload_len: u8,
+  /// `ADDRESS_KIND_*` guess of what the payload holds, from its length.
+  pub kind: i32,
+  /// The payload, zero-padded after `payload_len` bytes.
+  pub payload: [u8; 33],
+}
+
+impl Default for AddressInfo {
+  fn default() -> Self {
+    AddressInfo { ss58_prefix: 0, payload_len: 0, kind: ADDRESS_KIND_NONE, payload: [0; 33] }
+  }
+}
+
+/// Options for `verify_polkadot_signature_with_options`.
+///
+/// Zero means "default" for every field, so a zeroed struct with `size`
+/// set behaves like `verify_polkadot_signature_v2`. New fields are only
+/// ever appended; the library reads no further than `size` bytes.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct VerifyOptions {
+  /// `sizeof(VerifyOptions)` as compiled by the caller.
+  pub size: u32,
+  /// Bitwise OR of `OPT_*` flags.
+  pub flags: u32,
+  /// SS58 prefixes the address may use. NULL or empty accepts any prefix.
+  pub allowed_prefixes: *const u16,
+  /// Number of entries in `allowed_prefixes`.
+  pub allowed_prefixes_len: usize,
+  /// Nonzero verifies exactly the bytes given. Inputs that the default
+  /// lenient mode would normalize, such as a 0x prefix on hex, fail with
+  /// `ERR_NOT_STRICT`, and flags that normalize the message or signature
+  /// (`OPT_WRAP_BYTES`, `OPT_STRIP_SCHEME_PREFIX`,
+  /// `OPT_ALLOW_NON_CANONICAL_ECDSA`) fail with `ERR_INVALID_OPTIONS`.
+  pub strict: u8,
+}
+
+impl Default for VerifyOptions {
+  fn default() -> Self {
+    VerifyOptions {
+      size: std::mem::size_of::<VerifyOptions>() as u32,
+      flags: 0,
+      allowed_prefixes: std::ptr::null(),
+      allowed_prefixes_len: 0,
+      strict: 0,
+    }
+  }
+}
+
+/// What `verify_siws` checks a Sign-In with Substrate message against.
+///
+/// Like `VerifyOptions`, it carries its own size and new fields are only
+/// ever appended.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct SiwsExpectations {
+  /// `sizeof(SiwsExpectations)` as compiled by the caller.
+  pub size: u32,
+  /// Current time in seconds since the Unix epoch, checked against the
+  /// message's issued-at, not-before and expiration times.
+  pub now_unix: i64,
+  /// Domain the message must be for. NULL accepts any domain.
+  pub domain: *const c_char,
+  /// Nonce the message must carry. NULL accepts any nonce.
+  pub nonce: *const c_char,
+  /// SS58 address the message must be for, compared by public key so any
+  /// network prefix matches. NULL accepts any address.
+  pub address: *const c_char,
+}
+
+impl Default for SiwsExpectations {
+  fn default() -> Self {
+    SiwsExpectations {
+      size: std::mem::size_of::<SiwsExpectations>() as u32,
+      now_unix: 0,
+      domain: std::ptr::null(),
+      nonce: std::ptr::null(),
+      address: std::ptr::null(),
+    }
+  }
+}
+
+/// Counters filled in by `get_stats`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+  /// Signatures checked, counting each item of a batch.
+  pub total_verifications: u64,
+  /// Checks that did not return `VALID`, whether the signature did not
+  /// match or the inputs did not parse.
+  pub failures: u64,
+  /// Wall-clock time spent in verifying calls, in nanoseconds. A batch
+  /// adds its time once, however many threads it ran on.
+  pub total_nanos: u64,
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::mem::{align_of, size_of};
+
+  // Layouts promised by ABI_VERSION 2. If one of these fails, bump
+  // ABI_VERSION and update the expected values.
+  #[test]
+  fn test_abi_layouts() {
+    assert_eq!(ABI_VERSION, 2);
+
+    assert_eq!(size_of::<VerifyOutcome>(), 44);
+    assert_eq!(align_of::<VerifyOutcome>(), 4);
+
+    assert_eq!(size_of::<AddressInfo>(), 44);
+    assert_eq!(align_of::<AddressInfo>(), 4);
+
+    // `strict` is padded to the alignment of usize
+    assert_eq!(size_of::<VerifyOptions>(), 8 + 3 * size_of::<usize>());
+    assert_eq!(align_of::<VerifyOptions>(), align_of::<usize>());
+
+    assert_eq!(size_of::<SiwsExpectations>(), 16 + 3 * size_of::<usize>());
+    assert_eq!(align_of::<SiwsExpectations>(), align_of::<i64>());
+  }
+}
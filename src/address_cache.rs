@@ -0,0 +1,172 @@
+//! An optional LRU cache of parsed addresses, for callers verifying against
+//! the same addresses over and over through the stateless functions.
+//!
+//! The cache is off until `cache_init` turns it on, so by default
+//! verification allocates no more than it always did. Only addresses that
+//! parse are cached, keyed by their exact text, so a mistyped address is
+//! parsed, and rejected, every time.
+
+use crate::error::Error;
+#[cfg(feature = "ffi")]
+use crate::log::{self, LOG_DEBUG};
+use crate::parse::{parse_address, ParsedAddress};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+
+/// Parsed addresses, least recently used first out.
+struct Lru {
+  capacity: usize,
+  /// Counts lookups and inserts, to order entries by last use.
+  clock: u64,
+  entries: HashMap<String, (ParsedAddress, u64)>,
+  /// The addresses in `entries` by the clock value of their last use.
+  order: BTreeMap<u64, String>,
+}
+
+impl Lru {
+  #[cfg(feature = "ffi")]
+  fn new(capacity: usize) -> Lru {
+    Lru { capacity, clock: 0, entries: HashMap::new(), order: BTreeMap::new() }
+  }
+
+  fn get(&mut self, address: &str) -> Option<ParsedAddress> {
+    let (parsed, last_used) = self.entries.get_mut(address)?;
+    self.clock += 1;
+    let key = self.order.remove(last_used).expect("every entry is in the order");
+    *last_used = self.clock;
+    self.order.insert(self.clock, key);
+    Some(*parsed)
+  }
+
+  fn insert(&mut self, address: &str, parsed: ParsedAddress) {
+    // Another thread may have parsed the same address meanwhile
+    if self.entries.contains_key(address) {
+      return;
+    }
+    while self.entries.len() >= self.capacity {
+      let Some((_, oldest)) = self.order.pop_first() else { break };
+      self.entries.remove(&oldest);
+    }
+    self.clock += 1;
+    self.entries.insert(address.to_owned(), (parsed, self.clock));
+    self.order.insert(self.clock, address.to_owned());
+  }
+}
+
+/// Whether `cache_init` turned the cache on, checked before locking so a
+/// disabled cache costs one atomic load.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+#[cfg(test)]
+thread_local! {
+  // Tests run in parallel and some assert on the parse logging, so under
+  // test the cache only serves threads that opt in.
+  static SERVES_THIS_THREAD: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+}
+
+fn enabled() -> bool {
+  #[cfg(test)]
+  if !SERVES_THIS_THREAD.with(|serves| serves.get()) {
+    return false;
+  }
+  ENABLED.load(Ordering::Acquire)
+}
+
+fn cache() -> MutexGuard<'static, Option<Lru>> {
+  static CACHE: OnceLock<Mutex<Option<Lru>>> = OnceLock::new();
+  CACHE.get_or_init(Default::default).lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// `parse_address`, through the cache if it is on.
+pub(crate) fn parse_address_cached(address: &str) -> Result<ParsedAddress, Error> {
+  if !enabled() {
+    return parse_address(address);
+  }
+  if let Some(parsed) = cache().as_mut().and_then(|lru| lru.get(address)) {
+    return Ok(parsed);
+  }
+  // Parse without holding the lock, so other threads can use the cache
+  let parsed = parse_address(address)?;
+  if let Some(lru) = cache().as_mut() {
+    lru.insert(address, parsed);
+  }
+  Ok(parsed)
+}
+
+/// Turn the cache on with room for `capacity` addresses, or off for 0,
+/// forgetting any addresses already cached.
+#[cfg(feature = "ffi")]
+pub(crate) fn init(capacity: usize) {
+  let mut cache = cache();
+  *cache = (capacity > 0).then(|| Lru::new(capacity));
+  ENABLED.store(capacity > 0, Ordering::Release);
+  log::log(LOG_DEBUG, || format!("address cache capacity set to {capacity}"));
+}
+
+/// Forget every cached address, keeping the cache on if it was.
+#[cfg(feature = "ffi")]
+pub(crate) fn clear() {
+  if let Some(lru) = cache().as_mut() {
+    *lru = Lru::new(lru.capacity);
+  }
+}
+
+#[cfg(all(test, feature = "ffi"))]
+mod tests {
+  use super::*;
+  use crate::{api, ERR_SS58_BAD_CHECKSUM};
+  use sp_core::crypto::Ss58Codec;
+  use sp_core::{sr25519, Pair};
+
+  fn parsed(byte: u8) -> ParsedAddress {
+    ParsedAddress { account_id: [byte; 32], prefix: Some(42), kind: crate::ADDRESS_KIND_ACCOUNT_ID }
+  }
+
+  #[test]
+  fn test_least_recently_used_goes_first() {
+    let mut lru = Lru::new(2);
+    lru.insert("a", parsed(1));
+    lru.insert("b", parsed(2));
+    assert_eq!(lru.get("a"), Some(parsed(1)));
+    lru.insert("c", parsed(3));
+    assert_eq!(lru.get("b"), None);
+    assert_eq!(lru.get("a"), Some(parsed(1)));
+    assert_eq!(lru.get("c"), Some(parsed(3)));
+    assert_eq!((lru.entries.len(), lru.order.len()), (2, 2));
+
+    // Inserting again keeps the first parse
+    lru.insert("c", parsed(4));
+    assert_eq!(lru.get("c"), Some(parsed(3)));
+  }
+
+  fn verify(address: &str, signature: &str, message: &str) -> bool {
+    api::verify(address, signature, message.as_bytes()).is_ok()
+  }
+
+  #[test]
+  fn test_cached_verification() {
+    let pair = sr25519::Pair::from_string("//Alice", None).unwrap();
+    let address = pair.public().to_ss58check();
+    let signature = format!("0x{}", hex::encode(pair.sign(b"hello")));
+    let mut corrupted = address.clone();
+    corrupted.replace_range(10..11, if &address[10..11] == "a" { "b" } else { "a" });
+
+    SERVES_THIS_THREAD.with(|serves| serves.set(true));
+    init(1024);
+    for _ in 0..3 {
+      assert!(verify(&address, &signature, "hello"));
+      assert!(!verify(&address, &signature, "tampered"));
+      assert!(!verify(&corrupted, &signature, "hello"));
+      assert_eq!(parse_address_cached(&corrupted).unwrap_err().code, ERR_SS58_BAD_CHECKSUM);
+    }
+    assert_eq!(parse_address_cached(&address).ok(), parse_address(&address).ok());
+    assert!(!cache().as_ref().is_some_and(|lru| lru.entries.contains_key(&corrupted)));
+
+    clear();
+    assert!(cache().as_ref().is_some_and(|lru| lru.entries.is_empty()));
+    assert!(verify(&address, &signature, "hello"));
+    init(0);
+    assert!(cache().is_none());
+  }
+}
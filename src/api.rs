@@ -0,0 +1,1016 @@
+//! Verification for Rust callers, without C strings or raw pointers.
+//!
+//! Each function verifies exactly as the exported function of the same
+//! scheme does, which is a thin wrapper over it; see the notes on those
+//! for how addresses, signatures and messages are interpreted.
+
+use crate::address_cache::parse_address_cached;
+use crate::error::Error;
+use crate::message;
+use crate::options::Options;
+use crate::parse::{encode_ss58, MAX_SS58_PREFIX};
+use crate::verify;
+use crate::{registry, ss58};
+use crate::{
+  SignatureScheme, VerifyOutcome, ERR_BAD_BASE64, ERR_BAD_DERIVATION_PATH, ERR_BAD_DID_KEY, ERR_BAD_ERA,
+  ERR_BAD_ETH_ADDRESS, ERR_BAD_EXTRINSIC, ERR_BAD_FIRST_ADDRESS, ERR_BAD_HEX, ERR_BAD_HEX_LENGTH, ERR_BAD_JSON,
+  ERR_BAD_MESSAGE_HEX, ERR_BAD_MNEMONIC, ERR_BAD_MNEMONIC_CHECKSUM, ERR_BAD_MULTISIGNATURE, ERR_BAD_PUBLIC_KEY_LENGTH,
+  ERR_BAD_SECOND_ADDRESS, ERR_BAD_SEED, ERR_BAD_SEED_LENGTH, ERR_BAD_SIGNATURE_LENGTH, ERR_BAD_SIGNATURE_PREFIX,
+  ERR_BAD_SS58, ERR_ETH_ADDRESS_CHECKSUM, ERR_EXPIRED, ERR_FILE_IO, ERR_FILE_NOT_FOUND, ERR_FILE_PERMISSION_DENIED,
+  ERR_HARD_DERIVATION, ERR_INVALID_HANDLE, ERR_INVALID_OPTIONS, ERR_INVALID_UTF16, ERR_INVALID_UTF8,
+  ERR_NON_CANONICAL_SIGNATURE, ERR_NOT_SIGNED, ERR_NOT_STRICT, ERR_NOT_YET_VALID, ERR_NO_MATCHING_SCHEME,
+  ERR_NULL_POINTER, ERR_PANIC, ERR_REPLAYED, ERR_SCHEME_NOT_SUPPORTED, ERR_SIWS_ADDRESS_MISMATCH,
+  ERR_SIWS_DOMAIN_MISMATCH, ERR_SIWS_MALFORMED, ERR_SIWS_NONCE_MISMATCH, ERR_SS58_BAD_BASE58, ERR_SS58_BAD_CHECKSUM,
+  ERR_SS58_BAD_LENGTH, ERR_THREAD_POOL, ERR_TIMESTAMP_NOT_FOUND, ERR_UNKNOWN_DEV_ACCOUNT, ERR_UNKNOWN_MNEMONIC_WORD,
+  ERR_UNKNOWN_SCHEME, ERR_UNSUPPORTED_KEY_TYPE, ERR_WRONG_KEY_LENGTH, ERR_WRONG_NETWORK, INVALID, OPT_MESSAGE_BASE64,
+  OPT_MESSAGE_IS_HEX, OPT_NFC_NORMALIZE, OPT_SIGNATURE_BASE64, OPT_SKIP_LONG_MESSAGE_HASH, OPT_STRIP_SCHEME_PREFIX,
+  OPT_WRAP_BYTES,
+};
+
+/// Why an SS58 address could not be decoded.
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
+pub enum Ss58Issue {
+  #[error("address is not valid base58")]
+  BadBase58,
+  /// Also reported for a 33-byte ECDSA payload, which is not an account.
+  #[error("SS58 payload has the wrong length")]
+  BadLength,
+  #[error("SS58 checksum mismatch")]
+  BadChecksum,
+  #[error("SS58 prefix is invalid")]
+  BadPrefix,
+}
+
+/// Why a signature was not accepted.
+///
+/// The `Display` strings are the messages `get_last_error` gives for the
+/// same failures, except that SS58 decoding failures are described there in
+/// more detail. Variants holding a `reason` cover one kind of failure whose
+/// wording depends on the input.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum VerifyError {
+  #[error("{reason}")]
+  InvalidSs58 { reason: Ss58Issue },
+  /// An SS58 address rejected for another reason, such as a public key
+  /// that is not a valid curve point.
+  #[error("{reason}")]
+  InvalidAddress { reason: String },
+  #[error("SS58 prefix {prefix} is above {MAX_SS58_PREFIX}")]
+  PrefixOutOfRange { prefix: u16 },
+  /// A well-formed address whose payload no supported key type has.
+  #[error("{}", key_length_message(*.found))]
+  WrongKeyLength { found: usize },
+  #[error("first address: {reason}")]
+  InvalidFirstAddress { reason: String },
+  #[error("second address: {reason}")]
+  InvalidSecondAddress { reason: String },
+  #[error("{reason}")]
+  WrongPublicKeyLength { reason: String },
+  /// `reason` names the first bad digit, or the odd length.
+  #[error("{reason}")]
+  InvalidSignatureHex { reason: String },
+  /// Hex other than the signature or message, such as a public key or seed.
+  #[error("{reason}")]
+  InvalidHex { reason: String },
+  #[error("{reason}")]
+  InvalidMessageHex { reason: String },
+  #[error("{reason}")]
+  InvalidBase64 { reason: String },
+  #[error("signature is {found} bytes, expected {expected}")]
+  WrongSignatureLength { found: usize, expected: usize },
+  /// A fixed-length hex argument other than the signature, such as a
+  /// genesis hash or VRF proof, with the wrong number of bytes.
+  #[error("{reason}")]
+  WrongHexLength { reason: String },
+  /// A 65-byte signature whose first byte names no known scheme.
+  #[error("{reason}")]
+  InvalidSignaturePrefix { reason: String },
+  #[error("{reason}")]
+  InvalidMultiSignature { reason: String },
+  /// Rejected by strict mode, or with `OPT_ALLOW_NON_CANONICAL_ECDSA` unset.
+  #[error("{reason}")]
+  NonCanonicalSignature { reason: String },
+  /// The inputs were well formed but the signature does not verify.
+  #[error("signature does not match address and message")]
+  SignatureMismatch,
+  /// `prefix` is None for a raw public key, which has no network.
+  #[error("{}", network_message(*.prefix, .allowed))]
+  DisallowedNetwork { prefix: Option<u16>, allowed: Vec<u16> },
+  /// Options that contradict each other, from [`VerifierBuilder::build`].
+  #[error("{reason}")]
+  ConflictingOptions { reason: String },
+  /// An option value that is out of range or unusable on its own.
+  #[error("{reason}")]
+  InvalidOption { reason: String },
+  #[error("{reason}")]
+  NotStrict { reason: String },
+  #[error("{reason}")]
+  UnknownScheme { reason: String },
+  #[error("{reason}")]
+  SchemeNotSupported { reason: String },
+  #[error("{reason}")]
+  NoMatchingScheme { reason: String },
+  #[error("{reason}")]
+  InvalidEthereumAddress { reason: String },
+  #[error("address has an invalid EIP-55 checksum")]
+  EthereumChecksumMismatch,
+  #[error("{reason}")]
+  InvalidDidKey { reason: String },
+  #[error("{reason}")]
+  UnsupportedKeyType { reason: String },
+  #[error("{reason}")]
+  InvalidJson { reason: String },
+  #[error("{reason}")]
+  SiwsMalformed { reason: String },
+  #[error("{reason}")]
+  SiwsAddressMismatch { reason: String },
+  #[error("{reason}")]
+  SiwsDomainMismatch { reason: String },
+  #[error("{reason}")]
+  SiwsNonceMismatch { reason: String },
+  #[error("{reason}")]
+  Expired { reason: String },
+  #[error("{reason}")]
+  NotYetValid { reason: String },
+  #[error("{reason}")]
+  TimestampNotFound { reason: String },
+  #[error("{reason}")]
+  Replayed { reason: String },
+  #[error("{reason}")]
+  HardDerivation { reason: String },
+  #[error("{reason}")]
+  InvalidDerivationPath { reason: String },
+  #[error("seed is {found} bytes, expected 32")]
+  WrongSeedLength { found: usize },
+  #[error("{reason}")]
+  InvalidSeed { reason: String },
+  #[error("{reason}")]
+  InvalidMnemonic { reason: String },
+  #[error("{reason}")]
+  UnknownMnemonicWord { reason: String },
+  #[error("mnemonic checksum does not match its words")]
+  MnemonicChecksumMismatch,
+  #[error("{reason}")]
+  UnknownDevAccount { reason: String },
+  #[error("extrinsic is unsigned")]
+  NotSigned,
+  #[error("{reason}")]
+  InvalidExtrinsic { reason: String },
+  #[error("{reason}")]
+  InvalidEra { reason: String },
+  /// A pointer argument of the C API was NULL.
+  #[error("{name} pointer is NULL")]
+  NullPointer { name: String },
+  /// An argument the others make necessary was not given, such as the
+  /// block hash of a mortal era. Over FFI its pointer was NULL.
+  #[error("{reason}")]
+  MissingArgument { reason: String },
+  #[error("{reason}")]
+  InvalidUtf8 { reason: String },
+  #[error("{reason}")]
+  InvalidUtf16 { reason: String },
+  /// A handle of the C API that is unknown or already freed.
+  #[error("{reason}")]
+  InvalidHandle { reason: String },
+  #[error("{reason}")]
+  FileNotFound { reason: String },
+  #[error("{reason}")]
+  FilePermissionDenied { reason: String },
+  /// Any other failure to read a file.
+  #[error("{reason}")]
+  FileIo { reason: String },
+  #[error("{reason}")]
+  ThreadPool { reason: String },
+  /// A panic caught before it could unwind into the caller.
+  #[error("{reason}")]
+  Panic { reason: String },
+}
+
+pub(crate) fn network_message(prefix: Option<u16>, allowed: &[u16]) -> String {
+  match prefix {
+    Some(prefix) => format!("SS58 prefix {prefix} is not allowed, expected one of {allowed:?}"),
+    None => format!("a raw public key has no SS58 prefix, expected one of {allowed:?}"),
+  }
+}
+
+pub(crate) fn key_length_message(found: usize) -> String {
+  if found == 20 {
+    "address holds a 20-byte AccountId20, expected a 32-byte account id or 33-byte ECDSA public key".to_owned()
+  } else {
+    format!("address holds a {found}-byte payload, expected a 32-byte account id or 33-byte ECDSA public key")
+  }
+}
+
+/// `INVALID` or the `ERR_*` code the exported functions return for
+/// `error`. This is the only place the two are related.
+impl From<&VerifyError> for i32 {
+  fn from(error: &VerifyError) -> i32 {
+    match error {
+      VerifyError::InvalidSs58 { reason } => match reason {
+        Ss58Issue::BadBase58 => ERR_SS58_BAD_BASE58,
+        Ss58Issue::BadLength => ERR_SS58_BAD_LENGTH,
+        Ss58Issue::BadChecksum => ERR_SS58_BAD_CHECKSUM,
+        Ss58Issue::BadPrefix => ERR_BAD_SS58,
+      },
+      VerifyError::InvalidAddress { .. } | VerifyError::PrefixOutOfRange { .. } => ERR_BAD_SS58,
+      VerifyError::WrongKeyLength { .. } => ERR_WRONG_KEY_LENGTH,
+      VerifyError::InvalidFirstAddress { .. } => ERR_BAD_FIRST_ADDRESS,
+      VerifyError::InvalidSecondAddress { .. } => ERR_BAD_SECOND_ADDRESS,
+      VerifyError::WrongPublicKeyLength { .. } => ERR_BAD_PUBLIC_KEY_LENGTH,
+      VerifyError::InvalidSignatureHex { .. } | VerifyError::InvalidHex { .. } => ERR_BAD_HEX,
+      VerifyError::InvalidMessageHex { .. } => ERR_BAD_MESSAGE_HEX,
+      VerifyError::InvalidBase64 { .. } => ERR_BAD_BASE64,
+      VerifyError::WrongSignatureLength { .. } => ERR_BAD_SIGNATURE_LENGTH,
+      VerifyError::WrongHexLength { .. } => ERR_BAD_HEX_LENGTH,
+      VerifyError::InvalidSignaturePrefix { .. } => ERR_BAD_SIGNATURE_PREFIX,
+      VerifyError::InvalidMultiSignature { .. } => ERR_BAD_MULTISIGNATURE,
+      VerifyError::NonCanonicalSignature { .. } => ERR_NON_CANONICAL_SIGNATURE,
+      VerifyError::SignatureMismatch => INVALID,
+      VerifyError::DisallowedNetwork { .. } => ERR_WRONG_NETWORK,
+      VerifyError::ConflictingOptions { .. } | VerifyError::InvalidOption { .. } => ERR_INVALID_OPTIONS,
+      VerifyError::NotStrict { .. } => ERR_NOT_STRICT,
+      VerifyError::UnknownScheme { .. } => ERR_UNKNOWN_SCHEME,
+      VerifyError::SchemeNotSupported { .. } => ERR_SCHEME_NOT_SUPPORTED,
+      VerifyError::NoMatchingScheme { .. } => ERR_NO_MATCHING_SCHEME,
+      VerifyError::InvalidEthereumAddress { .. } => ERR_BAD_ETH_ADDRESS,
+      VerifyError::EthereumChecksumMismatch => ERR_ETH_ADDRESS_CHECKSUM,
+      VerifyError::InvalidDidKey { .. } => ERR_BAD_DID_KEY,
+      VerifyError::UnsupportedKeyType { .. } => ERR_UNSUPPORTED_KEY_TYPE,
+      VerifyError::InvalidJson { .. } => ERR_BAD_JSON,
+      VerifyError::SiwsMalformed { .. } => ERR_SIWS_MALFORMED,
+      VerifyError::SiwsAddressMismatch { .. } => ERR_SIWS_ADDRESS_MISMATCH,
+      VerifyError::SiwsDomainMismatch { .. } => ERR_SIWS_DOMAIN_MISMATCH,
+      VerifyError::SiwsNonceMismatch { .. } => ERR_SIWS_NONCE_MISMATCH,
+      VerifyError::Expired { .. } => ERR_EXPIRED,
+      VerifyError::NotYetValid { .. } => ERR_NOT_YET_VALID,
+      VerifyError::TimestampNotFound { .. } => ERR_TIMESTAMP_NOT_FOUND,
+      VerifyError::Replayed { .. } => ERR_REPLAYED,
+      VerifyError::HardDerivation { .. } => ERR_HARD_DERIVATION,
+      VerifyError::InvalidDerivationPath { .. } => ERR_BAD_DERIVATION_PATH,
+      VerifyError::WrongSeedLength { .. } => ERR_BAD_SEED_LENGTH,
+      VerifyError::InvalidSeed { .. } => ERR_BAD_SEED,
+      VerifyError::InvalidMnemonic { .. } => ERR_BAD_MNEMONIC,
+      VerifyError::UnknownMnemonicWord { .. } => ERR_UNKNOWN_MNEMONIC_WORD,
+      VerifyError::MnemonicChecksumMismatch => ERR_BAD_MNEMONIC_CHECKSUM,
+      VerifyError::UnknownDevAccount { .. } => ERR_UNKNOWN_DEV_ACCOUNT,
+      VerifyError::NotSigned => ERR_NOT_SIGNED,
+      VerifyError::InvalidExtrinsic { .. } => ERR_BAD_EXTRINSIC,
+      VerifyError::InvalidEra { .. } => ERR_BAD_ERA,
+      VerifyError::NullPointer { .. } | VerifyError::MissingArgument { .. } => ERR_NULL_POINTER,
+      VerifyError::InvalidUtf8 { .. } => ERR_INVALID_UTF8,
+      VerifyError::InvalidUtf16 { .. } => ERR_INVALID_UTF16,
+      VerifyError::InvalidHandle { .. } => ERR_INVALID_HANDLE,
+      VerifyError::FileNotFound { .. } => ERR_FILE_NOT_FOUND,
+      VerifyError::FilePermissionDenied { .. } => ERR_FILE_PERMISSION_DENIED,
+      VerifyError::FileIo { .. } => ERR_FILE_IO,
+      VerifyError::ThreadPool { .. } => ERR_THREAD_POOL,
+      VerifyError::Panic { .. } => ERR_PANIC,
+    }
+  }
+}
+
+impl From<Error> for VerifyError {
+  fn from(error: Error) -> Self {
+    error.detail
+  }
+}
+
+/// The verdict the exported functions report for an API result.
+#[cfg(feature = "ffi")]
+pub(crate) fn verdict_of<T>(result: Result<T, VerifyError>) -> Result<bool, Error> {
+  match result {
+    Ok(_) => Ok(true),
+    Err(VerifyError::SignatureMismatch) => Ok(false),
+    Err(error) => Err(error.into()),
+  }
+}
+
+fn accepted(outcome: Result<VerifyOutcome, Error>) -> Result<(), VerifyError> {
+  if outcome?.valid == 1 {
+    Ok(())
+  } else {
+    Err(VerifyError::SignatureMismatch)
+  }
+}
+
+/// Verify a Polkadot SR25519 signature, as `verify_polkadot_signature_bytes`
+///
+/// `address` is SS58 or a raw public key in hex, `signature_hex` is hex
+/// with or without 0x prefix, and `message` the signed bytes. Messages over
+/// 256 bytes are verified as their `blake2_256` hash.
+pub fn verify(address: &str, signature_hex: &str, message: &[u8]) -> Result<(), VerifyError> {
+  accepted(verify::verify_sr25519(address, signature_hex, message, &Options::default()))
+}
+
+/// Verify a Polkadot SR25519 signature as [`verify`] does, from an address
+/// on one of the networks `allowed_prefixes`, as
+/// `verify_polkadot_signature_with_options` does with only
+/// `allowed_prefixes` set.
+pub fn verify_on_networks(
+  allowed_prefixes: &[u16],
+  address: &str,
+  signature_hex: &str,
+  message: &[u8],
+) -> Result<(), VerifyError> {
+  let verifier = Verifier::builder().allowed_prefixes(allowed_prefixes.iter().copied()).build()?;
+  accepted(verify::verify_sr25519(address, signature_hex, message, &verifier.options()))
+}
+
+/// Verify a Polkadot ED25519 signature, as
+/// `verify_polkadot_ed25519_signature`.
+pub fn verify_ed25519(address: &str, signature_hex: &str, message: &[u8]) -> Result<(), VerifyError> {
+  accepted(verify::verify_ed25519(address, signature_hex, message, &Options::default()))
+}
+
+/// Verify a 65-byte Polkadot ECDSA signature, as
+/// `verify_polkadot_ecdsa_signature`. High-s signatures are malformed.
+pub fn verify_ecdsa(address: &str, signature_hex: &str, message: &[u8]) -> Result<(), VerifyError> {
+  accepted(verify::verify_ecdsa(address, signature_hex, message, &Options::default()))
+}
+
+/// Verify under `scheme`, as `verify_signature_with_options` with no flags.
+pub fn verify_with_scheme(
+  scheme: SignatureScheme,
+  address: &str,
+  signature_hex: &str,
+  message: &[u8],
+) -> Result<(), VerifyError> {
+  accepted(verify::verify_as(scheme, address, signature_hex, message, &Options::default()))
+}
+
+/// Verify a signature of unknown scheme, as `verify_any_signature`,
+/// returning the scheme that verified.
+pub fn verify_any(address: &str, signature_hex: &str, message: &[u8]) -> Result<SignatureScheme, VerifyError> {
+  let (outcome, scheme) = verify::verify_any(address, signature_hex, message, &Options::default())?;
+  accepted(Ok(outcome))?;
+  Ok(scheme.expect("a valid outcome has a scheme"))
+}
+
+/// Verify a SCALE-encoded `MultiSignature` in hex, as
+/// `verify_multisignature`.
+pub fn verify_multisignature(address: &str, multisignature_hex: &str, message: &[u8]) -> Result<(), VerifyError> {
+  accepted(verify::verify_multisignature(address, multisignature_hex, message, &Options::default()))
+}
+
+/// An SS58 address taken apart, from [`inspect_address`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddressDetails {
+  /// SS58 network prefix.
+  pub prefix: u16,
+  /// Registry name of the network, such as `polkadot`, if it is known.
+  pub network: Option<&'static str>,
+  /// The payload: a 32-byte account id, or a 33-byte compressed ECDSA
+  /// public key.
+  pub public_key: Vec<u8>,
+}
+
+/// Decode an SS58 address without verifying anything, accepting the
+/// addresses `validate_ss58_address` does.
+pub fn inspect_address(address: &str) -> Result<AddressDetails, VerifyError> {
+  let decoded = ss58::decode(address)?;
+  Ok(AddressDetails {
+    prefix: decoded.prefix,
+    network: registry::lookup(decoded.prefix).map(|network| network.name()),
+    public_key: decoded.payload[..decoded.payload_len].to_vec(),
+  })
+}
+
+/// Options for [`Verifier`], all off by default.
+///
+/// Each setter mirrors one field or flag of `VerifyOptions`, which the
+/// exported functions translate into a builder, so both interpret options
+/// the same way.
+#[derive(Debug, Clone, Default)]
+pub struct VerifierBuilder {
+  flags: u32,
+  allowed_prefixes: Option<Vec<u16>>,
+  strict: bool,
+  scheme: Option<SignatureScheme>,
+}
+
+impl VerifierBuilder {
+  fn flag(mut self, flag: u32, on: bool) -> Self {
+    if on {
+      self.flags |= flag;
+    } else {
+      self.flags &= !flag;
+    }
+    self
+  }
+
+  /// Set every `OPT_*` flag at once, as `VerifyOptions::flags`.
+  pub fn flags(mut self, flags: u32) -> Self {
+    self.flags = flags;
+    self
+  }
+
+  /// Accept only addresses of these SS58 prefixes, and no raw public keys.
+  pub fn allowed_prefixes(mut self, prefixes: impl IntoIterator<Item = u16>) -> Self {
+    self.allowed_prefixes = Some(prefixes.into_iter().collect());
+    self
+  }
+
+  /// Wrap messages in `<Bytes>...</Bytes>` unless they already are, as
+  /// `OPT_WRAP_BYTES`.
+  pub fn auto_wrap(self, on: bool) -> Self {
+    self.flag(OPT_WRAP_BYTES, on)
+  }
+
+  /// Messages are hex, as `OPT_MESSAGE_IS_HEX`.
+  pub fn message_is_hex(self, on: bool) -> Self {
+    self.flag(OPT_MESSAGE_IS_HEX, on)
+  }
+
+  /// Messages are base64, as `OPT_MESSAGE_BASE64`.
+  pub fn message_is_base64(self, on: bool) -> Self {
+    self.flag(OPT_MESSAGE_BASE64, on)
+  }
+
+  /// Signatures are base64 rather than hex, as `OPT_SIGNATURE_BASE64`.
+  pub fn signature_is_base64(self, on: bool) -> Self {
+    self.flag(OPT_SIGNATURE_BASE64, on)
+  }
+
+  /// Verify messages over 256 bytes as their `blake2_256` hash, as
+  /// polkadot-js signs them. On by default; off is `OPT_SKIP_LONG_MESSAGE_HASH`.
+  pub fn hash_long_messages(self, on: bool) -> Self {
+    self.flag(OPT_SKIP_LONG_MESSAGE_HASH, !on)
+  }
+
+  /// NFC-normalize UTF-8 messages, as `OPT_NFC_NORMALIZE`.
+  pub fn nfc_normalize(self, on: bool) -> Self {
+    self.flag(OPT_NFC_NORMALIZE, on)
+  }
+
+  /// Accept sr25519 signatures prefixed with a scheme byte, as
+  /// `OPT_STRIP_SCHEME_PREFIX`.
+  pub fn strip_scheme_prefix(self, on: bool) -> Self {
+    self.flag(OPT_STRIP_SCHEME_PREFIX, on)
+  }
+
+  /// Reject inputs that would need normalizing, as `VerifyOptions::strict`.
+  /// Conflicts with the options that normalize them.
+  pub fn strict(mut self, on: bool) -> Self {
+    self.strict = on;
+    self
+  }
+
+  /// Verify under `scheme` only. Without it the scheme is detected, as
+  /// `verify_any_signature` does.
+  pub fn scheme(mut self, scheme: SignatureScheme) -> Self {
+    self.scheme = Some(scheme);
+    self
+  }
+
+  /// Check the options, failing with [`VerifyError::ConflictingOptions`] if
+  /// they contradict each other.
+  pub fn build(self) -> Result<Verifier, VerifyError> {
+    Options::from_flags(self.flags, self.allowed_prefixes.as_deref(), self.strict)?;
+    let VerifierBuilder { flags, allowed_prefixes, strict, scheme } = self;
+    Ok(Verifier { flags, allowed_prefixes, strict, scheme })
+  }
+}
+
+/// Verifies signatures under fixed options, from [`Verifier::builder`].
+#[derive(Debug, Clone)]
+pub struct Verifier {
+  flags: u32,
+  allowed_prefixes: Option<Vec<u16>>,
+  strict: bool,
+  scheme: Option<SignatureScheme>,
+}
+
+/// A signature that verified.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Verified {
+  /// The scheme it verified under.
+  pub scheme: SignatureScheme,
+  /// The signer's account as SS58, under the prefix of the given address
+  /// or 42 for a raw public key. ECDSA accounts are the `blake2_256` hash
+  /// of the public key, as on chain.
+  pub address: String,
+}
+
+/// The SS58 prefix of generic Substrate addresses.
+const GENERIC_PREFIX: u16 = 42;
+
+impl Verifier {
+  /// Start from the default options.
+  pub fn builder() -> VerifierBuilder {
+    VerifierBuilder::default()
+  }
+
+  pub(crate) fn options(&self) -> Options<'_> {
+    Options::from_flags(self.flags, self.allowed_prefixes.as_deref(), self.strict).expect("checked by build")
+  }
+
+  /// Verify `signature` over `message` by `address`.
+  ///
+  /// `address` is SS58 or a raw public key in hex. The signature and
+  /// message are read as the options say.
+  pub fn verify(&self, address: &str, signature: &str, message: &[u8]) -> Result<Verified, VerifyError> {
+    let options = self.options();
+    let message = message::decode(message, &options)?;
+    let (outcome, scheme) = match self.scheme {
+      Some(scheme) => (verify::verify_as(scheme, address, signature, &message, &options)?, Some(scheme)),
+      None => verify::verify_any(address, signature, &message, &options)?,
+    };
+    accepted(Ok(outcome))?;
+
+    let parsed = parse_address_cached(address)?;
+    Ok(Verified {
+      scheme: scheme.expect("a valid outcome has a scheme"),
+      address: encode_ss58(&parsed.account_id, parsed.prefix.unwrap_or(GENERIC_PREFIX))?,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::codes::ALL_CODES;
+  use crate::{ERR_JOB_CANCELLED, VALID};
+
+  const ADDRESS: &str = "5HbqogsYaK54pN3QAgSi4t3Asqditt1X4P7cSBuDt2hPF2BR";
+  const SIGNATURE: &str = "0xf8ce1b80e76bf48a30610d0d3c2c9c8dbfdcb28a9aa8f56ac038d520ff17445ff3ce94809f8479a1224ef0d823d035022a9db2e72bd6c0d2f0244f766dec908b";
+  const MESSAGE: &[u8] = b"<Bytes>c15335d817e3d3d912d30f5a18a30c30162abc364cd0876d3a94af568d3c8c14</Bytes>";
+  const ED25519_ADDRESS: &str = "5FA9nQDVg267DEd8m1ZypXLBnvN7SFxYwV7ndqSYGiN9TTpu";
+  const ED25519_SIGNATURE: &str = "0xabd163f9ffa6334632220e353ca548fe66d65456413b69c1241e551dcdf1efba1f2f8a412a4243c6c389b3445997b8cf80e4e8a275e64bb714644a3b069d1d0d";
+  #[cfg(feature = "ecdsa")]
+  const ECDSA_ADDRESS: &str = "5C7C2Z5sWbytvHpuLTvzKunnnRwQxft1jiqrLD5rhucQ5S9X";
+  #[cfg(feature = "ecdsa")]
+  const ECDSA_SIGNATURE: &str = "0x6d49a01b12c9ba60e0ffd1638996f9cbcf6c1ca518adb3d44d2f2a68b983eaea3dcc0c125637af13b5bc679068e87afdd35f523efc33a9eb052275edf8ebbf8c00";
+
+  fn code<T>(result: Result<T, VerifyError>) -> i32 {
+    result.err().map_or(crate::VALID, |error| i32::from(&error))
+  }
+
+  #[test]
+  fn test_sr25519() {
+    assert_eq!(verify(ADDRESS, SIGNATURE, MESSAGE), Ok(()));
+    assert_eq!(verify(ADDRESS, &SIGNATURE[2..], MESSAGE), Ok(()));
+    assert_eq!(verify(ADDRESS, SIGNATURE, b"tampered"), Err(VerifyError::SignatureMismatch));
+    assert!(matches!(verify(ADDRESS, "0xzz", MESSAGE), Err(VerifyError::InvalidSignatureHex { .. })));
+    let checksum = VerifyError::InvalidSs58 { reason: Ss58Issue::BadChecksum };
+    assert_eq!(verify(&ADDRESS.replace("hPF2BR", "hPF2BS"), SIGNATURE, MESSAGE), Err(checksum));
+    let short = VerifyError::WrongSignatureLength { found: 63, expected: 64 };
+    assert_eq!(verify(ADDRESS, &SIGNATURE[..SIGNATURE.len() - 2], MESSAGE), Err(short));
+
+    assert_eq!(verify_on_networks(&[42], ADDRESS, SIGNATURE, MESSAGE), Ok(()));
+    let polkadot_only = VerifyError::DisallowedNetwork { prefix: Some(42), allowed: vec![0] };
+    assert_eq!(verify_on_networks(&[0], ADDRESS, SIGNATURE, MESSAGE), Err(polkadot_only));
+  }
+
+  #[test]
+  fn test_other_schemes() {
+    #[cfg(feature = "sp-core")]
+    {
+      assert_eq!(verify_ed25519(ED25519_ADDRESS, ED25519_SIGNATURE, MESSAGE), Ok(()));
+      assert_eq!(verify_ed25519(ED25519_ADDRESS, ED25519_SIGNATURE, b"tampered"), Err(VerifyError::SignatureMismatch));
+    }
+    assert_eq!(verify(ED25519_ADDRESS, ED25519_SIGNATURE, MESSAGE), Err(VerifyError::SignatureMismatch));
+    #[cfg(feature = "ecdsa")]
+    {
+      assert_eq!(verify_ecdsa(ECDSA_ADDRESS, ECDSA_SIGNATURE, MESSAGE), Ok(()));
+      assert_eq!(verify_ecdsa(ADDRESS, ECDSA_SIGNATURE, MESSAGE), Err(VerifyError::SignatureMismatch));
+    }
+    #[cfg(feature = "sp-core")]
+    assert_eq!(verify_with_scheme(SignatureScheme::Ed25519, ED25519_ADDRESS, ED25519_SIGNATURE, MESSAGE), Ok(()));
+
+    assert_eq!(verify_any(ADDRESS, SIGNATURE, MESSAGE), Ok(SignatureScheme::Sr25519));
+    #[cfg(feature = "sp-core")]
+    assert_eq!(verify_any(ED25519_ADDRESS, ED25519_SIGNATURE, MESSAGE), Ok(SignatureScheme::Ed25519));
+    #[cfg(feature = "ecdsa")]
+    assert_eq!(verify_any(ECDSA_ADDRESS, ECDSA_SIGNATURE, MESSAGE), Ok(SignatureScheme::Ecdsa));
+    assert_eq!(verify_any(ADDRESS, SIGNATURE, b"tampered"), Err(VerifyError::SignatureMismatch));
+    assert_eq!(code(verify_any(ADDRESS, "0x1234", MESSAGE)), ERR_NO_MATCHING_SCHEME);
+
+    let tagged = format!("0x01{}", &SIGNATURE[2..]);
+    assert_eq!(verify_multisignature(ADDRESS, &tagged, MESSAGE), Ok(()));
+  }
+
+  /// One of each variant, with the code and message it must keep.
+  fn every_variant() -> Vec<(VerifyError, i32, &'static str)> {
+    let ss58 = |reason| VerifyError::InvalidSs58 { reason };
+    let errors = vec![
+      (ss58(Ss58Issue::BadBase58), ERR_SS58_BAD_BASE58, "address is not valid base58"),
+      (ss58(Ss58Issue::BadLength), ERR_SS58_BAD_LENGTH, "SS58 payload has the wrong length"),
+      (ss58(Ss58Issue::BadChecksum), ERR_SS58_BAD_CHECKSUM, "SS58 checksum mismatch"),
+      (ss58(Ss58Issue::BadPrefix), ERR_BAD_SS58, "SS58 prefix is invalid"),
+      (
+        VerifyError::InvalidAddress { reason: "SS58 decode failed: FormatNotAllowed".to_owned() },
+        ERR_BAD_SS58,
+        "SS58 decode failed: FormatNotAllowed",
+      ),
+      (VerifyError::PrefixOutOfRange { prefix: 16384 }, ERR_BAD_SS58, "SS58 prefix 16384 is above 16383"),
+      (
+        VerifyError::WrongKeyLength { found: 20 },
+        ERR_WRONG_KEY_LENGTH,
+        "address holds a 20-byte AccountId20, expected a 32-byte account id or 33-byte ECDSA public key",
+      ),
+      (
+        VerifyError::WrongKeyLength { found: 48 },
+        ERR_WRONG_KEY_LENGTH,
+        "address holds a 48-byte payload, expected a 32-byte account id or 33-byte ECDSA public key",
+      ),
+      (
+        VerifyError::InvalidFirstAddress { reason: "SS58 checksum mismatch".to_owned() },
+        ERR_BAD_FIRST_ADDRESS,
+        "first address: SS58 checksum mismatch",
+      ),
+      (
+        VerifyError::InvalidSecondAddress { reason: "address is empty".to_owned() },
+        ERR_BAD_SECOND_ADDRESS,
+        "second address: address is empty",
+      ),
+      (
+        VerifyError::WrongPublicKeyLength { reason: "public key is 31 bytes, expected 32".to_owned() },
+        ERR_BAD_PUBLIC_KEY_LENGTH,
+        "public key is 31 bytes, expected 32",
+      ),
+      (
+        VerifyError::InvalidSignatureHex { reason: "signature hex had odd length".to_owned() },
+        ERR_BAD_HEX,
+        "signature hex had odd length",
+      ),
+      (
+        VerifyError::InvalidHex { reason: "seed hex had odd length".to_owned() },
+        ERR_BAD_HEX,
+        "seed hex had odd length",
+      ),
+      (
+        VerifyError::InvalidMessageHex { reason: "message hex had odd length".to_owned() },
+        ERR_BAD_MESSAGE_HEX,
+        "message hex had odd length",
+      ),
+      (
+        VerifyError::InvalidBase64 { reason: "signature is not valid base64: Invalid padding".to_owned() },
+        ERR_BAD_BASE64,
+        "signature is not valid base64: Invalid padding",
+      ),
+      (
+        VerifyError::WrongSignatureLength { found: 63, expected: 64 },
+        ERR_BAD_SIGNATURE_LENGTH,
+        "signature is 63 bytes, expected 64",
+      ),
+      (
+        VerifyError::WrongHexLength { reason: "genesis_hash is 2 bytes, expected 32".to_owned() },
+        ERR_BAD_HEX_LENGTH,
+        "genesis_hash is 2 bytes, expected 32",
+      ),
+      (
+        VerifyError::InvalidSignaturePrefix { reason: "65-byte signature starts with 0x02".to_owned() },
+        ERR_BAD_SIGNATURE_PREFIX,
+        "65-byte signature starts with 0x02",
+      ),
+      (
+        VerifyError::InvalidMultiSignature { reason: "MultiSignature is empty".to_owned() },
+        ERR_BAD_MULTISIGNATURE,
+        "MultiSignature is empty",
+      ),
+      (
+        VerifyError::NonCanonicalSignature { reason: "ECDSA signature has a high s value".to_owned() },
+        ERR_NON_CANONICAL_SIGNATURE,
+        "ECDSA signature has a high s value",
+      ),
+      (VerifyError::SignatureMismatch, INVALID, "signature does not match address and message"),
+      (
+        VerifyError::DisallowedNetwork { prefix: Some(42), allowed: vec![0, 2] },
+        ERR_WRONG_NETWORK,
+        "SS58 prefix 42 is not allowed, expected one of [0, 2]",
+      ),
+      (
+        VerifyError::DisallowedNetwork { prefix: None, allowed: vec![0] },
+        ERR_WRONG_NETWORK,
+        "a raw public key has no SS58 prefix, expected one of [0]",
+      ),
+      (
+        VerifyError::ConflictingOptions { reason: "flags 0x1 normalize the input".to_owned() },
+        ERR_INVALID_OPTIONS,
+        "flags 0x1 normalize the input",
+      ),
+      (
+        VerifyError::InvalidOption { reason: "unknown stream convention 7".to_owned() },
+        ERR_INVALID_OPTIONS,
+        "unknown stream convention 7",
+      ),
+      (
+        VerifyError::NotStrict { reason: "signature hex has a 0x prefix, which strict mode does not strip".to_owned() },
+        ERR_NOT_STRICT,
+        "signature hex has a 0x prefix, which strict mode does not strip",
+      ),
+      (
+        VerifyError::UnknownScheme { reason: "unknown signature scheme 9".to_owned() },
+        ERR_UNKNOWN_SCHEME,
+        "unknown signature scheme 9",
+      ),
+      (
+        VerifyError::SchemeNotSupported { reason: "ecdsa signatures are not supported by this build".to_owned() },
+        ERR_SCHEME_NOT_SUPPORTED,
+        "ecdsa signatures are not supported by this build",
+      ),
+      (
+        VerifyError::NoMatchingScheme { reason: "signature is 48 bytes, expected 64 or 65".to_owned() },
+        ERR_NO_MATCHING_SCHEME,
+        "signature is 48 bytes, expected 64 or 65",
+      ),
+      (
+        VerifyError::InvalidEthereumAddress { reason: "address is not 20 hex-encoded bytes".to_owned() },
+        ERR_BAD_ETH_ADDRESS,
+        "address is not 20 hex-encoded bytes",
+      ),
+      (VerifyError::EthereumChecksumMismatch, ERR_ETH_ADDRESS_CHECKSUM, "address has an invalid EIP-55 checksum"),
+      (
+        VerifyError::InvalidDidKey { reason: "did:key has no multicodec key type".to_owned() },
+        ERR_BAD_DID_KEY,
+        "did:key has no multicodec key type",
+      ),
+      (
+        VerifyError::UnsupportedKeyType { reason: "did:key has unsupported multicodec key type 0xec".to_owned() },
+        ERR_UNSUPPORTED_KEY_TYPE,
+        "did:key has unsupported multicodec key type 0xec",
+      ),
+      (
+        VerifyError::InvalidJson { reason: "invalid request: EOF while parsing a value at line 1 column 0".to_owned() },
+        ERR_BAD_JSON,
+        "invalid request: EOF while parsing a value at line 1 column 0",
+      ),
+      (
+        VerifyError::SiwsMalformed { reason: "message has no URI line".to_owned() },
+        ERR_SIWS_MALFORMED,
+        "message has no URI line",
+      ),
+      (
+        VerifyError::SiwsAddressMismatch { reason: "message is for 5Grw, expected 5Hbq".to_owned() },
+        ERR_SIWS_ADDRESS_MISMATCH,
+        "message is for 5Grw, expected 5Hbq",
+      ),
+      (
+        VerifyError::SiwsDomainMismatch { reason: "message is for evil.example, expected example.com".to_owned() },
+        ERR_SIWS_DOMAIN_MISMATCH,
+        "message is for evil.example, expected example.com",
+      ),
+      (
+        VerifyError::SiwsNonceMismatch { reason: "message nonce \"n2\" was not issued".to_owned() },
+        ERR_SIWS_NONCE_MISMATCH,
+        "message nonce \"n2\" was not issued",
+      ),
+      (
+        VerifyError::Expired { reason: "message expired at 10, now is 20".to_owned() },
+        ERR_EXPIRED,
+        "message expired at 10, now is 20",
+      ),
+      (
+        VerifyError::NotYetValid { reason: "message is not valid before 20, now is 10".to_owned() },
+        ERR_NOT_YET_VALID,
+        "message is not valid before 20, now is 10",
+      ),
+      (
+        VerifyError::TimestampNotFound { reason: "format has no {issued_at} placeholder".to_owned() },
+        ERR_TIMESTAMP_NOT_FOUND,
+        "format has no {issued_at} placeholder",
+      ),
+      (
+        VerifyError::Replayed { reason: "nonce \"n1\" was already used by this address".to_owned() },
+        ERR_REPLAYED,
+        "nonce \"n1\" was already used by this address",
+      ),
+      (
+        VerifyError::HardDerivation { reason: "derivation path \"//0\" has a hard junction".to_owned() },
+        ERR_HARD_DERIVATION,
+        "derivation path \"//0\" has a hard junction",
+      ),
+      (
+        VerifyError::InvalidDerivationPath { reason: "derivation path does not start with /".to_owned() },
+        ERR_BAD_DERIVATION_PATH,
+        "derivation path does not start with /",
+      ),
+      (VerifyError::WrongSeedLength { found: 2 }, ERR_BAD_SEED_LENGTH, "seed is 2 bytes, expected 32"),
+      (
+        VerifyError::InvalidSeed { reason: "seed is not a valid secret key".to_owned() },
+        ERR_BAD_SEED,
+        "seed is not a valid secret key",
+      ),
+      (
+        VerifyError::InvalidMnemonic { reason: "mnemonic has 11 words, expected 12, 15, 18, 21 or 24".to_owned() },
+        ERR_BAD_MNEMONIC,
+        "mnemonic has 11 words, expected 12, 15, 18, 21 or 24",
+      ),
+      (
+        VerifyError::UnknownMnemonicWord { reason: "word 3 of the mnemonic is not in the word list".to_owned() },
+        ERR_UNKNOWN_MNEMONIC_WORD,
+        "word 3 of the mnemonic is not in the word list",
+      ),
+      (VerifyError::MnemonicChecksumMismatch, ERR_BAD_MNEMONIC_CHECKSUM, "mnemonic checksum does not match its words"),
+      (
+        VerifyError::UnknownDevAccount { reason: "unknown dev account \"Mallory\"".to_owned() },
+        ERR_UNKNOWN_DEV_ACCOUNT,
+        "unknown dev account \"Mallory\"",
+      ),
+      (VerifyError::NotSigned, ERR_NOT_SIGNED, "extrinsic is unsigned"),
+      (
+        VerifyError::InvalidExtrinsic { reason: "extrinsic is truncated".to_owned() },
+        ERR_BAD_EXTRINSIC,
+        "extrinsic is truncated",
+      ),
+      (
+        VerifyError::InvalidEra { reason: "era 0x is not a SCALE-encoded Era".to_owned() },
+        ERR_BAD_ERA,
+        "era 0x is not a SCALE-encoded Era",
+      ),
+      (VerifyError::NullPointer { name: "address".to_owned() }, ERR_NULL_POINTER, "address pointer is NULL"),
+      (
+        VerifyError::MissingArgument { reason: "block_hash is needed to verify a mortal era".to_owned() },
+        ERR_NULL_POINTER,
+        "block_hash is needed to verify a mortal era",
+      ),
+      (
+        VerifyError::InvalidUtf8 { reason: "address is not valid UTF-8".to_owned() },
+        ERR_INVALID_UTF8,
+        "address is not valid UTF-8",
+      ),
+      (
+        VerifyError::InvalidUtf16 { reason: "address is not valid UTF-16".to_owned() },
+        ERR_INVALID_UTF16,
+        "address is not valid UTF-16",
+      ),
+      (
+        VerifyError::InvalidHandle { reason: "stream handle 0x1 is unknown or already freed".to_owned() },
+        ERR_INVALID_HANDLE,
+        "stream handle 0x1 is unknown or already freed",
+      ),
+      (
+        VerifyError::FileNotFound { reason: "cannot read a.txt: not found".to_owned() },
+        ERR_FILE_NOT_FOUND,
+        "cannot read a.txt: not found",
+      ),
+      (
+        VerifyError::FilePermissionDenied { reason: "cannot read a.txt: permission denied".to_owned() },
+        ERR_FILE_PERMISSION_DENIED,
+        "cannot read a.txt: permission denied",
+      ),
+      (
+        VerifyError::FileIo { reason: "cannot read a.txt: I/O error".to_owned() },
+        ERR_FILE_IO,
+        "cannot read a.txt: I/O error",
+      ),
+      (
+        VerifyError::ThreadPool { reason: "cannot start 4 batch threads".to_owned() },
+        ERR_THREAD_POOL,
+        "cannot start 4 batch threads",
+      ),
+      (VerifyError::Panic { reason: "internal panic: boom".to_owned() }, ERR_PANIC, "internal panic: boom"),
+    ];
+    // Adding a variant fails to compile here until it is listed above
+    for (error, _, _) in &errors {
+      match error {
+        VerifyError::InvalidSs58 { .. }
+        | VerifyError::InvalidAddress { .. }
+        | VerifyError::PrefixOutOfRange { .. }
+        | VerifyError::WrongKeyLength { .. }
+        | VerifyError::InvalidFirstAddress { .. }
+        | VerifyError::InvalidSecondAddress { .. }
+        | VerifyError::WrongPublicKeyLength { .. }
+        | VerifyError::InvalidSignatureHex { .. }
+        | VerifyError::InvalidHex { .. }
+        | VerifyError::InvalidMessageHex { .. }
+        | VerifyError::InvalidBase64 { .. }
+        | VerifyError::WrongSignatureLength { .. }
+        | VerifyError::WrongHexLength { .. }
+        | VerifyError::InvalidSignaturePrefix { .. }
+        | VerifyError::InvalidMultiSignature { .. }
+        | VerifyError::NonCanonicalSignature { .. }
+        | VerifyError::SignatureMismatch
+        | VerifyError::DisallowedNetwork { .. }
+        | VerifyError::ConflictingOptions { .. }
+        | VerifyError::InvalidOption { .. }
+        | VerifyError::NotStrict { .. }
+        | VerifyError::UnknownScheme { .. }
+        | VerifyError::SchemeNotSupported { .. }
+        | VerifyError::NoMatchingScheme { .. }
+        | VerifyError::InvalidEthereumAddress { .. }
+        | VerifyError::EthereumChecksumMismatch
+        | VerifyError::InvalidDidKey { .. }
+        | VerifyError::UnsupportedKeyType { .. }
+        | VerifyError::InvalidJson { .. }
+        | VerifyError::SiwsMalformed { .. }
+        | VerifyError::SiwsAddressMismatch { .. }
+        | VerifyError::SiwsDomainMismatch { .. }
+        | VerifyError::SiwsNonceMismatch { .. }
+        | VerifyError::Expired { .. }
+        | VerifyError::NotYetValid { .. }
+        | VerifyError::TimestampNotFound { .. }
+        | VerifyError::Replayed { .. }
+        | VerifyError::HardDerivation { .. }
+        | VerifyError::InvalidDerivationPath { .. }
+        | VerifyError::WrongSeedLength { .. }
+        | VerifyError::InvalidSeed { .. }
+        | VerifyError::InvalidMnemonic { .. }
+        | VerifyError::UnknownMnemonicWord { .. }
+        | VerifyError::MnemonicChecksumMismatch
+        | VerifyError::UnknownDevAccount { .. }
+        | VerifyError::NotSigned
+        | VerifyError::InvalidExtrinsic { .. }
+        | VerifyError::InvalidEra { .. }
+        | VerifyError::NullPointer { .. }
+        | VerifyError::MissingArgument { .. }
+        | VerifyError::InvalidUtf8 { .. }
+        | VerifyError::InvalidUtf16 { .. }
+        | VerifyError::InvalidHandle { .. }
+        | VerifyError::FileNotFound { .. }
+        | VerifyError::FilePermissionDenied { .. }
+        | VerifyError::FileIo { .. }
+        | VerifyError::ThreadPool { .. }
+        | VerifyError::Panic { .. } => {}
+      }
+    }
+    errors
+  }
+
+  #[test]
+  fn test_codes_and_messages() {
+    for (error, code, message) in every_variant() {
+      assert_eq!(i32::from(&error), code, "{error:?}");
+      assert_eq!(error.to_string(), message);
+
+      // The FFI reports the same code and message, and the API gets the
+      // variant back
+      let ffi = Error::from(error.clone());
+      assert_eq!((ffi.code, ffi.message.as_str()), (code, message));
+      assert_eq!(VerifyError::from(ffi), error);
+    }
+
+    // Every code a failure can be reported with has a variant. Cancelled
+    // jobs are only ever reported to their callback.
+    let codes: Vec<i32> = every_variant().iter().map(|(_, code, _)| *code).collect();
+    for code in ALL_CODES.iter().filter(|&&code| code != VALID && code != ERR_JOB_CANCELLED) {
+      assert!(codes.contains(code), "no variant has code {code}");
+    }
+
+    // Errors carry through `?` into other error types
+    let boxed: Box<dyn std::error::Error> = Box::new(VerifyError::SignatureMismatch);
+    assert_eq!(boxed.to_string(), "signature does not match address and message");
+  }
+
+  #[test]
+  fn test_inspect_address() {
+    let details = inspect_address(ADDRESS).unwrap();
+    assert_eq!((details.prefix, details.network), (42, Some("substrate")));
+    assert_eq!(hex::encode(&details.public_key), "f4ec883f28db136cd98e3b553cab48ab4671111978a4660f129a45cb4df84d30");
+    assert_eq!(code(inspect_address(&ADDRESS.replace("hPF2BR", "hPF2BS"))), ERR_SS58_BAD_CHECKSUM);
+    assert_eq!(inspect_address(ED25519_ADDRESS).unwrap().public_key.len(), 32);
+  }
+
+  #[test]
+  fn test_verifier() {
+    let unwrapped = &MESSAGE[7..MESSAGE.len() - 8];
+    let verifier = Verifier::builder().allowed_prefixes([42]).auto_wrap(true).scheme(SignatureScheme::Sr25519).build();
+    let verified = verifier.unwrap().verify(ADDRESS, SIGNATURE, unwrapped).unwrap();
+    assert_eq!(verified, Verified { scheme: SignatureScheme::Sr25519, address: ADDRESS.to_owned() });
+
+    // Without a scheme it is detected, and addresses are given as SS58
+    let verifier = Verifier::builder().build().unwrap();
+    #[cfg(feature = "sp-core")]
+    assert_eq!(verifier.verify(ED25519_ADDRESS, ED25519_SIGNATURE, MESSAGE).unwrap().scheme, SignatureScheme::Ed25519);
+    #[cfg(feature = "ecdsa")]
+    assert_eq!(verifier.verify(ECDSA_ADDRESS, ECDSA_SIGNATURE, MESSAGE).unwrap().scheme, SignatureScheme::Ecdsa);
+    let (public_key, _) = crate::backend::decode_ss58_account(ADDRESS).unwrap();
+    let raw = format!("0x{}", hex::encode(public_key));
+    assert_eq!(verifier.verify(&raw, SIGNATURE, MESSAGE).unwrap().address, ADDRESS);
+    assert_eq!(verifier.verify(ADDRESS, SIGNATURE, unwrapped), Err(VerifyError::SignatureMismatch));
+
+    let polkadot = Verifier::builder().allowed_prefixes([0]).build().unwrap();
+    assert!(matches!(polkadot.verify(ADDRESS, SIGNATURE, MESSAGE), Err(VerifyError::DisallowedNetwork { .. })));
+    #[cfg(feature = "sp-core")]
+    {
+      let ed25519 = Verifier::builder().scheme(SignatureScheme::Ed25519).build().unwrap();
+      assert_eq!(ed25519.verify(ADDRESS, SIGNATURE, MESSAGE), Err(VerifyError::SignatureMismatch));
+    }
+    let hex_message = Verifier::builder().message_is_hex(true).auto_wrap(true).build().unwrap();
+    assert!(hex_message.verify(ADDRESS, SIGNATURE, hex::encode(unwrapped).as_bytes()).is_ok());
+  }
+
+  #[test]
+  fn test_conflicting_options() {
+    let error = Verifier::builder().strict(true).auto_wrap(true).build().unwrap_err();
+    let reason = format!("flags {OPT_WRAP_BYTES:#x} normalize the input, which strict mode forbids");
+    assert_eq!(error, VerifyError::ConflictingOptions { reason });
+    assert_eq!(i32::from(&error), ERR_INVALID_OPTIONS);
+
+    let error = Verifier::builder().message_is_hex(true).message_is_base64(true).build().unwrap_err();
+    assert!(matches!(error, VerifyError::ConflictingOptions { .. }));
+    // A later setter can undo a conflict
+    assert!(Verifier::builder().strict(true).auto_wrap(true).auto_wrap(false).build().is_ok());
+    assert!(Verifier::builder().strict(true).hash_long_messages(false).build().is_ok());
+  }
+
+  #[test]
+  fn test_detailed_message() {
+    // SS58 decoding failures keep their detailed message over FFI
+    let ffi = Error::from(ss58::Failure::BadCharacter { index: 3, c: '0' });
+    let message = "address has invalid base58 character '0' at position 3";
+    assert_eq!((ffi.code, ffi.message.as_str()), (ERR_SS58_BAD_BASE58, message));
+    assert_eq!(VerifyError::from(ffi), VerifyError::InvalidSs58 { reason: Ss58Issue::BadBase58 });
+  }
+}
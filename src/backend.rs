@@ -0,0 +1,187 @@
+//! The two primitives every sr25519 verification rests on: decoding an
+//! SS58 address with a 32-byte payload, and checking a signature.
+//!
+//! By default both go through `sp_core`. With the `minimal` feature they
+//! go through `polkadot_sig_verifier_core`, which implements them on
+//! `bs58`, `blake2` and `schnorrkel` directly, as `sp_core` does
+//! underneath. The two backends accept and reject the same inputs with the
+//! same errors; `tests` below checks one against the other.
+//!
+//! `sp_core` itself is the optional `sp-core` feature. Without it the
+//! `minimal` backend is required, and ed25519, ECDSA, signing and
+//! derivation are rejected with `ERR_SCHEME_NOT_SUPPORTED`.
+
+use crate::api::{Ss58Issue, VerifyError};
+use crate::error::Error;
+
+/// The signing context `sr25519::Pair` signs and verifies under, as in
+/// `polkadot_sig_verifier_core::SIGNING_CONTEXT`.
+#[cfg(feature = "ffi")]
+pub(crate) const SR25519_SIGNING_CONTEXT: &[u8] = b"substrate";
+
+fn bad_base58() -> Error {
+  VerifyError::InvalidSs58 { reason: Ss58Issue::BadBase58 }.into()
+}
+
+/// Also the error for a 33-byte ECDSA payload, which callers decode again
+/// with [`crate::ss58::decode`].
+fn bad_length() -> Error {
+  VerifyError::InvalidSs58 { reason: Ss58Issue::BadLength }.into()
+}
+
+fn bad_checksum() -> Error {
+  VerifyError::InvalidSs58 { reason: Ss58Issue::BadChecksum }.into()
+}
+
+fn bad_prefix() -> Error {
+  VerifyError::InvalidSs58 { reason: Ss58Issue::BadPrefix }.into()
+}
+
+fn decode_failed(reason: impl std::fmt::Debug) -> Error {
+  VerifyError::InvalidAddress { reason: format!("SS58 decode failed: {reason:?}") }.into()
+}
+
+#[cfg(not(feature = "minimal"))]
+mod imp {
+  use super::*;
+  use sp_core::crypto::{PublicError, Ss58Codec};
+  use sp_core::{sr25519, Pair};
+
+  /// Classify why an address failed to decode.
+  fn ss58_error(err: PublicError) -> Error {
+    match err {
+      PublicError::BadBase58 => bad_base58(),
+      PublicError::BadLength => bad_length(),
+      PublicError::InvalidChecksum => bad_checksum(),
+      PublicError::InvalidPrefix => bad_prefix(),
+      other => decode_failed(other),
+    }
+  }
+
+  pub(crate) fn decode_ss58_account(address: &str) -> Result<([u8; 32], u16), Error> {
+    let (public_key, version) = sr25519::Public::from_ss58check_with_version(address).map_err(ss58_error)?;
+    Ok((public_key.0, u16::from(version)))
+  }
+
+  pub(crate) fn verify_sr25519(signature: [u8; 64], message: &[u8], public_key: [u8; 32]) -> bool {
+    let signature = sr25519::Signature::from_raw(signature);
+    sr25519::Pair::verify(&signature, message, &sr25519::Public::from_raw(public_key))
+  }
+}
+
+#[cfg(feature = "minimal")]
+mod imp {
+  use super::*;
+  use polkadot_sig_verifier_core::Ss58Error;
+
+  pub(crate) fn decode_ss58_account(address: &str) -> Result<([u8; 32], u16), Error> {
+    polkadot_sig_verifier_core::decode_ss58_account(address).map_err(|error| match error {
+      Ss58Error::BadBase58 => bad_base58(),
+      Ss58Error::BadLength => bad_length(),
+      Ss58Error::BadChecksum => bad_checksum(),
+      Ss58Error::BadPrefix => bad_prefix(),
+      Ss58Error::FormatNotAllowed => decode_failed(error),
+    })
+  }
+
+  pub(crate) fn verify_sr25519(signature: [u8; 64], message: &[u8], public_key: [u8; 32]) -> bool {
+    polkadot_sig_verifier_core::verify_sr25519(&signature, message, &public_key)
+  }
+}
+
+/// Decode an SS58 address whose payload is a 32-byte account id into the
+/// id and the network prefix.
+///
+/// Any other payload length fails with `ERR_SS58_BAD_LENGTH`, as in
+/// `sp_core`, which is how callers spot 33-byte ECDSA public keys.
+pub(crate) use imp::decode_ss58_account;
+
+/// Check a 64-byte sr25519 signature over `message` by `public_key`, under
+/// [`SR25519_SIGNING_CONTEXT`].
+///
+/// Keys and signatures schnorrkel cannot decode do not verify.
+pub(crate) use imp::verify_sr25519;
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{ERR_BAD_SS58, ERR_SS58_BAD_BASE58, ERR_SS58_BAD_CHECKSUM, ERR_SS58_BAD_LENGTH};
+  use sp_core::crypto::{Ss58AddressFormat, Ss58Codec};
+  use sp_core::{ecdsa, sr25519, Pair};
+
+  /// `sp_core`'s answer, whichever backend is built.
+  fn sp_core_decode(address: &str) -> Result<([u8; 32], u16), i32> {
+    sr25519::Public::from_ss58check_with_version(address)
+      .map(|(public_key, version)| (public_key.0, u16::from(version)))
+      .map_err(|err| match err {
+        sp_core::crypto::PublicError::BadBase58 => ERR_SS58_BAD_BASE58,
+        sp_core::crypto::PublicError::BadLength => ERR_SS58_BAD_LENGTH,
+        sp_core::crypto::PublicError::InvalidChecksum => ERR_SS58_BAD_CHECKSUM,
+        _ => ERR_BAD_SS58,
+      })
+  }
+
+  #[test]
+  fn test_ss58_matches_sp_core() {
+    let alice = sr25519::Pair::from_string("//Alice", None).unwrap().public();
+    let ecdsa = ecdsa::Pair::from_string("//Alice", None).unwrap().public();
+    let mut addresses: Vec<String> = [0, 2, 42, 63, 64, 1284, 16_383]
+      .into_iter()
+      .map(|prefix| alice.to_ss58check_with_version(Ss58AddressFormat::custom(prefix)))
+      .collect();
+    addresses.push(ecdsa.to_ss58check());
+    let valid = addresses.clone();
+    for address in &valid {
+      addresses.push(address[..address.len() - 1].to_owned());
+      addresses.push(format!("{address}1"));
+      addresses.push(address.replacen('5', "6", 1));
+      addresses.push(address.replacen(&address[1..2], "0", 1));
+    }
+    addresses.extend(
+      [
+        "",
+        "1",
+        "5gkUqf3A7UZySbfFnoxoaV6sTnCu7cXbp9L8tvURjT5LNAoF",
+        "DmmS5zJcCEx2zWnNw9c3LM3Pj7Je2Tbd9PuokVKYGT7R2Mfm",
+        "tTgoSAJJvPreV4Q7jo514ZnuQoTkoYH",
+        "5Hbqögs",
+      ]
+      .map(str::to_owned),
+    );
+
+    for address in &addresses {
+      let decoded = decode_ss58_account(address).map_err(|error| error.code);
+      assert_eq!(decoded, sp_core_decode(address), "{address}");
+    }
+    assert_eq!(decode_ss58_account(&valid[2]).unwrap(), (alice.0, 42));
+  }
+
+  #[test]
+  fn test_sr25519_matches_sp_core() {
+    let pair = sr25519::Pair::from_string("//Alice", None).unwrap();
+    let public_key = pair.public().0;
+    let signature = pair.sign(b"hello").0;
+    let mut unmarked = signature;
+    unmarked[63] &= 0x7f;
+    let mut flipped = signature;
+    flipped[0] ^= 1;
+
+    let cases: [([u8; 64], &[u8], [u8; 32]); 6] = [
+      (signature, b"hello", public_key),
+      (signature, b"tampered", public_key),
+      (flipped, b"hello", public_key),
+      (unmarked, b"hello", public_key),
+      (signature, b"hello", [0xff; 32]),
+      ([0; 64], b"", [0; 32]),
+    ];
+    for (signature, message, public_key) in cases {
+      let expected = sr25519::Pair::verify(
+        &sr25519::Signature::from_raw(signature),
+        message,
+        &sr25519::Public::from_raw(public_key),
+      );
+      assert_eq!(verify_sr25519(signature, message, public_key), expected);
+    }
+    assert!(verify_sr25519(signature, b"hello", public_key));
+  }
+}
@@ -0,0 +1,11 @@
+//! Internals `benches/verify.rs` measures directly. Not part of the API.
+
+use crate::options::Options;
+use crate::parse;
+
+/// Decode a 64-byte hex signature as the verify entry points do, without
+/// allocating.
+pub fn decode_signature(signature_hex: &str) -> Option<[u8; 64]> {
+  let signature = parse::decode_signature(signature_hex, &Options::default()).ok()?;
+  parse::signature_array(&signature).ok()
+}
@@ -0,0 +1,215 @@
+//! `polkadot-verify`, built with the `cli` feature:
+//!
+//! ```text
+//! polkadot-verify --address 5Hbq... --signature 0xf8ce... --message-file challenge.txt
+//! polkadot-verify batch --ndjson results.ndjson < input.ndjson
+//! polkadot-verify address inspect 5Hbq...
+//! polkadot-verify rpc
+//! polkadot-verify serve --listen 0.0.0.0:8080  # with the service feature
+//! ```
+//!
+//! Exits 0 when every signature verifies, 1 when one does not or its input
+//! is malformed, and 2 for usage errors and files it cannot read or write.
+
+mod rpc;
+
+use clap::{Args, Parser, Subcommand};
+use polkadot_sig_verifier::{api, verify_ndjson_line, verify_request, RequestOptions, VerificationRequest};
+use std::fs::File;
+use std::io::{self, BufRead, BufWriter, Write};
+#[cfg(feature = "service")]
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+#[derive(Parser)]
+#[command(name = "polkadot-verify", version, about = "Verify Polkadot signatures")]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+struct Cli {
+  #[command(subcommand)]
+  command: Option<Command>,
+  #[command(flatten)]
+  verify: VerifyArgs,
+}
+
+#[derive(Args)]
+struct VerifyArgs {
+  /// SS58-encoded address
+  #[arg(long, required = true)]
+  address: Option<String>,
+  /// Hex-encoded signature, with or without 0x
+  #[arg(long, required = true)]
+  signature: Option<String>,
+  #[command(flatten)]
+  message: MessageArgs,
+  /// Wrap the message in <Bytes>...</Bytes> unless it already is
+  #[arg(long)]
+  wrap_bytes: bool,
+  /// Scheme to verify under, such as ed25519 or ecdsa [default: sr25519]
+  #[arg(long)]
+  scheme: Option<String>,
+  /// Accept only addresses with this SS58 prefix. May be repeated.
+  #[arg(long = "allow-prefix", value_name = "PREFIX")]
+  allowed_prefixes: Vec<u16>,
+}
+
+#[derive(Args)]
+#[group(required = true, multiple = false)]
+struct MessageArgs {
+  /// The signed message
+  #[arg(long)]
+  message: Option<String>,
+  /// File holding the signed message, verified byte for byte
+  #[arg(long, value_name = "FILE")]
+  message_file: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+  /// Verify requests read from stdin as newline-delimited JSON, one
+  /// result per line, as `verify_ndjson` gives them
+  Batch {
+    /// Write the results here rather than to stdout
+    #[arg(long, value_name = "FILE")]
+    ndjson: Option<PathBuf>,
+  },
+  /// Work with SS58 addresses
+  Address {
+    #[command(subcommand)]
+    command: AddressCommand,
+  },
+  /// Answer JSON-RPC 2.0 requests from stdin, one per line, until it closes
+  Rpc,
+  /// Serve /verify, /verify/batch and /healthz over HTTP
+  #[cfg(feature = "service")]
+  Serve(ServeArgs),
+}
+
+#[derive(Subcommand)]
+enum AddressCommand {
+  /// Print the prefix, network and public key of an address
+  Inspect { address: String },
+}
+
+#[cfg(feature = "service")]
+#[derive(Args)]
+struct ServeArgs {
+  /// Address and port to listen on
+  #[arg(long, default_value = "127.0.0.1:8080")]
+  listen: SocketAddr,
+  /// Largest request body accepted, in bytes [default: 1048576]
+  #[arg(long)]
+  max_body_bytes: Option<usize>,
+  /// Most requests accepted in one batch [default: 1000]
+  #[arg(long)]
+  max_batch: Option<usize>,
+  /// Most signatures verified at once [default: one per core]
+  #[arg(long)]
+  concurrency: Option<usize>,
+}
+
+fn main() -> ExitCode {
+  let cli = Cli::parse();
+  let result = match cli.command {
+    None => verify(cli.verify),
+    Some(Command::Batch { ndjson }) => batch(ndjson),
+    Some(Command::Address { command: AddressCommand::Inspect { address } }) => inspect(&address),
+    Some(Command::Rpc) => rpc::run(),
+    #[cfg(feature = "service")]
+    Some(Command::Serve(args)) => serve(args),
+  };
+  result.unwrap_or_else(|error| {
+    eprintln!("polkadot-verify: {error}");
+    ExitCode::from(2)
+  })
+}
+
+fn verify(args: VerifyArgs) -> io::Result<ExitCode> {
+  // A file is passed on as hex, so it need not be UTF-8
+  let (message, message_is_hex) = match (args.message.message, args.message.message_file) {
+    (Some(message), _) => (message, false),
+    (None, Some(path)) => (hex::encode(std::fs::read(path)?), true),
+    (None, None) => unreachable!("clap requires a message"),
+  };
+  let request = VerificationRequest {
+    address: args.address.expect("clap requires an address"),
+    signature: args.signature.expect("clap requires a signature"),
+    message,
+    options: RequestOptions {
+      scheme: args.scheme,
+      wrap_bytes: args.wrap_bytes,
+      message_is_hex,
+      allowed_prefixes: (!args.allowed_prefixes.is_empty()).then_some(args.allowed_prefixes),
+      ..RequestOptions::default()
+    },
+  };
+
+  let outcome = verify_request(&request);
+  if outcome.valid {
+    println!("valid {}", outcome.scheme.as_deref().unwrap_or_default());
+    return Ok(ExitCode::SUCCESS);
+  }
+  match outcome.error_message {
+    Some(message) => eprintln!("invalid: {message}"),
+    None => eprintln!("invalid: signature does not match address and message"),
+  }
+  Ok(ExitCode::FAILURE)
+}
+
+fn batch(output: Option<PathBuf>) -> io::Result<ExitCode> {
+  let output: Box<dyn Write> = match output {
+    Some(path) => Box::new(File::create(path)?),
+    None => Box::new(io::stdout().lock()),
+  };
+  let mut output = BufWriter::new(output);
+
+  let mut all_valid = true;
+  let mut index = 0;
+  for line in io::stdin().lock().split(b'\n') {
+    let line = line?;
+    let line = line.strip_suffix(b"\r").unwrap_or(&line);
+    if line.iter().all(u8::is_ascii_whitespace) {
+      continue;
+    }
+    let result = verify_ndjson_line(index, line);
+    all_valid &= result.outcome.valid;
+    serde_json::to_writer(&mut output, &result)?;
+    output.write_all(b"\n")?;
+    index += 1;
+  }
+  output.flush()?;
+  Ok(if all_valid { ExitCode::SUCCESS } else { ExitCode::FAILURE })
+}
+
+fn inspect(address: &str) -> io::Result<ExitCode> {
+  match api::inspect_address(address) {
+    Ok(details) => {
+      println!("prefix: {}", details.prefix);
+      println!("network: {}", details.network.unwrap_or("unknown"));
+      println!("public key: 0x{}", hex::encode(&details.public_key));
+      Ok(ExitCode::SUCCESS)
+    }
+    Err(error) => {
+      eprintln!("invalid address: {error}");
+      Ok(ExitCode::FAILURE)
+    }
+  }
+}
+
+#[cfg(feature = "service")]
+fn serve(args: ServeArgs) -> io::Result<ExitCode> {
+  use polkadot_sig_verifier::service::{self, ServiceConfig};
+
+  let defaults = ServiceConfig::default();
+  let config = ServiceConfig {
+    max_body_bytes: args.max_body_bytes.unwrap_or(defaults.max_body_bytes),
+    max_batch_len: args.max_batch.unwrap_or(defaults.max_batch_len),
+    concurrency: args.concurrency.unwrap_or(defaults.concurrency),
+  };
+  tokio::runtime::Runtime::new()?.block_on(async {
+    let listener = tokio::net::TcpListener::bind(args.listen).await?;
+    eprintln!("polkadot-verify: listening on {}", listener.local_addr()?);
+    service::serve(listener, config).await
+  })?;
+  Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,133 @@
+//! `polkadot-verify rpc`: JSON-RPC 2.0 over stdin and stdout, one message
+//! per line, until stdin closes.
+//!
+//! * `verify` takes a `VerificationRequest` and returns its
+//!   `VerificationOutcome`, `{"valid": false, ...}` if the signature does
+//!   not match.
+//! * `verify_batch` takes an array of requests and returns an array of
+//!   outcomes, in order, with any errors inline as `verify_ndjson` gives
+//!   them.
+//! * `validate_address` takes `{"address": ...}` and returns its prefix,
+//!   network and public key.
+//! * `version` returns `{"version": ...}`.
+//!
+//! A request that cannot be verified, such as one with a malformed
+//! signature, is an error whose `code` is the library's `ERR_*` code, named
+//! in `data`. Protocol errors use the JSON-RPC codes. Requests without an
+//! `id` are notifications and get no response. Nothing but responses is
+//! written to stdout.
+
+use polkadot_sig_verifier::{api, error_code_name, verify_request, VerificationRequest};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::io::{self, BufRead, Write};
+use std::process::ExitCode;
+
+const PARSE_ERROR: i32 = -32700;
+const INVALID_REQUEST: i32 = -32600;
+const METHOD_NOT_FOUND: i32 = -32601;
+const INVALID_PARAMS: i32 = -32602;
+
+struct RpcError {
+  code: i32,
+  message: String,
+  data: Option<Value>,
+}
+
+impl RpcError {
+  fn new(code: i32, message: impl Into<String>) -> Self {
+    RpcError { code, message: message.into(), data: None }
+  }
+
+  /// An `ERR_*` code of the library.
+  fn library(code: i32, message: String) -> Self {
+    RpcError { code, message, data: error_code_name(code).map(|name| json!({ "name": name })) }
+  }
+}
+
+#[derive(Deserialize)]
+struct AddressParams {
+  address: String,
+}
+
+fn params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+  serde_json::from_value(params).map_err(|e| RpcError::new(INVALID_PARAMS, format!("invalid params: {e}")))
+}
+
+fn call(method: &str, params_value: Value) -> Result<Value, RpcError> {
+  match method {
+    "verify" => {
+      let outcome = verify_request(&params::<VerificationRequest>(params_value)?);
+      if let Some(code) = outcome.error_code {
+        return Err(RpcError::library(code, outcome.error_message.unwrap_or_default()));
+      }
+      Ok(json!(outcome))
+    }
+    "verify_batch" => {
+      let requests: Vec<VerificationRequest> = params(params_value)?;
+      Ok(json!(requests.iter().map(verify_request).collect::<Vec<_>>()))
+    }
+    "validate_address" => {
+      let AddressParams { address } = params(params_value)?;
+      let details =
+        api::inspect_address(&address).map_err(|error| RpcError::library(i32::from(&error), error.to_string()))?;
+      Ok(json!({
+        "prefix": details.prefix,
+        "network": details.network,
+        "public_key": format!("0x{}", hex::encode(&details.public_key)),
+      }))
+    }
+    "version" => Ok(json!({ "version": env!("CARGO_PKG_VERSION") })),
+    _ => Err(RpcError::new(METHOD_NOT_FOUND, format!("method not found: {method}"))),
+  }
+}
+
+fn response(id: Value, result: Result<Value, RpcError>) -> Value {
+  match result {
+    Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+    Err(RpcError { code, message, data: None }) => {
+      json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+    }
+    Err(RpcError { code, message, data: Some(data) }) => {
+      json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message, "data": data } })
+    }
+  }
+}
+
+/// The response to one line, or None if it is a notification.
+fn respond(line: &[u8]) -> Option<Value> {
+  let message = match serde_json::from_slice(line) {
+    Ok(Value::Object(message)) => message,
+    Ok(_) => return Some(response(Value::Null, Err(RpcError::new(INVALID_REQUEST, "request is not an object")))),
+    Err(e) => return Some(response(Value::Null, Err(RpcError::new(PARSE_ERROR, format!("parse error: {e}"))))),
+  };
+  let id = message.get("id").cloned();
+  let method = match (message.get("jsonrpc"), message.get("method")) {
+    (Some(Value::String(version)), Some(Value::String(method))) if version == "2.0" => method,
+    _ => {
+      let error = RpcError::new(INVALID_REQUEST, "not a JSON-RPC 2.0 request");
+      return Some(response(id.unwrap_or(Value::Null), Err(error)));
+    }
+  };
+  let result = call(method, message.get("params").cloned().unwrap_or(Value::Null));
+  id.map(|id| response(id, result))
+}
+
+pub fn run() -> io::Result<ExitCode> {
+  let mut stdout = io::stdout().lock();
+  for line in io::stdin().lock().split(b'\n') {
+    let line = line?;
+    let line = line.strip_suffix(b"\r").unwrap_or(&line);
+    if line.iter().all(u8::is_ascii_whitespace) {
+      continue;
+    }
+    if let Some(response) = respond(line) {
+      serde_json::to_writer(&mut stdout, &response)?;
+      stdout.write_all(b"\n")?;
+      // The caller waits for each response before sending more
+      stdout.flush()?;
+    }
+  }
+  Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,60 @@
+//! Finding which of several addresses made a signature, as in account
+//! recovery.
+//!
+//! The signature and message are decoded once and only the addresses vary.
+//! Candidates are checked one by one rather than in a schnorrkel batch: a
+//! batch only says whether every signature in it is valid, and one
+//! signature matches at most one of the candidates, so a batch of them
+//! would always fail.
+
+use crate::address_cache::parse_address_cached;
+use crate::backend;
+use crate::canonical;
+use crate::error::Error;
+use crate::log::{self, LOG_INFO, LOG_WARN};
+use crate::message;
+use crate::options::Options;
+use crate::parse::{decode_signature, signature_array};
+
+/// A signature and the message it should sign, decoded once to check
+/// against every candidate.
+pub(crate) struct Signed {
+  signature: [u8; 64],
+  message: Vec<u8>,
+}
+
+impl Signed {
+  /// Decode an SR25519 signature and message as `verify_polkadot_signature`
+  /// does.
+  pub(crate) fn decode(signature: &str, message: &str) -> Result<Self, Error> {
+    let options = Options::default();
+    let signature = signature_array::<64>(&decode_signature(signature, &options)?)?;
+    canonical::check_sr25519_reduced_s(&signature)?;
+    let message = message::decode(message.as_bytes(), &options)?;
+    let message = message::canonicalize_message(&message, &options)?.into_owned();
+    Ok(Signed { signature, message })
+  }
+}
+
+/// The index of the first candidate address that made `signed`, if any.
+/// Candidates that could not be read, or do not parse, are skipped with a
+/// `LOG_WARN` message.
+pub(crate) fn find_signer<'a>(
+  signed: &Signed,
+  candidates: impl IntoIterator<Item = Result<&'a str, Error>>,
+) -> Option<usize> {
+  let found = candidates.into_iter().enumerate().find_map(|(index, address)| {
+    match address.and_then(|address| parse_address_cached(address)?.public_key()) {
+      Ok(public_key) => backend::verify_sr25519(signed.signature, &signed.message, public_key).then_some(index),
+      Err(error) => {
+        log::log(LOG_WARN, || format!("skipped candidate {index}: {}", error.message));
+        None
+      }
+    }
+  });
+  match found {
+    Some(index) => log::log(LOG_INFO, || format!("signature verifies against candidate {index}")),
+    None => log::log(LOG_INFO, || "signature verifies against no candidate".to_owned()),
+  }
+  found
+}
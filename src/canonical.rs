@@ -0,0 +1,121 @@
+//! Canonical-encoding checks that tell malleated signatures apart from
+//! wrong ones.
+
+use crate::api::VerifyError;
+use crate::error::Error;
+
+/// Order of the secp256k1 group, big-endian.
+#[cfg(feature = "sp-core")]
+const SECP256K1_ORDER: [u8; 32] = [
+  0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe, 0xba, 0xae, 0xdc,
+  0xe6, 0xaf, 0x48, 0xa0, 0x3b, 0xbf, 0xd2, 0x5e, 0x8c, 0xd0, 0x36, 0x41, 0x41,
+];
+
+/// Half the secp256k1 group order, big-endian. Signatures with a larger `s`
+/// are the malleable twin of a canonical signature.
+#[cfg(feature = "sp-core")]
+const SECP256K1_HALF_ORDER: [u8; 32] = [
+  0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x5d, 0x57, 0x6e,
+  0x73, 0x57, 0xa4, 0x50, 0x1d, 0xdf, 0xe9, 0x2f, 0x46, 0x68, 0x1b, 0x20, 0xa0,
+];
+
+/// Order of the Ristretto25519 group, little-endian.
+const RISTRETTO_ORDER: [u8; 32] = [
+  0xed, 0xd3, 0xf5, 0x5c, 0x1a, 0x63, 0x12, 0x58, 0xd6, 0x9c, 0xf7, 0xa2, 0xde, 0xf9, 0xde, 0x14, 0x00, 0x00, 0x00,
+  0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10,
+];
+
+/// Reject a 65-byte (r, s, v) ECDSA signature whose `s` is in the upper
+/// half of the group order.
+#[cfg(feature = "sp-core")]
+pub(crate) fn check_ecdsa_low_s(signature: &[u8; 65]) -> Result<(), Error> {
+  if signature[32..64] > SECP256K1_HALF_ORDER[..] {
+    let reason = "ECDSA signature has a high s value".to_owned();
+    return Err(VerifyError::NonCanonicalSignature { reason }.into());
+  }
+  Ok(())
+}
+
+/// Replace a high `s` with `n - s` and flip the recovery id, which yields
+/// the canonical signature recovering the same key.
+#[cfg(feature = "sp-core")]
+pub(crate) fn normalize_ecdsa(signature: [u8; 65]) -> [u8; 65] {
+  if check_ecdsa_low_s(&signature).is_ok() {
+    return signature;
+  }
+
+  let mut normalized = signature;
+  let mut borrow = 0i16;
+  for i in (0..32).rev() {
+    let mut digit = SECP256K1_ORDER[i] as i16 - signature[32 + i] as i16 - borrow;
+    borrow = (digit < 0) as i16;
+    if digit < 0 {
+      digit += 256;
+    }
+    normalized[32 + i] = digit as u8;
+  }
+  normalized[64] ^= 1;
+  normalized
+}
+
+/// Reject a schnorrkel signature whose `s` is not reduced modulo the group
+/// order.
+///
+/// Only signatures carrying schnorrkel's marker bit are checked, others are
+/// left for verification to reject.
+pub(crate) fn check_sr25519_reduced_s(signature: &[u8; 64]) -> Result<(), Error> {
+  if signature[63] & 0x80 == 0 {
+    return Ok(());
+  }
+  let mut s = [0u8; 32];
+  s.copy_from_slice(&signature[32..]);
+  s[31] &= 0x7f;
+
+  // Compare little-endian numbers from the most significant byte down
+  if s.iter().rev().ge(RISTRETTO_ORDER.iter().rev()) {
+    let reason = "sr25519 signature s is not reduced".to_owned();
+    return Err(VerifyError::NonCanonicalSignature { reason }.into());
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::ERR_NON_CANONICAL_SIGNATURE;
+
+  fn array<const N: usize>(hex_str: &str) -> [u8; N] {
+    hex::decode(hex_str).unwrap().try_into().unwrap()
+  }
+
+  #[cfg(feature = "sp-core")]
+  const LOW_S: &str = "6d49a01b12c9ba60e0ffd1638996f9cbcf6c1ca518adb3d44d2f2a68b983eaea3dcc0c125637af13b5bc679068e87afdd35f523efc33a9eb052275edf8ebbf8c00";
+  #[cfg(feature = "sp-core")]
+  const HIGH_S: &str = "6d49a01b12c9ba60e0ffd1638996f9cbcf6c1ca518adb3d44d2f2a68b983eaeac233f3eda9c850ec4a43986f97178500e74f8aa7b314f650baafe89ed74a81b501";
+
+  #[test]
+  #[cfg(feature = "sp-core")]
+  fn test_ecdsa_low_s() {
+    assert!(check_ecdsa_low_s(&array(LOW_S)).is_ok());
+    assert_eq!(check_ecdsa_low_s(&array(HIGH_S)).unwrap_err().code, ERR_NON_CANONICAL_SIGNATURE);
+  }
+
+  #[test]
+  #[cfg(feature = "sp-core")]
+  fn test_normalize_ecdsa() {
+    assert_eq!(normalize_ecdsa(array(HIGH_S)), array(LOW_S));
+    assert_eq!(normalize_ecdsa(array(LOW_S)), array(LOW_S));
+  }
+
+  #[test]
+  fn test_sr25519_reduced_s() {
+    let reduced = "f8ce1b80e76bf48a30610d0d3c2c9c8dbfdcb28a9aa8f56ac038d520ff17445ff3ce94809f8479a1224ef0d823d035022a9db2e72bd6c0d2f0244f766dec908b";
+    // The same s plus the group order
+    let unreduced = "f8ce1b80e76bf48a30610d0d3c2c9c8dbfdcb28a9aa8f56ac038d520ff17445fe0a28addb9e78bf9f8eae77b02ca14172a9db2e72bd6c0d2f0244f766dec909b";
+    assert!(check_sr25519_reduced_s(&array(reduced)).is_ok());
+    assert_eq!(check_sr25519_reduced_s(&array(unreduced)).unwrap_err().code, ERR_NON_CANONICAL_SIGNATURE);
+
+    // Without the marker bit the signature is not schnorrkel's to judge
+    assert!(check_sr25519_reduced_s(&[0xff; 64].map(|b| b & 0x7f)).is_ok());
+  }
+}
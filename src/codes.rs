@@ -0,0 +1,310 @@
+//! Return codes of the exported verification functions, and option flags.
+//!
+//! Non-negative codes are verdicts, negative codes say why no verdict
+//! could be reached. Bindings should mirror these values verbatim. Hosts
+//! that load the library dynamically can read them at runtime from the
+//! `PSV_*` symbols exported at the bottom of this file.
+//!
+//! `VALID`, `INVALID`, the `ERR_*` codes and the `OPT_*` flags are defined
+//! in `polkadot_sig_verifier_core::codes` and re-exported here, so the
+//! browser build reports the same values.
+
+pub use polkadot_sig_verifier_core::codes::*;
+
+// Literal values rather than `SignatureScheme::X as i32`, which cbindgen
+// cannot write; `scheme::tests::test_constants` keeps them in step.
+
+/// No scheme, reported when nothing verified.
+pub const SCHEME_NONE: i32 = 0;
+/// `SignatureScheme::Sr25519`.
+pub const SCHEME_SR25519: i32 = 1;
+/// `SignatureScheme::Ed25519`.
+pub const SCHEME_ED25519: i32 = 2;
+/// `SignatureScheme::Ecdsa`.
+pub const SCHEME_ECDSA: i32 = 3;
+/// `SignatureScheme::Ethereum`.
+pub const SCHEME_ETHEREUM: i32 = 4;
+
+/// No Ledger convention verified.
+pub const LEDGER_NONE: i32 = 0;
+/// ed25519 over the raw message bytes, without `<Bytes>` wrapping.
+pub const LEDGER_RAW: i32 = 1;
+/// ed25519 over `blake2_256` of the message, used by Ledger app versions
+/// whose buffer cannot hold the full payload.
+pub const LEDGER_BLAKE2_256: i32 = 2;
+
+/// No form of the message verified.
+pub const MESSAGE_NONE: i32 = 0;
+/// The message verified exactly as given.
+pub const MESSAGE_AS_GIVEN: i32 = 1;
+/// The message verified after wrapping it in `<Bytes>...</Bytes>`.
+pub const MESSAGE_WRAPPED: i32 = 2;
+/// The message verified after stripping its `<Bytes>...</Bytes>` wrapper.
+pub const MESSAGE_UNWRAPPED: i32 = 3;
+
+/// `verify_stream_new`: the signer signed messages up to 256 bytes
+/// directly and longer ones as their `blake2_256` hash, like polkadot-js.
+pub const STREAM_PREHASH_LONG: i32 = 0;
+/// `verify_stream_new`: the signer signed the `blake2_256` hash of the
+/// message, whatever its length.
+pub const STREAM_PREHASH_ALWAYS: i32 = 1;
+
+/// `AddressInfo::kind` of an address that failed to decode.
+pub const ADDRESS_KIND_NONE: i32 = 0;
+/// `AddressInfo::kind`: a 32-byte account id. This is the public key for
+/// sr25519 and ed25519 and its `blake2_256` hash for ECDSA.
+pub const ADDRESS_KIND_ACCOUNT_ID: i32 = 1;
+/// `AddressInfo::kind`: a 33-byte compressed ECDSA public key.
+pub const ADDRESS_KIND_ECDSA_PUBLIC_KEY: i32 = 2;
+
+/// `verify_extrinsic` and `verify_signing_payload`: the runtime has no
+/// `CheckMetadataHash` signed extension.
+pub const METADATA_HASH_ABSENT: i32 = 0;
+/// `CheckMetadataHash` in mode 0: the signer committed to no metadata hash.
+pub const METADATA_HASH_DISABLED: i32 = 1;
+/// `CheckMetadataHash` in mode 1: the signer committed to the metadata hash
+/// passed alongside.
+pub const METADATA_HASH_ENABLED: i32 = 2;
+/// `verify_extrinsic` only: try the layout without `CheckMetadataHash`,
+/// then the one with the mode the extrinsic carries.
+pub const METADATA_HASH_DETECT: i32 = 3;
+
+/// Export each constant as a `#[no_mangle]` static named `PSV_<name>`.
+macro_rules! export_statics {
+  ($($name:ident = $value:ident: $ty:ty),* $(,)?) => {
+    $(
+      #[doc = concat!(" Runtime-readable copy of [`", stringify!($value), "`].")]
+      #[cfg_attr(feature = "ffi", no_mangle)]
+      pub static $name: $ty = $value;
+    )*
+
+    /// Every exported static as (symbol, static value, constant value).
+    #[cfg(test)]
+    const EXPORTED_STATICS: &[(&str, i64, i64)] = &[$((stringify!($name), $name as i64, $value as i64)),*];
+
+    /// Every exported constant as (name, value).
+    const CONSTANT_NAMES: &[(&str, i64)] = &[$((stringify!($value), $value as i64)),*];
+  };
+}
+
+export_statics! {
+  PSV_VALID = VALID: i32,
+  PSV_INVALID = INVALID: i32,
+  PSV_ERR_NULL_POINTER = ERR_NULL_POINTER: i32,
+  PSV_ERR_INVALID_UTF8 = ERR_INVALID_UTF8: i32,
+  PSV_ERR_BAD_SS58 = ERR_BAD_SS58: i32,
+  PSV_ERR_BAD_HEX = ERR_BAD_HEX: i32,
+  PSV_ERR_BAD_SIGNATURE_LENGTH = ERR_BAD_SIGNATURE_LENGTH: i32,
+  PSV_ERR_PANIC = ERR_PANIC: i32,
+  PSV_ERR_WRONG_NETWORK = ERR_WRONG_NETWORK: i32,
+  PSV_ERR_SS58_BAD_BASE58 = ERR_SS58_BAD_BASE58: i32,
+  PSV_ERR_SS58_BAD_LENGTH = ERR_SS58_BAD_LENGTH: i32,
+  PSV_ERR_SS58_BAD_CHECKSUM = ERR_SS58_BAD_CHECKSUM: i32,
+  PSV_ERR_NO_MATCHING_SCHEME = ERR_NO_MATCHING_SCHEME: i32,
+  PSV_ERR_BAD_MULTISIGNATURE = ERR_BAD_MULTISIGNATURE: i32,
+  PSV_ERR_BAD_SIGNATURE_PREFIX = ERR_BAD_SIGNATURE_PREFIX: i32,
+  PSV_ERR_BAD_ETH_ADDRESS = ERR_BAD_ETH_ADDRESS: i32,
+  PSV_ERR_ETH_ADDRESS_CHECKSUM = ERR_ETH_ADDRESS_CHECKSUM: i32,
+  PSV_ERR_UNKNOWN_SCHEME = ERR_UNKNOWN_SCHEME: i32,
+  PSV_ERR_NON_CANONICAL_SIGNATURE = ERR_NON_CANONICAL_SIGNATURE: i32,
+  PSV_ERR_BAD_MESSAGE_HEX = ERR_BAD_MESSAGE_HEX: i32,
+  PSV_ERR_BAD_BASE64 = ERR_BAD_BASE64: i32,
+  PSV_ERR_INVALID_OPTIONS = ERR_INVALID_OPTIONS: i32,
+  PSV_ERR_NOT_STRICT = ERR_NOT_STRICT: i32,
+  PSV_ERR_INVALID_HANDLE = ERR_INVALID_HANDLE: i32,
+  PSV_ERR_FILE_NOT_FOUND = ERR_FILE_NOT_FOUND: i32,
+  PSV_ERR_FILE_PERMISSION_DENIED = ERR_FILE_PERMISSION_DENIED: i32,
+  PSV_ERR_FILE_IO = ERR_FILE_IO: i32,
+  PSV_ERR_INVALID_UTF16 = ERR_INVALID_UTF16: i32,
+  PSV_ERR_BAD_JSON = ERR_BAD_JSON: i32,
+  PSV_ERR_SIWS_MALFORMED = ERR_SIWS_MALFORMED: i32,
+  PSV_ERR_SIWS_ADDRESS_MISMATCH = ERR_SIWS_ADDRESS_MISMATCH: i32,
+  PSV_ERR_SIWS_DOMAIN_MISMATCH = ERR_SIWS_DOMAIN_MISMATCH: i32,
+  PSV_ERR_SIWS_NONCE_MISMATCH = ERR_SIWS_NONCE_MISMATCH: i32,
+  PSV_ERR_EXPIRED = ERR_EXPIRED: i32,
+  PSV_ERR_NOT_YET_VALID = ERR_NOT_YET_VALID: i32,
+  PSV_ERR_TIMESTAMP_NOT_FOUND = ERR_TIMESTAMP_NOT_FOUND: i32,
+  PSV_ERR_REPLAYED = ERR_REPLAYED: i32,
+  PSV_ERR_BAD_PUBLIC_KEY_LENGTH = ERR_BAD_PUBLIC_KEY_LENGTH: i32,
+  PSV_ERR_BAD_FIRST_ADDRESS = ERR_BAD_FIRST_ADDRESS: i32,
+  PSV_ERR_BAD_SECOND_ADDRESS = ERR_BAD_SECOND_ADDRESS: i32,
+  PSV_ERR_HARD_DERIVATION = ERR_HARD_DERIVATION: i32,
+  PSV_ERR_BAD_DERIVATION_PATH = ERR_BAD_DERIVATION_PATH: i32,
+  PSV_ERR_UNSUPPORTED_KEY_TYPE = ERR_UNSUPPORTED_KEY_TYPE: i32,
+  PSV_ERR_BAD_DID_KEY = ERR_BAD_DID_KEY: i32,
+  PSV_ERR_WRONG_KEY_LENGTH = ERR_WRONG_KEY_LENGTH: i32,
+  PSV_ERR_BAD_SEED_LENGTH = ERR_BAD_SEED_LENGTH: i32,
+  PSV_ERR_BAD_MNEMONIC = ERR_BAD_MNEMONIC: i32,
+  PSV_ERR_UNKNOWN_MNEMONIC_WORD = ERR_UNKNOWN_MNEMONIC_WORD: i32,
+  PSV_ERR_BAD_MNEMONIC_CHECKSUM = ERR_BAD_MNEMONIC_CHECKSUM: i32,
+  PSV_ERR_UNKNOWN_DEV_ACCOUNT = ERR_UNKNOWN_DEV_ACCOUNT: i32,
+  PSV_ERR_BAD_SEED = ERR_BAD_SEED: i32,
+  PSV_ERR_THREAD_POOL = ERR_THREAD_POOL: i32,
+  PSV_ERR_JOB_CANCELLED = ERR_JOB_CANCELLED: i32,
+  PSV_ERR_SCHEME_NOT_SUPPORTED = ERR_SCHEME_NOT_SUPPORTED: i32,
+  PSV_ERR_NOT_SIGNED = ERR_NOT_SIGNED: i32,
+  PSV_ERR_BAD_EXTRINSIC = ERR_BAD_EXTRINSIC: i32,
+  PSV_ERR_BAD_ERA = ERR_BAD_ERA: i32,
+  PSV_ERR_BAD_HEX_LENGTH = ERR_BAD_HEX_LENGTH: i32,
+  PSV_SCHEME_NONE = SCHEME_NONE: i32,
+  PSV_SCHEME_SR25519 = SCHEME_SR25519: i32,
+  PSV_SCHEME_ED25519 = SCHEME_ED25519: i32,
+  PSV_SCHEME_ECDSA = SCHEME_ECDSA: i32,
+  PSV_SCHEME_ETHEREUM = SCHEME_ETHEREUM: i32,
+  PSV_LEDGER_NONE = LEDGER_NONE: i32,
+  PSV_LEDGER_RAW = LEDGER_RAW: i32,
+  PSV_LEDGER_BLAKE2_256 = LEDGER_BLAKE2_256: i32,
+  PSV_MESSAGE_NONE = MESSAGE_NONE: i32,
+  PSV_MESSAGE_AS_GIVEN = MESSAGE_AS_GIVEN: i32,
+  PSV_MESSAGE_WRAPPED = MESSAGE_WRAPPED: i32,
+  PSV_MESSAGE_UNWRAPPED = MESSAGE_UNWRAPPED: i32,
+  PSV_STREAM_PREHASH_LONG = STREAM_PREHASH_LONG: i32,
+  PSV_STREAM_PREHASH_ALWAYS = STREAM_PREHASH_ALWAYS: i32,
+  PSV_ADDRESS_KIND_NONE = ADDRESS_KIND_NONE: i32,
+  PSV_ADDRESS_KIND_ACCOUNT_ID = ADDRESS_KIND_ACCOUNT_ID: i32,
+  PSV_ADDRESS_KIND_ECDSA_PUBLIC_KEY = ADDRESS_KIND_ECDSA_PUBLIC_KEY: i32,
+  PSV_METADATA_HASH_ABSENT = METADATA_HASH_ABSENT: i32,
+  PSV_METADATA_HASH_DISABLED = METADATA_HASH_DISABLED: i32,
+  PSV_METADATA_HASH_ENABLED = METADATA_HASH_ENABLED: i32,
+  PSV_METADATA_HASH_DETECT = METADATA_HASH_DETECT: i32,
+  PSV_OPT_WRAP_BYTES = OPT_WRAP_BYTES: u32,
+  PSV_OPT_STRIP_SCHEME_PREFIX = OPT_STRIP_SCHEME_PREFIX: u32,
+  PSV_OPT_ALLOW_NON_CANONICAL_ECDSA = OPT_ALLOW_NON_CANONICAL_ECDSA: u32,
+  PSV_OPT_MESSAGE_IS_HEX = OPT_MESSAGE_IS_HEX: u32,
+  PSV_OPT_SIGNATURE_BASE64 = OPT_SIGNATURE_BASE64: u32,
+  PSV_OPT_MESSAGE_BASE64 = OPT_MESSAGE_BASE64: u32,
+  PSV_OPT_SKIP_LONG_MESSAGE_HASH = OPT_SKIP_LONG_MESSAGE_HASH: u32,
+  PSV_OPT_NFC_NORMALIZE = OPT_NFC_NORMALIZE: u32,
+}
+
+/// Every code the library can return, used to keep the tables complete.
+#[cfg(test)]
+pub(crate) const ALL_CODES: &[i32] = &[
+  VALID,
+  INVALID,
+  ERR_NULL_POINTER,
+  ERR_INVALID_UTF8,
+  ERR_BAD_SS58,
+  ERR_BAD_HEX,
+  ERR_BAD_SIGNATURE_LENGTH,
+  ERR_PANIC,
+  ERR_WRONG_NETWORK,
+  ERR_SS58_BAD_BASE58,
+  ERR_SS58_BAD_LENGTH,
+  ERR_SS58_BAD_CHECKSUM,
+  ERR_NO_MATCHING_SCHEME,
+  ERR_BAD_MULTISIGNATURE,
+  ERR_BAD_SIGNATURE_PREFIX,
+  ERR_BAD_ETH_ADDRESS,
+  ERR_ETH_ADDRESS_CHECKSUM,
+  ERR_UNKNOWN_SCHEME,
+  ERR_NON_CANONICAL_SIGNATURE,
+  ERR_BAD_MESSAGE_HEX,
+  ERR_BAD_BASE64,
+  ERR_INVALID_OPTIONS,
+  ERR_NOT_STRICT,
+  ERR_INVALID_HANDLE,
+  ERR_FILE_NOT_FOUND,
+  ERR_FILE_PERMISSION_DENIED,
+  ERR_FILE_IO,
+  ERR_INVALID_UTF16,
+  ERR_BAD_JSON,
+  ERR_SIWS_MALFORMED,
+  ERR_SIWS_ADDRESS_MISMATCH,
+  ERR_SIWS_DOMAIN_MISMATCH,
+  ERR_SIWS_NONCE_MISMATCH,
+  ERR_EXPIRED,
+  ERR_NOT_YET_VALID,
+  ERR_TIMESTAMP_NOT_FOUND,
+  ERR_REPLAYED,
+  ERR_BAD_PUBLIC_KEY_LENGTH,
+  ERR_BAD_FIRST_ADDRESS,
+  ERR_BAD_SECOND_ADDRESS,
+  ERR_HARD_DERIVATION,
+  ERR_BAD_DERIVATION_PATH,
+  ERR_UNSUPPORTED_KEY_TYPE,
+  ERR_BAD_DID_KEY,
+  ERR_WRONG_KEY_LENGTH,
+  ERR_BAD_SEED_LENGTH,
+  ERR_BAD_MNEMONIC,
+  ERR_UNKNOWN_MNEMONIC_WORD,
+  ERR_BAD_MNEMONIC_CHECKSUM,
+  ERR_UNKNOWN_DEV_ACCOUNT,
+  ERR_BAD_SEED,
+  ERR_THREAD_POOL,
+  ERR_JOB_CANCELLED,
+  ERR_SCHEME_NOT_SUPPORTED,
+  ERR_NOT_SIGNED,
+  ERR_BAD_EXTRINSIC,
+  ERR_BAD_ERA,
+  ERR_BAD_HEX_LENGTH,
+];
+
+/// The name of a negative `ERR_*` code, such as `"ERR_BAD_HEX"`, for
+/// bindings that report errors by name.
+pub fn error_code_name(code: i32) -> Option<&'static str> {
+  let mut errors = CONSTANT_NAMES.iter().filter(|(name, _)| name.starts_with("ERR_"));
+  errors.find(|&&(_, value)| value == i64::from(code)).map(|&(name, _)| name)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::collections::HashSet;
+
+  #[test]
+  fn test_statics_match_constants() {
+    for &(symbol, exported, constant) in EXPORTED_STATICS {
+      assert_eq!(exported, constant, "{symbol} disagrees with its constant");
+    }
+  }
+
+  #[test]
+  fn test_codes_do_not_collide() {
+    let unique: HashSet<i32> = ALL_CODES.iter().copied().collect();
+    assert_eq!(unique.len(), ALL_CODES.len(), "two codes share a value");
+
+    // Every code is exported, and every exported code is known
+    let exported: HashSet<i32> = EXPORTED_STATICS
+      .iter()
+      .filter(|(symbol, ..)| {
+        ![
+          "PSV_OPT_",
+          "PSV_SCHEME_",
+          "PSV_LEDGER_",
+          "PSV_MESSAGE_",
+          "PSV_STREAM_",
+          "PSV_ADDRESS_KIND_",
+          "PSV_METADATA_HASH_",
+        ]
+        .iter()
+        .any(|prefix| symbol.starts_with(prefix))
+      })
+      .map(|&(_, value, _)| value as i32)
+      .collect();
+    assert_eq!(exported, unique);
+  }
+
+  #[test]
+  fn test_option_flags_are_distinct_bits() {
+    let mut seen = 0u32;
+    for &(symbol, value, _) in EXPORTED_STATICS.iter().filter(|(symbol, ..)| symbol.starts_with("PSV_OPT_")) {
+      let flag = value as u32;
+      assert_eq!(flag.count_ones(), 1, "{symbol} is not a single bit");
+      assert_eq!(seen & flag, 0, "{symbol} reuses a bit");
+      seen |= flag;
+    }
+  }
+
+  #[test]
+  fn test_error_code_names() {
+    for &code in ALL_CODES.iter().filter(|&&code| code < 0) {
+      let name = error_code_name(code).unwrap_or_else(|| panic!("code {code} has no name"));
+      let symbol = format!("PSV_{name}");
+      assert!(EXPORTED_STATICS.iter().any(|&(exported, value, _)| exported == symbol && value == i64::from(code)));
+    }
+    assert_eq!(error_code_name(ERR_BAD_HEX), Some("ERR_BAD_HEX"));
+    assert_eq!(error_code_name(VALID), None);
+    assert_eq!(error_code_name(i32::MIN), None);
+  }
+}
@@ -0,0 +1,255 @@
+//! Secret URI parsing, and soft derivation of sr25519 public keys.
+//!
+//! Soft junctions only need the parent public key, so a child key can be
+//! computed from a registered root address without its secret. Hard
+//! junctions need the secret and are only applied when signing.
+
+use crate::api::VerifyError;
+use crate::error::Error;
+#[cfg(feature = "ffi")]
+use crate::log::{self, LOG_DEBUG};
+#[cfg(feature = "ffi")]
+use crate::options::Options;
+#[cfg(feature = "ffi")]
+use crate::parse::parse_address;
+#[cfg(feature = "ffi")]
+use crate::ss58;
+#[cfg(feature = "ffi")]
+use crate::verify;
+#[cfg(feature = "ffi")]
+use sp_core::crypto::Derive;
+use sp_core::crypto::DeriveJunction;
+#[cfg(feature = "ffi")]
+use sp_core::sr25519::Public;
+
+/// Split a path such as `//hard/soft` into its junctions, each flagged
+/// as hard or not.
+fn scan_path(path: &str) -> Result<Vec<(bool, &str)>, Error> {
+  let malformed = |reason: &str| -> Error {
+    VerifyError::InvalidDerivationPath { reason: format!("derivation path {path:?} {reason}") }.into()
+  };
+
+  let mut junctions = Vec::new();
+  let mut rest = path;
+  while !rest.is_empty() {
+    let junction = rest.strip_prefix('/').ok_or_else(|| malformed("does not start with /"))?;
+    let (hard, junction) = match junction.strip_prefix('/') {
+      Some(junction) => (true, junction),
+      None => (false, junction),
+    };
+    let (code, tail) = junction.split_at(junction.find('/').unwrap_or(junction.len()));
+    if code.is_empty() {
+      return Err(malformed("has an empty junction"));
+    }
+    junctions.push((hard, code));
+    rest = tail;
+  }
+  Ok(junctions)
+}
+
+/// A secret URI such as `<mnemonic>//polkadot/0///password`, split the way
+/// `sp_core`'s `Pair::from_string` splits it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Suri<'a> {
+  /// Everything before the first `/`: a mnemonic, a hex seed, or empty.
+  pub phrase: &'a str,
+  /// Hard and soft junctions, in order. Numeric junctions are encoded as a
+  /// SCALE `u64`, others as a SCALE string, and either is replaced by its
+  /// `blake2_256` hash if longer than 32 bytes, as `DeriveJunction` does.
+  pub junctions: Vec<DeriveJunction>,
+  /// Everything after the first `///`, slashes included.
+  pub password: Option<&'a str>,
+}
+
+/// Split a secret URI into phrase, junctions and password.
+///
+/// Errors name the junctions only, never the phrase or password.
+pub(crate) fn parse_suri(suri: &str) -> Result<Suri<'_>, Error> {
+  let (phrase, rest) = suri.split_at(suri.find('/').unwrap_or(suri.len()));
+  let (path, password) = match rest.find("///") {
+    Some(index) => (&rest[..index], Some(&rest[index + 3..])),
+    None => (rest, None),
+  };
+  let junctions = scan_path(path)?.into_iter().map(|(hard, code)| {
+    let junction = DeriveJunction::from(code);
+    if hard { junction.harden() } else { junction }
+  });
+  Ok(Suri { phrase, junctions: junctions.collect(), password })
+}
+
+/// Parse the path part of a secret URI, such as `//polkadot/0///password`,
+/// given without its phrase. The empty path has no junctions.
+pub(crate) fn parse_suri_path(path: &str) -> Result<Suri<'_>, Error> {
+  let suri = parse_suri(path)?;
+  if !suri.phrase.is_empty() {
+    // The text before the first / may be a secret, so it is not echoed
+    let reason = "derivation path does not start with /".to_owned();
+    return Err(VerifyError::InvalidDerivationPath { reason }.into());
+  }
+  Ok(suri)
+}
+
+/// Parse a path of soft junctions such as `/payments/0`, rejecting hard
+/// junctions and passwords, which need the secret key.
+#[cfg(feature = "ffi")]
+pub(crate) fn parse_soft_path(path: &str) -> Result<Vec<DeriveJunction>, Error> {
+  let mut junctions = Vec::new();
+  for (hard, code) in scan_path(path)? {
+    if hard {
+      let reason = format!("derivation path {path:?} has the hard junction //{code}, which needs the secret key");
+      return Err(VerifyError::HardDerivation { reason }.into());
+    }
+    junctions.push(DeriveJunction::from(code));
+  }
+  Ok(junctions)
+}
+
+/// Apply the soft junctions of `path` to an sr25519 public key.
+#[cfg(feature = "ffi")]
+pub(crate) fn derive_public_key(public_key: [u8; 32], path: &str) -> Result<[u8; 32], Error> {
+  let junctions = parse_soft_path(path)?;
+  log::log(LOG_DEBUG, || format!("deriving along {} soft junctions", junctions.len()));
+  let child = Public::from_raw(public_key)
+    .derive(junctions.into_iter())
+    .ok_or_else(|| VerifyError::InvalidAddress { reason: "address is not a valid sr25519 public key".to_owned() })?;
+  let mut raw = [0u8; 32];
+  raw.copy_from_slice(child.as_ref());
+  Ok(raw)
+}
+
+/// Derive the soft child `path` of `parent`, an SS58 address or hex
+/// public key, in the parent's form.
+#[cfg(feature = "ffi")]
+pub(crate) fn derive_child(parent: &str, path: &str) -> Result<String, Error> {
+  let address = parse_address(parent)?;
+  let child = derive_public_key(address.public_key()?, path)?;
+  Ok(match address.prefix {
+    Some(prefix) => ss58::encode(&child, prefix),
+    None => format!("0x{}", hex::encode(child)),
+  })
+}
+
+/// Verify an SR25519 signature made by the soft child `path` of `root`,
+/// or by `root` itself for the empty path.
+#[cfg(feature = "ffi")]
+pub(crate) fn verify_with_derivation(root: &str, path: &str, signature: &str, message: &[u8]) -> Result<bool, Error> {
+  let child = if path.is_empty() { root.to_owned() } else { derive_child(root, path)? };
+  let outcome = verify::verify_sr25519(&child, signature, message, &Options::default())?;
+  Ok(outcome.valid == 1)
+}
+
+#[cfg(all(test, feature = "ffi"))]
+mod tests {
+  use super::*;
+  use crate::{ERR_BAD_DERIVATION_PATH, ERR_HARD_DERIVATION, INVALID, VALID};
+  use sp_core::crypto::Ss58Codec;
+  use sp_core::{sr25519, Pair};
+
+  fn derive(parent: &str, path: &str) -> Result<String, Error> {
+    derive_child(parent, path)
+  }
+
+  fn code(path: &str) -> i32 {
+    parse_soft_path(path).unwrap_err().code
+  }
+
+  #[test]
+  fn test_matches_secret_derivation() {
+    let root = sr25519::Pair::from_string("//Alice", None).unwrap();
+    for path in ["/payments", "/payments/0", "/1/2/3", "/0x01"] {
+      let (child, _) = root.derive(parse_soft_path(path).unwrap().into_iter(), None).unwrap();
+      assert_eq!(derive(&root.public().to_ss58check(), path).unwrap(), child.public().to_ss58check(), "{path}");
+
+      let root_hex = format!("0x{}", hex::encode(root.public()));
+      assert_eq!(derive(&root_hex, path).unwrap(), format!("0x{}", hex::encode(child.public())), "{path}");
+    }
+  }
+
+  #[test]
+  fn test_matches_uri_derivation() {
+    let child = sr25519::Pair::from_string("//Alice/payments/7", None).unwrap();
+    let root = sr25519::Pair::from_string("//Alice", None).unwrap();
+    assert_eq!(derive(&root.public().to_ss58check(), "/payments/7").unwrap(), child.public().to_ss58check());
+  }
+
+  #[test]
+  fn test_keeps_prefix() {
+    let polkadot = "16Y8x28cS6LYFu3v8KViD2sKjTdNbBZf8sr6bUtaS7iuRDs5";
+    let child = derive(polkadot, "/x").unwrap();
+    assert_eq!(crate::parse::ss58_prefix(&child), Ok(0));
+    assert_eq!(derive(polkadot, "").unwrap(), polkadot);
+  }
+
+  #[test]
+  fn test_hard_junctions() {
+    assert_eq!(code("//0"), ERR_HARD_DERIVATION);
+    assert_eq!(code("/payments//0"), ERR_HARD_DERIVATION);
+    assert_eq!(code("//Alice/soft"), ERR_HARD_DERIVATION);
+
+    let message = derive("5HbqogsYaK54pN3QAgSi4t3Asqditt1X4P7cSBuDt2hPF2BR", "//0").unwrap_err().message;
+    assert!(message.contains("hard junction"), "{message}");
+  }
+
+  #[test]
+  fn test_malformed_paths() {
+    assert_eq!(code("payments"), ERR_BAD_DERIVATION_PATH);
+    assert_eq!(code("/"), ERR_BAD_DERIVATION_PATH);
+    assert_eq!(code("/payments/"), ERR_BAD_DERIVATION_PATH);
+    assert_eq!(code("///x"), ERR_BAD_DERIVATION_PATH);
+    assert_eq!(parse_soft_path(""), Ok(Vec::new()));
+  }
+
+  #[test]
+  fn test_parse_suri() {
+    let suri = parse_suri("bottom drive obey//polkadot/0///pass/word").unwrap();
+    assert_eq!(suri.phrase, "bottom drive obey");
+    assert_eq!(suri.junctions, vec![DeriveJunction::hard("polkadot"), DeriveJunction::soft(0u64)]);
+    assert_eq!(suri.password, Some("pass/word"));
+
+    let suri = parse_suri("///secret").unwrap();
+    assert_eq!((suri.phrase, suri.junctions.len(), suri.password), ("", 0, Some("secret")));
+    assert_eq!(parse_suri("0x1234").unwrap().phrase, "0x1234");
+  }
+
+  #[test]
+  fn test_parse_suri_path() {
+    assert_eq!(parse_suri_path("").unwrap().junctions, Vec::new());
+    assert_eq!(parse_suri_path("//1/a").unwrap().junctions, vec![DeriveJunction::hard(1u64), DeriveJunction::soft("a")]);
+    assert_eq!(parse_suri_path("//Alice///").unwrap().password, Some(""));
+
+    for path in ["Alice", "Alice///hunter2", "/", "//", "//Alice/", "/a//"] {
+      let error = parse_suri_path(path).unwrap_err();
+      assert_eq!(error.code, ERR_BAD_DERIVATION_PATH, "{path}");
+      assert!(!error.message.contains("hunter2"), "{}", error.message);
+    }
+  }
+
+  const ADDRESS: &str = "5HbqogsYaK54pN3QAgSi4t3Asqditt1X4P7cSBuDt2hPF2BR";
+  const SIGNATURE: &str = "0xf8ce1b80e76bf48a30610d0d3c2c9c8dbfdcb28a9aa8f56ac038d520ff17445ff3ce94809f8479a1224ef0d823d035022a9db2e72bd6c0d2f0244f766dec908b";
+  const MESSAGE: &str = "<Bytes>c15335d817e3d3d912d30f5a18a30c30162abc364cd0876d3a94af568d3c8c14</Bytes>";
+
+  fn verify_derived(root: &str, path: &str, signature: &str, message: &str) -> i32 {
+    verify::verdict(verify_with_derivation(root, path, signature, message.as_bytes()))
+  }
+
+  #[test]
+  fn test_verify_with_derivation() {
+    let root = sr25519::Pair::from_string("//Alice", None).unwrap();
+    let child = sr25519::Pair::from_string("//Alice/payments/0", None).unwrap();
+    let signature = hex::encode(child.sign(b"pay 10 DOT"));
+    let root_address = root.public().to_ss58check();
+
+    assert_eq!(verify_derived(&root_address, "/payments/0", &signature, "pay 10 DOT"), VALID);
+    assert_eq!(verify_derived(&root_address, "/payments/1", &signature, "pay 10 DOT"), INVALID);
+    assert_eq!(verify_derived(&root_address, "/payments", &signature, "pay 10 DOT"), INVALID);
+    assert_eq!(verify_derived(&root_address, "", &signature, "pay 10 DOT"), INVALID);
+    assert_eq!(verify_derived(&root_address, "/payments/0", &signature, "pay 11 DOT"), INVALID);
+  }
+
+  #[test]
+  fn test_verify_with_derivation_bad_paths() {
+    assert_eq!(verify_derived(ADDRESS, "//payments", SIGNATURE, MESSAGE), ERR_HARD_DERIVATION);
+    assert_eq!(verify_derived(ADDRESS, "payments", SIGNATURE, MESSAGE), ERR_BAD_DERIVATION_PATH);
+    assert_eq!(verify_derived(ADDRESS, "/payments/", SIGNATURE, MESSAGE), ERR_BAD_DERIVATION_PATH);
+  }
+}
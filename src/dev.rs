@@ -0,0 +1,75 @@
+//! The well-known development accounts, for generating test vectors.
+//!
+//! Their secrets derive from the public `DEV_PHRASE`, so anything they sign
+//! proves nothing outside a test.
+
+use crate::api::VerifyError;
+use crate::error::Error;
+use crate::parse::{check_ss58_prefix, encode_ss58};
+use crate::scheme::SignatureScheme;
+use sp_core::crypto::AccountId32;
+use sp_core::hashing::blake2_256;
+use sp_core::{ecdsa, ed25519, sr25519, Pair};
+
+/// The dev accounts, each derived along `//<name>`.
+const DEV_ACCOUNTS: [&str; 6] = ["Alice", "Bob", "Charlie", "Dave", "Eve", "Ferdie"];
+
+/// The key of the dev account `name`, ignoring ASCII case.
+fn dev_pair<P: Pair>(name: &str) -> Result<P, Error> {
+  let account = DEV_ACCOUNTS.iter().find(|account| account.eq_ignore_ascii_case(name)).ok_or_else(|| {
+    VerifyError::UnknownDevAccount { reason: format!("unknown dev account {name:?}, expected one of {DEV_ACCOUNTS:?}") }
+  })?;
+  Ok(P::from_string(&format!("//{account}"), None).expect("dev accounts derive from DEV_PHRASE"))
+}
+
+fn not_ss58() -> Error {
+  VerifyError::UnknownScheme { reason: "ethereum addresses are not SS58".to_owned() }.into()
+}
+
+/// The account id of a dev account: the public key itself, or its
+/// `blake2_256` hash for ECDSA.
+fn dev_account_id(name: &str, scheme: SignatureScheme) -> Result<AccountId32, Error> {
+  Ok(match scheme {
+    SignatureScheme::Sr25519 => dev_pair::<sr25519::Pair>(name)?.public().into(),
+    SignatureScheme::Ed25519 => dev_pair::<ed25519::Pair>(name)?.public().into(),
+    SignatureScheme::Ecdsa => blake2_256(dev_pair::<ecdsa::Pair>(name)?.public().as_ref()).into(),
+    SignatureScheme::Ethereum => return Err(not_ss58()),
+  })
+}
+
+/// Sign `message` as a dev account, returning the 0x-prefixed hex signature.
+pub(crate) fn dev_sign(name: &str, scheme: SignatureScheme, message: &[u8]) -> Result<String, Error> {
+  let signature = match scheme {
+    SignatureScheme::Sr25519 => hex::encode(dev_pair::<sr25519::Pair>(name)?.sign(message)),
+    SignatureScheme::Ed25519 => hex::encode(dev_pair::<ed25519::Pair>(name)?.sign(message)),
+    SignatureScheme::Ecdsa => hex::encode(dev_pair::<ecdsa::Pair>(name)?.sign(message)),
+    SignatureScheme::Ethereum => return Err(not_ss58()),
+  };
+  Ok(format!("0x{signature}"))
+}
+
+/// The SS58 address of a dev account under `prefix`.
+pub(crate) fn dev_address(name: &str, scheme: SignatureScheme, prefix: u16) -> Result<String, Error> {
+  check_ss58_prefix(prefix)?;
+  encode_ss58(dev_account_id(name, scheme)?.as_ref(), prefix)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{api, ERR_UNKNOWN_DEV_ACCOUNT, ERR_UNKNOWN_SCHEME};
+
+  #[test]
+  fn test_alice() {
+    let alice = dev_address("alice", SignatureScheme::Sr25519, 42).unwrap();
+    assert_eq!(alice, "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY");
+    let signature = dev_sign("Alice", SignatureScheme::Sr25519, b"hello polkadot").unwrap();
+    assert!(api::verify(&alice, &signature, b"hello polkadot").is_ok());
+  }
+
+  #[test]
+  fn test_unknown() {
+    assert_eq!(dev_pair::<sr25519::Pair>("Mallory").err().unwrap().code, ERR_UNKNOWN_DEV_ACCOUNT);
+    assert_eq!(dev_address("Alice", SignatureScheme::Ethereum, 42).unwrap_err().code, ERR_UNKNOWN_SCHEME);
+  }
+}
@@ -0,0 +1,175 @@
+//! `did:key` signer identifiers.
+//!
+//! A `did:key` is `did:key:z` followed by the base58btc encoding of a
+//! multicodec key type, as an unsigned varint, and the public key. The key
+//! type selects the signature scheme, unlike SS58 addresses.
+
+use crate::api::VerifyError;
+use crate::error::Error;
+use crate::message::blake2_256;
+use crate::options::Options;
+use crate::ss58::{self, Failure};
+use crate::verify;
+use crate::SignatureScheme;
+
+const PREFIX: &str = "did:key:z";
+/// Multicodec `ed25519-pub`.
+const ED25519_PUB: u64 = 0xed;
+/// Multicodec `sr25519-pub`.
+const SR25519_PUB: u64 = 0xef;
+/// Multicodec `secp256k1-pub`, a compressed public key.
+const SECP256K1_PUB: u64 = 0xe7;
+/// Varint of at most three bytes plus a 33-byte key.
+const MAX_DECODED_LEN: usize = 3 + 33;
+
+fn malformed(reason: impl Into<String>) -> Error {
+  VerifyError::InvalidDidKey { reason: reason.into() }.into()
+}
+
+/// Read the unsigned varint at the start of `bytes`, returning it and its
+/// length.
+fn read_varint(bytes: &[u8]) -> Option<(u64, usize)> {
+  let mut value = 0u64;
+  for (i, &byte) in bytes.iter().enumerate().take(9) {
+    value |= u64::from(byte & 0x7f) << (7 * i);
+    if byte & 0x80 == 0 {
+      return Some((value, i + 1));
+    }
+  }
+  None
+}
+
+/// Decode a `did:key` into its signature scheme and account id: the public
+/// key for sr25519 and ed25519, and `blake2_256` of the compressed key for
+/// secp256k1, as for Substrate ECDSA accounts.
+///
+/// A `#` fragment naming the verification method is ignored.
+pub(crate) fn parse_did_key(did: &str) -> Result<(SignatureScheme, [u8; 32]), Error> {
+  let did = did.split_once('#').map_or(did, |(did, _)| did);
+  let encoded = did
+    .strip_prefix(PREFIX)
+    .ok_or_else(|| malformed(format!("{did:?} does not start with {PREFIX}")))?;
+
+  let mut data = [0u8; MAX_DECODED_LEN];
+  let len = ss58::decode_base58(encoded, &mut data).map_err(|failure| match failure {
+    Failure::BadCharacter { index, c } => {
+      malformed(format!("did:key has invalid base58 character {c:?} at position {}", PREFIX.len() + index))
+    }
+    _ => malformed(format!("did:key decodes to more than {MAX_DECODED_LEN} bytes")),
+  })?;
+  let data = &data[..len];
+
+  let (codec, codec_len) = read_varint(data).ok_or_else(|| malformed("did:key has no multicodec key type"))?;
+  let (scheme, key_len) = match codec {
+    SR25519_PUB => (SignatureScheme::Sr25519, 32),
+    ED25519_PUB => (SignatureScheme::Ed25519, 32),
+    SECP256K1_PUB => (SignatureScheme::Ecdsa, 33),
+    other => {
+      let reason = format!("did:key has unsupported multicodec key type {other:#x}");
+      return Err(VerifyError::UnsupportedKeyType { reason }.into());
+    }
+  };
+
+  let key = &data[codec_len..];
+  if key.len() != key_len {
+    return Err(malformed(format!("did:key {scheme:?} key is {} bytes, expected {key_len}", key.len())));
+  }
+  let account_id = match scheme {
+    SignatureScheme::Ecdsa => blake2_256(key),
+    _ => key.try_into().expect("length checked above"),
+  };
+  Ok((scheme, account_id))
+}
+
+/// Verify a signature by the signer `did` identifies, in the scheme its
+/// key type selects.
+pub(crate) fn verify_by_did_key(did: &str, signature: &str, message: &[u8]) -> Result<bool, Error> {
+  let (scheme, account_id) = parse_did_key(did)?;
+  let outcome = verify::verify_key(scheme, account_id, signature, message, &Options::default())?;
+  Ok(outcome.valid == 1)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{ERR_BAD_DID_KEY, ERR_UNSUPPORTED_KEY_TYPE, INVALID, VALID};
+
+  // did:keys of the sr25519 test vector's public key and the ed25519 test
+  // key, built with the `sr25519-pub` and `ed25519-pub` multicodecs
+  const SR25519_DID: &str = "did:key:z6QP2VWUW6pmp7CBUv5r6TcG17BVGu8uvX96Vzfz8RAPGVnw";
+  const ED25519_DID: &str = "did:key:z6MkofWExWkUvTZeXb9TmLta5mBT6Qtj58es5Fqg1L5BCWQD";
+  const PUBLIC_KEY_HEX: &str = "f4ec883f28db136cd98e3b553cab48ab4671111978a4660f129a45cb4df84d30";
+  const SIGNATURE: &str = "0xf8ce1b80e76bf48a30610d0d3c2c9c8dbfdcb28a9aa8f56ac038d520ff17445ff3ce94809f8479a1224ef0d823d035022a9db2e72bd6c0d2f0244f766dec908b";
+  const ED25519_SIGNATURE: &str = "0xabd163f9ffa6334632220e353ca548fe66d65456413b69c1241e551dcdf1efba1f2f8a412a4243c6c389b3445997b8cf80e4e8a275e64bb714644a3b069d1d0d";
+  const MESSAGE: &str = "<Bytes>c15335d817e3d3d912d30f5a18a30c30162abc364cd0876d3a94af568d3c8c14</Bytes>";
+
+  fn verify(did: &str, signature: &str, message: &str) -> i32 {
+    verify::verdict(verify_by_did_key(did, signature, message.as_bytes()))
+  }
+
+  #[test]
+  fn test_parse_did_key() {
+    let (scheme, public_key) = parse_did_key(SR25519_DID).unwrap();
+    assert_eq!(scheme, SignatureScheme::Sr25519);
+    assert_eq!(hex::encode(public_key), PUBLIC_KEY_HEX);
+    assert_eq!(parse_did_key(&format!("{SR25519_DID}#{}", &SR25519_DID[8..])), Ok((scheme, public_key)));
+    assert_eq!(parse_did_key(ED25519_DID).unwrap().0, SignatureScheme::Ed25519);
+  }
+
+  #[test]
+  fn test_verify_by_did_key() {
+    assert_eq!(verify(SR25519_DID, SIGNATURE, MESSAGE), VALID);
+    assert_eq!(verify(SR25519_DID, SIGNATURE, "tampered"), INVALID);
+    #[cfg(feature = "sp-core")]
+    assert_eq!(verify(ED25519_DID, ED25519_SIGNATURE, MESSAGE), VALID);
+  }
+
+  #[test]
+  fn test_mismatched_scheme() {
+    // The sr25519 test key relabelled as ed25519 does not verify an
+    // sr25519 signature
+    let relabelled = "did:key:z6MkvwLiNDwdKwejSh9ynciUx6WTL3fSVxzgsvHVSnXQromh";
+    assert_eq!(parse_did_key(relabelled).unwrap(), (SignatureScheme::Ed25519, parse_did_key(SR25519_DID).unwrap().1));
+    #[cfg(feature = "sp-core")]
+    assert_eq!(verify(relabelled, SIGNATURE, MESSAGE), INVALID);
+    assert_eq!(verify(SR25519_DID, ED25519_SIGNATURE, MESSAGE), INVALID);
+  }
+
+  #[test]
+  #[cfg(feature = "ecdsa")]
+  fn test_secp256k1_did_key() {
+    use crate::ERR_BAD_SIGNATURE_LENGTH;
+    use sp_core::{ecdsa, Pair};
+
+    // `secp256k1-pub` did:key of the //Alice ECDSA key
+    let did = "did:key:zQ3shN65V8Q6vu9N89sa9HNd51gRVRySP5xTNgWUHaHGMCizU";
+    let pair = ecdsa::Pair::from_string("//Alice", None).unwrap();
+    let (scheme, account_id) = parse_did_key(did).unwrap();
+    assert_eq!(scheme, SignatureScheme::Ecdsa);
+    assert_eq!(account_id, blake2_256(pair.public().as_ref()));
+
+    let signature = hex::encode(pair.sign(MESSAGE.as_bytes()));
+    assert_eq!(verify(did, &signature, MESSAGE), VALID);
+    assert_eq!(verify(did, SIGNATURE, MESSAGE), ERR_BAD_SIGNATURE_LENGTH);
+  }
+
+  #[test]
+  fn test_unsupported_key_type() {
+    // A P-256 key, multicodec 0x1200
+    let p256 = "did:key:zDnaegv3iQb24JyBG6XCmRvDc9kyk4GoNLsQf6raeuAzrMGXy";
+    assert_eq!(parse_did_key(p256).unwrap_err().code, ERR_UNSUPPORTED_KEY_TYPE);
+    assert_eq!(verify(p256, SIGNATURE, MESSAGE), ERR_UNSUPPORTED_KEY_TYPE);
+  }
+
+  #[test]
+  fn test_malformed() {
+    let code = |did: &str| parse_did_key(did).unwrap_err().code;
+    assert_eq!(code("did:web:example.com"), ERR_BAD_DID_KEY);
+    assert_eq!(code("did:key:f01ef"), ERR_BAD_DID_KEY);
+    assert_eq!(code("did:key:z6QP0"), ERR_BAD_DID_KEY);
+    assert_eq!(code("did:key:z"), ERR_BAD_DID_KEY);
+    // A 31-byte sr25519 key
+    assert_eq!(code("did:key:z2E11LbmkJBNUthbYhaGewWjpbw2sjAx3A6Td2HY4wsA88t"), ERR_BAD_DID_KEY);
+    assert_eq!(code(&format!("{SR25519_DID}{}", &SR25519_DID[PREFIX.len()..])), ERR_BAD_DID_KEY);
+  }
+}
@@ -0,0 +1,107 @@
+//! Failure reporting shared by the exported functions.
+//!
+//! Every failure carries one of the [`codes`](crate::codes) and a
+//! human-readable message. The message of the most recent failure is kept
+//! in a thread-local slot so concurrent callers never see each other's
+//! errors.
+
+use crate::api::VerifyError;
+use std::cell::RefCell;
+use std::ffi::CString;
+#[cfg(feature = "ffi")]
+use std::ffi::c_char;
+
+/// A failed call: the code returned over FFI plus a description.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Error {
+  pub code: i32,
+  pub message: String,
+  /// The failure as the Rust API reports it. `code` is derived from it, and
+  /// so is `message` unless a more detailed one replaces it.
+  pub detail: VerifyError,
+}
+
+impl From<VerifyError> for Error {
+  fn from(error: VerifyError) -> Self {
+    Error { code: i32::from(&error), message: error.to_string(), detail: error }
+  }
+}
+
+thread_local! {
+  static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Record `error` as the most recent failure on this thread.
+#[cfg(feature = "ffi")]
+pub(crate) fn set_last_error(error: &Error) {
+  // Interior NULs cannot be represented, drop them rather than the message
+  let message = CString::new(error.message.replace('\0', ""))
+    .expect("NUL bytes were removed");
+  LAST_ERROR.with(|slot| *slot.borrow_mut() = Some(message));
+}
+
+/// Call `f` with the message of the most recent failure on this thread.
+#[cfg(feature = "ffi")]
+pub(crate) fn with_last_error<T>(f: impl FnOnce(Option<&str>) -> T) -> T {
+  LAST_ERROR.with(|slot| f(slot.borrow().as_ref().and_then(|message| message.to_str().ok())))
+}
+
+/// The most recent failure on this thread as a C string, or NULL. The
+/// pointer stays valid until the slot next changes.
+#[cfg(feature = "ffi")]
+pub(crate) fn last_error_ptr() -> *const c_char {
+  LAST_ERROR.with(|slot| match slot.borrow().as_ref() {
+    Some(message) => message.as_ptr(),
+    None => std::ptr::null(),
+  })
+}
+
+/// Forget the most recent failure on this thread.
+#[cfg(feature = "ffi")]
+pub(crate) fn clear_last_error() {
+  LAST_ERROR.with(|slot| *slot.borrow_mut() = None);
+}
+
+#[cfg(all(test, feature = "ffi"))]
+mod tests {
+  use super::*;
+
+  fn last_error() -> Option<String> {
+    with_last_error(|message| message.map(str::to_owned))
+  }
+
+  fn failure(message: &str) -> Error {
+    VerifyError::InvalidJson { reason: message.to_owned() }.into()
+  }
+
+  #[test]
+  fn test_set_and_clear() {
+    clear_last_error();
+    assert_eq!(last_error(), None);
+
+    set_last_error(&failure("first"));
+    set_last_error(&failure("second"));
+    assert_eq!(last_error().as_deref(), Some("second"));
+
+    clear_last_error();
+    assert_eq!(last_error(), None);
+  }
+
+  #[test]
+  fn test_thread_local() {
+    set_last_error(&failure("main thread"));
+    std::thread::spawn(|| {
+      assert_eq!(last_error(), None);
+      set_last_error(&failure("worker thread"));
+    })
+    .join()
+    .unwrap();
+    assert_eq!(last_error().as_deref(), Some("main thread"));
+  }
+
+  #[test]
+  fn test_interior_nul() {
+    set_last_error(&failure("bad\0input"));
+    assert_eq!(last_error().as_deref(), Some("badinput"));
+  }
+}
@@ -0,0 +1,135 @@
+//! Ethereum-style `personal_sign` verification for AccountId20 chains such
+//! as Moonbeam.
+
+use crate::canonical::check_ecdsa_low_s;
+use crate::api::VerifyError;
+use crate::error::Error;
+use crate::options::Options;
+use crate::parse::{decode_signature, signature_array};
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use sp_core::ecdsa;
+use sp_core::hashing::keccak_256;
+
+/// Parse a 20-byte address, with or without 0x prefix.
+///
+/// All-lowercase and all-uppercase addresses are accepted as is; mixed case
+/// must carry a valid EIP-55 checksum.
+fn parse_h160(address: &str) -> Result<[u8; 20], Error> {
+  let digits = address.strip_prefix("0x").unwrap_or(address);
+  let mut h160 = [0u8; 20];
+  hex::decode_to_slice(digits, &mut h160)
+    .map_err(|e| VerifyError::InvalidEthereumAddress { reason: format!("address is not 20 hex-encoded bytes: {e}") })?;
+
+  let has_lower = digits.bytes().any(|b| b.is_ascii_lowercase());
+  let has_upper = digits.bytes().any(|b| b.is_ascii_uppercase());
+  if has_lower && has_upper && digits != eip55_digits(&h160) {
+    return Err(VerifyError::EthereumChecksumMismatch.into());
+  }
+  Ok(h160)
+}
+
+/// Hex digits of `h160` with EIP-55 checksum casing, without 0x.
+fn eip55_digits(h160: &[u8; 20]) -> String {
+  let lower = hex::encode(h160);
+  let hash = keccak_256(lower.as_bytes());
+  lower
+    .chars()
+    .enumerate()
+    .map(|(i, c)| {
+      let nibble = (hash[i / 2] >> (if i % 2 == 0 { 4 } else { 0 })) & 0x0f;
+      if nibble >= 8 { c.to_ascii_uppercase() } else { c }
+    })
+    .collect()
+}
+
+/// `keccak_256` of `message` with the `personal_sign` prefix applied.
+fn personal_sign_hash(message: &[u8]) -> [u8; 32] {
+  let mut prefixed = format!("\x19Ethereum Signed Message:\n{}", message.len()).into_bytes();
+  prefixed.extend_from_slice(message);
+  keccak_256(&prefixed)
+}
+
+/// Recover the signer of a `personal_sign` signature as an H160.
+///
+/// Accepts recovery ids as 0/1 or Ethereum's 27/28. Returns `None` for
+/// signatures that recover nothing.
+fn recover_h160(signature: [u8; 65], message: &[u8]) -> Option<[u8; 20]> {
+  let mut normalized = signature;
+  if normalized[64] >= 27 {
+    normalized[64] -= 27;
+  }
+  let compressed = ecdsa::Signature::from_raw(normalized).recover_prehashed(&personal_sign_hash(message))?;
+
+  let public_key = k256::PublicKey::from_sec1_bytes(compressed.as_ref()).ok()?;
+  let uncompressed = public_key.to_encoded_point(false);
+  // Skip the 0x04 SEC1 tag, the address is the tail of the hash of x ++ y
+  let hash = keccak_256(&uncompressed.as_bytes()[1..]);
+
+  let mut h160 = [0u8; 20];
+  h160.copy_from_slice(&hash[12..]);
+  Some(h160)
+}
+
+/// Verify a `personal_sign` signature against an H160 address.
+///
+/// Signatures whose `s` is in the upper half of the curve order are
+/// rejected as malleable, as required by EIP-2.
+pub(crate) fn verify_ethereum(address: &str, signature_hex: &str, message: &[u8]) -> Result<bool, Error> {
+  let h160 = parse_h160(address)?;
+  let signature = signature_array::<65>(&decode_signature(signature_hex, &Options::default())?)?;
+  check_ecdsa_low_s(&signature)?;
+  Ok(recover_h160(signature, message) == Some(h160))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{ERR_BAD_ETH_ADDRESS, ERR_ETH_ADDRESS_CHECKSUM, ERR_NON_CANONICAL_SIGNATURE};
+
+  // Moonbeam's "Alith" dev account
+  const ALITH: &str = "0xf24FF3a9CF04c71Dbc94D0b566f7A27B94566cac";
+  const MESSAGE: &[u8] = b"polkadot-signature-verifier login challenge";
+  const SIGNATURE: &str = "0xe32c94c88261e68cc12089bc7fd87f2e55ebc24343c69078357d0a6a890407d23d77547af130b710042f7fecfb325e0c1b8212cd707b50b44051ce607d55b38a1c";
+  // The same signature with s replaced by n - s and the recovery id flipped
+  const HIGH_S_SIGNATURE: &str = "0xe32c94c88261e68cc12089bc7fd87f2e55ebc24343c69078357d0a6a890407d2c288ab850ecf48effbd0801304cda1f29f2cca193ecd4f877f80902c52e08db71b";
+
+  #[test]
+  fn test_known_answer() {
+    assert_eq!(verify_ethereum(ALITH, SIGNATURE, MESSAGE), Ok(true));
+    assert_eq!(verify_ethereum(ALITH, SIGNATURE, b"tampered"), Ok(false));
+  }
+
+  #[test]
+  fn test_address_forms() {
+    let lower = ALITH.to_ascii_lowercase();
+    assert_eq!(verify_ethereum(&lower, SIGNATURE, MESSAGE), Ok(true));
+    assert_eq!(verify_ethereum(&lower[2..], SIGNATURE, MESSAGE), Ok(true));
+    assert_eq!(verify_ethereum(&format!("0x{}", lower[2..].to_ascii_uppercase()), SIGNATURE, MESSAGE), Ok(true));
+  }
+
+  #[test]
+  fn test_bad_checksum() {
+    // Lowercase one checksummed letter
+    let mistyped = ALITH.replacen("FF", "Ff", 1);
+    assert_eq!(verify_ethereum(&mistyped, SIGNATURE, MESSAGE).unwrap_err().code, ERR_ETH_ADDRESS_CHECKSUM);
+    assert_eq!(eip55_digits(&parse_h160(ALITH).unwrap()), ALITH[2..]);
+  }
+
+  #[test]
+  fn test_bad_address() {
+    assert_eq!(verify_ethereum("0x1234", SIGNATURE, MESSAGE).unwrap_err().code, ERR_BAD_ETH_ADDRESS);
+    assert_eq!(verify_ethereum("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY", SIGNATURE, MESSAGE).unwrap_err().code, ERR_BAD_ETH_ADDRESS);
+  }
+
+  #[test]
+  fn test_recovery_id_forms() {
+    // 27/28 and 0/1 recovery ids are equivalent
+    let zero_based = format!("{}01", &SIGNATURE[..SIGNATURE.len() - 2]);
+    assert_eq!(verify_ethereum(ALITH, &zero_based, MESSAGE), Ok(true));
+  }
+
+  #[test]
+  fn test_high_s_rejected() {
+    assert_eq!(verify_ethereum(ALITH, HIGH_S_SIGNATURE, MESSAGE).unwrap_err().code, ERR_NON_CANONICAL_SIGNATURE);
+  }
+}
@@ -0,0 +1,157 @@
+//! Freshness checks for challenges that embed their issue time.
+
+use crate::api::VerifyError;
+use crate::error::Error;
+use crate::log::{self, LOG_DEBUG};
+use crate::options::Options;
+use crate::verify;
+
+/// Seconds a challenge may be issued ahead of the verifier's clock before
+/// it counts as future-dated.
+pub const MAX_CLOCK_SKEW_SECS: i64 = 30;
+
+const ISSUED_AT: &str = "{issued_at}";
+const ANY: &str = "{*}";
+
+fn not_found(reason: impl Into<String>) -> Error {
+  VerifyError::TimestampNotFound { reason: reason.into() }.into()
+}
+
+/// Extract the issue time from `message` using `format`.
+///
+/// `format` is the message with `{issued_at}` in place of the decimal Unix
+/// timestamp and `{*}` in place of any other text, e.g.
+/// `login:{issued_at}:{*}`. `{*}` matches up to the first occurrence of the
+/// text that follows it.
+fn extract_issued_at(message: &str, format: &str) -> Result<i64, Error> {
+  if format.matches(ISSUED_AT).count() != 1 {
+    return Err(not_found(format!("format {format:?} needs exactly one {ISSUED_AT}")));
+  }
+
+  let mismatch = || not_found(format!("message does not match {format:?}"));
+  let mut issued_at = None;
+  let (mut message, mut format) = (message, format);
+  while !format.is_empty() {
+    if let Some(rest) = format.strip_prefix(ISSUED_AT) {
+      let len = message.bytes().take_while(u8::is_ascii_digit).count();
+      issued_at = Some(message[..len].parse::<i64>().map_err(|_| mismatch())?);
+      (message, format) = (&message[len..], rest);
+    } else if let Some(rest) = format.strip_prefix(ANY) {
+      let literal_len = [rest.find(ISSUED_AT), rest.find(ANY)].into_iter().flatten().min().unwrap_or(rest.len());
+      let skipped = if rest.is_empty() {
+        message.len()
+      } else if literal_len == 0 {
+        return Err(not_found(format!("format {format:?} has a {ANY} not followed by text")));
+      } else {
+        message.find(&rest[..literal_len]).ok_or_else(mismatch)?
+      };
+      (message, format) = (&message[skipped..], rest);
+    } else {
+      let literal_len = [format.find(ISSUED_AT), format.find(ANY)].into_iter().flatten().min().unwrap_or(format.len());
+      message = message.strip_prefix(&format[..literal_len]).ok_or_else(mismatch)?;
+      format = &format[literal_len..];
+    }
+  }
+
+  if !message.is_empty() {
+    return Err(mismatch());
+  }
+  issued_at.ok_or_else(mismatch)
+}
+
+/// Check that a challenge issued at `issued_at` is fresh at `now`.
+fn check_freshness(issued_at: i64, max_age_secs: u64, now: i64) -> Result<(), Error> {
+  let age = i128::from(now) - i128::from(issued_at);
+  if age > i128::from(max_age_secs) {
+    let reason = format!("challenge issued at {issued_at} is {age}s old, at most {max_age_secs}s allowed");
+    return Err(VerifyError::Expired { reason }.into());
+  }
+  if -age > i128::from(MAX_CLOCK_SKEW_SECS) {
+    let reason = format!("challenge issued at {issued_at} is {}s in the future", -age);
+    return Err(VerifyError::NotYetValid { reason }.into());
+  }
+  Ok(())
+}
+
+/// Verify an sr25519 signature over a challenge, then check it is fresh
+/// at `now`. A bad signature wins over a stale challenge.
+pub(crate) fn verify_fresh(
+  address: &str,
+  signature: &str,
+  message: &str,
+  format: &str,
+  max_age_secs: u64,
+  now: i64,
+) -> Result<bool, Error> {
+  let issued_at = extract_issued_at(message, format)?;
+  log::log(LOG_DEBUG, || format!("challenge issued at {issued_at}, now is {now}"));
+
+  let outcome = verify::verify_sr25519(address, signature, message.as_bytes(), &Options::default())?;
+  if outcome.valid == 0 {
+    return Ok(false);
+  }
+  check_freshness(issued_at, max_age_secs, now)?;
+  Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{ERR_EXPIRED, ERR_NOT_YET_VALID, ERR_TIMESTAMP_NOT_FOUND, INVALID, VALID};
+  use sp_core::crypto::Ss58Codec;
+  use sp_core::{sr25519, Pair};
+
+  const FORMAT: &str = "login:{issued_at}:{*}";
+  const ISSUED_AT: i64 = 1_714_000_000;
+  const MESSAGE: &str = "login:1714000000:9f8e7d";
+  const MAX_AGE: u64 = 300;
+
+  fn verify(message: &str, signed: &str, now: i64) -> i32 {
+    let pair = sr25519::Pair::from_string("//Alice", None).unwrap();
+    let signature = hex::encode(pair.sign(signed.as_bytes()));
+    verify::verdict(verify_fresh(&pair.public().to_ss58check(), &signature, message, FORMAT, MAX_AGE, now))
+  }
+
+  #[test]
+  fn test_max_age_boundary() {
+    assert_eq!(verify(MESSAGE, MESSAGE, ISSUED_AT), VALID);
+    assert_eq!(verify(MESSAGE, MESSAGE, ISSUED_AT + MAX_AGE as i64), VALID);
+    assert_eq!(verify(MESSAGE, MESSAGE, ISSUED_AT + MAX_AGE as i64 + 1), ERR_EXPIRED);
+  }
+
+  #[test]
+  fn test_clock_skew_boundary() {
+    assert_eq!(verify(MESSAGE, MESSAGE, ISSUED_AT - MAX_CLOCK_SKEW_SECS), VALID);
+    assert_eq!(verify(MESSAGE, MESSAGE, ISSUED_AT - MAX_CLOCK_SKEW_SECS - 1), ERR_NOT_YET_VALID);
+  }
+
+  #[test]
+  fn test_bad_signature_wins_over_expiry() {
+    let other = "login:1714000000:000000";
+    assert_eq!(verify(MESSAGE, other, ISSUED_AT), INVALID);
+    assert_eq!(verify(MESSAGE, other, ISSUED_AT + 10_000), INVALID);
+  }
+
+  #[test]
+  fn test_extract_issued_at() {
+    assert_eq!(extract_issued_at(MESSAGE, FORMAT), Ok(ISSUED_AT));
+    assert_eq!(extract_issued_at("login:1714000000:", FORMAT), Ok(ISSUED_AT));
+    assert_eq!(extract_issued_at("1714000000", "{issued_at}"), Ok(ISSUED_AT));
+    assert_eq!(extract_issued_at("Sign in to app.example at 1714000000 (n=5)", "{*} at {issued_at} ({*})"), Ok(ISSUED_AT));
+    assert_eq!(extract_issued_at("nonce=ab;t=1714000000", "nonce={*};t={issued_at}"), Ok(ISSUED_AT));
+  }
+
+  #[test]
+  fn test_timestamp_not_found() {
+    let code = |message: &str, format: &str| extract_issued_at(message, format).unwrap_err().code;
+    assert_eq!(code("logout:1714000000:9f8e7d", FORMAT), ERR_TIMESTAMP_NOT_FOUND);
+    assert_eq!(code("login::9f8e7d", FORMAT), ERR_TIMESTAMP_NOT_FOUND);
+    assert_eq!(code("login:99999999999999999999:x", FORMAT), ERR_TIMESTAMP_NOT_FOUND);
+    assert_eq!(code("1714000000 extra", "{issued_at}"), ERR_TIMESTAMP_NOT_FOUND);
+    assert_eq!(code(MESSAGE, "login:{*}"), ERR_TIMESTAMP_NOT_FOUND);
+    assert_eq!(code(MESSAGE, "{issued_at}{issued_at}"), ERR_TIMESTAMP_NOT_FOUND);
+    assert_eq!(code(MESSAGE, "{*}{issued_at}{*}"), ERR_TIMESTAMP_NOT_FOUND);
+
+    assert_eq!(verify("login:soon:9f8e7d", "login:soon:9f8e7d", ISSUED_AT), ERR_TIMESTAMP_NOT_FOUND);
+  }
+}
@@ -0,0 +1,720 @@
+//! Signature checks for signed extrinsics, decoded from their SCALE
+//! encoding as they appear in a block body, and for the payloads wallets
+//! sign before an extrinsic is built.
+
+use crate::api::VerifyError;
+use crate::error::Error;
+use crate::log::{self, LOG_DEBUG};
+use crate::options::Options;
+use crate::parse::{decode_hex_array, decode_signature, hex_error};
+use crate::verify::{self, Account};
+use crate::{
+  SignatureScheme, ADDRESS_KIND_ACCOUNT_ID, METADATA_HASH_ABSENT, METADATA_HASH_DETECT,
+  METADATA_HASH_DISABLED, METADATA_HASH_ENABLED,
+};
+
+/// The chain state a signer commits to besides the extrinsic itself.
+pub(crate) struct Chain {
+  genesis_hash: [u8; 32],
+  block_hash: Option<[u8; 32]>,
+  spec_version: u32,
+  transaction_version: u32,
+  /// One of the `METADATA_HASH_*` modes.
+  metadata_hash_mode: i32,
+  metadata_hash: Option<[u8; 32]>,
+}
+
+impl Chain {
+  /// Decode the hex hashes of the chain state, each with or without 0x
+  /// prefix. `block_hash` is only needed for mortal eras, `metadata_hash`
+  /// only for `METADATA_HASH_ENABLED` and detecting it.
+  pub(crate) fn decode(
+    genesis_hash: &str,
+    block_hash: Option<&str>,
+    spec_version: u32,
+    transaction_version: u32,
+    metadata_hash_mode: i32,
+    metadata_hash: Option<&str>,
+  ) -> Result<Chain, Error> {
+    let optional_hash = |hash: Option<&str>, name| hash.map(|hash| decode_hex_array(hash, name)).transpose();
+    Ok(Chain {
+      genesis_hash: decode_hex_array(genesis_hash, "genesis_hash")?,
+      block_hash: optional_hash(block_hash, "block_hash")?,
+      spec_version,
+      transaction_version,
+      metadata_hash_mode,
+      metadata_hash: optional_hash(metadata_hash, "metadata_hash")?,
+    })
+  }
+}
+
+/// What `CheckMetadataHash` adds in `mode`: its mode byte to the extra, and
+/// the encoded `Option` of the metadata hash to the additional signed
+/// data. `None` if the runtime has no such extension.
+fn metadata_hash_extension(mode: i32, metadata_hash: Option<[u8; 32]>) -> Result<Option<(u8, Vec<u8>)>, Error> {
+  match (mode, metadata_hash) {
+    (METADATA_HASH_ABSENT, _) => Ok(None),
+    (METADATA_HASH_DISABLED, _) => Ok(Some((0, vec![0]))),
+    (METADATA_HASH_ENABLED, Some(hash)) => Ok(Some((1, [&[1][..], &hash].concat()))),
+    (METADATA_HASH_ENABLED, None) => {
+      Err(VerifyError::MissingArgument {
+        reason: "metadata_hash is needed when metadata_hash_mode is METADATA_HASH_ENABLED".to_owned(),
+      }
+      .into())
+    }
+    _ => Err(VerifyError::InvalidOption { reason: format!("unknown metadata_hash_mode {mode}") }.into()),
+  }
+}
+
+fn malformed(reason: impl Into<String>) -> Error {
+  VerifyError::InvalidExtrinsic { reason: reason.into() }.into()
+}
+
+/// The SCALE compact encoding of `value`.
+fn compact(value: u128) -> Vec<u8> {
+  match value {
+    0..=0x3f => vec![(value as u8) << 2],
+    0x40..=0x3fff => ((value as u16) << 2 | 1).to_le_bytes().to_vec(),
+    0x4000..=0x3fff_ffff => ((value as u32) << 2 | 2).to_le_bytes().to_vec(),
+    _ => {
+      let len = 16 - value.leading_zeros() as usize / 8;
+      [&[((len - 4) as u8) << 2 | 3][..], &value.to_le_bytes()[..len]].concat()
+    }
+  }
+}
+
+/// Reads SCALE values off the front of a byte slice.
+struct Reader<'a> {
+  bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+  fn take(&mut self, len: usize, what: &str) -> Result<&'a [u8], Error> {
+    if self.bytes.len() < len {
+      return Err(malformed(format!("extrinsic ends inside its {what}")));
+    }
+    let (taken, rest) = self.bytes.split_at(len);
+    self.bytes = rest;
+    Ok(taken)
+  }
+
+  fn byte(&mut self, what: &str) -> Result<u8, Error> {
+    Ok(self.take(1, what)?[0])
+  }
+
+  /// A SCALE compact integer of up to 128 bits.
+  fn compact(&mut self, what: &str) -> Result<u128, Error> {
+    let first = self.byte(what)?;
+    Ok(match first & 0b11 {
+      0 => u128::from(first >> 2),
+      1 => u128::from(u16::from_le_bytes([first, self.byte(what)?]) >> 2),
+      2 => {
+        let rest = self.take(3, what)?;
+        u128::from(u32::from_le_bytes([first, rest[0], rest[1], rest[2]]) >> 2)
+      }
+      _ => {
+        let len = usize::from(first >> 2) + 4;
+        if len > 16 {
+          return Err(malformed(format!("{what} is a {len}-byte integer, at most 16 are supported")));
+        }
+        let mut bytes = [0u8; 16];
+        bytes[..len].copy_from_slice(self.take(len, what)?);
+        u128::from_le_bytes(bytes)
+      }
+    })
+  }
+}
+
+/// The parts of a v4 signed extrinsic.
+struct Signed<'a> {
+  signer: [u8; 32],
+  scheme: SignatureScheme,
+  signature: &'a [u8],
+  /// The era, nonce and tip, as encoded.
+  extra: &'a [u8],
+  mortal: bool,
+  /// Everything after the tip: the call, preceded by the
+  /// `CheckMetadataHash` mode byte on runtimes that have it.
+  rest: &'a [u8],
+}
+
+fn decode(extrinsic: &[u8]) -> Result<Signed<'_>, Error> {
+  let mut reader = Reader { bytes: extrinsic };
+  let len = reader.compact("length prefix")?;
+  if len != reader.bytes.len() as u128 {
+    return Err(malformed(format!("extrinsic length prefix says {len} bytes, but {} follow", reader.bytes.len())));
+  }
+  match reader.byte("version")? {
+    0x84 => {}
+    // v4 unsigned and v5 bare extrinsics
+    0x04 | 0x05 => return Err(VerifyError::NotSigned.into()),
+    version => return Err(malformed(format!("extrinsic version byte {version:#04x} is not a v4 signed extrinsic"))),
+  }
+
+  let signer = match reader.byte("signer")? {
+    // MultiAddress::Id and MultiAddress::Address32
+    0 | 3 => reader.take(32, "signer")?.try_into().expect("took 32 bytes"),
+    1 => return Err(malformed("signer is an account index, which needs chain state to resolve")),
+    tag => return Err(malformed(format!("signer is MultiAddress variant {tag:#04x}, not an account ID"))),
+  };
+  let (scheme, signature_len) = verify::multisignature_variant(reader.byte("signature")?)?;
+  let signature = reader.take(signature_len, "signature")?;
+
+  let extra_start = reader.bytes;
+  let mortal = reader.byte("era")? != 0;
+  if mortal {
+    reader.take(1, "era")?;
+  }
+  let nonce = reader.compact("nonce")?;
+  let tip = reader.compact("tip")?;
+  let extra = &extra_start[..extra_start.len() - reader.bytes.len()];
+  if reader.bytes.is_empty() {
+    return Err(malformed("extrinsic has no call"));
+  }
+
+  log::log(LOG_DEBUG, || {
+    let era = if mortal { "mortal" } else { "immortal" };
+    format!("{era} extrinsic signed by 0x{} with {}, nonce {nonce}, tip {tip}", hex::encode(signer), scheme.name())
+  });
+  Ok(Signed { signer, scheme, signature, extra, mortal, rest: reader.bytes })
+}
+
+/// The additional signed data of the relay chains' signed extensions, up
+/// to `CheckMortality`: spec and transaction version, genesis hash, and the
+/// hash of the block a mortal era starts at.
+fn additional_signed(chain: &Chain, mortal: bool) -> Result<Vec<u8>, Error> {
+  let block_hash = match (mortal, chain.block_hash) {
+    (false, _) => chain.genesis_hash,
+    (true, Some(block_hash)) => block_hash,
+    (true, None) => {
+      let reason = "block_hash is needed to verify a mortal era".to_owned();
+      return Err(VerifyError::MissingArgument { reason }.into());
+    }
+  };
+  let mut additional = Vec::with_capacity(8 + 32 + 32 + 33);
+  additional.extend_from_slice(&chain.spec_version.to_le_bytes());
+  additional.extend_from_slice(&chain.transaction_version.to_le_bytes());
+  additional.extend_from_slice(&chain.genesis_hash);
+  additional.extend_from_slice(&block_hash);
+  Ok(additional)
+}
+
+/// The payloads `signed` may have been signed as: call, then extra, then
+/// additional signed data, with `CheckMetadataHash` appended to both as
+/// `chain.metadata_hash_mode` says. `METADATA_HASH_DETECT` gives the
+/// layout without it first, then the one with it if the byte after the tip
+/// can be its mode.
+fn payloads(signed: &Signed, chain: &Chain) -> Result<Vec<Vec<u8>>, Error> {
+  let additional = additional_signed(chain, signed.mortal)?;
+  let (mode, call) = (signed.rest[0], &signed.rest[1..]);
+  if chain.metadata_hash_mode != METADATA_HASH_DETECT {
+    let extension = metadata_hash_extension(chain.metadata_hash_mode, chain.metadata_hash)?;
+    let Some((expected, metadata_hash)) = extension else {
+      return Ok(vec![[signed.rest, signed.extra, &additional].concat()]);
+    };
+    if mode != expected {
+      return Err(malformed(format!("extrinsic's CheckMetadataHash mode is {mode}, expected {expected}")));
+    }
+    if call.is_empty() {
+      return Err(malformed("extrinsic has no call"));
+    }
+    return Ok(vec![[call, signed.extra, &[mode], &additional, &metadata_hash].concat()]);
+  }
+
+  let mut payloads = vec![[signed.rest, signed.extra, &additional].concat()];
+  if !call.is_empty() {
+    let metadata_hash = match (mode, chain.metadata_hash) {
+      (0, _) => Some(vec![0]),
+      (1, Some(hash)) => Some([&[1][..], &hash].concat()),
+      _ => None,
+    };
+    if let Some(metadata_hash) = metadata_hash {
+      payloads.push([call, signed.extra, &[mode], &additional, &metadata_hash].concat());
+    }
+  }
+  Ok(payloads)
+}
+
+fn verify_extrinsic_bytes(extrinsic: &[u8], chain: &Chain) -> Result<bool, Error> {
+  let signed = decode(extrinsic)?;
+  let account = Account { id: signed.signer, ss58_prefix: 0, kind: ADDRESS_KIND_ACCOUNT_ID };
+  // Runtimes accept high-s ECDSA signatures, so this does too
+  let options = Options {
+    #[cfg(feature = "sp-core")]
+    allow_non_canonical_ecdsa: true,
+    ..Options::default()
+  };
+  for payload in payloads(&signed, chain)? {
+    if verify::verify_scheme(signed.scheme, &account, signed.signature, &payload, &options)?.valid == 1 {
+      return Ok(true);
+    }
+  }
+  Ok(false)
+}
+
+/// The `SignedPayload` of a call signed with `era`, `nonce` and `tip`,
+/// before any hashing. `CheckMetadataHash` comes last in both the extra and
+/// the additional signed data.
+fn signing_payload(call: &[u8], era: &[u8], nonce: u64, tip: u128, chain: &Chain) -> Result<Vec<u8>, Error> {
+  let mortal = match era {
+    [0] => false,
+    [first, _] if *first != 0 => true,
+    _ => {
+      let reason = format!("era 0x{} is not a SCALE-encoded Era", hex::encode(era));
+      return Err(VerifyError::InvalidEra { reason }.into());
+    }
+  };
+  if chain.metadata_hash_mode == METADATA_HASH_DETECT {
+    let reason = "METADATA_HASH_DETECT needs an extrinsic to detect from".to_owned();
+    return Err(VerifyError::InvalidOption { reason }.into());
+  }
+  let additional = additional_signed(chain, mortal)?;
+  let mut payload = [call, era, &compact(nonce.into()), &compact(tip)].concat();
+  match metadata_hash_extension(chain.metadata_hash_mode, chain.metadata_hash)? {
+    Some((mode, metadata_hash)) => payload.extend([&[mode][..], &additional, &metadata_hash].concat()),
+    None => payload.extend(additional),
+  }
+  Ok(payload)
+}
+
+/// Decode the hex argument `name`, with or without 0x prefix.
+fn decode_hex(hex_str: &str, name: &str) -> Result<Vec<u8>, Error> {
+  hex::decode(hex_str.trim_start_matches("0x")).map_err(|e| hex_error(e, name))
+}
+
+/// Verify the signature of the hex-encoded signed extrinsic `extrinsic`
+/// against `chain`.
+pub(crate) fn verify_extrinsic(extrinsic: &str, chain: &Chain) -> Result<bool, Error> {
+  verify_extrinsic_bytes(&decode_hex(extrinsic, "extrinsic")?, chain)
+}
+
+/// Verify an sr25519 signature, or a `MultiSignature` of any variant, over
+/// the signing payload of the hex-encoded `call` and `era`.
+pub(crate) fn verify_signing_payload(
+  address: &str,
+  signature: &str,
+  call: &str,
+  era: &str,
+  nonce: u64,
+  tip: u128,
+  chain: &Chain,
+) -> Result<bool, Error> {
+  let payload = signing_payload(&decode_hex(call, "call")?, &decode_hex(era, "era")?, nonce, tip, chain)?;
+  let options = Options { strip_scheme_prefix: true, ..Options::default() };
+  // A 0x02 tag and 65 bytes can only be an ECDSA `MultiSignature`
+  if let [0x02, ecdsa @ ..] = &decode_signature(signature, &options)?[..] {
+    if ecdsa.len() == 65 {
+      // Runtimes accept high-s ECDSA signatures, so this does too
+      let options = Options {
+        #[cfg(feature = "sp-core")]
+        allow_non_canonical_ecdsa: true,
+        ..options
+      };
+      return Ok(verify::verify_multisignature(address, signature, &payload, &options)?.valid == 1);
+    }
+  }
+  Ok(verify::verify_sr25519(address, signature, &payload, &options)?.valid == 1)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::error::with_last_error;
+  use crate::verify::verdict;
+  use crate::{
+    ERR_BAD_ERA, ERR_BAD_EXTRINSIC, ERR_BAD_HEX, ERR_BAD_HEX_LENGTH, ERR_BAD_MULTISIGNATURE, ERR_INVALID_OPTIONS,
+    ERR_NOT_SIGNED, ERR_NULL_POINTER, INVALID, VALID,
+  };
+  use codec::{Compact, Encode, Output};
+  use sp_core::crypto::{AccountId32, Ss58Codec};
+  use sp_core::hashing::blake2_256;
+  use sp_core::{ecdsa, ed25519, sr25519, Pair, H256};
+  use sp_runtime::generic::Era;
+
+  const GENESIS_HASH: [u8; 32] = [0x91; 32];
+  const BLOCK_HASH: [u8; 32] = [0xb1; 32];
+  const METADATA_HASH: [u8; 32] = [0x3d; 32];
+  const SPEC_VERSION: u32 = 1_003_000;
+  const TRANSACTION_VERSION: u32 = 26;
+  const SEED: [u8; 32] = [7; 32];
+  /// Balances `transfer_keep_alive` of 1 DOT to Alice: pallet and call
+  /// index, a `MultiAddress::Id` destination and a compact amount.
+  const CALL: &str = "050300d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d0700e40b5402";
+
+  /// How a test extrinsic is signed.
+  struct Extrinsic {
+    scheme: SignatureScheme,
+    mortal: bool,
+    /// `None` for runtimes without `CheckMetadataHash`, otherwise whether
+    /// it is enabled.
+    metadata_hash: Option<bool>,
+    call: Vec<u8>,
+  }
+
+  impl Default for Extrinsic {
+    fn default() -> Self {
+      let call = hex::decode(CALL).unwrap();
+      Extrinsic { scheme: SignatureScheme::Sr25519, mortal: false, metadata_hash: None, call }
+    }
+  }
+
+  /// The account ID of the test key of `scheme` and its `MultiSignature`
+  /// over `payload`.
+  fn sign(scheme: SignatureScheme, payload: &[u8]) -> ([u8; 32], Vec<u8>) {
+    let id = |public: &[u8]| <[u8; 32]>::try_from(public).unwrap();
+    match scheme {
+      SignatureScheme::Ed25519 => {
+        let pair = ed25519::Pair::from_seed(&SEED);
+        (id(pair.public().as_ref()), [&[0][..], pair.sign(payload).as_ref()].concat())
+      }
+      SignatureScheme::Sr25519 => {
+        let pair = sr25519::Pair::from_seed(&SEED);
+        (id(pair.public().as_ref()), [&[1][..], pair.sign(payload).as_ref()].concat())
+      }
+      SignatureScheme::Ecdsa => {
+        let pair = ecdsa::Pair::from_seed(&SEED);
+        (blake2_256(pair.public().as_ref()), [&[2][..], pair.sign(payload).as_ref()].concat())
+      }
+      other => unreachable!("{other:?} has no MultiSignature variant"),
+    }
+  }
+
+  /// Sign `extrinsic` as a wallet would, with an independent SCALE
+  /// encoder, and encode it without its length prefix.
+  fn signed_body(extrinsic: &Extrinsic) -> Vec<u8> {
+    let mut extra = if extrinsic.mortal { vec![0x15, 0x02] } else { vec![0] };
+    extra.extend(Compact(7u32).encode());
+    extra.extend(Compact(10_000_000_000u128).encode());
+    let block_hash = if extrinsic.mortal { BLOCK_HASH } else { GENESIS_HASH };
+    let mut additional = (SPEC_VERSION, TRANSACTION_VERSION, GENESIS_HASH, block_hash).encode();
+    if let Some(enabled) = extrinsic.metadata_hash {
+      extra.push(enabled as u8);
+      additional.extend(enabled.then_some(METADATA_HASH).encode());
+    }
+
+    let payload = [&extrinsic.call[..], &extra, &additional].concat();
+    let payload = if payload.len() > 256 { blake2_256(&payload).to_vec() } else { payload };
+    let (account, multisignature) = sign(extrinsic.scheme, &payload);
+    [&[0x84, 0x00][..], &account, &multisignature, &extra, &extrinsic.call].concat()
+  }
+
+  /// `signed_body` with its length prefix.
+  fn encode(extrinsic: &Extrinsic) -> Vec<u8> {
+    signed_body(extrinsic).encode()
+  }
+
+  /// Call `verify_extrinsic` with hex arguments, reporting a code.
+  fn call(
+    extrinsic: &str,
+    genesis_hash: &str,
+    block_hash: Option<&str>,
+    spec: u32,
+    mode: i32,
+    metadata: Option<&str>,
+  ) -> i32 {
+    let chain = Chain::decode(genesis_hash, block_hash, spec, TRANSACTION_VERSION, mode, metadata);
+    verdict(chain.and_then(|chain| verify_extrinsic(extrinsic, &chain)))
+  }
+
+  fn verify_with(extrinsic: &[u8], block: Option<[u8; 32]>, spec: u32, mode: i32, metadata: Option<[u8; 32]>) -> i32 {
+    let (block_hash, metadata_hash) = (block.map(hex::encode), metadata.map(hex::encode));
+    call(
+      &format!("0x{}", hex::encode(extrinsic)),
+      &hex::encode(GENESIS_HASH),
+      block_hash.as_deref(),
+      spec,
+      mode,
+      metadata_hash.as_deref(),
+    )
+  }
+
+  fn verify(extrinsic: &[u8]) -> i32 {
+    verify_with(extrinsic, Some(BLOCK_HASH), SPEC_VERSION, METADATA_HASH_DETECT, Some(METADATA_HASH))
+  }
+
+  fn last_error() -> String {
+    with_last_error(|message| message.unwrap_or_default().to_owned())
+  }
+
+  #[test]
+  fn test_verify_extrinsic() {
+    let schemes = [SignatureScheme::Sr25519, SignatureScheme::Ed25519, SignatureScheme::Ecdsa];
+    for scheme in schemes.into_iter().filter(|scheme| scheme.is_supported()) {
+      let extrinsic = encode(&Extrinsic { scheme, ..Extrinsic::default() });
+      assert_eq!(verify_with(&extrinsic, None, SPEC_VERSION, METADATA_HASH_DETECT, None), VALID, "{scheme:?}");
+
+      let mut tampered = extrinsic.clone();
+      *tampered.last_mut().unwrap() ^= 1;
+      assert_eq!(verify(&tampered), INVALID, "{scheme:?}");
+    }
+  }
+
+  #[test]
+  fn test_commits_to_chain() {
+    let extrinsic = encode(&Extrinsic::default());
+    assert_eq!(verify_with(&extrinsic, None, SPEC_VERSION + 1, METADATA_HASH_DETECT, None), INVALID);
+
+    let extrinsic = hex::encode(&extrinsic);
+    assert_eq!(call(&extrinsic, &hex::encode(GENESIS_HASH), None, SPEC_VERSION, METADATA_HASH_DETECT, None), VALID);
+    assert_eq!(call(&extrinsic, &hex::encode([0x92; 32]), None, SPEC_VERSION, METADATA_HASH_DETECT, None), INVALID);
+    assert_eq!(call(&extrinsic, "0x1234", None, SPEC_VERSION, METADATA_HASH_DETECT, None), ERR_BAD_HEX_LENGTH);
+    assert_eq!(last_error(), "genesis_hash is 2 bytes, expected 32");
+  }
+
+  #[test]
+  fn test_mortal() {
+    let extrinsic = encode(&Extrinsic { mortal: true, ..Extrinsic::default() });
+    assert_eq!(verify_with(&extrinsic, Some(BLOCK_HASH), SPEC_VERSION, METADATA_HASH_DETECT, None), VALID);
+    assert_eq!(verify_with(&extrinsic, Some(GENESIS_HASH), SPEC_VERSION, METADATA_HASH_DETECT, None), INVALID);
+    assert_eq!(verify_with(&extrinsic, None, SPEC_VERSION, METADATA_HASH_DETECT, None), ERR_NULL_POINTER);
+    assert_eq!(last_error(), "block_hash is needed to verify a mortal era");
+  }
+
+  #[test]
+  fn test_metadata_hash() {
+    let disabled = encode(&Extrinsic { metadata_hash: Some(false), ..Extrinsic::default() });
+    assert_eq!(verify_with(&disabled, None, SPEC_VERSION, METADATA_HASH_DETECT, None), VALID);
+
+    let enabled = encode(&Extrinsic { metadata_hash: Some(true), ..Extrinsic::default() });
+    assert_eq!(verify_with(&enabled, None, SPEC_VERSION, METADATA_HASH_DETECT, Some(METADATA_HASH)), VALID);
+    assert_eq!(verify_with(&enabled, None, SPEC_VERSION, METADATA_HASH_DETECT, Some([0; 32])), INVALID);
+    assert_eq!(verify_with(&enabled, None, SPEC_VERSION, METADATA_HASH_DETECT, None), INVALID);
+  }
+
+  #[test]
+  fn test_metadata_hash_mode() {
+    let absent = encode(&Extrinsic::default());
+    let disabled = encode(&Extrinsic { metadata_hash: Some(false), ..Extrinsic::default() });
+    let enabled = encode(&Extrinsic { metadata_hash: Some(true), ..Extrinsic::default() });
+    let verify = |extrinsic: &[u8], mode: i32| verify_with(extrinsic, None, SPEC_VERSION, mode, Some(METADATA_HASH));
+    assert_eq!(verify(&absent, METADATA_HASH_ABSENT), VALID);
+    assert_eq!(verify(&disabled, METADATA_HASH_DISABLED), VALID);
+    assert_eq!(verify(&enabled, METADATA_HASH_ENABLED), VALID);
+
+    // The runtime's layout only
+    assert_eq!(verify(&disabled, METADATA_HASH_ABSENT), INVALID);
+    assert_eq!(verify(&enabled, METADATA_HASH_DISABLED), ERR_BAD_EXTRINSIC);
+    assert_eq!(last_error(), "extrinsic's CheckMetadataHash mode is 1, expected 0");
+    assert_eq!(verify(&disabled, METADATA_HASH_ENABLED), ERR_BAD_EXTRINSIC);
+    assert_eq!(verify(&absent, METADATA_HASH_DISABLED), ERR_BAD_EXTRINSIC);
+
+    assert_eq!(verify_with(&enabled, None, SPEC_VERSION, METADATA_HASH_ENABLED, Some([0; 32])), INVALID);
+    assert_eq!(verify_with(&enabled, None, SPEC_VERSION, METADATA_HASH_ENABLED, None), ERR_NULL_POINTER);
+    assert_eq!(verify(&absent, 7), ERR_INVALID_OPTIONS);
+    assert_eq!(last_error(), "unknown metadata_hash_mode 7");
+  }
+
+  #[test]
+  fn test_long_payload_is_hashed() {
+    let schemes = [SignatureScheme::Sr25519, SignatureScheme::Ecdsa];
+    for scheme in schemes.into_iter().filter(|scheme| scheme.is_supported()) {
+      let call = [hex::decode("0000").unwrap(), vec![0xab; 300].encode()].concat();
+      let extrinsic = encode(&Extrinsic { scheme, mortal: true, metadata_hash: Some(false), call });
+      assert_eq!(verify(&extrinsic), VALID, "{scheme:?}");
+    }
+  }
+
+  #[test]
+  fn test_not_signed() {
+    // A v4 unsigned `timestamp.set`
+    let unsigned = [&[0x04, 0x03, 0x00][..], &Compact(1_700_000_000_000u64).encode()].concat().encode();
+    assert_eq!(verify(&unsigned), ERR_NOT_SIGNED);
+    assert_eq!(verify(&[&[0x05u8, 0x03, 0x00][..], &[0x0b; 6]].concat().encode()), ERR_NOT_SIGNED);
+  }
+
+  #[test]
+  fn test_malformed() {
+    let body = &signed_body(&Extrinsic::default())[..];
+
+    let misprefixed = [Compact(body.len() as u32 + 1).encode(), body.to_vec()].concat();
+    assert_eq!(verify(&misprefixed), ERR_BAD_EXTRINSIC);
+    let (said, found) = (body.len() + 1, body.len());
+    assert_eq!(last_error(), format!("extrinsic length prefix says {said} bytes, but {found} follow"));
+    assert_eq!(verify(&body[..40].to_vec().encode()), ERR_BAD_EXTRINSIC);
+    assert_eq!(last_error(), "extrinsic ends inside its signature");
+    let without_call = &body[..body.len() - hex::decode(CALL).unwrap().len()];
+    assert_eq!(verify(&without_call.to_vec().encode()), ERR_BAD_EXTRINSIC);
+    assert_eq!(last_error(), "extrinsic has no call");
+
+    let mut v5 = body.to_vec();
+    v5[0] = 0x45;
+    assert_eq!(verify(&v5.encode()), ERR_BAD_EXTRINSIC);
+
+    let index = [&[0x84, 0x01][..], &Compact(5u32).encode(), &body[34..]].concat();
+    assert_eq!(verify(&index.encode()), ERR_BAD_EXTRINSIC);
+    assert_eq!(last_error(), "signer is an account index, which needs chain state to resolve");
+
+    let mut unknown_signature = body.to_vec();
+    unknown_signature[34] = 0x07;
+    assert_eq!(verify(&unknown_signature.encode()), ERR_BAD_MULTISIGNATURE);
+
+    assert_eq!(call("0xzz", &hex::encode(GENESIS_HASH), None, SPEC_VERSION, METADATA_HASH_DETECT, None), ERR_BAD_HEX);
+  }
+
+  #[test]
+  fn test_compact() {
+    let values = [0, 1, 63, 64, 0x3fff, 0x4000, 0x3fff_ffff, 0x4000_0000, u64::MAX.into(), u128::MAX];
+    for value in values {
+      let encoded = compact(value);
+      assert_eq!(encoded, Compact(value).encode(), "{value}");
+      assert_eq!(Reader { bytes: &encoded }.compact("value").unwrap(), value);
+    }
+  }
+
+  /// A call encoded as is, the way `SignedPayload` encodes a runtime call.
+  struct RawCall<'a>(&'a [u8]);
+
+  impl Encode for RawCall<'_> {
+    fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
+      dest.write(self.0);
+    }
+  }
+
+  fn sign_payload(call: &[u8], era: Era, nonce: u32, tip: u128, block_hash: H256) -> String {
+    sign_payload_with(call, era, nonce, tip, block_hash, None)
+  }
+
+  /// The `SignedPayload` of `call`, built from sp-runtime types as
+  /// `SignedPayload::from_raw` builds it. `metadata` is as
+  /// `Extrinsic::metadata_hash`.
+  fn signed_payload(call: &[u8], era: Era, nonce: u32, tip: u128, block: H256, metadata: Option<bool>) -> Vec<u8> {
+    let additional = (SPEC_VERSION, TRANSACTION_VERSION, H256(GENESIS_HASH), block);
+    match metadata {
+      None => (RawCall(call), (era, Compact(nonce), Compact(tip)), additional).encode(),
+      Some(enabled) => {
+        let extra = (era, Compact(nonce), Compact(tip), enabled as u8);
+        (RawCall(call), extra, (additional, enabled.then_some(H256(METADATA_HASH)))).encode()
+      }
+    }
+  }
+
+  /// Sign the `signed_payload` of `call` with the sr25519 test key.
+  fn sign_payload_with(call: &[u8], era: Era, nonce: u32, tip: u128, block: H256, metadata: Option<bool>) -> String {
+    let payload = signed_payload(call, era, nonce, tip, block, metadata);
+    let pair = sr25519::Pair::from_seed(&SEED);
+    let signature = if payload.len() > 256 { pair.sign(&blake2_256(&payload)) } else { pair.sign(&payload) };
+    format!("0x{}", hex::encode(signature))
+  }
+
+  fn verify_payload(signature: &str, call: &str, era: &[u8], nonce: u64, tip: u128, block: Option<[u8; 32]>) -> i32 {
+    verify_payload_with(signature, call, era, nonce, tip, block, METADATA_HASH_ABSENT, None)
+  }
+
+  #[allow(clippy::too_many_arguments)]
+  fn verify_payload_with(
+    signature: &str,
+    call: &str,
+    era: &[u8],
+    nonce: u64,
+    tip: u128,
+    block: Option<[u8; 32]>,
+    mode: i32,
+    metadata: Option<[u8; 32]>,
+  ) -> i32 {
+    let address = sr25519::Pair::from_seed(&SEED).public().to_ss58check();
+    let (block_hash, metadata_hash) = (block.map(hex::encode), metadata.map(hex::encode));
+    let genesis_hash = hex::encode(GENESIS_HASH);
+    let chain =
+      Chain::decode(&genesis_hash, block_hash.as_deref(), SPEC_VERSION, TRANSACTION_VERSION, mode, metadata_hash.as_deref());
+    let era = hex::encode(era);
+    verdict(chain.and_then(|chain| verify_signing_payload(&address, signature, call, &era, nonce, tip, &chain)))
+  }
+
+  #[test]
+  fn test_verify_signing_payload() {
+    const TIP: u128 = 10_000_000_000;
+    let era = Era::mortal(64, 12_345_678);
+    let signature = sign_payload(&hex::decode(CALL).unwrap(), era, 7, TIP, H256(BLOCK_HASH));
+    let verify = |signature: &str, era: &[u8], nonce: u64, tip: u128, block_hash: [u8; 32]| {
+      verify_payload(signature, CALL, era, nonce, tip, Some(block_hash))
+    };
+    assert_eq!(verify(&signature, &era.encode(), 7, TIP, BLOCK_HASH), VALID);
+
+    // Every field is signed for
+    assert_eq!(verify(&signature, &era.encode(), 8, TIP, BLOCK_HASH), INVALID);
+    assert_eq!(verify(&signature, &era.encode(), 7, 0, BLOCK_HASH), INVALID);
+    assert_eq!(verify(&signature, &era.encode(), TIP as u64, 7, BLOCK_HASH), INVALID);
+    assert_eq!(verify(&signature, &era.encode(), 7, TIP, GENESIS_HASH), INVALID);
+    assert_eq!(verify(&signature, &Era::mortal(64, 12_345_700).encode(), 7, TIP, BLOCK_HASH), INVALID);
+    assert_eq!(verify(&signature, &[0], 7, TIP, BLOCK_HASH), INVALID);
+    assert_eq!(verify_payload(&signature, &CALL[2..], &era.encode(), 7, TIP, Some(BLOCK_HASH)), INVALID);
+
+    // As `signer.signPayload` returns it
+    assert_eq!(verify(&format!("0x01{}", &signature[2..]), &era.encode(), 7, TIP, BLOCK_HASH), VALID);
+  }
+
+  #[test]
+  fn test_signing_payload_immortal() {
+    let call = hex::decode(CALL).unwrap();
+    let signature = sign_payload(&call, Era::Immortal, 70_000, u128::MAX, H256(GENESIS_HASH));
+    assert_eq!(verify_payload(&signature, CALL, &Era::Immortal.encode(), 70_000, u128::MAX, None), VALID);
+    assert_eq!(verify_payload(&signature, CALL, &[0], 70_000, u128::MAX, Some(BLOCK_HASH)), VALID);
+  }
+
+  #[test]
+  fn test_signing_payload_ecdsa() {
+    let era = Era::mortal(64, 12_345_678);
+    let payload = signed_payload(&hex::decode(CALL).unwrap(), era, 7, 0, H256(BLOCK_HASH), None);
+    let (signer, multisignature) = sign(SignatureScheme::Ecdsa, &payload);
+    let address = AccountId32::from(signer).to_ss58check();
+    let (genesis_hash, block_hash) = (hex::encode(GENESIS_HASH), hex::encode(BLOCK_HASH));
+    let mode = METADATA_HASH_ABSENT;
+    let chain = Chain::decode(&genesis_hash, Some(&block_hash), SPEC_VERSION, TRANSACTION_VERSION, mode, None).unwrap();
+    let (signature, era) = (hex::encode(multisignature), hex::encode(era.encode()));
+    let verify = |nonce| verdict(verify_signing_payload(&address, &signature, CALL, &era, nonce, 0, &chain));
+    assert_eq!(verify(7), VALID);
+    assert_eq!(verify(8), INVALID);
+  }
+
+  #[test]
+  fn test_signing_payload_metadata_hash() {
+    let call = hex::decode(CALL).unwrap();
+    let era = Era::mortal(64, 12_345_678);
+    let sign = |metadata_hash| sign_payload_with(&call, era, 7, 0, H256(BLOCK_HASH), metadata_hash);
+    let verify = |signature: &str, mode: i32, metadata_hash: Option<[u8; 32]>| {
+      verify_payload_with(signature, CALL, &era.encode(), 7, 0, Some(BLOCK_HASH), mode, metadata_hash)
+    };
+
+    let disabled = sign(Some(false));
+    assert_eq!(verify(&disabled, METADATA_HASH_DISABLED, None), VALID);
+    assert_eq!(verify(&disabled, METADATA_HASH_DISABLED, Some(METADATA_HASH)), VALID);
+    assert_eq!(verify(&disabled, METADATA_HASH_ABSENT, None), INVALID);
+    assert_eq!(verify(&disabled, METADATA_HASH_ENABLED, Some(METADATA_HASH)), INVALID);
+
+    let enabled = sign(Some(true));
+    assert_eq!(verify(&enabled, METADATA_HASH_ENABLED, Some(METADATA_HASH)), VALID);
+    assert_eq!(verify(&enabled, METADATA_HASH_ENABLED, Some([0; 32])), INVALID);
+    assert_eq!(verify(&enabled, METADATA_HASH_DISABLED, None), INVALID);
+    assert_eq!(verify(&enabled, METADATA_HASH_ABSENT, None), INVALID);
+
+    let absent = sign(None);
+    assert_eq!(verify(&absent, METADATA_HASH_ABSENT, None), VALID);
+    assert_eq!(verify(&absent, METADATA_HASH_DISABLED, None), INVALID);
+
+    assert_eq!(verify(&enabled, METADATA_HASH_ENABLED, None), ERR_NULL_POINTER);
+    assert_eq!(last_error(), "metadata_hash is needed when metadata_hash_mode is METADATA_HASH_ENABLED");
+    assert_eq!(verify(&enabled, METADATA_HASH_DETECT, Some(METADATA_HASH)), ERR_INVALID_OPTIONS);
+    assert_eq!(verify(&enabled, -1, None), ERR_INVALID_OPTIONS);
+  }
+
+  #[test]
+  fn test_long_signing_payload_is_hashed() {
+    let remark = [hex::decode("0000").unwrap(), vec![0xab; 300].encode()].concat();
+    let signature = sign_payload(&remark, Era::Immortal, 0, 0, H256(GENESIS_HASH));
+    assert_eq!(verify_payload(&signature, &hex::encode(&remark), &[0], 0, 0, None), VALID);
+  }
+
+  #[test]
+  fn test_signing_payload_errors() {
+    let signature = sign_payload(&hex::decode(CALL).unwrap(), Era::Immortal, 0, 0, H256(GENESIS_HASH));
+    let eras: [&[u8]; 4] = [&[], &[0, 0], &[0x15], &[0x15, 0x02, 0x00]];
+    for era in eras {
+      assert_eq!(verify_payload(&signature, CALL, era, 0, 0, Some(BLOCK_HASH)), ERR_BAD_ERA, "{era:?}");
+    }
+    assert_eq!(last_error(), "era 0x150200 is not a SCALE-encoded Era");
+
+    let mortal = Era::mortal(64, 12_345_678).encode();
+    assert_eq!(verify_payload(&signature, CALL, &mortal, 0, 0, None), ERR_NULL_POINTER);
+    assert_eq!(verify_payload(&signature, "0xzz", &[0], 0, 0, None), ERR_BAD_HEX);
+  }
+}
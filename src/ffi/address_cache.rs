@@ -0,0 +1,28 @@
+//! Turning the cache of [`crate::address_cache`] on and off from C.
+
+use crate::address_cache;
+use crate::ffi::guard::ffi_guard;
+
+/// Turn on the cache of parsed addresses, or resize or turn it off
+///
+/// Verification functions that take an SS58 or hex address then look it up
+/// here before parsing it, which saves the base58 decoding and checksum
+/// for addresses seen recently. Results are the same either way. The cache
+/// is shared by all threads.
+///
+/// # Arguments
+/// * `capacity` - Most addresses remembered, such as 1024. When full, the
+///   least recently used is forgotten. 0 turns the cache off and frees it,
+///   as it is before the first call.
+///
+/// Any addresses already cached are forgotten.
+#[no_mangle]
+pub extern "C" fn cache_init(capacity: usize) {
+  ffi_guard((), || address_cache::init(capacity))
+}
+
+/// Forget every cached address, keeping the cache on if it was
+#[no_mangle]
+pub extern "C" fn cache_clear() {
+  ffi_guard((), address_cache::clear)
+}
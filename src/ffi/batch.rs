@@ -0,0 +1,424 @@
+//! Verifying many signatures in one call, serially or on a thread pool.
+
+use crate::api::VerifyError;
+use crate::error::{set_last_error, Error};
+use crate::ffi::guard::ffi_guard;
+use crate::ffi::verify_c_strs;
+use crate::log::{self, LOG_DEBUG, LOG_INFO};
+use crate::message;
+use crate::options::Options;
+use crate::parse::str_from_ptr;
+use crate::stats;
+use crate::verify::{self, Sr25519Item};
+use crate::{ERR_NULL_POINTER, ERR_PANIC, INVALID, VALID};
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::borrow::Cow;
+use std::ffi::c_char;
+use std::sync::OnceLock;
+use std::thread;
+
+/// Items per schnorrkel batch. One bad signature sends its whole chunk
+/// back to verifying one by one, so chunks are small enough to keep that
+/// cheap but large enough to gain from batching.
+const CHUNK: usize = 64;
+
+/// Result of an item awaiting its chunk's batch check.
+const PENDING: i32 = i32::MIN;
+
+/// The input arrays of a batch, which every pool thread reads.
+#[derive(Clone, Copy)]
+struct Batch {
+  addresses: *const *const c_char,
+  signatures: *const *const c_char,
+  messages: *const *const c_char,
+}
+
+// Safety: the caller keeps the arrays and their strings alive and unchanged
+// until the call returns, and the pool threads only read them.
+unsafe impl Send for Batch {}
+unsafe impl Sync for Batch {}
+
+impl Batch {
+  /// Verify item `i` like `verify_polkadot_signature_v2`, leaving the last
+  /// error alone.
+  fn verify(&self, i: usize, options: &Options) -> i32 {
+    // Safety: `i` is below the `count` elements of each array
+    let (address, signature, message) =
+      unsafe { (*self.addresses.add(i), *self.signatures.add(i), *self.messages.add(i)) };
+    match verify_c_strs(verify::verify_sr25519, address, signature, message, options) {
+      Ok(outcome) if outcome.valid == 1 => VALID,
+      Ok(_) => INVALID,
+      Err(error) => error.code,
+    }
+  }
+
+  /// Parse item `i` for `verify::check_sr25519_batch`, or `None` if it is
+  /// to be verified alone.
+  fn prepare(&self, i: usize, options: &Options) -> Option<Sr25519Item<'_>> {
+    // Safety: as in `verify`, and non-NULL entries are NUL-terminated
+    let (address, signature, message) = unsafe {
+      let address = str_from_ptr(*self.addresses.add(i), "address").ok()?;
+      let signature = str_from_ptr(*self.signatures.add(i), "signature").ok()?;
+      (address, signature, str_from_ptr(*self.messages.add(i), "message").ok()?)
+    };
+    let Cow::Borrowed(message) = message::decode(message.as_bytes(), options).ok()? else {
+      return None;
+    };
+    verify::prepare_sr25519(address, signature, message, options)
+  }
+
+  /// Verify items `start..start + results.len()` in one schnorrkel batch,
+  /// then one by one only if the batch fails, returning the number of
+  /// failures. Items the batch cannot take are verified alone up front.
+  fn verify_chunk(&self, start: usize, results: &mut [i32], options: &Options) -> usize {
+    let mut items = Vec::with_capacity(results.len());
+    for (i, result) in (start..).zip(results.iter_mut()) {
+      match self.prepare(i, options) {
+        Some(item) => {
+          items.push(item);
+          *result = PENDING;
+        }
+        None => *result = self.verify(i, options),
+      }
+    }
+    let all_valid = verify::check_sr25519_batch(&items);
+    if !all_valid {
+      log::log(LOG_DEBUG, || format!("batch of {} sr25519 signatures failed, checking each", items.len()));
+    }
+    for (i, result) in (start..).zip(results.iter_mut()).filter(|(_, result)| **result == PENDING) {
+      *result = if all_valid { VALID } else { self.verify(i, options) };
+    }
+    results.iter().filter(|&&result| result != VALID).count()
+  }
+}
+
+/// Body of the batch exports: check the arrays, then hand them and the
+/// results slice to `run`, which returns the number of failures.
+fn run_batch(
+  addresses: *const *const c_char,
+  signatures: *const *const c_char,
+  messages: *const *const c_char,
+  count: usize,
+  results: *mut i32,
+  run: impl FnOnce(Batch, &mut [i32]) -> Result<usize, Error>,
+) -> i32 {
+  if count == 0 {
+    return 0;
+  }
+  if addresses.is_null() || signatures.is_null() || messages.is_null() || results.is_null() {
+    set_last_error(&VerifyError::NullPointer { name: "batch array".to_owned() }.into());
+    return ERR_NULL_POINTER;
+  }
+
+  // Safety: the caller passes arrays of `count` elements
+  let results = unsafe { std::slice::from_raw_parts_mut(results, count) };
+  match run(Batch { addresses, signatures, messages }, results) {
+    Ok(failures) => {
+      log::log(LOG_INFO, || format!("verified a batch of {count}, {failures} failed"));
+      stats::record(count as u64, failures as u64);
+      i32::try_from(failures).unwrap_or(i32::MAX)
+    }
+    Err(error) => {
+      set_last_error(&error);
+      error.code
+    }
+  }
+}
+
+/// The number of cores, which caps the threads a parallel batch uses.
+fn max_threads() -> usize {
+  thread::available_parallelism().map_or(1, |cores| cores.get())
+}
+
+/// The threads a parallel batch asked for `num_threads` uses: 0 and counts
+/// above the number of cores mean one per core.
+fn clamp_threads(num_threads: u32) -> usize {
+  match usize::try_from(num_threads) {
+    Ok(0) | Err(_) => max_threads(),
+    Ok(n) => n.min(max_threads()),
+  }
+}
+
+/// The pool of one thread per core that parallel batches share, started
+/// by the first one and kept for the life of the process, so later batches
+/// of any size start straight away.
+fn pool() -> Result<&'static ThreadPool, Error> {
+  static POOL: OnceLock<ThreadPool> = OnceLock::new();
+  if let Some(pool) = POOL.get() {
+    return Ok(pool);
+  }
+  let num_threads = max_threads();
+  let pool = ThreadPoolBuilder::new()
+    .num_threads(num_threads)
+    .thread_name(|i| format!("psv-batch-{i}"))
+    .build()
+    .map_err(|err| {
+      Error::from(VerifyError::ThreadPool { reason: format!("cannot start {num_threads} batch threads: {err}") })
+    })?;
+  log::log(LOG_DEBUG, || format!("started a pool of {num_threads} batch threads"));
+  // A pool another host thread started meanwhile wins, and this one stops
+  Ok(POOL.get_or_init(|| pool))
+}
+
+/// Verify many Polkadot SR25519 signatures in one call
+///
+/// Item `i` is verified like `verify_polkadot_signature_v2` with
+/// `addresses[i]`, `signatures[i]` and `messages[i]`. Carries on past
+/// failures, and leaves `get_last_error` alone for them, so a batch costs
+/// one FFI call rather than one per item.
+///
+/// Items are checked with schnorrkel's batch verification in chunks, which
+/// is several times faster than one at a time while all are valid. A
+/// chunk that fails is checked again one item at a time, so each result is
+/// exactly what verifying that item alone gives.
+///
+/// # Arguments
+/// * `addresses` - Array of `count` SS58 addresses
+/// * `signatures` - Array of `count` hex signatures
+/// * `messages` - Array of `count` messages
+/// * `count` - Number of items
+/// * `results` - Array of `count` codes to fill with what
+///   `verify_polkadot_signature_v2` would return for each item, including
+///   `ERR_NULL_POINTER` for NULL entries
+///
+/// # Returns
+/// * The number of items whose result is not `VALID`, so 0 means every
+///   signature verified. Saturates at `i32::MAX`.
+/// * `ERR_NULL_POINTER` if an array is NULL while `count` is not 0, in
+///   which case `results` is untouched
+#[no_mangle]
+pub extern "C" fn verify_batch(
+  addresses: *const *const c_char,
+  signatures: *const *const c_char,
+  messages: *const *const c_char,
+  count: usize,
+  results: *mut i32,
+) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    run_batch(addresses, signatures, messages, count, results, |batch, results| {
+      let options = Options::default();
+      let chunks = results.chunks_mut(CHUNK).enumerate();
+      Ok(chunks.map(|(n, chunk)| batch.verify_chunk(n * CHUNK, chunk, &options)).sum())
+    })
+  })
+}
+
+/// Verify many Polkadot SR25519 signatures across several threads
+///
+/// Takes the arguments of `verify_batch`, fills `results` with the same
+/// codes at the same indices, and returns the same count. Chunks of items
+/// are shared out over at most `num_threads` threads of a pool of one
+/// thread per core, started by the first call and reused by later ones.
+/// Safe to call from several host threads at once, which then share the
+/// pool.
+///
+/// A log callback set with `set_log_callback` may be called from the pool
+/// threads.
+///
+/// # Arguments
+/// * `num_threads` - Most threads to use, or 0 for one per core. Counts
+///   above the number of cores are clamped to it.
+///
+/// # Returns
+/// * As for `verify_batch`
+/// * `ERR_THREAD_POOL` if the pool threads could not be started, in which
+///   case `results` is untouched
+#[no_mangle]
+pub extern "C" fn verify_batch_parallel(
+  addresses: *const *const c_char,
+  signatures: *const *const c_char,
+  messages: *const *const c_char,
+  count: usize,
+  results: *mut i32,
+  num_threads: u32,
+) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    run_batch(addresses, signatures, messages, count, results, |batch, results| {
+      // One task per thread, each taking a run of whole chunks
+      let run = results.len().div_ceil(CHUNK).div_ceil(clamp_threads(num_threads)) * CHUNK;
+      let options = Options::default();
+      let verify_run = |(r, results): (usize, &mut [i32])| -> usize {
+        let chunks = results.chunks_mut(CHUNK).enumerate();
+        chunks.map(|(n, chunk)| batch.verify_chunk(r * run + n * CHUNK, chunk, &options)).sum()
+      };
+      Ok(pool()?.install(|| results.par_chunks_mut(run).enumerate().map(verify_run).sum()))
+    })
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::ffi::error::{clear_last_error, get_last_error};
+  use crate::{ERR_BAD_HEX, ERR_BAD_SIGNATURE_LENGTH};
+  use sp_core::crypto::Ss58Codec;
+  use sp_core::{sr25519, Pair};
+  use std::ffi::CString;
+
+  /// Owned inputs for a batch. `kind(i)` picks what item `i` is: 0 valid,
+  /// 1 tampered, 2 bad hex, 3 truncated, 4 with a NULL message.
+  struct Inputs {
+    address: CString,
+    signatures: Vec<CString>,
+    messages: Vec<Option<CString>>,
+  }
+
+  impl Inputs {
+    fn new(count: usize, kind: impl Fn(usize) -> usize) -> Inputs {
+      let pair = sr25519::Pair::from_string("//Alice", None).unwrap();
+      let mut signatures = Vec::new();
+      let mut messages = Vec::new();
+      for i in 0..count {
+        let message = format!("claim {i}");
+        let signature = format!("0x{}", hex::encode(pair.sign(message.as_bytes())));
+        let (signature, message) = match kind(i) {
+          1 => (signature, Some(format!("claim {}", i + 1))),
+          2 => ("0xzz".to_owned(), Some(message)),
+          3 => (signature[..66].to_owned(), Some(message)),
+          4 => (signature, None),
+          _ => (signature, Some(message)),
+        };
+        signatures.push(CString::new(signature).unwrap());
+        messages.push(message.map(|message| CString::new(message).unwrap()));
+      }
+      Inputs { address: CString::new(pair.public().to_ss58check()).unwrap(), signatures, messages }
+    }
+
+    /// Every kind of item in turn.
+    fn mixed(count: usize) -> Inputs {
+      Inputs::new(count, |i| i % 5)
+    }
+
+    fn arrays(&self) -> [Vec<*const c_char>; 3] {
+      [
+        vec![self.address.as_ptr(); self.signatures.len()],
+        self.signatures.iter().map(|signature| signature.as_ptr()).collect(),
+        self.messages.iter().map(|message| message.as_ref().map_or(std::ptr::null(), |m| m.as_ptr())).collect(),
+      ]
+    }
+
+    /// Verify the inputs serially, or on `num_threads` threads, returning
+    /// the failure count and the codes.
+    fn run(&self, num_threads: Option<u32>) -> (i32, Vec<i32>) {
+      let count = self.signatures.len();
+      let [addresses, signatures, messages] = self.arrays();
+      let mut results = vec![PENDING; count];
+      let (addresses, signatures, messages) = (addresses.as_ptr(), signatures.as_ptr(), messages.as_ptr());
+      let failures = match num_threads {
+        None => verify_batch(addresses, signatures, messages, count, results.as_mut_ptr()),
+        Some(n) => verify_batch_parallel(addresses, signatures, messages, count, results.as_mut_ptr(), n),
+      };
+      (failures, results)
+    }
+
+    /// Verify each item alone, bypassing schnorrkel's batching.
+    fn run_alone(&self) -> Vec<i32> {
+      let [addresses, signatures, messages] = self.arrays();
+      let batch = Batch { addresses: addresses.as_ptr(), signatures: signatures.as_ptr(), messages: messages.as_ptr() };
+      (0..self.signatures.len()).map(|i| batch.verify(i, &Options::default())).collect()
+    }
+  }
+
+  #[test]
+  fn test_verify_batch() {
+    clear_last_error();
+    let (failures, results) = Inputs::mixed(1000).run(None);
+    assert_eq!(failures, 800);
+    for (i, &result) in results.iter().enumerate() {
+      let expected = [VALID, INVALID, ERR_BAD_HEX, ERR_BAD_SIGNATURE_LENGTH, ERR_NULL_POINTER][i % 5];
+      assert_eq!(result, expected, "item {i}");
+    }
+    // Item failures leave the last error alone
+    assert!(get_last_error().is_null());
+
+    assert_eq!(verify_batch(std::ptr::null(), std::ptr::null(), std::ptr::null(), 0, std::ptr::null_mut()), 0);
+    let mut result = 0;
+    let code = verify_batch(std::ptr::null(), std::ptr::null(), std::ptr::null(), 1, &mut result);
+    assert_eq!(code, ERR_NULL_POINTER);
+  }
+
+  #[test]
+  fn test_parallel_matches_serial() {
+    let inputs = Inputs::mixed(1000);
+    let serial = inputs.run(None);
+    for num_threads in [0, 1, 3, 8] {
+      assert_eq!(inputs.run(Some(num_threads)), serial, "{num_threads} threads");
+    }
+    // Host threads may share a pool
+    std::thread::scope(|scope| {
+      let threads: Vec<_> = (0..4).map(|_| scope.spawn(|| inputs.run(Some(3)))).collect();
+      for thread in threads {
+        assert_eq!(thread.join().unwrap(), serial);
+      }
+    });
+  }
+
+  #[test]
+  fn test_thread_count_clamped() {
+    let cores = max_threads();
+    assert_eq!(clamp_threads(0), cores);
+    assert_eq!(clamp_threads(1), 1);
+    assert_eq!(clamp_threads(u32::MAX), cores);
+
+    // Every count runs on the one pool, without starting threads of its own
+    let inputs = Inputs::mixed(300);
+    let serial = inputs.run(None);
+    for num_threads in [u32::MAX, cores as u32 + 1, 1] {
+      assert_eq!(inputs.run(Some(num_threads)), serial, "{num_threads} threads");
+    }
+    assert_eq!(pool().unwrap().current_num_threads(), cores);
+  }
+
+  #[test]
+  fn test_sr25519_batch_fast_path() {
+    let pair = sr25519::Pair::from_string("//Alice", None).unwrap();
+    let address = pair.public().to_ss58check();
+    let options = Options::default();
+    // Long messages are signed and checked as their hash
+    let messages = ["hello".to_owned(), "<Bytes>hello</Bytes>".to_owned(), "x".repeat(300), String::new()];
+    let sign = |message: &String| {
+      let signed = message::canonicalize_message(message.as_bytes(), &options).unwrap();
+      format!("0x{}", hex::encode(pair.sign(&signed)))
+    };
+    let signatures: Vec<_> = messages.iter().map(sign).collect();
+    let prepare = |signature: &str, message: &'static [u8]| {
+      verify::prepare_sr25519(&address, signature, message, &options).unwrap()
+    };
+    let mut items: Vec<_> = (0..4)
+      .map(|i| verify::prepare_sr25519(&address, &signatures[i], messages[i].as_bytes(), &options).unwrap())
+      .collect();
+    assert!(verify::check_sr25519_batch(&items));
+
+    items[2] = prepare(&signatures[2], b"tampered");
+    assert!(!verify::check_sr25519_batch(&items));
+    assert!(verify::prepare_sr25519(&address, "0xzz", b"hello", &options).is_none());
+  }
+
+  #[test]
+  fn test_all_valid() {
+    let inputs = Inputs::new(300, |_| 0);
+    assert_eq!(inputs.run(None), (0, vec![VALID; 300]));
+    assert_eq!(inputs.run(Some(2)), (0, vec![VALID; 300]));
+  }
+
+  #[test]
+  fn test_one_bad_apple() {
+    let inputs = Inputs::new(300, |i| usize::from(i == 137));
+    let (failures, results) = inputs.run(None);
+    assert_eq!(failures, 1);
+    for (i, &result) in results.iter().enumerate() {
+      assert_eq!(result, if i == 137 { INVALID } else { VALID }, "item {i}");
+    }
+    assert_eq!(inputs.run(Some(2)), (failures, results));
+  }
+
+  #[test]
+  fn test_batched_matches_alone() {
+    let sparse = Inputs::new(500, |i| i % 97 % 5);
+    let chunk_starts = Inputs::new(200, |i| usize::from(i % CHUNK == 0));
+    for inputs in [Inputs::mixed(1000), sparse, chunk_starts] {
+      assert_eq!(inputs.run(None).1, inputs.run_alone());
+    }
+  }
+}
@@ -0,0 +1,164 @@
+//! Finding which of several addresses made a signature, from C.
+
+use crate::api::VerifyError;
+use crate::candidates::{self, Signed};
+use crate::error::{set_last_error, Error};
+use crate::ffi::guard::ffi_guard;
+use crate::parse::str_from_ptr;
+use crate::ERR_PANIC;
+use std::ffi::{c_char, CStr};
+use std::slice;
+
+/// Returned by `verify_against_addresses` when no candidate verifies.
+pub const NO_MATCHING_ADDRESS: i64 = -1;
+
+/// The return value of `verify_against_addresses` for `code`, moved down
+/// by one so `ERR_NULL_POINTER` (-1) is not mistaken for no match.
+fn error_result(code: i32) -> i64 {
+  i64::from(code) - 1
+}
+
+/// Candidate `address` as a string, or why it was skipped.
+fn candidate<'a>(address: *const c_char) -> Result<&'a str, Error> {
+  if address.is_null() {
+    return Err(VerifyError::NullPointer { name: "address".to_owned() }.into());
+  }
+  // Safety: non-NULL entries are NUL-terminated strings
+  unsafe { CStr::from_ptr(address) }
+    .to_str()
+    .map_err(|e| Error::from(VerifyError::InvalidUtf8 { reason: format!("address is not valid UTF-8: {e}") }))
+}
+
+/// Find the address that made a Polkadot SR25519 signature
+///
+/// # Arguments
+/// * `addresses` - Array of `count` candidate SS58 addresses, or raw public
+///   keys in hex as for `verify_polkadot_signature`
+/// * `count` - Number of candidates
+/// * `signature_ptr` - Hex-encoded signature (with or without 0x prefix)
+/// * `message_ptr` - The original message that was signed
+///
+/// Candidates that are NULL or fail to parse are skipped with a
+/// `LOG_WARN` message, so one malformed entry does not hide a match.
+///
+/// # Returns
+/// * The index of the first candidate the signature verifies against
+/// * `NO_MATCHING_ADDRESS` (-1) if there is none
+/// * One less than an `ERR_*` code if the signature or message is
+///   malformed, or `addresses` is NULL while `count` is not 0: -2 for
+///   `ERR_NULL_POINTER`, -5 for `ERR_BAD_HEX` and so on. `get_last_error`
+///   has the details.
+#[no_mangle]
+pub extern "C" fn verify_against_addresses(
+  addresses: *const *const c_char,
+  count: usize,
+  signature_ptr: *const c_char,
+  message_ptr: *const c_char,
+) -> i64 {
+  ffi_guard(error_result(ERR_PANIC), || {
+    let result = (|| {
+      // Safety: Convert C strings to Rust strings
+      let signature = unsafe { str_from_ptr(signature_ptr, "signature")? };
+      let message = unsafe { str_from_ptr(message_ptr, "message")? };
+      let signed = Signed::decode(signature, message)?;
+      if count == 0 {
+        return Ok(candidates::find_signer(&signed, []));
+      }
+      if addresses.is_null() {
+        return Err(VerifyError::NullPointer { name: "addresses".to_owned() }.into());
+      }
+
+      // Safety: the caller passes an array of `count` elements
+      let addresses = unsafe { slice::from_raw_parts(addresses, count) };
+      Ok(candidates::find_signer(&signed, addresses.iter().map(|&address| candidate(address))))
+    })();
+    match result {
+      Ok(Some(index)) => index as i64,
+      Ok(None) => NO_MATCHING_ADDRESS,
+      Err(error) => {
+        set_last_error(&error);
+        error_result(error.code)
+      }
+    }
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::address_cache::parse_address_cached;
+  use crate::log::{capture, LOG_WARN};
+  use crate::{ERR_BAD_HEX, ERR_NULL_POINTER, ERR_SS58_BAD_CHECKSUM};
+  use sp_core::crypto::Ss58Codec;
+  use sp_core::{sr25519, Pair};
+  use std::ffi::CString;
+
+  const NAMES: [&str; 5] = ["//Alice", "//Bob", "//Charlie", "//Dave", "//Eve"];
+
+  fn address(name: &str) -> CString {
+    CString::new(sr25519::Pair::from_string(name, None).unwrap().public().to_ss58check()).unwrap()
+  }
+
+  fn signed_by(name: &str, message: &str) -> CString {
+    let pair = sr25519::Pair::from_string(name, None).unwrap();
+    CString::new(format!("0x{}", hex::encode(pair.sign(message.as_bytes())))).unwrap()
+  }
+
+  fn find(addresses: &[CString], signature: &CString, message: &str) -> i64 {
+    let pointers: Vec<*const c_char> = addresses.iter().map(|address| address.as_ptr()).collect();
+    let message = CString::new(message).unwrap();
+    verify_against_addresses(pointers.as_ptr(), pointers.len(), signature.as_ptr(), message.as_ptr())
+  }
+
+  #[test]
+  fn test_finds_signer_at_any_position() {
+    let addresses: Vec<CString> = NAMES.iter().map(|name| address(name)).collect();
+    for (index, name) in NAMES.iter().enumerate() {
+      assert_eq!(find(&addresses, &signed_by(name, "recover me"), "recover me"), index as i64);
+    }
+    // The first match wins
+    let twice = [addresses[1].clone(), addresses[0].clone(), addresses[1].clone()];
+    assert_eq!(find(&twice, &signed_by("//Bob", "recover me"), "recover me"), 0);
+  }
+
+  #[test]
+  fn test_no_match() {
+    let addresses: Vec<CString> = NAMES[..3].iter().map(|name| address(name)).collect();
+    assert_eq!(find(&addresses, &signed_by("//Ferdie", "recover me"), "recover me"), NO_MATCHING_ADDRESS);
+    assert_eq!(find(&addresses, &signed_by("//Alice", "recover me"), "tampered"), NO_MATCHING_ADDRESS);
+    assert_eq!(find(&[], &signed_by("//Alice", "recover me"), "recover me"), NO_MATCHING_ADDRESS);
+  }
+
+  #[test]
+  fn test_malformed_candidate_is_skipped() {
+    let mut corrupted = address("//Bob").into_string().unwrap();
+    corrupted.replace_range(10..11, if &corrupted[10..11] == "a" { "b" } else { "a" });
+    let addresses = [address("//Alice"), CString::new(corrupted).unwrap(), address("//Charlie")];
+    let signature = signed_by("//Charlie", "recover me");
+
+    let mut index = 0;
+    let received = capture(|| index = find(&addresses, &signature, "recover me"));
+    assert_eq!(index, 2);
+    let warnings: Vec<_> = received.into_iter().filter(|(level, _)| *level == LOG_WARN).collect();
+    assert_eq!(warnings, vec![(LOG_WARN, "skipped candidate 1: SS58 checksum mismatch".to_owned())]);
+    let skipped = candidate(addresses[1].as_ptr()).and_then(|address| parse_address_cached(address)?.public_key());
+    assert_eq!(skipped.err().map(|error| error.code), Some(ERR_SS58_BAD_CHECKSUM));
+
+    // A NULL entry is skipped the same way
+    let message = CString::new("recover me").unwrap();
+    let pointers = [std::ptr::null(), addresses[2].as_ptr()];
+    assert_eq!(verify_against_addresses(pointers.as_ptr(), 2, signature.as_ptr(), message.as_ptr()), 1);
+  }
+
+  #[test]
+  fn test_malformed_inputs() {
+    let addresses = [address("//Alice")];
+    assert_eq!(find(&addresses, &CString::new("0xzz").unwrap(), "recover me"), error_result(ERR_BAD_HEX));
+    let message = CString::new("recover me").unwrap();
+    let signature = signed_by("//Alice", "recover me");
+    let code = verify_against_addresses(std::ptr::null(), 1, signature.as_ptr(), message.as_ptr());
+    assert_eq!(code, -2);
+    let code = verify_against_addresses(std::ptr::null(), 1, std::ptr::null(), message.as_ptr());
+    assert_eq!(code, error_result(ERR_NULL_POINTER));
+  }
+}
@@ -0,0 +1,109 @@
+//! Descriptions of the return codes of [`crate::codes`], for C callers.
+
+use crate::codes::*;
+use crate::ffi::guard::ffi_guard;
+use std::ffi::c_char;
+
+/// NUL-terminated English description of `code`.
+fn description(code: i32) -> &'static str {
+  match code {
+    VALID => "signature is valid\0",
+    INVALID => "signature does not match address and message\0",
+    ERR_NULL_POINTER => "a required pointer argument was NULL\0",
+    ERR_INVALID_UTF8 => "a string argument was not valid UTF-8\0",
+    ERR_BAD_SS58 => "address is not a valid SS58 string\0",
+    ERR_BAD_HEX => "signature is not valid hex\0",
+    ERR_BAD_SIGNATURE_LENGTH => "signature has the wrong length\0",
+    ERR_PANIC => "internal error in the verifier library\0",
+    ERR_WRONG_NETWORK => "address belongs to a network that is not allowed\0",
+    ERR_SS58_BAD_BASE58 => "address is not valid base58\0",
+    ERR_SS58_BAD_LENGTH => "address has the wrong length\0",
+    ERR_SS58_BAD_CHECKSUM => "address checksum mismatch\0",
+    ERR_NO_MATCHING_SCHEME => "signature length matches no supported scheme for this address\0",
+    ERR_BAD_MULTISIGNATURE => "MultiSignature encoding is malformed\0",
+    ERR_BAD_SIGNATURE_PREFIX => "signature starts with an unknown scheme byte\0",
+    ERR_BAD_ETH_ADDRESS => "address is not a 20-byte hex Ethereum address\0",
+    ERR_ETH_ADDRESS_CHECKSUM => "address has an invalid EIP-55 checksum\0",
+    ERR_UNKNOWN_SCHEME => "unknown signature scheme\0",
+    ERR_NON_CANONICAL_SIGNATURE => "signature is a non-canonical, malleable encoding\0",
+    ERR_BAD_MESSAGE_HEX => "message is not valid hex\0",
+    ERR_BAD_BASE64 => "signature or message is not valid base64\0",
+    ERR_INVALID_OPTIONS => "options contradict each other\0",
+    ERR_NOT_STRICT => "input needs normalizing, which strict mode forbids\0",
+    ERR_INVALID_HANDLE => "handle is NULL, unknown, or already finalized or freed\0",
+    ERR_FILE_NOT_FOUND => "file does not exist\0",
+    ERR_FILE_PERMISSION_DENIED => "file may not be read\0",
+    ERR_FILE_IO => "file could not be read\0",
+    ERR_INVALID_UTF16 => "a wide string argument was not valid UTF-16\0",
+    ERR_BAD_JSON => "request is not valid JSON or misses a required field\0",
+    ERR_SIWS_MALFORMED => "message is not a valid Sign-In with Substrate message\0",
+    ERR_SIWS_ADDRESS_MISMATCH => "sign-in message is for another address\0",
+    ERR_SIWS_DOMAIN_MISMATCH => "sign-in message is for another domain\0",
+    ERR_SIWS_NONCE_MISMATCH => "sign-in message has an unexpected nonce\0",
+    ERR_EXPIRED => "message has expired\0",
+    ERR_NOT_YET_VALID => "message is not valid yet\0",
+    ERR_TIMESTAMP_NOT_FOUND => "message has no timestamp in the expected format\0",
+    ERR_REPLAYED => "nonce was already used\0",
+    ERR_BAD_PUBLIC_KEY_LENGTH => "hex public key has the wrong length\0",
+    ERR_BAD_FIRST_ADDRESS => "first address is invalid\0",
+    ERR_BAD_SECOND_ADDRESS => "second address is invalid\0",
+    ERR_HARD_DERIVATION => "hard derivation needs the secret key\0",
+    ERR_BAD_DERIVATION_PATH => "derivation path is malformed\0",
+    ERR_UNSUPPORTED_KEY_TYPE => "key type is not supported\0",
+    ERR_BAD_DID_KEY => "did:key is malformed\0",
+    ERR_WRONG_KEY_LENGTH => "address payload has an unsupported length\0",
+    ERR_BAD_SEED_LENGTH => "signing seed has the wrong length\0",
+    ERR_BAD_MNEMONIC => "mnemonic is malformed\0",
+    ERR_UNKNOWN_MNEMONIC_WORD => "mnemonic has an unknown word\0",
+    ERR_BAD_MNEMONIC_CHECKSUM => "mnemonic checksum mismatch\0",
+    ERR_UNKNOWN_DEV_ACCOUNT => "unknown dev account\0",
+    ERR_BAD_SEED => "seed is not a valid secret key\0",
+    ERR_THREAD_POOL => "thread pool could not be started\0",
+    ERR_JOB_CANCELLED => "job was cancelled before it ran\0",
+    ERR_SCHEME_NOT_SUPPORTED => "scheme or operation is not supported by this build\0",
+    ERR_NOT_SIGNED => "extrinsic is not signed\0",
+    ERR_BAD_EXTRINSIC => "extrinsic is malformed\0",
+    ERR_BAD_ERA => "era is malformed\0",
+    ERR_BAD_HEX_LENGTH => "hex argument has the wrong length\0",
+    _ => "unknown error\0",
+  }
+}
+
+/// Describe a return code
+///
+/// # Returns
+/// A static, NUL-terminated English description. Never NULL, unknown codes
+/// map to "unknown error". The pointer must not be freed.
+#[no_mangle]
+pub extern "C" fn error_code_to_string(code: i32) -> *const c_char {
+  ffi_guard(description(ERR_PANIC), || description(code)).as_ptr() as *const c_char
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::collections::HashSet;
+  use std::ffi::CStr;
+
+  fn to_string(code: i32) -> &'static str {
+    unsafe { CStr::from_ptr(error_code_to_string(code)) }.to_str().unwrap()
+  }
+
+  #[test]
+  fn test_descriptions_unique() {
+    let unknown = to_string(i32::MIN);
+    let mut seen = HashSet::new();
+    for &code in ALL_CODES {
+      let description = to_string(code);
+      assert!(!description.is_empty(), "code {code} has an empty description");
+      assert_ne!(description, unknown, "code {code} has no description");
+      assert!(seen.insert(description), "code {code} shares its description");
+    }
+  }
+
+  #[test]
+  fn test_unknown_code() {
+    assert_eq!(to_string(i32::MIN), "unknown error");
+    assert_eq!(to_string(12345), "unknown error");
+  }
+}
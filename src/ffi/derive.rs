@@ -0,0 +1,175 @@
+//! Soft derivation of sr25519 public keys, from C.
+
+use crate::derive;
+use crate::error::set_last_error;
+use crate::ffi::guard::ffi_guard;
+use crate::ffi::string_or_null;
+use crate::parse::str_from_ptr;
+use crate::verify::verdict;
+use crate::{ERR_PANIC, VALID};
+use std::ffi::c_char;
+
+/// Derive a soft child of an sr25519 public key
+///
+/// # Arguments
+/// * `parent_ptr` - SS58-encoded address or hex public key of the parent
+/// * `path_ptr` - Soft junctions such as `/payments/0`. Numeric junctions
+///   are integers, as with `sp_core` and `subkey`. The empty path returns
+///   the parent.
+///
+/// # Returns
+/// * The child in the parent's form: an SS58 address with the same prefix,
+///   or `0x` and 64 hex digits. Release it with `free_string`.
+/// * NULL if the parent does not decode, the path has a hard junction
+///   (`ERR_HARD_DERIVATION`) or is malformed (`ERR_BAD_DERIVATION_PATH`),
+///   see `get_last_error`
+#[no_mangle]
+pub extern "C" fn derive_child_public_key(parent_ptr: *const c_char, path_ptr: *const c_char) -> *mut c_char {
+  ffi_guard(std::ptr::null_mut(), || {
+    let result = (|| {
+      // Safety: Convert C strings to Rust strings
+      let parent = unsafe { str_from_ptr(parent_ptr, "parent")? };
+      let path = unsafe { str_from_ptr(path_ptr, "path")? };
+      derive::derive_child(parent, path)
+    })();
+    string_or_null(result)
+  })
+}
+
+/// Verify a Polkadot SR25519 signature made by a soft-derived child key
+///
+/// # Arguments
+/// * `root_address_ptr` - SS58-encoded address or hex public key of the
+///   registered root
+/// * `soft_path_ptr` - Soft junctions leading from the root to the signer,
+///   such as `/payments/0`. The empty path verifies against the root
+///   exactly like `verify_polkadot_signature_v2`.
+/// * `signature_ptr` - Hex-encoded signature, with or without 0x prefix
+/// * `message_ptr` - The original message that was signed
+///
+/// # Returns
+/// The same codes as `verify_polkadot_signature_v2`, plus
+/// `ERR_HARD_DERIVATION` or `ERR_BAD_DERIVATION_PATH` for paths that
+/// cannot be applied to a public key.
+#[no_mangle]
+pub extern "C" fn verify_with_derivation(
+  root_address_ptr: *const c_char,
+  soft_path_ptr: *const c_char,
+  signature_ptr: *const c_char,
+  message_ptr: *const c_char,
+) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    let result = (|| {
+      // Safety: Convert C strings to Rust strings
+      let root = unsafe { str_from_ptr(root_address_ptr, "address")? };
+      let path = unsafe { str_from_ptr(soft_path_ptr, "path")? };
+      let signature = unsafe { str_from_ptr(signature_ptr, "signature")? };
+      let message = unsafe { str_from_ptr(message_ptr, "message")? };
+
+      derive::verify_with_derivation(root, path, signature, message.as_bytes())
+    })();
+    verdict(result)
+  })
+}
+
+/// Check a derivation path without deriving anything
+///
+/// # Arguments
+/// * `path_ptr` - Junctions such as `//polkadot/0` or `/payments/0`,
+///   optionally followed by `///password`, as accepted by
+///   `sign_with_mnemonic` and `address_from_mnemonic`. The empty path is
+///   valid.
+///
+/// # Returns
+/// * `VALID` (1) if the path parses
+/// * `ERR_BAD_DERIVATION_PATH` if it does not, see `get_last_error`
+/// * `ERR_NULL_POINTER` or `ERR_INVALID_UTF8` for bad arguments
+#[no_mangle]
+pub extern "C" fn validate_derivation_path(path_ptr: *const c_char) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    // Safety: Convert C strings to Rust strings
+    let result = unsafe { str_from_ptr(path_ptr, "path") }.and_then(derive::parse_suri_path);
+    match result {
+      Ok(_) => VALID,
+      Err(error) => {
+        set_last_error(&error);
+        error.code
+      }
+    }
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::ffi::error::get_last_error;
+  use crate::ffi::json::free_string;
+  use crate::ffi::verify_polkadot_signature_v2;
+  use crate::{ERR_BAD_DERIVATION_PATH, ERR_HARD_DERIVATION, ERR_NULL_POINTER, ERR_SS58_BAD_CHECKSUM};
+  use std::ffi::{CStr, CString};
+
+  fn derive(parent: &str, path: &str) -> Result<String, String> {
+    let parent = CString::new(parent).unwrap();
+    let path = CString::new(path).unwrap();
+    let child = derive_child_public_key(parent.as_ptr(), path.as_ptr());
+    if child.is_null() {
+      return Err(unsafe { CStr::from_ptr(get_last_error()) }.to_str().unwrap().to_owned());
+    }
+    let string = unsafe { CStr::from_ptr(child) }.to_str().unwrap().to_owned();
+    free_string(child);
+    Ok(string)
+  }
+
+  #[test]
+  fn test_derive_child_public_key() {
+    let polkadot = "16Y8x28cS6LYFu3v8KViD2sKjTdNbBZf8sr6bUtaS7iuRDs5";
+    assert_eq!(derive(polkadot, "/x"), Ok(derive::derive_child(polkadot, "/x").unwrap()));
+    assert!(derive(polkadot, "//x").unwrap_err().contains("hard junction"));
+    assert!(derive_child_public_key(std::ptr::null(), std::ptr::null()).is_null());
+  }
+
+  #[test]
+  fn test_validate_derivation_path() {
+    let validate = |path: &str| {
+      let path = CString::new(path).unwrap();
+      validate_derivation_path(path.as_ptr())
+    };
+    assert_eq!(validate("//polkadot//0/payments///secret"), VALID);
+    assert_eq!(validate(""), VALID);
+    assert_eq!(validate("polkadot"), ERR_BAD_DERIVATION_PATH);
+    assert_eq!(validate_derivation_path(std::ptr::null()), ERR_NULL_POINTER);
+  }
+
+  const ADDRESS: &str = "5HbqogsYaK54pN3QAgSi4t3Asqditt1X4P7cSBuDt2hPF2BR";
+  const SIGNATURE: &str = "0xf8ce1b80e76bf48a30610d0d3c2c9c8dbfdcb28a9aa8f56ac038d520ff17445ff3ce94809f8479a1224ef0d823d035022a9db2e72bd6c0d2f0244f766dec908b";
+  const MESSAGE: &str = "<Bytes>c15335d817e3d3d912d30f5a18a30c30162abc364cd0876d3a94af568d3c8c14</Bytes>";
+
+  fn verify_derived(root: &str, path: &str, signature: &str, message: &str) -> i32 {
+    let root = CString::new(root).unwrap();
+    let path = CString::new(path).unwrap();
+    let signature = CString::new(signature).unwrap();
+    let message = CString::new(message).unwrap();
+    verify_with_derivation(root.as_ptr(), path.as_ptr(), signature.as_ptr(), message.as_ptr())
+  }
+
+  #[test]
+  fn test_verify_with_derivation_bad_paths() {
+    assert_eq!(verify_derived(ADDRESS, "//payments", SIGNATURE, MESSAGE), ERR_HARD_DERIVATION);
+    assert_eq!(verify_derived(ADDRESS, "payments", SIGNATURE, MESSAGE), ERR_BAD_DERIVATION_PATH);
+  }
+
+  #[test]
+  fn test_verify_with_empty_path_matches_v2() {
+    let corrupted = ADDRESS.replace("hPF2BR", "hPF2BS");
+    for (address, message) in [(ADDRESS, MESSAGE), (ADDRESS, "tampered"), (corrupted.as_str(), MESSAGE)] {
+      let v2 = {
+        let (address, signature, message) =
+          (CString::new(address).unwrap(), CString::new(SIGNATURE).unwrap(), CString::new(message).unwrap());
+        verify_polkadot_signature_v2(address.as_ptr(), signature.as_ptr(), message.as_ptr())
+      };
+      assert_eq!(verify_derived(address, "", SIGNATURE, message), v2, "{address} {message}");
+    }
+    assert_eq!(verify_derived(ADDRESS, "", SIGNATURE, MESSAGE), VALID);
+    assert_eq!(verify_derived(&corrupted, "", SIGNATURE, MESSAGE), ERR_SS58_BAD_CHECKSUM);
+  }
+}
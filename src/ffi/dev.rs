@@ -0,0 +1,148 @@
+//! The well-known development accounts, from C.
+
+use crate::api::VerifyError;
+use crate::dev;
+use crate::error::Error;
+use crate::ffi::guard::ffi_guard;
+use crate::ffi::string_or_null;
+use crate::parse::str_from_ptr;
+use crate::scheme::SignatureScheme;
+use std::ffi::c_char;
+
+fn scheme_from_i32(scheme: i32) -> Result<SignatureScheme, Error> {
+  SignatureScheme::from_i32(scheme)
+    .ok_or_else(|| VerifyError::UnknownScheme { reason: format!("unknown signature scheme {scheme}") }.into())
+}
+
+/// Address of a well-known dev account, for tests only
+///
+/// # Arguments
+/// * `name_ptr` - `Alice`, `Bob`, `Charlie`, `Dave`, `Eve` or `Ferdie`,
+///   ignoring case. The key is `Pair::from_string("//<name>", None)`.
+/// * `scheme` - `SCHEME_SR25519`, `SCHEME_ED25519` or `SCHEME_ECDSA`
+/// * `prefix` - SS58 network prefix, such as 42 for generic Substrate
+///
+/// # Returns
+/// * The SS58 address. Release it with `free_string`.
+/// * NULL on failure, with `get_last_error` naming
+///   `ERR_UNKNOWN_DEV_ACCOUNT`, `ERR_UNKNOWN_SCHEME` or `ERR_BAD_SS58`
+#[no_mangle]
+pub extern "C" fn dev_account_address(name_ptr: *const c_char, scheme: i32, prefix: u16) -> *mut c_char {
+  ffi_guard(std::ptr::null_mut(), || {
+    let result = (|| {
+      // Safety: Convert C strings to Rust strings
+      let name = unsafe { str_from_ptr(name_ptr, "name")? };
+      dev::dev_address(name, scheme_from_i32(scheme)?, prefix)
+    })();
+    string_or_null(result)
+  })
+}
+
+/// Sign a message as a well-known dev account, for tests only
+///
+/// # Arguments
+/// * `name_ptr` - Dev account name, as for `dev_account_address`
+/// * `scheme` - `SCHEME_SR25519`, `SCHEME_ED25519` or `SCHEME_ECDSA`
+/// * `message_ptr` - The message to sign, signed as its UTF-8 bytes with
+///   no wrapping
+///
+/// # Returns
+/// * The 0x-prefixed hex signature, which `verify_signature_with_options`
+///   accepts for the same scheme. Release it with `free_string`.
+/// * NULL on failure, see `get_last_error`
+#[no_mangle]
+pub extern "C" fn dev_account_sign(name_ptr: *const c_char, scheme: i32, message_ptr: *const c_char) -> *mut c_char {
+  ffi_guard(std::ptr::null_mut(), || {
+    let result = (|| {
+      // Safety: Convert C strings to Rust strings
+      let name = unsafe { str_from_ptr(name_ptr, "name")? };
+      let message = unsafe { str_from_ptr(message_ptr, "message")? };
+      dev::dev_sign(name, scheme_from_i32(scheme)?, message.as_bytes())
+    })();
+    string_or_null(result)
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::ffi::error::get_last_error;
+  use crate::ffi::json::free_string;
+  use crate::ffi::{verify_polkadot_signature_v2, verify_signature_with_options};
+  use crate::{SCHEME_ECDSA, SCHEME_ETHEREUM, SCHEME_SR25519, INVALID, VALID};
+  use std::ffi::{CStr, CString};
+
+  fn take(string: *mut c_char) -> Option<String> {
+    if string.is_null() {
+      return None;
+    }
+    let owned = unsafe { CStr::from_ptr(string) }.to_str().unwrap().to_owned();
+    free_string(string);
+    Some(owned)
+  }
+
+  fn address(name: &str, scheme: i32, prefix: u16) -> Option<String> {
+    let name = CString::new(name).unwrap();
+    take(dev_account_address(name.as_ptr(), scheme, prefix))
+  }
+
+  fn sign(name: &str, scheme: i32, message: &str) -> Option<String> {
+    let name = CString::new(name).unwrap();
+    let message = CString::new(message).unwrap();
+    take(dev_account_sign(name.as_ptr(), scheme, message.as_ptr()))
+  }
+
+  fn verify(address: &str, signature: &str, message: &str, scheme: i32) -> i32 {
+    let address = CString::new(address).unwrap();
+    let signature = CString::new(signature).unwrap();
+    let message = CString::new(message).unwrap();
+    verify_signature_with_options(address.as_ptr(), signature.as_ptr(), message.as_ptr(), scheme, std::ptr::null())
+  }
+
+  fn last_error() -> String {
+    unsafe { CStr::from_ptr(get_last_error()) }.to_str().unwrap().to_owned()
+  }
+
+  #[test]
+  fn test_alice_signs_and_verifies() {
+    let alice = address("Alice", SCHEME_SR25519, 42).unwrap();
+    assert_eq!(alice, "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY");
+    assert_eq!(address("alice", SCHEME_SR25519, 0).unwrap(), "15oF4uVJwmo4TdGW7VfQxNLavjCXviqxT9S1MgbjMNHr6Sp5");
+
+    let signature = sign("Alice", SCHEME_SR25519, "hello polkadot").unwrap();
+    let (alice, signature_c, message) =
+      (CString::new(alice).unwrap(), CString::new(signature).unwrap(), CString::new("hello polkadot").unwrap());
+    assert_eq!(verify_polkadot_signature_v2(alice.as_ptr(), signature_c.as_ptr(), message.as_ptr()), VALID);
+  }
+
+  #[test]
+  fn test_every_account_and_scheme() {
+    for name in ["Alice", "Bob", "Charlie", "Dave", "Eve", "Ferdie"] {
+      let schemes = [SignatureScheme::Sr25519, SignatureScheme::Ed25519, SignatureScheme::Ecdsa];
+      for scheme in schemes.into_iter().filter(|scheme| scheme.is_supported()).map(|scheme| scheme as i32) {
+        let account = address(name, scheme, 42).unwrap();
+        let signature = sign(name, scheme, "hello").unwrap();
+        assert_eq!(verify(&account, &signature, "hello", scheme), VALID, "{name} {scheme}");
+        assert_eq!(verify(&account, &signature, "tampered", scheme), INVALID, "{name} {scheme}");
+      }
+    }
+    assert_eq!(address("Bob", SCHEME_SR25519, 42).unwrap(), "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty");
+    assert_eq!(address("Alice", SCHEME_ECDSA, 42).unwrap(), "5C7C2Z5sWbytvHpuLTvzKunnnRwQxft1jiqrLD5rhucQ5S9X");
+  }
+
+  #[test]
+  fn test_bad_arguments() {
+    assert_eq!(address("Mallory", SCHEME_SR25519, 42), None);
+    assert_eq!(
+      last_error(),
+      "unknown dev account \"Mallory\", expected one of [\"Alice\", \"Bob\", \"Charlie\", \"Dave\", \"Eve\", \"Ferdie\"]"
+    );
+    assert_eq!(sign("Mallory", SCHEME_SR25519, "hello"), None);
+    assert_eq!(address("Alice", SCHEME_ETHEREUM, 42), None);
+    assert_eq!(last_error(), "ethereum addresses are not SS58");
+    assert_eq!(sign("Alice", 0, "hello"), None);
+    assert_eq!(last_error(), "unknown signature scheme 0");
+    assert_eq!(address("Alice", SCHEME_SR25519, 16_384), None);
+    assert_eq!(last_error(), "SS58 prefix 16384 is above 16383");
+  }
+}
@@ -0,0 +1,63 @@
+//! Verifying by `did:key` signer identifiers, from C.
+
+use crate::did;
+use crate::ffi::guard::ffi_guard;
+use crate::parse::str_from_ptr;
+use crate::verify::verdict;
+use crate::ERR_PANIC;
+use std::ffi::c_char;
+
+/// Verify a signature by the signer a `did:key` identifies
+///
+/// # Arguments
+/// * `did_key_ptr` - `did:key:z...` with an sr25519, ed25519 or secp256k1
+///   public key, optionally followed by a `#` fragment
+/// * `signature_ptr` - Hex-encoded signature, with or without 0x prefix:
+///   64 bytes for sr25519 and ed25519, 65 recoverable bytes for secp256k1
+/// * `message_ptr` - The original message that was signed
+///
+/// The scheme follows from the key type, and the message is verified as
+/// given, like `verify_polkadot_signature_v2`.
+///
+/// # Returns
+/// The same codes as `verify_polkadot_signature_v2`, with
+/// `ERR_UNSUPPORTED_KEY_TYPE` for other key types and `ERR_BAD_DID_KEY` for
+/// identifiers that do not decode.
+#[no_mangle]
+pub extern "C" fn verify_by_did_key(
+  did_key_ptr: *const c_char,
+  signature_ptr: *const c_char,
+  message_ptr: *const c_char,
+) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    let result = (|| {
+      // Safety: Convert C strings to Rust strings
+      let did = unsafe { str_from_ptr(did_key_ptr, "did:key")? };
+      let signature = unsafe { str_from_ptr(signature_ptr, "signature")? };
+      let message = unsafe { str_from_ptr(message_ptr, "message")? };
+
+      did::verify_by_did_key(did, signature, message.as_bytes())
+    })();
+    verdict(result)
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{ERR_NULL_POINTER, INVALID, VALID};
+  use std::ffi::CString;
+
+  const SR25519_DID: &str = "did:key:z6QP2VWUW6pmp7CBUv5r6TcG17BVGu8uvX96Vzfz8RAPGVnw";
+  const SIGNATURE: &str = "0xf8ce1b80e76bf48a30610d0d3c2c9c8dbfdcb28a9aa8f56ac038d520ff17445ff3ce94809f8479a1224ef0d823d035022a9db2e72bd6c0d2f0244f766dec908b";
+  const MESSAGE: &str = "<Bytes>c15335d817e3d3d912d30f5a18a30c30162abc364cd0876d3a94af568d3c8c14</Bytes>";
+
+  #[test]
+  fn test_verify_by_did_key() {
+    let (did, signature) = (CString::new(SR25519_DID).unwrap(), CString::new(SIGNATURE).unwrap());
+    let (message, tampered) = (CString::new(MESSAGE).unwrap(), CString::new("tampered").unwrap());
+    assert_eq!(verify_by_did_key(did.as_ptr(), signature.as_ptr(), message.as_ptr()), VALID);
+    assert_eq!(verify_by_did_key(did.as_ptr(), signature.as_ptr(), tampered.as_ptr()), INVALID);
+    assert_eq!(verify_by_did_key(std::ptr::null(), signature.as_ptr(), message.as_ptr()), ERR_NULL_POINTER);
+  }
+}
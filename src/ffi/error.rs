@@ -0,0 +1,41 @@
+//! The last-error slot of [`crate::error`], read from C.
+
+use crate::error;
+use crate::ffi::guard::ffi_guard;
+use std::ffi::c_char;
+
+/// Describe the most recent failure on the calling thread
+///
+/// # Returns
+/// * A NUL-terminated message, valid until the next call into this library
+///   on the same thread. The library keeps ownership: do not free it.
+/// * NULL if nothing failed since the last `clear_last_error`
+///
+/// Like `errno`, the slot is not reset by successful calls.
+#[no_mangle]
+pub extern "C" fn get_last_error() -> *const c_char {
+  ffi_guard(std::ptr::null(), error::last_error_ptr)
+}
+
+/// Forget the most recent failure on the calling thread.
+#[no_mangle]
+pub extern "C" fn clear_last_error() {
+  ffi_guard((), error::clear_last_error);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::api::VerifyError;
+  use crate::error::set_last_error;
+  use std::ffi::CStr;
+
+  #[test]
+  fn test_get_and_clear() {
+    set_last_error(&VerifyError::InvalidJson { reason: "failed".to_owned() }.into());
+    // Safety: a non-NULL last error is a NUL-terminated string
+    assert_eq!(unsafe { CStr::from_ptr(get_last_error()) }.to_str(), Ok("failed"));
+    clear_last_error();
+    assert!(get_last_error().is_null());
+  }
+}
@@ -0,0 +1,70 @@
+//! Freshness checks for challenges that embed their issue time, from C.
+
+use crate::expiry;
+use crate::ffi::guard::ffi_guard;
+use crate::parse::str_from_ptr;
+use crate::verify::verdict;
+use crate::ERR_PANIC;
+use std::ffi::c_char;
+
+/// Verify a Polkadot SR25519 signature over a challenge that must be fresh
+///
+/// Takes the same first three arguments as `verify_polkadot_signature`,
+/// plus:
+/// * `issued_at_format_ptr` - The challenge with `{issued_at}` in place of
+///   its decimal Unix timestamp and `{*}` in place of any other text, e.g.
+///   `login:{issued_at}:{*}`
+/// * `max_age_secs` - How long after issue the challenge stays valid
+/// * `now_unix` - Current time in seconds since the Unix epoch
+///
+/// # Returns
+/// The same codes as `verify_polkadot_signature_v2`, plus for validly
+/// signed challenges:
+/// * `ERR_EXPIRED` when `now_unix - issued_at > max_age_secs`
+/// * `ERR_NOT_YET_VALID` when the challenge is dated more than
+///   `MAX_CLOCK_SKEW_SECS` after `now_unix`
+/// * `ERR_TIMESTAMP_NOT_FOUND` when the message does not match the format
+#[no_mangle]
+pub extern "C" fn verify_with_expiry(
+  address_ptr: *const c_char,
+  signature_ptr: *const c_char,
+  message_ptr: *const c_char,
+  issued_at_format_ptr: *const c_char,
+  max_age_secs: u64,
+  now_unix: i64,
+) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    let result = (|| {
+      // Safety: Convert C strings to Rust strings
+      let address = unsafe { str_from_ptr(address_ptr, "address")? };
+      let signature = unsafe { str_from_ptr(signature_ptr, "signature")? };
+      let message = unsafe { str_from_ptr(message_ptr, "message")? };
+      let format = unsafe { str_from_ptr(issued_at_format_ptr, "format")? };
+      expiry::verify_fresh(address, signature, message, format, max_age_secs, now_unix)
+    })();
+    verdict(result)
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{ERR_EXPIRED, ERR_NULL_POINTER, VALID};
+  use sp_core::crypto::Ss58Codec;
+  use sp_core::{sr25519, Pair};
+  use std::ffi::CString;
+
+  #[test]
+  fn test_verify_with_expiry() {
+    let pair = sr25519::Pair::from_string("//Alice", None).unwrap();
+    let address = CString::new(pair.public().to_ss58check()).unwrap();
+    let signature = CString::new(hex::encode(pair.sign(b"login:1714000000:9f8e7d"))).unwrap();
+    let message = CString::new("login:1714000000:9f8e7d").unwrap();
+    let format = CString::new("login:{issued_at}:{*}").unwrap();
+    let verify = |now| verify_with_expiry(address.as_ptr(), signature.as_ptr(), message.as_ptr(), format.as_ptr(), 300, now);
+    assert_eq!(verify(1_714_000_000), VALID);
+    assert_eq!(verify(1_714_000_301), ERR_EXPIRED);
+    let code = verify_with_expiry(address.as_ptr(), signature.as_ptr(), message.as_ptr(), std::ptr::null(), 300, 0);
+    assert_eq!(code, ERR_NULL_POINTER);
+  }
+}
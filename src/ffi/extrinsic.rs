@@ -0,0 +1,197 @@
+//! Signature checks for signed extrinsics and signing payloads, from C.
+
+use crate::api::VerifyError;
+use crate::error::Error;
+use crate::extrinsic::{self, Chain};
+use crate::ffi::guard::ffi_guard;
+use crate::parse::str_from_ptr;
+use crate::verify::verdict;
+use crate::ERR_PANIC;
+use std::ffi::c_char;
+
+/// The string argument `name`, or `None` if it is NULL.
+///
+/// Safety: `ptr` is NULL or a NUL-terminated string.
+unsafe fn optional_str<'a>(ptr: *const c_char, name: &str) -> Result<Option<&'a str>, Error> {
+  if ptr.is_null() {
+    return Ok(None);
+  }
+  str_from_ptr(ptr, name).map(Some)
+}
+
+/// Verify the signature of a signed extrinsic
+///
+/// Decodes a v4 signed extrinsic, rebuilds the payload its signer signed,
+/// hashed with `blake2_256` if over 256 bytes, and verifies the embedded
+/// `MultiSignature` against the embedded signer.
+///
+/// The signed extensions must be those of the Polkadot and Kusama relay
+/// chains: an era, a compact nonce and a compact tip, followed on runtimes
+/// since 2024 by the `CheckMetadataHash` mode byte. `metadata_hash_mode`
+/// says which layout the runtime uses, or `METADATA_HASH_DETECT` tries
+/// both for callers that do not know. Chains with other extensions, such
+/// as `ChargeAssetTxPayment`, never verify.
+///
+/// # Arguments
+/// * `extrinsic_ptr` - Hex-encoded extrinsic, with or without 0x prefix,
+///   including its compact length prefix
+/// * `genesis_hash_ptr` - Hex-encoded genesis hash of the chain
+/// * `block_hash_ptr` - Hex-encoded hash of the block a mortal extrinsic's
+///   era starts at. May be NULL for immortal extrinsics, which commit to
+///   the genesis hash instead.
+/// * `spec_version`, `transaction_version` - The runtime versions the
+///   extrinsic was signed for
+/// * `metadata_hash_mode` - One of the `METADATA_HASH_*` modes. With
+///   `METADATA_HASH_DISABLED` or `METADATA_HASH_ENABLED`, the extrinsic
+///   must carry that mode.
+/// * `metadata_hash_ptr` - Hex-encoded metadata hash, needed for
+///   `METADATA_HASH_ENABLED` and for detecting an extrinsic signed with it.
+///   May be NULL otherwise.
+///
+/// # Returns
+/// * `VALID` or `INVALID`
+/// * `ERR_NOT_SIGNED` if the extrinsic is unsigned
+/// * `ERR_BAD_EXTRINSIC` if it cannot be decoded, its signer is not an
+///   account ID, or it carries another `CheckMetadataHash` mode than
+///   `metadata_hash_mode`
+/// * `ERR_INVALID_OPTIONS` for an unknown `metadata_hash_mode`
+/// * `ERR_BAD_MULTISIGNATURE` for an unknown signature variant
+/// * `ERR_NULL_POINTER` if the extrinsic is mortal and `block_hash_ptr` is
+///   NULL, or `METADATA_HASH_ENABLED` is given without a metadata hash
+/// * `ERR_BAD_HEX`, or `ERR_BAD_HEX_LENGTH` for a hash that is not
+///   32 bytes
+#[no_mangle]
+pub extern "C" fn verify_extrinsic(
+  extrinsic_ptr: *const c_char,
+  genesis_hash_ptr: *const c_char,
+  block_hash_ptr: *const c_char,
+  spec_version: u32,
+  transaction_version: u32,
+  metadata_hash_mode: i32,
+  metadata_hash_ptr: *const c_char,
+) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    let result = (|| {
+      // Safety: Convert C strings to Rust strings
+      let extrinsic = unsafe { str_from_ptr(extrinsic_ptr, "extrinsic")? };
+      let genesis_hash = unsafe { str_from_ptr(genesis_hash_ptr, "genesis_hash")? };
+      let block_hash = unsafe { optional_str(block_hash_ptr, "block_hash")? };
+      let metadata_hash = unsafe { optional_str(metadata_hash_ptr, "metadata_hash")? };
+      let chain =
+        Chain::decode(genesis_hash, block_hash, spec_version, transaction_version, metadata_hash_mode, metadata_hash)?;
+      extrinsic::verify_extrinsic(extrinsic, &chain)
+    })();
+    verdict(result)
+  })
+}
+
+/// Verify a signature over a transaction's signing payload
+///
+/// For when only the fields a wallet showed before signing are at hand,
+/// not the extrinsic. The payload is assembled as `SignedPayload` encodes
+/// it for the relay chains' signed extensions: the call, the era, the
+/// compact nonce and tip, the spec and transaction versions, the genesis
+/// hash and the block hash, then `CheckMetadataHash` if the runtime has
+/// it: its mode byte after the tip and the optional metadata hash after the
+/// block hash. It is verified as `blake2_256` of itself if over 256 bytes.
+///
+/// # Arguments
+/// * `address_ptr` - SS58-encoded address of the signer
+/// * `signature_ptr` - Hex-encoded sr25519 signature, with or without 0x
+///   prefix. A `MultiSignature` as returned by `signer.signPayload`,
+///   tagged 0x01 for sr25519, 0x00 for ed25519 or 0x02 for ECDSA, is
+///   accepted too.
+/// * `call_ptr` - Hex-encoded call, without a length prefix
+/// * `era_ptr` - Hex-encoded `Era`: `00` for an immortal era, or its two
+///   bytes
+/// * `nonce` - The account nonce
+/// * `tip_ptr` - The tip as a decimal integer, since it can exceed 64 bits
+/// * `spec_version`, `transaction_version` - The runtime versions
+/// * `genesis_hash_ptr` - Hex-encoded genesis hash of the chain
+/// * `block_hash_ptr` - Hex-encoded hash of the block a mortal era starts
+///   at. May be NULL for an immortal era.
+/// * `metadata_hash_mode` - `METADATA_HASH_ABSENT`,
+///   `METADATA_HASH_DISABLED` or `METADATA_HASH_ENABLED`
+/// * `metadata_hash_ptr` - Hex-encoded metadata hash, needed for
+///   `METADATA_HASH_ENABLED`. May be NULL otherwise.
+///
+/// # Returns
+/// The same codes as `verify_polkadot_signature_v2`, plus
+/// * `ERR_BAD_ERA` if the era is malformed
+/// * `ERR_INVALID_OPTIONS` for `METADATA_HASH_DETECT`, an unknown mode or
+///   a tip that is not a decimal `u128`
+/// * `ERR_BAD_MULTISIGNATURE` for a 0x02-tagged signature when ECDSA
+///   support is not compiled in
+/// * `ERR_NULL_POINTER` if the era is mortal and `block_hash_ptr` is NULL,
+///   or `METADATA_HASH_ENABLED` is given without a metadata hash
+/// * `ERR_BAD_HEX`, or `ERR_BAD_HEX_LENGTH` for a hash that is not
+///   32 bytes
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub extern "C" fn verify_signing_payload(
+  address_ptr: *const c_char,
+  signature_ptr: *const c_char,
+  call_ptr: *const c_char,
+  era_ptr: *const c_char,
+  nonce: u64,
+  tip_ptr: *const c_char,
+  spec_version: u32,
+  transaction_version: u32,
+  genesis_hash_ptr: *const c_char,
+  block_hash_ptr: *const c_char,
+  metadata_hash_mode: i32,
+  metadata_hash_ptr: *const c_char,
+) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    let result = (|| {
+      // Safety: Convert C strings to Rust strings
+      let address = unsafe { str_from_ptr(address_ptr, "address")? };
+      let signature = unsafe { str_from_ptr(signature_ptr, "signature")? };
+      let call = unsafe { str_from_ptr(call_ptr, "call")? };
+      let era = unsafe { str_from_ptr(era_ptr, "era")? };
+      let tip = unsafe { str_from_ptr(tip_ptr, "tip")? };
+      let reason = || format!("tip {tip:?} is not a decimal integer");
+      let tip = tip.parse().map_err(|_| VerifyError::InvalidOption { reason: reason() })?;
+      let genesis_hash = unsafe { str_from_ptr(genesis_hash_ptr, "genesis_hash")? };
+      let block_hash = unsafe { optional_str(block_hash_ptr, "block_hash")? };
+      let metadata_hash = unsafe { optional_str(metadata_hash_ptr, "metadata_hash")? };
+      let chain =
+        Chain::decode(genesis_hash, block_hash, spec_version, transaction_version, metadata_hash_mode, metadata_hash)?;
+      extrinsic::verify_signing_payload(address, signature, call, era, nonce, tip, &chain)
+    })();
+    verdict(result)
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{ERR_BAD_HEX_LENGTH, ERR_INVALID_OPTIONS, ERR_NULL_POINTER, INVALID, METADATA_HASH_ABSENT};
+  use std::ffi::CString;
+
+  #[test]
+  fn test_c_arguments() {
+    let genesis_hash = CString::new(hex::encode([0x91; 32])).unwrap();
+    let null = std::ptr::null();
+    let code = verify_extrinsic(null, genesis_hash.as_ptr(), null, 1, 1, METADATA_HASH_ABSENT, null);
+    assert_eq!(code, ERR_NULL_POINTER);
+    let (extrinsic, short) = (CString::new("0x00").unwrap(), CString::new("0x1234").unwrap());
+    let code = verify_extrinsic(extrinsic.as_ptr(), short.as_ptr(), null, 1, 1, METADATA_HASH_ABSENT, null);
+    assert_eq!(code, ERR_BAD_HEX_LENGTH);
+
+    let address = CString::new("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY").unwrap();
+    let signature = CString::new(format!("0x{}", "00".repeat(64))).unwrap();
+    let (call, era) = (CString::new("0000").unwrap(), CString::new("00").unwrap());
+    let (address, signature, call, era) = (address.as_ptr(), signature.as_ptr(), call.as_ptr(), era.as_ptr());
+    let mode = METADATA_HASH_ABSENT;
+    let verify = |tip: &str, genesis_hash| {
+      let tip = CString::new(tip).unwrap();
+      verify_signing_payload(address, signature, call, era, 0, tip.as_ptr(), 1, 1, genesis_hash, null, mode, null)
+    };
+    assert_eq!(verify("0", genesis_hash.as_ptr()), INVALID);
+    assert_eq!(verify(&u128::MAX.to_string(), genesis_hash.as_ptr()), INVALID);
+    assert_eq!(verify("0", null), ERR_NULL_POINTER);
+    assert_eq!(verify("-1", genesis_hash.as_ptr()), ERR_INVALID_OPTIONS);
+    assert_eq!(verify("0x10", genesis_hash.as_ptr()), ERR_INVALID_OPTIONS);
+  }
+}
@@ -0,0 +1,63 @@
+//! Verification of signatures over file contents, from C.
+
+use crate::ffi::guard::ffi_guard;
+use crate::file;
+use crate::parse::str_from_ptr;
+use crate::verify::verdict;
+use crate::ERR_PANIC;
+use std::ffi::c_char;
+
+/// Verify a Polkadot SR25519 signature over the contents of a file
+///
+/// # Arguments
+/// * `address_ptr` - SS58-encoded address
+/// * `signature_ptr` - Hex-encoded signature (with or without 0x prefix)
+/// * `path_ptr` - UTF-8 path of the file
+///
+/// The file is read in chunks, never whole. Like
+/// `verify_polkadot_signature_bytes`, files up to 256 bytes must be signed
+/// directly and longer ones as the `blake2_256` hash of their contents.
+///
+/// # Returns
+/// The same codes as `verify_polkadot_signature_v2`, plus
+/// `ERR_FILE_NOT_FOUND`, `ERR_FILE_PERMISSION_DENIED` or `ERR_FILE_IO` when
+/// the file cannot be read.
+#[no_mangle]
+pub extern "C" fn verify_polkadot_signature_file(
+  address_ptr: *const c_char,
+  signature_ptr: *const c_char,
+  path_ptr: *const c_char,
+) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    let result = (|| {
+      // Safety: Convert C strings to Rust strings
+      let address = unsafe { str_from_ptr(address_ptr, "address")? };
+      let signature = unsafe { str_from_ptr(signature_ptr, "signature")? };
+      let path = unsafe { str_from_ptr(path_ptr, "path")? };
+      file::verify_file(address, signature, path)
+    })();
+    verdict(result.map(|outcome| outcome.valid == 1))
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{ERR_FILE_NOT_FOUND, ERR_NULL_POINTER, VALID};
+  use sp_core::{crypto::Ss58Codec, sr25519, Pair};
+  use std::ffi::CString;
+
+  #[test]
+  fn test_verify_file() {
+    let pair = sr25519::Pair::from_string("//Alice", None).unwrap();
+    let address = CString::new(pair.public().to_ss58check()).unwrap();
+    let signature = CString::new(hex::encode(pair.sign(b"release notes"))).unwrap();
+    let path = std::env::temp_dir().join(format!("psv-{}-ffi", std::process::id()));
+    std::fs::write(&path, b"release notes").unwrap();
+    let path_c = CString::new(path.to_str().unwrap()).unwrap();
+    assert_eq!(verify_polkadot_signature_file(address.as_ptr(), signature.as_ptr(), path_c.as_ptr()), VALID);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(verify_polkadot_signature_file(address.as_ptr(), signature.as_ptr(), path_c.as_ptr()), ERR_FILE_NOT_FOUND);
+    assert_eq!(verify_polkadot_signature_file(address.as_ptr(), signature.as_ptr(), std::ptr::null()), ERR_NULL_POINTER);
+  }
+}
@@ -0,0 +1,83 @@
+//! Keeps panics from unwinding across the FFI boundary.
+//!
+//! Every exported function runs its body through [`ffi_guard`], which turns
+//! a panic into the function's failure value and records the panic message
+//! as the last error.
+
+use crate::api::VerifyError;
+use crate::error::{set_last_error, Error};
+use crate::log::{self, LOG_ERROR};
+use crate::stats;
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+
+/// Extract the message passed to `panic!`.
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+  if let Some(message) = payload.downcast_ref::<&str>() {
+    message
+  } else if let Some(message) = payload.downcast_ref::<String>() {
+    message
+  } else {
+    "unknown panic payload"
+  }
+}
+
+/// Run `body`, turning a panic into an `ERR_PANIC` error.
+pub(crate) fn catch<T>(body: impl FnOnce() -> Result<T, Error>) -> Result<T, Error> {
+  panic::catch_unwind(AssertUnwindSafe(body)).unwrap_or_else(|payload| {
+    let message = format!("internal panic: {}", panic_message(&*payload));
+    log::log(LOG_ERROR, || message.clone());
+    Err(VerifyError::Panic { reason: message }.into())
+  })
+}
+
+/// Run the body of an exported function, returning `on_panic` if it panics.
+pub(crate) fn ffi_guard<T>(on_panic: T, body: impl FnOnce() -> T) -> T {
+  stats::timed(|| match catch(|| Ok(body())) {
+    Ok(value) => value,
+    Err(error) => {
+      set_last_error(&error);
+      on_panic
+    }
+  })
+}
+
+#[cfg(test)]
+thread_local! {
+  static PANIC_ON_NEXT_CALL: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+}
+
+/// Make the next verification on this thread panic.
+#[cfg(test)]
+pub(crate) fn panic_on_next_call() {
+  PANIC_ON_NEXT_CALL.with(|flag| flag.set(true));
+}
+
+/// Panic if a test asked for it, see [`panic_on_next_call`].
+#[cfg(test)]
+pub(crate) fn test_hook() {
+  if PANIC_ON_NEXT_CALL.with(|flag| flag.replace(false)) {
+    panic!("test hook panic");
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::error::with_last_error;
+
+  fn last_error() -> String {
+    with_last_error(|message| message.unwrap_or_default().to_owned())
+  }
+
+  #[test]
+  fn test_ffi_guard_passes_values_through() {
+    assert_eq!(ffi_guard(-1, || 7), 7);
+    assert_eq!(ffi_guard(-1, || panic!("boom")), -1);
+    assert_eq!(last_error(), "internal panic: boom");
+
+    let formatted = ffi_guard(String::new(), || panic!("{} {}", "formatted", 42));
+    assert_eq!(formatted, "");
+    assert_eq!(last_error(), "internal panic: formatted 42");
+  }
+}
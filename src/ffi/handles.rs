@@ -0,0 +1,79 @@
+//! Opaque handles given out over FFI.
+//!
+//! A handle is an id into a registry rather than a pointer to memory, so a
+//! handle that was already freed, or never issued, is reported instead of
+//! being dereferenced. Ids start at 1 so no handle is NULL, and are never
+//! reused.
+
+use crate::api::VerifyError;
+use crate::error::Error;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+
+/// The live handles of one kind, each mapped to its shared state.
+pub(crate) struct HandleRegistry<T> {
+  /// Names the handle in error messages, such as `"keypair"`.
+  kind: &'static str,
+  entries: OnceLock<Mutex<HashMap<usize, Arc<T>>>>,
+  next_id: AtomicUsize,
+}
+
+impl<T> HandleRegistry<T> {
+  pub(crate) const fn new(kind: &'static str) -> Self {
+    HandleRegistry { kind, entries: OnceLock::new(), next_id: AtomicUsize::new(1) }
+  }
+
+  fn entries(&self) -> MutexGuard<'_, HashMap<usize, Arc<T>>> {
+    self.entries.get_or_init(Default::default).lock().unwrap_or_else(|e| e.into_inner())
+  }
+
+  fn unknown<H>(&self, handle: *mut H) -> Error {
+    let reason = format!("{} handle {handle:p} is unknown or already freed", self.kind);
+    VerifyError::InvalidHandle { reason }.into()
+  }
+
+  /// Register `value` under a new handle.
+  pub(crate) fn insert<H>(&self, value: T) -> *mut H {
+    let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+    self.entries().insert(id, Arc::new(value));
+    id as *mut H
+  }
+
+  /// The state behind `handle`, which stays registered.
+  pub(crate) fn get<H>(&self, handle: *mut H) -> Result<Arc<T>, Error> {
+    self.entries().get(&(handle as usize)).cloned().ok_or_else(|| self.unknown(handle))
+  }
+
+  /// Unregister `handle`. Calls already holding its state keep it until
+  /// they return.
+  pub(crate) fn remove<H>(&self, handle: *mut H) -> Result<Arc<T>, Error> {
+    self.entries().remove(&(handle as usize)).ok_or_else(|| self.unknown(handle))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::ERR_INVALID_HANDLE;
+
+  enum Handle {}
+
+  #[test]
+  fn test_lifecycle() {
+    let registry = HandleRegistry::new("test");
+    let first: *mut Handle = registry.insert(1);
+    let second: *mut Handle = registry.insert(2);
+    assert!(!first.is_null());
+    assert_ne!(first, second);
+    assert_eq!(*registry.get(first).unwrap(), 1);
+
+    assert_eq!(*registry.remove(first).unwrap(), 1);
+    let error = registry.get(first).unwrap_err();
+    assert_eq!(error.code, ERR_INVALID_HANDLE);
+    assert_eq!(error.message, format!("test handle {first:p} is unknown or already freed"));
+    assert!(registry.remove(first).is_err());
+    assert!(registry.get(std::ptr::null_mut::<Handle>()).is_err());
+    assert_eq!(*registry.get(second).unwrap(), 2);
+  }
+}
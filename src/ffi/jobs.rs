@@ -0,0 +1,329 @@
+//! Verification off the caller's thread, for hosts running an event loop.
+//!
+//! Jobs queue up for a pool of worker threads, started by the first
+//! `verify_async` and stopped by `async_shutdown`. Arguments are copied when
+//! a job is submitted, so the caller's strings may be freed straight away.
+
+use crate::api::VerifyError;
+use crate::error::{set_last_error, Error};
+use crate::ffi::guard::ffi_guard;
+use crate::ffi::verify_polkadot_signature_v2;
+use crate::log::{self, LOG_DEBUG};
+use crate::ERR_JOB_CANCELLED;
+use std::collections::VecDeque;
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+
+/// Receives the code `verify_polkadot_signature_v2` returned for a job, or
+/// `ERR_JOB_CANCELLED`, and the `user_data` the job was submitted with.
+pub type AsyncCallback = extern "C" fn(result: i32, user_data: *mut c_void);
+
+/// The caller's `user_data`, handed back untouched on whichever thread
+/// runs the callback.
+struct UserData(*mut c_void);
+
+// Safety: the library never dereferences it
+unsafe impl Send for UserData {}
+
+struct Job {
+  id: u64,
+  address: Option<CString>,
+  signature: Option<CString>,
+  message: Option<CString>,
+  user_data: UserData,
+  callback: AsyncCallback,
+}
+
+/// NULL for an argument that was NULL when submitted, so the job fails as
+/// the synchronous call would.
+fn ptr(string: &Option<CString>) -> *const c_char {
+  string.as_ref().map_or(std::ptr::null(), |string| string.as_ptr())
+}
+
+impl Job {
+  fn run(self) {
+    let result = verify_polkadot_signature_v2(ptr(&self.address), ptr(&self.signature), ptr(&self.message));
+    log::log(LOG_DEBUG, || format!("async job {} finished with code {result}", self.id));
+    (self.callback)(result, self.user_data.0);
+  }
+
+  fn cancel(self) {
+    log::log(LOG_DEBUG, || format!("async job {} cancelled", self.id));
+    (self.callback)(ERR_JOB_CANCELLED, self.user_data.0);
+  }
+}
+
+#[derive(Default)]
+struct Queue {
+  jobs: VecDeque<Job>,
+  /// Set by `async_shutdown`. Workers exit once the queue is empty.
+  closed: bool,
+}
+
+#[derive(Default)]
+struct Shared {
+  queue: Mutex<Queue>,
+  /// Signalled when a job is queued or the queue is closed.
+  changed: Condvar,
+}
+
+impl Shared {
+  fn queue(&self) -> MutexGuard<'_, Queue> {
+    self.queue.lock().unwrap_or_else(|e| e.into_inner())
+  }
+}
+
+struct Pool {
+  shared: Arc<Shared>,
+  workers: Vec<JoinHandle<()>>,
+}
+
+impl Pool {
+  fn start() -> Result<Pool, Error> {
+    let shared = Arc::new(Shared::default());
+    let count = thread::available_parallelism().map_or(1, |n| n.get());
+    let mut workers = Vec::with_capacity(count);
+    for i in 0..count {
+      let worker_shared = Arc::clone(&shared);
+      let spawned = thread::Builder::new().name(format!("psv-async-{i}")).spawn(move || work(&worker_shared));
+      match spawned {
+        Ok(worker) => workers.push(worker),
+        Err(e) => {
+          // Let the workers already started exit
+          Pool { shared, workers }.stop(true);
+          return Err(VerifyError::ThreadPool { reason: format!("async worker could not be started: {e}") }.into());
+        }
+      }
+    }
+    log::log(LOG_DEBUG, || format!("started {count} async workers"));
+    Ok(Pool { shared, workers })
+  }
+
+  /// Close the queue, cancel what is left in it unless `drain`, and wait
+  /// for the workers to exit.
+  fn stop(self, drain: bool) {
+    let abandoned = {
+      let mut queue = self.shared.queue();
+      queue.closed = true;
+      if drain { VecDeque::new() } else { std::mem::take(&mut queue.jobs) }
+    };
+    self.shared.changed.notify_all();
+    abandoned.into_iter().for_each(Job::cancel);
+    // A callback shutting the pool down cannot wait for its own thread
+    let current = thread::current().id();
+    for worker in self.workers {
+      if worker.thread().id() != current {
+        let _ = worker.join();
+      }
+    }
+  }
+}
+
+fn work(shared: &Shared) {
+  let mut queue = shared.queue();
+  loop {
+    if let Some(job) = queue.jobs.pop_front() {
+      drop(queue);
+      job.run();
+      queue = shared.queue();
+    } else if queue.closed {
+      return;
+    } else {
+      queue = shared.changed.wait(queue).unwrap_or_else(|e| e.into_inner());
+    }
+  }
+}
+
+fn pool() -> MutexGuard<'static, Option<Pool>> {
+  static POOL: Mutex<Option<Pool>> = Mutex::new(None);
+  POOL.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Ids start at 1 so 0 can mean failure, and are never reused.
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Copy a C string argument, keeping NULL as None.
+///
+/// # Safety
+/// `ptr` must be NULL or a valid NUL-terminated string.
+unsafe fn copy(ptr: *const c_char) -> Option<CString> {
+  (!ptr.is_null()).then(|| CStr::from_ptr(ptr).to_owned())
+}
+
+/// Verify a Polkadot SR25519 signature on a worker thread
+///
+/// Takes the arguments of `verify_polkadot_signature_v2`, which are copied
+/// before returning, and queues the verification for a pool of one worker
+/// thread per core. The pool starts with the first job.
+///
+/// # Arguments
+/// * `user_data` - Passed back to `callback` untouched
+/// * `callback` - Called exactly once per job id returned, on a worker
+///   thread, with the code `verify_polkadot_signature_v2` would return,
+///   or with `ERR_JOB_CANCELLED` on the thread calling `async_shutdown` if
+///   the job was abandoned. Callbacks from different jobs may run at once
+///   and in any order.
+///
+/// # Returns
+/// The job id, or 0 with the last error set if `callback` is NULL
+/// (`ERR_NULL_POINTER`) or the pool could not start (`ERR_THREAD_POOL`).
+/// The callback is not called for jobs that were not queued.
+#[no_mangle]
+pub extern "C" fn verify_async(
+  address_ptr: *const c_char,
+  signature_ptr: *const c_char,
+  message_ptr: *const c_char,
+  user_data: *mut c_void,
+  callback: Option<AsyncCallback>,
+) -> u64 {
+  ffi_guard(0, || {
+    let result = (|| {
+      let callback = callback.ok_or_else(|| Error::from(VerifyError::NullPointer { name: "callback".to_owned() }))?;
+      let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+      // Safety: the caller passes valid NUL-terminated strings or NULL
+      let job = unsafe {
+        Job {
+          id,
+          address: copy(address_ptr),
+          signature: copy(signature_ptr),
+          message: copy(message_ptr),
+          user_data: UserData(user_data),
+          callback,
+        }
+      };
+
+      let mut pool = pool();
+      if pool.is_none() {
+        *pool = Some(Pool::start()?);
+      }
+      let shared = &pool.as_ref().expect("started above").shared;
+      shared.queue().jobs.push_back(job);
+      shared.changed.notify_one();
+      Ok(id)
+    })();
+    result.unwrap_or_else(|error| {
+      set_last_error(&error);
+      0
+    })
+  })
+}
+
+/// Stop the worker threads of `verify_async`
+///
+/// Returns once no worker is running and no callback will be called
+/// again, so the library can then be unloaded. Jobs submitted later start
+/// a new pool. Does nothing if no pool is running.
+///
+/// # Arguments
+/// * `wait` - If true, queued jobs all run first. If false, jobs not yet
+///   started are cancelled: their callbacks get `ERR_JOB_CANCELLED` on
+///   this thread, before it returns. Jobs already running finish either
+///   way.
+///
+/// Called from a callback, it returns without waiting for that callback's
+/// own worker, which exits once the callback returns.
+#[no_mangle]
+pub extern "C" fn async_shutdown(wait: bool) {
+  ffi_guard((), || {
+    // Take the pool before stopping it, so callbacks may submit jobs
+    let stopping = pool().take();
+    if let Some(stopping) = stopping {
+      stopping.stop(wait);
+      log::log(LOG_DEBUG, || "async workers stopped".to_owned());
+    }
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{ERR_BAD_HEX, INVALID, VALID};
+  use sp_core::crypto::Ss58Codec;
+  use sp_core::{sr25519, Pair};
+  use std::sync::atomic::AtomicI32;
+
+  /// One slot per job, recording its result and how often it was called.
+  struct Slot {
+    result: AtomicI32,
+    calls: AtomicI32,
+  }
+
+  extern "C" fn record(result: i32, user_data: *mut c_void) {
+    // Safety: the tests pass a `Slot` that outlives the job
+    let slot = unsafe { &*(user_data as *const Slot) };
+    slot.result.store(result, Ordering::SeqCst);
+    slot.calls.fetch_add(1, Ordering::SeqCst);
+  }
+
+  fn slots(count: usize) -> Vec<Slot> {
+    (0..count).map(|_| Slot { result: AtomicI32::new(i32::MIN), calls: AtomicI32::new(0) }).collect()
+  }
+
+  fn submit(inputs: &(CString, CString, CString), slot: &Slot) -> u64 {
+    let (address, signature, message) = inputs;
+    let user_data = slot as *const Slot as *mut c_void;
+    verify_async(address.as_ptr(), signature.as_ptr(), message.as_ptr(), user_data, Some(record))
+  }
+
+  fn inputs() -> [(CString, CString, CString); 3] {
+    let pair = sr25519::Pair::from_string("//Alice", None).unwrap();
+    let address = CString::new(pair.public().to_ss58check()).unwrap();
+    let signature = CString::new(format!("0x{}", hex::encode(pair.sign(b"hello")))).unwrap();
+    let hello = CString::new("hello").unwrap();
+    [
+      (address.clone(), signature.clone(), hello.clone()),
+      (address.clone(), signature, CString::new("tampered").unwrap()),
+      (address, CString::new("0xzz").unwrap(), hello),
+    ]
+  }
+
+  // The pool is global, so the tests that shut it down run as one
+  #[test]
+  fn test_jobs_and_shutdown() {
+    let inputs = inputs();
+    let expected = [VALID, INVALID, ERR_BAD_HEX];
+
+    // Every callback fires exactly once, with the synchronous result
+    let results = slots(300);
+    let mut ids: Vec<u64> = results.iter().enumerate().map(|(i, slot)| submit(&inputs[i % 3], slot)).collect();
+    async_shutdown(true);
+    for (i, slot) in results.iter().enumerate() {
+      assert_eq!(slot.calls.load(Ordering::SeqCst), 1, "job {i}");
+      assert_eq!(slot.result.load(Ordering::SeqCst), expected[i % 3], "job {i}");
+    }
+    ids.sort_unstable();
+    ids.dedup();
+    assert_eq!(ids.len(), 300);
+    assert!(!ids.contains(&0));
+
+    // Abandoning pending work cancels what has not run, and nothing runs
+    // after shutdown returns
+    let results = slots(300);
+    for (i, slot) in results.iter().enumerate() {
+      assert_ne!(submit(&inputs[i % 3], slot), 0);
+    }
+    async_shutdown(false);
+    let calls: Vec<i32> = results.iter().map(|slot| slot.calls.load(Ordering::SeqCst)).collect();
+    assert!(calls.iter().all(|&calls| calls == 1), "{calls:?}");
+    for (i, slot) in results.iter().enumerate() {
+      let result = slot.result.load(Ordering::SeqCst);
+      assert!(result == expected[i % 3] || result == ERR_JOB_CANCELLED, "job {i}: {result}");
+    }
+
+    // Shutting down twice is harmless, and jobs then start a new pool
+    async_shutdown(false);
+    let results = slots(1);
+    assert_ne!(submit(&inputs[0], &results[0]), 0);
+    async_shutdown(true);
+    assert_eq!(results[0].result.load(Ordering::SeqCst), VALID);
+  }
+
+  #[test]
+  fn test_null_arguments() {
+    let (address, signature, message) = &inputs()[0];
+    let id = verify_async(address.as_ptr(), signature.as_ptr(), message.as_ptr(), std::ptr::null_mut(), None);
+    assert_eq!(id, 0);
+  }
+}
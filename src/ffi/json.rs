@@ -0,0 +1,218 @@
+//! Verification requests and outcomes as JSON, from C.
+
+use crate::error::set_last_error;
+use crate::ffi::guard::ffi_guard;
+use crate::json;
+use crate::parse::{bytes_from_ptr, str_from_ptr};
+use crate::types;
+use crate::verify::verdict;
+use crate::ERR_PANIC;
+use std::ffi::{CString, c_char};
+use zeroize::Zeroize;
+
+/// Verify a signature described by a JSON request
+///
+/// # Arguments
+/// * `request` - NUL-terminated JSON object with `address`, `signature`
+///   and `message` strings and an optional `options` object whose fields
+///   mirror `VerifyOptions`, e.g. `{"scheme": "ed25519", "wrap_bytes": true}`.
+///   Unknown fields are ignored.
+///
+/// # Returns
+/// A JSON object such as `{"valid":true,"scheme":"sr25519"}` to be released
+/// with `free_string`. Failures, including malformed JSON, add
+/// `"error_code"`, one of the `ERR_*` codes, and `"error_message"`.
+/// `scheme` is absent if the request could not be read. NULL is only
+/// returned if the library panicked.
+#[no_mangle]
+pub extern "C" fn verify_json(request: *const c_char) -> *mut c_char {
+  ffi_guard(std::ptr::null_mut(), || {
+    // Safety: Convert C strings to Rust strings
+    let response = match unsafe { str_from_ptr(request, "request") } {
+      Ok(request) => json::verify_json_str(request),
+      Err(error) => types::failed(None, error),
+    };
+    let json = serde_json::to_string(&response).expect("responses always serialize");
+    CString::new(json).expect("JSON escapes NUL").into_raw()
+  })
+}
+
+/// Verify requests given one per line, from a single buffer
+///
+/// For hosts where passing arrays of pointers, as to `verify_batch`, is
+/// awkward.
+///
+/// # Arguments
+/// * `input` - Newline-delimited JSON: one request per line, each an
+///   object as for `verify_json`. Lines may end in `\r\n`. Need not be
+///   NUL-terminated, and may be NULL if `input_len` is 0.
+/// * `input_len` - Number of bytes at `input`
+///
+/// Blank lines are skipped. A line that is not a valid request gets an
+/// error result, and the lines after it are still verified.
+///
+/// # Returns
+/// One line per request, in input order, each ending in a newline: the
+/// outcome `verify_json` gives, plus `index`, such as
+/// `{"index":0,"valid":true,"scheme":"sr25519"}`. `index` counts requests,
+/// skipping blank lines. To be released with
+/// `free_string`. NULL with the last error set if `input` is NULL while
+/// `input_len` is not 0.
+#[no_mangle]
+pub extern "C" fn verify_ndjson(input: *const c_char, input_len: usize) -> *mut c_char {
+  ffi_guard(std::ptr::null_mut(), || {
+    // Safety: the caller passes `input_len` readable bytes or NULL
+    let input = match unsafe { bytes_from_ptr(input as *const u8, input_len, "input") } {
+      Ok(input) => input,
+      Err(error) => {
+        set_last_error(&error);
+        return std::ptr::null_mut();
+      }
+    };
+
+    CString::new(json::verify_ndjson_bytes(input)).expect("JSON escapes NUL").into_raw()
+  })
+}
+
+/// Verify the result of a polkadot-js `signRaw` call
+///
+/// # Arguments
+/// * `address_ptr` - SS58-encoded address
+/// * `signraw_json_ptr` - The object `signer.signRaw` resolved with, as
+///   JSON, e.g. `{"id":1,"signature":"0x..."}`
+/// * `payload_ptr` - The `data` passed to `signRaw`, which the extension
+///   wrapped in `<Bytes>...</Bytes>` before signing unless it already was
+///
+/// Payloads over 256 bytes are verified as their `blake2_256` hash, as the
+/// extension signs them.
+///
+/// # Returns
+/// The same codes as `verify_polkadot_signature_v2`, plus `ERR_BAD_JSON`
+/// if the result is not JSON or `signature` is missing or not a string.
+#[no_mangle]
+pub extern "C" fn verify_signraw_result(
+  address_ptr: *const c_char,
+  signraw_json_ptr: *const c_char,
+  payload_ptr: *const c_char,
+) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    let result = (|| {
+      // Safety: Convert C strings to Rust strings
+      let address = unsafe { str_from_ptr(address_ptr, "address")? };
+      let signraw_json = unsafe { str_from_ptr(signraw_json_ptr, "signRaw result")? };
+      let payload = unsafe { str_from_ptr(payload_ptr, "payload")? };
+      json::verify_signraw(address, signraw_json, payload.as_bytes())
+    })();
+    verdict(result)
+  })
+}
+
+/// Release a string returned by this library, such as from `verify_json`
+/// or `reencode_ss58_address`
+///
+/// Every function that hands over a string says so with "release it with
+/// `free_string`", whether it returns the string or writes it to an out
+/// parameter. Those strings come from Rust's allocator, so the host's
+/// `free()` must not be used on them: with another C runtime, as is common
+/// on Windows, that corrupts the heap. Pointers that are not handed over,
+/// such as from `get_last_error`, `get_version` or `scheme_to_string`,
+/// must not be passed here. No function returns byte buffers, so there is
+/// no `free_bytes`.
+///
+/// NULL is ignored, so a failed call's NULL out parameter may be passed
+/// without checking. A string must not be freed twice: this is not
+/// detected, and is undefined behavior like a double `free()`. Set the
+/// pointer to NULL after freeing it. The bytes are zeroed before the
+/// memory is released, so a seed from `generate_sr25519_keypair` does not
+/// outlive its string.
+#[no_mangle]
+pub extern "C" fn free_string(string: *mut c_char) {
+  ffi_guard((), || {
+    if !string.is_null() {
+      // Safety: the caller passes a string from `CString::into_raw` above
+      unsafe { CString::from_raw(string) }.into_bytes_with_nul().zeroize();
+    }
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{ERR_NULL_POINTER, VALID};
+  use serde_json::{json, Value};
+  use std::ffi::CStr;
+
+  const ADDRESS: &str = "5HbqogsYaK54pN3QAgSi4t3Asqditt1X4P7cSBuDt2hPF2BR";
+  const SIGNATURE: &str = "0xf8ce1b80e76bf48a30610d0d3c2c9c8dbfdcb28a9aa8f56ac038d520ff17445ff3ce94809f8479a1224ef0d823d035022a9db2e72bd6c0d2f0244f766dec908b";
+  const MESSAGE: &str = "<Bytes>c15335d817e3d3d912d30f5a18a30c30162abc364cd0876d3a94af568d3c8c14</Bytes>";
+
+  /// Take a string the library handed over, freeing it.
+  fn take(string: *mut c_char) -> String {
+    let owned = unsafe { CStr::from_ptr(string) }.to_str().unwrap().to_owned();
+    free_string(string);
+    owned
+  }
+
+  #[test]
+  fn test_c_round_trip() {
+    let request = json!({ "address": ADDRESS, "signature": SIGNATURE, "message": MESSAGE }).to_string();
+    let c_request = CString::new(request.as_str()).unwrap();
+    let response: Value = serde_json::from_str(&take(verify_json(c_request.as_ptr()))).unwrap();
+    assert_eq!(response, json!({ "valid": true, "scheme": "sr25519" }));
+
+    let ndjson = take(verify_ndjson(request.as_ptr() as *const c_char, request.len()));
+    assert_eq!(ndjson, "{\"index\":0,\"valid\":true,\"scheme\":\"sr25519\"}\n");
+
+    let address = CString::new(ADDRESS).unwrap();
+    let result = CString::new(format!(r#"{{"id":1,"signature":"{SIGNATURE}"}}"#)).unwrap();
+    let payload = CString::new(MESSAGE).unwrap();
+    assert_eq!(verify_signraw_result(address.as_ptr(), result.as_ptr(), payload.as_ptr()), VALID);
+    assert_eq!(verify_signraw_result(address.as_ptr(), std::ptr::null(), payload.as_ptr()), ERR_NULL_POINTER);
+  }
+
+  #[test]
+  fn test_null_request() {
+    let response: Value = serde_json::from_str(&take(verify_json(std::ptr::null()))).unwrap();
+    assert_eq!(response["error_code"].as_i64().unwrap(), ERR_NULL_POINTER as i64);
+
+    free_string(std::ptr::null_mut());
+  }
+
+  #[test]
+  fn test_ndjson_null() {
+    assert_eq!(take(verify_ndjson(std::ptr::null(), 0)), "");
+    assert!(verify_ndjson(std::ptr::null(), 1).is_null());
+  }
+
+  /// Allocates and frees every kind of string the library hands over, so a
+  /// leak or an invalid free shows up under Miri, ASAN or valgrind.
+  #[test]
+  fn test_free_string_loop() {
+    let request = json!({ "address": ADDRESS, "signature": SIGNATURE, "message": MESSAGE });
+    let request = CString::new(request.to_string()).unwrap();
+    let address = CString::new(ADDRESS).unwrap();
+    let bad_seed = CString::new("0x00").unwrap();
+    for _ in 0..256 {
+      free_string(verify_json(request.as_ptr()));
+      free_string(verify_ndjson(request.as_ptr(), request.as_bytes().len()));
+      free_string(crate::ffi::reencode_ss58_address(address.as_ptr(), 0));
+      free_string(crate::ffi::registry::ss58_registry_json());
+      free_string(crate::ffi::get_build_info());
+
+      #[cfg(feature = "sp-core")]
+      {
+        let seed = CString::new("0xe5be9a5092b81bca64be81d212e7f2f9eba183bb7a90954f7b76361f6edb5c0a").unwrap();
+        let mut signature = std::ptr::null_mut();
+        assert_eq!(crate::ffi::sign::sign_sr25519(seed.as_ptr(), address.as_ptr(), &mut signature), crate::VALID);
+        assert!(!signature.is_null());
+        free_string(signature);
+      }
+      // A failed call leaves NULL behind, which is safe to free
+      let mut signature = std::ptr::null_mut();
+      assert!(crate::ffi::sign::sign_sr25519(bad_seed.as_ptr(), address.as_ptr(), &mut signature) < 0);
+      assert!(signature.is_null());
+      free_string(signature);
+      free_string(crate::ffi::reencode_ss58_address(std::ptr::null(), 0));
+    }
+  }
+}
@@ -0,0 +1,396 @@
+//! Long-lived keypairs behind opaque handles.
+//!
+//! A keypair is parsed once and then signs any number of messages, from
+//! any number of threads. Handles are registry ids, like nonce cache
+//! handles, so a freed handle is reported rather than dereferenced and
+//! freeing twice does nothing.
+
+use crate::api::VerifyError;
+use crate::error::{set_last_error, Error};
+use crate::ffi::guard::ffi_guard;
+use crate::ffi::handles::HandleRegistry;
+use crate::ffi::sign::into_c_string;
+use crate::ffi::string_or_null;
+use crate::keypair::AnyPair;
+use crate::log::{self, LOG_DEBUG};
+use crate::parse::{bytes_from_ptr, encode_ss58, str_from_ptr};
+use crate::scheme::SignatureScheme;
+use crate::sign::{decode_seed, pair_from_mnemonic};
+use crate::{ERR_NULL_POINTER, ERR_PANIC, VALID};
+use std::ffi::c_char;
+
+/// Opaque handle returned by `keypair_from_seed`,
+/// `keypair_from_seed_with_scheme` and `keypair_from_mnemonic`.
+pub enum Keypair {}
+
+/// Signing only reads the pair, so threads share it without a lock. The
+/// secret key is zeroed when the last reference is dropped.
+static PAIRS: HandleRegistry<AnyPair> = HandleRegistry::new("keypair");
+
+fn insert(pair: AnyPair) -> *mut Keypair {
+  let scheme = pair.scheme().name();
+  let handle = PAIRS.insert(pair);
+  log::log(LOG_DEBUG, || format!("created {scheme} keypair handle {handle:p}"));
+  handle
+}
+
+fn handle_or_null(result: Result<AnyPair, Error>) -> *mut Keypair {
+  match result {
+    Ok(pair) => insert(pair),
+    Err(error) => {
+      set_last_error(&error);
+      std::ptr::null_mut()
+    }
+  }
+}
+
+/// Load an sr25519 keypair from a seed
+///
+/// # Arguments
+/// * `seed_hex_ptr` - Hex-encoded 32-byte mini-secret seed, with or without
+///   0x prefix, as for `sign_sr25519`
+///
+/// # Returns
+/// A handle for `keypair_sign`, to be released with `keypair_free`, or
+/// NULL with `ERR_BAD_SEED_LENGTH`, `ERR_BAD_HEX`, `ERR_NULL_POINTER` or
+/// `ERR_INVALID_UTF8` as the last error. The decoded seed is zeroed before
+/// returning.
+#[no_mangle]
+pub extern "C" fn keypair_from_seed(seed_hex_ptr: *const c_char) -> *mut Keypair {
+  ffi_guard(std::ptr::null_mut(), || {
+    let result = (|| {
+      // Safety: Convert C strings to Rust strings
+      let seed = unsafe { str_from_ptr(seed_hex_ptr, "seed")? };
+      AnyPair::from_seed(&decode_seed(seed)?[..], SignatureScheme::Sr25519)
+    })();
+    handle_or_null(result)
+  })
+}
+
+/// Load a keypair of any signing scheme from a seed
+///
+/// # Arguments
+/// * `seed_hex_ptr` - Hex-encoded 32-byte seed, as for `sign_sr25519`,
+///   `sign_ed25519` or `sign_ecdsa`
+/// * `scheme` - `SCHEME_SR25519`, `SCHEME_ED25519` or `SCHEME_ECDSA`
+///
+/// # Returns
+/// A handle, or NULL with the last error set as for `keypair_from_seed`,
+/// or to `ERR_UNKNOWN_SCHEME` or `ERR_BAD_SEED` for a secp256k1 secret
+/// out of range.
+#[no_mangle]
+pub extern "C" fn keypair_from_seed_with_scheme(seed_hex_ptr: *const c_char, scheme: i32) -> *mut Keypair {
+  ffi_guard(std::ptr::null_mut(), || {
+    let result = (|| {
+      // Safety: Convert C strings to Rust strings
+      let seed = unsafe { str_from_ptr(seed_hex_ptr, "seed")? };
+      let scheme = SignatureScheme::from_i32(scheme)
+        .ok_or_else(|| VerifyError::UnknownScheme { reason: format!("unknown signature scheme {scheme}") })?;
+      AnyPair::from_seed(&decode_seed(seed)?[..], scheme)
+    })();
+    handle_or_null(result)
+  })
+}
+
+/// Load an sr25519 keypair from a BIP39 mnemonic and derivation path
+///
+/// # Arguments
+/// * `mnemonic_ptr` - English BIP39 mnemonic of 12 to 24 words
+/// * `derivation_path_ptr` - Junctions optionally followed by
+///   `///password`, as for `sign_with_mnemonic`. May be empty.
+///
+/// # Returns
+/// A handle for `keypair_sign`, to be released with `keypair_free`, or
+/// NULL with the last error set to one of the codes `sign_with_mnemonic`
+/// returns. Neither the mnemonic nor the password is echoed in it.
+#[no_mangle]
+pub extern "C" fn keypair_from_mnemonic(
+  mnemonic_ptr: *const c_char,
+  derivation_path_ptr: *const c_char,
+) -> *mut Keypair {
+  ffi_guard(std::ptr::null_mut(), || {
+    let result = (|| {
+      // Safety: Convert C strings to Rust strings
+      let mnemonic = unsafe { str_from_ptr(mnemonic_ptr, "mnemonic")? };
+      let path = unsafe { str_from_ptr(derivation_path_ptr, "derivation path")? };
+      Ok(AnyPair::Sr25519(pair_from_mnemonic(mnemonic, path)?))
+    })();
+    handle_or_null(result)
+  })
+}
+
+/// Sign bytes with a keypair
+///
+/// Safe to call from several threads at once on the same handle.
+///
+/// # Arguments
+/// * `keypair` - Any keypair handle, signing with its own scheme
+/// * `message_ptr` - The bytes to sign, signed as given with no wrapping,
+///   or as their `blake2_256` hash if over 256 bytes, the way verification
+///   checks them. May be NULL if `message_len` is 0.
+/// * `message_len` - Number of bytes at `message_ptr`
+/// * `out_signature_hex` - Receives the 0x-prefixed hex signature. Release
+///   it with `free_string`. Set to NULL on failure.
+///
+/// # Returns
+/// * `VALID` (1) if the message was signed
+/// * `ERR_INVALID_HANDLE` if the handle is NULL, unknown or freed
+/// * `ERR_NULL_POINTER` for a NULL output or message pointer
+#[no_mangle]
+pub extern "C" fn keypair_sign(
+  keypair: *mut Keypair,
+  message_ptr: *const u8,
+  message_len: usize,
+  out_signature_hex: *mut *mut c_char,
+) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    if out_signature_hex.is_null() {
+      set_last_error(&VerifyError::NullPointer { name: "signature output".to_owned() }.into());
+      return ERR_NULL_POINTER;
+    }
+    // Safety: the caller passes a valid, writable pointer, checked above
+    unsafe { out_signature_hex.write(std::ptr::null_mut()) };
+
+    let result = (|| {
+      let pair = PAIRS.get(keypair)?;
+      // Safety: the caller passes `message_len` readable bytes
+      let message = unsafe { bytes_from_ptr(message_ptr, message_len, "message")? };
+      Ok(pair.sign(message))
+    })();
+    match result {
+      Ok(signature) => {
+        // Safety: as above
+        unsafe { out_signature_hex.write(into_c_string(signature)) };
+        VALID
+      }
+      Err(error) => {
+        set_last_error(&error);
+        error.code
+      }
+    }
+  })
+}
+
+/// Signing scheme of a keypair
+///
+/// # Returns
+/// * `SCHEME_SR25519`, `SCHEME_ED25519` or `SCHEME_ECDSA`
+/// * `ERR_INVALID_HANDLE` if the handle is NULL, unknown or freed
+#[no_mangle]
+pub extern "C" fn keypair_scheme(keypair: *mut Keypair) -> i32 {
+  ffi_guard(ERR_PANIC, || match PAIRS.get(keypair) {
+    Ok(pair) => pair.scheme() as i32,
+    Err(error) => {
+      set_last_error(&error);
+      error.code
+    }
+  })
+}
+
+/// Public key of a keypair
+///
+/// # Returns
+/// * The 0x-prefixed hex public key: 32 bytes, or 33 compressed bytes for
+///   ECDSA. Release it with `free_string`.
+/// * NULL with `ERR_INVALID_HANDLE` as the last error if the handle is
+///   NULL, unknown or freed
+#[no_mangle]
+pub extern "C" fn keypair_public_hex(keypair: *mut Keypair) -> *mut c_char {
+  ffi_guard(std::ptr::null_mut(), || {
+    string_or_null(PAIRS.get(keypair).map(|pair| format!("0x{}", hex::encode(pair.public()))))
+  })
+}
+
+/// SS58 address of a keypair
+///
+/// # Arguments
+/// * `keypair` - Any keypair handle. ECDSA addresses hold the `blake2_256`
+///   hash of the public key, as for `address_from_mnemonic`.
+/// * `prefix` - SS58 network prefix, such as 0 for Polkadot or 42 for
+///   generic Substrate
+///
+/// # Returns
+/// * The SS58 address. Release it with `free_string`.
+/// * NULL with `ERR_INVALID_HANDLE`, or `ERR_BAD_SS58` for a prefix above
+///   16383, as the last error
+#[no_mangle]
+pub extern "C" fn keypair_ss58(keypair: *mut Keypair, prefix: u16) -> *mut c_char {
+  ffi_guard(std::ptr::null_mut(), || {
+    let result = PAIRS.get(keypair).and_then(|pair| encode_ss58(pair.account().as_ref(), prefix));
+    string_or_null(result)
+  })
+}
+
+/// Release a keypair and zero its secret key
+///
+/// NULL, unknown and already freed handles are ignored, so freeing twice is
+/// harmless. Callers should still set their copy of the handle to NULL
+/// after freeing it. Signatures still running on other threads finish
+/// first; the secret is zeroed when the last of them returns.
+#[no_mangle]
+pub extern "C" fn keypair_free(keypair: *mut Keypair) {
+  ffi_guard((), || {
+    let _ = PAIRS.remove(keypair);
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::ffi::error::get_last_error;
+  use crate::ffi::json::free_string;
+  use crate::ffi::{verify_polkadot_signature_bytes, verify_signature_with_options};
+  use crate::{ERR_BAD_SEED_LENGTH, ERR_INVALID_HANDLE, SCHEME_SR25519};
+  use sp_core::crypto::DEV_PHRASE;
+  use std::ffi::{CStr, CString};
+
+  // The `//Alice` development seed
+  const SEED: &str = "0xe5be9a5092b81bca64be81d212e7f2f9eba183bb7a90954f7b76361f6edb5c0a";
+  const ALICE: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+
+  fn sign_bytes(keypair: *mut Keypair, message: &[u8]) -> Result<String, i32> {
+    let mut out = std::ptr::null_mut();
+    let code = keypair_sign(keypair, message.as_ptr(), message.len(), &mut out);
+    if code != VALID {
+      assert!(out.is_null());
+      return Err(code);
+    }
+    let signature = unsafe { CStr::from_ptr(out) }.to_str().unwrap().to_owned();
+    free_string(out);
+    Ok(signature)
+  }
+
+  fn take(string: *mut c_char) -> Option<String> {
+    if string.is_null() {
+      return None;
+    }
+    let owned = unsafe { CStr::from_ptr(string) }.to_str().unwrap().to_owned();
+    free_string(string);
+    Some(owned)
+  }
+
+  fn verify(address: &str, signature: &str, message: &[u8]) -> i32 {
+    let address = CString::new(address).unwrap();
+    let signature = CString::new(signature).unwrap();
+    verify_polkadot_signature_bytes(address.as_ptr(), signature.as_ptr(), message.as_ptr(), message.len())
+  }
+
+  #[test]
+  fn test_sign_across_threads() {
+    let seed = CString::new(SEED).unwrap();
+    let keypair = keypair_from_seed(seed.as_ptr());
+    assert!(!keypair.is_null());
+
+    let handle = keypair as usize;
+    let threads: Vec<_> = (0..4)
+      .map(|thread| {
+        std::thread::spawn(move || {
+          (0..250)
+            .map(|i| {
+              // Some over 256 bytes, which are signed as their hash
+              let message = format!("message {thread}-{i} ").repeat(1 + i % 40).into_bytes();
+              let signature = sign_bytes(handle as *mut Keypair, &message).unwrap();
+              (message, signature)
+            })
+            .collect::<Vec<_>>()
+        })
+      })
+      .collect();
+    let signed: Vec<_> = threads.into_iter().flat_map(|thread| thread.join().unwrap()).collect();
+
+    assert_eq!(signed.len(), 1000);
+    for (message, signature) in &signed {
+      assert_eq!(verify(ALICE, signature, message), VALID);
+    }
+    keypair_free(keypair);
+  }
+
+  #[test]
+  fn test_from_mnemonic() {
+    let mnemonic = CString::new(DEV_PHRASE).unwrap();
+    let path = CString::new("//Alice").unwrap();
+    let keypair = keypair_from_mnemonic(mnemonic.as_ptr(), path.as_ptr());
+    assert_eq!(take(keypair_ss58(keypair, 42)).unwrap(), ALICE);
+
+    // Binary messages, NUL bytes included, are signed as given
+    let message = [0u8, 1, 2, 0xff];
+    assert_eq!(verify(ALICE, &sign_bytes(keypair, &message).unwrap(), &message), VALID);
+    assert_eq!(verify(ALICE, &sign_bytes(keypair, &[]).unwrap(), &[]), VALID);
+    keypair_free(keypair);
+
+    let bad = CString::new(DEV_PHRASE.replace("walk", "walkk")).unwrap();
+    assert!(keypair_from_mnemonic(bad.as_ptr(), path.as_ptr()).is_null());
+    let error = unsafe { CStr::from_ptr(get_last_error()) }.to_str().unwrap();
+    assert_eq!(error, "word 12 of the mnemonic is not in the English BIP39 word list");
+  }
+
+  #[test]
+  fn test_identity() {
+    let seed = CString::new(SEED).unwrap();
+    let keypair = keypair_from_seed(seed.as_ptr());
+    assert_eq!(keypair_scheme(keypair), SCHEME_SR25519);
+    assert_eq!(take(keypair_ss58(keypair, 42)).unwrap(), ALICE);
+    assert_eq!(take(keypair_ss58(keypair, 0)).unwrap(), "15oF4uVJwmo4TdGW7VfQxNLavjCXviqxT9S1MgbjMNHr6Sp5");
+    assert_eq!(
+      take(keypair_public_hex(keypair)).unwrap(),
+      "0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d"
+    );
+    assert_eq!(take(keypair_ss58(keypair, 16_384)), None);
+    let error = unsafe { CStr::from_ptr(get_last_error()) }.to_str().unwrap();
+    assert_eq!(error, "SS58 prefix 16384 is above 16383");
+    keypair_free(keypair);
+
+    assert_eq!(keypair_scheme(keypair), ERR_INVALID_HANDLE);
+    assert_eq!(take(keypair_public_hex(keypair)), None);
+    assert_eq!(take(keypair_ss58(keypair, 42)), None);
+  }
+
+  #[test]
+  fn test_every_scheme() {
+    let seed = CString::new(SEED).unwrap();
+    let schemes = [(SignatureScheme::Sr25519, 32), (SignatureScheme::Ed25519, 32), (SignatureScheme::Ecdsa, 33)];
+    for (scheme, public_len) in schemes.into_iter().filter(|(scheme, _)| scheme.is_supported()) {
+      let scheme = scheme as i32;
+      let keypair = keypair_from_seed_with_scheme(seed.as_ptr(), scheme);
+      assert_eq!(keypair_scheme(keypair), scheme);
+      assert_eq!(take(keypair_public_hex(keypair)).unwrap().len(), 2 + 2 * public_len);
+
+      let address = CString::new(take(keypair_ss58(keypair, 42)).unwrap()).unwrap();
+      for message in ["hello".to_owned(), "x".repeat(300)] {
+        let signature = CString::new(sign_bytes(keypair, message.as_bytes()).unwrap()).unwrap();
+        let message = CString::new(message).unwrap();
+        let code = verify_signature_with_options(
+          address.as_ptr(),
+          signature.as_ptr(),
+          message.as_ptr(),
+          scheme,
+          std::ptr::null(),
+        );
+        assert_eq!(code, VALID, "scheme {scheme}, {} bytes", message.as_bytes().len());
+      }
+      keypair_free(keypair);
+    }
+    assert!(keypair_from_seed_with_scheme(seed.as_ptr(), 0).is_null());
+  }
+
+  #[test]
+  fn test_handles() {
+    let short = CString::new(&SEED[..SEED.len() - 2]).unwrap();
+    assert!(keypair_from_seed(short.as_ptr()).is_null());
+    assert_eq!(PAIRS.get(std::ptr::null_mut::<Keypair>()).err().map(|error| error.code), Some(ERR_INVALID_HANDLE));
+    assert_eq!(decode_seed(&SEED[..SEED.len() - 2]).unwrap_err().code, ERR_BAD_SEED_LENGTH);
+
+    let seed = CString::new(SEED).unwrap();
+    let keypair = keypair_from_seed(seed.as_ptr());
+    let mut out = std::ptr::null_mut();
+    assert_eq!(keypair_sign(keypair, std::ptr::null(), 1, &mut out), ERR_NULL_POINTER);
+    assert_eq!(keypair_sign(keypair, std::ptr::null(), 0, std::ptr::null_mut()), ERR_NULL_POINTER);
+
+    keypair_free(keypair);
+    assert_eq!(sign_bytes(keypair, b"hello"), Err(ERR_INVALID_HANDLE));
+    assert_eq!(sign_bytes(std::ptr::null_mut(), b"hello"), Err(ERR_INVALID_HANDLE));
+    // Freeing twice, or NULL, is harmless
+    keypair_free(keypair);
+    keypair_free(std::ptr::null_mut());
+  }
+}
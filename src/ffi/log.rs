@@ -0,0 +1,44 @@
+//! Diagnostics routed to a host-provided callback, from C.
+
+use crate::ffi::guard::ffi_guard;
+use crate::log::{self, LogCallback};
+
+/// Install or remove the log callback
+///
+/// # Arguments
+/// * `cb` - Called on the verifying thread at key points of every call.
+///   Pass NULL to disable logging.
+///
+/// The callback may be invoked from several threads at once.
+#[no_mangle]
+pub extern "C" fn set_log_callback(cb: Option<LogCallback>) {
+  ffi_guard((), || log::set_callback(cb))
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::ffi::verify_polkadot_signature_v2;
+  use crate::log::{capture, LOG_DEBUG, LOG_WARN};
+  use std::ffi::CString;
+
+  #[test]
+  fn test_failing_verification_is_logged() {
+    let address = CString::new("5HbqogsYaK54pN3QAgSi4t3Asqditt1X4P7cSBuDt2hPF2BR").unwrap();
+    // One byte short of a signature
+    let signature = CString::new(format!("0x{}", "ab".repeat(63))).unwrap();
+    let message = CString::new("hello").unwrap();
+    let received = capture(|| {
+      verify_polkadot_signature_v2(address.as_ptr(), signature.as_ptr(), message.as_ptr());
+    });
+
+    assert_eq!(
+      received,
+      vec![
+        (LOG_DEBUG, "verifying 5 byte message for 5HbqogsYaK54pN3QAgSi4t3Asqditt1X4P7cSBuDt2hPF2BR".to_owned()),
+        (LOG_DEBUG, "address decoded with SS58 prefix 42".to_owned()),
+        (LOG_DEBUG, "signature is 63 bytes".to_owned()),
+        (LOG_WARN, "verification failed with code -5: signature is 63 bytes, expected 64".to_owned()),
+      ]
+    );
+  }
+}
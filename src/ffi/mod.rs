@@ -0,0 +1,2272 @@
+//! The C API.
+//!
+//! Every exported function lives under this module, built only with the
+//! `ffi` feature. Each one converts its arguments from C, calls into the
+//! module of the same name one level up, and reports the outcome as a code
+//! and the last error. The module is private, so Rust callers use `api`
+//! and never hand these functions a raw pointer.
+
+mod address_cache;
+mod batch;
+mod candidates;
+mod codes;
+#[cfg(feature = "sp-core")]
+mod derive;
+#[cfg(feature = "sp-core")]
+mod dev;
+mod did;
+mod error;
+mod expiry;
+mod extrinsic;
+mod file;
+mod guard;
+mod handles;
+mod jobs;
+mod json;
+#[cfg(feature = "sp-core")]
+mod keypair;
+mod log;
+mod nonce;
+mod parsed_signature;
+mod pinvoke;
+mod registry;
+mod scheme;
+#[cfg(feature = "sp-core")]
+mod sign;
+mod siws;
+mod ss58;
+mod stats;
+mod stream;
+/// cbindgen:ignore
+#[cfg(not(feature = "sp-core"))]
+mod unsupported;
+mod verifier;
+
+#[cfg(all(test, not(feature = "sp-core")))]
+use unsupported::sign;
+
+use crate::error::{set_last_error, Error};
+use crate::options::{read_options, Options};
+use crate::parse::{self, bytes_from_ptr, str_from_ptr, string_from_wide};
+use crate::schemes::{Scheme, Sr25519};
+use crate::verify::{self, verdict};
+use crate::{api, ethereum, message, vrf, BuildInfo, SignatureScheme, VerifyOptions, VerifyOutcome};
+use crate::{ABI_VERSION, ERR_NULL_POINTER, ERR_PANIC, LEDGER_NONE, LOG_DEBUG, MESSAGE_NONE, SCHEME_NONE, VALID};
+use guard::ffi_guard;
+use std::ffi::{c_char, CString};
+
+/// Verify a Polkadot SR25519 signature
+/// 
+/// # Arguments
+/// * `address_ptr` - SS58-encoded Polkadot address (e.g., "14zNh..."), or
+///   the raw 32-byte public key as 64 hex digits, optionally 0x-prefixed
+/// * `signature_ptr` - Hex-encoded signature (with or without 0x prefix)
+/// * `message_ptr` - The original message that was signed
+/// 
+/// # Returns
+/// * 1 if signature is valid
+/// * 0 if signature is invalid or any error occurred
+///
+/// Kept for backwards compatibility, see `verify_polkadot_signature_v2`
+/// for a variant that reports why verification failed.
+#[no_mangle]
+pub extern "C" fn verify_polkadot_signature(
+  address_ptr: *const c_char,
+  signature_ptr: *const c_char,
+  message_ptr: *const c_char,
+) -> u8 {
+  ffi_guard(0, || (verify_polkadot_signature_v2(address_ptr, signature_ptr, message_ptr) == VALID) as u8)
+}
+
+/// Verify a Polkadot SR25519 signature, reporting the failure class
+///
+/// Takes the same arguments as `verify_polkadot_signature`.
+///
+/// # Returns
+/// * `VALID` (1) if signature is valid
+/// * `INVALID` (0) if the inputs parsed but the signature does not verify
+/// * a negative `ERR_*` code from [`codes`] if the inputs could not be parsed
+///
+/// On failure `get_last_error` describes what went wrong.
+#[no_mangle]
+pub extern "C" fn verify_polkadot_signature_v2(
+  address_ptr: *const c_char,
+  signature_ptr: *const c_char,
+  message_ptr: *const c_char,
+) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    let result = verify_c_strs(from_api(api::verify), address_ptr, signature_ptr, message_ptr, &Options::default());
+    verdict(result)
+  })
+}
+
+/// Verify a Polkadot SR25519 signature over binary data
+///
+/// # Arguments
+/// * `address_ptr` - SS58-encoded address
+/// * `signature_ptr` - Hex-encoded signature (with or without 0x prefix)
+/// * `message_ptr` - The signed bytes, which may contain NUL bytes and
+///   need not be UTF-8. May be NULL if `message_len` is 0.
+/// * `message_len` - Number of bytes at `message_ptr`
+///
+/// # Returns
+/// The same codes as `verify_polkadot_signature_v2`.
+#[no_mangle]
+pub extern "C" fn verify_polkadot_signature_bytes(
+  address_ptr: *const c_char,
+  signature_ptr: *const c_char,
+  message_ptr: *const u8,
+  message_len: usize,
+) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    let result = verify_c_bytes(
+      from_api(api::verify),
+      address_ptr,
+      signature_ptr,
+      message_ptr,
+      message_len,
+      &Options::default(),
+    );
+    verdict(result)
+  })
+}
+
+/// Verify a Polkadot SR25519 signature from raw bytes, without parsing
+///
+/// For hot paths that already hold the account and signature bytes.
+///
+/// # Arguments
+/// * `account_id` - Exactly 32 bytes: the sr25519 public key
+/// * `signature` - Exactly 64 bytes: the signature
+/// * `message` - The signed bytes. May be NULL if `message_len` is 0.
+/// * `message_len` - Number of bytes at `message`
+///
+/// There are deliberately no length arguments for `account_id` and
+/// `signature`: the library reads exactly 32 and 64 bytes from them, and
+/// shorter buffers are undefined behavior. The message is verified as
+/// given, without `<Bytes>` wrapping or hashing of long messages.
+///
+/// # Returns
+/// * `VALID` (1) or `INVALID` (0)
+/// * `ERR_NULL_POINTER` if a pointer is NULL
+/// * `ERR_NON_CANONICAL_SIGNATURE` for a malleated signature encoding
+#[no_mangle]
+pub extern "C" fn verify_by_account_id(
+  account_id: *const u8,
+  signature: *const u8,
+  message: *const u8,
+  message_len: usize,
+) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    let result = (|| {
+      if account_id.is_null() || signature.is_null() {
+        let name = if account_id.is_null() { "account id" } else { "signature" };
+        return Err(api::VerifyError::NullPointer { name: name.to_owned() }.into());
+      }
+      // Safety: the caller passes 32 and 64 readable bytes
+      let account_id = unsafe { (account_id as *const [u8; 32]).read_unaligned() };
+      let signature = unsafe { (signature as *const [u8; 64]).read_unaligned() };
+      // Safety: the caller passes `message_len` readable bytes or NULL
+      let message = unsafe { bytes_from_ptr(message, message_len, "message")? };
+      Sr25519.verify(&signature, message, account_id, &Options::default())
+    })();
+    verdict(result)
+  })
+}
+
+/// Verify a Polkadot SR25519 signature given as UTF-16 strings
+///
+/// For hosts such as .NET that marshal strings as UTF-16. Takes the same
+/// arguments as `verify_polkadot_signature`, each a NUL-terminated UTF-16
+/// string. The signature is checked over the UTF-8 encoding of the
+/// message, which is what polkadot-js signs.
+///
+/// # Returns
+/// The same codes as `verify_polkadot_signature_v2`, plus
+/// `ERR_INVALID_UTF16` if an argument contains a lone surrogate.
+#[no_mangle]
+pub extern "C" fn verify_polkadot_signature_w(
+  address_ptr: *const u16,
+  signature_ptr: *const u16,
+  message_ptr: *const u16,
+) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    let result = (|| {
+      // Safety: Convert UTF-16 strings to Rust strings
+      let address = unsafe { string_from_wide(address_ptr, "address")? };
+      let signature_hex = unsafe { string_from_wide(signature_ptr, "signature")? };
+      let message = unsafe { string_from_wide(message_ptr, "message")? };
+      crate::log::log(LOG_DEBUG, || format!("verifying {} byte message for {address}", message.len()));
+      verify::verify_sr25519(&address, &signature_hex, message.as_bytes(), &Options::default())
+    })();
+    verdict(result.map(|outcome| outcome.valid == 1))
+  })
+}
+
+/// Verify a Polkadot SR25519 signature with per-call options
+///
+/// Takes the same arguments as `verify_polkadot_signature`, plus:
+/// * `options` - Behavior tweaks, see `VerifyOptions`. NULL uses the
+///   defaults, which match `verify_polkadot_signature_v2`.
+///
+/// # Returns
+/// The same codes as `verify_polkadot_signature_v2`, plus
+/// `ERR_WRONG_NETWORK` when the address prefix is not allowed.
+#[no_mangle]
+pub extern "C" fn verify_polkadot_signature_with_options(
+  address_ptr: *const c_char,
+  signature_ptr: *const c_char,
+  message_ptr: *const c_char,
+  options: *const VerifyOptions,
+) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    // Safety: the caller passes a valid VerifyOptions or NULL
+    let result = unsafe { read_options(options) }.and_then(|verifier| {
+      verify_c_strs(verify::verify_sr25519, address_ptr, signature_ptr, message_ptr, &verifier.options())
+    });
+    verdict(result.map(|outcome| outcome.valid == 1))
+  })
+}
+
+/// Verify a Polkadot SR25519 signature from a single network
+///
+/// Takes the same arguments as `verify_polkadot_signature`, plus:
+/// * `prefix` - The only SS58 prefix the address may use, e.g. 0 for
+///   Polkadot or 2 for Kusama
+///
+/// Shorthand for `verify_polkadot_signature_with_options` with
+/// `allowed_prefixes` set to `prefix` alone.
+///
+/// # Returns
+/// The same codes as `verify_polkadot_signature_v2`, plus
+/// `ERR_WRONG_NETWORK` when the address decodes but uses another prefix,
+/// or is a raw public key, which has none.
+#[no_mangle]
+pub extern "C" fn verify_polkadot_signature_strict_network(
+  address_ptr: *const c_char,
+  signature_ptr: *const c_char,
+  message_ptr: *const c_char,
+  prefix: u16,
+) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    let allowed = [prefix];
+    let options = Options { allowed_prefixes: Some(&allowed), ..Options::default() };
+    let result = verify_c_strs(verify::verify_sr25519, address_ptr, signature_ptr, message_ptr, &options);
+    verdict(result.map(|outcome| outcome.valid == 1))
+  })
+}
+
+/// Verify a signature of a given scheme with per-call options
+///
+/// Takes the same arguments as `verify_polkadot_signature_with_options`,
+/// plus:
+/// * `scheme` - `SCHEME_SR25519`, `SCHEME_ED25519` or `SCHEME_ECDSA`
+///
+/// # Returns
+/// The same codes as `verify_polkadot_signature_with_options`, plus
+/// `ERR_UNKNOWN_SCHEME` for any other scheme, including
+/// `SCHEME_ETHEREUM` whose addresses are not SS58.
+#[no_mangle]
+pub extern "C" fn verify_signature_with_options(
+  address_ptr: *const c_char,
+  signature_ptr: *const c_char,
+  message_ptr: *const c_char,
+  scheme: i32,
+  options: *const VerifyOptions,
+) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    // Safety: the caller passes a valid VerifyOptions or NULL
+    let result = unsafe { read_options(options) }.and_then(|verifier| {
+      verify_c_strs(
+        |address, signature_hex, message, options| {
+          let scheme = SignatureScheme::from_i32(scheme)
+            .ok_or_else(|| api::VerifyError::UnknownScheme { reason: format!("unknown signature scheme {scheme}") })?;
+          verify::verify_as(scheme, address, signature_hex, message, options)
+        },
+        address_ptr,
+        signature_ptr,
+        message_ptr,
+        &verifier.options(),
+      )
+    });
+    verdict(result.map(|outcome| outcome.valid == 1))
+  })
+}
+
+/// Verify a Polkadot SR25519 signature, tolerating a scheme byte
+///
+/// Like `verify_polkadot_signature_v2`, but also accepts the 65-byte
+/// signatures some wallets return from `signRaw`, where the first byte is
+/// the `MultiSignature` tag: 0x01 verifies the remaining 64 bytes as
+/// sr25519, 0x00 as ed25519.
+///
+/// # Returns
+/// The same codes as `verify_polkadot_signature_v2`, plus
+/// `ERR_BAD_SIGNATURE_PREFIX` for 65-byte signatures with any other first
+/// byte.
+#[no_mangle]
+pub extern "C" fn verify_polkadot_signature_lenient(
+  address_ptr: *const c_char,
+  signature_ptr: *const c_char,
+  message_ptr: *const c_char,
+) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    let options = Options { strip_scheme_prefix: true, ..Options::default() };
+    let result = verify_c_strs(verify::verify_sr25519, address_ptr, signature_ptr, message_ptr, &options);
+    verdict(result.map(|outcome| outcome.valid == 1))
+  })
+}
+
+/// Verify a Polkadot SR25519 signature, with or without `<Bytes>` wrapping
+///
+/// Takes the same arguments as `verify_polkadot_signature`, plus:
+/// * `form_out` - Receives the `MESSAGE_*` constant of the form that
+///   verified, or `MESSAGE_NONE`. May be NULL.
+///
+/// polkadot-js wraps raw messages in `<Bytes>...</Bytes>` before signing,
+/// which callers easily get wrong. The message is tried as given first,
+/// then with the wrapper removed if it has one, or added if it does not.
+///
+/// # Returns
+/// The same codes as `verify_polkadot_signature_v2`.
+#[no_mangle]
+pub extern "C" fn verify_polkadot_signature_auto(
+  address_ptr: *const c_char,
+  signature_ptr: *const c_char,
+  message_ptr: *const c_char,
+  form_out: *mut i32,
+) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    let mut form = MESSAGE_NONE;
+    let result = verify_c_strs(
+      |address, signature_hex, message, options| {
+        let (outcome, matched) = verify::verify_auto(address, signature_hex, message, options)?;
+        form = matched;
+        Ok(outcome)
+      },
+      address_ptr,
+      signature_ptr,
+      message_ptr,
+      &Options::default(),
+    );
+    if !form_out.is_null() {
+      // Safety: the caller passes a valid, writable i32 or NULL
+      unsafe { form_out.write(form) };
+    }
+    verdict(result.map(|outcome| outcome.valid == 1))
+  })
+}
+
+/// Verify a Polkadot SR25519 signature and describe the signer
+///
+/// Takes the same arguments as `verify_polkadot_signature`, plus:
+/// * `out` - Filled with the verdict, decoded public key and SS58 prefix.
+///   May be NULL, in which case only the code is returned.
+///
+/// # Returns
+/// The same codes as `verify_polkadot_signature_v2`. When the code is
+/// negative `out` is zeroed apart from `error_code`, so callers never see
+/// stale data.
+#[no_mangle]
+pub extern "C" fn verify_polkadot_signature_ex(
+  address_ptr: *const c_char,
+  signature_ptr: *const c_char,
+  message_ptr: *const c_char,
+  out: *mut VerifyOutcome,
+) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    let result = guard::catch(|| {
+      verify_c_strs(verify::verify_sr25519, address_ptr, signature_ptr, message_ptr, &Options::default())
+    });
+    let outcome = match &result {
+      Ok(outcome) => *outcome,
+      Err(error) => VerifyOutcome { error_code: error.code, ..VerifyOutcome::default() },
+    };
+    let code = verdict(result.map(|outcome| outcome.valid == 1));
+
+    if !out.is_null() {
+      // Safety: the caller passes a valid, writable VerifyOutcome or NULL
+      unsafe { out.write(outcome) };
+    }
+    code
+  })
+}
+
+/// Verify a Polkadot ED25519 signature
+///
+/// Takes the same arguments and returns the same codes as
+/// `verify_polkadot_signature_v2`. sr25519 and ed25519 keys encode to
+/// identical SS58 strings, so the address does not reveal the scheme; this
+/// function always verifies as ed25519. Builds without the `sp-core`
+/// feature return `ERR_SCHEME_NOT_SUPPORTED`.
+#[no_mangle]
+pub extern "C" fn verify_polkadot_ed25519_signature(
+  address_ptr: *const c_char,
+  signature_ptr: *const c_char,
+  message_ptr: *const c_char,
+) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    let verify = from_api(api::verify_ed25519);
+    let result = verify_c_strs(verify, address_ptr, signature_ptr, message_ptr, &Options::default());
+    verdict(result)
+  })
+}
+
+/// Verify a Polkadot ECDSA (secp256k1) signature
+///
+/// # Arguments
+/// * `address_ptr` - SS58-encoded account id of an ECDSA key, or the
+///   33-byte compressed key itself SS58-encoded
+/// * `signature_ptr` - Hex-encoded 65-byte recoverable signature (r, s,
+///   recovery id), with or without 0x prefix
+/// * `message_ptr` - The original message that was signed
+///
+/// An ECDSA account id is `blake2_256` of the compressed public key, not
+/// the key itself. The key is recovered from the signature, hashed and
+/// compared with the account id, which is first computed the same way for
+/// addresses holding the key.
+///
+/// # Returns
+/// The same codes as `verify_polkadot_signature_v2`, plus
+/// `ERR_NON_CANONICAL_SIGNATURE` for high-s signatures. Legacy wallets
+/// producing those can be accepted through `verify_signature_with_options`
+/// with `OPT_ALLOW_NON_CANONICAL_ECDSA`.
+#[no_mangle]
+pub extern "C" fn verify_polkadot_ecdsa_signature(
+  address_ptr: *const c_char,
+  signature_ptr: *const c_char,
+  message_ptr: *const c_char,
+) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    let verify = from_api(api::verify_ecdsa);
+    let result = verify_c_strs(verify, address_ptr, signature_ptr, message_ptr, &Options::default());
+    verdict(result)
+  })
+}
+
+/// Verify a signature of unknown scheme
+///
+/// Takes the same arguments as `verify_polkadot_signature`, plus:
+/// * `detected_scheme_out` - Receives the `SCHEME_*` constant that
+///   verified, or `SCHEME_NONE`. May be NULL.
+///
+/// Schemes are tried by signature length: 64 bytes as sr25519 then
+/// ed25519, 65 bytes as ECDSA.
+///
+/// # Returns
+/// The same codes as `verify_polkadot_signature_v2`, plus
+/// `ERR_NO_MATCHING_SCHEME` when the signature length fits no scheme.
+#[no_mangle]
+pub extern "C" fn verify_any_signature(
+  address_ptr: *const c_char,
+  signature_ptr: *const c_char,
+  message_ptr: *const c_char,
+  detected_scheme_out: *mut i32,
+) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    let mut scheme = SCHEME_NONE;
+    let result = verify_c_strs(
+      |address, signature_hex, message, options| {
+        let (outcome, detected) = verify::verify_any(address, signature_hex, message, options)?;
+        scheme = detected.map_or(SCHEME_NONE, |scheme| scheme as i32);
+        Ok(outcome)
+      },
+      address_ptr,
+      signature_ptr,
+      message_ptr,
+      &Options::default(),
+    );
+    if !detected_scheme_out.is_null() {
+      // Safety: the caller passes a valid, writable i32 or NULL
+      unsafe { detected_scheme_out.write(scheme) };
+    }
+    verdict(result.map(|outcome| outcome.valid == 1))
+  })
+}
+
+/// Verify a SCALE-encoded `MultiSignature`
+///
+/// # Arguments
+/// * `address_ptr` - SS58-encoded address
+/// * `multisignature_ptr` - Hex-encoded `MultiSignature`, with or without
+///   0x prefix: a variant tag (0x00 ed25519, 0x01 sr25519, 0x02 ecdsa)
+///   followed by the 64 or 65 signature bytes of that scheme
+/// * `message_ptr` - The original message that was signed
+///
+/// # Returns
+/// The same codes as `verify_polkadot_signature_v2`, plus
+/// `ERR_BAD_MULTISIGNATURE` for unknown tags or truncated payloads.
+#[no_mangle]
+pub extern "C" fn verify_multisignature(
+  address_ptr: *const c_char,
+  multisignature_ptr: *const c_char,
+  message_ptr: *const c_char,
+) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    let result = verify_c_strs(
+      verify::verify_multisignature,
+      address_ptr,
+      multisignature_ptr,
+      message_ptr,
+      &Options::default(),
+    );
+    verdict(result.map(|outcome| outcome.valid == 1))
+  })
+}
+
+/// Verify an ed25519 signature from the Polkadot Ledger app
+///
+/// Takes the same arguments as `verify_polkadot_signature`, plus:
+/// * `convention_out` - Receives the `LEDGER_*` constant of the
+///   convention that verified, or `LEDGER_NONE`. May be NULL.
+///
+/// Depending on its version, the Ledger app signs either the raw message
+/// or, when the payload exceeds the device buffer, its `blake2_256` hash.
+/// They are tried in that order, and neither wraps the message in
+/// `<Bytes>`.
+///
+/// # Returns
+/// The same codes as `verify_polkadot_signature_v2`, or
+/// `ERR_SCHEME_NOT_SUPPORTED` in builds without the `sp-core` feature.
+#[no_mangle]
+pub extern "C" fn verify_ledger_signature(
+  address_ptr: *const c_char,
+  signature_ptr: *const c_char,
+  message_ptr: *const c_char,
+  convention_out: *mut i32,
+) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    let mut convention = LEDGER_NONE;
+    let result = verify_c_strs(
+      |address, signature_hex, message, options| {
+        let (outcome, matched) = verify::verify_ledger(address, signature_hex, message, options)?;
+        convention = matched;
+        Ok(outcome)
+      },
+      address_ptr,
+      signature_ptr,
+      message_ptr,
+      &Options::default(),
+    );
+    if !convention_out.is_null() {
+      // Safety: the caller passes a valid, writable i32 or NULL
+      unsafe { convention_out.write(convention) };
+    }
+    verdict(result.map(|outcome| outcome.valid == 1))
+  })
+}
+
+/// Verify an Ethereum-style `personal_sign` signature
+///
+/// For AccountId20 chains such as Moonbeam.
+///
+/// # Arguments
+/// * `address_ptr` - 20-byte H160 address in hex, with or without 0x
+///   prefix. Case-insensitive, but mixed case must be valid EIP-55.
+/// * `signature_ptr` - Hex-encoded 65-byte signature (r, s, v) with v as
+///   0/1 or 27/28
+/// * `message_ptr` - The message passed to `personal_sign`, without the
+///   "\x19Ethereum Signed Message:\n" prefix, which is applied here
+///
+/// # Returns
+/// * `VALID` (1) if the signature recovers to the address
+/// * `INVALID` (0) otherwise
+/// * `ERR_NON_CANONICAL_SIGNATURE` for high-s signatures, rejected by EIP-2
+/// * `ERR_BAD_ETH_ADDRESS` or `ERR_ETH_ADDRESS_CHECKSUM` for bad addresses
+/// * `ERR_SCHEME_NOT_SUPPORTED` if built without the `ecdsa` feature
+/// * the signature and pointer errors of `verify_polkadot_signature_v2`
+#[no_mangle]
+pub extern "C" fn verify_ethereum_style_signature(
+  h160_address_ptr: *const c_char,
+  signature_ptr: *const c_char,
+  message_ptr: *const c_char,
+) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    let result = (|| {
+      // Safety: Convert C strings to Rust strings
+      let address = unsafe { str_from_ptr(h160_address_ptr, "address")? };
+      let signature_hex = unsafe { str_from_ptr(signature_ptr, "signature")? };
+      let message = unsafe { str_from_ptr(message_ptr, "message")? };
+      SignatureScheme::Ethereum.ensure_supported()?;
+      ethereum::verify_ethereum(address, signature_hex, message.as_bytes())
+    })();
+    verdict(result)
+  })
+}
+
+/// Verify an sr25519 VRF proof
+///
+/// # Arguments
+/// * `address_ptr` - SS58-encoded address of the prover
+/// * `vrf_output_ptr` - Hex-encoded 32-byte VRF pre-output
+/// * `vrf_proof_ptr` - Hex-encoded 64-byte VRF proof
+/// * `input_ptr` - The VRF input message
+/// * `label_ptr` - Signing context label of the transcript, which differs
+///   per application
+/// * `randomness_out` - Receives 32 bytes of randomness derived from the
+///   VRF output with the label as context, or zeroes if the proof does not
+///   verify. May be NULL.
+///
+/// # Returns
+/// The same codes as `verify_polkadot_signature_v2`, plus `ERR_BAD_HEX_LENGTH`
+/// for an output or proof of the wrong length.
+#[no_mangle]
+pub extern "C" fn verify_vrf(
+  address_ptr: *const c_char,
+  vrf_output_ptr: *const c_char,
+  vrf_proof_ptr: *const c_char,
+  input_ptr: *const c_char,
+  label_ptr: *const c_char,
+  randomness_out: *mut u8,
+) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    let result = (|| {
+      // Safety: Convert C strings to Rust strings
+      let address = unsafe { str_from_ptr(address_ptr, "address")? };
+      let output_hex = unsafe { str_from_ptr(vrf_output_ptr, "VRF output")? };
+      let proof_hex = unsafe { str_from_ptr(vrf_proof_ptr, "VRF proof")? };
+      let input = unsafe { str_from_ptr(input_ptr, "input")? };
+      let label = unsafe { str_from_ptr(label_ptr, "label")? };
+      vrf::verify_vrf(address, output_hex, proof_hex, input.as_bytes(), label)
+    })();
+
+    let randomness = result.as_ref().ok().copied().flatten().unwrap_or_default();
+    if !randomness_out.is_null() {
+      // Safety: the caller passes 32 writable bytes or NULL
+      unsafe { std::ptr::copy_nonoverlapping(randomness.as_ptr(), randomness_out, randomness.len()) };
+    }
+    verdict(result.map(|randomness| randomness.is_some()))
+  })
+}
+
+/// Adapt a function of [`api`] to the `verify` argument of
+/// `verify_c_strs`, for exported functions that take default options.
+pub(crate) fn from_api(
+  verify: fn(&str, &str, &[u8]) -> Result<(), api::VerifyError>,
+) -> impl FnOnce(&str, &str, &[u8], &Options) -> Result<bool, Error> {
+  move |address, signature_hex, message, _| api::verdict_of(verify(address, signature_hex, message))
+}
+
+pub(crate) fn verify_c_strs<T>(
+  verify: impl FnOnce(&str, &str, &[u8], &Options) -> Result<T, Error>,
+  address_ptr: *const c_char,
+  signature_ptr: *const c_char,
+  message_ptr: *const c_char,
+  options: &Options,
+) -> Result<T, Error> {
+  // Safety: Convert C strings to Rust strings
+  let message = || unsafe { str_from_ptr(message_ptr, "message") }.map(str::as_bytes);
+  verify_c_args(verify, address_ptr, signature_ptr, message, options)
+}
+
+pub(crate) fn verify_c_bytes<T>(
+  verify: impl FnOnce(&str, &str, &[u8], &Options) -> Result<T, Error>,
+  address_ptr: *const c_char,
+  signature_ptr: *const c_char,
+  message_ptr: *const u8,
+  message_len: usize,
+  options: &Options,
+) -> Result<T, Error> {
+  // Safety: the caller passes `message_len` readable bytes or NULL
+  let message = || unsafe { bytes_from_ptr(message_ptr, message_len, "message") };
+  verify_c_args(verify, address_ptr, signature_ptr, message, options)
+}
+
+fn verify_c_args<'a, T>(
+  verify: impl FnOnce(&str, &str, &[u8], &Options) -> Result<T, Error>,
+  address_ptr: *const c_char,
+  signature_ptr: *const c_char,
+  message: impl FnOnce() -> Result<&'a [u8], Error>,
+  options: &Options,
+) -> Result<T, Error> {
+  #[cfg(test)]
+  guard::test_hook();
+
+  // Safety: Convert C strings to Rust strings
+  let address = unsafe { str_from_ptr(address_ptr, "address")? };
+  let signature_hex = unsafe { str_from_ptr(signature_ptr, "signature")? };
+  let message = message()?;
+  crate::log::log(LOG_DEBUG, || format!("verifying {} byte message for {address}", message.len()));
+
+  // Message should be the same format that was signed client-side
+  let message = message::decode(message, options)?;
+  verify(address, signature_hex, &message, options)
+}
+
+/// Report the network of an SS58 address
+///
+/// # Arguments
+/// * `address_ptr` - SS58-encoded address with a 32-byte payload, or a
+///   33-byte one such as a compressed ECDSA public key
+///
+/// # Returns
+/// * The SS58 prefix, e.g. 0 for Polkadot, 2 for Kusama or 42 for generic
+///   Substrate
+/// * a negative `ERR_*` code if the address does not decode, such as
+///   `ERR_SS58_BAD_CHECKSUM`
+#[no_mangle]
+pub extern "C" fn get_ss58_prefix(address_ptr: *const c_char) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    // Safety: Convert C strings to Rust strings
+    let result = unsafe { str_from_ptr(address_ptr, "address") }.and_then(parse::ss58_prefix);
+    match result {
+      Ok(prefix) => i32::from(prefix),
+      Err(error) => {
+        set_last_error(&error);
+        error.code
+      }
+    }
+  })
+}
+
+/// Convert an SS58 address to another network's encoding of the same key
+///
+/// # Arguments
+/// * `address_ptr` - SS58-encoded address with a 32- or 33-byte payload
+/// * `target_prefix` - SS58 prefix to encode with, at most 16383
+///
+/// # Returns
+/// * The re-encoded address, to be released with `free_string`
+/// * NULL if the address does not decode or the prefix is out of range, see
+///   `get_last_error`
+#[no_mangle]
+pub extern "C" fn reencode_ss58_address(address_ptr: *const c_char, target_prefix: u16) -> *mut c_char {
+  ffi_guard(std::ptr::null_mut(), || {
+    // Safety: Convert C strings to Rust strings
+    let result = unsafe { str_from_ptr(address_ptr, "address") }
+      .and_then(|address| parse::reencode_ss58(address, target_prefix));
+    string_or_null(result)
+  })
+}
+
+/// Decode an address into the hex of its 32-byte account id
+///
+/// # Arguments
+/// * `address_ptr` - SS58-encoded address or hex public key
+///
+/// # Returns
+/// * `0x` and 64 lowercase hex digits, to be released with `free_string`.
+///   For sr25519 and ed25519 this is the public key. ECDSA accounts are
+///   identified by the `blake2_256` hash of the 33-byte compressed public
+///   key, which is not itself recoverable: addresses holding that hash
+///   return it unchanged, and addresses holding the 33-byte key return its
+///   hash.
+/// * NULL if the address does not decode, see `get_last_error`
+#[no_mangle]
+pub extern "C" fn address_to_public_key_hex(address_ptr: *const c_char) -> *mut c_char {
+  ffi_guard(std::ptr::null_mut(), || {
+    // Safety: Convert C strings to Rust strings
+    let result = unsafe { str_from_ptr(address_ptr, "address") }.and_then(parse::parse_address);
+    string_or_null(result.map(|address| format!("0x{}", hex::encode(address.account_id))))
+  })
+}
+
+/// Encode a public key as an SS58 address
+///
+/// # Arguments
+/// * `public_key_hex_ptr` - 32-byte public key or account id as hex, with
+///   or without `0x`
+/// * `prefix` - SS58 prefix of the network, at most 16383
+///
+/// # Returns
+/// * The address, to be released with `free_string`
+/// * NULL with `get_last_error` describing an `ERR_BAD_HEX`,
+///   `ERR_BAD_PUBLIC_KEY_LENGTH` or `ERR_BAD_SS58` (prefix out of range)
+///   failure
+#[no_mangle]
+pub extern "C" fn public_key_to_ss58(public_key_hex_ptr: *const c_char, prefix: u16) -> *mut c_char {
+  ffi_guard(std::ptr::null_mut(), || {
+    // Safety: Convert C strings to Rust strings
+    let result = unsafe { str_from_ptr(public_key_hex_ptr, "public key") }
+      .and_then(|public_key_hex| parse::public_key_to_ss58(public_key_hex, prefix));
+    string_or_null(result)
+  })
+}
+
+/// Check whether two addresses belong to the same account
+///
+/// Addresses are compared by account id, so encodings of one key for
+/// different networks are equal.
+///
+/// # Arguments
+/// * `a_ptr`, `b_ptr` - SS58-encoded addresses or hex public keys
+///
+/// # Returns
+/// * 1 if both decode to the same account id
+/// * 0 if they differ
+/// * `ERR_BAD_FIRST_ADDRESS` or `ERR_BAD_SECOND_ADDRESS` if that side does
+///   not decode, checking the first one first
+/// * `ERR_NULL_POINTER` or `ERR_INVALID_UTF8` for bad arguments
+#[no_mangle]
+pub extern "C" fn addresses_equal(a_ptr: *const c_char, b_ptr: *const c_char) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    let result = (|| {
+      // Safety: Convert C strings to Rust strings
+      let a = unsafe { str_from_ptr(a_ptr, "first address")? };
+      let b = unsafe { str_from_ptr(b_ptr, "second address")? };
+      let a = parse::parse_address(a)
+        .map_err(|error| api::VerifyError::InvalidFirstAddress { reason: error.message })?;
+      let b = parse::parse_address(b)
+        .map_err(|error| api::VerifyError::InvalidSecondAddress { reason: error.message })?;
+      Ok(a.account_id == b.account_id)
+    })();
+    match result {
+      Ok(equal) => i32::from(equal),
+      Err(error) => {
+        set_last_error(&error);
+        error.code
+      }
+    }
+  })
+}
+
+/// Hand a string to the caller, who releases it with `free_string`, or
+/// record the error and return NULL.
+pub(crate) fn string_or_null(result: Result<String, Error>) -> *mut c_char {
+  match result {
+    Ok(string) => CString::new(string).expect("encodings have no NUL").into_raw(),
+    Err(error) => {
+      set_last_error(&error);
+      std::ptr::null_mut()
+    }
+  }
+}
+
+/// Get the library version, such as "0.1.0", as set in Cargo.toml
+///
+/// The string is static and NUL-terminated: nothing is allocated, so the
+/// caller must not pass it to `free_string`, and it stays valid for the
+/// life of the process.
+#[no_mangle]
+pub extern "C" fn get_version() -> *const c_char {
+  ffi_guard(std::ptr::null(), || concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr() as *const c_char)
+}
+
+/// The major, minor and patch components of `CARGO_PKG_VERSION`.
+fn version_numbers() -> [u32; 3] {
+  [env!("CARGO_PKG_VERSION_MAJOR"), env!("CARGO_PKG_VERSION_MINOR"), env!("CARGO_PKG_VERSION_PATCH")]
+    .map(|value| value.parse().expect("cargo version components are numeric"))
+}
+
+/// Get the library version as numbers, for hosts comparing versions
+///
+/// # Arguments
+/// * `major`, `minor`, `patch` - Receive the version components
+///
+/// # Returns
+/// * `VALID` (1) once all three are written
+/// * `ERR_NULL_POINTER` if any pointer is NULL, in which case nothing is
+///   written
+#[no_mangle]
+pub extern "C" fn get_version_numbers(major: *mut u32, minor: *mut u32, patch: *mut u32) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    let outs = [(major, "major"), (minor, "minor"), (patch, "patch")];
+    if let Some((_, name)) = outs.iter().find(|(out, _)| out.is_null()) {
+      set_last_error(&api::VerifyError::NullPointer { name: name.to_string() }.into());
+      return ERR_NULL_POINTER;
+    }
+    for ((out, _), value) in outs.into_iter().zip(version_numbers()) {
+      // Safety: the caller passes valid, writable pointers
+      unsafe { out.write(value) };
+    }
+    VALID
+  })
+}
+
+/// Get the crate version as numbers
+///
+/// Prefer `get_version_numbers`, which reports NULL pointers.
+///
+/// # Arguments
+/// * `major`, `minor`, `patch` - Receive the version components. Any of
+///   them may be NULL.
+#[no_mangle]
+pub extern "C" fn get_crate_version_numbers(major: *mut u32, minor: *mut u32, patch: *mut u32) {
+  ffi_guard((), || {
+    for (out, value) in [major, minor, patch].into_iter().zip(version_numbers()) {
+      if !out.is_null() {
+        // Safety: the caller passes valid, writable pointers or NULL
+        unsafe { out.write(value) };
+      }
+    }
+  })
+}
+
+/// ABI revision this library was built with, see `ABI_VERSION`
+///
+/// Loaders should refuse to use a library whose ABI version they do not
+/// know.
+#[no_mangle]
+pub extern "C" fn get_abi_version() -> u32 {
+  ffi_guard(0, || ABI_VERSION)
+}
+
+/// Describe how the loaded library was built
+///
+/// # Returns
+/// A JSON object such as `{"version": "0.1.0", "features": ["default",
+/// "ffi"], "target": "x86_64-unknown-linux-gnu", "commit": "79bfe07"}`.
+/// `commit` is the output of `git describe --always --dirty`, or
+/// `"unknown"` if git was not available at build time. Release it with
+/// `free_string`. NULL is only returned if the library panicked.
+#[no_mangle]
+pub extern "C" fn get_build_info() -> *mut c_char {
+  ffi_guard(std::ptr::null_mut(), || {
+    string_or_null(Ok(serde_json::to_string(&BuildInfo::current()).expect("build info serializes")))
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::error::{clear_last_error, get_last_error};
+  use super::guard::panic_on_next_call;
+  use super::json::free_string;
+  use super::*;
+  use crate::codes::*;
+  use std::ffi::CStr;
+
+  #[test]
+  fn test_verify_signature() {
+      let address = CString::new("5HbqogsYaK54pN3QAgSi4t3Asqditt1X4P7cSBuDt2hPF2BR").unwrap();
+      let signature = CString::new("0xf8ce1b80e76bf48a30610d0d3c2c9c8dbfdcb28a9aa8f56ac038d520ff17445ff3ce94809f8479a1224ef0d823d035022a9db2e72bd6c0d2f0244f766dec908b").unwrap();
+
+      // wrapping format first
+      let wrapped_message = CString::new("<Bytes>c15335d817e3d3d912d30f5a18a30c30162abc364cd0876d3a94af568d3c8c14</Bytes>").unwrap();
+
+      let result = verify_polkadot_signature(
+          address.as_ptr(),
+          signature.as_ptr(),
+          wrapped_message.as_ptr(),
+      );
+
+      eprintln!("Testing with wrapped message:");
+      eprintln!("Address: {}", address.to_str().unwrap());
+      eprintln!("Signature: {}", signature.to_str().unwrap());
+      eprintln!("Message: {}", wrapped_message.to_str().unwrap());
+      eprintln!("Result: {}", result);
+
+      if result == 1 {
+          eprintln!("✓ Signature valid with <Bytes> wrapping");
+          return;
+      }
+
+      // Try without wrapping
+      let plain_message = CString::new("c15335d817e3d3d912d30f5a18a30c30162abc364cd0876d3a94af568d3c8c14").unwrap();
+      let result2 = verify_polkadot_signature(
+          address.as_ptr(),
+          signature.as_ptr(),
+          plain_message.as_ptr(),
+      );
+
+      eprintln!("\nTesting with plain message:");
+      eprintln!("Message: {}", plain_message.to_str().unwrap());
+      eprintln!("Result: {}", result2);
+
+      assert!(result == 1 || result2 == 1, "Signature verification failed with both wrapped and plain message");
+  }
+
+  #[test]
+  fn test_invalid_signature() {
+      let address = CString::new("5HbqogsYaK54pN3QAgSi4t3Asqditt1X4P7cSBuDt2hPF2BR").unwrap();
+      // Valid format but wrong signature
+      let bad_signature = CString::new("0x0000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000").unwrap();
+      let message = CString::new("<Bytes>c15335d817e3d3d912d30f5a18a30c30162abc364cd0876d3a94af568d3c8c14</Bytes>").unwrap();
+
+      let result = verify_polkadot_signature(
+          address.as_ptr(),
+          bad_signature.as_ptr(),
+          message.as_ptr(),
+      );
+
+      assert_eq!(result, 0, "Invalid signature should return 0");
+
+      // The all-zero signature is 65 bytes long, so it never reaches verification
+      let code = verify_polkadot_signature_v2(
+          address.as_ptr(),
+          bad_signature.as_ptr(),
+          message.as_ptr(),
+      );
+      assert_eq!(code, ERR_BAD_SIGNATURE_LENGTH);
+  }
+
+  #[test]
+  fn test_tampered_message() {
+      let address = CString::new("5HbqogsYaK54pN3QAgSi4t3Asqditt1X4P7cSBuDt2hPF2BR").unwrap();
+      let signature = CString::new("0xf8ce1b80e76bf48a30610d0d3c2c9c8dbfdcb28a9aa8f56ac038d520ff17445ff3ce94809f8479a1224ef0d823d035022a9db2e72bd6c0d2f0244f766dec908b").unwrap();
+      // Original message with one character changed (first 'c' -> 'd')
+      let tampered_message = CString::new("<Bytes>d15335d817e3d3d912d30f5a18a30c30162abc364cd0876d3a94af568d3c8c14</Bytes>").unwrap();
+
+      let result = verify_polkadot_signature(
+          address.as_ptr(),
+          signature.as_ptr(),
+          tampered_message.as_ptr(),
+      );
+
+      assert_eq!(result, 0, "Tampered message should fail verification");
+
+      let code = verify_polkadot_signature_v2(
+          address.as_ptr(),
+          signature.as_ptr(),
+          tampered_message.as_ptr(),
+      );
+      assert_eq!(code, INVALID);
+  }
+
+  #[test]
+  fn test_wrong_address() {
+      // Different address (Alice's well-known dev address)
+      let wrong_address = CString::new("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY").unwrap();
+      // Valid signature from a different keypair
+      let signature = CString::new("0xf8ce1b80e76bf48a30610d0d3c2c9c8dbfdcb28a9aa8f56ac038d520ff17445ff3ce94809f8479a1224ef0d823d035022a9db2e72bd6c0d2f0244f766dec908b").unwrap();
+      let message = CString::new("<Bytes>c15335d817e3d3d912d30f5a18a30c30162abc364cd0876d3a94af568d3c8c14</Bytes>").unwrap();
+
+      let result = verify_polkadot_signature(
+          wrong_address.as_ptr(),
+          signature.as_ptr(),
+          message.as_ptr(),
+      );
+
+      assert_eq!(result, 0, "Signature verified against wrong address should fail");
+
+      let code = verify_polkadot_signature_v2(
+          wrong_address.as_ptr(),
+          signature.as_ptr(),
+          message.as_ptr(),
+      );
+      assert_eq!(code, INVALID);
+  }
+
+  const ADDRESS: &str = "5HbqogsYaK54pN3QAgSi4t3Asqditt1X4P7cSBuDt2hPF2BR";
+  const SIGNATURE: &str = "0xf8ce1b80e76bf48a30610d0d3c2c9c8dbfdcb28a9aa8f56ac038d520ff17445ff3ce94809f8479a1224ef0d823d035022a9db2e72bd6c0d2f0244f766dec908b";
+  const MESSAGE: &str = "<Bytes>c15335d817e3d3d912d30f5a18a30c30162abc364cd0876d3a94af568d3c8c14</Bytes>";
+
+  fn last_error() -> String {
+      let ptr = get_last_error();
+      assert!(!ptr.is_null(), "last error should be set");
+      unsafe { CStr::from_ptr(ptr) }.to_str().unwrap().to_owned()
+  }
+
+  fn verify_v2(address: &str, signature: &str, message: &str) -> i32 {
+      let address = CString::new(address).unwrap();
+      let signature = CString::new(signature).unwrap();
+      let message = CString::new(message).unwrap();
+      verify_polkadot_signature_v2(address.as_ptr(), signature.as_ptr(), message.as_ptr())
+  }
+
+  #[test]
+  fn test_v2_valid() {
+      assert_eq!(verify_v2(ADDRESS, SIGNATURE, MESSAGE), VALID);
+  }
+
+  #[test]
+  fn test_v2_null_pointer() {
+      let address = CString::new(ADDRESS).unwrap();
+      let signature = CString::new(SIGNATURE).unwrap();
+      let message = CString::new(MESSAGE).unwrap();
+
+      let null = std::ptr::null();
+      assert_eq!(verify_polkadot_signature_v2(null, signature.as_ptr(), message.as_ptr()), ERR_NULL_POINTER);
+      assert_eq!(verify_polkadot_signature_v2(address.as_ptr(), null, message.as_ptr()), ERR_NULL_POINTER);
+      assert_eq!(verify_polkadot_signature_v2(address.as_ptr(), signature.as_ptr(), null), ERR_NULL_POINTER);
+      assert_eq!(verify_polkadot_signature(null, signature.as_ptr(), message.as_ptr()), 0);
+  }
+
+  #[test]
+  fn test_v2_invalid_utf8() {
+      let address = CString::new(ADDRESS).unwrap();
+      let signature = CString::new(SIGNATURE).unwrap();
+      let message = CString::new(vec![0xffu8, 0xfe]).unwrap();
+
+      let code = verify_polkadot_signature_v2(address.as_ptr(), signature.as_ptr(), message.as_ptr());
+      assert_eq!(code, ERR_INVALID_UTF8);
+  }
+
+  #[test]
+  fn test_v2_bad_ss58() {
+      assert_eq!(verify_v2("not-an-address", SIGNATURE, MESSAGE), ERR_SS58_BAD_BASE58);
+      assert_eq!(last_error(), "address is not valid base58");
+  }
+
+  #[test]
+  fn test_ss58_bad_checksum() {
+      // Flip the last character so only the checksum is wrong
+      let mut mistyped = ADDRESS.to_owned();
+      mistyped.pop();
+      mistyped.push('S');
+      assert_eq!(verify_v2(&mistyped, SIGNATURE, MESSAGE), ERR_SS58_BAD_CHECKSUM);
+      assert_eq!(last_error(), "SS58 checksum mismatch");
+  }
+
+  #[test]
+  fn test_ss58_bad_length() {
+      // Dropping a character leaves a 31-byte payload that fails its checksum
+      assert_eq!(verify_v2(&ADDRESS[..ADDRESS.len() - 1], SIGNATURE, MESSAGE), ERR_SS58_BAD_LENGTH);
+      assert_eq!(last_error(), "SS58 payload has the wrong length");
+      assert_eq!(verify_v2("", SIGNATURE, MESSAGE), ERR_SS58_BAD_LENGTH);
+  }
+
+  #[test]
+  fn test_ss58_wrong_key_length() {
+      // A well-formed address around a 20-byte AccountId20
+      assert_eq!(verify_v2("tTgoSAJJvPreV4Q7jo514ZnuQoTkoYH", SIGNATURE, MESSAGE), ERR_WRONG_KEY_LENGTH);
+      assert!(last_error().contains("20-byte AccountId20"), "{}", last_error());
+
+      // The lengths that do hold keys still verify
+      assert_eq!(verify_v2(ADDRESS, SIGNATURE, MESSAGE), VALID);
+      #[cfg(feature = "ecdsa")]
+      assert_eq!(verify_ecdsa(ECDSA_ADDRESS, ECDSA_SIGNATURE, MESSAGE), VALID);
+  }
+
+  #[test]
+  fn test_ss58_prefix_not_allowed() {
+      assert_eq!(verify_with(MESSAGE, 0, &[0]), ERR_WRONG_NETWORK);
+      assert_eq!(last_error(), "SS58 prefix 42 is not allowed, expected one of [0]");
+  }
+
+  #[test]
+  fn test_strict_network() {
+      // The test key encoded for Polkadot, Kusama and generic Substrate
+      let encodings = [
+          (0, "16Y8x28cS6LYFu3v8KViD2sKjTdNbBZf8sr6bUtaS7iuRDs5"),
+          (2, "J7TU1DRCg5za1rqwPFkxqQB2RuxhYphWkxMprBBMpusz4S5"),
+          (42, ADDRESS),
+      ];
+      let signature = CString::new(SIGNATURE).unwrap();
+      let message = CString::new(MESSAGE).unwrap();
+      for (allowed, _) in encodings {
+          for (prefix, address) in encodings {
+              let address = CString::new(address).unwrap();
+              let code =
+                  verify_polkadot_signature_strict_network(address.as_ptr(), signature.as_ptr(), message.as_ptr(), allowed);
+              assert_eq!(code, if prefix == allowed { VALID } else { ERR_WRONG_NETWORK }, "prefix {prefix}, allowed {allowed}");
+          }
+      }
+
+      // The default stays permissive
+      for (_, address) in encodings {
+          assert_eq!(verify_v2(address, SIGNATURE, MESSAGE), VALID);
+      }
+  }
+
+  #[test]
+  fn test_v2_bad_hex() {
+      assert_eq!(verify_v2(ADDRESS, "0xzz", MESSAGE), ERR_BAD_HEX);
+      assert_eq!(last_error(), "signature hex has invalid character 'z' at position 0");
+
+      // Odd number of hex digits
+      assert_eq!(verify_v2(ADDRESS, &SIGNATURE[..SIGNATURE.len() - 1], MESSAGE), ERR_BAD_HEX);
+      assert_eq!(last_error(), "signature hex had odd length");
+  }
+
+  #[test]
+  fn test_v2_bad_signature_length() {
+      assert_eq!(verify_v2(ADDRESS, &SIGNATURE[..SIGNATURE.len() - 2], MESSAGE), ERR_BAD_SIGNATURE_LENGTH);
+      assert_eq!(last_error(), "signature is 63 bytes, expected 64");
+  }
+
+  #[test]
+  fn test_ex_outcome() {
+      let address = CString::new(ADDRESS).unwrap();
+      let signature = CString::new(SIGNATURE).unwrap();
+      let message = CString::new(MESSAGE).unwrap();
+
+      let mut outcome = VerifyOutcome::default();
+      let code = verify_polkadot_signature_ex(address.as_ptr(), signature.as_ptr(), message.as_ptr(), &mut outcome);
+
+      let mut public_key = [0u8; 32];
+      hex::decode_to_slice("f4ec883f28db136cd98e3b553cab48ab4671111978a4660f129a45cb4df84d30", &mut public_key).unwrap();
+      assert_eq!(code, VALID);
+      assert_eq!(outcome, VerifyOutcome { valid: 1, error_code: VALID, public_key, ss58_prefix: 42 });
+  }
+
+  #[test]
+  fn test_ex_zeroes_outcome_on_error() {
+      let address = CString::new(ADDRESS).unwrap();
+      let signature = CString::new("0xzz").unwrap();
+      let message = CString::new(MESSAGE).unwrap();
+
+      let mut outcome = VerifyOutcome { valid: 1, error_code: 7, public_key: [0xff; 32], ss58_prefix: 2 };
+      let code = verify_polkadot_signature_ex(address.as_ptr(), signature.as_ptr(), message.as_ptr(), &mut outcome);
+
+      assert_eq!(code, ERR_BAD_HEX);
+      assert_eq!(outcome, VerifyOutcome { error_code: ERR_BAD_HEX, ..VerifyOutcome::default() });
+  }
+
+  #[test]
+  fn test_ex_null_out() {
+      let address = CString::new(ADDRESS).unwrap();
+      let signature = CString::new(SIGNATURE).unwrap();
+      let message = CString::new(MESSAGE).unwrap();
+
+      let code = verify_polkadot_signature_ex(address.as_ptr(), signature.as_ptr(), message.as_ptr(), std::ptr::null_mut());
+      assert_eq!(code, VALID);
+  }
+
+  fn verify_with(message: &str, flags: u32, allowed_prefixes: &[u16]) -> i32 {
+      let address = CString::new(ADDRESS).unwrap();
+      let signature = CString::new(SIGNATURE).unwrap();
+      let message = CString::new(message).unwrap();
+      let options = VerifyOptions {
+          flags,
+          allowed_prefixes: allowed_prefixes.as_ptr(),
+          allowed_prefixes_len: allowed_prefixes.len(),
+          ..VerifyOptions::default()
+      };
+      verify_polkadot_signature_with_options(address.as_ptr(), signature.as_ptr(), message.as_ptr(), &options)
+  }
+
+  #[test]
+  fn test_options_default() {
+      let address = CString::new(ADDRESS).unwrap();
+      let signature = CString::new(SIGNATURE).unwrap();
+      let message = CString::new(MESSAGE).unwrap();
+      let code = verify_polkadot_signature_with_options(address.as_ptr(), signature.as_ptr(), message.as_ptr(), std::ptr::null());
+      assert_eq!(code, VALID);
+
+      assert_eq!(verify_with(MESSAGE, 0, &[]), VALID);
+  }
+
+  #[test]
+  fn test_options_prefix_and_wrap() {
+      let raw = "c15335d817e3d3d912d30f5a18a30c30162abc364cd0876d3a94af568d3c8c14";
+
+      // The test address uses the generic substrate prefix 42
+      assert_eq!(verify_with(raw, OPT_WRAP_BYTES, &[42]), VALID);
+      assert_eq!(verify_with(raw, OPT_WRAP_BYTES, &[0, 42]), VALID);
+      // Already wrapped messages are not wrapped twice
+      assert_eq!(verify_with(MESSAGE, OPT_WRAP_BYTES, &[42]), VALID);
+      assert_eq!(verify_with(raw, 0, &[42]), INVALID);
+
+      // The prefix is checked before the message is even looked at
+      assert_eq!(verify_with(raw, OPT_WRAP_BYTES, &[0]), ERR_WRONG_NETWORK);
+      assert_eq!(last_error(), "SS58 prefix 42 is not allowed, expected one of [0]");
+      assert_eq!(verify_with(raw, 0, &[0, 2]), ERR_WRONG_NETWORK);
+  }
+
+  // //Alice as an ed25519 key, signing MESSAGE
+  const ED25519_ADDRESS: &str = "5FA9nQDVg267DEd8m1ZypXLBnvN7SFxYwV7ndqSYGiN9TTpu";
+  const ED25519_SIGNATURE: &str = "0xabd163f9ffa6334632220e353ca548fe66d65456413b69c1241e551dcdf1efba1f2f8a412a4243c6c389b3445997b8cf80e4e8a275e64bb714644a3b069d1d0d";
+
+  #[cfg(feature = "sp-core")]
+  fn verify_ed25519(address: &str, signature: &str, message: &str) -> i32 {
+      let address = CString::new(address).unwrap();
+      let signature = CString::new(signature).unwrap();
+      let message = CString::new(message).unwrap();
+      verify_polkadot_ed25519_signature(address.as_ptr(), signature.as_ptr(), message.as_ptr())
+  }
+
+  #[test]
+  #[cfg(feature = "sp-core")]
+  fn test_ed25519_known_answer() {
+      assert_eq!(verify_ed25519(ED25519_ADDRESS, ED25519_SIGNATURE, MESSAGE), VALID);
+      assert_eq!(verify_ed25519(ED25519_ADDRESS, ED25519_SIGNATURE, "tampered"), INVALID);
+  }
+
+  #[test]
+  fn test_ed25519_matches_dev_key() {
+      use sp_core::{crypto::Ss58Codec, ed25519, Pair};
+
+      let pair = ed25519::Pair::from_string("//Alice", None).unwrap();
+      assert_eq!(pair.public().to_ss58check(), ED25519_ADDRESS);
+
+      // ed25519 is deterministic, so signing again reproduces the vector
+      let signature = pair.sign(MESSAGE.as_bytes());
+      assert_eq!(format!("0x{}", hex::encode(signature)), ED25519_SIGNATURE);
+  }
+
+  #[test]
+  fn test_schemes_do_not_cross_verify() {
+      // An sr25519 signature is not a valid ed25519 signature for the same key
+      #[cfg(feature = "sp-core")]
+      assert_eq!(verify_ed25519(ADDRESS, SIGNATURE, MESSAGE), INVALID);
+      // and vice versa
+      assert_eq!(verify_v2(ED25519_ADDRESS, ED25519_SIGNATURE, MESSAGE), INVALID);
+  }
+
+  #[test]
+  #[cfg(feature = "sp-core")]
+  fn test_ed25519_parse_errors() {
+      assert_eq!(verify_ed25519("not-an-address", ED25519_SIGNATURE, MESSAGE), ERR_SS58_BAD_BASE58);
+      assert_eq!(verify_ed25519(ED25519_ADDRESS, "0x1234", MESSAGE), ERR_BAD_SIGNATURE_LENGTH);
+  }
+
+  // //Alice as an ECDSA key, signing MESSAGE. The address is the blake2-256
+  // hash of the compressed public key.
+  const ECDSA_ADDRESS: &str = "5C7C2Z5sWbytvHpuLTvzKunnnRwQxft1jiqrLD5rhucQ5S9X";
+  const ECDSA_SIGNATURE: &str = "0x6d49a01b12c9ba60e0ffd1638996f9cbcf6c1ca518adb3d44d2f2a68b983eaea3dcc0c125637af13b5bc679068e87afdd35f523efc33a9eb052275edf8ebbf8c00";
+
+  #[cfg(feature = "ecdsa")]
+  fn verify_ecdsa(address: &str, signature: &str, message: &str) -> i32 {
+      let address = CString::new(address).unwrap();
+      let signature = CString::new(signature).unwrap();
+      let message = CString::new(message).unwrap();
+      verify_polkadot_ecdsa_signature(address.as_ptr(), signature.as_ptr(), message.as_ptr())
+  }
+
+  #[test]
+  #[cfg(feature = "ecdsa")]
+  fn test_ecdsa_known_answer() {
+      assert_eq!(verify_ecdsa(ECDSA_ADDRESS, ECDSA_SIGNATURE, MESSAGE), VALID);
+      assert_eq!(verify_ecdsa(ECDSA_ADDRESS, ECDSA_SIGNATURE, "tampered"), INVALID);
+      assert_eq!(verify_ecdsa(ADDRESS, ECDSA_SIGNATURE, MESSAGE), INVALID);
+  }
+
+  #[test]
+  fn test_ecdsa_matches_dev_key() {
+      use sp_core::{crypto::Ss58Codec, ecdsa, Pair};
+
+      let pair = ecdsa::Pair::from_string("//Alice", None).unwrap();
+      let account_id = sp_core::hashing::blake2_256(pair.public().as_ref());
+      assert_eq!(sp_core::crypto::AccountId32::from(account_id).to_ss58check(), ECDSA_ADDRESS);
+
+      // RFC 6979 nonces make ECDSA signing deterministic
+      let signature = pair.sign(MESSAGE.as_bytes());
+      assert_eq!(format!("0x{}", hex::encode(signature)), ECDSA_SIGNATURE);
+  }
+
+  #[test]
+  #[cfg(feature = "ecdsa")]
+  fn test_ecdsa_flipped_recovery_id() {
+      // Flipping the recovery id recovers a different public key
+      let flipped = format!("{}01", &ECDSA_SIGNATURE[..ECDSA_SIGNATURE.len() - 2]);
+      assert_eq!(verify_ecdsa(ECDSA_ADDRESS, &flipped, MESSAGE), INVALID);
+
+      // Recovery ids above 3 recover nothing
+      let out_of_range = format!("{}05", &ECDSA_SIGNATURE[..ECDSA_SIGNATURE.len() - 2]);
+      assert_eq!(verify_ecdsa(ECDSA_ADDRESS, &out_of_range, MESSAGE), INVALID);
+  }
+
+  #[test]
+  #[cfg(feature = "ecdsa")]
+  fn test_ecdsa_signature_length() {
+      // A 64-byte sr25519 signature is too short for ECDSA
+      assert_eq!(verify_ecdsa(ECDSA_ADDRESS, SIGNATURE, MESSAGE), ERR_BAD_SIGNATURE_LENGTH);
+      assert_eq!(last_error(), "signature is 64 bytes, expected 65");
+  }
+
+  #[test]
+  #[cfg(feature = "ecdsa")]
+  fn test_ecdsa_both_address_encodings() {
+      use sp_core::{crypto::Ss58Codec, ecdsa, Pair};
+
+      let pair = ecdsa::Pair::from_string("//Alice", None).unwrap();
+      let key_address = pair.public().to_ss58check();
+      let account_address = sp_core::crypto::AccountId32::from(sp_core::hashing::blake2_256(pair.public().as_ref())).to_ss58check();
+      assert_eq!(account_address, ECDSA_ADDRESS);
+
+      for address in [account_address.as_str(), key_address.as_str()] {
+          assert_eq!(verify_ecdsa(address, ECDSA_SIGNATURE, MESSAGE), VALID, "{address}");
+          assert_eq!(verify_ecdsa(address, ECDSA_SIGNATURE, "tampered"), INVALID, "{address}");
+          assert_eq!(verify_scheme_with(address, ECDSA_SIGNATURE, MESSAGE, SCHEME_ECDSA, 0), VALID, "{address}");
+          assert_eq!(verify_any(address, ECDSA_SIGNATURE, MESSAGE), (VALID, SCHEME_ECDSA), "{address}");
+      }
+      assert_eq!(equal(&key_address, &account_address), 1);
+      assert_eq!(public_key_hex_of(&key_address), public_key_hex_of(&account_address));
+  }
+
+  #[test]
+  fn test_ecdsa_public_key_address_needs_ecdsa_signature() {
+      use sp_core::{crypto::Ss58Codec, ecdsa, Pair};
+
+      let key_address = ecdsa::Pair::from_string("//Alice", None).unwrap().public().to_ss58check();
+      assert_eq!(verify_v2(&key_address, SIGNATURE, MESSAGE), ERR_NO_MATCHING_SCHEME);
+      assert_eq!(last_error(), "address holds a 33-byte ECDSA public key, which a 64-byte signature cannot match");
+      #[cfg(feature = "sp-core")]
+      assert_eq!(verify_ed25519(&key_address, ED25519_SIGNATURE, MESSAGE), ERR_NO_MATCHING_SCHEME);
+  }
+
+  // ECDSA_SIGNATURE with s replaced by n - s and the recovery id flipped
+  #[cfg(feature = "ecdsa")]
+  const ECDSA_HIGH_S_SIGNATURE: &str = "0x6d49a01b12c9ba60e0ffd1638996f9cbcf6c1ca518adb3d44d2f2a68b983eaeac233f3eda9c850ec4a43986f97178500e74f8aa7b314f650baafe89ed74a81b501";
+
+  fn verify_scheme_with(address: &str, signature: &str, message: &str, scheme: i32, flags: u32) -> i32 {
+      let address = CString::new(address).unwrap();
+      let signature = CString::new(signature).unwrap();
+      let message = CString::new(message).unwrap();
+      let options = VerifyOptions { flags, ..VerifyOptions::default() };
+      verify_signature_with_options(address.as_ptr(), signature.as_ptr(), message.as_ptr(), scheme, &options)
+  }
+
+  #[test]
+  #[cfg(feature = "ecdsa")]
+  fn test_ecdsa_high_s_rejected() {
+      assert_eq!(verify_ecdsa(ECDSA_ADDRESS, ECDSA_HIGH_S_SIGNATURE, MESSAGE), ERR_NON_CANONICAL_SIGNATURE);
+      assert_eq!(last_error(), "ECDSA signature has a high s value");
+      assert_eq!(verify_scheme_with(ECDSA_ADDRESS, ECDSA_HIGH_S_SIGNATURE, MESSAGE, SCHEME_ECDSA, 0), ERR_NON_CANONICAL_SIGNATURE);
+  }
+
+  #[test]
+  #[cfg(feature = "ecdsa")]
+  fn test_ecdsa_high_s_allowed() {
+      let flags = OPT_ALLOW_NON_CANONICAL_ECDSA;
+      assert_eq!(verify_scheme_with(ECDSA_ADDRESS, ECDSA_HIGH_S_SIGNATURE, MESSAGE, SCHEME_ECDSA, flags), VALID);
+      assert_eq!(verify_scheme_with(ECDSA_ADDRESS, ECDSA_SIGNATURE, MESSAGE, SCHEME_ECDSA, flags), VALID);
+      // Normalizing does not make a wrong signature verify
+      assert_eq!(verify_scheme_with(ECDSA_ADDRESS, ECDSA_HIGH_S_SIGNATURE, "tampered", SCHEME_ECDSA, flags), INVALID);
+  }
+
+  #[test]
+  fn test_sr25519_unreduced_s() {
+      // SIGNATURE with the group order added to s
+      let unreduced = "0xf8ce1b80e76bf48a30610d0d3c2c9c8dbfdcb28a9aa8f56ac038d520ff17445fe0a28addb9e78bf9f8eae77b02ca14172a9db2e72bd6c0d2f0244f766dec909b";
+      assert_eq!(verify_v2(ADDRESS, unreduced, MESSAGE), ERR_NON_CANONICAL_SIGNATURE);
+  }
+
+  #[test]
+  fn test_scheme_with_options() {
+      assert_eq!(verify_scheme_with(ADDRESS, SIGNATURE, MESSAGE, SCHEME_SR25519, 0), VALID);
+      #[cfg(feature = "sp-core")]
+      assert_eq!(verify_scheme_with(ED25519_ADDRESS, ED25519_SIGNATURE, MESSAGE, SCHEME_ED25519, 0), VALID);
+      assert_eq!(verify_scheme_with(ED25519_ADDRESS, ED25519_SIGNATURE, MESSAGE, SCHEME_SR25519, 0), INVALID);
+      assert_eq!(verify_scheme_with(ADDRESS, SIGNATURE, MESSAGE, SCHEME_NONE, 0), ERR_UNKNOWN_SCHEME);
+      #[cfg(feature = "ecdsa")]
+      assert_eq!(verify_scheme_with(ADDRESS, SIGNATURE, MESSAGE, SCHEME_ETHEREUM, 0), ERR_UNKNOWN_SCHEME);
+  }
+
+  fn verify_any(address: &str, signature: &str, message: &str) -> (i32, i32) {
+      let address = CString::new(address).unwrap();
+      let signature = CString::new(signature).unwrap();
+      let message = CString::new(message).unwrap();
+      let mut scheme = -1;
+      let code = verify_any_signature(address.as_ptr(), signature.as_ptr(), message.as_ptr(), &mut scheme);
+      (code, scheme)
+  }
+
+  #[test]
+  fn test_any_detects_scheme() {
+      assert_eq!(verify_any(ADDRESS, SIGNATURE, MESSAGE), (VALID, SCHEME_SR25519));
+      #[cfg(feature = "sp-core")]
+      assert_eq!(verify_any(ED25519_ADDRESS, ED25519_SIGNATURE, MESSAGE), (VALID, SCHEME_ED25519));
+      #[cfg(feature = "ecdsa")]
+      assert_eq!(verify_any(ECDSA_ADDRESS, ECDSA_SIGNATURE, MESSAGE), (VALID, SCHEME_ECDSA));
+  }
+
+  #[test]
+  fn test_any_no_match() {
+      assert_eq!(verify_any(ADDRESS, SIGNATURE, "tampered"), (INVALID, SCHEME_NONE));
+      #[cfg(feature = "ecdsa")]
+      assert_eq!(verify_any(ECDSA_ADDRESS, ECDSA_SIGNATURE, "tampered"), (INVALID, SCHEME_NONE));
+  }
+
+  #[test]
+  fn test_any_unknown_length() {
+      let signature = format!("0x{}", "ab".repeat(66));
+      assert_eq!(verify_any(ADDRESS, &signature, MESSAGE), (ERR_NO_MATCHING_SCHEME, SCHEME_NONE));
+      assert_eq!(last_error(), "signature is 66 bytes, expected 64 (sr25519, ed25519) or 65 (ecdsa)");
+
+      let address = CString::new(ADDRESS).unwrap();
+      let signature = CString::new(SIGNATURE).unwrap();
+      let message = CString::new(MESSAGE).unwrap();
+      let code = verify_any_signature(address.as_ptr(), signature.as_ptr(), message.as_ptr(), std::ptr::null_mut());
+      assert_eq!(code, VALID);
+  }
+
+  fn verify_multi(address: &str, multisignature: &str, message: &str) -> i32 {
+      let address = CString::new(address).unwrap();
+      let multisignature = CString::new(multisignature).unwrap();
+      let message = CString::new(message).unwrap();
+      verify_multisignature(address.as_ptr(), multisignature.as_ptr(), message.as_ptr())
+  }
+
+  /// Prefix a hex signature with a MultiSignature variant tag.
+  fn tagged(tag: &str, signature: &str) -> String {
+      format!("0x{tag}{}", signature.trim_start_matches("0x"))
+  }
+
+  #[test]
+  fn test_multisignature_variants() {
+      #[cfg(feature = "sp-core")]
+      assert_eq!(verify_multi(ED25519_ADDRESS, &tagged("00", ED25519_SIGNATURE), MESSAGE), VALID);
+      assert_eq!(verify_multi(ADDRESS, &tagged("01", SIGNATURE), MESSAGE), VALID);
+      #[cfg(feature = "ecdsa")]
+      {
+          assert_eq!(verify_multi(ECDSA_ADDRESS, &tagged("02", ECDSA_SIGNATURE), MESSAGE), VALID);
+          assert_eq!(verify_multi(ECDSA_ADDRESS, &tagged("02", ECDSA_SIGNATURE), "tampered"), INVALID);
+      }
+  }
+
+  #[test]
+  fn test_multisignature_tag_mismatch() {
+      // An ed25519 signature tagged as sr25519 must not verify
+      assert_eq!(verify_multi(ED25519_ADDRESS, &tagged("01", ED25519_SIGNATURE), MESSAGE), INVALID);
+      #[cfg(feature = "sp-core")]
+      assert_eq!(verify_multi(ADDRESS, &tagged("00", SIGNATURE), MESSAGE), INVALID);
+  }
+
+  #[test]
+  fn test_multisignature_malformed() {
+      assert_eq!(verify_multi(ADDRESS, &tagged("03", SIGNATURE), MESSAGE), ERR_BAD_MULTISIGNATURE);
+      assert_eq!(last_error(), "unknown MultiSignature variant 0x03");
+
+      // Truncated payloads, and an sr25519 payload under the ECDSA tag
+      assert_eq!(verify_multi(ADDRESS, &tagged("01", &SIGNATURE[..SIGNATURE.len() - 2]), MESSAGE), ERR_BAD_MULTISIGNATURE);
+      assert_eq!(verify_multi(ADDRESS, &tagged("02", SIGNATURE), MESSAGE), ERR_BAD_MULTISIGNATURE);
+      assert_eq!(last_error(), "MultiSignature variant 0x02 carries 64 bytes, expected 65");
+
+      assert_eq!(verify_multi(ADDRESS, "0x", MESSAGE), ERR_BAD_MULTISIGNATURE);
+      assert_eq!(verify_multi(ADDRESS, "0x01zz", MESSAGE), ERR_BAD_HEX);
+  }
+
+  fn verify_lenient(address: &str, signature: &str, message: &str) -> i32 {
+      let address = CString::new(address).unwrap();
+      let signature = CString::new(signature).unwrap();
+      let message = CString::new(message).unwrap();
+      verify_polkadot_signature_lenient(address.as_ptr(), signature.as_ptr(), message.as_ptr())
+  }
+
+  #[test]
+  fn test_lenient_scheme_prefix() {
+      assert_eq!(verify_lenient(ADDRESS, &tagged("01", SIGNATURE), MESSAGE), VALID);
+      #[cfg(feature = "sp-core")]
+      assert_eq!(verify_lenient(ED25519_ADDRESS, &tagged("00", ED25519_SIGNATURE), MESSAGE), VALID);
+      assert_eq!(verify_lenient(ADDRESS, &tagged("01", SIGNATURE), "tampered"), INVALID);
+      // Plain 64-byte signatures keep working
+      assert_eq!(verify_lenient(ADDRESS, SIGNATURE, MESSAGE), VALID);
+
+      // The strict default still rejects the prefixed form
+      assert_eq!(verify_v2(ADDRESS, &tagged("01", SIGNATURE), MESSAGE), ERR_BAD_SIGNATURE_LENGTH);
+  }
+
+  #[test]
+  fn test_strip_scheme_prefix_option() {
+      let address = CString::new(ADDRESS).unwrap();
+      let signature = CString::new(tagged("01", SIGNATURE)).unwrap();
+      let message = CString::new(MESSAGE).unwrap();
+      let options = VerifyOptions { flags: OPT_STRIP_SCHEME_PREFIX, ..VerifyOptions::default() };
+      let code = verify_polkadot_signature_with_options(address.as_ptr(), signature.as_ptr(), message.as_ptr(), &options);
+      assert_eq!(code, VALID);
+  }
+
+  #[test]
+  fn test_lenient_unknown_prefix() {
+      assert_eq!(verify_lenient(ADDRESS, &tagged("02", SIGNATURE), MESSAGE), ERR_BAD_SIGNATURE_PREFIX);
+      assert_eq!(last_error(), "65-byte signature starts with 0x02, expected a 0x00 or 0x01 scheme byte");
+      // Other lengths are still length errors
+      assert_eq!(verify_lenient(ADDRESS, &tagged("0101", SIGNATURE), MESSAGE), ERR_BAD_SIGNATURE_LENGTH);
+  }
+
+  #[cfg(feature = "sp-core")]
+  const LEDGER_RAW_MESSAGE: &str = "Sign in to example.com with your Ledger";
+  #[cfg(feature = "sp-core")]
+  const LEDGER_RAW_SIGNATURE: &str = "0x6789fa0f4fdc74a5cc74544111f1f893f20140e1fbb008322874c7a85d36a749f5e0d2ba32e9153e430d408a0aa5d64ac4888e0769071a5b687fff98333ed907";
+  // Over blake2_256 of "Ledger blind-signing payload " repeated 12 times
+  #[cfg(feature = "sp-core")]
+  const LEDGER_HASHED_SIGNATURE: &str = "0xb4eb6c6cafee73657676a1899b8a154de98b5e1732dd830bdd85473197820f15787df14d004e409ecea9a49524a622a67acf6c7fdde897fc5f4d87f914478c03";
+
+  #[cfg(feature = "sp-core")]
+  fn verify_ledger(address: &str, signature: &str, message: &str) -> (i32, i32) {
+      let address = CString::new(address).unwrap();
+      let signature = CString::new(signature).unwrap();
+      let message = CString::new(message).unwrap();
+      let mut convention = -1;
+      let code = verify_ledger_signature(address.as_ptr(), signature.as_ptr(), message.as_ptr(), &mut convention);
+      (code, convention)
+  }
+
+  #[test]
+  #[cfg(feature = "sp-core")]
+  fn test_ledger_raw() {
+      assert_eq!(verify_ledger(ED25519_ADDRESS, LEDGER_RAW_SIGNATURE, LEDGER_RAW_MESSAGE), (VALID, LEDGER_RAW));
+      // The plain ed25519 entry point agrees
+      assert_eq!(verify_ed25519(ED25519_ADDRESS, LEDGER_RAW_SIGNATURE, LEDGER_RAW_MESSAGE), VALID);
+  }
+
+  #[test]
+  #[cfg(feature = "sp-core")]
+  fn test_ledger_blake2_256() {
+      let payload = "Ledger blind-signing payload ".repeat(12);
+      assert_eq!(verify_ledger(ED25519_ADDRESS, LEDGER_HASHED_SIGNATURE, &payload), (VALID, LEDGER_BLAKE2_256));
+      // Past 256 bytes this coincides with the polkadot-js hashing rule
+      assert_eq!(verify_ed25519(ED25519_ADDRESS, LEDGER_HASHED_SIGNATURE, &payload), VALID);
+  }
+
+  #[test]
+  #[cfg(feature = "sp-core")]
+  fn test_ledger_no_match() {
+      assert_eq!(verify_ledger(ED25519_ADDRESS, LEDGER_RAW_SIGNATURE, "tampered"), (INVALID, LEDGER_NONE));
+      // sr25519 signatures are not Ledger signatures
+      assert_eq!(verify_ledger(ADDRESS, SIGNATURE, MESSAGE), (INVALID, LEDGER_NONE));
+      assert_eq!(verify_ledger(ED25519_ADDRESS, "0x00", LEDGER_RAW_MESSAGE), (ERR_BAD_SIGNATURE_LENGTH, LEDGER_NONE));
+  }
+
+  fn verify_bytes(address: &str, signature: &str, message: *const u8, message_len: usize) -> i32 {
+      let address = CString::new(address).unwrap();
+      let signature = CString::new(signature).unwrap();
+      verify_polkadot_signature_bytes(address.as_ptr(), signature.as_ptr(), message, message_len)
+  }
+
+  #[test]
+  fn test_bytes_interior_nul() {
+      use sp_core::{crypto::Ss58Codec, sr25519, Pair};
+
+      let pair = sr25519::Pair::from_string("//Alice", None).unwrap();
+      let address = pair.public().to_ss58check();
+      // A SCALE-encoded (u32, Vec<u8>) with zero bytes in both fields
+      let message = [0x2a, 0x00, 0x00, 0x00, 0x0c, 0x00, 0xff, 0x00];
+      let signature = format!("0x{}", hex::encode(pair.sign(&message)));
+
+      assert_eq!(verify_bytes(&address, &signature, message.as_ptr(), message.len()), VALID);
+      // Cut at the first NUL, as a C string would be
+      assert_eq!(verify_bytes(&address, &signature, message.as_ptr(), 1), INVALID);
+  }
+
+  #[test]
+  fn test_bytes_empty_message() {
+      use sp_core::{crypto::Ss58Codec, sr25519, Pair};
+
+      let pair = sr25519::Pair::from_string("//Alice", None).unwrap();
+      let address = pair.public().to_ss58check();
+      let signature = format!("0x{}", hex::encode(pair.sign(b"")));
+
+      let empty: [u8; 0] = [];
+      assert_eq!(verify_bytes(&address, &signature, empty.as_ptr(), 0), VALID);
+      assert_eq!(verify_bytes(&address, &signature, std::ptr::null(), 0), VALID);
+
+      assert_eq!(verify_bytes(&address, &signature, std::ptr::null(), 4), ERR_NULL_POINTER);
+      assert_eq!(last_error(), "message pointer is NULL but its length is 4");
+  }
+
+  #[test]
+  fn test_bytes_matches_c_string_api() {
+      assert_eq!(verify_bytes(ADDRESS, SIGNATURE, MESSAGE.as_ptr(), MESSAGE.len()), VALID);
+      assert_eq!(verify_bytes(ADDRESS, SIGNATURE, MESSAGE.as_ptr(), MESSAGE.len() - 1), INVALID);
+  }
+
+  // ED25519_ADDRESS signatures over the bytes 9f3c00a1b2c3d4e5f60718293a4b5c6d
+  const HEX_MESSAGE: &str = "0x9f3c00a1b2c3d4e5f60718293a4b5c6d";
+  const HEX_MESSAGE_SIGNATURE: &str = "0x23b389598e83a7a991b05a2bf66dcb8f6eaaae75c1dd3576f1e95d7bf943e68f01f64fca0d8dea5f0cfed42d710d0f9b39f0e385d19fc538d150adb947d8ea07";
+  // ...and over the same bytes wrapped in <Bytes>...</Bytes>
+  #[cfg(feature = "sp-core")]
+  const HEX_MESSAGE_WRAPPED_SIGNATURE: &str = "0x3ff20a4089c6e021070d23de06cb21f5cd856d2adf87a9508472b501462afe16c1da3c3dd21bae2fc78607908f0f9a26fea641d7bd2c7c7bf68593d6e3f49605";
+
+  #[test]
+  #[cfg(feature = "sp-core")]
+  fn test_message_is_hex() {
+      let flags = OPT_MESSAGE_IS_HEX;
+      assert_eq!(verify_scheme_with(ED25519_ADDRESS, HEX_MESSAGE_SIGNATURE, HEX_MESSAGE, SCHEME_ED25519, flags), VALID);
+      assert_eq!(verify_scheme_with(ED25519_ADDRESS, HEX_MESSAGE_SIGNATURE, &HEX_MESSAGE[2..], SCHEME_ED25519, flags), VALID);
+      // Without the flag the ASCII hex string is verified
+      assert_eq!(verify_scheme_with(ED25519_ADDRESS, HEX_MESSAGE_SIGNATURE, HEX_MESSAGE, SCHEME_ED25519, 0), INVALID);
+      assert_eq!(verify_scheme_with(ED25519_ADDRESS, HEX_MESSAGE_WRAPPED_SIGNATURE, HEX_MESSAGE, SCHEME_ED25519, flags), INVALID);
+  }
+
+  #[test]
+  #[cfg(feature = "sp-core")]
+  fn test_message_is_hex_wrapped() {
+      let flags = OPT_MESSAGE_IS_HEX | OPT_WRAP_BYTES;
+      assert_eq!(verify_scheme_with(ED25519_ADDRESS, HEX_MESSAGE_WRAPPED_SIGNATURE, HEX_MESSAGE, SCHEME_ED25519, flags), VALID);
+      assert_eq!(verify_scheme_with(ED25519_ADDRESS, HEX_MESSAGE_SIGNATURE, HEX_MESSAGE, SCHEME_ED25519, flags), INVALID);
+  }
+
+  #[test]
+  fn test_message_is_hex_malformed() {
+      let flags = OPT_MESSAGE_IS_HEX;
+      assert_eq!(verify_scheme_with(ED25519_ADDRESS, HEX_MESSAGE_SIGNATURE, "0x9f3", SCHEME_ED25519, flags), ERR_BAD_MESSAGE_HEX);
+      assert_eq!(last_error(), "message hex had odd length");
+      assert_eq!(verify_scheme_with(ED25519_ADDRESS, HEX_MESSAGE_SIGNATURE, MESSAGE, SCHEME_ED25519, flags), ERR_BAD_MESSAGE_HEX);
+  }
+
+  #[test]
+  fn test_signature_base64() {
+      let flags = OPT_SIGNATURE_BASE64;
+      let standard = "+M4bgOdr9IowYQ0NPCycjb/csoqaqPVqwDjVIP8XRF/zzpSAn4R5oSJO8Ngj0DUCKp2y5yvWwNLwJE92beyQiw==";
+      let url_safe = "-M4bgOdr9IowYQ0NPCycjb_csoqaqPVqwDjVIP8XRF_zzpSAn4R5oSJO8Ngj0DUCKp2y5yvWwNLwJE92beyQiw";
+      assert_eq!(verify_scheme_with(ADDRESS, standard, MESSAGE, SCHEME_SR25519, flags), VALID);
+      assert_eq!(verify_scheme_with(ADDRESS, url_safe, MESSAGE, SCHEME_SR25519, flags), VALID);
+      assert_eq!(verify_scheme_with(ADDRESS, &standard[..standard.len() - 2], MESSAGE, SCHEME_SR25519, flags), VALID);
+
+      // The flag decides, hex is not accepted in base64 mode and vice versa
+      assert_eq!(verify_scheme_with(ADDRESS, SIGNATURE, MESSAGE, SCHEME_SR25519, flags), ERR_BAD_BASE64);
+      assert_eq!(verify_scheme_with(ADDRESS, standard, MESSAGE, SCHEME_SR25519, 0), ERR_BAD_HEX);
+  }
+
+  #[test]
+  fn test_signature_base64_bad_padding() {
+      let flags = OPT_SIGNATURE_BASE64;
+      let overpadded = "+M4bgOdr9IowYQ0NPCycjb/csoqaqPVqwDjVIP8XRF/zzpSAn4R5oSJO8Ngj0DUCKp2y5yvWwNLwJE92beyQiw===";
+      assert_eq!(verify_scheme_with(ADDRESS, overpadded, MESSAGE, SCHEME_SR25519, flags), ERR_BAD_BASE64);
+      let misplaced = "+M4bgOdr9IowYQ0NPCycjb/csoqaqPVqwDjVIP8XRF/zzpSAn4R5oSJO8Ngj0DUCKp2y5yvWwNLwJE92be==yQiw";
+      assert_eq!(verify_scheme_with(ADDRESS, misplaced, MESSAGE, SCHEME_SR25519, flags), ERR_BAD_BASE64);
+      assert!(last_error().starts_with("signature is not valid base64: "));
+  }
+
+  #[test]
+  #[cfg(feature = "sp-core")]
+  fn test_message_base64() {
+      // HEX_MESSAGE's bytes, base64 encoded
+      let flags = OPT_MESSAGE_BASE64 | OPT_SIGNATURE_BASE64;
+      let message = "nzwAobLD1OX2BxgpOktcbQ";
+      let signature = "I7OJWY6Dp6mRsFor9m3Lj26qrnXB3TV28elde_lD5o8B9k_KDY3qXwz-1C1xDQ-bOfDjhdGfxTjRUK25R9jqBw";
+      assert_eq!(verify_scheme_with(ED25519_ADDRESS, signature, message, SCHEME_ED25519, flags), VALID);
+      assert_eq!(verify_scheme_with(ED25519_ADDRESS, HEX_MESSAGE_SIGNATURE, "nzwAobLD1OX2BxgpOktcbQ==", SCHEME_ED25519, OPT_MESSAGE_BASE64), VALID);
+      assert_eq!(verify_scheme_with(ED25519_ADDRESS, HEX_MESSAGE_SIGNATURE, "nzwAobLD1OX2BxgpOktcbQ===", SCHEME_ED25519, OPT_MESSAGE_BASE64), ERR_BAD_BASE64);
+      assert!(last_error().starts_with("message is not valid base64: "));
+  }
+
+  #[test]
+  fn test_message_encodings_are_exclusive() {
+      let flags = OPT_MESSAGE_BASE64 | OPT_MESSAGE_IS_HEX;
+      assert_eq!(verify_scheme_with(ED25519_ADDRESS, HEX_MESSAGE_SIGNATURE, HEX_MESSAGE, SCHEME_ED25519, flags), ERR_INVALID_OPTIONS);
+  }
+
+  fn verify_auto(address: &str, signature: &str, message: &str) -> (i32, i32) {
+      let address = CString::new(address).unwrap();
+      let signature = CString::new(signature).unwrap();
+      let message = CString::new(message).unwrap();
+      let mut form = -1;
+      let code = verify_polkadot_signature_auto(address.as_ptr(), signature.as_ptr(), message.as_ptr(), &mut form);
+      (code, form)
+  }
+
+  #[test]
+  fn test_auto_as_given() {
+      assert_eq!(verify_auto(ADDRESS, SIGNATURE, MESSAGE), (VALID, MESSAGE_AS_GIVEN));
+  }
+
+  #[test]
+  fn test_auto_wraps() {
+      let unwrapped = "c15335d817e3d3d912d30f5a18a30c30162abc364cd0876d3a94af568d3c8c14";
+      assert_eq!(verify_auto(ADDRESS, SIGNATURE, unwrapped), (VALID, MESSAGE_WRAPPED));
+  }
+
+  #[test]
+  fn test_auto_unwraps() {
+      let double_wrapped = format!("<Bytes>{MESSAGE}</Bytes>");
+      assert_eq!(verify_auto(ADDRESS, SIGNATURE, &double_wrapped), (VALID, MESSAGE_UNWRAPPED));
+  }
+
+  #[test]
+  fn test_auto_no_match() {
+      assert_eq!(verify_auto(ADDRESS, SIGNATURE, "tampered"), (INVALID, MESSAGE_NONE));
+      assert_eq!(verify_auto(ADDRESS, "0x00", MESSAGE), (ERR_BAD_SIGNATURE_LENGTH, MESSAGE_NONE));
+  }
+
+  fn verify_strict(signature: &str, message: &str, flags: u32, strict: bool) -> i32 {
+      let address = CString::new(ADDRESS).unwrap();
+      let signature = CString::new(signature).unwrap();
+      let message = CString::new(message).unwrap();
+      let options = VerifyOptions { flags, strict: strict as u8, ..VerifyOptions::default() };
+      verify_polkadot_signature_with_options(address.as_ptr(), signature.as_ptr(), message.as_ptr(), &options)
+  }
+
+  #[test]
+  fn test_strict_needs_exact_message() {
+      let unwrapped = "c15335d817e3d3d912d30f5a18a30c30162abc364cd0876d3a94af568d3c8c14";
+      let signature = &SIGNATURE[2..];
+      // Lenient mode wraps on request, strict mode verifies the bytes given
+      assert_eq!(verify_strict(signature, unwrapped, OPT_WRAP_BYTES, false), VALID);
+      assert_eq!(verify_strict(signature, unwrapped, 0, true), INVALID);
+      assert_eq!(verify_strict(signature, unwrapped, OPT_WRAP_BYTES, true), ERR_INVALID_OPTIONS);
+      assert_eq!(verify_strict(signature, MESSAGE, 0, true), VALID);
+  }
+
+  #[test]
+  fn test_strict_rejects_hex_prefix() {
+      assert_eq!(verify_strict(SIGNATURE, MESSAGE, 0, false), VALID);
+      assert_eq!(verify_strict(SIGNATURE, MESSAGE, 0, true), ERR_NOT_STRICT);
+      assert_eq!(last_error(), "signature hex has a 0x prefix, which strict mode does not strip");
+  }
+
+  #[test]
+  fn test_strict_rejects_normalizing_flags() {
+      let signature = &SIGNATURE[2..];
+      for flags in [OPT_WRAP_BYTES, OPT_STRIP_SCHEME_PREFIX, OPT_ALLOW_NON_CANONICAL_ECDSA] {
+          assert_eq!(verify_strict(signature, MESSAGE, flags, true), ERR_INVALID_OPTIONS, "flags {flags:#x}");
+          assert_eq!(verify_strict(signature, MESSAGE, flags, false), VALID, "flags {flags:#x}");
+      }
+  }
+
+  /// Verify `message` against //Alice's sr25519 signature over `signed`.
+  fn verify_alice(message: &str, signed: &[u8], flags: u32) -> i32 {
+      use sp_core::{crypto::Ss58Codec, sr25519, Pair};
+
+      let pair = sr25519::Pair::from_string("//Alice", None).unwrap();
+      let address = CString::new(pair.public().to_ss58check()).unwrap();
+      let signature = CString::new(hex::encode(pair.sign(signed))).unwrap();
+      let message = CString::new(message).unwrap();
+      let options = VerifyOptions { flags, ..VerifyOptions::default() };
+      verify_polkadot_signature_with_options(address.as_ptr(), signature.as_ptr(), message.as_ptr(), &options)
+  }
+
+  #[test]
+  fn test_long_message_hash_boundary() {
+      use sp_core::hashing::blake2_256;
+
+      // 256 bytes are signed directly
+      let at_limit = "a".repeat(256);
+      assert_eq!(verify_alice(&at_limit, at_limit.as_bytes(), 0), VALID);
+      assert_eq!(verify_alice(&at_limit, &blake2_256(at_limit.as_bytes()), 0), INVALID);
+
+      // 257 bytes are signed as their hash
+      let over_limit = "a".repeat(257);
+      assert_eq!(verify_alice(&over_limit, &blake2_256(over_limit.as_bytes()), 0), VALID);
+      assert_eq!(verify_alice(&over_limit, over_limit.as_bytes(), 0), INVALID);
+  }
+
+  #[test]
+  fn test_long_message_hash_after_wrapping() {
+      use sp_core::hashing::blake2_256;
+
+      // 285 bytes grow to 300 once wrapped, like polkadot-js signRaw
+      let message = "x".repeat(285);
+      let wrapped = format!("<Bytes>{message}</Bytes>");
+      assert_eq!(wrapped.len(), 300);
+      assert_eq!(verify_alice(&message, &blake2_256(wrapped.as_bytes()), OPT_WRAP_BYTES), VALID);
+
+      // 250 bytes exceed the limit only after wrapping
+      let message = "y".repeat(250);
+      let wrapped = format!("<Bytes>{message}</Bytes>");
+      assert_eq!(verify_alice(&message, &blake2_256(wrapped.as_bytes()), OPT_WRAP_BYTES), VALID);
+  }
+
+  #[test]
+  fn test_skip_long_message_hash() {
+      use sp_core::hashing::blake2_256;
+
+      let message = "z".repeat(300);
+      assert_eq!(verify_alice(&message, message.as_bytes(), OPT_SKIP_LONG_MESSAGE_HASH), VALID);
+      assert_eq!(verify_alice(&message, &blake2_256(message.as_bytes()), OPT_SKIP_LONG_MESSAGE_HASH), INVALID);
+  }
+
+  #[test]
+  #[cfg(feature = "ecdsa")]
+  fn test_ethereum_style_ffi() {
+      let address = CString::new("0xf24ff3a9cf04c71dbc94d0b566f7a27b94566cac").unwrap();
+      let signature = CString::new("0xe32c94c88261e68cc12089bc7fd87f2e55ebc24343c69078357d0a6a890407d23d77547af130b710042f7fecfb325e0c1b8212cd707b50b44051ce607d55b38a1c").unwrap();
+      let message = CString::new("polkadot-signature-verifier login challenge").unwrap();
+      let code = verify_ethereum_style_signature(address.as_ptr(), signature.as_ptr(), message.as_ptr());
+      assert_eq!(code, VALID);
+
+      let code = verify_ethereum_style_signature(std::ptr::null(), signature.as_ptr(), message.as_ptr());
+      assert_eq!(code, ERR_NULL_POINTER);
+  }
+
+  #[test]
+  fn test_vrf_ffi() {
+      let address = CString::new(ADDRESS).unwrap();
+      let output = CString::new("00".repeat(32)).unwrap();
+      let proof = CString::new("00".repeat(64)).unwrap();
+      let input = CString::new("round 7").unwrap();
+      let label = CString::new("fair-draw").unwrap();
+
+      let mut randomness = [0xffu8; 32];
+      let code = verify_vrf(address.as_ptr(), output.as_ptr(), proof.as_ptr(), input.as_ptr(), label.as_ptr(), randomness.as_mut_ptr());
+      assert_eq!(code, INVALID);
+      assert_eq!(randomness, [0u8; 32]);
+
+      let code = verify_vrf(address.as_ptr(), output.as_ptr(), proof.as_ptr(), input.as_ptr(), std::ptr::null(), std::ptr::null_mut());
+      assert_eq!(code, ERR_NULL_POINTER);
+      assert_eq!(last_error(), "label pointer is NULL");
+  }
+
+  #[test]
+  fn test_get_abi_version() {
+      assert_eq!(get_abi_version(), ABI_VERSION);
+  }
+
+  #[test]
+  fn test_crate_version_numbers() {
+      let (mut major, mut minor, mut patch) = (u32::MAX, u32::MAX, u32::MAX);
+      get_crate_version_numbers(&mut major, &mut minor, &mut patch);
+      assert_eq!(format!("{major}.{minor}.{patch}"), env!("CARGO_PKG_VERSION"));
+
+      // NULL components are skipped
+      get_crate_version_numbers(std::ptr::null_mut(), &mut minor, std::ptr::null_mut());
+  }
+
+  #[test]
+  fn test_build_info() {
+      let info = get_build_info();
+      assert!(!info.is_null());
+      // Safety: get_build_info returns a NUL-terminated string
+      let json = unsafe { CStr::from_ptr(info) }.to_str().unwrap().to_owned();
+      free_string(info);
+
+      let info: serde_json::Value = serde_json::from_str(&json).unwrap();
+      assert_eq!(info["version"], env!("CARGO_PKG_VERSION"));
+      let features: Vec<&str> = info["features"].as_array().unwrap().iter().map(|f| f.as_str().unwrap()).collect();
+      assert_eq!(features.contains(&"ffi"), cfg!(feature = "ffi"));
+      assert_eq!(features.contains(&"minimal"), cfg!(feature = "minimal"));
+      assert_eq!(features.contains(&"sp-core"), cfg!(feature = "sp-core"));
+      assert!(!info["target"].as_str().unwrap().is_empty());
+      assert!(!info["commit"].as_str().unwrap().is_empty());
+  }
+
+  #[test]
+  fn test_version_matches_cargo() {
+      // Safety: get_version returns a static NUL-terminated string
+      let version = unsafe { CStr::from_ptr(get_version()) }.to_str().unwrap();
+      assert_eq!(version, env!("CARGO_PKG_VERSION"));
+      assert_eq!(get_version(), get_version(), "the string is static, not allocated per call");
+
+      let (mut major, mut minor, mut patch) = (u32::MAX, u32::MAX, u32::MAX);
+      assert_eq!(get_version_numbers(&mut major, &mut minor, &mut patch), VALID);
+      let parsed: Vec<u32> = version.split('.').map(|part| part.parse().unwrap()).collect();
+      assert_eq!(parsed, [major, minor, patch]);
+
+      let mut untouched = u32::MAX;
+      assert_eq!(get_version_numbers(&mut untouched, std::ptr::null_mut(), &mut patch), ERR_NULL_POINTER);
+      assert_eq!(last_error(), "minor pointer is NULL");
+      assert_eq!(untouched, u32::MAX);
+  }
+
+  #[test]
+  fn test_panic_becomes_error_code() {
+      let address = CString::new(ADDRESS).unwrap();
+      let signature = CString::new(SIGNATURE).unwrap();
+      let message = CString::new(MESSAGE).unwrap();
+
+      panic_on_next_call();
+      let code = verify_polkadot_signature_v2(address.as_ptr(), signature.as_ptr(), message.as_ptr());
+      assert_eq!(code, ERR_PANIC);
+      assert_eq!(last_error(), "internal panic: test hook panic");
+
+      panic_on_next_call();
+      assert_eq!(verify_polkadot_signature(address.as_ptr(), signature.as_ptr(), message.as_ptr()), 0);
+
+      panic_on_next_call();
+      let mut outcome = VerifyOutcome { valid: 1, ..VerifyOutcome::default() };
+      let code = verify_polkadot_signature_ex(address.as_ptr(), signature.as_ptr(), message.as_ptr(), &mut outcome);
+      assert_eq!(code, ERR_PANIC);
+      assert_eq!(outcome, VerifyOutcome { error_code: ERR_PANIC, ..VerifyOutcome::default() });
+
+      // The hook only fires once
+      let code = verify_polkadot_signature_v2(address.as_ptr(), signature.as_ptr(), message.as_ptr());
+      assert_eq!(code, VALID);
+  }
+
+  #[test]
+  fn test_v2_last_error_on_null_and_mismatch() {
+      let signature = CString::new(SIGNATURE).unwrap();
+      let message = CString::new(MESSAGE).unwrap();
+      verify_polkadot_signature_v2(std::ptr::null(), signature.as_ptr(), message.as_ptr());
+      assert_eq!(last_error(), "address pointer is NULL");
+
+      assert_eq!(verify_v2("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY", SIGNATURE, MESSAGE), INVALID);
+      assert_eq!(last_error(), "signature does not match address and message");
+
+      clear_last_error();
+      assert!(get_last_error().is_null());
+  }
+
+  fn wide(s: &str) -> Vec<u16> {
+      s.encode_utf16().chain([0]).collect()
+  }
+
+  #[test]
+  fn test_wide_non_ascii_message() {
+      use sp_core::{crypto::Ss58Codec, sr25519, Pair};
+
+      let pair = sr25519::Pair::from_string("//Alice", None).unwrap();
+      let address = wide(&pair.public().to_ss58check());
+      let message = "Sign in to 波卡 🔐 #42";
+      let signature = wide(&hex::encode(pair.sign(message.as_bytes())));
+
+      let code = verify_polkadot_signature_w(address.as_ptr(), signature.as_ptr(), wide(message).as_ptr());
+      assert_eq!(code, VALID);
+      let tampered = wide("Sign in to 波卡 🔓 #42");
+      assert_eq!(verify_polkadot_signature_w(address.as_ptr(), signature.as_ptr(), tampered.as_ptr()), INVALID);
+  }
+
+  #[test]
+  fn test_wide_lone_surrogate() {
+      let address = wide(ADDRESS);
+      let signature = wide(SIGNATURE);
+      let message = [0x61, 0xd83d, 0x62, 0];
+      assert_eq!(verify_polkadot_signature_w(address.as_ptr(), signature.as_ptr(), message.as_ptr()), ERR_INVALID_UTF16);
+      assert_eq!(last_error(), "message is not valid UTF-16: invalid utf-16: lone surrogate found");
+
+      assert_eq!(verify_polkadot_signature_w(address.as_ptr(), std::ptr::null(), message.as_ptr()), ERR_NULL_POINTER);
+  }
+
+  #[test]
+  fn test_nfc_normalization() {
+      // iOS composes "é" as one code point, the stored challenge decomposes it
+      let precomposed = "<Bytes>Sign in to caf\u{e9}.example</Bytes>";
+      let decomposed = "<Bytes>Sign in to cafe\u{301}.example</Bytes>";
+      assert_eq!(verify_alice(decomposed, precomposed.as_bytes(), 0), INVALID);
+      assert_eq!(verify_alice(decomposed, precomposed.as_bytes(), OPT_NFC_NORMALIZE), VALID);
+      assert_eq!(verify_alice(precomposed, precomposed.as_bytes(), OPT_NFC_NORMALIZE), VALID);
+
+      // Composes with wrapping: normalized inside, then wrapped
+      let unwrapped = &decomposed[7..decomposed.len() - 8];
+      assert_eq!(verify_alice(unwrapped, precomposed.as_bytes(), OPT_WRAP_BYTES), INVALID);
+      assert_eq!(verify_alice(unwrapped, precomposed.as_bytes(), OPT_WRAP_BYTES | OPT_NFC_NORMALIZE), VALID);
+  }
+
+  #[test]
+  fn test_nfc_normalization_strict() {
+      use sp_core::{crypto::Ss58Codec, sr25519, Pair};
+
+      let pair = sr25519::Pair::from_string("//Alice", None).unwrap();
+      let address = CString::new(pair.public().to_ss58check()).unwrap();
+      let precomposed = "caf\u{e9}";
+      let signature = CString::new(hex::encode(pair.sign(precomposed.as_bytes()))).unwrap();
+      let options = VerifyOptions { flags: OPT_NFC_NORMALIZE, strict: 1, ..VerifyOptions::default() };
+      let verify = |message: &str| {
+          let message = CString::new(message).unwrap();
+          verify_polkadot_signature_with_options(address.as_ptr(), signature.as_ptr(), message.as_ptr(), &options)
+      };
+
+      assert_eq!(verify(precomposed), VALID);
+      assert_eq!(verify("cafe\u{301}"), ERR_NOT_STRICT);
+      assert_eq!(last_error(), "message is not NFC-normalized, which strict mode does not fix");
+  }
+
+  const PUBLIC_KEY_HEX: &str = "f4ec883f28db136cd98e3b553cab48ab4671111978a4660f129a45cb4df84d30";
+
+  #[test]
+  fn test_hex_public_key_address() {
+      assert_eq!(verify_v2(PUBLIC_KEY_HEX, SIGNATURE, MESSAGE), VALID);
+      assert_eq!(verify_v2(&format!("0x{PUBLIC_KEY_HEX}"), SIGNATURE, MESSAGE), VALID);
+      assert_eq!(verify_v2(&PUBLIC_KEY_HEX.to_uppercase(), SIGNATURE, MESSAGE), VALID);
+
+      let mut outcome = VerifyOutcome::default();
+      let address = CString::new(PUBLIC_KEY_HEX).unwrap();
+      let signature = CString::new(SIGNATURE).unwrap();
+      let message = CString::new(MESSAGE).unwrap();
+      verify_polkadot_signature_ex(address.as_ptr(), signature.as_ptr(), message.as_ptr(), &mut outcome);
+      assert_eq!((hex::encode(outcome.public_key), outcome.ss58_prefix), (PUBLIC_KEY_HEX.to_owned(), 0));
+  }
+
+  #[test]
+  fn test_hex_public_key_errors() {
+      assert_eq!(verify_v2(&format!("0x{}", &PUBLIC_KEY_HEX[2..]), SIGNATURE, MESSAGE), ERR_BAD_PUBLIC_KEY_LENGTH);
+      assert_eq!(last_error(), "public key hex has 62 digits, expected 64");
+      assert_eq!(verify_v2(&format!("0x{PUBLIC_KEY_HEX}00"), SIGNATURE, MESSAGE), ERR_BAD_PUBLIC_KEY_LENGTH);
+      assert_eq!(verify_v2(&format!("0x{}", "zz".repeat(32)), SIGNATURE, MESSAGE), ERR_BAD_HEX);
+
+      // Without 0x only exactly 64 hex digits are a public key, anything
+      // else is decoded as SS58
+      assert_eq!(verify_v2(&PUBLIC_KEY_HEX[1..], SIGNATURE, MESSAGE), ERR_SS58_BAD_BASE58);
+
+      // A raw key has no network, so it never satisfies a prefix list
+      let address = CString::new(PUBLIC_KEY_HEX).unwrap();
+      let signature = CString::new(SIGNATURE).unwrap();
+      let message = CString::new(MESSAGE).unwrap();
+      let prefixes = [42u16];
+      let options = VerifyOptions {
+          allowed_prefixes: prefixes.as_ptr(),
+          allowed_prefixes_len: prefixes.len(),
+          ..VerifyOptions::default()
+      };
+      let code = verify_polkadot_signature_with_options(address.as_ptr(), signature.as_ptr(), message.as_ptr(), &options);
+      assert_eq!(code, ERR_WRONG_NETWORK);
+  }
+
+  fn test_vector_bytes() -> ([u8; 32], [u8; 64]) {
+      let account_id = hex::decode(PUBLIC_KEY_HEX).unwrap().try_into().unwrap();
+      let signature = hex::decode(&SIGNATURE[2..]).unwrap().try_into().unwrap();
+      (account_id, signature)
+  }
+
+  #[test]
+  fn test_verify_by_account_id() {
+      let (account_id, signature) = test_vector_bytes();
+      let message = MESSAGE.as_bytes();
+      assert_eq!(verify_by_account_id(account_id.as_ptr(), signature.as_ptr(), message.as_ptr(), message.len()), VALID);
+      assert_eq!(verify_by_account_id(account_id.as_ptr(), signature.as_ptr(), message.as_ptr(), message.len() - 1), INVALID);
+
+      assert_eq!(verify_by_account_id(std::ptr::null(), signature.as_ptr(), message.as_ptr(), message.len()), ERR_NULL_POINTER);
+      assert_eq!(last_error(), "account id pointer is NULL");
+      assert_eq!(verify_by_account_id(account_id.as_ptr(), std::ptr::null(), message.as_ptr(), message.len()), ERR_NULL_POINTER);
+      assert_eq!(verify_by_account_id(account_id.as_ptr(), signature.as_ptr(), std::ptr::null(), 1), ERR_NULL_POINTER);
+  }
+
+  fn ss58_prefix_of(address: &str) -> i32 {
+      let address = CString::new(address).unwrap();
+      get_ss58_prefix(address.as_ptr())
+  }
+
+  #[test]
+  fn test_get_ss58_prefix() {
+      assert_eq!(ss58_prefix_of("16Y8x28cS6LYFu3v8KViD2sKjTdNbBZf8sr6bUtaS7iuRDs5"), 0);
+      assert_eq!(ss58_prefix_of("J7TU1DRCg5za1rqwPFkxqQB2RuxhYphWkxMprBBMpusz4S5"), 2);
+      assert_eq!(ss58_prefix_of(ADDRESS), 42);
+  }
+
+  #[test]
+  fn test_get_ss58_prefix_ecdsa_public_key() {
+      use sp_core::{crypto::Ss58Codec, ecdsa, Pair};
+
+      // A 33-byte payload: the compressed public key itself
+      let public = ecdsa::Pair::from_string("//Alice", None).unwrap().public();
+      assert_eq!(ss58_prefix_of(&public.to_ss58check_with_version(2u16.into())), 2);
+      assert_eq!(ss58_prefix_of(&public.to_ss58check_with_version(1284u16.into())), 1284);
+  }
+
+  #[test]
+  fn test_get_ss58_prefix_errors() {
+      let corrupted = ADDRESS.replace("hPF2BR", "hPF2BS");
+      assert_eq!(ss58_prefix_of(&corrupted), ERR_SS58_BAD_CHECKSUM);
+      assert_eq!(ss58_prefix_of("5Hbq0"), ERR_SS58_BAD_BASE58);
+      assert_eq!(ss58_prefix_of("5HbqogsYaK54pN3Q"), ERR_SS58_BAD_LENGTH);
+      assert_eq!(get_ss58_prefix(std::ptr::null()), ERR_NULL_POINTER);
+  }
+
+  fn reencode(address: &str, target_prefix: u16) -> Option<String> {
+      let address = CString::new(address).unwrap();
+      let reencoded = reencode_ss58_address(address.as_ptr(), target_prefix);
+      if reencoded.is_null() {
+          return None;
+      }
+      let string = unsafe { CStr::from_ptr(reencoded) }.to_str().unwrap().to_owned();
+      free_string(reencoded);
+      Some(string)
+  }
+
+  #[test]
+  fn test_reencode_ss58_address() {
+      const POLKADOT: &str = "16Y8x28cS6LYFu3v8KViD2sKjTdNbBZf8sr6bUtaS7iuRDs5";
+      const KUSAMA: &str = "J7TU1DRCg5za1rqwPFkxqQB2RuxhYphWkxMprBBMpusz4S5";
+
+      assert_eq!(reencode(ADDRESS, 0).as_deref(), Some(POLKADOT));
+      assert_eq!(reencode(POLKADOT, 2).as_deref(), Some(KUSAMA));
+      assert_eq!(reencode(KUSAMA, 42).as_deref(), Some(ADDRESS));
+      assert_eq!(reencode(ADDRESS, 42).as_deref(), Some(ADDRESS));
+      assert_eq!(ss58_prefix_of(&reencode(ADDRESS, 16_383).unwrap()), 16_383);
+  }
+
+  #[test]
+  fn test_reencode_ss58_address_errors() {
+      clear_last_error();
+      assert_eq!(reencode(ADDRESS, 16_384), None);
+      let message = unsafe { CStr::from_ptr(get_last_error()) }.to_str().unwrap();
+      assert!(message.contains("16384"), "{message}");
+
+      assert_eq!(reencode(&ADDRESS.replace("hPF2BR", "hPF2BS"), 0), None);
+      assert!(reencode_ss58_address(std::ptr::null(), 0).is_null());
+  }
+
+  fn public_key_hex_of(address: &str) -> Option<String> {
+      let address = CString::new(address).unwrap();
+      let public_key = address_to_public_key_hex(address.as_ptr());
+      if public_key.is_null() {
+          return None;
+      }
+      let string = unsafe { CStr::from_ptr(public_key) }.to_str().unwrap().to_owned();
+      free_string(public_key);
+      Some(string)
+  }
+
+  #[test]
+  fn test_address_to_public_key_hex() {
+      use sp_core::crypto::{Ss58AddressFormat, Ss58Codec};
+
+      let expected = format!("0x{PUBLIC_KEY_HEX}");
+      assert_eq!(public_key_hex_of(ADDRESS), Some(expected.clone()));
+      assert_eq!(public_key_hex_of("16Y8x28cS6LYFu3v8KViD2sKjTdNbBZf8sr6bUtaS7iuRDs5"), Some(expected.clone()));
+      assert_eq!(public_key_hex_of(&PUBLIC_KEY_HEX.to_uppercase()), Some(expected.clone()));
+
+      // Round trip back to the address
+      let raw = <[u8; 32]>::try_from(hex::decode(&expected[2..]).unwrap()).unwrap();
+      let public = sp_core::sr25519::Public::from_raw(raw);
+      assert_eq!(public.to_ss58check_with_version(Ss58AddressFormat::custom(42)), ADDRESS);
+  }
+
+  #[test]
+  fn test_address_to_public_key_hex_ecdsa() {
+      use sp_core::{crypto::Ss58Codec, ecdsa, hashing::blake2_256, Pair};
+
+      let public = ecdsa::Pair::from_string("//Alice", None).unwrap().public();
+      let account_id = blake2_256(public.as_ref());
+      let expected = format!("0x{}", hex::encode(account_id));
+
+      let account_address = sp_core::sr25519::Public::from_raw(account_id).to_ss58check();
+      assert_eq!(public_key_hex_of(&account_address), Some(expected.clone()));
+      assert_eq!(public_key_hex_of(&public.to_ss58check()), Some(expected));
+  }
+
+  #[test]
+  fn test_address_to_public_key_hex_errors() {
+      assert_eq!(public_key_hex_of(&ADDRESS.replace("hPF2BR", "hPF2BS")), None);
+      let message = unsafe { CStr::from_ptr(get_last_error()) }.to_str().unwrap();
+      assert!(message.contains("checksum"), "{message}");
+      assert_eq!(public_key_hex_of("0x1234"), None);
+      assert!(address_to_public_key_hex(std::ptr::null()).is_null());
+  }
+
+  fn ss58_of(public_key_hex: &str, prefix: u16) -> Option<String> {
+      let public_key_hex = CString::new(public_key_hex).unwrap();
+      let address = public_key_to_ss58(public_key_hex.as_ptr(), prefix);
+      if address.is_null() {
+          return None;
+      }
+      let string = unsafe { CStr::from_ptr(address) }.to_str().unwrap().to_owned();
+      free_string(address);
+      Some(string)
+  }
+
+  #[test]
+  fn test_public_key_to_ss58_dev_keys() {
+      // Expected addresses from `subkey inspect //Alice` and `//Bob`
+      const ALICE: &str = "0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d";
+      const BOB: &str = "8eaf04151687736326c9fea17e25fc5287613693c912909cb226aa4794f26a48";
+
+      assert_eq!(ss58_of(ALICE, 42).as_deref(), Some("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"));
+      assert_eq!(ss58_of(ALICE, 0).as_deref(), Some("15oF4uVJwmo4TdGW7VfQxNLavjCXviqxT9S1MgbjMNHr6Sp5"));
+      assert_eq!(ss58_of(BOB, 42).as_deref(), Some("5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty"));
+      assert_eq!(ss58_of(BOB, 2).as_deref(), Some("FoQJpPyadYccjavVdTWxpxU7rUEaYhfLCPwXgkfD6Zat9QP"));
+  }
+
+  #[test]
+  fn test_public_key_to_ss58_round_trip() {
+      let address = ss58_of(PUBLIC_KEY_HEX, 42).unwrap();
+      assert_eq!(address, ADDRESS);
+      assert_eq!(public_key_hex_of(&address), Some(format!("0x{PUBLIC_KEY_HEX}")));
+
+      let kusama = "J7TU1DRCg5za1rqwPFkxqQB2RuxhYphWkxMprBBMpusz4S5";
+      assert_eq!(ss58_of(&public_key_hex_of(kusama).unwrap(), 2).as_deref(), Some(kusama));
+  }
+
+  #[test]
+  fn test_public_key_to_ss58_errors() {
+      let last_error = || unsafe { CStr::from_ptr(get_last_error()) }.to_str().unwrap().to_owned();
+
+      assert_eq!(ss58_of("0xzz", 42), None);
+      assert!(last_error().contains("invalid character"), "{}", last_error());
+      assert_eq!(ss58_of(&PUBLIC_KEY_HEX[..62], 42), None);
+      assert!(last_error().contains("31 bytes"), "{}", last_error());
+      assert_eq!(ss58_of(PUBLIC_KEY_HEX, 16_384), None);
+      assert!(last_error().contains("16384"), "{}", last_error());
+      assert!(public_key_to_ss58(std::ptr::null(), 42).is_null());
+  }
+
+  fn equal(a: &str, b: &str) -> i32 {
+      let a = CString::new(a).unwrap();
+      let b = CString::new(b).unwrap();
+      addresses_equal(a.as_ptr(), b.as_ptr())
+  }
+
+  #[test]
+  fn test_addresses_equal_across_prefixes() {
+      let polkadot = "16Y8x28cS6LYFu3v8KViD2sKjTdNbBZf8sr6bUtaS7iuRDs5";
+      let kusama = "J7TU1DRCg5za1rqwPFkxqQB2RuxhYphWkxMprBBMpusz4S5";
+      assert_eq!(equal(polkadot, kusama), 1);
+      assert_eq!(equal(kusama, ADDRESS), 1);
+      assert_eq!(equal(ADDRESS, ADDRESS), 1);
+      assert_eq!(equal(&format!("0x{PUBLIC_KEY_HEX}"), polkadot), 1);
+      assert_eq!(equal(ADDRESS, PUBLIC_KEY_HEX), 1);
+  }
+
+  #[test]
+  fn test_addresses_equal_different_keys() {
+      let alice = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+      let bob = "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty";
+      assert_eq!(equal(alice, bob), 0);
+      assert_eq!(equal(ADDRESS, alice), 0);
+  }
+
+  #[test]
+  fn test_addresses_equal_malformed_side() {
+      let corrupted = ADDRESS.replace("hPF2BR", "hPF2BS");
+      assert_eq!(equal(&corrupted, ADDRESS), ERR_BAD_FIRST_ADDRESS);
+      let message = unsafe { CStr::from_ptr(get_last_error()) }.to_str().unwrap();
+      assert!(message.starts_with("first address") && message.contains("checksum"), "{message}");
+
+      assert_eq!(equal(ADDRESS, &corrupted), ERR_BAD_SECOND_ADDRESS);
+      assert_eq!(equal(ADDRESS, "0x1234"), ERR_BAD_SECOND_ADDRESS);
+      assert_eq!(equal(&corrupted, "0x1234"), ERR_BAD_FIRST_ADDRESS);
+      assert_eq!(addresses_equal(std::ptr::null(), std::ptr::null()), ERR_NULL_POINTER);
+  }
+}
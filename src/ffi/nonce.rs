@@ -0,0 +1,120 @@
+//! Replay protection by remembering recently used nonces, from C.
+//!
+//! Handles are registry ids, like stream handles, so freed handles are
+//! reported rather than dereferenced.
+
+use crate::error::set_last_error;
+use crate::ffi::guard::ffi_guard;
+use crate::ffi::handles::HandleRegistry;
+use crate::nonce::{self, NonceCacheState};
+use crate::parse::str_from_ptr;
+use crate::verify::verdict;
+use crate::ERR_PANIC;
+use std::ffi::c_char;
+
+/// Opaque handle returned by `nonce_cache_new`.
+pub enum NonceCache {}
+
+static CACHES: HandleRegistry<NonceCacheState> = HandleRegistry::new("nonce cache");
+
+/// Create a cache of used nonces
+///
+/// # Arguments
+/// * `capacity` - Most (address, nonce) pairs remembered at once. When
+///   full, the oldest pair is forgotten early.
+/// * `ttl_secs` - How long a pair is remembered after it was used
+///
+/// # Returns
+/// A handle for `verify_with_nonce`, to be released with
+/// `nonce_cache_free`, or NULL with `ERR_INVALID_OPTIONS` as the last error
+/// if `capacity` is 0. The cache may be shared between threads.
+#[no_mangle]
+pub extern "C" fn nonce_cache_new(capacity: usize, ttl_secs: u64) -> *mut NonceCache {
+  ffi_guard(std::ptr::null_mut(), || match NonceCacheState::new(capacity, ttl_secs, Box::new(nonce::system_clock)) {
+    Ok(state) => CACHES.insert(state),
+    Err(error) => {
+      set_last_error(&error);
+      std::ptr::null_mut()
+    }
+  })
+}
+
+/// Verify a Polkadot SR25519 signature and reject replays of its nonce
+///
+/// Takes the same arguments as `verify_polkadot_signature`, plus:
+/// * `cache` - From `nonce_cache_new`
+/// * `nonce_ptr` - The nonce of the challenge. It must be part of the
+///   signed message, or a replay could simply claim a fresh nonce.
+///
+/// A valid signature records the (address, nonce) pair; checking for and
+/// recording the pair is atomic, so of two concurrent calls with the same
+/// pair only one succeeds.
+///
+/// # Returns
+/// The same codes as `verify_polkadot_signature_v2`, plus
+/// `ERR_REPLAYED` if the pair was recorded within the TTL and
+/// `ERR_INVALID_HANDLE` for NULL, unknown or freed caches.
+#[no_mangle]
+pub extern "C" fn verify_with_nonce(
+  cache: *mut NonceCache,
+  address_ptr: *const c_char,
+  signature_ptr: *const c_char,
+  message_ptr: *const c_char,
+  nonce_ptr: *const c_char,
+) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    let result = (|| {
+      let cache = CACHES.get(cache)?;
+      // Safety: Convert C strings to Rust strings
+      let address = unsafe { str_from_ptr(address_ptr, "address")? };
+      let signature = unsafe { str_from_ptr(signature_ptr, "signature")? };
+      let message = unsafe { str_from_ptr(message_ptr, "message")? };
+      let nonce = unsafe { str_from_ptr(nonce_ptr, "nonce")? };
+      nonce::verify_and_record(&cache, address, signature, message, nonce)
+    })();
+    verdict(result)
+  })
+}
+
+/// Release a nonce cache
+///
+/// NULL, unknown and already freed handles are ignored. Calls still
+/// running on other threads finish with the cache first.
+#[no_mangle]
+pub extern "C" fn nonce_cache_free(cache: *mut NonceCache) {
+  ffi_guard((), || {
+    let _ = CACHES.remove(cache);
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{ERR_INVALID_HANDLE, ERR_REPLAYED, VALID};
+  use sp_core::crypto::Ss58Codec;
+  use sp_core::{sr25519, Pair};
+  use std::ffi::CString;
+
+  fn verify(cache: *mut NonceCache, signer: &str, nonce: &str) -> i32 {
+    let pair = sr25519::Pair::from_string(signer, None).unwrap();
+    let message = format!("login:{nonce}");
+    let address = CString::new(pair.public().to_ss58check()).unwrap();
+    let signature = CString::new(hex::encode(pair.sign(message.as_bytes()))).unwrap();
+    let message = CString::new(message).unwrap();
+    let nonce = CString::new(nonce).unwrap();
+    verify_with_nonce(cache, address.as_ptr(), signature.as_ptr(), message.as_ptr(), nonce.as_ptr())
+  }
+
+  #[test]
+  fn test_handles() {
+    assert!(nonce_cache_new(0, 60).is_null());
+    let cache = nonce_cache_new(8, 60);
+    assert!(!cache.is_null());
+    assert_eq!(verify(cache, "//Alice", "n1"), VALID);
+    assert_eq!(verify(cache, "//Alice", "n1"), ERR_REPLAYED);
+    nonce_cache_free(cache);
+    assert_eq!(verify(cache, "//Alice", "n1"), ERR_INVALID_HANDLE);
+    assert_eq!(verify(std::ptr::null_mut(), "//Alice", "n1"), ERR_INVALID_HANDLE);
+    nonce_cache_free(cache);
+  }
+}
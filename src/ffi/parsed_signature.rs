@@ -0,0 +1,212 @@
+//! Signatures decoded once and verified against many messages or signers,
+//! from C.
+//!
+//! Handles are registry ids, as for verifiers, so a freed handle is
+//! reported rather than dereferenced. The decoded signature is read-only,
+//! so one handle can be shared between any number of threads.
+
+use crate::error::set_last_error;
+use crate::ffi::guard::ffi_guard;
+use crate::ffi::handles::HandleRegistry;
+use crate::log::{self, LOG_DEBUG};
+use crate::parse::{bytes_from_ptr, str_from_ptr};
+use crate::parsed_signature;
+use crate::verify::verdict;
+use crate::ERR_PANIC;
+use std::ffi::c_char;
+
+/// Opaque handle returned by `signature_parse`.
+pub enum ParsedSignature {}
+
+static SIGNATURES: HandleRegistry<[u8; 64]> = HandleRegistry::new("signature");
+
+/// Decode and check a signature once for repeated verification
+///
+/// # Arguments
+/// * `signature_ptr` - Hex-encoded SR25519 signature (with or without 0x
+///   prefix)
+///
+/// # Returns
+/// A handle for `signature_verify`, to be released with `signature_free`,
+/// or NULL with the last error set to the code `verify_polkadot_signature_v2`
+/// would give for the signature: `ERR_BAD_HEX`, `ERR_BAD_SIGNATURE_LENGTH`
+/// or `ERR_NON_CANONICAL_SIGNATURE`. The handle may be shared between
+/// threads.
+#[no_mangle]
+pub extern "C" fn signature_parse(signature_ptr: *const c_char) -> *mut ParsedSignature {
+  ffi_guard(std::ptr::null_mut(), || {
+    let result = (|| {
+      // Safety: Convert C strings to Rust strings
+      let signature = unsafe { str_from_ptr(signature_ptr, "signature")? };
+      parsed_signature::parse(signature)
+    })();
+    match result {
+      Ok(signature) => {
+        let handle = SIGNATURES.insert(signature);
+        log::log(LOG_DEBUG, || format!("created signature handle {handle:p}"));
+        handle
+      }
+      Err(error) => {
+        set_last_error(&error);
+        std::ptr::null_mut()
+      }
+    }
+  })
+}
+
+/// Verify a parsed Polkadot SR25519 signature over a message
+///
+/// Gives the same result as `verify_polkadot_signature_bytes` with the
+/// signature the handle was parsed from, without decoding it again.
+///
+/// # Arguments
+/// * `signature` - From `signature_parse`
+/// * `address_ptr` - SS58-encoded address, or the raw 32-byte public key
+///   as 64 hex digits, optionally 0x-prefixed
+/// * `message_ptr` - The signed bytes. May be NULL if `message_len` is 0.
+/// * `message_len` - Number of bytes at `message_ptr`
+///
+/// # Returns
+/// The same codes as `verify_polkadot_signature_v2`, except that the
+/// signature cannot fail to decode, plus `ERR_INVALID_HANDLE` for NULL,
+/// unknown or freed handles.
+#[no_mangle]
+pub extern "C" fn signature_verify(
+  signature: *mut ParsedSignature,
+  address_ptr: *const c_char,
+  message_ptr: *const u8,
+  message_len: usize,
+) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    let result = (|| {
+      let signature = SIGNATURES.get(signature)?;
+      // Safety: Convert C strings to Rust strings
+      let address = unsafe { str_from_ptr(address_ptr, "address")? };
+      // Safety: the caller passes `message_len` readable bytes or NULL
+      let message = unsafe { bytes_from_ptr(message_ptr, message_len, "message")? };
+      parsed_signature::verify(&signature, address, message)
+    })();
+    verdict(result)
+  })
+}
+
+/// Release a parsed signature
+///
+/// NULL, unknown and already freed handles are ignored.
+#[no_mangle]
+pub extern "C" fn signature_free(signature: *mut ParsedSignature) {
+  ffi_guard((), || {
+    let _ = SIGNATURES.remove(signature);
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::ffi::verify_polkadot_signature_bytes;
+  use crate::{
+    ERR_BAD_HEX, ERR_BAD_SIGNATURE_LENGTH, ERR_INVALID_HANDLE, ERR_NULL_POINTER, ERR_SS58_BAD_CHECKSUM,
+    INVALID, VALID,
+  };
+  use sp_core::crypto::Ss58Codec;
+  use sp_core::{ecdsa, sr25519, Pair};
+  use std::ffi::CString;
+
+  const ADDRESS: &str = "5HbqogsYaK54pN3QAgSi4t3Asqditt1X4P7cSBuDt2hPF2BR";
+  const SIGNATURE: &str = "0xf8ce1b80e76bf48a30610d0d3c2c9c8dbfdcb28a9aa8f56ac038d520ff17445ff3ce94809f8479a1224ef0d823d035022a9db2e72bd6c0d2f0244f766dec908b";
+  const MESSAGE: &str = "<Bytes>c15335d817e3d3d912d30f5a18a30c30162abc364cd0876d3a94af568d3c8c14</Bytes>";
+
+  fn parse(signature: &str) -> *mut ParsedSignature {
+    let signature = CString::new(signature).unwrap();
+    signature_parse(signature.as_ptr())
+  }
+
+  fn check(signature: *mut ParsedSignature, address: &str, message: &[u8]) -> i32 {
+    let address = CString::new(address).unwrap();
+    signature_verify(signature, address.as_ptr(), message.as_ptr(), message.len())
+  }
+
+  fn check_one_shot(address: &str, signature: &str, message: &[u8]) -> i32 {
+    let (address, signature) = (CString::new(address).unwrap(), CString::new(signature).unwrap());
+    verify_polkadot_signature_bytes(address.as_ptr(), signature.as_ptr(), message.as_ptr(), message.len())
+  }
+
+  #[test]
+  fn test_matches_one_shot_path() {
+    let pair = sr25519::Pair::from_string("//Alice", None).unwrap();
+    let alice = pair.public().to_ss58check();
+    let alice_hex = format!("0x{}", hex::encode(pair.public()));
+    let alice_signature = format!("0x{}", hex::encode(pair.sign(b"webhook body")));
+    let long = [7u8; 300];
+    let long_signature = format!("0x{}", hex::encode(pair.sign(&sp_core::hashing::blake2_256(&long))));
+    let mut corrupted = alice.clone();
+    corrupted.replace_range(10..11, if &alice[10..11] == "a" { "b" } else { "a" });
+    let ecdsa = ecdsa::Pair::from_string("//Alice", None).unwrap().public().to_ss58check();
+
+    let vectors: [(&str, &str, &[u8]); 10] = [
+      (ADDRESS, SIGNATURE, MESSAGE.as_bytes()),
+      (ADDRESS, &SIGNATURE[2..], MESSAGE.as_bytes()),
+      (ADDRESS, SIGNATURE, b"retried body"),
+      (&alice, &alice_signature, b"webhook body"),
+      (&alice_hex, &alice_signature, b"webhook body"),
+      (&alice, &alice_signature, b"webhook body, retried"),
+      (ADDRESS, &alice_signature, b"webhook body"),
+      (&alice, &long_signature, &long),
+      (&corrupted, &alice_signature, b"webhook body"),
+      (&ecdsa, &alice_signature, b"webhook body"),
+    ];
+    for (address, signature, message) in vectors {
+      let handle = parse(signature);
+      assert!(!handle.is_null());
+      assert_eq!(check(handle, address, message), check_one_shot(address, signature, message), "{address}");
+      signature_free(handle);
+    }
+
+    let handle = parse(&alice_signature);
+    assert_eq!(check(handle, &alice, b"webhook body"), VALID);
+    assert_eq!(check(handle, &alice, b"webhook body, retried"), INVALID);
+    assert_eq!(check(handle, &corrupted, b"webhook body"), ERR_SS58_BAD_CHECKSUM);
+    assert_eq!(signature_verify(handle, std::ptr::null(), std::ptr::null(), 0), ERR_NULL_POINTER);
+    signature_free(handle);
+
+    assert_eq!(check(handle, &alice, b"webhook body"), ERR_INVALID_HANDLE);
+    assert_eq!(check(std::ptr::null_mut(), &alice, b"webhook body"), ERR_INVALID_HANDLE);
+    // Freeing twice, or NULL, is harmless
+    signature_free(handle);
+    signature_free(std::ptr::null_mut());
+  }
+
+  #[test]
+  fn test_formatting_fails_at_parse() {
+    let code = |signature: &str| {
+      assert!(parse(signature).is_null());
+      check_one_shot(ADDRESS, signature, MESSAGE.as_bytes())
+    };
+    assert_eq!(code("0xzz"), ERR_BAD_HEX);
+    assert_eq!(code(&SIGNATURE[..SIGNATURE.len() - 2]), ERR_BAD_SIGNATURE_LENGTH);
+    assert_eq!(code(&format!("{SIGNATURE}00")), ERR_BAD_SIGNATURE_LENGTH);
+    assert!(signature_parse(std::ptr::null()).is_null());
+    let unknown = SIGNATURES.get(std::ptr::null_mut::<ParsedSignature>());
+    assert_eq!(unknown.err().map(|error| error.code), Some(ERR_INVALID_HANDLE));
+  }
+
+  #[test]
+  fn test_shared_across_threads() {
+    let pair = sr25519::Pair::from_string("//Bob", None).unwrap();
+    let address = pair.public().to_ss58check();
+    let handle = parse(&format!("0x{}", hex::encode(pair.sign(b"shared")))) as usize;
+    std::thread::scope(|scope| {
+      for _ in 0..4 {
+        let address = &address;
+        scope.spawn(move || {
+          for i in 0..100 {
+            let expected = if i % 2 == 0 { VALID } else { INVALID };
+            let message: &[u8] = if i % 2 == 0 { b"shared" } else { b"other" };
+            assert_eq!(check(handle as *mut ParsedSignature, address, message), expected);
+          }
+        });
+      }
+    });
+    signature_free(handle as *mut ParsedSignature);
+  }
+}
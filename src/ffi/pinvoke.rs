@@ -0,0 +1,255 @@
+//! Exports for .NET P/Invoke and other Windows hosts.
+//!
+//! These use the `system` calling convention: `__stdcall` on 32-bit
+//! Windows, which P/Invoke assumes unless told otherwise, and the only
+//! convention there is on x64. Messages are copied into buffers the caller
+//! owns, as UTF-16, so nothing needs releasing with `free_string`.
+
+use crate::error::{with_last_error, Error};
+use crate::ffi::guard::ffi_guard;
+use crate::ffi::{from_api, verify_c_bytes};
+use crate::options::Options;
+use crate::parse::{bytes_from_ptr, string_from_wide};
+use crate::verify::verdict;
+use crate::{api, ERR_PANIC, VALID};
+use std::ffi::c_char;
+
+/// Copy `message` into `buf` as a NUL-terminated UTF-16 string, cut short
+/// to `buf_len` units including the NUL if it does not fit. A surrogate
+/// pair is never split. Nothing is written if `buf` is NULL or `buf_len`
+/// is 0.
+///
+/// Returns the length of the whole message in UTF-16 units, without the
+/// NUL.
+///
+/// Safety: `buf` is NULL or has `buf_len` writable units.
+unsafe fn write_wide(message: &str, buf: *mut u16, buf_len: usize) -> usize {
+  let units: Vec<u16> = message.encode_utf16().collect();
+  if !buf.is_null() && buf_len > 0 {
+    let mut len = units.len().min(buf_len - 1);
+    if len < units.len() && len > 0 && (0xd800..0xdc00).contains(&units[len - 1]) {
+      len -= 1;
+    }
+    std::ptr::copy_nonoverlapping(units.as_ptr(), buf, len);
+    buf.add(len).write(0);
+  }
+  units.len()
+}
+
+fn check_buffer(buf: *mut u16, buf_len: usize) -> Result<(), Error> {
+  if buf.is_null() && buf_len > 0 {
+    let message = format!("error buffer pointer is NULL but its length is {buf_len}");
+    return Err(Error { message, ..api::VerifyError::NullPointer { name: "error buffer".to_owned() }.into() });
+  }
+  Ok(())
+}
+
+/// Return `code`, with the last error copied into the error buffer unless
+/// it is `VALID`.
+fn into_buffer(code: i32, err_buf: *mut u16, err_buf_len: usize) -> i32 {
+  // Safety: the caller passes `err_buf_len` writable units or NULL
+  if code == VALID {
+    unsafe { write_wide("", err_buf, err_buf_len) };
+  } else {
+    with_last_error(|message| unsafe { write_wide(message.unwrap_or(""), err_buf, err_buf_len) });
+  }
+  code
+}
+
+/// Verify a Polkadot SR25519 signature over binary data, describing any
+/// failure in a caller-provided buffer
+///
+/// # Arguments
+/// * `address_ptr` - SS58-encoded address, as UTF-8
+/// * `signature_ptr` - Hex-encoded signature (with or without 0x prefix),
+///   as UTF-8
+/// * `message_ptr` - The signed bytes. May be NULL if `message_len` is 0.
+/// * `message_len` - Number of bytes at `message_ptr`
+/// * `err_buf` - Receives the message `get_last_error` gives for the
+///   result as a NUL-terminated UTF-16 string, or an empty string if the
+///   signature is valid. May be NULL if `err_buf_len` is 0.
+/// * `err_buf_len` - Size of `err_buf` in UTF-16 units, including the NUL.
+///   A message that does not fit is cut short at a character boundary;
+///   `get_last_error_w` then gives the whole of it.
+///
+/// # Returns
+/// The same codes as `verify_polkadot_signature_bytes`, or
+/// `ERR_NULL_POINTER` if `err_buf` is NULL while `err_buf_len` is not 0.
+#[no_mangle]
+pub extern "system" fn verify_into_buffer(
+  address_ptr: *const c_char,
+  signature_ptr: *const c_char,
+  message_ptr: *const u8,
+  message_len: usize,
+  err_buf: *mut u16,
+  err_buf_len: usize,
+) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    let result = check_buffer(err_buf, err_buf_len).and_then(|()| {
+      let verify = from_api(api::verify);
+      verify_c_bytes(verify, address_ptr, signature_ptr, message_ptr, message_len, &Options::default())
+    });
+    into_buffer(verdict(result), err_buf, err_buf_len)
+  })
+}
+
+/// Verify a Polkadot SR25519 signature given UTF-16 strings, describing
+/// any failure in a caller-provided buffer
+///
+/// Takes the same arguments as `verify_into_buffer`, except that the
+/// address and signature are NUL-terminated UTF-16 strings.
+///
+/// # Returns
+/// The same codes as `verify_into_buffer`, plus `ERR_INVALID_UTF16` if the
+/// address or signature contains a lone surrogate.
+#[no_mangle]
+pub extern "system" fn verify_into_buffer_w(
+  address_ptr: *const u16,
+  signature_ptr: *const u16,
+  message_ptr: *const u8,
+  message_len: usize,
+  err_buf: *mut u16,
+  err_buf_len: usize,
+) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    let result = check_buffer(err_buf, err_buf_len).and_then(|()| {
+      // Safety: Convert UTF-16 strings to Rust strings
+      let address = unsafe { string_from_wide(address_ptr, "address")? };
+      let signature_hex = unsafe { string_from_wide(signature_ptr, "signature")? };
+      // Safety: the caller passes `message_len` readable bytes or NULL
+      let message = unsafe { bytes_from_ptr(message_ptr, message_len, "message")? };
+      api::verdict_of(api::verify(&address, &signature_hex, message))
+    });
+    into_buffer(verdict(result), err_buf, err_buf_len)
+  })
+}
+
+/// Copy the most recent failure on the calling thread into a
+/// caller-provided buffer
+///
+/// # Arguments
+/// * `buf` - Receives the message as a NUL-terminated UTF-16 string, cut
+///   short at a character boundary if it does not fit. May be NULL if
+///   `buf_len` is 0, to ask for the length.
+/// * `buf_len` - Size of `buf` in UTF-16 units, including the NUL
+///
+/// # Returns
+/// The length of the whole message in UTF-16 units, without the NUL, or 0
+/// if nothing failed since the last `clear_last_error`. A result of
+/// `buf_len` or more means the message was cut short. Never changes the
+/// last error itself, so a NULL `buf` with a nonzero `buf_len` is ignored.
+#[no_mangle]
+pub extern "system" fn get_last_error_w(buf: *mut u16, buf_len: usize) -> usize {
+  // Safety: the caller passes `buf_len` writable units or NULL
+  ffi_guard(0, || with_last_error(|message| message.map_or(0, |message| unsafe { write_wide(message, buf, buf_len) })))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::ffi::error::clear_last_error;
+  use crate::{ERR_BAD_SIGNATURE_LENGTH, ERR_INVALID_UTF16, ERR_NULL_POINTER, INVALID};
+  use std::ffi::CString;
+
+  const ADDRESS: &str = "5HbqogsYaK54pN3QAgSi4t3Asqditt1X4P7cSBuDt2hPF2BR";
+  const SIGNATURE: &str = "0xf8ce1b80e76bf48a30610d0d3c2c9c8dbfdcb28a9aa8f56ac038d520ff17445ff3ce94809f8479a1224ef0d823d035022a9db2e72bd6c0d2f0244f766dec908b";
+  const MESSAGE: &str = "<Bytes>c15335d817e3d3d912d30f5a18a30c30162abc364cd0876d3a94af568d3c8c14</Bytes>";
+  const MISMATCH: &str = "signature does not match address and message";
+
+  fn wide(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain([0]).collect()
+  }
+
+  /// The NUL-terminated string at the start of `buf`.
+  fn unwide(buf: &[u16]) -> String {
+    let len = buf.iter().position(|&unit| unit == 0).expect("buffer is NUL-terminated");
+    String::from_utf16(&buf[..len]).unwrap()
+  }
+
+  fn verify(signature: &str, message: &[u8], err_buf: &mut [u16]) -> i32 {
+    let address = CString::new(ADDRESS).unwrap();
+    let signature = CString::new(signature).unwrap();
+    let (buf, len) = (err_buf.as_mut_ptr(), err_buf.len());
+    verify_into_buffer(address.as_ptr(), signature.as_ptr(), message.as_ptr(), message.len(), buf, len)
+  }
+
+  #[test]
+  fn test_verify_into_buffer() {
+    let mut err_buf = [0xffff; 128];
+    assert_eq!(verify(SIGNATURE, MESSAGE.as_bytes(), &mut err_buf), VALID);
+    assert_eq!(unwide(&err_buf), "");
+
+    assert_eq!(verify(SIGNATURE, b"tampered", &mut err_buf), INVALID);
+    assert_eq!(unwide(&err_buf), MISMATCH);
+
+    assert_eq!(verify(&SIGNATURE[..128], MESSAGE.as_bytes(), &mut err_buf), ERR_BAD_SIGNATURE_LENGTH);
+    assert_eq!(unwide(&err_buf), "signature is 63 bytes, expected 64");
+
+    // No buffer at all is fine, but a NULL one with a length is not
+    assert_eq!(verify(SIGNATURE, MESSAGE.as_bytes(), &mut []), VALID);
+    let address = CString::new(ADDRESS).unwrap();
+    let signature = CString::new(SIGNATURE).unwrap();
+    let null = std::ptr::null_mut();
+    let code = verify_into_buffer(address.as_ptr(), signature.as_ptr(), MESSAGE.as_ptr(), MESSAGE.len(), null, 16);
+    assert_eq!(code, ERR_NULL_POINTER);
+  }
+
+  #[test]
+  fn test_error_buffer_too_small() {
+    let mut err_buf = [0xffff; 10];
+    assert_eq!(verify(SIGNATURE, b"tampered", &mut err_buf), INVALID);
+    assert_eq!(unwide(&err_buf), &MISMATCH[..9]);
+
+    // The whole message is still there for a second, larger buffer
+    let len = get_last_error_w(std::ptr::null_mut(), 0);
+    assert_eq!(len, MISMATCH.len());
+    let mut buf = vec![0xffff; len + 1];
+    assert_eq!(get_last_error_w(buf.as_mut_ptr(), buf.len()), len);
+    assert_eq!(unwide(&buf), MISMATCH);
+
+    // A single unit only has room for the NUL
+    let mut err_buf = [0xffff; 1];
+    assert_eq!(verify(SIGNATURE, b"tampered", &mut err_buf), INVALID);
+    assert_eq!(err_buf, [0]);
+  }
+
+  #[test]
+  fn test_surrogate_pair_not_split() {
+    let mut buf = [0xffff; 4];
+    // Two units, then a pair that only half fits
+    assert_eq!(unsafe { write_wide("ab🔓", buf.as_mut_ptr(), buf.len()) }, 4);
+    assert_eq!(unwide(&buf), "ab");
+    let mut buf = [0xffff; 5];
+    assert_eq!(unsafe { write_wide("ab🔓", buf.as_mut_ptr(), buf.len()) }, 4);
+    assert_eq!(unwide(&buf), "ab🔓");
+  }
+
+  #[test]
+  fn test_verify_into_buffer_w() {
+    let address = wide(ADDRESS);
+    let mut err_buf = [0xffff; 128];
+    let mut verify = |signature: &[u16], message: &[u8]| {
+      let (buf, len) = (err_buf.as_mut_ptr(), err_buf.len());
+      let code =
+        verify_into_buffer_w(address.as_ptr(), signature.as_ptr(), message.as_ptr(), message.len(), buf, len);
+      (code, unwide(&err_buf))
+    };
+
+    assert_eq!(verify(&wide(SIGNATURE), MESSAGE.as_bytes()), (VALID, String::new()));
+    assert_eq!(verify(&wide(SIGNATURE), b"tampered"), (INVALID, MISMATCH.to_owned()));
+
+    let mut lone = wide(SIGNATURE);
+    lone[2] = 0xd800;
+    let (code, message) = verify(&lone, MESSAGE.as_bytes());
+    assert_eq!(code, ERR_INVALID_UTF16);
+    assert!(message.starts_with("signature is not valid UTF-16"));
+  }
+
+  #[test]
+  fn test_get_last_error_w_empty() {
+    clear_last_error();
+    let mut buf = [0xffff; 4];
+    assert_eq!(get_last_error_w(buf.as_mut_ptr(), buf.len()), 0);
+    assert_eq!(buf, [0xffff; 4]);
+  }
+}
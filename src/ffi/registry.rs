@@ -0,0 +1,62 @@
+//! Network names for SS58 prefixes, from C.
+
+use crate::ffi::guard::ffi_guard;
+use crate::ffi::string_or_null;
+use crate::registry;
+use std::ffi::c_char;
+
+const UNKNOWN: &str = "unknown\0";
+
+/// Name the network an SS58 prefix belongs to
+///
+/// # Returns
+/// A static, NUL-terminated registry name such as `polkadot`, `kusama` or
+/// `substrate`. Never NULL: prefixes missing from the compiled-in registry
+/// snapshot map to `unknown`. The pointer must not be freed.
+#[no_mangle]
+pub extern "C" fn ss58_network_name(prefix: u16) -> *const c_char {
+  ffi_guard(UNKNOWN, || registry::lookup(prefix).map_or(UNKNOWN, |network| network.network)).as_ptr() as *const c_char
+}
+
+/// Describe every network in the compiled-in registry snapshot
+///
+/// # Returns
+/// A JSON array ordered by prefix, such as
+/// `[{"prefix": 0, "network": "polkadot", "displayName": "Polkadot Relay
+/// Chain", "symbol": "DOT", "decimals": 10}, ...]`, with a `null` symbol
+/// for networks without a token. Release it with `free_string`. NULL is
+/// only returned if the library panicked.
+#[no_mangle]
+pub extern "C" fn ss58_registry_json() -> *mut c_char {
+  ffi_guard(std::ptr::null_mut(), || {
+    string_or_null(Ok(serde_json::to_string(registry::NETWORKS).expect("registry entries serialize")))
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::ffi::json::free_string;
+  use serde_json::Value;
+  use std::ffi::CStr;
+
+  fn name(prefix: u16) -> &'static str {
+    unsafe { CStr::from_ptr(ss58_network_name(prefix)) }.to_str().unwrap()
+  }
+
+  #[test]
+  fn test_network_name() {
+    assert_eq!(name(0), "polkadot");
+    assert_eq!(name(1284), "moonbeam");
+    assert_eq!(name(9999), "unknown");
+    assert_eq!(ss58_network_name(9999), ss58_network_name(16_383));
+  }
+
+  #[test]
+  fn test_registry_json() {
+    let json = ss58_registry_json();
+    let table: Value = serde_json::from_str(unsafe { CStr::from_ptr(json) }.to_str().unwrap()).unwrap();
+    free_string(json);
+    assert_eq!(table, serde_json::to_value(registry::NETWORKS).unwrap());
+  }
+}
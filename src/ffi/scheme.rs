@@ -0,0 +1,106 @@
+//! Names and numbers of the supported signature schemes, from C.
+
+use crate::error::set_last_error;
+use crate::ffi::guard::ffi_guard;
+use crate::parse::str_from_ptr;
+use crate::scheme::SignatureScheme;
+use crate::{ERR_PANIC, INVALID, VALID};
+use std::ffi::c_char;
+
+/// Look up a scheme by name
+///
+/// # Arguments
+/// * `name` - Scheme name such as "sr25519" or "ECDSA", case-insensitive
+///
+/// # Returns
+/// * The `SCHEME_*` constant of the scheme
+/// * `ERR_UNKNOWN_SCHEME` if no scheme has that name
+/// * `ERR_NULL_POINTER` or `ERR_INVALID_UTF8` for bad arguments
+#[no_mangle]
+pub extern "C" fn parse_scheme(name: *const c_char) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    let result = unsafe { str_from_ptr(name, "name") }.and_then(SignatureScheme::parse);
+    match result {
+      Ok(scheme) => scheme as i32,
+      Err(error) => {
+        set_last_error(&error);
+        error.code
+      }
+    }
+  })
+}
+
+/// Name a scheme
+///
+/// # Returns
+/// A static, NUL-terminated lowercase name that `parse_scheme` accepts, or
+/// NULL if `scheme` is not a `SCHEME_*` constant. The pointer must not be
+/// freed.
+#[no_mangle]
+pub extern "C" fn scheme_to_string(scheme: i32) -> *const c_char {
+  ffi_guard(std::ptr::null(), || match SignatureScheme::from_i32(scheme) {
+    Some(scheme) => scheme.name_with_nul().as_ptr() as *const c_char,
+    None => std::ptr::null(),
+  })
+}
+
+/// List the schemes this build verifies
+///
+/// # Returns
+/// The bits `1 << SCHEME_*` of every supported scheme, see
+/// `SignatureScheme`. Which schemes are supported depends on the cargo
+/// features the library was built with.
+#[no_mangle]
+pub extern "C" fn get_supported_schemes() -> u32 {
+  ffi_guard(0, SignatureScheme::supported_mask)
+}
+
+/// Check whether this build verifies a scheme
+///
+/// # Returns
+/// * `VALID` (1) if `scheme` is supported
+/// * `INVALID` (0) if it was left out of this build
+/// * `ERR_UNKNOWN_SCHEME` if `scheme` is not a `SCHEME_*` constant
+#[no_mangle]
+pub extern "C" fn scheme_supported(scheme: i32) -> i32 {
+  ffi_guard(ERR_PANIC, || match SignatureScheme::known(scheme) {
+    Ok(scheme) if scheme.is_supported() => VALID,
+    Ok(_) => INVALID,
+    Err(error) => {
+      set_last_error(&error);
+      error.code
+    }
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{ERR_NULL_POINTER, ERR_UNKNOWN_SCHEME};
+  use std::ffi::{CStr, CString};
+
+  #[test]
+  fn test_round_trip() {
+    for scheme in SignatureScheme::ALL {
+      let name = unsafe { CStr::from_ptr(scheme_to_string(scheme as i32)) }.to_str().unwrap();
+      assert_eq!(name, scheme.name());
+      let upper = CString::new(name.to_ascii_uppercase()).unwrap();
+      assert_eq!(parse_scheme(upper.as_ptr()), scheme as i32);
+    }
+    let unknown = CString::new("secp256k1").unwrap();
+    assert_eq!(parse_scheme(unknown.as_ptr()), ERR_UNKNOWN_SCHEME);
+    assert_eq!(parse_scheme(std::ptr::null()), ERR_NULL_POINTER);
+    assert!(scheme_to_string(0).is_null());
+    assert!(scheme_to_string(-1).is_null());
+  }
+
+  #[test]
+  fn test_supported_schemes() {
+    assert_eq!(get_supported_schemes(), SignatureScheme::supported_mask());
+    for scheme in SignatureScheme::ALL {
+      assert_eq!(scheme_supported(scheme as i32), scheme.is_supported() as i32);
+    }
+    assert_eq!(scheme_supported(0), ERR_UNKNOWN_SCHEME);
+    assert_eq!(scheme_supported(5), ERR_UNKNOWN_SCHEME);
+  }
+}
@@ -0,0 +1,827 @@
+//! Key generation, signing and address derivation, from C.
+//!
+//! Secrets handed to the caller are written straight into the string they
+//! receive, so no copy is left behind; `free_string` zeroes it again.
+//! Secrets read from C strings are borrowed, never copied.
+
+use crate::api::VerifyError;
+use crate::error::set_last_error;
+use crate::ffi::guard::ffi_guard;
+use crate::ffi::string_or_null;
+use crate::parse::str_from_ptr;
+use crate::scheme::SignatureScheme;
+use crate::sign;
+use crate::ss58;
+use crate::{ERR_NULL_POINTER, ERR_PANIC, VALID};
+use bip39::Mnemonic;
+use sp_core::{ecdsa, ed25519, sr25519, Pair};
+use std::ffi::{CString, c_char};
+use std::fmt::Write;
+use zeroize::{Zeroize, Zeroizing};
+
+/// Hand `string` to the caller, who releases it with `free_string`.
+pub(crate) fn into_c_string(string: String) -> *mut c_char {
+  CString::new(string).expect("hex and SS58 have no NUL").into_raw()
+}
+
+/// Hand the 0x-prefixed hex of `secret` to the caller, leaving no copy
+/// behind: the string is written in place with room for the NUL, so
+/// `CString` takes it over without reallocating.
+fn secret_hex_c_string(secret: &[u8]) -> *mut c_char {
+  let mut hex = Zeroizing::new(String::with_capacity(2 + 2 * secret.len() + 1));
+  hex.push_str("0x");
+  for byte in secret {
+    write!(hex, "{byte:02x}").expect("writing to a String cannot fail");
+  }
+  into_c_string(std::mem::take(&mut *hex))
+}
+
+/// Hand the words of `mnemonic` to the caller, leaving no copy behind, as
+/// `secret_hex_c_string` does.
+fn mnemonic_c_string(mnemonic: &Mnemonic) -> *mut c_char {
+  // One separator or NUL after each word
+  let mut phrase = Zeroizing::new(String::with_capacity(mnemonic.words().map(|word| word.len() + 1).sum()));
+  for word in mnemonic.words() {
+    if !phrase.is_empty() {
+      phrase.push(' ');
+    }
+    phrase.push_str(word);
+  }
+  into_c_string(std::mem::take(&mut *phrase))
+}
+
+/// Body of the `sign_*` exports: sign with the `P` key of a hex seed,
+/// over the message as given or canonicalized like a wallet would.
+fn sign_with_seed<P: Pair>(
+  seed_hex_ptr: *const c_char,
+  message_ptr: *const c_char,
+  out_signature_hex: *mut *mut c_char,
+  wrapped: bool,
+) -> i32 {
+  if out_signature_hex.is_null() {
+    set_last_error(&VerifyError::NullPointer { name: "signature output".to_owned() }.into());
+    return ERR_NULL_POINTER;
+  }
+  // Safety: the caller passes a valid, writable pointer, checked above
+  unsafe { out_signature_hex.write(std::ptr::null_mut()) };
+
+  let result = (|| {
+    // Safety: Convert C strings to Rust strings
+    let seed = unsafe { str_from_ptr(seed_hex_ptr, "seed")? };
+    let message = unsafe { str_from_ptr(message_ptr, "message")? };
+    sign::sign_with_seed::<P>(seed, message, wrapped)
+  })();
+  match result {
+    Ok(signature) => {
+      // Safety: as above
+      unsafe { out_signature_hex.write(into_c_string(signature)) };
+      VALID
+    }
+    Err(error) => {
+      set_last_error(&error);
+      error.code
+    }
+  }
+}
+
+/// Sign a message with an sr25519 key
+///
+/// Meant for tests and tooling that need signatures this library can
+/// verify. The seed is never logged or echoed in `get_last_error`, and its
+/// decoded bytes and the key are zeroed before returning, on failure too.
+///
+/// # Arguments
+/// * `seed_hex_ptr` - Hex-encoded 32-byte mini-secret seed, with or without
+///   0x prefix, as printed by `subkey inspect` under "Secret seed"
+/// * `message_ptr` - The message to sign, signed as its UTF-8 bytes with
+///   no wrapping, or as their `blake2_256` hash if over 256 bytes, the
+///   way `verify_polkadot_signature_v2` checks it
+/// * `out_signature_hex` - Receives the 0x-prefixed hex signature. Release
+///   it with `free_string`. Set to NULL on failure.
+///
+/// # Returns
+/// * `VALID` (1) if the message was signed
+/// * `ERR_BAD_SEED_LENGTH` if the seed is not 32 bytes
+/// * `ERR_BAD_HEX`, `ERR_NULL_POINTER` or `ERR_INVALID_UTF8` for bad
+///   arguments
+///
+/// Signatures are randomized, so signing twice gives different results
+/// that both verify.
+#[no_mangle]
+pub extern "C" fn sign_sr25519(
+  seed_hex_ptr: *const c_char,
+  message_ptr: *const c_char,
+  out_signature_hex: *mut *mut c_char,
+) -> i32 {
+  ffi_guard(ERR_PANIC, || sign_with_seed::<sr25519::Pair>(seed_hex_ptr, message_ptr, out_signature_hex, false))
+}
+
+/// Sign a message with an sr25519 key the way polkadot-js extensions do
+///
+/// Takes the same arguments as `sign_sr25519` and returns the same codes,
+/// so the signature is released with `free_string`, but signs the message
+/// wrapped in `<Bytes>...</Bytes>`, and as its
+/// `blake2_256` hash once wrapped it is longer than 256 bytes. Already
+/// wrapped messages are not wrapped again. The signature verifies with
+/// `verify_polkadot_signature_auto` and with `OPT_WRAP_BYTES`, since
+/// signing and verification share one canonicalization.
+#[no_mangle]
+pub extern "C" fn sign_wrapped(
+  seed_hex_ptr: *const c_char,
+  message_ptr: *const c_char,
+  out_signature_hex: *mut *mut c_char,
+) -> i32 {
+  ffi_guard(ERR_PANIC, || sign_with_seed::<sr25519::Pair>(seed_hex_ptr, message_ptr, out_signature_hex, true))
+}
+
+/// Sign a message with an ed25519 key
+///
+/// Takes the same arguments as `sign_sr25519`, with the 32-byte ed25519
+/// secret seed, and returns the same codes. Messages over 256 bytes are
+/// signed as their `blake2_256` hash, as for sr25519. The signature is 64
+/// bytes and deterministic. Release it with `free_string`.
+#[no_mangle]
+pub extern "C" fn sign_ed25519(
+  seed_hex_ptr: *const c_char,
+  message_ptr: *const c_char,
+  out_signature_hex: *mut *mut c_char,
+) -> i32 {
+  ffi_guard(ERR_PANIC, || sign_with_seed::<ed25519::Pair>(seed_hex_ptr, message_ptr, out_signature_hex, false))
+}
+
+/// Sign a message with a secp256k1 ECDSA key
+///
+/// Takes the same arguments as `sign_sr25519`, with the 32-byte secret key
+/// as seed, and returns the same codes plus `ERR_BAD_SEED` for a secret key
+/// outside the curve order. The signature is the 65-byte recoverable form
+/// over the `blake2_256` hash of the message, after a message over 256
+/// bytes is first replaced by its own `blake2_256` hash as for sr25519.
+/// `verify_signature_with_options` checks it with `SCHEME_ECDSA` against
+/// the address of the hashed public key. Release it with `free_string`.
+#[no_mangle]
+pub extern "C" fn sign_ecdsa(
+  seed_hex_ptr: *const c_char,
+  message_ptr: *const c_char,
+  out_signature_hex: *mut *mut c_char,
+) -> i32 {
+  ffi_guard(ERR_PANIC, || sign_with_seed::<ecdsa::Pair>(seed_hex_ptr, message_ptr, out_signature_hex, false))
+}
+
+/// Sign a message with an sr25519 key derived from a BIP39 mnemonic
+///
+/// The key is the one `subkey` and `sr25519::Pair::from_string` derive
+/// from the mnemonic followed by the derivation path. Neither the mnemonic
+/// nor the password is ever logged or echoed in `get_last_error`. The
+/// entropy, seeds and keys computed along the way are zeroed before
+/// returning, including when the path or mnemonic is rejected.
+///
+/// # Arguments
+/// * `mnemonic_ptr` - English BIP39 mnemonic of 12 to 24 words
+/// * `derivation_path_ptr` - Junctions such as `//polkadot//0/1`,
+///   optionally followed by `///password`. May be empty for the root key.
+/// * `message_ptr` - The message to sign, signed as `sign_sr25519` signs
+///   it
+/// * `out_signature_hex` - Receives the 0x-prefixed hex signature. Release
+///   it with `free_string`. Set to NULL on failure.
+/// * `out_address` - Receives the SS58 address of the derived key under the
+///   generic Substrate prefix 42, so the caller can check it is the
+///   expected signer. Release it with `free_string`. May be NULL if not
+///   wanted, and is set to NULL on failure.
+///
+/// # Returns
+/// * `VALID` (1) if the message was signed
+/// * `ERR_UNKNOWN_MNEMONIC_WORD` for a word missing from the word list
+/// * `ERR_BAD_MNEMONIC_CHECKSUM` for known words that fail the checksum
+/// * `ERR_BAD_MNEMONIC` for a wrong number of words
+/// * `ERR_BAD_DERIVATION_PATH` for a malformed path
+/// * `ERR_NULL_POINTER` or `ERR_INVALID_UTF8` for bad arguments
+#[no_mangle]
+pub extern "C" fn sign_with_mnemonic(
+  mnemonic_ptr: *const c_char,
+  derivation_path_ptr: *const c_char,
+  message_ptr: *const c_char,
+  out_signature_hex: *mut *mut c_char,
+  out_address: *mut *mut c_char,
+) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    if out_signature_hex.is_null() {
+      set_last_error(&VerifyError::NullPointer { name: "signature output".to_owned() }.into());
+      return ERR_NULL_POINTER;
+    }
+    // Safety: the caller passes valid, writable pointers, or NULL for
+    // out_address
+    unsafe { out_signature_hex.write(std::ptr::null_mut()) };
+    if !out_address.is_null() {
+      unsafe { out_address.write(std::ptr::null_mut()) };
+    }
+
+    let result = (|| {
+      // Safety: Convert C strings to Rust strings
+      let mnemonic = unsafe { str_from_ptr(mnemonic_ptr, "mnemonic")? };
+      let path = unsafe { str_from_ptr(derivation_path_ptr, "derivation path")? };
+      let message = unsafe { str_from_ptr(message_ptr, "message")? };
+      sign::sign_with_mnemonic(mnemonic, path, message)
+    })();
+    match result {
+      Ok((signature, address)) => {
+        // Safety: as above
+        unsafe { out_signature_hex.write(into_c_string(signature)) };
+        if !out_address.is_null() {
+          unsafe { out_address.write(into_c_string(address)) };
+        }
+        VALID
+      }
+      Err(error) => {
+        set_last_error(&error);
+        error.code
+      }
+    }
+  })
+}
+
+/// Generate a throwaway sr25519 keypair
+///
+/// The seed comes from the operating system's CSPRNG. It is never logged,
+/// and the library's own copies are zeroed before returning: only the
+/// returned string holds it, and `free_string` zeroes that too.
+///
+/// # Arguments
+/// * `out_seed_hex` - Receives the 0x-prefixed hex 32-byte seed, which
+///   `sign_sr25519` accepts
+/// * `out_public_hex` - Receives the 0x-prefixed hex 32-byte public key
+/// * `out_ss58` - Receives the SS58 address of the public key under
+///   `prefix`
+/// * `prefix` - SS58 network prefix of the address, such as 0 for Polkadot
+///   or 42 for generic Substrate
+///
+/// All three out-pointers are required. On success each receives its own
+/// allocated string, which the caller owns and must release separately
+/// with `free_string`. On failure all three are set to NULL and nothing
+/// needs releasing.
+///
+/// # Returns
+/// * `VALID` (1) if a keypair was generated
+/// * `ERR_BAD_SS58` if `prefix` is above 16383
+/// * `ERR_NULL_POINTER` if an out-pointer is NULL
+#[no_mangle]
+pub extern "C" fn generate_sr25519_keypair(
+  out_seed_hex: *mut *mut c_char,
+  out_public_hex: *mut *mut c_char,
+  out_ss58: *mut *mut c_char,
+  prefix: u16,
+) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    let outs = [out_seed_hex, out_public_hex, out_ss58];
+    for out in outs.into_iter().filter(|out| !out.is_null()) {
+      // Safety: the caller passes valid, writable pointers or NULL
+      unsafe { out.write(std::ptr::null_mut()) };
+    }
+    if outs.iter().any(|out| out.is_null()) {
+      set_last_error(&VerifyError::NullPointer { name: "keypair output".to_owned() }.into());
+      return ERR_NULL_POINTER;
+    }
+
+    let (seed, public) = match sign::generate_sr25519(prefix) {
+      Ok(generated) => generated,
+      Err(error) => {
+        set_last_error(&error);
+        return error.code;
+      }
+    };
+
+    // Safety: checked non-NULL above
+    unsafe {
+      out_seed_hex.write(secret_hex_c_string(&seed[..]));
+      out_public_hex.write(into_c_string(format!("0x{}", hex::encode(public))));
+      out_ss58.write(into_c_string(ss58::encode(public.as_ref(), prefix)));
+    }
+    VALID
+  })
+}
+
+/// Generate a new English BIP39 mnemonic
+///
+/// The entropy comes from the operating system's CSPRNG, and is zeroed
+/// before returning. The phrase is never logged.
+///
+/// # Arguments
+/// * `word_count` - 12, 15, 18, 21 or 24
+///
+/// # Returns
+/// * The mnemonic, words separated by single spaces. It is secret: release
+///   it with `free_string`, which zeroes it.
+/// * NULL with `ERR_BAD_MNEMONIC` as the last error for any other word
+///   count
+#[no_mangle]
+pub extern "C" fn generate_mnemonic(word_count: u32) -> *mut c_char {
+  ffi_guard(std::ptr::null_mut(), || {
+    let mut mnemonic = match sign::generate_mnemonic(word_count) {
+      Ok(mnemonic) => mnemonic,
+      Err(error) => {
+        set_last_error(&error);
+        return std::ptr::null_mut();
+      }
+    };
+    let phrase = mnemonic_c_string(&mnemonic);
+    mnemonic.zeroize();
+    phrase
+  })
+}
+
+/// Check a BIP39 mnemonic against the English word list and its checksum
+///
+/// Neither the mnemonic nor any word of it is logged or echoed in
+/// `get_last_error`, which names only the position of an unknown word.
+///
+/// # Arguments
+/// * `phrase_ptr` - The mnemonic, words separated by single spaces
+///
+/// # Returns
+/// * `VALID` (1) if the mnemonic is valid
+/// * `ERR_UNKNOWN_MNEMONIC_WORD` if a word is not in the word list
+/// * `ERR_BAD_MNEMONIC_CHECKSUM` if the words fail their checksum
+/// * `ERR_BAD_MNEMONIC` if there are not 12, 15, 18, 21 or 24 words
+/// * `ERR_NULL_POINTER` or `ERR_INVALID_UTF8` for a bad argument
+#[no_mangle]
+pub extern "C" fn validate_mnemonic(phrase_ptr: *const c_char) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    // Safety: Convert C strings to Rust strings
+    let result = unsafe { str_from_ptr(phrase_ptr, "mnemonic") }.and_then(sign::check_mnemonic);
+    match result {
+      Ok(()) => VALID,
+      Err(error) => {
+        set_last_error(&error);
+        error.code
+      }
+    }
+  })
+}
+
+/// Derive the SS58 address of a BIP39 mnemonic without signing anything
+///
+/// Lets support staff check that a mnemonic belongs to an address. Neither
+/// the mnemonic nor the password is ever logged or echoed in
+/// `get_last_error`, and the key derived to compute the address is zeroed
+/// before returning.
+///
+/// # Arguments
+/// * `mnemonic_ptr` - English BIP39 mnemonic of 12 to 24 words
+/// * `derivation_path_ptr` - Junctions such as `//Alice` or `//polkadot/0`,
+///   optionally followed by `///password`. May be empty for the root key.
+///   Soft junctions need `SCHEME_SR25519`.
+/// * `scheme` - `SCHEME_SR25519`, `SCHEME_ED25519` or `SCHEME_ECDSA`. ECDSA
+///   addresses hold the `blake2_256` hash of the public key, as `subkey`
+///   prints them.
+/// * `prefix` - SS58 network prefix, such as 0 for Polkadot or 42 for
+///   generic Substrate
+///
+/// # Returns
+/// * The SS58 address. Release it with `free_string`.
+/// * NULL on failure, with `get_last_error` naming `ERR_UNKNOWN_MNEMONIC_WORD`,
+///   `ERR_BAD_MNEMONIC_CHECKSUM`, `ERR_BAD_MNEMONIC`,
+///   `ERR_BAD_DERIVATION_PATH`, `ERR_UNKNOWN_SCHEME` or `ERR_BAD_SS58` for
+///   a prefix above 16383
+#[no_mangle]
+pub extern "C" fn address_from_mnemonic(
+  mnemonic_ptr: *const c_char,
+  derivation_path_ptr: *const c_char,
+  scheme: i32,
+  prefix: u16,
+) -> *mut c_char {
+  ffi_guard(std::ptr::null_mut(), || {
+    let result = (|| {
+      // Safety: Convert C strings to Rust strings
+      let mnemonic = unsafe { str_from_ptr(mnemonic_ptr, "mnemonic")? };
+      let path = unsafe { str_from_ptr(derivation_path_ptr, "derivation path")? };
+      let scheme = SignatureScheme::from_i32(scheme)
+        .ok_or_else(|| VerifyError::UnknownScheme { reason: format!("unknown signature scheme {scheme}") })?;
+      sign::address_from_mnemonic(mnemonic, path, scheme, prefix)
+    })();
+    string_or_null(result)
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::ffi::error::get_last_error;
+  use crate::ffi::json::free_string;
+  use crate::ffi::{verify_polkadot_signature_auto, verify_polkadot_signature_v2, verify_signature_with_options};
+  use crate::sign::{decode_seed, wipe};
+  use crate::{
+    ERR_BAD_DERIVATION_PATH, ERR_BAD_HEX, ERR_BAD_MNEMONIC,
+    ERR_BAD_MNEMONIC_CHECKSUM, ERR_BAD_SEED, ERR_BAD_SEED_LENGTH, ERR_BAD_SS58, ERR_UNKNOWN_MNEMONIC_WORD, INVALID,
+    MESSAGE_AS_GIVEN, MESSAGE_WRAPPED,
+  };
+  use sp_core::crypto::{AccountId32, Ss58Codec, DEV_PHRASE};
+  use sp_core::hashing::blake2_256;
+  use std::ffi::CStr;
+
+  // The `//Alice` development seed
+  const SEED: &str = "0xe5be9a5092b81bca64be81d212e7f2f9eba183bb7a90954f7b76361f6edb5c0a";
+  const ALICE: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+
+  fn sign_c(seed: &str, message: &str) -> (i32, Option<String>) {
+    let seed = CString::new(seed).unwrap();
+    let message = CString::new(message).unwrap();
+    let mut out = std::ptr::null_mut();
+    let code = sign_sr25519(seed.as_ptr(), message.as_ptr(), &mut out);
+    if out.is_null() {
+      return (code, None);
+    }
+    let signature = unsafe { CStr::from_ptr(out) }.to_str().unwrap().to_owned();
+    free_string(out);
+    (code, Some(signature))
+  }
+
+  fn verify(address: &str, signature: &str, message: &str) -> i32 {
+    let address = CString::new(address).unwrap();
+    let signature = CString::new(signature).unwrap();
+    let message = CString::new(message).unwrap();
+    verify_polkadot_signature_v2(address.as_ptr(), signature.as_ptr(), message.as_ptr())
+  }
+
+  fn last_error() -> String {
+    unsafe { CStr::from_ptr(get_last_error()) }.to_str().unwrap().to_owned()
+  }
+
+  #[test]
+  fn test_round_trip() {
+    let (code, signature) = sign_c(SEED, "hello polkadot");
+    assert_eq!(code, VALID);
+    let signature = signature.unwrap();
+    assert!(signature.starts_with("0x") && signature.len() == 2 + 128, "{signature}");
+
+    assert_eq!(verify(ALICE, &signature, "hello polkadot"), VALID);
+    assert_eq!(verify(ALICE, &signature, "tampered"), INVALID);
+
+    // The seed is the one of the address
+    let public = sr25519::Pair::from_seed(&decode_seed(SEED).unwrap()).public();
+    assert_eq!(public.to_ss58check(), ALICE);
+  }
+
+  type SignFn = extern "C" fn(*const c_char, *const c_char, *mut *mut c_char) -> i32;
+
+  fn sign_seed_with(sign_fn: SignFn, seed: &str, message: &str) -> (i32, Option<String>) {
+    let seed = CString::new(seed).unwrap();
+    let message = CString::new(message).unwrap();
+    let mut out = std::ptr::null_mut();
+    let code = sign_fn(seed.as_ptr(), message.as_ptr(), &mut out);
+    (code, take(out))
+  }
+
+  fn verify_scheme(address: &str, signature: &str, message: &str, scheme: SignatureScheme) -> i32 {
+    let address = CString::new(address).unwrap();
+    let signature = CString::new(signature).unwrap();
+    let message = CString::new(message).unwrap();
+    let (address, signature, message) = (address.as_ptr(), signature.as_ptr(), message.as_ptr());
+    verify_signature_with_options(address, signature, message, scheme as i32, std::ptr::null())
+  }
+
+  fn verify_auto(signature: &str, message: &str) -> (i32, i32) {
+    let (address, signature, message) =
+      (CString::new(ALICE).unwrap(), CString::new(signature).unwrap(), CString::new(message).unwrap());
+    let mut form = -1;
+    let code = verify_polkadot_signature_auto(address.as_ptr(), signature.as_ptr(), message.as_ptr(), &mut form);
+    (code, form)
+  }
+
+  #[test]
+  fn test_long_message_round_trip() {
+    let message = "x".repeat(300);
+    let (code, signature) = sign_c(SEED, &message);
+    assert_eq!(code, VALID);
+    let signature = signature.unwrap();
+    assert_eq!(verify(ALICE, &signature, &message), VALID);
+    assert_eq!(verify(ALICE, &signature, &"x".repeat(301)), INVALID);
+
+    let (code, signature, _) = sign_mnemonic(DEV_PHRASE, "//Alice", &message);
+    assert_eq!(code, VALID);
+    assert_eq!(verify(ALICE, &signature.unwrap(), &message), VALID);
+  }
+
+  #[test]
+  fn test_wrapped_round_trip() {
+    for message in ["hello polkadot".to_owned(), "x".repeat(300)] {
+      let (code, signature) = sign_seed_with(sign_wrapped, SEED, &message);
+      assert_eq!(code, VALID);
+      let signature = signature.unwrap();
+      assert_eq!(verify_auto(&signature, &message), (VALID, MESSAGE_WRAPPED), "{} bytes", message.len());
+
+      // Over the wrapped form itself it verifies as given
+      let wrapped = format!("<Bytes>{message}</Bytes>");
+      assert_eq!(verify_auto(&signature, &wrapped), (VALID, MESSAGE_AS_GIVEN));
+      assert_eq!(verify(ALICE, &signature, &message), INVALID);
+    }
+  }
+
+  #[test]
+  fn test_wrapped_does_not_double_wrap() {
+    let (_, signature) = sign_seed_with(sign_wrapped, SEED, "<Bytes>hello</Bytes>");
+    assert_eq!(verify_auto(&signature.unwrap(), "hello"), (VALID, MESSAGE_WRAPPED));
+  }
+
+  #[test]
+  fn test_ed25519_round_trip() {
+    let seed = [0x11u8; 32];
+    let address = AccountId32::from(ed25519::Pair::from_seed(&seed).public()).to_ss58check();
+
+    let (code, signature) = sign_seed_with(sign_ed25519, &hex::encode(seed), "hello polkadot");
+    assert_eq!(code, VALID);
+    let signature = signature.unwrap();
+    assert_eq!(signature.len(), 2 + 2 * 64);
+    assert_eq!(verify_scheme(&address, &signature, "hello polkadot", SignatureScheme::Ed25519), VALID);
+    assert_eq!(verify_scheme(&address, &signature, "tampered", SignatureScheme::Ed25519), INVALID);
+
+    let long = "x".repeat(300);
+    let (_, signature) = sign_seed_with(sign_ed25519, &hex::encode(seed), &long);
+    assert_eq!(verify_scheme(&address, &signature.unwrap(), &long, SignatureScheme::Ed25519), VALID);
+  }
+
+  #[test]
+  #[cfg(feature = "ecdsa")]
+  fn test_ecdsa_round_trip() {
+    let seed = [0x11u8; 32];
+    // ECDSA accounts are the blake2 hash of the 33-byte public key
+    let public = ecdsa::Pair::from_seed(&seed).public();
+    let address = AccountId32::from(blake2_256(public.as_ref())).to_ss58check();
+
+    let (code, signature) = sign_seed_with(sign_ecdsa, &format!("0x{}", hex::encode(seed)), "hello polkadot");
+    assert_eq!(code, VALID);
+    let signature = signature.unwrap();
+    assert_eq!(signature.len(), 2 + 2 * 65);
+    assert_eq!(verify_scheme(&address, &signature, "hello polkadot", SignatureScheme::Ecdsa), VALID);
+    assert_eq!(verify_scheme(&address, &signature, "tampered", SignatureScheme::Ecdsa), INVALID);
+
+    let long = "x".repeat(300);
+    let (_, signature) = sign_seed_with(sign_ecdsa, &hex::encode(seed), &long);
+    assert_eq!(verify_scheme(&address, &signature.unwrap(), &long, SignatureScheme::Ecdsa), VALID);
+  }
+
+  #[test]
+  fn test_ecdsa_bad_secret() {
+    assert_eq!(sign_seed_with(sign_ecdsa, &"00".repeat(32), "hello"), (ERR_BAD_SEED, None));
+    assert_eq!(last_error(), "seed is not a valid secret key");
+    assert_eq!(sign_seed_with(sign_ed25519, &"00".repeat(31), "hello"), (ERR_BAD_SEED_LENGTH, None));
+  }
+
+  #[test]
+  fn test_secret_buffers_are_wiped() {
+    // The decoded seed is zeroed when dropped, on any return path
+    let seed: Zeroizing<[u8; 32]> = decode_seed(SEED).unwrap();
+    assert_eq!(hex::encode(&seed[..]), SEED[2..]);
+
+    let mut seed = [0xa5u8; 32];
+    wipe(&mut seed);
+    assert_eq!(seed, [0u8; 32]);
+
+    let secret = secret_hex_c_string(&[0xab, 0xcd]);
+    assert_eq!(take(secret).as_deref(), Some("0xabcd"));
+  }
+
+  #[test]
+  fn test_bad_seed_length() {
+    let (code, signature) = sign_c(&SEED[..SEED.len() - 2], "hello");
+    assert_eq!((code, signature), (ERR_BAD_SEED_LENGTH, None));
+    assert_eq!(last_error(), "seed is 31 bytes, expected 32");
+
+    assert_eq!(sign_c("", "hello").0, ERR_BAD_SEED_LENGTH);
+  }
+
+  #[test]
+  fn test_bad_seed_hex_is_not_echoed() {
+    let seed = format!("{}zz", &SEED[..SEED.len() - 2]);
+    assert_eq!(sign_c(&seed, "hello"), (ERR_BAD_HEX, None));
+    assert_eq!(last_error(), "seed hex has an invalid character at position 62");
+  }
+
+  #[test]
+  fn test_null_arguments() {
+    let message = CString::new("hello").unwrap();
+    let mut out = std::ptr::null_mut();
+    assert_eq!(sign_sr25519(std::ptr::null(), message.as_ptr(), &mut out), ERR_NULL_POINTER);
+    assert!(out.is_null());
+
+    let seed = CString::new(SEED).unwrap();
+    assert_eq!(sign_sr25519(seed.as_ptr(), message.as_ptr(), std::ptr::null_mut()), ERR_NULL_POINTER);
+  }
+
+  fn take(string: *mut c_char) -> Option<String> {
+    if string.is_null() {
+      return None;
+    }
+    let owned = unsafe { CStr::from_ptr(string) }.to_str().unwrap().to_owned();
+    free_string(string);
+    Some(owned)
+  }
+
+  fn sign_mnemonic(mnemonic: &str, path: &str, message: &str) -> (i32, Option<String>, Option<String>) {
+    let mnemonic = CString::new(mnemonic).unwrap();
+    let path = CString::new(path).unwrap();
+    let message = CString::new(message).unwrap();
+    let (mut signature, mut address) = (std::ptr::null_mut(), std::ptr::null_mut());
+    let code = sign_with_mnemonic(mnemonic.as_ptr(), path.as_ptr(), message.as_ptr(), &mut signature, &mut address);
+    (code, take(signature), take(address))
+  }
+
+  fn generate(prefix: u16) -> (i32, Option<String>, Option<String>, Option<String>) {
+    let (mut seed, mut public, mut address) = (std::ptr::null_mut(), std::ptr::null_mut(), std::ptr::null_mut());
+    let code = generate_sr25519_keypair(&mut seed, &mut public, &mut address, prefix);
+    (code, take(seed), take(public), take(address))
+  }
+
+  #[test]
+  fn test_generate_sign_verify() {
+    let (code, seed, public, address) = generate(0);
+    assert_eq!(code, VALID);
+    let (seed, public, address) = (seed.unwrap(), public.unwrap(), address.unwrap());
+    assert!(address.starts_with('1'), "{address}");
+
+    let (code, signature) = sign_c(&seed, "hello polkadot");
+    assert_eq!(code, VALID);
+    let signature = signature.unwrap();
+    assert_eq!(verify(&address, &signature, "hello polkadot"), VALID);
+    assert_eq!(verify(&public, &signature, "hello polkadot"), VALID);
+    assert_eq!(verify(&address, &signature, "tampered"), INVALID);
+
+    // Each call draws a fresh seed
+    assert_ne!(generate(0).1.unwrap(), seed);
+  }
+
+  #[test]
+  fn test_generate_bad_arguments() {
+    assert_eq!(generate(16_384), (ERR_BAD_SS58, None, None, None));
+
+    let (mut seed, mut public) = (std::ptr::null_mut(), std::ptr::null_mut());
+    assert_eq!(generate_sr25519_keypair(&mut seed, &mut public, std::ptr::null_mut(), 42), ERR_NULL_POINTER);
+    assert!(seed.is_null() && public.is_null());
+  }
+
+  fn mnemonic_address(mnemonic: &str, path: &str, scheme: SignatureScheme, prefix: u16) -> Option<String> {
+    let mnemonic = CString::new(mnemonic).unwrap();
+    let path = CString::new(path).unwrap();
+    take(address_from_mnemonic(mnemonic.as_ptr(), path.as_ptr(), scheme as i32, prefix))
+  }
+
+  fn validate(phrase: &str) -> i32 {
+    let phrase = CString::new(phrase).unwrap();
+    validate_mnemonic(phrase.as_ptr())
+  }
+
+  #[test]
+  fn test_generate_mnemonic() {
+    for word_count in [12, 15, 18, 21, 24] {
+      let phrase = take(generate_mnemonic(word_count)).unwrap();
+      assert_eq!(phrase.split(' ').count(), word_count as usize);
+      assert_eq!(validate(&phrase), VALID);
+      assert!(mnemonic_address(&phrase, "", SignatureScheme::Sr25519, 42).is_some());
+      assert!(mnemonic_address(&phrase, "//0", SignatureScheme::Ecdsa, 0).is_some());
+    }
+    assert_ne!(take(generate_mnemonic(12)), take(generate_mnemonic(12)));
+    assert_eq!(take(generate_mnemonic(13)), None);
+    assert_eq!(last_error(), "cannot generate 13 words, expected 12, 15, 18, 21 or 24");
+  }
+
+  #[test]
+  fn test_validate_mnemonic() {
+    assert_eq!(validate(DEV_PHRASE), VALID);
+
+    let phrase = take(generate_mnemonic(12)).unwrap();
+    let mut words: Vec<_> = phrase.split(' ').collect();
+    words[3] = "walkk";
+    assert_eq!(validate(&words.join(" ")), ERR_UNKNOWN_MNEMONIC_WORD);
+    assert_eq!(last_error(), "word 4 of the mnemonic is not in the English BIP39 word list");
+
+    // Twelve times "abandon" fails the checksum that eleven and "about" pass
+    let abandon = ["abandon"; 11].join(" ");
+    assert_eq!(validate(&format!("{abandon} about")), VALID);
+    assert_eq!(validate(&format!("{abandon} abandon")), ERR_BAD_MNEMONIC_CHECKSUM);
+    assert_eq!(validate(&abandon), ERR_BAD_MNEMONIC);
+    assert_eq!(validate_mnemonic(std::ptr::null()), ERR_NULL_POINTER);
+  }
+
+  #[test]
+  fn test_address_from_mnemonic() {
+    let alice = |prefix| mnemonic_address(DEV_PHRASE, "//Alice", SignatureScheme::Sr25519, prefix);
+    assert_eq!(alice(42).as_deref(), Some(ALICE));
+    assert_eq!(alice(0).as_deref(), Some("15oF4uVJwmo4TdGW7VfQxNLavjCXviqxT9S1MgbjMNHr6Sp5"));
+
+    let ecdsa_alice = mnemonic_address(DEV_PHRASE, "//Alice", SignatureScheme::Ecdsa, 42);
+    assert_eq!(ecdsa_alice.as_deref(), Some("5C7C2Z5sWbytvHpuLTvzKunnnRwQxft1jiqrLD5rhucQ5S9X"));
+
+    for path in ["", "//Alice", "//Alice///secret"] {
+      let expected = ed25519::Pair::from_string(&format!("{DEV_PHRASE}{path}"), None).unwrap().public();
+      let address = mnemonic_address(DEV_PHRASE, path, SignatureScheme::Ed25519, 42);
+      assert_eq!(address, Some(AccountId32::from(expected).to_ss58check()), "{path}");
+    }
+  }
+
+  #[test]
+  fn test_suri_matrix_matches_from_string() {
+    let hard_paths = [
+      "",
+      "//0",
+      "//1",
+      "//polkadot",
+      "//Alice//stash",
+      "//18446744073709551615",
+      "//18446744073709551616",
+      "//-1",
+      "//01",
+      "//a_junction_name_longer_than_thirty_two_bytes",
+      "//ünïcødé",
+      "//Alice///password",
+      "///password",
+      "//Alice///pass/word",
+      "//Alice///",
+    ];
+    let soft_paths = ["/0", "/polkadot", "//polkadot/0", "/1//2/3", "//Alice/a_junction_name_longer_than_thirty_two_bytes"];
+
+    for path in hard_paths.iter().chain(&soft_paths) {
+      let suri = format!("{DEV_PHRASE}{path}");
+      let sr25519 = sr25519::Pair::from_string(&suri, None).unwrap().public();
+      let address = mnemonic_address(DEV_PHRASE, path, SignatureScheme::Sr25519, 42);
+      assert_eq!(address, Some(AccountId32::from(sr25519).to_ss58check()), "sr25519 {path}");
+    }
+    for path in hard_paths {
+      let suri = format!("{DEV_PHRASE}{path}");
+      let ed25519 = ed25519::Pair::from_string(&suri, None).unwrap().public();
+      let address = mnemonic_address(DEV_PHRASE, path, SignatureScheme::Ed25519, 42);
+      assert_eq!(address, Some(AccountId32::from(ed25519).to_ss58check()), "ed25519 {path}");
+
+      let ecdsa = ecdsa::Pair::from_string(&suri, None).unwrap().public();
+      let address = mnemonic_address(DEV_PHRASE, path, SignatureScheme::Ecdsa, 42);
+      assert_eq!(address, Some(AccountId32::from(blake2_256(ecdsa.as_ref())).to_ss58check()), "ecdsa {path}");
+    }
+  }
+
+  #[test]
+  fn test_address_from_mnemonic_errors() {
+    // Soft junctions need the public key arithmetic only sr25519 has
+    assert_eq!(mnemonic_address(DEV_PHRASE, "//Alice/0", SignatureScheme::Ed25519, 42), None);
+    assert_eq!(last_error(), "derivation path has a soft junction, which needs an sr25519 key");
+    assert_eq!(mnemonic_address(DEV_PHRASE, "//Alice", SignatureScheme::Ethereum, 42), None);
+    assert_eq!(mnemonic_address(DEV_PHRASE, "//Alice", SignatureScheme::Sr25519, 16_384), None);
+
+    // Nothing of the mnemonic or password reaches the last error
+    let mnemonic = DEV_PHRASE.replace("obey", "obeyy");
+    assert_eq!(mnemonic_address(&mnemonic, "//Alice///hunter2", SignatureScheme::Sr25519, 42), None);
+    let error = last_error();
+    assert_eq!(error, "word 3 of the mnemonic is not in the English BIP39 word list");
+    assert!(DEV_PHRASE.split(' ').chain(["obeyy", "hunter2"]).all(|word| !error.contains(word)));
+  }
+
+  #[test]
+  fn test_mnemonic_round_trip() {
+    let (code, signature, address) = sign_mnemonic(DEV_PHRASE, "//Alice", "hello polkadot");
+    assert_eq!(code, VALID);
+    assert_eq!(address.as_deref(), Some(ALICE));
+    let signature = signature.unwrap();
+    assert_eq!(verify(ALICE, &signature, "hello polkadot"), VALID);
+    assert_eq!(verify(ALICE, &signature, "tampered"), INVALID);
+  }
+
+  #[test]
+  fn test_mnemonic_matches_from_string() {
+    for path in ["", "//Alice", "//polkadot/0/1", "//Alice///secret", "///secret"] {
+      let expected = sr25519::Pair::from_string(&format!("{DEV_PHRASE}{path}"), None).unwrap().public();
+      let (code, signature, address) = sign_mnemonic(DEV_PHRASE, path, "hello");
+      assert_eq!(code, VALID, "{path}");
+      assert_eq!(address, Some(expected.to_ss58check()), "{path}");
+      assert_eq!(verify(&expected.to_ss58check(), &signature.unwrap(), "hello"), VALID, "{path}");
+    }
+  }
+
+  #[test]
+  fn test_mnemonic_address_is_optional() {
+    let mnemonic = CString::new(DEV_PHRASE).unwrap();
+    let path = CString::new("//Alice").unwrap();
+    let message = CString::new("hello").unwrap();
+    let mut signature = std::ptr::null_mut();
+    let code = sign_with_mnemonic(mnemonic.as_ptr(), path.as_ptr(), message.as_ptr(), &mut signature, std::ptr::null_mut());
+    assert_eq!(code, VALID);
+    assert_eq!(verify(ALICE, &take(signature).unwrap(), "hello"), VALID);
+  }
+
+  #[test]
+  fn test_bad_mnemonics() {
+    let unknown = DEV_PHRASE.replace("walk", "walkk");
+    assert_eq!(sign_mnemonic(&unknown, "//Alice", "hello"), (ERR_UNKNOWN_MNEMONIC_WORD, None, None));
+    assert_eq!(last_error(), "word 12 of the mnemonic is not in the English BIP39 word list");
+
+    // Every word is valid but the last one should be "about"
+    let checksum = ["abandon"; 12].join(" ");
+    assert_eq!(sign_mnemonic(&checksum, "", "hello").0, ERR_BAD_MNEMONIC_CHECKSUM);
+    assert_eq!(sign_mnemonic(&format!("{}about", "abandon ".repeat(11)), "", "hello").0, VALID);
+
+    assert_eq!(sign_mnemonic("bottom drive obey", "", "hello").0, ERR_BAD_MNEMONIC);
+    assert_eq!(last_error(), "mnemonic has 3 words, expected 12, 15, 18, 21 or 24");
+  }
+
+  #[test]
+  fn test_bad_mnemonic_paths() {
+    for path in ["Alice", "//", "//Alice//", "/a//b/"] {
+      assert_eq!(sign_mnemonic(DEV_PHRASE, path, "hello"), (ERR_BAD_DERIVATION_PATH, None, None), "{path}");
+    }
+  }
+}
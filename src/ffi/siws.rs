@@ -0,0 +1,110 @@
+//! Sign-In with Substrate (SIWS) messages, from C.
+
+use crate::abi::SiwsExpectations;
+use crate::api::VerifyError;
+use crate::error::Error;
+use crate::ffi::guard::ffi_guard;
+use crate::parse::str_from_ptr;
+use crate::siws::{self, Expectations};
+use crate::verify::verdict;
+use crate::ERR_PANIC;
+use std::ffi::c_char;
+use std::mem::size_of;
+use std::ptr;
+
+/// # Safety
+/// `ptr` must be NULL or a valid C string.
+unsafe fn optional_str<'a>(ptr: *const c_char, name: &str) -> Result<Option<&'a str>, Error> {
+  if ptr.is_null() {
+    return Ok(None);
+  }
+  str_from_ptr(ptr, name).map(Some)
+}
+
+/// Read a caller's `SiwsExpectations`, no further than its `size`.
+///
+/// # Safety
+/// `ptr` must be NULL or point to at least `size` readable bytes whose
+/// string fields are NULL or valid C strings.
+unsafe fn read_expectations<'a>(ptr: *const SiwsExpectations) -> Result<Expectations<'a>, Error> {
+  if ptr.is_null() {
+    return Err(VerifyError::NullPointer { name: "expectations".to_owned() }.into());
+  }
+
+  let mut raw = SiwsExpectations::default();
+  let size = (ptr as *const u32).read_unaligned() as usize;
+  let len = size.min(size_of::<SiwsExpectations>());
+  ptr::copy_nonoverlapping(ptr as *const u8, &mut raw as *mut SiwsExpectations as *mut u8, len);
+
+  Ok(Expectations {
+    now: raw.now_unix,
+    domain: optional_str(raw.domain, "expected domain")?,
+    nonce: optional_str(raw.nonce, "expected nonce")?,
+    address: optional_str(raw.address, "expected address")?,
+  })
+}
+
+/// Verify a Sign-In with Substrate message
+///
+/// # Arguments
+/// * `message_ptr` - The message text, with LF or CRLF line endings
+/// * `signature_ptr` - Hex-encoded sr25519 signature by the address named
+///   in the message, over the message wrapped in `<Bytes>...</Bytes>`
+/// * `expected` - What the message must match, see `SiwsExpectations`
+///
+/// # Returns
+/// * `VALID` (1) if the signature verifies and every expectation holds
+/// * `INVALID` (0) if the signature does not verify
+/// * `ERR_SIWS_MALFORMED` if the message cannot be parsed
+/// * `ERR_SIWS_ADDRESS_MISMATCH`, `ERR_SIWS_DOMAIN_MISMATCH` or
+///   `ERR_SIWS_NONCE_MISMATCH` if a field differs from `expected`
+/// * `ERR_NOT_YET_VALID` if the message was issued, or only becomes valid,
+///   after `now_unix`
+/// * `ERR_EXPIRED` if its expiration time is at or before `now_unix`
+/// * the parse errors of `verify_polkadot_signature_v2`
+#[no_mangle]
+pub extern "C" fn verify_siws(
+  message_ptr: *const c_char,
+  signature_ptr: *const c_char,
+  expected: *const SiwsExpectations,
+) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    let result = (|| {
+      // Safety: Convert C strings to Rust strings
+      let message = unsafe { str_from_ptr(message_ptr, "message")? };
+      let signature = unsafe { str_from_ptr(signature_ptr, "signature")? };
+      // Safety: the caller passes a valid SiwsExpectations or NULL
+      let expected = unsafe { read_expectations(expected)? };
+      siws::verify_siws_text(message, signature, &expected)
+    })();
+    verdict(result)
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::message::{hash_if_long, wrap_bytes};
+  use crate::{ERR_NULL_POINTER, ERR_SIWS_NONCE_MISMATCH, VALID};
+  use sp_core::crypto::Ss58Codec;
+  use sp_core::{sr25519, Pair};
+  use std::ffi::CString;
+
+  #[test]
+  fn test_expectations_from_c() {
+    let alice = sr25519::Pair::from_string("//Alice", None).unwrap();
+    let text = format!(
+      "example.com wants you to sign in with your Substrate account:\n{}\n\nURI: https://example.com\nVersion: 1\nNonce: 7",
+      alice.public().to_ss58check()
+    );
+    let signature = CString::new(hex::encode(alice.sign(&hash_if_long(wrap_bytes(text.as_bytes()))))).unwrap();
+    let text = CString::new(text).unwrap();
+    let (domain, nonce, other) = (CString::new("example.com").unwrap(), CString::new("7").unwrap(), CString::new("8").unwrap());
+
+    let expected = SiwsExpectations { domain: domain.as_ptr(), nonce: nonce.as_ptr(), ..SiwsExpectations::default() };
+    assert_eq!(verify_siws(text.as_ptr(), signature.as_ptr(), &expected), VALID);
+    let expected = SiwsExpectations { nonce: other.as_ptr(), ..expected };
+    assert_eq!(verify_siws(text.as_ptr(), signature.as_ptr(), &expected), ERR_SIWS_NONCE_MISMATCH);
+    assert_eq!(verify_siws(text.as_ptr(), signature.as_ptr(), std::ptr::null()), ERR_NULL_POINTER);
+  }
+}
@@ -0,0 +1,160 @@
+//! SS58 address checks, from C.
+
+use crate::abi::AddressInfo;
+use crate::api::VerifyError;
+use crate::error::set_last_error;
+use crate::ffi::guard::ffi_guard;
+use crate::parse::str_from_ptr;
+use crate::ss58;
+use crate::{ERR_INVALID_UTF8, ERR_NULL_POINTER, ERR_PANIC, VALID};
+use std::ffi::{CStr, c_char};
+use std::slice;
+
+/// Check an SS58 address without verifying anything
+///
+/// # Arguments
+/// * `address_ptr` - SS58-encoded address
+/// * `out` - Receives the prefix, payload and kind of a valid address. May
+///   be NULL, in which case only the code is returned.
+///
+/// # Returns
+/// * 1 (`VALID`) if the address decodes
+/// * `ERR_SS58_BAD_BASE58`, `ERR_SS58_BAD_LENGTH` or `ERR_SS58_BAD_CHECKSUM`
+///   for the corresponding corruption
+/// * `ERR_WRONG_KEY_LENGTH` for an intact address whose payload is neither
+///   32 nor 33 bytes, such as a 20-byte `AccountId20`
+/// * `ERR_BAD_SS58` for an invalid or reserved prefix
+/// * `ERR_NULL_POINTER` or `ERR_INVALID_UTF8` for bad arguments
+///
+/// When the code is negative `out` is zeroed.
+#[no_mangle]
+pub extern "C" fn validate_ss58_address(address_ptr: *const c_char, out: *mut AddressInfo) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    // Safety: Convert C strings to Rust strings
+    let result = unsafe { str_from_ptr(address_ptr, "address") }.and_then(ss58::decode);
+    let (code, info) = match result {
+      Ok(decoded) => (VALID, AddressInfo::from(decoded)),
+      Err(error) => {
+        set_last_error(&error);
+        (error.code, AddressInfo::default())
+      }
+    };
+
+    if !out.is_null() {
+      // Safety: the caller passes a valid, writable AddressInfo or NULL
+      unsafe { out.write(info) };
+    }
+    code
+  })
+}
+
+/// Check many SS58 addresses in one call
+///
+/// Classifies each address like `validate_ss58_address`, without
+/// allocating or touching `get_last_error`, and carries on past failures.
+///
+/// # Arguments
+/// * `addresses` - Array of `count` NUL-terminated addresses
+/// * `count` - Number of addresses
+/// * `results` - Array of `count` codes to fill: 1 (`VALID`) or the
+///   negative code `validate_ss58_address` would return, including
+///   `ERR_NULL_POINTER` for NULL entries
+///
+/// # Returns
+/// * The number of invalid addresses, so 0 means all are valid
+/// * `ERR_NULL_POINTER` if `addresses` or `results` is NULL while `count`
+///   is not 0, in which case `results` is untouched
+#[no_mangle]
+pub extern "C" fn validate_ss58_addresses(addresses: *const *const c_char, count: usize, results: *mut i32) -> i64 {
+  ffi_guard(i64::from(ERR_PANIC), || {
+    if count == 0 {
+      return 0;
+    }
+    if addresses.is_null() || results.is_null() {
+      set_last_error(&VerifyError::NullPointer { name: "addresses or results".to_owned() }.into());
+      return i64::from(ERR_NULL_POINTER);
+    }
+
+    // Safety: the caller passes arrays of `count` elements
+    let addresses = unsafe { slice::from_raw_parts(addresses, count) };
+    let results = unsafe { slice::from_raw_parts_mut(results, count) };
+    let mut invalid = 0;
+    for (&address, result) in addresses.iter().zip(results.iter_mut()) {
+      *result = if address.is_null() {
+        ERR_NULL_POINTER
+      } else {
+        // Safety: non-NULL entries are NUL-terminated strings
+        match unsafe { CStr::from_ptr(address) }.to_str() {
+          Ok(address) => ss58::classify(address),
+          Err(_) => ERR_INVALID_UTF8,
+        }
+      };
+      if *result != VALID {
+        invalid += 1;
+      }
+    }
+    invalid
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{ADDRESS_KIND_ACCOUNT_ID, ERR_SS58_BAD_BASE58, ERR_SS58_BAD_CHECKSUM, ERR_SS58_BAD_LENGTH};
+  use std::ffi::CString;
+
+  const ADDRESS: &str = "5HbqogsYaK54pN3QAgSi4t3Asqditt1X4P7cSBuDt2hPF2BR";
+
+  fn validate(address: &str) -> (i32, AddressInfo) {
+    let address = CString::new(address).unwrap();
+    let mut info = AddressInfo { payload_len: 0xff, ..AddressInfo::default() };
+    let code = validate_ss58_address(address.as_ptr(), &mut info);
+    (code, info)
+  }
+
+  #[test]
+  fn test_validate() {
+    let (code, info) = validate(ADDRESS);
+    assert_eq!((code, info.ss58_prefix, info.payload_len, info.kind), (VALID, 42, 32, ADDRESS_KIND_ACCOUNT_ID));
+
+    // Failures zero `out`
+    let (code, info) = validate("5Hbq0gsYaK54pN3QAgSi4t3Asqditt1X4P7cSBuDt2hPF2BR");
+    assert_eq!(code, ERR_SS58_BAD_BASE58);
+    assert_eq!(info, AddressInfo::default());
+
+    let address = CString::new(ADDRESS).unwrap();
+    assert_eq!(validate_ss58_address(address.as_ptr(), std::ptr::null_mut()), VALID);
+    assert_eq!(validate_ss58_address(std::ptr::null(), std::ptr::null_mut()), ERR_NULL_POINTER);
+  }
+
+  #[test]
+  fn test_batch() {
+    let polkadot = CString::new("16Y8x28cS6LYFu3v8KViD2sKjTdNbBZf8sr6bUtaS7iuRDs5").unwrap();
+    let kusama = CString::new("J7TU1DRCg5za1rqwPFkxqQB2RuxhYphWkxMprBBMpusz4S5").unwrap();
+    let corrupted = CString::new(ADDRESS.replace("hPF2BR", "hPF2BS")).unwrap();
+    let empty = CString::new("").unwrap();
+    let not_utf8 = CString::new(vec![0xff]).unwrap();
+    let addresses = [polkadot.as_ptr(), corrupted.as_ptr(), kusama.as_ptr(), empty.as_ptr(), std::ptr::null(), not_utf8.as_ptr()];
+
+    let mut results = [0; 6];
+    assert_eq!(validate_ss58_addresses(addresses.as_ptr(), addresses.len(), results.as_mut_ptr()), 4);
+    assert_eq!(
+      results,
+      [VALID, ERR_SS58_BAD_CHECKSUM, VALID, ERR_SS58_BAD_LENGTH, ERR_NULL_POINTER, ERR_INVALID_UTF8]
+    );
+
+    let mut results = [0; 2];
+    assert_eq!(validate_ss58_addresses([polkadot.as_ptr(), kusama.as_ptr()].as_ptr(), 2, results.as_mut_ptr()), 0);
+    assert_eq!(results, [VALID; 2]);
+  }
+
+  #[test]
+  fn test_batch_bad_arguments() {
+    assert_eq!(validate_ss58_addresses(std::ptr::null(), 0, std::ptr::null_mut()), 0);
+    let mut results = [7; 1];
+    assert_eq!(validate_ss58_addresses(std::ptr::null(), 1, results.as_mut_ptr()), i64::from(ERR_NULL_POINTER));
+    assert_eq!(results, [7]);
+    let address = CString::new(ADDRESS).unwrap();
+    assert_eq!(validate_ss58_addresses([address.as_ptr()].as_ptr(), 1, std::ptr::null_mut()), i64::from(ERR_NULL_POINTER));
+  }
+}
@@ -0,0 +1,106 @@
+//! Verification counters, from C.
+
+use crate::abi::Stats;
+use crate::ffi::guard::ffi_guard;
+use crate::stats;
+
+/// Turn verification counting on or off
+///
+/// # Arguments
+/// * `enabled` - Nonzero to count every verification from now on, 0 to
+///   stop. Counters keep their values either way; see `reset_stats`.
+#[no_mangle]
+pub extern "C" fn stats_init(enabled: u8) {
+  ffi_guard((), || stats::set_enabled(enabled != 0))
+}
+
+/// Read the verification counters
+///
+/// # Arguments
+/// * `out` - Receives the counts since the last `reset_stats`, or since
+///   the library was loaded. May be NULL, in which case nothing is written.
+///
+/// The counters only move while counting is on, see `stats_init`.
+#[no_mangle]
+pub extern "C" fn get_stats(out: *mut Stats) {
+  ffi_guard((), || {
+    let stats = stats::snapshot();
+    if !out.is_null() {
+      // Safety: the caller passes a valid, writable Stats or NULL
+      unsafe { out.write(stats) };
+    }
+  })
+}
+
+/// Set every verification counter back to 0
+#[no_mangle]
+pub extern "C" fn reset_stats() {
+  ffi_guard((), stats::reset)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::ffi::batch::verify_batch;
+  use crate::ffi::{verify_polkadot_signature, verify_polkadot_signature_v2};
+  use crate::stats::COUNTS_THIS_THREAD;
+  use crate::{INVALID, VALID};
+  use sp_core::crypto::Ss58Codec;
+  use sp_core::{sr25519, Pair};
+  use std::ffi::{c_char, CString};
+
+  fn stats() -> Stats {
+    let mut stats = Stats::default();
+    get_stats(&mut stats);
+    stats
+  }
+
+  fn counts() -> (u64, u64) {
+    let stats = stats();
+    (stats.total_verifications, stats.failures)
+  }
+
+  #[test]
+  fn test_counters() {
+    let pair = sr25519::Pair::from_string("//Alice", None).unwrap();
+    let address = CString::new(pair.public().to_ss58check()).unwrap();
+    let signature = CString::new(format!("0x{}", hex::encode(pair.sign(b"hello")))).unwrap();
+    let (hello, tampered) = (CString::new("hello").unwrap(), CString::new("tampered").unwrap());
+    let bad_hex = CString::new("0xzz").unwrap();
+    let verify = |signature: &CString, message: &CString| {
+      verify_polkadot_signature_v2(address.as_ptr(), signature.as_ptr(), message.as_ptr())
+    };
+
+    COUNTS_THIS_THREAD.with(|counts| counts.set(true));
+    stats_init(1);
+    reset_stats();
+    assert_eq!(stats(), Stats::default());
+
+    assert_eq!(verify(&signature, &hello), VALID);
+    assert_eq!(counts(), (1, 0));
+    assert!(stats().total_nanos > 0);
+    assert_eq!(verify(&signature, &tampered), INVALID);
+    assert_ne!(verify(&bad_hex, &hello), VALID);
+    assert_eq!(counts(), (3, 2));
+
+    // The legacy wrapper counts once, not once per exported function
+    assert_eq!(verify_polkadot_signature(address.as_ptr(), signature.as_ptr(), hello.as_ptr()), 1);
+    assert_eq!(counts(), (4, 2));
+
+    let addresses: [*const c_char; 3] = [address.as_ptr(); 3];
+    let signatures = [signature.as_ptr(), signature.as_ptr(), bad_hex.as_ptr()];
+    let messages = [hello.as_ptr(), tampered.as_ptr(), hello.as_ptr()];
+    let mut results = [0; 3];
+    let failed = verify_batch(addresses.as_ptr(), signatures.as_ptr(), messages.as_ptr(), 3, results.as_mut_ptr());
+    assert_eq!(failed, 2);
+    assert_eq!(counts(), (7, 4));
+
+    stats_init(0);
+    assert_eq!(verify(&signature, &hello), VALID);
+    assert_eq!(counts(), (7, 4));
+    reset_stats();
+    assert_eq!(stats(), Stats::default());
+    get_stats(std::ptr::null_mut());
+    assert!(!stats::timing());
+  }
+}
@@ -0,0 +1,208 @@
+//! Incremental verification of messages too large for a single buffer,
+//! from C.
+//!
+//! Handles are ids into a registry rather than pointers to memory, so a
+//! handle that was already finalized is reported instead of being
+//! dereferenced.
+
+use crate::api::VerifyError;
+use crate::error::set_last_error;
+use crate::ffi::guard::ffi_guard;
+use crate::ffi::handles::HandleRegistry;
+use crate::parse::{bytes_from_ptr, str_from_ptr};
+use crate::stream::StreamState;
+use crate::verify::verdict;
+use crate::ERR_PANIC;
+use std::ffi::c_char;
+use std::sync::{Arc, Mutex};
+
+/// Opaque handle returned by `verify_stream_new`.
+pub enum VerifyStream {}
+
+static STREAMS: HandleRegistry<Mutex<StreamState>> = HandleRegistry::new("stream");
+
+/// Start verifying an sr25519 signature over a message fed in chunks
+///
+/// # Arguments
+/// * `address_ptr` - SS58-encoded address
+/// * `signature_ptr` - Hex-encoded signature (with or without 0x prefix)
+/// * `convention` - What the signer signed:
+///   * `STREAM_PREHASH_LONG` - like polkadot-js and
+///     `verify_polkadot_signature_bytes`: messages up to 256 bytes directly,
+///     longer ones as their `blake2_256` hash
+///   * `STREAM_PREHASH_ALWAYS` - the `blake2_256` hash of any message
+///
+/// # Returns
+/// A handle for `verify_stream_update` and `verify_stream_finalize`, or
+/// NULL if an argument is invalid; `get_last_error` says which. Every
+/// handle must be passed to exactly one of `verify_stream_finalize` or
+/// `verify_stream_free`.
+#[no_mangle]
+pub extern "C" fn verify_stream_new(
+  address_ptr: *const c_char,
+  signature_ptr: *const c_char,
+  convention: i32,
+) -> *mut VerifyStream {
+  ffi_guard(std::ptr::null_mut(), || {
+    let result = (|| {
+      // Safety: Convert C strings to Rust strings
+      let address = unsafe { str_from_ptr(address_ptr, "address")? };
+      let signature = unsafe { str_from_ptr(signature_ptr, "signature")? };
+      StreamState::new(address, signature, convention)
+    })();
+
+    match result {
+      Ok(state) => STREAMS.insert(Mutex::new(state)),
+      Err(error) => {
+        set_last_error(&error);
+        std::ptr::null_mut()
+      }
+    }
+  })
+}
+
+/// Feed the next chunk of the message
+///
+/// # Arguments
+/// * `handle` - From `verify_stream_new`
+/// * `chunk_ptr` - The next `chunk_len` bytes of the message. May be NULL
+///   if `chunk_len` is 0.
+///
+/// # Returns
+/// * 0 once the chunk is consumed
+/// * `ERR_INVALID_HANDLE` for NULL, unknown or finalized handles
+/// * `ERR_NULL_POINTER` for a NULL chunk with a nonzero length
+#[no_mangle]
+pub extern "C" fn verify_stream_update(handle: *mut VerifyStream, chunk_ptr: *const u8, chunk_len: usize) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    let result = (|| {
+      let stream = STREAMS.get(handle)?;
+      // Safety: the caller passes `chunk_len` readable bytes or NULL
+      let chunk = unsafe { bytes_from_ptr(chunk_ptr, chunk_len, "chunk")? };
+      stream.lock().unwrap_or_else(|e| e.into_inner()).update(chunk);
+      Ok(())
+    })();
+
+    match result {
+      Ok(()) => 0,
+      Err(error) => {
+        set_last_error(&error);
+        error.code
+      }
+    }
+  })
+}
+
+/// Verify the signature over everything fed so far and free the handle
+///
+/// # Returns
+/// The same codes as `verify_polkadot_signature_v2`, plus
+/// `ERR_INVALID_HANDLE` for NULL, unknown or already finalized handles.
+#[no_mangle]
+pub extern "C" fn verify_stream_finalize(handle: *mut VerifyStream) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    let result = STREAMS.remove(handle).and_then(|stream| {
+      let state = match Arc::try_unwrap(stream) {
+        Ok(state) => state.into_inner().unwrap_or_else(|e| e.into_inner()),
+        // Another thread is still updating; it holds its own reference
+        Err(_) => {
+          let reason = "stream was finalized during an update".to_owned();
+          return Err(VerifyError::InvalidHandle { reason }.into());
+        }
+      };
+      state.finish()
+    });
+    verdict(result.map(|outcome| outcome.valid == 1))
+  })
+}
+
+/// Free a handle without verifying
+///
+/// For abandoning a stream, for example after an I/O error. NULL, unknown
+/// and already finalized handles are ignored.
+#[no_mangle]
+pub extern "C" fn verify_stream_free(handle: *mut VerifyStream) {
+  ffi_guard((), || {
+    let _ = STREAMS.remove(handle);
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::ffi::error::get_last_error;
+  use crate::ffi::verify_polkadot_signature_bytes;
+  use crate::{ERR_INVALID_HANDLE, INVALID, STREAM_PREHASH_LONG, VALID};
+  use sp_core::hashing::blake2_256;
+  use sp_core::{crypto::Ss58Codec, sr25519, Pair};
+  use std::ffi::{CStr, CString};
+
+  fn last_error() -> String {
+    unsafe { CStr::from_ptr(get_last_error()) }.to_str().unwrap().to_owned()
+  }
+
+  /// //Alice's address and signature over `signed`, as C strings.
+  fn alice(signed: &[u8]) -> (CString, CString) {
+    let pair = sr25519::Pair::from_string("//Alice", None).unwrap();
+    let address = CString::new(pair.public().to_ss58check()).unwrap();
+    let signature = CString::new(hex::encode(pair.sign(signed))).unwrap();
+    (address, signature)
+  }
+
+  fn stream(address: &CString, signature: &CString, convention: i32, message: &[u8], chunk_len: usize) -> i32 {
+    let handle = verify_stream_new(address.as_ptr(), signature.as_ptr(), convention);
+    assert!(!handle.is_null());
+    for chunk in message.chunks(chunk_len) {
+      assert_eq!(verify_stream_update(handle, chunk.as_ptr(), chunk.len()), 0);
+    }
+    verify_stream_finalize(handle)
+  }
+
+  #[test]
+  fn test_matches_one_shot() {
+    let message: Vec<u8> = (0..64 * 1024).map(|i| (i % 251) as u8).collect();
+    let (address, signature) = alice(&blake2_256(&message));
+
+    let one_shot = verify_polkadot_signature_bytes(address.as_ptr(), signature.as_ptr(), message.as_ptr(), message.len());
+    assert_eq!(one_shot, VALID);
+    assert_eq!(stream(&address, &signature, STREAM_PREHASH_LONG, &message, 4096), one_shot);
+
+    let mut tampered = message;
+    tampered[32 * 1024] ^= 1;
+    assert_eq!(stream(&address, &signature, STREAM_PREHASH_LONG, &tampered, 4096), INVALID);
+  }
+
+  #[test]
+  fn test_double_finalize() {
+    let (address, signature) = alice(b"");
+    let handle = verify_stream_new(address.as_ptr(), signature.as_ptr(), STREAM_PREHASH_LONG);
+    assert_eq!(verify_stream_finalize(handle), VALID);
+    assert_eq!(verify_stream_finalize(handle), ERR_INVALID_HANDLE);
+    assert_eq!(verify_stream_update(handle, b"x".as_ptr(), 1), ERR_INVALID_HANDLE);
+    // Freeing a finalized handle is harmless
+    verify_stream_free(handle);
+  }
+
+  #[test]
+  fn test_free() {
+    let (address, signature) = alice(b"");
+    let handle = verify_stream_new(address.as_ptr(), signature.as_ptr(), STREAM_PREHASH_LONG);
+    verify_stream_free(handle);
+    assert_eq!(verify_stream_finalize(handle), ERR_INVALID_HANDLE);
+  }
+
+  #[test]
+  fn test_invalid_arguments() {
+    assert_eq!(verify_stream_finalize(std::ptr::null_mut()), ERR_INVALID_HANDLE);
+    assert_eq!(verify_stream_update(std::ptr::null_mut(), std::ptr::null(), 0), ERR_INVALID_HANDLE);
+
+    let (address, _) = alice(b"");
+    let short = CString::new("0xabcd").unwrap();
+    assert!(verify_stream_new(address.as_ptr(), short.as_ptr(), STREAM_PREHASH_LONG).is_null());
+    assert_eq!(last_error(), "signature is 2 bytes, expected 64");
+
+    let (_, signature) = alice(b"");
+    assert!(verify_stream_new(address.as_ptr(), signature.as_ptr(), 7).is_null());
+    assert_eq!(last_error(), "unknown stream convention 7");
+  }
+}
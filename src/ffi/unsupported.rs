@@ -0,0 +1,213 @@
+//! Stand-ins for the exports of modules left out by a cargo feature.
+//!
+//! Without `sp-core` there is no ed25519 or ECDSA key pair to sign or
+//! derive with. The C API keeps the same functions either way, so a host
+//! linking this build fails cleanly at run time: each stand-in sets
+//! `ERR_SCHEME_NOT_SUPPORTED` as the last error, NULLs its out-pointers,
+//! and returns the code or NULL.
+
+use crate::error::set_last_error;
+use crate::unsupported::unsupported;
+use std::ffi::c_char;
+
+/// Record that `what` needs `sp-core`, returning the code.
+fn fail(what: &str) -> i32 {
+  let error = unsupported(what, "sp-core");
+  set_last_error(&error);
+  error.code
+}
+
+/// Record that `what` needs `sp-core`, returning NULL.
+fn fail_null<T>(what: &str) -> *mut T {
+  set_last_error(&unsupported(what, "sp-core"));
+  std::ptr::null_mut()
+}
+
+/// NULL every non-NULL out-pointer, as the real exports do on failure.
+fn clear(outs: &[*mut *mut c_char]) {
+  for out in outs.iter().filter(|out| !out.is_null()) {
+    // Safety: the caller passes valid, writable pointers or NULL
+    unsafe { out.write(std::ptr::null_mut()) };
+  }
+}
+
+pub(crate) mod sign {
+  use super::*;
+
+  #[no_mangle]
+  pub extern "C" fn sign_sr25519(_: *const c_char, _: *const c_char, out_signature_hex: *mut *mut c_char) -> i32 {
+    clear(&[out_signature_hex]);
+    fail("signing")
+  }
+
+  #[no_mangle]
+  pub extern "C" fn sign_wrapped(_: *const c_char, _: *const c_char, out_signature_hex: *mut *mut c_char) -> i32 {
+    clear(&[out_signature_hex]);
+    fail("signing")
+  }
+
+  #[no_mangle]
+  pub extern "C" fn sign_ed25519(_: *const c_char, _: *const c_char, out_signature_hex: *mut *mut c_char) -> i32 {
+    clear(&[out_signature_hex]);
+    fail("signing")
+  }
+
+  #[no_mangle]
+  pub extern "C" fn sign_ecdsa(_: *const c_char, _: *const c_char, out_signature_hex: *mut *mut c_char) -> i32 {
+    clear(&[out_signature_hex]);
+    fail("signing")
+  }
+
+  #[no_mangle]
+  pub extern "C" fn sign_with_mnemonic(
+    _: *const c_char,
+    _: *const c_char,
+    _: *const c_char,
+    out_signature_hex: *mut *mut c_char,
+    out_address: *mut *mut c_char,
+  ) -> i32 {
+    clear(&[out_signature_hex, out_address]);
+    fail("signing")
+  }
+
+  #[no_mangle]
+  pub extern "C" fn generate_sr25519_keypair(
+    out_seed_hex: *mut *mut c_char,
+    out_public_hex: *mut *mut c_char,
+    out_ss58: *mut *mut c_char,
+    _: u16,
+  ) -> i32 {
+    clear(&[out_seed_hex, out_public_hex, out_ss58]);
+    fail("key generation")
+  }
+
+  #[no_mangle]
+  pub extern "C" fn generate_mnemonic(_: u32) -> *mut c_char {
+    fail_null("key generation")
+  }
+
+  #[no_mangle]
+  pub extern "C" fn validate_mnemonic(_: *const c_char) -> i32 {
+    fail("mnemonic validation")
+  }
+
+  #[no_mangle]
+  pub extern "C" fn address_from_mnemonic(_: *const c_char, _: *const c_char, _: i32, _: u16) -> *mut c_char {
+    fail_null("key derivation")
+  }
+}
+
+pub mod keypair {
+  use super::*;
+
+  /// Opaque handle of the keypair exports, none of which returns one in
+  /// this build.
+  pub enum Keypair {}
+
+  #[no_mangle]
+  pub extern "C" fn keypair_from_seed(_: *const c_char) -> *mut Keypair {
+    fail_null("key generation")
+  }
+
+  #[no_mangle]
+  pub extern "C" fn keypair_from_seed_with_scheme(_: *const c_char, _: i32) -> *mut Keypair {
+    fail_null("key generation")
+  }
+
+  #[no_mangle]
+  pub extern "C" fn keypair_from_mnemonic(_: *const c_char, _: *const c_char) -> *mut Keypair {
+    fail_null("key generation")
+  }
+
+  #[no_mangle]
+  pub extern "C" fn keypair_sign(_: *mut Keypair, _: *const u8, _: usize, out_signature_hex: *mut *mut c_char) -> i32 {
+    clear(&[out_signature_hex]);
+    fail("signing")
+  }
+
+  #[no_mangle]
+  pub extern "C" fn keypair_scheme(_: *mut Keypair) -> i32 {
+    fail("key generation")
+  }
+
+  #[no_mangle]
+  pub extern "C" fn keypair_public_hex(_: *mut Keypair) -> *mut c_char {
+    fail_null("key generation")
+  }
+
+  #[no_mangle]
+  pub extern "C" fn keypair_ss58(_: *mut Keypair, _: u16) -> *mut c_char {
+    fail_null("key generation")
+  }
+
+  /// Nothing to free, but NULL and stale handles are accepted as by the
+  /// real `keypair_free`.
+  #[no_mangle]
+  pub extern "C" fn keypair_free(_: *mut Keypair) {}
+}
+
+pub(crate) mod derive {
+  use super::*;
+
+  #[no_mangle]
+  pub extern "C" fn derive_child_public_key(_: *const c_char, _: *const c_char) -> *mut c_char {
+    fail_null("key derivation")
+  }
+
+  #[no_mangle]
+  pub extern "C" fn verify_with_derivation(
+    _: *const c_char,
+    _: *const c_char,
+    _: *const c_char,
+    _: *const c_char,
+  ) -> i32 {
+    fail("key derivation")
+  }
+
+  #[no_mangle]
+  pub extern "C" fn validate_derivation_path(_: *const c_char) -> i32 {
+    fail("key derivation")
+  }
+}
+
+pub(crate) mod dev {
+  use super::*;
+
+  #[no_mangle]
+  pub extern "C" fn dev_account_address(_: *const c_char, _: i32, _: u16) -> *mut c_char {
+    fail_null("dev accounts")
+  }
+
+  #[no_mangle]
+  pub extern "C" fn dev_account_sign(_: *const c_char, _: i32, _: *const c_char) -> *mut c_char {
+    fail_null("dev accounts")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::derive::validate_derivation_path;
+  use super::keypair::{keypair_free, keypair_from_seed};
+  use super::sign::sign_sr25519;
+  use crate::ffi::error::get_last_error;
+  use crate::ERR_SCHEME_NOT_SUPPORTED;
+  use std::ffi::{c_char, CStr, CString};
+
+  #[test]
+  fn test_exports_fail_cleanly() {
+    let seed = CString::new("00".repeat(32)).unwrap();
+    let message = CString::new("hello").unwrap();
+    let mut placeholder: c_char = 0;
+    let mut signature: *mut c_char = &mut placeholder;
+    assert_eq!(sign_sr25519(seed.as_ptr(), message.as_ptr(), &mut signature), ERR_SCHEME_NOT_SUPPORTED);
+    assert!(signature.is_null());
+    // Safety: get_last_error returns a NUL-terminated string
+    let error = unsafe { CStr::from_ptr(get_last_error()) }.to_str().unwrap();
+    assert!(error.contains("sp-core"), "{error}");
+
+    assert!(keypair_from_seed(seed.as_ptr()).is_null());
+    keypair_free(std::ptr::null_mut());
+    let path = CString::new("//Alice").unwrap();
+    assert_eq!(validate_derivation_path(path.as_ptr()), ERR_SCHEME_NOT_SUPPORTED);
+  }
+}
@@ -0,0 +1,183 @@
+//! Verifiers that parse an address once and check many signatures by it,
+//! from C.
+//!
+//! Handles are registry ids, like nonce cache handles, so a freed handle is
+//! reported rather than dereferenced.
+
+use crate::error::set_last_error;
+use crate::ffi::guard::ffi_guard;
+use crate::ffi::handles::HandleRegistry;
+use crate::log::{self, LOG_DEBUG};
+use crate::parse::{bytes_from_ptr, str_from_ptr};
+use crate::verifier::VerifierState;
+use crate::verify::verdict;
+use crate::ERR_PANIC;
+use std::ffi::c_char;
+
+/// Opaque handle returned by `verifier_new`.
+pub enum Verifier {}
+
+static VERIFIERS: HandleRegistry<VerifierState> = HandleRegistry::new("verifier");
+
+/// Parse an address once for repeated verification
+///
+/// # Arguments
+/// * `address_ptr` - SS58-encoded address, as for
+///   `verify_polkadot_signature_v2`
+///
+/// # Returns
+/// A handle for `verifier_verify`, to be released with `verifier_free`, or
+/// NULL with the last error set to the code `verify_polkadot_signature_v2`
+/// would give for the address, such as `ERR_SS58_BAD_CHECKSUM`. The handle
+/// may be shared between threads.
+#[no_mangle]
+pub extern "C" fn verifier_new(address_ptr: *const c_char) -> *mut Verifier {
+  ffi_guard(std::ptr::null_mut(), || {
+    let result = (|| {
+      // Safety: Convert C strings to Rust strings
+      let address = unsafe { str_from_ptr(address_ptr, "address")? };
+      VerifierState::new(address)
+    })();
+    match result {
+      Ok(state) => {
+        let handle = VERIFIERS.insert(state);
+        log::log(LOG_DEBUG, || format!("created verifier handle {handle:p}"));
+        handle
+      }
+      Err(error) => {
+        set_last_error(&error);
+        std::ptr::null_mut()
+      }
+    }
+  })
+}
+
+/// Verify a Polkadot SR25519 signature by a verifier's address
+///
+/// Gives the same result as `verify_polkadot_signature_bytes` with the
+/// address the verifier was created from, without parsing it again.
+///
+/// # Arguments
+/// * `verifier` - From `verifier_new`
+/// * `signature_ptr` - Hex-encoded signature (with or without 0x prefix)
+/// * `message_ptr` - The signed bytes. May be NULL if `message_len` is 0.
+/// * `message_len` - Number of bytes at `message_ptr`
+///
+/// # Returns
+/// The same codes as `verify_polkadot_signature_v2`, plus
+/// `ERR_INVALID_HANDLE` for NULL, unknown or freed verifiers.
+#[no_mangle]
+pub extern "C" fn verifier_verify(
+  verifier: *mut Verifier,
+  signature_ptr: *const c_char,
+  message_ptr: *const u8,
+  message_len: usize,
+) -> i32 {
+  ffi_guard(ERR_PANIC, || {
+    let result = (|| {
+      let verifier = VERIFIERS.get(verifier)?;
+      // Safety: Convert C strings to Rust strings
+      let signature = unsafe { str_from_ptr(signature_ptr, "signature")? };
+      // Safety: the caller passes `message_len` readable bytes or NULL
+      let message = unsafe { bytes_from_ptr(message_ptr, message_len, "message")? };
+      verifier.verify(signature, message)
+    })();
+    verdict(result)
+  })
+}
+
+/// Release a verifier
+///
+/// NULL, unknown and already freed handles are ignored. Calls still
+/// running on other threads finish with the verifier first.
+#[no_mangle]
+pub extern "C" fn verifier_free(verifier: *mut Verifier) {
+  ffi_guard((), || {
+    let _ = VERIFIERS.remove(verifier);
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::ffi::verify_polkadot_signature_bytes;
+  use crate::{ERR_BAD_HEX, ERR_INVALID_HANDLE, INVALID, VALID};
+  use sp_core::crypto::Ss58Codec;
+  use sp_core::{sr25519, Pair};
+  use std::ffi::CString;
+
+  fn new(address: &str) -> *mut Verifier {
+    let address = CString::new(address).unwrap();
+    verifier_new(address.as_ptr())
+  }
+
+  fn check(verifier: *mut Verifier, signature: &str, message: &[u8]) -> i32 {
+    let signature = CString::new(signature).unwrap();
+    verifier_verify(verifier, signature.as_ptr(), message.as_ptr(), message.len())
+  }
+
+  fn check_stateless(address: &str, signature: &str, message: &[u8]) -> i32 {
+    let (address, signature) = (CString::new(address).unwrap(), CString::new(signature).unwrap());
+    verify_polkadot_signature_bytes(address.as_ptr(), signature.as_ptr(), message.as_ptr(), message.len())
+  }
+
+  #[test]
+  fn test_matches_stateless_api() {
+    let pair = sr25519::Pair::from_string("//Alice", None).unwrap();
+    let address = pair.public().to_ss58check();
+    let signed = b"hello\0relay";
+    let signature = format!("0x{}", hex::encode(pair.sign(signed)));
+    let long = [7u8; 300];
+    let long_signature = format!("0x{}", hex::encode(pair.sign(&sp_core::hashing::blake2_256(&long))));
+
+    let verifier = new(&address);
+    let cases: [(&str, &[u8]); 6] = [
+      (&signature, signed),
+      (&signature, b"hello"),
+      (&signature[2..], signed),
+      ("0xzz", signed),
+      (&signature[..66], signed),
+      (&long_signature, &long),
+    ];
+    for (signature, message) in cases {
+      assert_eq!(check(verifier, signature, message), check_stateless(&address, signature, message));
+    }
+    assert_eq!(check(verifier, &signature, signed), VALID);
+    assert_eq!(check(verifier, &signature, b"hello"), INVALID);
+    assert_eq!(check(verifier, "0xzz", signed), ERR_BAD_HEX);
+    assert_eq!(check(verifier, &long_signature, &long), VALID);
+    verifier_free(verifier);
+
+    assert_eq!(check(verifier, &signature, signed), ERR_INVALID_HANDLE);
+    assert_eq!(check(std::ptr::null_mut(), &signature, signed), ERR_INVALID_HANDLE);
+    // Freeing twice, or NULL, is harmless
+    verifier_free(verifier);
+    verifier_free(std::ptr::null_mut());
+  }
+
+  #[test]
+  fn test_bad_address() {
+    assert!(new("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQZ").is_null());
+    assert_eq!(VERIFIERS.get(std::ptr::null_mut::<Verifier>()).err().map(|error| error.code), Some(ERR_INVALID_HANDLE));
+    assert!(verifier_new(std::ptr::null()).is_null());
+  }
+
+  #[test]
+  fn test_shared_across_threads() {
+    let pair = sr25519::Pair::from_string("//Bob", None).unwrap();
+    let handle = new(&pair.public().to_ss58check()) as usize;
+    std::thread::scope(|scope| {
+      for thread in 0..4 {
+        let pair = pair.clone();
+        scope.spawn(move || {
+          for i in 0..100 {
+            let message = format!("message {thread}-{i}");
+            let signature = format!("0x{}", hex::encode(pair.sign(message.as_bytes())));
+            assert_eq!(check(handle as *mut Verifier, &signature, message.as_bytes()), VALID);
+          }
+        });
+      }
+    });
+    verifier_free(handle as *mut Verifier);
+  }
+}
@@ -0,0 +1,134 @@
+//! Verification of signatures over file contents.
+
+use crate::api::VerifyError;
+use crate::error::Error;
+use crate::log::{self, LOG_DEBUG};
+use crate::stream::StreamState;
+use crate::{VerifyOutcome, STREAM_PREHASH_LONG};
+use std::fs::File;
+use std::io::{self, ErrorKind, Read};
+
+/// Bytes read from the file at a time.
+const CHUNK_LEN: usize = 64 * 1024;
+
+fn io_error(err: io::Error, path: &str) -> Error {
+  let reason = format!("cannot read {path}: {err}");
+  match err.kind() {
+    ErrorKind::NotFound => VerifyError::FileNotFound { reason },
+    ErrorKind::PermissionDenied => VerifyError::FilePermissionDenied { reason },
+    _ => VerifyError::FileIo { reason },
+  }
+  .into()
+}
+
+/// Stream the file at `path` through an sr25519 verification.
+pub(crate) fn verify_file(address: &str, signature: &str, path: &str) -> Result<VerifyOutcome, Error> {
+  let mut stream = StreamState::new(address, signature, STREAM_PREHASH_LONG)?;
+  let mut file = File::open(path).map_err(|e| io_error(e, path))?;
+
+  let mut buffer = vec![0u8; CHUNK_LEN];
+  let mut total = 0u64;
+  loop {
+    let read = match file.read(&mut buffer) {
+      Ok(0) => break,
+      Ok(read) => read,
+      Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+      Err(e) => return Err(io_error(e, path)),
+    };
+    stream.update(&buffer[..read]);
+    total += read as u64;
+  }
+
+  log::log(LOG_DEBUG, || format!("read {total} bytes from {path}"));
+  stream.finish()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::verify::verdict;
+  use crate::{ERR_FILE_IO, ERR_FILE_NOT_FOUND, ERR_FILE_PERMISSION_DENIED, INVALID, VALID};
+  use sp_core::hashing::blake2_256;
+  use sp_core::{crypto::Ss58Codec, sr25519, Pair};
+  use std::path::PathBuf;
+
+  /// A file in the temp directory, removed on drop.
+  struct TempFile(PathBuf);
+
+  impl TempFile {
+    fn new(name: &str, contents: &[u8]) -> TempFile {
+      let path = std::env::temp_dir().join(format!("psv-{}-{name}", std::process::id()));
+      std::fs::write(&path, contents).unwrap();
+      TempFile(path)
+    }
+  }
+
+  impl Drop for TempFile {
+    fn drop(&mut self) {
+      let _ = std::fs::remove_file(&self.0);
+    }
+  }
+
+  fn verify(address: &str, signature: &str, path: &str) -> i32 {
+    verdict(verify_file(address, signature, path).map(|outcome| outcome.valid == 1))
+  }
+
+  fn alice() -> (sr25519::Pair, String) {
+    let pair = sr25519::Pair::from_string("//Alice", None).unwrap();
+    let address = pair.public().to_ss58check();
+    (pair, address)
+  }
+
+  #[test]
+  fn test_signed_file() {
+    let (pair, address) = alice();
+    // Several chunks long, so it is signed as its hash
+    let contents: Vec<u8> = (0..3 * CHUNK_LEN + 17).map(|i| (i % 253) as u8).collect();
+    let signature = hex::encode(pair.sign(&blake2_256(&contents)));
+
+    let file = TempFile::new("signed", &contents);
+    assert_eq!(verify(&address, &signature, file.0.to_str().unwrap()), VALID);
+  }
+
+  #[test]
+  fn test_small_file() {
+    let (pair, address) = alice();
+    let contents = b"release-1.2.3.tar.gz sha256:...";
+    let signature = hex::encode(pair.sign(contents));
+
+    let file = TempFile::new("small", contents);
+    assert_eq!(verify(&address, &signature, file.0.to_str().unwrap()), VALID);
+  }
+
+  #[test]
+  fn test_modified_after_signing() {
+    let (pair, address) = alice();
+    let contents = vec![42u8; 1000];
+    let signature = hex::encode(pair.sign(&blake2_256(&contents)));
+
+    let mut modified = contents;
+    modified[999] = 43;
+    let file = TempFile::new("modified", &modified);
+    assert_eq!(verify(&address, &signature, file.0.to_str().unwrap()), INVALID);
+  }
+
+  #[test]
+  fn test_unreadable_paths() {
+    let (pair, address) = alice();
+    let signature = hex::encode(pair.sign(b""));
+
+    let missing = std::env::temp_dir().join(format!("psv-{}-missing", std::process::id()));
+    assert_eq!(verify(&address, &signature, missing.to_str().unwrap()), ERR_FILE_NOT_FOUND);
+    // Opening a directory succeeds on Unix, reading it does not
+    #[cfg(unix)]
+    assert_eq!(verify(&address, &signature, std::env::temp_dir().to_str().unwrap()), ERR_FILE_IO);
+  }
+
+  #[test]
+  fn test_io_error_codes() {
+    let path = "release.tar.gz";
+    assert_eq!(io_error(ErrorKind::NotFound.into(), path).code, ERR_FILE_NOT_FOUND);
+    assert_eq!(io_error(ErrorKind::PermissionDenied.into(), path).code, ERR_FILE_PERMISSION_DENIED);
+    assert_eq!(io_error(ErrorKind::UnexpectedEof.into(), path).code, ERR_FILE_IO);
+  }
+}
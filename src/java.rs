@@ -0,0 +1,327 @@
+//! JNI bindings for `io.polkadot.verifier.Native`, built with the `jni`
+//! feature, as `java/io/polkadot/verifier/Native.java` declares them.
+//!
+//! Both verify with [`api::verify`] and return what the C functions do:
+//! `VALID`, `INVALID` or a negative `ERR_*` code for malformed input.
+//! Arguments that cannot be read are thrown instead, as Java exceptions:
+//! `NullPointerException` for a null argument or array element, and
+//! `IllegalArgumentException` for strings that are not valid UTF-16 or
+//! batch arrays of different lengths.
+
+use crate::{api, INVALID, VALID};
+use jni::errors::Error as JniError;
+use jni::objects::{JByteArray, JClass, JObject, JObjectArray, JString};
+use jni::sys::{jint, jintArray, jsize};
+use jni::JNIEnv;
+use std::panic::{self, AssertUnwindSafe};
+
+const NULL_POINTER_EXCEPTION: &str = "java/lang/NullPointerException";
+const ILLEGAL_ARGUMENT_EXCEPTION: &str = "java/lang/IllegalArgumentException";
+const ILLEGAL_STATE_EXCEPTION: &str = "java/lang/IllegalStateException";
+
+/// Why a native method returns without a result.
+enum Failure {
+  /// The JVM already has an exception pending.
+  Pending,
+  /// An exception to throw, by class and message.
+  Throw(&'static str, String),
+}
+
+impl Failure {
+  fn null(name: String) -> Self {
+    Failure::Throw(NULL_POINTER_EXCEPTION, format!("{name} is null"))
+  }
+}
+
+impl From<JniError> for Failure {
+  fn from(error: JniError) -> Self {
+    match error {
+      JniError::JavaException => Failure::Pending,
+      error => Failure::Throw(ILLEGAL_STATE_EXCEPTION, error.to_string()),
+    }
+  }
+}
+
+type Result<T> = std::result::Result<T, Failure>;
+
+/// Run `body`, throwing what it fails with as a Java exception and
+/// returning `fallback`, which the JVM ignores while one is pending.
+/// Panics are thrown as `IllegalStateException` rather than unwinding into
+/// the JVM.
+fn guarded<'local, T>(
+  env: &mut JNIEnv<'local>,
+  fallback: T,
+  body: impl FnOnce(&mut JNIEnv<'local>) -> Result<T>,
+) -> T {
+  let failure = match panic::catch_unwind(AssertUnwindSafe(|| body(env))) {
+    Ok(Ok(value)) => return value,
+    Ok(Err(failure)) => failure,
+    Err(_) => Failure::Throw(ILLEGAL_STATE_EXCEPTION, "panic while verifying".to_string()),
+  };
+  if let Failure::Throw(class, message) = failure {
+    // Nothing more can be reported if even throwing fails
+    let _ = env.throw_new(class, message);
+  }
+  fallback
+}
+
+fn read_string(env: &mut JNIEnv, string: &JString, name: impl FnOnce() -> String) -> Result<String> {
+  if string.is_null() {
+    return Err(Failure::null(name()));
+  }
+  // JNI hands strings over as modified UTF-8, which spells characters
+  // outside the BMP as surrogate pairs
+  let chars = env.get_string(string)?;
+  match cesu8::from_java_cesu8(chars.to_bytes()) {
+    Ok(string) => Ok(string.into_owned()),
+    Err(_) => Err(Failure::Throw(ILLEGAL_ARGUMENT_EXCEPTION, format!("{} is not valid UTF-16", name()))),
+  }
+}
+
+fn read_bytes(env: &mut JNIEnv, bytes: &JByteArray, name: impl FnOnce() -> String) -> Result<Vec<u8>> {
+  if bytes.is_null() {
+    return Err(Failure::null(name()));
+  }
+  Ok(env.convert_byte_array(bytes)?)
+}
+
+fn array_length(env: &mut JNIEnv, array: &JObjectArray, name: &str) -> Result<jsize> {
+  if array.is_null() {
+    return Err(Failure::null(name.to_string()));
+  }
+  Ok(env.get_array_length(array)?)
+}
+
+fn code(address: &str, signature: &str, message: &[u8]) -> jint {
+  match api::verify(address, signature, message) {
+    Ok(()) => VALID,
+    Err(error) => i32::from(&error),
+  }
+}
+
+/// `Native.verifySignature(String address, String signature, byte[] message)`.
+///
+/// Verifies a Polkadot SR25519 signature as [`api::verify`] does, with the
+/// message as the given bytes, without `<Bytes>` wrapping.
+#[no_mangle]
+pub extern "system" fn Java_io_polkadot_verifier_Native_verifySignature<'local>(
+  mut env: JNIEnv<'local>,
+  _class: JClass<'local>,
+  address: JString<'local>,
+  signature: JString<'local>,
+  message: JByteArray<'local>,
+) -> jint {
+  guarded(&mut env, INVALID, |env| {
+    let address = read_string(env, &address, || "address".to_string())?;
+    let signature = read_string(env, &signature, || "signature".to_string())?;
+    let message = read_bytes(env, &message, || "message".to_string())?;
+    Ok(code(&address, &signature, &message))
+  })
+}
+
+/// `Native.verifyBatch(String[] addresses, String[] signatures, byte[][] messages)`.
+///
+/// Returns one code per item, in order, as `verifySignature` gives for
+/// `addresses[i]`, `signatures[i]` and `messages[i]`. Each element's local
+/// reference is released once read, so batches of any size fit the JVM's
+/// local reference table.
+#[no_mangle]
+pub extern "system" fn Java_io_polkadot_verifier_Native_verifyBatch<'local>(
+  mut env: JNIEnv<'local>,
+  _class: JClass<'local>,
+  addresses: JObjectArray<'local>,
+  signatures: JObjectArray<'local>,
+  messages: JObjectArray<'local>,
+) -> jintArray {
+  guarded(&mut env, JObject::null().into_raw(), |env| {
+    let count = array_length(env, &addresses, "addresses")?;
+    for (array, name) in [(&signatures, "signatures"), (&messages, "messages")] {
+      let length = array_length(env, array, name)?;
+      if length != count {
+        let message = format!("{name} has {length} items, but addresses has {count}");
+        return Err(Failure::Throw(ILLEGAL_ARGUMENT_EXCEPTION, message));
+      }
+    }
+
+    let mut codes = Vec::with_capacity(count as usize);
+    for index in 0..count {
+      let address = env.get_object_array_element(&addresses, index)?;
+      let address = env.auto_local(JString::from(address));
+      let address = read_string(env, &address, || format!("addresses[{index}]"))?;
+      let signature = env.get_object_array_element(&signatures, index)?;
+      let signature = env.auto_local(JString::from(signature));
+      let signature = read_string(env, &signature, || format!("signatures[{index}]"))?;
+      let message = env.get_object_array_element(&messages, index)?;
+      let message = env.auto_local(JByteArray::from(message));
+      let message = read_bytes(env, &message, || format!("messages[{index}]"))?;
+      codes.push(code(&address, &signature, &message));
+    }
+
+    let results = env.new_int_array(count)?;
+    env.set_int_array_region(&results, 0, &codes)?;
+    Ok(results.into_raw())
+  })
+}
+
+/// Run on the host JVM with `cargo test --features jni-invocation --lib java`,
+/// with `JAVA_HOME` set.
+#[cfg(all(test, feature = "jni-invocation"))]
+mod tests {
+  use super::*;
+  use crate::{ERR_BAD_HEX, ERR_BAD_SIGNATURE_LENGTH};
+  use jni::objects::{JIntArray, JValue};
+  use jni::{InitArgsBuilder, JNIVersion, JavaVM};
+  use std::sync::OnceLock;
+
+  const ADDRESS: &str = "5HbqogsYaK54pN3QAgSi4t3Asqditt1X4P7cSBuDt2hPF2BR";
+  const SIGNATURE: &str = "0xf8ce1b80e76bf48a30610d0d3c2c9c8dbfdcb28a9aa8f56ac038d520ff17445ff3ce94809f8479a1224ef0d823d035022a9db2e72bd6c0d2f0244f766dec908b";
+  const MESSAGE: &str = "<Bytes>c15335d817e3d3d912d30f5a18a30c30162abc364cd0876d3a94af568d3c8c14</Bytes>";
+
+  /// A process can only start one JVM, so the tests share it.
+  fn vm() -> &'static JavaVM {
+    static VM: OnceLock<JavaVM> = OnceLock::new();
+    VM.get_or_init(|| {
+      let args = InitArgsBuilder::new().version(JNIVersion::V8).build().unwrap();
+      JavaVM::new(args).unwrap()
+    })
+  }
+
+  fn verify<'local>(
+    env: &mut JNIEnv<'local>,
+    address: JString<'local>,
+    signature: JString<'local>,
+    message: JByteArray<'local>,
+  ) -> jint {
+    // SAFETY: the clone is only used for this call, on this thread
+    let env = unsafe { env.unsafe_clone() };
+    Java_io_polkadot_verifier_Native_verifySignature(env, JClass::from(JObject::null()), address, signature, message)
+  }
+
+  fn verify_batch<'local>(
+    env: &mut JNIEnv<'local>,
+    addresses: JObjectArray<'local>,
+    signatures: JObjectArray<'local>,
+    messages: JObjectArray<'local>,
+  ) -> Option<Vec<jint>> {
+    // SAFETY: as in `verify`
+    let native_env = unsafe { env.unsafe_clone() };
+    let class = JClass::from(JObject::null());
+    let results = Java_io_polkadot_verifier_Native_verifyBatch(native_env, class, addresses, signatures, messages);
+    if results.is_null() {
+      return None;
+    }
+    // SAFETY: a non-null result is a new int[]
+    let results = unsafe { JIntArray::from_raw(results) };
+    let mut codes = vec![0; env.get_array_length(&results).unwrap() as usize];
+    env.get_int_array_region(&results, 0, &mut codes).unwrap();
+    Some(codes)
+  }
+
+  /// Take the pending exception, asserting it is an instance of `class`.
+  fn take_exception(env: &mut JNIEnv, class: &str) {
+    let exception = env.exception_occurred().unwrap();
+    assert!(!exception.is_null(), "expected a {class}");
+    env.exception_clear().unwrap();
+    assert!(env.is_instance_of(&exception, class).unwrap());
+  }
+
+  fn strings<'local>(env: &mut JNIEnv<'local>, items: &[&str]) -> JObjectArray<'local> {
+    let array = env.new_object_array(items.len() as jsize, "java/lang/String", JObject::null()).unwrap();
+    for (index, item) in items.iter().enumerate() {
+      let item = env.new_string(item).unwrap();
+      env.set_object_array_element(&array, index as jsize, item).unwrap();
+    }
+    array
+  }
+
+  fn byte_arrays<'local>(env: &mut JNIEnv<'local>, items: &[Option<&[u8]>]) -> JObjectArray<'local> {
+    let array = env.new_object_array(items.len() as jsize, "[B", JObject::null()).unwrap();
+    for (index, item) in items.iter().enumerate() {
+      if let Some(item) = item {
+        let item = env.byte_array_from_slice(item).unwrap();
+        env.set_object_array_element(&array, index as jsize, item).unwrap();
+      }
+    }
+    array
+  }
+
+  #[test]
+  fn test_verify_signature() {
+    let mut env = vm().attach_current_thread().unwrap();
+    let cases: [(&str, &[u8], jint); 4] = [
+      (SIGNATURE, MESSAGE.as_bytes(), VALID),
+      (SIGNATURE, b"tampered", INVALID),
+      (&SIGNATURE[..128], MESSAGE.as_bytes(), ERR_BAD_SIGNATURE_LENGTH),
+      ("0xzz", MESSAGE.as_bytes(), ERR_BAD_HEX),
+    ];
+    for (signature, message, expected) in cases {
+      let address = env.new_string(ADDRESS).unwrap();
+      let signature = env.new_string(signature).unwrap();
+      let message = env.byte_array_from_slice(message).unwrap();
+      assert_eq!(verify(&mut env, address, signature, message), expected);
+      assert!(!env.exception_check().unwrap());
+    }
+  }
+
+  #[test]
+  fn test_verify_signature_throws() {
+    let mut env = vm().attach_current_thread().unwrap();
+
+    let signature = env.new_string(SIGNATURE).unwrap();
+    let message = env.byte_array_from_slice(MESSAGE.as_bytes()).unwrap();
+    verify(&mut env, JString::from(JObject::null()), signature, message);
+    take_exception(&mut env, NULL_POINTER_EXCEPTION);
+
+    let address = env.new_string(ADDRESS).unwrap();
+    let signature = env.new_string(SIGNATURE).unwrap();
+    verify(&mut env, address, signature, JByteArray::from(JObject::null()));
+    take_exception(&mut env, NULL_POINTER_EXCEPTION);
+
+    // A lone surrogate has no UTF-8 encoding
+    let chars = env.new_char_array(1).unwrap();
+    env.set_char_array_region(&chars, 0, &[0xd800]).unwrap();
+    let lone = env.new_object("java/lang/String", "([C)V", &[JValue::Object(&chars)]).unwrap();
+    let signature = env.new_string(SIGNATURE).unwrap();
+    let message = env.byte_array_from_slice(MESSAGE.as_bytes()).unwrap();
+    verify(&mut env, JString::from(lone), signature, message);
+    take_exception(&mut env, ILLEGAL_ARGUMENT_EXCEPTION);
+  }
+
+  #[test]
+  fn test_verify_batch() {
+    let mut env = vm().attach_current_thread().unwrap();
+    let addresses = strings(&mut env, &[ADDRESS, ADDRESS, ADDRESS]);
+    let signatures = strings(&mut env, &[SIGNATURE, SIGNATURE, &SIGNATURE[..128]]);
+    let messages = byte_arrays(&mut env, &[Some(MESSAGE.as_bytes()), Some(b"tampered"), Some(MESSAGE.as_bytes())]);
+    let codes = verify_batch(&mut env, addresses, signatures, messages);
+    assert_eq!(codes, Some(vec![VALID, INVALID, ERR_BAD_SIGNATURE_LENGTH]));
+
+    let addresses = strings(&mut env, &[]);
+    let signatures = strings(&mut env, &[]);
+    let messages = byte_arrays(&mut env, &[]);
+    assert_eq!(verify_batch(&mut env, addresses, signatures, messages), Some(vec![]));
+    assert!(!env.exception_check().unwrap());
+  }
+
+  #[test]
+  fn test_verify_batch_throws() {
+    let mut env = vm().attach_current_thread().unwrap();
+
+    let addresses = strings(&mut env, &[ADDRESS, ADDRESS]);
+    let signatures = strings(&mut env, &[SIGNATURE, SIGNATURE]);
+    let messages = byte_arrays(&mut env, &[Some(MESSAGE.as_bytes())]);
+    assert_eq!(verify_batch(&mut env, addresses, signatures, messages), None);
+    take_exception(&mut env, ILLEGAL_ARGUMENT_EXCEPTION);
+
+    let addresses = strings(&mut env, &[ADDRESS, ADDRESS]);
+    let signatures = strings(&mut env, &[SIGNATURE, SIGNATURE]);
+    let messages = byte_arrays(&mut env, &[Some(MESSAGE.as_bytes()), None]);
+    assert_eq!(verify_batch(&mut env, addresses, signatures, messages), None);
+    take_exception(&mut env, NULL_POINTER_EXCEPTION);
+
+    let signatures = strings(&mut env, &[SIGNATURE]);
+    let messages = byte_arrays(&mut env, &[Some(MESSAGE.as_bytes())]);
+    assert_eq!(verify_batch(&mut env, JObjectArray::from(JObject::null()), signatures, messages), None);
+    take_exception(&mut env, NULL_POINTER_EXCEPTION);
+  }
+}
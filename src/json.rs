@@ -0,0 +1,287 @@
+//! Verification requests and outcomes as JSON.
+//!
+//! The shapes are those of [`crate::types`], shared by the Rust API,
+//! [`verify_request`], and the C API, `verify_json` and `verify_ndjson`.
+//! The C API only converts its arguments and serializes what these
+//! functions return.
+
+use crate::api::{Verifier, VerifyError};
+use crate::error::Error;
+use crate::message;
+#[cfg(feature = "ffi")]
+use crate::options::Options;
+use crate::types::{self, VerificationOutcome, VerificationRequest};
+use crate::verify;
+use serde::{Deserialize, Serialize};
+
+/// Verify a request with the same rules as `verify_signature_with_options`.
+pub fn verify_request(request: &VerificationRequest) -> VerificationOutcome {
+  let scheme = match types::scheme(&request.options) {
+    Ok(scheme) => scheme,
+    Err(error) => return types::failed(None, error),
+  };
+
+  let result = (|| {
+    let mut builder = Verifier::builder().flags(request.options.flags()).strict(request.options.strict);
+    if let Some(allowed_prefixes) = &request.options.allowed_prefixes {
+      builder = builder.allowed_prefixes(allowed_prefixes.iter().copied());
+    }
+    let verifier = builder.build()?;
+    let options = verifier.options();
+    let message = message::decode(request.message.as_bytes(), &options)?;
+    verify::verify_as(scheme, &request.address, &request.signature, &message, &options)
+  })();
+
+  match result {
+    Ok(outcome) => types::verified(scheme, outcome.valid == 1),
+    Err(error) => types::failed(Some(scheme), error),
+  }
+}
+
+/// Verify a request given as JSON, as `verify_json` does.
+pub(crate) fn verify_json_str(request: &str) -> VerificationOutcome {
+  match serde_json::from_str::<VerificationRequest>(request) {
+    Ok(request) => verify_request(&request),
+    Err(e) => {
+      let error = VerifyError::InvalidJson { reason: format!("invalid request: {e}") };
+      types::failed(None, error.into())
+    }
+  }
+}
+
+/// One line of the output of `verify_ndjson`: the outcome of a request,
+/// with its position.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct NdjsonResult {
+  /// Position of the request among the non-blank input lines, from 0.
+  pub index: usize,
+  #[serde(flatten)]
+  pub outcome: VerificationOutcome,
+}
+
+/// Verify one line of `verify_ndjson` input, the `index`th request.
+///
+/// A line that is not UTF-8 or not a request gets an error outcome, as
+/// `verify_ndjson` gives it.
+pub fn verify_ndjson_line(index: usize, line: &[u8]) -> NdjsonResult {
+  let outcome = match std::str::from_utf8(line) {
+    Ok(line) => verify_json_str(line),
+    Err(e) => types::failed(None, VerifyError::InvalidUtf8 { reason: format!("line is not valid UTF-8: {e}") }.into()),
+  };
+  NdjsonResult { index, outcome }
+}
+
+/// Verify the requests of `verify_ndjson` input, one line of output each.
+#[cfg(feature = "ffi")]
+pub(crate) fn verify_ndjson_bytes(input: &[u8]) -> String {
+  let lines = input.split(|&b| b == b'\n').map(|line| line.strip_suffix(b"\r").unwrap_or(line));
+  let requests = lines.filter(|line| !line.iter().all(u8::is_ascii_whitespace));
+  let mut output = String::new();
+  for (index, line) in requests.enumerate() {
+    let result = verify_ndjson_line(index, line);
+    output += &serde_json::to_string(&result).expect("results always serialize");
+    output.push('\n');
+  }
+  output
+}
+
+/// The object polkadot-js extensions resolve `signer.signRaw` with, such
+/// as `{"id": 1, "signature": "0x..."}`. Unknown fields are ignored.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignRawResult {
+  /// Request counter of the extension.
+  #[serde(default)]
+  pub id: u64,
+  /// Hex-encoded signature.
+  pub signature: String,
+}
+
+/// Verify the result of a polkadot-js `signRaw` call, as
+/// `verify_signraw_result` does.
+#[cfg(feature = "ffi")]
+pub(crate) fn verify_signraw(address: &str, result: &str, payload: &[u8]) -> Result<bool, Error> {
+  let result: SignRawResult = serde_json::from_str(result)
+    .map_err(|e| VerifyError::InvalidJson { reason: format!("invalid signRaw result: {e}") })?;
+  let options = Options { wrap_bytes: true, ..Options::default() };
+  let outcome = verify::verify_sr25519(address, &result.signature, payload, &options)?;
+  Ok(outcome.valid == 1)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::types::RequestOptions;
+  #[cfg(feature = "ffi")]
+  use crate::verify::verdict;
+  use crate::{SignatureScheme, ERR_BAD_JSON, ERR_INVALID_OPTIONS, ERR_INVALID_UTF8, ERR_UNKNOWN_SCHEME};
+  use serde_json::{json, Value};
+
+  const ADDRESS: &str = "5HbqogsYaK54pN3QAgSi4t3Asqditt1X4P7cSBuDt2hPF2BR";
+  const SIGNATURE: &str = "0xf8ce1b80e76bf48a30610d0d3c2c9c8dbfdcb28a9aa8f56ac038d520ff17445ff3ce94809f8479a1224ef0d823d035022a9db2e72bd6c0d2f0244f766dec908b";
+  const MESSAGE: &str = "<Bytes>c15335d817e3d3d912d30f5a18a30c30162abc364cd0876d3a94af568d3c8c14</Bytes>";
+
+  fn verify(request: &str) -> Value {
+    serde_json::to_value(verify_json_str(request)).unwrap()
+  }
+
+  fn error_code(response: &Value) -> i64 {
+    response["error_code"].as_i64().unwrap()
+  }
+
+  #[test]
+  fn test_valid() {
+    let request = json!({ "address": ADDRESS, "signature": SIGNATURE, "message": MESSAGE });
+    assert_eq!(verify(&request.to_string()), json!({ "valid": true, "scheme": "sr25519" }));
+  }
+
+  #[test]
+  fn test_invalid_and_options() {
+    let unwrapped = &MESSAGE[7..MESSAGE.len() - 8];
+    let request = json!({ "address": ADDRESS, "signature": SIGNATURE, "message": unwrapped });
+    assert_eq!(verify(&request.to_string()), json!({ "valid": false, "scheme": "sr25519" }));
+
+    let request = json!({
+      "address": ADDRESS,
+      "signature": SIGNATURE,
+      "message": unwrapped,
+      "options": { "scheme": "SR25519", "wrap_bytes": true, "allowed_prefixes": [42] }
+    });
+    assert_eq!(verify(&request.to_string())["valid"], json!(true));
+  }
+
+  #[test]
+  fn test_unknown_fields_are_ignored() {
+    let request = json!({
+      "address": ADDRESS,
+      "signature": SIGNATURE,
+      "message": MESSAGE,
+      "client": "deno",
+      "options": { "future_flag": 1 }
+    });
+    assert_eq!(verify(&request.to_string())["valid"], json!(true));
+  }
+
+  #[test]
+  fn test_malformed_json() {
+    let response = verify("{\"address\": ");
+    assert_eq!(response["valid"], json!(false));
+    assert!(response.get("scheme").is_none());
+    assert_eq!(error_code(&response), ERR_BAD_JSON as i64);
+
+    assert_eq!(error_code(&verify("[]")), ERR_BAD_JSON as i64);
+    assert_eq!(error_code(&verify("")), ERR_BAD_JSON as i64);
+  }
+
+  #[test]
+  fn test_missing_field() {
+    let response = verify(&json!({ "address": ADDRESS, "signature": SIGNATURE }).to_string());
+    assert_eq!(error_code(&response), ERR_BAD_JSON as i64);
+    assert!(response["error_message"].as_str().unwrap().contains("missing field `message`"));
+  }
+
+  #[test]
+  fn test_request_errors() {
+    let request = json!({ "address": ADDRESS, "signature": SIGNATURE, "message": MESSAGE, "options": { "scheme": "rsa" } });
+    assert_eq!(error_code(&verify(&request.to_string())), ERR_UNKNOWN_SCHEME as i64);
+
+    let options = json!({ "message_is_hex": true, "message_base64": true });
+    let request = json!({ "address": ADDRESS, "signature": SIGNATURE, "message": MESSAGE, "options": options });
+    let response = verify(&request.to_string());
+    assert_eq!((error_code(&response), &response["scheme"]), (ERR_INVALID_OPTIONS as i64, &json!("sr25519")));
+  }
+
+  #[test]
+  fn test_rust_api() {
+    let request = VerificationRequest {
+      address: ADDRESS.to_owned(),
+      signature: SIGNATURE.to_owned(),
+      message: MESSAGE.to_owned(),
+      options: RequestOptions::default(),
+    };
+    let response = verify_request(&request);
+    assert_eq!(response, types::verified(SignatureScheme::Sr25519, true));
+  }
+
+  #[cfg(feature = "ffi")]
+  fn verify_ndjson(input: &[u8]) -> Vec<NdjsonResult> {
+    let ndjson = verify_ndjson_bytes(input);
+    assert!(ndjson.is_empty() || ndjson.ends_with('\n'));
+    ndjson.lines().map(|line| serde_json::from_str(line).unwrap()).collect()
+  }
+
+  #[test]
+  #[cfg(feature = "ffi")]
+  fn test_ndjson_mixed() {
+    let valid = json!({ "address": ADDRESS, "signature": SIGNATURE, "message": MESSAGE }).to_string();
+    let invalid = json!({ "address": ADDRESS, "signature": SIGNATURE, "message": "tampered" }).to_string();
+    let bad_hex = json!({ "address": ADDRESS, "signature": "0xzz", "message": MESSAGE }).to_string();
+    let missing_field = json!({ "address": ADDRESS, "signature": SIGNATURE }).to_string();
+
+    // 100 lines: requests of every kind, blank lines, CRLF endings and a
+    // line that is not UTF-8
+    let mut input = Vec::new();
+    let mut expected = Vec::new();
+    for line in 0..100 {
+      let (request, outcome): (&[u8], _) = match line % 7 {
+        0 => (valid.as_bytes(), (true, None)),
+        1 => (invalid.as_bytes(), (false, None)),
+        2 => (bad_hex.as_bytes(), (false, Some(crate::ERR_BAD_HEX))),
+        3 => (b"{\"address\": ", (false, Some(ERR_BAD_JSON))),
+        4 => (b"  \t", (false, None)),
+        5 => (missing_field.as_bytes(), (false, Some(ERR_BAD_JSON))),
+        _ => (b"\xff{}", (false, Some(ERR_INVALID_UTF8))),
+      };
+      input.extend_from_slice(request);
+      input.extend_from_slice(if line % 2 == 0 { b"\n" } else { b"\r\n" });
+      if line % 7 != 4 {
+        expected.push((expected.len(), outcome));
+      }
+    }
+    let results = verify_ndjson(&input);
+    assert_eq!(results.len(), 86);
+    let results: Vec<_> =
+      results.into_iter().map(|result| (result.index, (result.outcome.valid, result.outcome.error_code))).collect();
+    assert_eq!(results, expected);
+
+    // The last line needs no newline
+    let outcome = types::verified(SignatureScheme::Sr25519, true);
+    assert_eq!(verify_ndjson(valid.as_bytes()), vec![NdjsonResult { index: 0, outcome }]);
+  }
+
+  #[test]
+  #[cfg(feature = "ffi")]
+  fn test_ndjson_empty() {
+    assert_eq!(verify_ndjson(b""), vec![]);
+    assert_eq!(verify_ndjson(b"\n\r\n \n"), vec![]);
+  }
+
+  #[cfg(feature = "ffi")]
+  fn verify_signraw_c(result: &str, payload: &str) -> i32 {
+    verdict(verify_signraw(ADDRESS, result, payload.as_bytes()))
+  }
+
+  #[test]
+  #[cfg(feature = "ffi")]
+  fn test_signraw_result() {
+    // Captured from polkadot-js extension, signing the payload below
+    let result = format!(r#"{{"id":1,"signature":"{SIGNATURE}"}}"#);
+    let payload = &MESSAGE[7..MESSAGE.len() - 8];
+    assert_eq!(verify_signraw_c(&result, payload), crate::VALID);
+    assert_eq!(verify_signraw_c(&result, MESSAGE), crate::VALID);
+    assert_eq!(verify_signraw_c(&result, &payload.replace('c', "d")), crate::INVALID);
+
+    let result = format!(r#"{{"signature":"{SIGNATURE}","extra":[1,2]}}"#);
+    assert_eq!(verify_signraw_c(&result, payload), crate::VALID);
+  }
+
+  #[test]
+  #[cfg(feature = "ffi")]
+  fn test_signraw_result_malformed() {
+    assert_eq!(verify_signraw_c(r#"{"id":1}"#, "payload"), ERR_BAD_JSON);
+    assert_eq!(verify_signraw_c(r#"{"id":1,"signature":42}"#, "payload"), ERR_BAD_JSON);
+    assert_eq!(verify_signraw_c(r#"{"id":1,"signature":null}"#, "payload"), ERR_BAD_JSON);
+    assert_eq!(verify_signraw_c("0xf8ce", "payload"), ERR_BAD_JSON);
+    assert_eq!(verify_signraw_c(r#"{"signature":"0xzz"}"#, "payload"), crate::ERR_BAD_HEX);
+  }
+}
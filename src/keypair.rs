@@ -0,0 +1,71 @@
+//! Keypairs of any signing scheme.
+//!
+//! A keypair is parsed once and then signs any number of messages. The C
+//! API hands them out behind opaque handles, the Swift and Kotlin bindings
+//! as objects.
+
+use crate::api::VerifyError;
+use crate::error::Error;
+use crate::scheme::SignatureScheme;
+use crate::sign::sign;
+use sp_core::crypto::AccountId32;
+use sp_core::hashing::blake2_256;
+use sp_core::{ecdsa, ed25519, sr25519, Pair};
+
+/// A key of any signing scheme, which remembers its scheme.
+// Handles already box it, so the ed25519 variant's size costs nothing
+#[allow(clippy::large_enum_variant)]
+pub(crate) enum AnyPair {
+  Sr25519(sr25519::Pair),
+  Ed25519(ed25519::Pair),
+  Ecdsa(ecdsa::Pair),
+}
+
+impl AnyPair {
+  pub(crate) fn from_seed(seed: &[u8], scheme: SignatureScheme) -> Result<AnyPair, Error> {
+    let bad_seed = |_| VerifyError::InvalidSeed { reason: "seed is not a valid secret key".to_owned() };
+    Ok(match scheme {
+      SignatureScheme::Sr25519 => AnyPair::Sr25519(sr25519::Pair::from_seed_slice(seed).map_err(bad_seed)?),
+      SignatureScheme::Ed25519 => AnyPair::Ed25519(ed25519::Pair::from_seed_slice(seed).map_err(bad_seed)?),
+      SignatureScheme::Ecdsa => AnyPair::Ecdsa(ecdsa::Pair::from_seed_slice(seed).map_err(bad_seed)?),
+      SignatureScheme::Ethereum => {
+        return Err(VerifyError::UnknownScheme { reason: "ethereum keypairs are not supported".to_owned() }.into());
+      }
+    })
+  }
+
+  pub(crate) fn scheme(&self) -> SignatureScheme {
+    match self {
+      AnyPair::Sr25519(_) => SignatureScheme::Sr25519,
+      AnyPair::Ed25519(_) => SignatureScheme::Ed25519,
+      AnyPair::Ecdsa(_) => SignatureScheme::Ecdsa,
+    }
+  }
+
+  /// The raw public key: 32 bytes, or 33 compressed bytes for ECDSA.
+  pub(crate) fn public(&self) -> Vec<u8> {
+    match self {
+      AnyPair::Sr25519(pair) => pair.public().0.to_vec(),
+      AnyPair::Ed25519(pair) => pair.public().0.to_vec(),
+      AnyPair::Ecdsa(pair) => pair.public().0.to_vec(),
+    }
+  }
+
+  /// The account id: the public key itself, or its `blake2_256` hash for
+  /// ECDSA.
+  pub(crate) fn account(&self) -> AccountId32 {
+    match self {
+      AnyPair::Sr25519(pair) => pair.public().into(),
+      AnyPair::Ed25519(pair) => pair.public().into(),
+      AnyPair::Ecdsa(pair) => blake2_256(pair.public().as_ref()).into(),
+    }
+  }
+
+  pub(crate) fn sign(&self, message: &[u8]) -> String {
+    match self {
+      AnyPair::Sr25519(pair) => sign(pair, message),
+      AnyPair::Ed25519(pair) => sign(pair, message),
+      AnyPair::Ecdsa(pair) => sign(pair, message),
+    }
+  }
+}
@@ -0,0 +1,67 @@
+//! Optional diagnostics routed to a host-provided callback.
+//!
+//! Logging is off until `set_log_callback` installs a callback. While it is
+//! off, every log point costs a single atomic load and no formatting.
+
+use std::ffi::{CString, c_char};
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+/// Unexpected failures.
+pub const LOG_ERROR: i32 = 1;
+/// Rejected inputs.
+pub const LOG_WARN: i32 = 2;
+/// Verification verdicts.
+pub const LOG_INFO: i32 = 3;
+/// Intermediate parsing steps.
+pub const LOG_DEBUG: i32 = 4;
+
+/// Receives a level (`LOG_*`) and a NUL-terminated message that is only
+/// valid for the duration of the call.
+pub type LogCallback = extern "C" fn(level: i32, msg: *const c_char);
+
+static CALLBACK: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+/// Install `cb`, or turn logging off if it is None.
+#[cfg(feature = "ffi")]
+pub(crate) fn set_callback(cb: Option<LogCallback>) {
+  let raw = cb.map_or(ptr::null_mut(), |cb| cb as *mut ());
+  CALLBACK.store(raw, Ordering::Release);
+}
+
+/// Send a message to the installed callback, if any.
+///
+/// `message` is only evaluated when a callback is installed.
+pub(crate) fn log(level: i32, message: impl FnOnce() -> String) {
+  let raw = CALLBACK.load(Ordering::Acquire);
+  if raw.is_null() {
+    return;
+  }
+  // Safety: only `set_callback` stores into CALLBACK, always a LogCallback
+  let cb: LogCallback = unsafe { std::mem::transmute::<*mut (), LogCallback>(raw) };
+  let message = CString::new(message().replace('\0', "")).expect("NUL bytes were removed");
+  cb(level, message.as_ptr());
+}
+
+#[cfg(all(test, feature = "ffi"))]
+thread_local! {
+  // The callback is global but runs on the calling thread, so collecting
+  // per thread keeps concurrently running tests apart.
+  static RECEIVED: std::cell::RefCell<Vec<(i32, String)>> = const { std::cell::RefCell::new(Vec::new()) };
+}
+
+#[cfg(all(test, feature = "ffi"))]
+extern "C" fn collect(level: i32, msg: *const c_char) {
+  let msg = unsafe { std::ffi::CStr::from_ptr(msg) }.to_str().unwrap().to_owned();
+  RECEIVED.with(|received| received.borrow_mut().push((level, msg)));
+}
+
+/// Run `body`, returning what it logged on this thread. Every test that
+/// looks at logs goes through here, so they all install the same callback.
+#[cfg(all(test, feature = "ffi"))]
+pub(crate) fn capture(body: impl FnOnce()) -> Vec<(i32, String)> {
+  set_callback(Some(collect));
+  RECEIVED.with(|received| received.take());
+  body();
+  RECEIVED.with(|received| received.take())
+}
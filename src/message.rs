@@ -0,0 +1,191 @@
+//! Message conventions used by Polkadot wallets.
+
+use crate::api::VerifyError;
+use crate::error::Error;
+use crate::log::{self, LOG_DEBUG};
+use crate::options::Options;
+use crate::parse::decode_base64;
+use blake2::digest::consts::U32;
+use blake2::{Blake2b, Digest};
+use polkadot_sig_verifier_core::hex_reason;
+use std::borrow::Cow;
+use unicode_normalization::{is_nfc, UnicodeNormalization};
+
+/// Prefix polkadot-js adds to raw messages before signing.
+pub(crate) const BYTES_PREFIX: &[u8] = b"<Bytes>";
+/// Suffix polkadot-js adds to raw messages before signing.
+pub(crate) const BYTES_SUFFIX: &[u8] = b"</Bytes>";
+/// Longest message polkadot-js signs directly, longer ones are signed as
+/// their `blake2_256` hash.
+pub(crate) const MAX_UNHASHED_LEN: usize = 256;
+
+/// The 32-byte BLAKE2b hash of `data`, as `sp_core::hashing::blake2_256`.
+pub(crate) fn blake2_256(data: &[u8]) -> [u8; 32] {
+  Blake2b::<U32>::digest(data).into()
+}
+
+/// Whether `message` is already wrapped in `<Bytes>...</Bytes>`.
+pub(crate) fn is_wrapped(message: &[u8]) -> bool {
+  message.len() >= BYTES_PREFIX.len() + BYTES_SUFFIX.len()
+    && message.starts_with(BYTES_PREFIX)
+    && message.ends_with(BYTES_SUFFIX)
+}
+
+/// Wrap `message` in `<Bytes>...</Bytes>` the way polkadot-js `u8aWrapBytes`
+/// does, leaving already wrapped messages untouched.
+pub(crate) fn wrap_bytes(message: &[u8]) -> Cow<'_, [u8]> {
+  if is_wrapped(message) {
+    return Cow::Borrowed(message);
+  }
+  Cow::Owned([BYTES_PREFIX, message, BYTES_SUFFIX].concat())
+}
+
+/// Replace messages longer than `MAX_UNHASHED_LEN` with their `blake2_256`
+/// hash, as polkadot-js does before signing.
+pub(crate) fn hash_if_long(message: Cow<'_, [u8]>) -> Cow<'_, [u8]> {
+  if message.len() <= MAX_UNHASHED_LEN {
+    return message;
+  }
+  log::log(LOG_DEBUG, || format!("{} byte message is verified as its blake2-256 hash", message.len()));
+  Cow::Owned(blake2_256(&message).to_vec())
+}
+
+/// Strip `<Bytes>...</Bytes>` from a wrapped message, leaving others
+/// untouched.
+pub(crate) fn unwrap_bytes(message: &[u8]) -> &[u8] {
+  if !is_wrapped(message) {
+    return message;
+  }
+  &message[BYTES_PREFIX.len()..message.len() - BYTES_SUFFIX.len()]
+}
+
+/// Decode a hex-encoded message, with or without 0x prefix unless strict.
+pub(crate) fn decode_hex(message: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+  options.check_hex_prefix(message, "message")?;
+  let digits = message.strip_prefix(b"0x").unwrap_or(message);
+  let decoded = hex::decode(digits).map_err(|e| VerifyError::InvalidMessageHex { reason: hex_reason(e, "message") })?;
+
+  log::log(LOG_DEBUG, || format!("message decoded from hex to {} bytes", decoded.len()));
+  Ok(decoded)
+}
+
+/// NFC-normalize the UTF-8 text of `message`, keeping any `<Bytes>`
+/// wrapper. Messages that are not UTF-8 are left untouched.
+///
+/// In strict mode a message that is not already normalized fails with
+/// `ERR_NOT_STRICT`.
+pub(crate) fn nfc_normalize<'a>(message: Cow<'a, [u8]>, options: &Options) -> Result<Cow<'a, [u8]>, Error> {
+  let Ok(text) = std::str::from_utf8(unwrap_bytes(&message)) else {
+    return Ok(message);
+  };
+  if is_nfc(text) {
+    return Ok(message);
+  }
+  if options.strict {
+    let reason = "message is not NFC-normalized, which strict mode does not fix".to_owned();
+    return Err(VerifyError::NotStrict { reason }.into());
+  }
+
+  log::log(LOG_DEBUG, || "message NFC-normalized".to_owned());
+  let normalized: String = text.nfc().collect();
+  Ok(if is_wrapped(&message) {
+    Cow::Owned([BYTES_PREFIX, normalized.as_bytes(), BYTES_SUFFIX].concat())
+  } else {
+    Cow::Owned(normalized.into_bytes())
+  })
+}
+
+/// The bytes a signature over `message` covers under `options`: wrapped in
+/// `<Bytes>` if asked, NFC-normalized if asked, then hashed if long unless
+/// asked not to.
+///
+/// Verification and `sign_wrapped` both go through here, so what one signs
+/// the other accepts.
+pub(crate) fn canonicalize_message<'a>(message: &'a [u8], options: &Options) -> Result<Cow<'a, [u8]>, Error> {
+  let message = if options.wrap_bytes { wrap_bytes(message) } else { message.into() };
+  // Normalizes inside the wrapper, so the order with wrapping is irrelevant
+  let message = if options.nfc_normalize { nfc_normalize(message, options)? } else { message };
+  Ok(if options.skip_long_message_hash { message } else { hash_if_long(message) })
+}
+
+/// Decode the message argument as `options` ask: from hex, from base64 or
+/// not at all.
+pub(crate) fn decode<'a>(message: &'a [u8], options: &Options) -> Result<Cow<'a, [u8]>, Error> {
+  Ok(if options.message_is_hex {
+    Cow::Owned(decode_hex(message, options)?)
+  } else if options.message_is_base64 {
+    Cow::Owned(decode_base64(message, "message")?)
+  } else {
+    Cow::Borrowed(message)
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{ERR_BAD_MESSAGE_HEX, ERR_NOT_STRICT};
+
+  #[test]
+  fn test_wrap_bytes() {
+    assert_eq!(&*wrap_bytes(b"hello"), b"<Bytes>hello</Bytes>");
+    assert_eq!(&*wrap_bytes(b""), b"<Bytes></Bytes>");
+    assert!(matches!(wrap_bytes(b"<Bytes>hello</Bytes>"), Cow::Borrowed(_)));
+  }
+
+  #[test]
+  fn test_unwrap_bytes() {
+    assert_eq!(unwrap_bytes(b"<Bytes>hello</Bytes>"), b"hello");
+    assert_eq!(unwrap_bytes(b"<Bytes><Bytes>hello</Bytes></Bytes>"), b"<Bytes>hello</Bytes>");
+    assert_eq!(unwrap_bytes(b"<Bytes>hello"), b"<Bytes>hello");
+    assert_eq!(unwrap_bytes(b"<Bytes></Bytes>"), b"");
+  }
+
+  #[test]
+  fn test_hash_if_long() {
+    let boundary = vec![7u8; MAX_UNHASHED_LEN];
+    assert!(matches!(hash_if_long(Cow::Borrowed(&boundary)), Cow::Borrowed(_)));
+
+    let long = vec![7u8; MAX_UNHASHED_LEN + 1];
+    assert_eq!(&*hash_if_long(Cow::Borrowed(&long)), sp_core::hashing::blake2_256(&long));
+  }
+
+  #[test]
+  fn test_is_wrapped() {
+    assert!(is_wrapped(b"<Bytes></Bytes>"));
+    assert!(!is_wrapped(b"<Bytes>hello"));
+    assert!(!is_wrapped(b"hello</Bytes>"));
+    // Overlapping prefix and suffix is not a wrapped message
+    assert!(!is_wrapped(b"<Bytes>"));
+  }
+
+  #[test]
+  fn test_decode_hex() {
+    assert_eq!(decode_hex(b"0x00ff", &Options::default()).unwrap(), [0x00, 0xff]);
+    assert_eq!(decode_hex(b"00FF", &Options::default()).unwrap(), [0x00, 0xff]);
+    assert!(decode_hex(b"", &Options::default()).unwrap().is_empty());
+
+    let err = decode_hex(b"0xabc", &Options::default()).unwrap_err();
+    assert_eq!((err.code, err.message.as_str()), (ERR_BAD_MESSAGE_HEX, "message hex had odd length"));
+    let err = decode_hex(b"hello!", &Options::default()).unwrap_err();
+    assert_eq!((err.code, err.message.as_str()), (ERR_BAD_MESSAGE_HEX, "message hex has invalid character 'h' at position 0"));
+  }
+
+  #[test]
+  fn test_nfc_normalize() {
+    let decomposed = "cafe\u{301}".as_bytes();
+    let precomposed = "caf\u{e9}".as_bytes();
+    let options = Options::default();
+    assert_eq!(&*nfc_normalize(decomposed.into(), &options).unwrap(), precomposed);
+    assert!(matches!(nfc_normalize(precomposed.into(), &options).unwrap(), Cow::Borrowed(_)));
+
+    let wrapped = [BYTES_PREFIX, decomposed, BYTES_SUFFIX].concat();
+    assert_eq!(&*nfc_normalize(wrapped.into(), &options).unwrap(), &*wrap_bytes(precomposed));
+
+    // Binary messages are not text and stay as they are
+    assert_eq!(&*nfc_normalize([0xff, 0xfe].as_slice().into(), &options).unwrap(), [0xff, 0xfe]);
+
+    let strict = Options { strict: true, ..Options::default() };
+    assert_eq!(nfc_normalize(decomposed.into(), &strict).unwrap_err().code, ERR_NOT_STRICT);
+    assert!(nfc_normalize(precomposed.into(), &strict).is_ok());
+  }
+}
@@ -0,0 +1,334 @@
+//! Swift and Kotlin bindings, built with the `uniffi` feature.
+//!
+//! `cargo build --features uniffi` embeds the uniffi scaffolding in the
+//! library, and the bundled `uniffi-bindgen` generates each language's
+//! bindings from it:
+//!
+//! ```text
+//! cargo run --features uniffi --bin uniffi-bindgen -- generate \
+//!   --library target/debug/libpolkadot_sig_verifier.so --language kotlin --out-dir target/uniffi
+//! ```
+
+use crate::api::{self, Ss58Issue};
+use crate::error::Error;
+use crate::keypair::AnyPair;
+use crate::parse::{encode_ss58, MAX_SS58_PREFIX};
+use crate::scheme::SignatureScheme;
+use crate::sign::{decode_seed, pair_from_mnemonic};
+use crate::types;
+use std::sync::Arc;
+
+/// [`api::VerifyError`], with sizes as `u64` since uniffi has no `usize`.
+#[derive(uniffi::Error, thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum VerifyError {
+  #[error("{reason}")]
+  InvalidSs58 { reason: Ss58Issue },
+  #[error("{reason}")]
+  InvalidAddress { reason: String },
+  #[error("SS58 prefix {prefix} is above {MAX_SS58_PREFIX}")]
+  PrefixOutOfRange { prefix: u16 },
+  #[error("{}", api::key_length_message(*.found as usize))]
+  WrongKeyLength { found: u64 },
+  #[error("first address: {reason}")]
+  InvalidFirstAddress { reason: String },
+  #[error("second address: {reason}")]
+  InvalidSecondAddress { reason: String },
+  #[error("{reason}")]
+  WrongPublicKeyLength { reason: String },
+  #[error("{reason}")]
+  InvalidSignatureHex { reason: String },
+  #[error("{reason}")]
+  InvalidHex { reason: String },
+  #[error("{reason}")]
+  InvalidMessageHex { reason: String },
+  #[error("{reason}")]
+  InvalidBase64 { reason: String },
+  #[error("signature is {found} bytes, expected {expected}")]
+  WrongSignatureLength { found: u64, expected: u64 },
+  #[error("{reason}")]
+  WrongHexLength { reason: String },
+  #[error("{reason}")]
+  InvalidSignaturePrefix { reason: String },
+  #[error("{reason}")]
+  InvalidMultiSignature { reason: String },
+  #[error("{reason}")]
+  NonCanonicalSignature { reason: String },
+  #[error("signature does not match address and message")]
+  SignatureMismatch,
+  #[error("{}", api::network_message(*.prefix, .allowed))]
+  DisallowedNetwork { prefix: Option<u16>, allowed: Vec<u16> },
+  #[error("{reason}")]
+  ConflictingOptions { reason: String },
+  #[error("{reason}")]
+  InvalidOption { reason: String },
+  #[error("{reason}")]
+  NotStrict { reason: String },
+  #[error("{reason}")]
+  UnknownScheme { reason: String },
+  #[error("{reason}")]
+  SchemeNotSupported { reason: String },
+  #[error("{reason}")]
+  NoMatchingScheme { reason: String },
+  #[error("{reason}")]
+  InvalidEthereumAddress { reason: String },
+  #[error("address has an invalid EIP-55 checksum")]
+  EthereumChecksumMismatch,
+  #[error("{reason}")]
+  InvalidDidKey { reason: String },
+  #[error("{reason}")]
+  UnsupportedKeyType { reason: String },
+  #[error("{reason}")]
+  InvalidJson { reason: String },
+  #[error("{reason}")]
+  SiwsMalformed { reason: String },
+  #[error("{reason}")]
+  SiwsAddressMismatch { reason: String },
+  #[error("{reason}")]
+  SiwsDomainMismatch { reason: String },
+  #[error("{reason}")]
+  SiwsNonceMismatch { reason: String },
+  #[error("{reason}")]
+  Expired { reason: String },
+  #[error("{reason}")]
+  NotYetValid { reason: String },
+  #[error("{reason}")]
+  TimestampNotFound { reason: String },
+  #[error("{reason}")]
+  Replayed { reason: String },
+  #[error("{reason}")]
+  HardDerivation { reason: String },
+  #[error("{reason}")]
+  InvalidDerivationPath { reason: String },
+  #[error("seed is {found} bytes, expected 32")]
+  WrongSeedLength { found: u64 },
+  #[error("{reason}")]
+  InvalidSeed { reason: String },
+  #[error("{reason}")]
+  InvalidMnemonic { reason: String },
+  #[error("{reason}")]
+  UnknownMnemonicWord { reason: String },
+  #[error("mnemonic checksum does not match its words")]
+  MnemonicChecksumMismatch,
+  #[error("{reason}")]
+  UnknownDevAccount { reason: String },
+  #[error("extrinsic is unsigned")]
+  NotSigned,
+  #[error("{reason}")]
+  InvalidExtrinsic { reason: String },
+  #[error("{reason}")]
+  InvalidEra { reason: String },
+  #[error("{name} pointer is NULL")]
+  NullPointer { name: String },
+  #[error("{reason}")]
+  MissingArgument { reason: String },
+  #[error("{reason}")]
+  InvalidUtf8 { reason: String },
+  #[error("{reason}")]
+  InvalidUtf16 { reason: String },
+  #[error("{reason}")]
+  InvalidHandle { reason: String },
+  #[error("{reason}")]
+  FileNotFound { reason: String },
+  #[error("{reason}")]
+  FilePermissionDenied { reason: String },
+  #[error("{reason}")]
+  FileIo { reason: String },
+  #[error("{reason}")]
+  ThreadPool { reason: String },
+  #[error("{reason}")]
+  Panic { reason: String },
+}
+
+impl From<api::VerifyError> for VerifyError {
+  fn from(error: api::VerifyError) -> Self {
+    match error {
+      api::VerifyError::InvalidSs58 { reason } => VerifyError::InvalidSs58 { reason },
+      api::VerifyError::InvalidAddress { reason } => VerifyError::InvalidAddress { reason },
+      api::VerifyError::PrefixOutOfRange { prefix } => VerifyError::PrefixOutOfRange { prefix },
+      api::VerifyError::WrongKeyLength { found } => VerifyError::WrongKeyLength { found: found as u64 },
+      api::VerifyError::InvalidFirstAddress { reason } => VerifyError::InvalidFirstAddress { reason },
+      api::VerifyError::InvalidSecondAddress { reason } => VerifyError::InvalidSecondAddress { reason },
+      api::VerifyError::WrongPublicKeyLength { reason } => VerifyError::WrongPublicKeyLength { reason },
+      api::VerifyError::InvalidSignatureHex { reason } => VerifyError::InvalidSignatureHex { reason },
+      api::VerifyError::InvalidHex { reason } => VerifyError::InvalidHex { reason },
+      api::VerifyError::InvalidMessageHex { reason } => VerifyError::InvalidMessageHex { reason },
+      api::VerifyError::InvalidBase64 { reason } => VerifyError::InvalidBase64 { reason },
+      api::VerifyError::WrongSignatureLength { found, expected } => {
+        VerifyError::WrongSignatureLength { found: found as u64, expected: expected as u64 }
+      }
+      api::VerifyError::WrongHexLength { reason } => VerifyError::WrongHexLength { reason },
+      api::VerifyError::InvalidSignaturePrefix { reason } => VerifyError::InvalidSignaturePrefix { reason },
+      api::VerifyError::InvalidMultiSignature { reason } => VerifyError::InvalidMultiSignature { reason },
+      api::VerifyError::NonCanonicalSignature { reason } => VerifyError::NonCanonicalSignature { reason },
+      api::VerifyError::SignatureMismatch => VerifyError::SignatureMismatch,
+      api::VerifyError::DisallowedNetwork { prefix, allowed } => VerifyError::DisallowedNetwork { prefix, allowed },
+      api::VerifyError::ConflictingOptions { reason } => VerifyError::ConflictingOptions { reason },
+      api::VerifyError::InvalidOption { reason } => VerifyError::InvalidOption { reason },
+      api::VerifyError::NotStrict { reason } => VerifyError::NotStrict { reason },
+      api::VerifyError::UnknownScheme { reason } => VerifyError::UnknownScheme { reason },
+      api::VerifyError::SchemeNotSupported { reason } => VerifyError::SchemeNotSupported { reason },
+      api::VerifyError::NoMatchingScheme { reason } => VerifyError::NoMatchingScheme { reason },
+      api::VerifyError::InvalidEthereumAddress { reason } => VerifyError::InvalidEthereumAddress { reason },
+      api::VerifyError::EthereumChecksumMismatch => VerifyError::EthereumChecksumMismatch,
+      api::VerifyError::InvalidDidKey { reason } => VerifyError::InvalidDidKey { reason },
+      api::VerifyError::UnsupportedKeyType { reason } => VerifyError::UnsupportedKeyType { reason },
+      api::VerifyError::InvalidJson { reason } => VerifyError::InvalidJson { reason },
+      api::VerifyError::SiwsMalformed { reason } => VerifyError::SiwsMalformed { reason },
+      api::VerifyError::SiwsAddressMismatch { reason } => VerifyError::SiwsAddressMismatch { reason },
+      api::VerifyError::SiwsDomainMismatch { reason } => VerifyError::SiwsDomainMismatch { reason },
+      api::VerifyError::SiwsNonceMismatch { reason } => VerifyError::SiwsNonceMismatch { reason },
+      api::VerifyError::Expired { reason } => VerifyError::Expired { reason },
+      api::VerifyError::NotYetValid { reason } => VerifyError::NotYetValid { reason },
+      api::VerifyError::TimestampNotFound { reason } => VerifyError::TimestampNotFound { reason },
+      api::VerifyError::Replayed { reason } => VerifyError::Replayed { reason },
+      api::VerifyError::HardDerivation { reason } => VerifyError::HardDerivation { reason },
+      api::VerifyError::InvalidDerivationPath { reason } => VerifyError::InvalidDerivationPath { reason },
+      api::VerifyError::WrongSeedLength { found } => VerifyError::WrongSeedLength { found: found as u64 },
+      api::VerifyError::InvalidSeed { reason } => VerifyError::InvalidSeed { reason },
+      api::VerifyError::InvalidMnemonic { reason } => VerifyError::InvalidMnemonic { reason },
+      api::VerifyError::UnknownMnemonicWord { reason } => VerifyError::UnknownMnemonicWord { reason },
+      api::VerifyError::MnemonicChecksumMismatch => VerifyError::MnemonicChecksumMismatch,
+      api::VerifyError::UnknownDevAccount { reason } => VerifyError::UnknownDevAccount { reason },
+      api::VerifyError::NotSigned => VerifyError::NotSigned,
+      api::VerifyError::InvalidExtrinsic { reason } => VerifyError::InvalidExtrinsic { reason },
+      api::VerifyError::InvalidEra { reason } => VerifyError::InvalidEra { reason },
+      api::VerifyError::NullPointer { name } => VerifyError::NullPointer { name },
+      api::VerifyError::MissingArgument { reason } => VerifyError::MissingArgument { reason },
+      api::VerifyError::InvalidUtf8 { reason } => VerifyError::InvalidUtf8 { reason },
+      api::VerifyError::InvalidUtf16 { reason } => VerifyError::InvalidUtf16 { reason },
+      api::VerifyError::InvalidHandle { reason } => VerifyError::InvalidHandle { reason },
+      api::VerifyError::FileNotFound { reason } => VerifyError::FileNotFound { reason },
+      api::VerifyError::FilePermissionDenied { reason } => VerifyError::FilePermissionDenied { reason },
+      api::VerifyError::FileIo { reason } => VerifyError::FileIo { reason },
+      api::VerifyError::ThreadPool { reason } => VerifyError::ThreadPool { reason },
+      api::VerifyError::Panic { reason } => VerifyError::Panic { reason },
+    }
+  }
+}
+
+impl From<Error> for VerifyError {
+  fn from(error: Error) -> Self {
+    api::VerifyError::from(error).into()
+  }
+}
+
+/// [`types::VerificationOutcome`] as a uniffi record, since the core crate
+/// that defines it does not depend on uniffi.
+#[derive(uniffi::Record, Debug, Clone, PartialEq, Eq)]
+pub struct VerificationOutcome {
+  pub valid: bool,
+  pub scheme: Option<String>,
+  pub error_code: Option<i32>,
+  pub error_message: Option<String>,
+}
+
+impl From<types::VerificationOutcome> for VerificationOutcome {
+  fn from(outcome: types::VerificationOutcome) -> Self {
+    VerificationOutcome {
+      valid: outcome.valid,
+      scheme: outcome.scheme,
+      error_code: outcome.error_code,
+      error_message: outcome.error_message,
+    }
+  }
+}
+
+/// Verify a Polkadot SR25519 signature, as [`api::verify`] does.
+///
+/// The message is verified as the given bytes, without `<Bytes>` wrapping.
+/// Returns the outcome whether or not the signature matches, and throws if
+/// the address or signature is malformed, so `error_code` is never set.
+#[uniffi::export]
+pub fn verify_signature(
+  address: String,
+  signature_hex: String,
+  message: Vec<u8>,
+) -> Result<VerificationOutcome, VerifyError> {
+  match api::verify(&address, &signature_hex, &message) {
+    Ok(()) => Ok(types::verified(SignatureScheme::Sr25519, true).into()),
+    Err(api::VerifyError::SignatureMismatch) => Ok(types::verified(SignatureScheme::Sr25519, false).into()),
+    Err(error) => Err(error.into()),
+  }
+}
+
+/// A keypair, as behind the C API's `Keypair` handles. The secret key is
+/// zeroed when the foreign object is released.
+#[derive(uniffi::Object)]
+pub struct Keypair(AnyPair);
+
+#[uniffi::export]
+impl Keypair {
+  /// Load a keypair from a hex seed, as `keypair_from_seed_with_scheme`.
+  #[uniffi::constructor]
+  pub fn from_seed(seed_hex: String, scheme: SignatureScheme) -> Result<Arc<Self>, VerifyError> {
+    Ok(Arc::new(Keypair(AnyPair::from_seed(&decode_seed(&seed_hex)?[..], scheme)?)))
+  }
+
+  /// Load an sr25519 keypair from a BIP39 mnemonic and derivation path,
+  /// as `keypair_from_mnemonic`.
+  #[uniffi::constructor]
+  pub fn from_mnemonic(mnemonic: String, derivation_path: String) -> Result<Arc<Self>, VerifyError> {
+    Ok(Arc::new(Keypair(AnyPair::Sr25519(pair_from_mnemonic(&mnemonic, &derivation_path)?))))
+  }
+
+  pub fn scheme(&self) -> SignatureScheme {
+    self.0.scheme()
+  }
+
+  /// The 0x-prefixed hex public key, as `keypair_public_hex`.
+  pub fn public_hex(&self) -> String {
+    format!("0x{}", hex::encode(self.0.public()))
+  }
+
+  /// The SS58 address on network `prefix`, as `keypair_ss58`.
+  pub fn ss58(&self, prefix: u16) -> Result<String, VerifyError> {
+    Ok(encode_ss58(self.0.account().as_ref(), prefix)?)
+  }
+
+  /// Sign `message` as `keypair_sign` does, returning the 0x-prefixed hex
+  /// signature.
+  pub fn sign(&self, message: Vec<u8>) -> String {
+    self.0.sign(&message)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use sp_core::crypto::DEV_PHRASE;
+
+  const ADDRESS: &str = "5HbqogsYaK54pN3QAgSi4t3Asqditt1X4P7cSBuDt2hPF2BR";
+  const SIGNATURE: &str = "0xf8ce1b80e76bf48a30610d0d3c2c9c8dbfdcb28a9aa8f56ac038d520ff17445ff3ce94809f8479a1224ef0d823d035022a9db2e72bd6c0d2f0244f766dec908b";
+  const MESSAGE: &str = "<Bytes>c15335d817e3d3d912d30f5a18a30c30162abc364cd0876d3a94af568d3c8c14</Bytes>";
+  const ALICE: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+
+  fn verify(address: &str, signature: &str, message: &[u8]) -> Result<VerificationOutcome, VerifyError> {
+    verify_signature(address.to_owned(), signature.to_owned(), message.to_vec())
+  }
+
+  #[test]
+  fn test_verify_signature() {
+    let outcome = verify(ADDRESS, SIGNATURE, MESSAGE.as_bytes()).unwrap();
+    assert!(outcome.valid);
+    assert_eq!(outcome.scheme.as_deref(), Some("sr25519"));
+    assert!(!verify(ADDRESS, SIGNATURE, b"tampered").unwrap().valid);
+
+    let error = verify(ADDRESS, &SIGNATURE[..128], MESSAGE.as_bytes()).unwrap_err();
+    assert_eq!(error, VerifyError::WrongSignatureLength { found: 63, expected: 64 });
+    let error = verify(&ADDRESS.replace("hPF2BR", "hPF2BS"), SIGNATURE, MESSAGE.as_bytes()).unwrap_err();
+    assert_eq!(error, VerifyError::InvalidSs58 { reason: Ss58Issue::BadChecksum });
+  }
+
+  #[test]
+  fn test_keypair() {
+    let keypair = Keypair::from_mnemonic(DEV_PHRASE.to_owned(), "//Alice".to_owned()).unwrap();
+    assert_eq!(keypair.scheme(), SignatureScheme::Sr25519);
+    assert_eq!(keypair.ss58(42).unwrap(), ALICE);
+    let signature = keypair.sign(b"hello".to_vec());
+    assert!(verify(ALICE, &signature, b"hello").unwrap().valid);
+    assert_eq!(keypair.ss58(16384).unwrap_err(), VerifyError::PrefixOutOfRange { prefix: 16384 });
+
+    let error = Keypair::from_seed("0x1234".to_owned(), SignatureScheme::Ed25519).err().unwrap();
+    assert_eq!(error, VerifyError::WrongSeedLength { found: 2 });
+  }
+}
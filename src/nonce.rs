@@ -0,0 +1,180 @@
+//! Replay protection by remembering recently used nonces.
+//!
+//! A cache records each (account, nonce) pair whose signature verified and
+//! rejects the pair again until its TTL runs out.
+
+use crate::api::VerifyError;
+use crate::error::Error;
+use crate::log::{self, LOG_DEBUG, LOG_WARN};
+use crate::options::Options;
+use crate::verify;
+use std::collections::{HashSet, VecDeque};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Seconds since the Unix epoch, replaceable in tests.
+pub(crate) type Clock = Box<dyn Fn() -> u64 + Send + Sync>;
+
+pub(crate) fn system_clock() -> u64 {
+  SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs())
+}
+
+/// A used nonce: the account id from the address and the nonce itself.
+type Key = ([u8; 32], String);
+
+struct Entries {
+  /// Pairs recorded within the TTL.
+  seen: HashSet<Key>,
+  /// The same pairs oldest first, with the time they were recorded.
+  order: VecDeque<(u64, Key)>,
+}
+
+impl Entries {
+  fn pop_oldest(&mut self) {
+    if let Some((_, key)) = self.order.pop_front() {
+      self.seen.remove(&key);
+    }
+  }
+}
+
+pub(crate) struct NonceCacheState {
+  capacity: usize,
+  ttl_secs: u64,
+  clock: Clock,
+  entries: Mutex<Entries>,
+}
+
+impl NonceCacheState {
+  pub fn new(capacity: usize, ttl_secs: u64, clock: Clock) -> Result<NonceCacheState, Error> {
+    if capacity == 0 {
+      return Err(VerifyError::InvalidOption { reason: "nonce cache capacity must be at least 1".to_owned() }.into());
+    }
+    let entries = Entries { seen: HashSet::new(), order: VecDeque::new() };
+    Ok(NonceCacheState { capacity, ttl_secs, clock, entries: Mutex::new(entries) })
+  }
+
+  /// Record `key`, failing with `ERR_REPLAYED` if it was recorded within
+  /// the TTL. The check and the insert happen under one lock.
+  pub fn record(&self, key: Key) -> Result<(), Error> {
+    let now = (self.clock)();
+    let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+
+    while entries.order.front().is_some_and(|&(recorded_at, _)| now.saturating_sub(recorded_at) >= self.ttl_secs) {
+      entries.pop_oldest();
+    }
+    if entries.seen.contains(&key) {
+      let reason = format!("nonce {:?} was already used by this address", key.1);
+      return Err(VerifyError::Replayed { reason }.into());
+    }
+    while entries.seen.len() >= self.capacity {
+      log::log(LOG_WARN, || "nonce cache is full, forgetting the oldest nonce before its TTL".to_owned());
+      entries.pop_oldest();
+    }
+
+    entries.seen.insert(key.clone());
+    entries.order.push_back((now, key));
+    Ok(())
+  }
+}
+
+/// Verify, then record the nonce if the signature is valid.
+pub(crate) fn verify_and_record(
+  cache: &NonceCacheState,
+  address: &str,
+  signature: &str,
+  message: &str,
+  nonce: &str,
+) -> Result<bool, Error> {
+  let outcome = verify::verify_sr25519(address, signature, message.as_bytes(), &Options::default())?;
+  if outcome.valid == 0 {
+    return Ok(false);
+  }
+  cache.record((outcome.public_key, nonce.to_owned()))?;
+  log::log(LOG_DEBUG, || format!("recorded nonce {nonce:?} for {address}"));
+  Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::verify::verdict;
+  use crate::{ERR_REPLAYED, INVALID, VALID};
+  use sp_core::crypto::Ss58Codec;
+  use sp_core::{sr25519, Pair};
+  use std::sync::atomic::{AtomicU64, Ordering};
+  use std::sync::Arc;
+
+  /// A cache whose clock is the returned counter.
+  fn cache_with_clock(capacity: usize, ttl_secs: u64) -> (Arc<NonceCacheState>, Arc<AtomicU64>) {
+    let now = Arc::new(AtomicU64::new(1_000));
+    let clock = Arc::clone(&now);
+    let state = NonceCacheState::new(capacity, ttl_secs, Box::new(move || clock.load(Ordering::Relaxed))).unwrap();
+    (Arc::new(state), now)
+  }
+
+  fn verify(cache: &NonceCacheState, signer: &str, nonce: &str) -> i32 {
+    let pair = sr25519::Pair::from_string(signer, None).unwrap();
+    let message = format!("login:{nonce}");
+    let signature = hex::encode(pair.sign(message.as_bytes()));
+    verdict(verify_and_record(cache, &pair.public().to_ss58check(), &signature, &message, nonce))
+  }
+
+  #[test]
+  fn test_replay_rejected() {
+    let (cache, _) = cache_with_clock(16, 60);
+    assert_eq!(verify(&cache, "//Alice", "n1"), VALID);
+    assert_eq!(verify(&cache, "//Alice", "n1"), ERR_REPLAYED);
+    // Other nonces and other addresses are unaffected
+    assert_eq!(verify(&cache, "//Alice", "n2"), VALID);
+    assert_eq!(verify(&cache, "//Bob", "n1"), VALID);
+  }
+
+  #[test]
+  fn test_invalid_signature_is_not_recorded() {
+    let (cache, _) = cache_with_clock(16, 60);
+    let pair = sr25519::Pair::from_string("//Alice", None).unwrap();
+    let signature = hex::encode(pair.sign(b"something else"));
+    let code = verdict(verify_and_record(&cache, &pair.public().to_ss58check(), &signature, "login:n1", "n1"));
+    assert_eq!(code, INVALID);
+    assert_eq!(verify(&cache, "//Alice", "n1"), VALID);
+  }
+
+  #[test]
+  fn test_ttl_expiry() {
+    let (cache, now) = cache_with_clock(16, 60);
+    assert_eq!(verify(&cache, "//Alice", "n1"), VALID);
+    now.fetch_add(59, Ordering::Relaxed);
+    assert_eq!(verify(&cache, "//Alice", "n1"), ERR_REPLAYED);
+    now.fetch_add(1, Ordering::Relaxed);
+    assert_eq!(verify(&cache, "//Alice", "n1"), VALID);
+    assert_eq!(verify(&cache, "//Alice", "n1"), ERR_REPLAYED);
+  }
+
+  #[test]
+  fn test_capacity_evicts_oldest() {
+    let (cache, _) = cache_with_clock(2, 60);
+    assert_eq!(verify(&cache, "//Alice", "n1"), VALID);
+    assert_eq!(verify(&cache, "//Alice", "n2"), VALID);
+    assert_eq!(verify(&cache, "//Alice", "n3"), VALID);
+    // n1 was evicted to make room, n2 and n3 are remembered
+    assert_eq!(verify(&cache, "//Alice", "n3"), ERR_REPLAYED);
+    assert_eq!(verify(&cache, "//Alice", "n2"), ERR_REPLAYED);
+    assert_eq!(verify(&cache, "//Alice", "n1"), VALID);
+  }
+
+  #[test]
+  fn test_concurrent_replay() {
+    let (cache, _) = cache_with_clock(64, 60);
+    let results: Vec<i32> = (0..8)
+      .map(|_| {
+        let cache = Arc::clone(&cache);
+        std::thread::spawn(move || verify(&cache, "//Alice", "race"))
+      })
+      .collect::<Vec<_>>()
+      .into_iter()
+      .map(|thread| thread.join().unwrap())
+      .collect();
+    assert_eq!(results.iter().filter(|&&code| code == VALID).count(), 1);
+    assert_eq!(results.iter().filter(|&&code| code == ERR_REPLAYED).count(), 7);
+  }
+}
@@ -0,0 +1,201 @@
+//! Per-call verification options.
+
+#[cfg(feature = "ffi")]
+use crate::abi::VerifyOptions;
+#[cfg(feature = "ffi")]
+use crate::api::Verifier;
+use crate::api::VerifyError;
+use crate::error::Error;
+use crate::{
+  OPT_ALLOW_NON_CANONICAL_ECDSA, OPT_MESSAGE_BASE64, OPT_MESSAGE_IS_HEX, OPT_NFC_NORMALIZE, OPT_SIGNATURE_BASE64,
+  OPT_SKIP_LONG_MESSAGE_HASH, OPT_STRIP_SCHEME_PREFIX, OPT_WRAP_BYTES,
+};
+#[cfg(feature = "ffi")]
+use std::mem::size_of;
+#[cfg(feature = "ffi")]
+use std::{ptr, slice};
+
+/// Caller options after validation, borrowing from the caller's struct.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Options<'a> {
+  /// Wrap the message in `<Bytes>...</Bytes>` unless it already is.
+  pub wrap_bytes: bool,
+  /// Verify messages longer than 256 bytes directly rather than as their
+  /// `blake2_256` hash.
+  pub skip_long_message_hash: bool,
+  /// NFC-normalize UTF-8 messages, inside any `<Bytes>` wrapper.
+  pub nfc_normalize: bool,
+  /// The message argument is hex; verify over the decoded bytes.
+  pub message_is_hex: bool,
+  /// The message argument is base64; verify over the decoded bytes.
+  pub message_is_base64: bool,
+  /// The signature argument is base64 rather than hex.
+  pub signature_is_base64: bool,
+  /// Accept 65-byte sr25519 signatures prefixed with a scheme byte.
+  pub strip_scheme_prefix: bool,
+  /// Normalize high-s ECDSA signatures instead of rejecting them. Only the
+  /// sp-core backend verifies ECDSA.
+  #[cfg(feature = "sp-core")]
+  pub allow_non_canonical_ecdsa: bool,
+  /// SS58 prefixes accepted for the address, `None` accepts any.
+  pub allowed_prefixes: Option<&'a [u16]>,
+  /// Reject inputs that would need normalizing, see `VerifyOptions::strict`.
+  pub strict: bool,
+}
+
+impl<'a> Options<'a> {
+  /// Build options from `OPT_*` flags, failing with `ERR_INVALID_OPTIONS`
+  /// if they contradict each other.
+  pub fn from_flags(flags: u32, allowed_prefixes: Option<&'a [u16]>, strict: bool) -> Result<Self, Error> {
+    let conflict = |reason: String| Err(VerifyError::ConflictingOptions { reason }.into());
+    if flags & OPT_MESSAGE_IS_HEX != 0 && flags & OPT_MESSAGE_BASE64 != 0 {
+      return conflict("OPT_MESSAGE_IS_HEX and OPT_MESSAGE_BASE64 are mutually exclusive".to_owned());
+    }
+    if strict && flags & NORMALIZING_FLAGS != 0 {
+      return conflict(format!("flags {:#x} normalize the input, which strict mode forbids", flags & NORMALIZING_FLAGS));
+    }
+
+    Ok(Options {
+      wrap_bytes: flags & OPT_WRAP_BYTES != 0,
+      skip_long_message_hash: flags & OPT_SKIP_LONG_MESSAGE_HASH != 0,
+      nfc_normalize: flags & OPT_NFC_NORMALIZE != 0,
+      message_is_hex: flags & OPT_MESSAGE_IS_HEX != 0,
+      message_is_base64: flags & OPT_MESSAGE_BASE64 != 0,
+      signature_is_base64: flags & OPT_SIGNATURE_BASE64 != 0,
+      strip_scheme_prefix: flags & OPT_STRIP_SCHEME_PREFIX != 0,
+      #[cfg(feature = "sp-core")]
+      allow_non_canonical_ecdsa: flags & OPT_ALLOW_NON_CANONICAL_ECDSA != 0,
+      allowed_prefixes,
+      strict,
+    })
+  }
+
+  /// Reject addresses whose network prefix is not allowed. Raw public keys
+  /// have no prefix, so they are rejected whenever prefixes are restricted.
+  pub fn check_prefix(&self, prefix: Option<u16>) -> Result<(), Error> {
+    match self.allowed_prefixes {
+      Some(allowed) if !prefix.is_some_and(|prefix| allowed.contains(&prefix)) => {
+        Err(VerifyError::DisallowedNetwork { prefix, allowed: allowed.to_vec() }.into())
+      }
+      _ => Ok(()),
+    }
+  }
+
+  /// Reject a 0x prefix on the hex argument `name` in strict mode, where it
+  /// is not stripped.
+  pub fn check_hex_prefix(&self, hex: &[u8], name: &str) -> Result<(), Error> {
+    if self.strict && hex.starts_with(b"0x") {
+      let reason = format!("{name} hex has a 0x prefix, which strict mode does not strip");
+      return Err(VerifyError::NotStrict { reason }.into());
+    }
+    Ok(())
+  }
+}
+
+/// Flags that change the message or signature before verifying.
+/// `OPT_NFC_NORMALIZE` is not among them, strict mode reports messages it
+/// would change instead.
+const NORMALIZING_FLAGS: u32 = OPT_WRAP_BYTES | OPT_STRIP_SCHEME_PREFIX | OPT_ALLOW_NON_CANONICAL_ECDSA;
+
+/// Read a caller's `VerifyOptions`.
+///
+/// NULL means defaults. Only the first `size` bytes of the struct are read,
+/// so callers built against an older, shorter layout get defaults for the
+/// fields appended since. The options are checked as a
+/// [`crate::api::VerifierBuilder`] checks them: contradictory flags fail with
+/// `ERR_INVALID_OPTIONS`.
+///
+/// # Safety
+/// `ptr` must be NULL or point to at least `size` readable bytes, and
+/// `allowed_prefixes` must point to `allowed_prefixes_len` values.
+#[cfg(feature = "ffi")]
+pub(crate) unsafe fn read_options(ptr: *const VerifyOptions) -> Result<Verifier, Error> {
+  let mut builder = Verifier::builder();
+  if ptr.is_null() {
+    return Ok(builder.build()?);
+  }
+
+  let mut raw = VerifyOptions::default();
+  let size = (ptr as *const u32).read_unaligned() as usize;
+  let len = size.min(size_of::<VerifyOptions>());
+  ptr::copy_nonoverlapping(ptr as *const u8, &mut raw as *mut VerifyOptions as *mut u8, len);
+
+  if !raw.allowed_prefixes.is_null() && raw.allowed_prefixes_len != 0 {
+    let allowed_prefixes = slice::from_raw_parts(raw.allowed_prefixes, raw.allowed_prefixes_len);
+    builder = builder.allowed_prefixes(allowed_prefixes.iter().copied());
+  }
+  Ok(builder.flags(raw.flags).strict(raw.strict != 0).build()?)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  #[cfg(feature = "ffi")]
+  use crate::ERR_INVALID_OPTIONS;
+  use crate::{ERR_NOT_STRICT, ERR_WRONG_NETWORK};
+
+  #[test]
+  #[cfg(feature = "ffi")]
+  fn test_null_is_default() {
+    let verifier = unsafe { read_options(ptr::null()) }.unwrap();
+    let options = verifier.options();
+    assert!(!options.wrap_bytes);
+    assert!(options.allowed_prefixes.is_none());
+  }
+
+  #[test]
+  #[cfg(feature = "ffi")]
+  fn test_fields_past_size_are_ignored() {
+    let prefixes = [0u16];
+    let raw = VerifyOptions {
+      size: 8,
+      flags: OPT_WRAP_BYTES,
+      allowed_prefixes: prefixes.as_ptr(),
+      allowed_prefixes_len: prefixes.len(),
+      strict: 1,
+    };
+    let verifier = unsafe { read_options(&raw) }.unwrap();
+    let options = verifier.options();
+    assert!(!options.strict);
+    assert!(options.wrap_bytes);
+    assert!(options.allowed_prefixes.is_none());
+  }
+
+  #[test]
+  #[cfg(feature = "ffi")]
+  fn test_conflicting_message_encodings() {
+    let raw = VerifyOptions { flags: OPT_MESSAGE_IS_HEX | OPT_MESSAGE_BASE64, ..VerifyOptions::default() };
+    assert_eq!(unsafe { read_options(&raw) }.unwrap_err().code, ERR_INVALID_OPTIONS);
+  }
+
+  #[test]
+  #[cfg(feature = "ffi")]
+  fn test_strict_forbids_normalizing_flags() {
+    let raw = VerifyOptions { flags: OPT_WRAP_BYTES, strict: 1, ..VerifyOptions::default() };
+    assert_eq!(unsafe { read_options(&raw) }.unwrap_err().code, ERR_INVALID_OPTIONS);
+
+    let raw = VerifyOptions { flags: OPT_MESSAGE_IS_HEX, strict: 1, ..VerifyOptions::default() };
+    assert!(unsafe { read_options(&raw) }.unwrap().options().strict);
+  }
+
+  #[test]
+  fn test_check_hex_prefix() {
+    assert!(Options::default().check_hex_prefix(b"0xab", "signature").is_ok());
+
+    let strict = Options { strict: true, ..Options::default() };
+    assert!(strict.check_hex_prefix(b"ab", "signature").is_ok());
+    let err = strict.check_hex_prefix(b"0xab", "signature").unwrap_err();
+    assert_eq!((err.code, err.message.as_str()), (ERR_NOT_STRICT, "signature hex has a 0x prefix, which strict mode does not strip"));
+  }
+
+  #[test]
+  fn test_check_prefix() {
+    let prefixes = [0u16, 2];
+    let options = Options { allowed_prefixes: Some(&prefixes), ..Options::default() };
+    assert!(options.check_prefix(Some(2)).is_ok());
+    assert_eq!(options.check_prefix(Some(42)).unwrap_err().code, ERR_WRONG_NETWORK);
+    assert_eq!(options.check_prefix(None).unwrap_err().code, ERR_WRONG_NETWORK);
+    assert!(Options::default().check_prefix(Some(42)).is_ok());
+    assert!(Options::default().check_prefix(None).is_ok());
+  }
+}
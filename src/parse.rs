@@ -0,0 +1,337 @@
+//! Parsing of the string arguments shared by the exported functions.
+
+use crate::api::VerifyError;
+#[cfg(feature = "ffi")]
+use crate::api::Ss58Issue;
+use crate::backend;
+use crate::error::Error;
+use crate::log::{self, LOG_DEBUG};
+use crate::message::blake2_256;
+use crate::options::Options;
+use crate::ss58;
+use crate::{ADDRESS_KIND_ACCOUNT_ID, ADDRESS_KIND_ECDSA_PUBLIC_KEY, ERR_BAD_SS58, ERR_SS58_BAD_LENGTH};
+use base64::alphabet;
+use base64::engine::{DecodePaddingMode, Engine, GeneralPurpose, GeneralPurposeConfig};
+use polkadot_sig_verifier_core::{hex_reason, signature_hex_digits};
+#[cfg(feature = "ffi")]
+use std::ffi::{CStr, c_char};
+use std::ops::Deref;
+#[cfg(feature = "ffi")]
+use std::slice;
+
+const ANY_PAD: GeneralPurposeConfig =
+  GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent);
+const STANDARD_ANY_PAD: GeneralPurpose = GeneralPurpose::new(&alphabet::STANDARD, ANY_PAD);
+const URL_SAFE_ANY_PAD: GeneralPurpose = GeneralPurpose::new(&alphabet::URL_SAFE, ANY_PAD);
+
+/// Borrow a NUL-terminated C string as `&str`.
+///
+/// `name` identifies the argument in error messages.
+#[cfg(feature = "ffi")]
+pub(crate) unsafe fn str_from_ptr<'a>(ptr: *const c_char, name: &str) -> Result<&'a str, Error> {
+  if ptr.is_null() {
+    return Err(VerifyError::NullPointer { name: name.to_owned() }.into());
+  }
+  CStr::from_ptr(ptr)
+    .to_str()
+    .map_err(|e| Error::from(VerifyError::InvalidUtf8 { reason: format!("{name} is not valid UTF-8: {e}") }))
+}
+
+/// Copy a NUL-terminated UTF-16 string into a `String`.
+///
+/// `name` identifies the argument in error messages.
+#[cfg(feature = "ffi")]
+pub(crate) unsafe fn string_from_wide(ptr: *const u16, name: &str) -> Result<String, Error> {
+  if ptr.is_null() {
+    return Err(VerifyError::NullPointer { name: name.to_owned() }.into());
+  }
+  let mut len = 0;
+  while *ptr.add(len) != 0 {
+    len += 1;
+  }
+  String::from_utf16(slice::from_raw_parts(ptr, len))
+    .map_err(|e| Error::from(VerifyError::InvalidUtf16 { reason: format!("{name} is not valid UTF-16: {e}") }))
+}
+
+/// Borrow `len` bytes at `ptr` as a slice.
+///
+/// NULL is accepted for an empty slice. `name` identifies the argument in
+/// error messages.
+#[cfg(feature = "ffi")]
+pub(crate) unsafe fn bytes_from_ptr<'a>(ptr: *const u8, len: usize, name: &str) -> Result<&'a [u8], Error> {
+  if ptr.is_null() {
+    if len == 0 {
+      return Ok(&[]);
+    }
+    let message = format!("{name} pointer is NULL but its length is {len}");
+    return Err(Error { message, ..VerifyError::NullPointer { name: name.to_owned() }.into() });
+  }
+  Ok(slice::from_raw_parts(ptr, len))
+}
+
+/// Fail for argument `name`, whose hex did not decode.
+pub(crate) fn hex_error(err: hex::FromHexError, name: &str) -> Error {
+  VerifyError::InvalidHex { reason: hex_reason(err, name) }.into()
+}
+
+/// The hex digits of `address` if it is a raw public key rather than SS58:
+/// 0x-prefixed, or exactly 64 hex digits. SS58 never contains a 0 and is
+/// shorter than 64 characters for 32-byte accounts, so neither form is
+/// ambiguous.
+fn public_key_hex(address: &str) -> Option<&str> {
+  if let Some(digits) = address.strip_prefix("0x") {
+    return Some(digits);
+  }
+  (address.len() == 64 && address.bytes().all(|b| b.is_ascii_hexdigit())).then_some(address)
+}
+
+/// An address decoded by [`parse_address`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ParsedAddress {
+  /// The 32-byte account id. This is the public key for sr25519 and
+  /// ed25519 and `blake2_256` of the compressed public key for ECDSA.
+  pub account_id: [u8; 32],
+  /// SS58 network prefix, None for a raw public key.
+  pub prefix: Option<u16>,
+  /// `ADDRESS_KIND_*` of the payload the address carried.
+  pub kind: i32,
+}
+
+impl ParsedAddress {
+  /// The public key of an sr25519 or ed25519 account.
+  #[cfg(feature = "ffi")]
+  pub fn public_key(&self) -> Result<[u8; 32], Error> {
+    if self.kind == ADDRESS_KIND_ECDSA_PUBLIC_KEY {
+      return Err(Error {
+        message: "address holds a 33-byte ECDSA public key, expected a 32-byte sr25519 or ed25519 key".to_owned(),
+        ..VerifyError::InvalidSs58 { reason: Ss58Issue::BadLength }.into()
+      });
+    }
+    Ok(self.account_id)
+  }
+}
+
+/// Decode an address into its account id and SS58 network prefix.
+///
+/// The address is SS58 or a raw public key in hex, which has no prefix.
+/// SS58 payloads are either the 32-byte account id or, as some tooling
+/// emits for ECDSA accounts, the 33-byte compressed public key, which is
+/// hashed into the account id.
+pub(crate) fn parse_address(address: &str) -> Result<ParsedAddress, Error> {
+  if let Some(digits) = public_key_hex(address) {
+    if digits.len() != 64 {
+      let reason = format!("public key hex has {} digits, expected 64", digits.len());
+      return Err(VerifyError::WrongPublicKeyLength { reason }.into());
+    }
+    let account_id = decode_hex_array::<32>(digits, "public key")?;
+    log::log(LOG_DEBUG, || "address is a raw public key".to_owned());
+    return Ok(ParsedAddress { account_id, prefix: None, kind: ADDRESS_KIND_ACCOUNT_ID });
+  }
+
+  let (account_id, prefix, kind) = match backend::decode_ss58_account(address) {
+    Ok((account_id, prefix)) => (account_id, prefix, ADDRESS_KIND_ACCOUNT_ID),
+    // Decode the container alone to route by payload length, or explain
+    // why the length is wrong
+    Err(error) if error.code == ERR_SS58_BAD_LENGTH => {
+      let decoded = ss58::decode(address)?;
+      debug_assert_eq!(decoded.kind, ADDRESS_KIND_ECDSA_PUBLIC_KEY, "32-byte payloads decode above");
+      log::log(LOG_DEBUG, || "address holds a compressed ECDSA public key".to_owned());
+      (blake2_256(&decoded.payload[..decoded.payload_len]), decoded.prefix, decoded.kind)
+    }
+    // sp_core reads the first byte of a truncated address as a bad prefix,
+    // which the container decoder tells apart
+    Err(error) if error.code == ERR_BAD_SS58 => return Err(ss58::decode(address).err().unwrap_or(error)),
+    Err(error) => return Err(error),
+  };
+  log::log(LOG_DEBUG, || format!("address decoded with SS58 prefix {prefix}"));
+  Ok(ParsedAddress { account_id, prefix: Some(prefix), kind })
+}
+
+/// The network prefix of an SS58 address with a 32-byte payload, or a
+/// 33-byte one such as a compressed ECDSA public key.
+#[cfg(feature = "ffi")]
+pub(crate) fn ss58_prefix(address: &str) -> Result<u16, Error> {
+  let prefix = match backend::decode_ss58_account(address) {
+    Ok((_, prefix)) => prefix,
+    Err(error) if error.code == ERR_SS58_BAD_LENGTH => ss58::decode(address)?.prefix,
+    Err(error) => return Err(error),
+  };
+  Ok(prefix)
+}
+
+/// Highest prefix SS58 can encode: 14 bits spread over two bytes.
+pub(crate) const MAX_SS58_PREFIX: u16 = 16_383;
+
+/// Fail with `ERR_BAD_SS58` unless SS58 can encode `prefix`.
+pub(crate) fn check_ss58_prefix(prefix: u16) -> Result<(), Error> {
+  if prefix > MAX_SS58_PREFIX {
+    return Err(VerifyError::PrefixOutOfRange { prefix }.into());
+  }
+  Ok(())
+}
+
+/// Encode a 32-byte account id or 33-byte compressed ECDSA public key as
+/// an SS58 address under `prefix`.
+pub(crate) fn encode_ss58(payload: &[u8], prefix: u16) -> Result<String, Error> {
+  check_ss58_prefix(prefix)?;
+  Ok(ss58::encode(payload, prefix))
+}
+
+/// Re-encode an SS58 address under `target_prefix`, keeping its 32- or
+/// 33-byte payload.
+#[cfg(feature = "ffi")]
+pub(crate) fn reencode_ss58(address: &str, target_prefix: u16) -> Result<String, Error> {
+  check_ss58_prefix(target_prefix)?;
+  match backend::decode_ss58_account(address) {
+    Ok((account_id, _)) => Ok(ss58::encode(&account_id, target_prefix)),
+    Err(error) if error.code == ERR_SS58_BAD_LENGTH => {
+      let decoded = ss58::decode(address)?;
+      Ok(ss58::encode(&decoded.payload[..decoded.payload_len], target_prefix))
+    }
+    Err(error) => Err(error),
+  }
+}
+
+/// Encode a 32-byte public key, given in hex with or without `0x`, as an
+/// SS58 address under `prefix`.
+#[cfg(feature = "ffi")]
+pub(crate) fn public_key_to_ss58(public_key_hex: &str, prefix: u16) -> Result<String, Error> {
+  check_ss58_prefix(prefix)?;
+  let bytes = hex::decode(public_key_hex.trim_start_matches("0x")).map_err(|e| hex_error(e, "public key"))?;
+  let raw = <[u8; 32]>::try_from(bytes.as_slice()).map_err(|_| VerifyError::WrongPublicKeyLength {
+    reason: format!("public key is {} bytes, expected 32", bytes.len()),
+  })?;
+  Ok(ss58::encode(&raw, prefix))
+}
+
+/// Longest hex signature decoded without allocating: room for a 65-byte
+/// ECDSA signature behind a `MultiSignature` scheme byte, and to spare.
+const INLINE_SIGNATURE_LEN: usize = 96;
+
+/// A decoded signature, on the stack unless it is longer than any
+/// signature this library verifies.
+pub(crate) enum SignatureBytes {
+  Inline { bytes: [u8; INLINE_SIGNATURE_LEN], len: usize },
+  Heap(Vec<u8>),
+}
+
+impl Deref for SignatureBytes {
+  type Target = [u8];
+
+  fn deref(&self) -> &[u8] {
+    match self {
+      SignatureBytes::Inline { bytes, len } => &bytes[..*len],
+      SignatureBytes::Heap(bytes) => bytes,
+    }
+  }
+}
+
+/// Decode hex digits into a `SignatureBytes`, failing exactly as
+/// `hex::decode` would.
+fn decode_hex_signature(digits: &str) -> Result<SignatureBytes, hex::FromHexError> {
+  let len = digits.len() / 2;
+  if !digits.len().is_multiple_of(2) || len > INLINE_SIGNATURE_LEN {
+    return hex::decode(digits).map(SignatureBytes::Heap);
+  }
+  let mut bytes = [0u8; INLINE_SIGNATURE_LEN];
+  hex::decode_to_slice(digits, &mut bytes[..len])?;
+  Ok(SignatureBytes::Inline { bytes, len })
+}
+
+/// Decode a signature of any length.
+///
+/// The signature is hex, with or without 0x prefix unless strict, or base64
+/// if `options.signature_is_base64` is set. Hex signatures of any real
+/// length are decoded without allocating.
+pub(crate) fn decode_signature(signature: &str, options: &Options) -> Result<SignatureBytes, Error> {
+  let signature_bytes = if options.signature_is_base64 {
+    SignatureBytes::Heap(decode_base64(signature.as_bytes(), "signature")?)
+  } else {
+    options.check_hex_prefix(signature.as_bytes(), "signature")?;
+    decode_hex_signature(signature_hex_digits(signature))
+      .map_err(|e| VerifyError::InvalidSignatureHex { reason: hex_reason(e, "signature") })?
+  };
+
+  log::log(LOG_DEBUG, || format!("signature is {} bytes", signature_bytes.len()));
+  Ok(signature_bytes)
+}
+
+/// Decode base64 in the standard or URL-safe alphabet, padded or not.
+///
+/// `name` identifies the argument in error messages.
+pub(crate) fn decode_base64(input: &[u8], name: &str) -> Result<Vec<u8>, Error> {
+  // The alphabets only differ in two characters, so they cannot be confused
+  let engine = if input.iter().any(|&c| c == b'-' || c == b'_') { &URL_SAFE_ANY_PAD } else { &STANDARD_ANY_PAD };
+  let reason = |e: base64::DecodeError| format!("{name} is not valid base64: {e}");
+  engine.decode(input).map_err(|e| VerifyError::InvalidBase64 { reason: reason(e) }.into())
+}
+
+/// Check that a decoded signature is exactly `N` bytes long.
+pub(crate) fn signature_array<const N: usize>(signature_bytes: &[u8]) -> Result<[u8; N], Error> {
+  if signature_bytes.len() != N {
+    return Err(VerifyError::WrongSignatureLength { found: signature_bytes.len(), expected: N }.into());
+  }
+
+  let mut sig_array = [0u8; N];
+  sig_array.copy_from_slice(signature_bytes);
+  Ok(sig_array)
+}
+
+/// Decode the hex argument `name` into exactly `N` bytes, with or without
+/// 0x prefix.
+pub(crate) fn decode_hex_array<const N: usize>(hex_str: &str, name: &str) -> Result<[u8; N], Error> {
+  let digits = hex_str.trim_start_matches("0x");
+  if digits.len() == 2 * N {
+    let mut array = [0u8; N];
+    hex::decode_to_slice(digits, &mut array).map_err(|e| hex_error(e, name))?;
+    return Ok(array);
+  }
+  // Decode anyway, so bad digits are reported before the length
+  let bytes = hex::decode(digits).map_err(|e| hex_error(e, name))?;
+  Err(VerifyError::WrongHexLength { reason: format!("{name} is {} bytes, expected {N}", bytes.len()) }.into())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{ERR_BAD_HEX, ERR_BAD_HEX_LENGTH};
+
+  const SIGNATURE: &str = "0xf8ce1b80e76bf48a30610d0d3c2c9c8dbfdcb28a9aa8f56ac038d520ff17445ff3ce94809f8479a1224ef0d823d035022a9db2e72bd6c0d2f0244f766dec908b";
+
+  /// `decode_signature` as it was, allocating for every signature.
+  fn decode_signature_vec(signature: &str) -> Result<Vec<u8>, Error> {
+    hex::decode(signature_hex_digits(signature)).map_err(|e| hex_error(e, "signature"))
+  }
+
+  #[test]
+  fn test_decode_signature_matches_hex_decode() {
+    let long = format!("0x{}", "ab".repeat(INLINE_SIGNATURE_LEN + 1));
+    let odd = &long[..long.len() - 1];
+    let inputs = [SIGNATURE, &SIGNATURE[2..], "", "0x", "0x0xabcd", "0xabc", "0xabzz", "0xzz", "0x01ff", &long, odd];
+    for input in inputs {
+      let expected = decode_signature_vec(input);
+      let actual = decode_signature(input, &Options::default());
+      match (expected, actual) {
+        (Ok(expected), Ok(actual)) => assert_eq!(expected, &*actual, "{input}"),
+        (Err(expected), Err(actual)) => {
+          assert_eq!((expected.code, expected.message), (actual.code, actual.message), "{input}")
+        }
+        (expected, actual) => panic!("{input}: {:?} but {:?}", expected.is_ok(), actual.is_ok()),
+      }
+    }
+    assert!(matches!(decode_signature(SIGNATURE, &Options::default()), Ok(SignatureBytes::Inline { len: 64, .. })));
+    assert!(matches!(decode_signature(&long, &Options::default()), Ok(SignatureBytes::Heap(_))));
+  }
+
+  #[test]
+  fn test_decode_hex_array() {
+    assert_eq!(decode_hex_array::<2>("0x01ff", "key").unwrap(), [0x01, 0xff]);
+    assert_eq!(decode_hex_array::<2>("01ff", "key").unwrap(), [0x01, 0xff]);
+    let error = decode_hex_array::<2>("0x01zz", "key").unwrap_err();
+    assert_eq!((error.code, error.message.as_str()), (ERR_BAD_HEX, "key hex has invalid character 'z' at position 2"));
+    let error = decode_hex_array::<2>("0x01", "key").unwrap_err();
+    assert_eq!((error.code, error.message.as_str()), (ERR_BAD_HEX_LENGTH, "key is 1 bytes, expected 2"));
+    // Bad digits are reported before a bad length
+    assert_eq!(decode_hex_array::<2>("0xzz", "key").unwrap_err().code, ERR_BAD_HEX);
+  }
+}
@@ -0,0 +1,46 @@
+//! Signatures decoded once and verified against many messages or signers.
+
+use crate::canonical;
+use crate::error::Error;
+use crate::message;
+use crate::options::Options;
+use crate::parse::{decode_signature, signature_array};
+use crate::verify;
+
+/// Decode and check a signature as `verify_polkadot_signature_v2` would.
+pub(crate) fn parse(signature: &str) -> Result<[u8; 64], Error> {
+  let signature = signature_array::<64>(&decode_signature(signature, &Options::default())?)?;
+  canonical::check_sr25519_reduced_s(&signature)?;
+  Ok(signature)
+}
+
+/// Verify a signature from `parse` over `message`, as
+/// `verify_polkadot_signature_bytes` would.
+pub(crate) fn verify(signature: &[u8; 64], address: &str, message: &[u8]) -> Result<bool, Error> {
+  let options = Options::default();
+  let message = message::decode(message, &options)?;
+  let account = verify::parse_account(address, &options)?;
+  let outcome = verify::verify_sr25519_decoded(&account, *signature, &message, &options)?;
+  Ok(outcome.valid == 1)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::verify::verdict;
+  use crate::{ERR_BAD_HEX, ERR_BAD_SIGNATURE_LENGTH, INVALID, VALID};
+
+  const ADDRESS: &str = "5HbqogsYaK54pN3QAgSi4t3Asqditt1X4P7cSBuDt2hPF2BR";
+  const SIGNATURE: &str = "0xf8ce1b80e76bf48a30610d0d3c2c9c8dbfdcb28a9aa8f56ac038d520ff17445ff3ce94809f8479a1224ef0d823d035022a9db2e72bd6c0d2f0244f766dec908b";
+  const MESSAGE: &str = "<Bytes>c15335d817e3d3d912d30f5a18a30c30162abc364cd0876d3a94af568d3c8c14</Bytes>";
+
+  #[test]
+  fn test_parse_then_verify() {
+    let signature = parse(SIGNATURE).unwrap();
+    assert_eq!(verdict(verify(&signature, ADDRESS, MESSAGE.as_bytes())), VALID);
+    assert_eq!(verdict(verify(&signature, ADDRESS, b"retried body")), INVALID);
+
+    assert_eq!(parse("0xzz").err().map(|error| error.code), Some(ERR_BAD_HEX));
+    assert_eq!(parse(&SIGNATURE[..SIGNATURE.len() - 2]).err().map(|error| error.code), Some(ERR_BAD_SIGNATURE_LENGTH));
+  }
+}
@@ -0,0 +1,110 @@
+//! Network names for SS58 prefixes.
+//!
+//! The table is generated by `cargo xtask registry` from a snapshot of the
+//! public SS58 registry (`ss58-registry.json` in paritytech/ss58-registry),
+//! and compiled in so lookups need no file or network access. Entries are
+//! sorted by prefix.
+
+use serde::Serialize;
+
+/// A registered network.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Network {
+  pub prefix: u16,
+  /// Registry name such as `polkadot`, NUL-terminated for
+  /// `ss58_network_name`.
+  #[serde(serialize_with = "without_nul")]
+  pub network: &'static str,
+  pub display_name: &'static str,
+  /// Native token, if the network has one.
+  pub symbol: Option<&'static str>,
+  pub decimals: u8,
+}
+
+impl Network {
+  /// The registry name, without the NUL.
+  pub(crate) fn name(&self) -> &'static str {
+    &self.network[..self.network.len() - 1]
+  }
+}
+
+fn without_nul<S: serde::Serializer>(network: &&'static str, serializer: S) -> Result<S::Ok, S::Error> {
+  serializer.serialize_str(&network[..network.len() - 1])
+}
+
+macro_rules! networks {
+  (@symbol None) => { None };
+  (@symbol $symbol:literal) => { Some($symbol) };
+  ($($prefix:literal $network:literal $display_name:literal $symbol:tt $decimals:literal;)*) => {
+    &[$(Network {
+      prefix: $prefix,
+      network: concat!($network, "\0"),
+      display_name: $display_name,
+      symbol: networks!(@symbol $symbol),
+      decimals: $decimals,
+    }),*]
+  };
+}
+
+include!("registry_table.rs");
+
+/// The registry entry for `prefix`, if any.
+pub(crate) fn lookup(prefix: u16) -> Option<&'static Network> {
+  NETWORKS.binary_search_by_key(&prefix, |network| network.prefix).ok().map(|index| &NETWORKS[index])
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::Value;
+
+  fn name(prefix: u16) -> &'static str {
+    lookup(prefix).map_or("unknown", Network::name)
+  }
+
+  #[test]
+  fn test_well_known_networks() {
+    assert_eq!(name(0), "polkadot");
+    assert_eq!(name(2), "kusama");
+    assert_eq!(name(42), "substrate");
+    assert_eq!(name(1284), "moonbeam");
+    assert_eq!(name(18), "darwinia");
+
+    let kusama = lookup(2).unwrap();
+    assert_eq!((kusama.display_name, kusama.symbol, kusama.decimals), ("Kusama Relay Chain", Some("KSM"), 12));
+    assert_eq!(lookup(0).unwrap().decimals, 10);
+  }
+
+  #[test]
+  fn test_unknown_prefix() {
+    assert_eq!(lookup(9999), None);
+    assert_eq!(lookup(16_383), None);
+  }
+
+  #[test]
+  fn test_sorted_and_unique() {
+    assert!(NETWORKS.windows(2).all(|pair| pair[0].prefix < pair[1].prefix));
+    for network in NETWORKS {
+      assert_eq!(lookup(network.prefix), Some(network));
+      assert_eq!(network.network.find('\0'), Some(network.network.len() - 1));
+    }
+  }
+
+  #[test]
+  fn test_registry_json() {
+    let table = serde_json::to_value(NETWORKS).unwrap();
+
+    let entries = table.as_array().unwrap();
+    assert_eq!(entries.len(), NETWORKS.len());
+    assert_eq!(
+      entries[0],
+      serde_json::json!({
+        "prefix": 0, "network": "polkadot", "displayName": "Polkadot Relay Chain", "symbol": "DOT", "decimals": 10
+      })
+    );
+    let substrate = entries.iter().find(|entry| entry["prefix"] == 42).unwrap();
+    assert_eq!(substrate["network"], "substrate");
+    assert_eq!(substrate["symbol"], Value::Null);
+  }
+}
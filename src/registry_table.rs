@@ -0,0 +1,154 @@
+// Generated from xtask/ss58-registry.json by `cargo xtask registry`, do not edit.
+
+#[rustfmt::skip]
+pub(crate) const NETWORKS: &[Network] = networks! {
+  0 "polkadot" "Polkadot Relay Chain" "DOT" 10;
+  1 "BareSr25519" "Bare 32-bit Schnorr/Ristretto (S/R 25519) public key." None 0;
+  2 "kusama" "Kusama Relay Chain" "KSM" 12;
+  3 "BareEd25519" "Bare 32-bit Ed25519 public key." None 0;
+  4 "katalchain" "Katal Chain" None 0;
+  5 "astar" "Astar Network" "ASTR" 18;
+  6 "bifrost" "Bifrost" "BNC" 12;
+  7 "edgeware" "Edgeware" "EDG" 18;
+  8 "karura" "Karura" "KAR" 12;
+  9 "reynolds" "Laminar Reynolds Canary" "REY" 18;
+  10 "acala" "Acala" "ACA" 12;
+  11 "laminar" "Laminar" "LAMI" 18;
+  12 "polymesh" "Polymesh" "POLYX" 6;
+  13 "integritee" "Integritee" "TEER" 12;
+  14 "totem" "Totem" "TOTEM" 0;
+  15 "synesthesia" "Synesthesia" "SYN" 12;
+  16 "kulupu" "Kulupu" "KLP" 12;
+  17 "dark" "Dark Mainnet" None 0;
+  18 "darwinia" "Darwinia Network" "RING" 18;
+  19 "watr" "Watr Protocol" "WATR" 18;
+  20 "stafi" "Stafi" "FIS" 12;
+  21 "karmachain" "Karmacoin" "KCOIN" 6;
+  22 "dock-pos-mainnet" "Dock Mainnet" "DCK" 6;
+  23 "shift" "ShiftNrg" None 0;
+  24 "zero" "ZERO" "ZERO" 18;
+  25 "zero-alphaville" "ZERO Alphaville" "ZERO" 18;
+  26 "jupiter" "Jupiter" "jDOT" 10;
+  27 "kabocha" "Kabocha" "KAB" 12;
+  28 "subsocial" "Subsocial" None 0;
+  29 "cord" "CORD Network" "DHI" 12;
+  30 "phala" "Phala Network" "PHA" 12;
+  31 "litentry" "Litentry Network" "LIT" 12;
+  32 "robonomics" "Robonomics" "XRT" 9;
+  33 "datahighway" "DataHighway" None 0;
+  34 "ares" "Ares Protocol" "ARES" 12;
+  35 "vln" "Valiu Liquidity Network" "USDv" 15;
+  36 "centrifuge" "Centrifuge Chain" "CFG" 18;
+  37 "nodle" "Nodle Chain" "NODL" 11;
+  38 "kilt" "KILT Spiritnet" "KILT" 15;
+  39 "mathchain" "MathChain mainnet" "MATH" 18;
+  40 "mathchain-testnet" "MathChain testnet" "MATH" 18;
+  41 "polimec" "Polimec Protocol" "PLMC" 10;
+  42 "substrate" "Substrate" None 0;
+  43 "BareSecp256k1" "Bare 32-bit ECDSA SECP-256k1 public key." None 0;
+  44 "chainx" "ChainX" "PCX" 8;
+  45 "uniarts" "UniArts Network" "UART" 12;
+  46 "reserved46" "This prefix is reserved." None 0;
+  47 "reserved47" "This prefix is reserved." None 0;
+  48 "neatcoin" "Neatcoin Mainnet" "NEAT" 12;
+  49 "picasso" "Picasso" "PICA" 12;
+  50 "composable" "Composable Finance" "LAYR" 12;
+  51 "oak" "OAK Network" "OAK" 10;
+  52 "KICO" "KICO" "KICO" 14;
+  53 "DICO" "DICO" "DICO" 14;
+  54 "cere" "Cere Network" "CERE" 10;
+  55 "xxnetwork" "xx network" "XX" 9;
+  56 "pendulum" "Pendulum chain" "PEN" 12;
+  57 "amplitude" "Amplitude chain" "AMPE" 12;
+  58 "eternal-civilization" "Eternal Civilization" "ECC" 12;
+  63 "hydradx" "Hydration" "HDX" 12;
+  65 "aventus" "Aventus Mainnet" "AVT" 18;
+  66 "crust" "Crust Network" "CRU" 12;
+  67 "genshiro" "Genshiro Network" "GENS" 9;
+  68 "equilibrium" "Equilibrium Network" "EQ" 9;
+  69 "sora" "SORA Network" "XOR" 18;
+  71 "p3d" "3DP network" "P3D" 12;
+  72 "p3dt" "3DP test network" "P3Dt" 12;
+  73 "zeitgeist" "Zeitgeist" "ZTG" 10;
+  77 "manta" "Manta network" "MANTA" 18;
+  78 "calamari" "Calamari: Manta Canary Network" "KMA" 12;
+  81 "sora_dot_para" "SORA Polkadot Parachain" "XOR" 18;
+  88 "polkadex" "Polkadex Mainnet" "PDEX" 12;
+  89 "polkadexparachain" "Polkadex Parachain" "PDEX" 12;
+  90 "frequency" "Frequency" "FRQCY" 8;
+  92 "anmol" "Anmol Network" "ANML" 18;
+  93 "fragnova" "Fragnova Network" "NOVA" 12;
+  98 "polkasmith" "PolkaSmith Canary Network" "PKS" 18;
+  99 "polkafoundry" "PolkaFoundry Network" "PKF" 18;
+  100 "ibtida" "Anmol Network Ibtida Canary network" "IANML" 18;
+  101 "origintrail-parachain" "OriginTrail Parachain" "OTP" 12;
+  105 "pontem-network" "Pontem Network" "PONT" 10;
+  110 "heiko" "Heiko" "HKO" 12;
+  113 "integritee-incognito" "Integritee Incognito" None 0;
+  117 "tinker" "Tinker" "TNKR" 12;
+  126 "joystream" "Joystream" "JOY" 10;
+  128 "clover" "Clover Finance" "CLV" 18;
+  129 "dorafactory-polkadot" "Dorafactory Polkadot Network" "DORA" 12;
+  131 "litmus" "Litmus Network" "LIT" 12;
+  136 "altair" "Altair" "AIR" 18;
+  137 "vara" "Vara Network" "VARA" 12;
+  172 "parallel" "Parallel" "PARA" 12;
+  252 "social-network" "Social Network" "NET" 18;
+  255 "quartz_mainnet" "QUARTZ by UNIQUE" "QTZ" 18;
+  268 "pioneer_network" "Pioneer Network by Bit.Country" "NEER" 18;
+  420 "sora_kusama_para" "SORA Kusama Parachain" "XOR" 18;
+  440 "allfeat_network" "Allfeat Network" "AFT" 12;
+  666 "metaquity_network" "Metaquity Network" "MQTY" 18;
+  777 "curio" "Curio" "CGT" 18;
+  789 "geek" "GEEK Network" "GEEK" 18;
+  995 "ternoa" "Ternoa" "CAPS" 18;
+  1110 "efinity" "Efinity" "EFI" 18;
+  1221 "peaq" "Peaq Network" "PEAQ" 18;
+  1222 "krest" "Krest Network" "KREST" 18;
+  1284 "moonbeam" "Moonbeam" "GLMR" 18;
+  1285 "moonriver" "Moonriver" "MOVR" 18;
+  1328 "ajuna" "Ajuna Network" "AJUN" 12;
+  1337 "bajun" "Bajun Network" "BAJU" 12;
+  1516 "societal" "Societal" "SCTL" 12;
+  1985 "seals" "Seals Network" "SEAL" 9;
+  2007 "kapex" "Kapex" "KAPEX" 12;
+  2009 "cloudwalk_mainnet" "CloudWalk Network Mainnet" "CWN" 18;
+  2021 "logion" "logion network" "LGNT" 18;
+  2024 "vow-chain" "Enigmatic Smile" "VOW" 18;
+  2032 "interlay" "Interlay" "INTR" 10;
+  2092 "kintsugi" "Kintsugi" "KINT" 12;
+  2106 "bitgreen" "Bitgreen" "BBB" 18;
+  2112 "chainflip" "Chainflip" "FLIP" 18;
+  2199 "moonsama" "Moonsama" "SAMA" 18;
+  2206 "ICE" "ICE Network" "ICY" 18;
+  2207 "SNOW" "SNOW: ICE Canary Network" "ICZ" 18;
+  2254 "subspace_testnet" "Subspace testnet" "tSSC" 18;
+  3333 "peerplays" "Peerplays" "PPY" 18;
+  4450 "g1" "Ğ1" "G1" 2;
+  5234 "humanode" "Humanode Network" "HMND" 18;
+  5845 "tangle" "Tangle Network" "TNT" 18;
+  6094 "autonomys" "Autonomys" "AI3" 18;
+  7007 "tidefi" "Tidefi" "TDFY" 12;
+  7013 "gm" "GM" "FREN" 12;
+  7306 "krigan" "Krigan Network" "KRGN" 9;
+  7391 "unique_mainnet" "Unique Network" "UNQ" 18;
+  8866 "golden_gate" "Golden Gate" "GGX" 18;
+  8883 "sapphire_mainnet" "Sapphire by Unique" "QTZ" 18;
+  8886 "golden_gate_sydney" "Golden Gate Sydney" "GGXT" 18;
+  8888 "xcavate" "Xcavate Protocol" "XCAV" 12;
+  9072 "hashed" "Hashed Network" "HASH" 18;
+  9807 "dentnet" "DENTNet" "DENTX" 18;
+  9935 "t3rn" "t3rn" "TRN" 12;
+  10041 "basilisk" "Basilisk" "BSX" 12;
+  11330 "cess-testnet" "CESS Testnet" "TCESS" 18;
+  11331 "cess" "CESS" "CESS" 18;
+  11486 "luhn" "Luhn Network" "LUHN" 18;
+  11820 "contextfree" "Automata ContextFree" "CTX" 18;
+  12155 "impact" "Impact Protocol Network" "BSTY" 18;
+  12191 "nftmart" "NFTMart" "NMT" 12;
+  12850 "analog-timechain" "Analog Timechain" "ANLOG" 12;
+  13116 "bittensor" "Bittensor" "TAO" 9;
+  14697 "goro" "GORO Network" "GORO" 9;
+  14998 "mosaic-chain" "Mosaic Chain" "MOS" 18;
+  29972 "mythos" "Mythos" "MYTH" 18;
+};
@@ -0,0 +1,163 @@
+//! Names and numbers of the supported signature schemes.
+
+use crate::api::VerifyError;
+use crate::error::Error;
+
+/// A signature scheme, numbered like the `SCHEME_*` constants.
+///
+/// In the bitmask of `get_supported_schemes`, each scheme is the bit
+/// `1 << SCHEME_*`: 0x2 sr25519, 0x4 ed25519, 0x8 ecdsa, 0x10 ethereum.
+/// Numbers and bits never change, and new schemes get new ones.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
+pub enum SignatureScheme {
+  /// Schnorrkel signatures over Ristretto25519, the Polkadot default.
+  Sr25519 = 1,
+  /// Ed25519 signatures, used by Ledger and some hardware wallets.
+  Ed25519 = 2,
+  /// Recoverable secp256k1 ECDSA signatures over blake2-256 hashed messages.
+  Ecdsa = 3,
+  /// Ethereum `personal_sign` signatures for 20-byte addresses.
+  Ethereum = 4,
+}
+
+impl SignatureScheme {
+  /// Every scheme, in discriminant order.
+  pub const ALL: [SignatureScheme; 4] =
+    [SignatureScheme::Sr25519, SignatureScheme::Ed25519, SignatureScheme::Ecdsa, SignatureScheme::Ethereum];
+
+  /// Lowercase name, as accepted by `parse_scheme`.
+  pub fn name(self) -> &'static str {
+    let name = self.name_with_nul();
+    &name[..name.len() - 1]
+  }
+
+  /// `name` with a NUL appended, for C.
+  pub(crate) fn name_with_nul(self) -> &'static str {
+    match self {
+      SignatureScheme::Sr25519 => "sr25519\0",
+      SignatureScheme::Ed25519 => "ed25519\0",
+      SignatureScheme::Ecdsa => "ecdsa\0",
+      SignatureScheme::Ethereum => "ethereum\0",
+    }
+  }
+
+  /// The scheme numbered `value`, if any.
+  pub fn from_i32(value: i32) -> Option<SignatureScheme> {
+    SignatureScheme::ALL.into_iter().find(|&scheme| scheme as i32 == value)
+  }
+
+  /// The scheme called `name`, ignoring ASCII case.
+  pub fn from_name(name: &str) -> Option<SignatureScheme> {
+    SignatureScheme::ALL.into_iter().find(|scheme| scheme.name().eq_ignore_ascii_case(name))
+  }
+
+  /// The scheme called `name`, failing with `ERR_UNKNOWN_SCHEME`.
+  #[cfg(feature = "ffi")]
+  pub(crate) fn parse(name: &str) -> Result<SignatureScheme, Error> {
+    SignatureScheme::from_name(name)
+      .ok_or_else(|| VerifyError::UnknownScheme { reason: format!("unknown signature scheme {name:?}") }.into())
+  }
+
+  /// The scheme numbered `value`, failing with `ERR_UNKNOWN_SCHEME`.
+  #[cfg(feature = "ffi")]
+  pub(crate) fn known(value: i32) -> Result<SignatureScheme, Error> {
+    SignatureScheme::from_i32(value)
+      .ok_or_else(|| VerifyError::UnknownScheme { reason: format!("unknown signature scheme {value}") }.into())
+  }
+
+  /// The bit of this scheme in the mask of `get_supported_schemes`.
+  pub fn bit(self) -> u32 {
+    1 << self as u32
+  }
+
+  /// Whether this build verifies the scheme. ed25519 needs the default
+  /// `sp-core` feature, and ECDSA and Ethereum-style signatures the
+  /// default `ecdsa` feature.
+  pub fn is_supported(self) -> bool {
+    match self {
+      SignatureScheme::Sr25519 => true,
+      SignatureScheme::Ed25519 => cfg!(feature = "sp-core"),
+      SignatureScheme::Ecdsa | SignatureScheme::Ethereum => cfg!(feature = "ecdsa"),
+    }
+  }
+
+  /// The bits of every scheme this build verifies, as
+  /// `get_supported_schemes` reports them.
+  #[cfg(feature = "ffi")]
+  pub(crate) fn supported_mask() -> u32 {
+    SignatureScheme::ALL.into_iter().filter(|scheme| scheme.is_supported()).fold(0, |mask, scheme| mask | scheme.bit())
+  }
+
+  /// Fail with `ERR_SCHEME_NOT_SUPPORTED` unless this build verifies the
+  /// scheme.
+  pub(crate) fn ensure_supported(self) -> Result<(), Error> {
+    if self.is_supported() {
+      Ok(())
+    } else {
+      let reason = format!("{} signatures are not supported by this build", self.name());
+      Err(VerifyError::SchemeNotSupported { reason }.into())
+    }
+  }
+}
+
+#[cfg(all(test, feature = "ffi"))]
+mod tests {
+  use super::*;
+  use crate::{ERR_UNKNOWN_SCHEME, SCHEME_ECDSA, SCHEME_ED25519, SCHEME_ETHEREUM, SCHEME_SR25519};
+
+  fn parse(name: &str) -> i32 {
+    SignatureScheme::parse(name).map_or_else(|error| error.code, |scheme| scheme as i32)
+  }
+
+  #[test]
+  fn test_round_trip() {
+    for scheme in SignatureScheme::ALL {
+      let name = scheme.name();
+      assert_eq!(SignatureScheme::from_i32(scheme as i32), Some(scheme));
+      assert_eq!(parse(name), scheme as i32);
+      assert_eq!(parse(&name.to_ascii_uppercase()), scheme as i32);
+    }
+  }
+
+  #[test]
+  fn test_constants() {
+    assert_eq!(parse("Sr25519"), SCHEME_SR25519);
+    assert_eq!(parse("ED25519"), SCHEME_ED25519);
+    assert_eq!(parse("ecdsa"), SCHEME_ECDSA);
+    assert_eq!(parse("Ethereum"), SCHEME_ETHEREUM);
+  }
+
+  #[test]
+  fn test_unknown() {
+    assert_eq!(parse("secp256k1"), ERR_UNKNOWN_SCHEME);
+    assert_eq!(parse(""), ERR_UNKNOWN_SCHEME);
+    assert_eq!(parse(" sr25519"), ERR_UNKNOWN_SCHEME);
+
+    assert_eq!(SignatureScheme::from_i32(0), None);
+    assert_eq!(SignatureScheme::from_i32(5), None);
+    assert_eq!(SignatureScheme::known(-1).unwrap_err().code, ERR_UNKNOWN_SCHEME);
+  }
+
+  #[test]
+  fn test_supported_schemes() {
+    let mask = SignatureScheme::supported_mask();
+    assert_eq!(SignatureScheme::Sr25519.bit(), 0x2);
+    assert_eq!(SignatureScheme::Ethereum.bit(), 0x10);
+    for scheme in SignatureScheme::ALL {
+      assert_eq!(mask & scheme.bit() != 0, scheme.is_supported());
+    }
+    assert_eq!(mask & !0x1e, 0);
+  }
+
+  #[test]
+  #[cfg(not(feature = "ecdsa"))]
+  fn test_without_ecdsa() {
+    let mask = SignatureScheme::supported_mask();
+    assert_eq!(mask & (1 << SCHEME_SR25519), 1 << SCHEME_SR25519);
+    assert_eq!(mask & (1 << SCHEME_ECDSA), 0);
+    assert_eq!(mask & (1 << SCHEME_ETHEREUM), 0);
+    assert_eq!(SignatureScheme::Ecdsa.ensure_supported().unwrap_err().code, crate::ERR_SCHEME_NOT_SUPPORTED);
+  }
+}
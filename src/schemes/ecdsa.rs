@@ -0,0 +1,65 @@
+//! Recoverable secp256k1 signatures, checked against the `blake2_256`
+//! hash of the public key in the address.
+
+use super::Scheme;
+#[cfg(feature = "sp-core")]
+use crate::canonical;
+use crate::error::Error;
+use crate::options::Options;
+#[cfg(feature = "sp-core")]
+use crate::parse::signature_array;
+use crate::verify::Account;
+use crate::SignatureScheme;
+#[cfg(feature = "sp-core")]
+use sp_core::{ecdsa, hashing::blake2_256};
+
+pub(crate) struct Ecdsa;
+
+impl Scheme for Ecdsa {
+  fn scheme(&self) -> SignatureScheme {
+    SignatureScheme::Ecdsa
+  }
+
+  fn signature_len(&self) -> usize {
+    65
+  }
+
+  fn multisignature_tag(&self) -> u8 {
+    0x02
+  }
+
+  /// Any address: one holding the 33-byte public key itself is decoded to
+  /// its hash too.
+  fn account_matches(&self, _: &Account) -> bool {
+    true
+  }
+
+  /// The address holds a hash rather than the key, so the key is recovered
+  /// from the signature and hashed before comparing.
+  ///
+  /// High-s signatures are rejected unless
+  /// `options.allow_non_canonical_ecdsa` is set, in which case they are
+  /// normalized first.
+  #[cfg(feature = "sp-core")]
+  fn verify(&self, signature: &[u8], message: &[u8], account_id: [u8; 32], options: &Options) -> Result<bool, Error> {
+    let signature = signature_array::<65>(signature)?;
+    let signature = if options.allow_non_canonical_ecdsa {
+      canonical::normalize_ecdsa(signature)
+    } else {
+      canonical::check_ecdsa_low_s(&signature)?;
+      signature
+    };
+
+    let signature = ecdsa::Signature::from_raw(signature);
+    Ok(match signature.recover(message) {
+      Some(public_key) => blake2_256(public_key.as_ref()) == account_id,
+      None => false,
+    })
+  }
+
+  /// Fails with `ERR_SCHEME_NOT_SUPPORTED`: ECDSA needs `sp-core`.
+  #[cfg(not(feature = "sp-core"))]
+  fn verify(&self, _: &[u8], _: &[u8], _: [u8; 32], _: &Options) -> Result<bool, Error> {
+    self.scheme().ensure_supported().map(|()| false)
+  }
+}
@@ -0,0 +1,38 @@
+//! Ed25519 signatures, checked against the public key in the address.
+
+use super::Scheme;
+use crate::error::Error;
+use crate::options::Options;
+#[cfg(feature = "sp-core")]
+use crate::parse::signature_array;
+use crate::SignatureScheme;
+#[cfg(feature = "sp-core")]
+use sp_core::{ed25519, Pair};
+
+pub(crate) struct Ed25519;
+
+impl Scheme for Ed25519 {
+  fn scheme(&self) -> SignatureScheme {
+    SignatureScheme::Ed25519
+  }
+
+  fn signature_len(&self) -> usize {
+    64
+  }
+
+  fn multisignature_tag(&self) -> u8 {
+    0x00
+  }
+
+  #[cfg(feature = "sp-core")]
+  fn verify(&self, signature: &[u8], message: &[u8], public_key: [u8; 32], _: &Options) -> Result<bool, Error> {
+    let signature = ed25519::Signature::from_raw(signature_array::<64>(signature)?);
+    Ok(ed25519::Pair::verify(&signature, message, &ed25519::Public::from_raw(public_key)))
+  }
+
+  /// Fails with `ERR_SCHEME_NOT_SUPPORTED`: ed25519 needs `sp-core`.
+  #[cfg(not(feature = "sp-core"))]
+  fn verify(&self, _: &[u8], _: &[u8], _: [u8; 32], _: &Options) -> Result<bool, Error> {
+    self.scheme().ensure_supported().map(|()| false)
+  }
+}
@@ -0,0 +1,70 @@
+//! The signature schemes verified against SS58 addresses, one file each.
+//!
+//! `verify` dispatches through [`SCHEMES`] rather than matching on
+//! [`SignatureScheme`], so a new scheme is a new [`Scheme`] implementation
+//! plus an entry there. Ethereum-style signatures are checked against
+//! 20-byte addresses by `ethereum` instead, and have no entry.
+
+mod ecdsa;
+mod ed25519;
+mod sr25519;
+
+use crate::error::Error;
+use crate::options::Options;
+use crate::verify::Account;
+use crate::{SignatureScheme, ADDRESS_KIND_ECDSA_PUBLIC_KEY};
+
+pub(crate) use ecdsa::Ecdsa;
+pub(crate) use ed25519::Ed25519;
+pub(crate) use sr25519::Sr25519;
+
+/// How one signature scheme checks signatures against the payload of an
+/// SS58 address.
+pub(crate) trait Scheme: Sync {
+  /// The scheme implemented.
+  fn scheme(&self) -> SignatureScheme;
+
+  /// Length of a signature in bytes.
+  fn signature_len(&self) -> usize;
+
+  /// Tag of the scheme's variant in a SCALE-encoded `MultiSignature`.
+  fn multisignature_tag(&self) -> u8;
+
+  /// Whether a key of this scheme could be behind `account`. By default,
+  /// any address but one holding a 33-byte ECDSA public key.
+  fn account_matches(&self, account: &Account) -> bool {
+    account.kind != ADDRESS_KIND_ECDSA_PUBLIC_KEY
+  }
+
+  /// Check a signature of exactly `signature_len` bytes over `message`
+  /// against the 32-byte account id of an address.
+  ///
+  /// Fails with `ERR_NON_CANONICAL_SIGNATURE` for malleated encodings of an
+  /// otherwise valid signature.
+  fn verify(&self, signature: &[u8], message: &[u8], account_id: [u8; 32], options: &Options) -> Result<bool, Error>;
+}
+
+/// Every scheme with an SS58 address, in the order `verify_any` tries
+/// signatures of the same length.
+pub(crate) static SCHEMES: [&dyn Scheme; 3] = [&Sr25519, &Ed25519, &Ecdsa];
+
+/// The registry entry of `scheme`, if it is verified against SS58
+/// addresses.
+pub(crate) fn lookup(scheme: SignatureScheme) -> Option<&'static dyn Scheme> {
+  SCHEMES.into_iter().find(|entry| entry.scheme() == scheme)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_registry() {
+    for entry in SCHEMES {
+      assert_eq!(lookup(entry.scheme()).unwrap().scheme(), entry.scheme());
+      let same_tag = SCHEMES.iter().filter(|other| other.multisignature_tag() == entry.multisignature_tag());
+      assert_eq!(same_tag.count(), 1, "{} shares its MultiSignature tag", entry.scheme().name());
+    }
+    assert!(lookup(SignatureScheme::Ethereum).is_none());
+  }
+}
@@ -0,0 +1,31 @@
+//! Schnorrkel signatures, checked against the public key in the address.
+
+use super::Scheme;
+use crate::backend;
+use crate::canonical;
+use crate::error::Error;
+use crate::options::Options;
+use crate::parse::signature_array;
+use crate::SignatureScheme;
+
+pub(crate) struct Sr25519;
+
+impl Scheme for Sr25519 {
+  fn scheme(&self) -> SignatureScheme {
+    SignatureScheme::Sr25519
+  }
+
+  fn signature_len(&self) -> usize {
+    64
+  }
+
+  fn multisignature_tag(&self) -> u8 {
+    0x01
+  }
+
+  fn verify(&self, signature: &[u8], message: &[u8], public_key: [u8; 32], _: &Options) -> Result<bool, Error> {
+    let signature = signature_array::<64>(signature)?;
+    canonical::check_sr25519_reduced_s(&signature)?;
+    Ok(backend::verify_sr25519(signature, message, public_key))
+  }
+}
@@ -0,0 +1,139 @@
+//! An HTTP front end for `verify_request`, built with the `service` feature
+//! and started by `polkadot-verify serve`.
+//!
+//! * `POST /verify` takes a `VerificationRequest` and answers with its
+//!   `VerificationOutcome`.
+//! * `POST /verify/batch` takes an array of requests and answers with an
+//!   array of outcomes, in order.
+//! * `GET /healthz` answers with what `get_build_info` reports, plus
+//!   `"status": "ok"`.
+//!
+//! A signature that does not verify is still a 200, with an outcome that
+//! says why. A body that is not JSON is a 400, and one that is not a
+//! request a 422, each with an `error` message. Bodies over
+//! `max_body_bytes` and batches over `max_batch_len` are a 413.
+
+use crate::api::VerifyError;
+use crate::error::Error;
+use crate::types::{self, VerificationOutcome, VerificationRequest};
+use crate::{verify_request, BuildInfo, ERR_PANIC};
+use axum::extract::rejection::JsonRejection;
+use axum::extract::{DefaultBodyLimit, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tokio::sync::Semaphore;
+use tokio::task::{JoinError, JoinHandle};
+
+/// Limits for `router` and `serve`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ServiceConfig {
+  /// Largest request body accepted, in bytes.
+  pub max_body_bytes: usize,
+  /// Most requests accepted in one `/verify/batch` call.
+  pub max_batch_len: usize,
+  /// Most signatures verified at once, across all connections. Requests
+  /// beyond it wait for a worker.
+  pub concurrency: usize,
+}
+
+impl Default for ServiceConfig {
+  /// 1 MiB bodies, batches of up to 1000, and one worker per core.
+  fn default() -> Self {
+    let cores = std::thread::available_parallelism().map_or(1, usize::from);
+    ServiceConfig { max_body_bytes: 1 << 20, max_batch_len: 1000, concurrency: cores }
+  }
+}
+
+#[derive(Clone)]
+struct Service {
+  workers: Arc<Semaphore>,
+  max_batch_len: usize,
+}
+
+impl Service {
+  /// Wait for a free worker, then verify `request` on the blocking pool.
+  async fn spawn(&self, request: VerificationRequest) -> JoinHandle<VerificationOutcome> {
+    let permit = self.workers.clone().acquire_owned().await.expect("the semaphore is never closed");
+    tokio::task::spawn_blocking(move || {
+      let _permit = permit;
+      verify_request(&request)
+    })
+  }
+}
+
+fn outcome(joined: Result<VerificationOutcome, JoinError>) -> VerificationOutcome {
+  let panicked = || VerifyError::Panic { reason: "verification panicked".to_owned() };
+  joined.unwrap_or_else(|_| types::failed(None, panicked().into()))
+}
+
+fn error(status: StatusCode, message: String) -> Response {
+  (status, Json(serde_json::json!({ "error": message }))).into_response()
+}
+
+fn rejected(rejection: JsonRejection) -> Response {
+  error(rejection.status(), rejection.body_text())
+}
+
+async fn verify(State(service): State<Service>, body: Result<Json<VerificationRequest>, JsonRejection>) -> Response {
+  match body {
+    Ok(Json(request)) => Json(outcome(service.spawn(request).await.await)).into_response(),
+    Err(rejection) => rejected(rejection),
+  }
+}
+
+async fn verify_batch(
+  State(service): State<Service>,
+  body: Result<Json<Vec<VerificationRequest>>, JsonRejection>,
+) -> Response {
+  let requests = match body {
+    Ok(Json(requests)) => requests,
+    Err(rejection) => return rejected(rejection),
+  };
+  if requests.len() > service.max_batch_len {
+    let message = format!("batch has {} requests, at most {} are accepted", requests.len(), service.max_batch_len);
+    return error(StatusCode::PAYLOAD_TOO_LARGE, message);
+  }
+
+  let mut tasks = Vec::with_capacity(requests.len());
+  for request in requests {
+    tasks.push(service.spawn(request).await);
+  }
+  let mut outcomes = Vec::with_capacity(tasks.len());
+  for task in tasks {
+    outcomes.push(outcome(task.await));
+  }
+  Json(outcomes).into_response()
+}
+
+#[derive(Serialize)]
+struct Health {
+  status: &'static str,
+  #[serde(flatten)]
+  build: BuildInfo,
+}
+
+async fn healthz() -> Json<Health> {
+  Json(Health { status: "ok", build: BuildInfo::current() })
+}
+
+/// The service's routes, for embedding in another axum app.
+pub fn router(config: ServiceConfig) -> Router {
+  let workers = Arc::new(Semaphore::new(config.concurrency.max(1)));
+  let service = Service { workers, max_batch_len: config.max_batch_len };
+  Router::new()
+    .route("/verify", post(verify))
+    .route("/verify/batch", post(verify_batch))
+    .route("/healthz", get(healthz))
+    .layer(DefaultBodyLimit::max(config.max_body_bytes))
+    .with_state(service)
+}
+
+/// Serve the routes of `router` on `listener` until the process exits.
+pub async fn serve(listener: TcpListener, config: ServiceConfig) -> std::io::Result<()> {
+  axum::serve(listener, router(config)).await
+}
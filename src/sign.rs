@@ -0,0 +1,224 @@
+//! Key generation, signing and address derivation from seeds and
+//! mnemonics, for tests, tooling and support.
+//!
+//! Seeds, mnemonics and passwords are secret: no log message or error ever
+//! includes them, or any character or word of them. Buffers the library
+//! fills with secret bytes are `Zeroizing`, or wiped by hand where a type
+//! from `sp_core` or `bip39` holds them, so they are zeroed on every return
+//! path. The key pairs zero their own secrets when dropped. Secrets read
+//! from C strings are borrowed, never copied.
+
+use crate::api::VerifyError;
+use crate::derive;
+use crate::error::Error;
+use crate::log::{self, LOG_DEBUG};
+use crate::message;
+use crate::options::Options;
+#[cfg(feature = "ffi")]
+use crate::parse::{check_ss58_prefix, encode_ss58};
+#[cfg(feature = "ffi")]
+use crate::scheme::SignatureScheme;
+use bip39::{Language, Mnemonic};
+#[cfg(feature = "ffi")]
+use sp_core::crypto::{AccountId32, Ss58Codec};
+#[cfg(feature = "ffi")]
+use sp_core::hashing::blake2_256;
+#[cfg(feature = "ffi")]
+use sp_core::{ecdsa, ed25519, sr25519};
+use sp_core::Pair;
+use zeroize::{Zeroize, Zeroizing};
+
+/// Decode a 32-byte hex seed, with or without 0x prefix.
+///
+/// Unlike `decode_hex_array`, failures name no character of the input.
+pub(crate) fn decode_seed(seed_hex: &str) -> Result<Zeroizing<[u8; 32]>, Error> {
+  let bytes = hex::decode(seed_hex.trim_start_matches("0x")).map_err(|err| {
+    let reason = match err {
+      hex::FromHexError::InvalidHexCharacter { index, .. } => {
+        format!("seed hex has an invalid character at position {index}")
+      }
+      _ => "seed hex had odd length".to_owned(),
+    };
+    VerifyError::InvalidHex { reason }
+  })?;
+  let bytes = Zeroizing::new(bytes);
+  if bytes.len() != 32 {
+    return Err(VerifyError::WrongSeedLength { found: bytes.len() }.into());
+  }
+  let mut seed = Zeroizing::new([0u8; 32]);
+  seed.copy_from_slice(&bytes);
+  Ok(seed)
+}
+
+/// Zero a seed handed back by `sp_core`, whose `Seed` types are plain
+/// byte arrays.
+pub(crate) fn wipe(seed: &mut impl AsMut<[u8]>) {
+  seed.as_mut().zeroize();
+}
+
+/// Check `mnemonic` against the English BIP39 word list, so an unknown
+/// word and a checksum mismatch can be told apart.
+pub(crate) fn check_mnemonic(mnemonic: &str) -> Result<(), Error> {
+  match Mnemonic::parse_in_normalized(Language::English, mnemonic) {
+    // The parsed mnemonic holds the entropy, so it is wiped straight away
+    Ok(mut parsed) => {
+      parsed.zeroize();
+      Ok(())
+    }
+    Err(bip39::Error::BadWordCount(count)) => {
+      let reason = format!("mnemonic has {count} words, expected 12, 15, 18, 21 or 24");
+      Err(VerifyError::InvalidMnemonic { reason }.into())
+    }
+    Err(bip39::Error::UnknownWord(index)) => {
+      let reason = format!("word {} of the mnemonic is not in the English BIP39 word list", index + 1);
+      Err(VerifyError::UnknownMnemonicWord { reason }.into())
+    }
+    Err(bip39::Error::InvalidChecksum) => Err(VerifyError::MnemonicChecksumMismatch.into()),
+    Err(other) => Err(VerifyError::InvalidMnemonic { reason: format!("mnemonic is invalid: {other}") }.into()),
+  }
+}
+
+/// The key of `mnemonic` followed by `path`, as `Pair::from_string` would
+/// derive it from the two concatenated.
+///
+/// `path` is any number of `//hard` and `/soft` junctions, optionally
+/// followed by `///password`. Only sr25519 keys have soft junctions.
+pub(crate) fn pair_from_mnemonic<P: Pair>(mnemonic: &str, path: &str) -> Result<P, Error> {
+  let suri = derive::parse_suri_path(path)?;
+  check_mnemonic(mnemonic)?;
+
+  let (root, mut seed) = P::from_phrase(mnemonic, suri.password)
+    .map_err(|_| VerifyError::InvalidMnemonic { reason: "mnemonic is invalid".to_owned() })?;
+  wipe(&mut seed);
+  log::log(LOG_DEBUG, || format!("deriving along {} junctions", suri.junctions.len()));
+  let (pair, seed) = root.derive(suri.junctions.into_iter(), None).map_err(|_| VerifyError::InvalidDerivationPath {
+    reason: "derivation path has a soft junction, which needs an sr25519 key".to_owned(),
+  })?;
+  if let Some(mut seed) = seed {
+    wipe(&mut seed);
+  }
+  Ok(pair)
+}
+
+/// The account id of the `scheme` key of `mnemonic` followed by `path`:
+/// the public key itself, or its `blake2_256` hash for ECDSA.
+#[cfg(feature = "ffi")]
+fn account_from_mnemonic(mnemonic: &str, path: &str, scheme: SignatureScheme) -> Result<AccountId32, Error> {
+  Ok(match scheme {
+    SignatureScheme::Sr25519 => pair_from_mnemonic::<sr25519::Pair>(mnemonic, path)?.public().into(),
+    SignatureScheme::Ed25519 => pair_from_mnemonic::<ed25519::Pair>(mnemonic, path)?.public().into(),
+    SignatureScheme::Ecdsa => blake2_256(pair_from_mnemonic::<ecdsa::Pair>(mnemonic, path)?.public().as_ref()).into(),
+    SignatureScheme::Ethereum => {
+      return Err(VerifyError::UnknownScheme { reason: "ethereum addresses are not SS58".to_owned() }.into());
+    }
+  })
+}
+
+/// Sign `message` with `pair`, returning the 0x-prefixed hex signature.
+///
+/// The message is canonicalized as verification does with default
+/// options, so one over 256 bytes is signed as its `blake2_256` hash.
+pub(crate) fn sign<P: Pair>(pair: &P, message: &[u8]) -> String {
+  sign_canonical(pair, message, &Options::default()).expect("default options accept any message")
+}
+
+/// `sign` over `message` canonicalized as `options` ask.
+fn sign_canonical<P: Pair>(pair: &P, message: &[u8], options: &Options) -> Result<String, Error> {
+  let message = message::canonicalize_message(message, options)?;
+  log::log(LOG_DEBUG, || format!("signing {} message bytes", message.len()));
+  Ok(format!("0x{}", hex::encode(pair.sign(&message))))
+}
+
+/// Sign `message` with the `P` key of a hex seed, as given or wrapped
+/// in `<Bytes>...</Bytes>` like a wallet would.
+#[cfg(feature = "ffi")]
+pub(crate) fn sign_with_seed<P: Pair>(seed_hex: &str, message: &str, wrapped: bool) -> Result<String, Error> {
+  let pair = P::from_seed_slice(&decode_seed(seed_hex)?[..])
+    .map_err(|_| VerifyError::InvalidSeed { reason: "seed is not a valid secret key".to_owned() })?;
+  let options = Options { wrap_bytes: wrapped, ..Options::default() };
+  sign_canonical(&pair, message.as_bytes(), &options)
+}
+
+/// Sign `message` with the sr25519 key of `mnemonic` followed by `path`,
+/// returning the signature and the key's address under prefix 42.
+#[cfg(feature = "ffi")]
+pub(crate) fn sign_with_mnemonic(mnemonic: &str, path: &str, message: &str) -> Result<(String, String), Error> {
+  let pair = pair_from_mnemonic::<sr25519::Pair>(mnemonic, path)?;
+  Ok((sign(&pair, message.as_bytes()), pair.public().to_ss58check()))
+}
+
+/// A new English BIP39 mnemonic of `word_count` words from the operating
+/// system's CSPRNG. The caller zeroes it once done.
+#[cfg(feature = "ffi")]
+pub(crate) fn generate_mnemonic(word_count: u32) -> Result<Mnemonic, Error> {
+  if ![12, 15, 18, 21, 24].contains(&word_count) {
+    let reason = format!("cannot generate {word_count} words, expected 12, 15, 18, 21 or 24");
+    return Err(VerifyError::InvalidMnemonic { reason }.into());
+  }
+  let mnemonic = Mnemonic::generate_in(Language::English, word_count as usize).expect("word count is checked above");
+  log::log(LOG_DEBUG, || format!("generated a {word_count}-word mnemonic"));
+  Ok(mnemonic)
+}
+
+/// The SS58 address under `prefix` of the `scheme` key of `mnemonic`
+/// followed by `path`.
+#[cfg(feature = "ffi")]
+pub(crate) fn address_from_mnemonic(
+  mnemonic: &str,
+  path: &str,
+  scheme: SignatureScheme,
+  prefix: u16,
+) -> Result<String, Error> {
+  check_ss58_prefix(prefix)?;
+  encode_ss58(account_from_mnemonic(mnemonic, path, scheme)?.as_ref(), prefix)
+}
+
+/// A new sr25519 key from the operating system's CSPRNG, for an address
+/// under `prefix`: its seed, zeroed when dropped, and its public key.
+#[cfg(feature = "ffi")]
+pub(crate) fn generate_sr25519(prefix: u16) -> Result<(Zeroizing<[u8; 32]>, sr25519::Public), Error> {
+  check_ss58_prefix(prefix)?;
+  let (pair, seed) = sr25519::Pair::generate();
+  let seed = Zeroizing::new(seed);
+  log::log(LOG_DEBUG, || format!("generated a keypair for SS58 prefix {prefix}"));
+  Ok((seed, pair.public()))
+}
+
+#[cfg(all(test, feature = "ffi"))]
+mod tests {
+  use super::*;
+  use crate::{api, ERR_BAD_MNEMONIC, ERR_BAD_SS58};
+  use sp_core::crypto::DEV_PHRASE;
+
+  // The `//Alice` development seed
+  const SEED: &str = "0xe5be9a5092b81bca64be81d212e7f2f9eba183bb7a90954f7b76361f6edb5c0a";
+  const ALICE: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+
+  #[test]
+  fn test_sign_with_seed() {
+    let signature = sign_with_seed::<sr25519::Pair>(SEED, "hello polkadot", false).unwrap();
+    assert!(api::verify(ALICE, &signature, b"hello polkadot").is_ok());
+    let signature = sign_with_seed::<sr25519::Pair>(SEED, "hello polkadot", true).unwrap();
+    assert!(api::verify(ALICE, &signature, b"<Bytes>hello polkadot</Bytes>").is_ok());
+  }
+
+  #[test]
+  fn test_mnemonic_helpers() {
+    let (signature, address) = sign_with_mnemonic(DEV_PHRASE, "//Alice", "hello").unwrap();
+    assert_eq!(address, ALICE);
+    assert!(api::verify(ALICE, &signature, b"hello").is_ok());
+    assert_eq!(address_from_mnemonic(DEV_PHRASE, "//Alice", SignatureScheme::Sr25519, 42).as_deref(), Ok(ALICE));
+
+    let mut mnemonic = generate_mnemonic(24).unwrap();
+    assert_eq!(mnemonic.words().count(), 24);
+    mnemonic.zeroize();
+    assert_eq!(generate_mnemonic(13).unwrap_err().code, ERR_BAD_MNEMONIC);
+  }
+
+  #[test]
+  fn test_generate_sr25519() {
+    let (seed, public) = generate_sr25519(0).unwrap();
+    assert_eq!(sr25519::Pair::from_seed(&seed).public(), public);
+    assert_eq!(generate_sr25519(16_384).unwrap_err().code, ERR_BAD_SS58);
+  }
+}
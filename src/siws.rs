@@ -0,0 +1,380 @@
+//! Sign-In with Substrate (SIWS) messages.
+//!
+//! The format follows Sign-In with Ethereum, with "Substrate account" in
+//! the header and an SS58 address:
+//!
+//! ```text
+//! example.com wants you to sign in with your Substrate account:
+//! 5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY
+//!
+//! Optional statement.
+//!
+//! URI: https://example.com/login
+//! Version: 1
+//! Nonce: 32891756
+//! Issued At: 2024-04-25T10:00:00Z
+//! ```
+
+use crate::api::VerifyError;
+use crate::error::Error;
+use crate::log::{self, LOG_DEBUG};
+use crate::options::Options;
+use crate::parse::parse_address;
+use crate::verify;
+
+const HEADER_SUFFIX: &str = " wants you to sign in with your Substrate account:";
+
+/// The fields of a SIWS message that `verify_siws` checks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SiwsMessage<'a> {
+  domain: &'a str,
+  address: &'a str,
+  nonce: &'a str,
+  issued_at: Option<i64>,
+  expiration_time: Option<i64>,
+  not_before: Option<i64>,
+}
+
+/// Caller expectations after validation, see `SiwsExpectations`.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Expectations<'a> {
+  pub now: i64,
+  pub domain: Option<&'a str>,
+  pub nonce: Option<&'a str>,
+  pub address: Option<&'a str>,
+}
+
+fn malformed(reason: impl Into<String>) -> Error {
+  VerifyError::SiwsMalformed { reason: reason.into() }.into()
+}
+
+/// Days from 1970-01-01 to the given proleptic Gregorian date.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+  let year = if month <= 2 { year - 1 } else { year };
+  let era = year.div_euclid(400);
+  let year_of_era = year - era * 400;
+  let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+  let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+  era * 146_097 + day_of_era - 719_468
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+  match month {
+    2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+    2 => 28,
+    4 | 6 | 9 | 11 => 30,
+    _ => 31,
+  }
+}
+
+/// Parse an RFC 3339 timestamp such as `2024-04-25T10:00:00.000Z` into
+/// seconds since the Unix epoch. Fractional seconds are dropped.
+fn parse_timestamp(value: &str) -> Option<i64> {
+  let digits = |start: usize, len: usize| -> Option<i64> {
+    let field = value.get(start..start + len)?;
+    field.bytes().all(|b| b.is_ascii_digit()).then(|| field.parse().ok())?
+  };
+  let bytes = value.as_bytes();
+  if bytes.len() < 20
+    || bytes[4] != b'-'
+    || bytes[7] != b'-'
+    || !matches!(bytes[10], b'T' | b't' | b' ')
+    || bytes[13] != b':'
+    || bytes[16] != b':'
+  {
+    return None;
+  }
+
+  let (year, month, day) = (digits(0, 4)?, digits(5, 2)?, digits(8, 2)?);
+  let (hour, minute, second) = (digits(11, 2)?, digits(14, 2)?, digits(17, 2)?);
+  if !(1..=12).contains(&month) || !(1..=days_in_month(year, month)).contains(&day) {
+    return None;
+  }
+  if hour > 23 || minute > 59 || second > 60 {
+    return None;
+  }
+
+  let mut rest = &value[19..];
+  if let Some(fraction) = rest.strip_prefix('.') {
+    let len = fraction.bytes().take_while(u8::is_ascii_digit).count();
+    if len == 0 {
+      return None;
+    }
+    rest = &fraction[len..];
+  }
+  let offset = match rest.as_bytes() {
+    [b'Z' | b'z'] => 0,
+    [sign @ (b'+' | b'-'), _, _, b':', _, _] => {
+      let (hours, minutes) = (digits(value.len() - 5, 2)?, digits(value.len() - 2, 2)?);
+      if hours > 23 || minutes > 59 {
+        return None;
+      }
+      let offset = hours * 3600 + minutes * 60;
+      if *sign == b'-' { -offset } else { offset }
+    }
+    _ => return None,
+  };
+
+  Some(days_from_civil(year, month, day) * 86_400 + hour * 3600 + minute * 60 + second - offset)
+}
+
+/// Store `value` for the field `key`, rejecting repeated fields.
+fn set_once<T>(slot: &mut Option<T>, value: T, key: &str) -> Result<(), Error> {
+  if slot.replace(value).is_some() {
+    return Err(malformed(format!("{key} appears more than once")));
+  }
+  Ok(())
+}
+
+fn timestamp(value: &str, key: &str) -> Result<i64, Error> {
+  parse_timestamp(value).ok_or_else(|| malformed(format!("{key} {value:?} is not an RFC 3339 timestamp")))
+}
+
+/// Parse a SIWS message, accepting LF or CRLF line endings and any
+/// optional field.
+fn parse_message(text: &str) -> Result<SiwsMessage<'_>, Error> {
+  let mut lines = text.split('\n').map(|line| line.strip_suffix('\r').unwrap_or(line));
+  let domain = lines
+    .next()
+    .and_then(|line| line.strip_suffix(HEADER_SUFFIX))
+    .filter(|domain| !domain.is_empty())
+    .ok_or_else(|| malformed("first line is not a Sign-In with Substrate header"))?;
+  let address = lines.next().filter(|address| !address.is_empty()).ok_or_else(|| malformed("address line is missing"))?;
+
+  let (mut uri, mut version, mut chain_id, mut nonce, mut request_id) = (None, None, None, None, None);
+  let (mut issued_at, mut expiration_time, mut not_before) = (None, None, None);
+  let mut in_fields = false;
+  let mut in_resources = false;
+  for line in lines {
+    if line.is_empty() {
+      continue;
+    }
+    if in_resources {
+      if !line.starts_with("- ") {
+        return Err(malformed(format!("unexpected line {line:?} after Resources")));
+      }
+      continue;
+    }
+
+    match line.split_once(": ") {
+      Some(("URI", value)) => set_once(&mut uri, value, "URI")?,
+      Some(("Version", value)) => set_once(&mut version, value, "Version")?,
+      Some(("Chain ID", value)) => set_once(&mut chain_id, value, "Chain ID")?,
+      Some(("Nonce", value)) => set_once(&mut nonce, value, "Nonce")?,
+      Some(("Issued At", value)) => set_once(&mut issued_at, timestamp(value, "Issued At")?, "Issued At")?,
+      Some(("Expiration Time", value)) => {
+        set_once(&mut expiration_time, timestamp(value, "Expiration Time")?, "Expiration Time")?
+      }
+      Some(("Not Before", value)) => set_once(&mut not_before, timestamp(value, "Not Before")?, "Not Before")?,
+      Some(("Request ID", value)) => set_once(&mut request_id, value, "Request ID")?,
+      _ if line == "Resources:" => in_resources = true,
+      // Lines before the first field are the statement
+      _ if !in_fields => continue,
+      _ => return Err(malformed(format!("unexpected line {line:?}"))),
+    }
+    in_fields = true;
+  }
+
+  if uri.is_none() || version.is_none() {
+    return Err(malformed("URI and Version are required"));
+  }
+  let nonce = nonce.ok_or_else(|| malformed("Nonce is required"))?;
+  Ok(SiwsMessage { domain, address, nonce, issued_at, expiration_time, not_before })
+}
+
+/// Check a parsed message against the caller's expectations.
+fn check_expectations(message: &SiwsMessage, expected: &Expectations) -> Result<(), Error> {
+  if let Some(address) = expected.address {
+    if parse_address(address)?.account_id != parse_address(message.address)?.account_id {
+      let reason = format!("message is for {}, expected {address}", message.address);
+      return Err(VerifyError::SiwsAddressMismatch { reason }.into());
+    }
+  }
+  if let Some(domain) = expected.domain {
+    if !message.domain.eq_ignore_ascii_case(domain) {
+      let reason = format!("message is for {}, expected {domain}", message.domain);
+      return Err(VerifyError::SiwsDomainMismatch { reason }.into());
+    }
+  }
+  if expected.nonce.is_some_and(|nonce| nonce != message.nonce) {
+    let reason = format!("message nonce {:?} was not issued", message.nonce);
+    return Err(VerifyError::SiwsNonceMismatch { reason }.into());
+  }
+
+  let now = expected.now;
+  if let Some(issued_at) = message.issued_at.filter(|&issued_at| issued_at > now) {
+    let reason = format!("message was issued at {issued_at}, after {now}");
+    return Err(VerifyError::NotYetValid { reason }.into());
+  }
+  if let Some(not_before) = message.not_before.filter(|&not_before| not_before > now) {
+    let reason = format!("message is not valid before {not_before}, now is {now}");
+    return Err(VerifyError::NotYetValid { reason }.into());
+  }
+  if let Some(expiration_time) = message.expiration_time.filter(|&expiration_time| expiration_time <= now) {
+    let reason = format!("message expired at {expiration_time}, now is {now}");
+    return Err(VerifyError::Expired { reason }.into());
+  }
+  Ok(())
+}
+
+/// Verify the signature of a SIWS message, then check its fields.
+///
+/// The message is signed by the address it names, wrapped in
+/// `<Bytes>...</Bytes>` as polkadot-js `signRaw` does.
+pub(crate) fn verify_siws_text(text: &str, signature: &str, expected: &Expectations) -> Result<bool, Error> {
+  let message = parse_message(text)?;
+  log::log(LOG_DEBUG, || format!("SIWS message from {} for {}", message.address, message.domain));
+
+  let options = Options { wrap_bytes: true, ..Options::default() };
+  let outcome = verify::verify_sr25519(message.address, signature, text.as_bytes(), &options)?;
+  if outcome.valid == 0 {
+    return Ok(false);
+  }
+  check_expectations(&message, expected)?;
+  Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::message::{hash_if_long, wrap_bytes};
+  use crate::verify::verdict;
+  use crate::{
+    ERR_EXPIRED, ERR_NOT_YET_VALID, ERR_SIWS_ADDRESS_MISMATCH, ERR_SIWS_DOMAIN_MISMATCH, ERR_SIWS_MALFORMED,
+    ERR_SIWS_NONCE_MISMATCH, INVALID, VALID,
+  };
+  use sp_core::crypto::Ss58Codec;
+  use sp_core::{sr25519, Pair};
+
+  const DOMAIN: &str = "example.com";
+  const NONCE: &str = "32891756";
+  /// 2024-04-25T10:00:00Z
+  const ISSUED_AT: i64 = 1_714_039_200;
+  /// 2024-04-25T10:05:00Z
+  const EXPIRES_AT: i64 = 1_714_039_500;
+
+  fn alice() -> sr25519::Pair {
+    sr25519::Pair::from_string("//Alice", None).unwrap()
+  }
+
+  fn message(address: &str) -> String {
+    format!(
+      "{DOMAIN} wants you to sign in with your Substrate account:\n{address}\n\nSign in to Example.\n\n\
+       URI: https://example.com/login\nVersion: 1\nChain ID: polkadot\nNonce: {NONCE}\n\
+       Issued At: 2024-04-25T10:00:00.000Z\nExpiration Time: 2024-04-25T10:05:00Z\n\
+       Resources:\n- https://example.com/terms"
+    )
+  }
+
+  fn sign(pair: &sr25519::Pair, text: &str) -> String {
+    hex::encode(pair.sign(&hash_if_long(wrap_bytes(text.as_bytes()))))
+  }
+
+  fn verify(text: &str, signature: &str, domain: &str, nonce: &str, now: i64) -> i32 {
+    let expected = Expectations { now, domain: Some(domain), nonce: Some(nonce), ..Expectations::default() };
+    verdict(verify_siws_text(text, signature, &expected))
+  }
+
+  #[test]
+  fn test_valid() {
+    let text = message(&alice().public().to_ss58check());
+    let signature = sign(&alice(), &text);
+    assert_eq!(verify(&text, &signature, DOMAIN, NONCE, ISSUED_AT + 60), VALID);
+  }
+
+  #[test]
+  fn test_crlf_line_endings() {
+    let text = message(&alice().public().to_ss58check()).replace('\n', "\r\n");
+    let signature = sign(&alice(), &text);
+    assert_eq!(verify(&text, &signature, DOMAIN, NONCE, ISSUED_AT), VALID);
+  }
+
+  #[test]
+  fn test_bad_signature() {
+    let text = message(&alice().public().to_ss58check());
+    let signature = sign(&alice(), &text.replace("Example", "Exemple"));
+    assert_eq!(verify(&text, &signature, DOMAIN, NONCE, ISSUED_AT), INVALID);
+
+    // Signed by someone other than the address in the message
+    let bob = sr25519::Pair::from_string("//Bob", None).unwrap();
+    assert_eq!(verify(&text, &sign(&bob, &text), DOMAIN, NONCE, ISSUED_AT), INVALID);
+  }
+
+  #[test]
+  fn test_wrong_domain_and_nonce() {
+    let text = message(&alice().public().to_ss58check());
+    let signature = sign(&alice(), &text);
+    assert_eq!(verify(&text, &signature, "evil.example", NONCE, ISSUED_AT), ERR_SIWS_DOMAIN_MISMATCH);
+    assert_eq!(verify(&text, &signature, "EXAMPLE.com", NONCE, ISSUED_AT), VALID);
+    assert_eq!(verify(&text, &signature, DOMAIN, "32891757", ISSUED_AT), ERR_SIWS_NONCE_MISMATCH);
+  }
+
+  #[test]
+  fn test_address_mismatch() {
+    let text = message(&alice().public().to_ss58check());
+    let signature = sign(&alice(), &text);
+
+    let bob = sr25519::Pair::from_string("//Bob", None).unwrap().public().to_ss58check();
+    let expected = Expectations { now: ISSUED_AT, address: Some(&bob), ..Expectations::default() };
+    assert_eq!(verdict(verify_siws_text(&text, &signature, &expected)), ERR_SIWS_ADDRESS_MISMATCH);
+
+    // The same key under the Polkadot prefix matches
+    let polkadot = alice().public().to_ss58check_with_version(0u16.into());
+    let expected = Expectations { now: ISSUED_AT, address: Some(&polkadot), ..Expectations::default() };
+    assert_eq!(verdict(verify_siws_text(&text, &signature, &expected)), VALID);
+  }
+
+  #[test]
+  fn test_time_window() {
+    let text = message(&alice().public().to_ss58check());
+    let signature = sign(&alice(), &text);
+    assert_eq!(verify(&text, &signature, DOMAIN, NONCE, ISSUED_AT - 1), ERR_NOT_YET_VALID);
+    assert_eq!(verify(&text, &signature, DOMAIN, NONCE, ISSUED_AT), VALID);
+    assert_eq!(verify(&text, &signature, DOMAIN, NONCE, EXPIRES_AT - 1), VALID);
+    assert_eq!(verify(&text, &signature, DOMAIN, NONCE, EXPIRES_AT), ERR_EXPIRED);
+  }
+
+  #[test]
+  fn test_optional_fields() {
+    let address = alice().public().to_ss58check();
+    let text = format!(
+      "{DOMAIN} wants you to sign in with your Substrate account:\n{address}\n\nURI: https://example.com\nVersion: 1\nNonce: {NONCE}"
+    );
+    let parsed = parse_message(&text).unwrap();
+    assert_eq!((parsed.domain, parsed.address, parsed.nonce), (DOMAIN, address.as_str(), NONCE));
+    assert_eq!((parsed.issued_at, parsed.expiration_time, parsed.not_before), (None, None, None));
+    assert_eq!(verify(&text, &sign(&alice(), &text), DOMAIN, NONCE, 0), VALID);
+  }
+
+  #[test]
+  fn test_malformed() {
+    let address = alice().public().to_ss58check();
+    let text = message(&address);
+    let code = |text: &str| parse_message(text).unwrap_err().code;
+
+    assert_eq!(code("hello"), ERR_SIWS_MALFORMED);
+    assert_eq!(code(&text.replace("Substrate", "Ethereum")), ERR_SIWS_MALFORMED);
+    assert_eq!(code(&text.replace("Nonce: ", "Nonce:")), ERR_SIWS_MALFORMED);
+    assert_eq!(code(&text.replace("Version: 1\n", "")), ERR_SIWS_MALFORMED);
+    assert_eq!(code(&text.replace("2024-04-25T10:05:00Z", "tomorrow")), ERR_SIWS_MALFORMED);
+    assert_eq!(code(&format!("{text}\nNonce: 1")), ERR_SIWS_MALFORMED);
+    assert_eq!(code(&text.replace("Version: 1", "Version: 1\nNonce: 1")), ERR_SIWS_MALFORMED);
+
+    let signature = sign(&alice(), "hello");
+    assert_eq!(verify("hello", &signature, DOMAIN, NONCE, ISSUED_AT), ERR_SIWS_MALFORMED);
+  }
+
+  #[test]
+  fn test_parse_timestamp() {
+    assert_eq!(parse_timestamp("1970-01-01T00:00:00Z"), Some(0));
+    assert_eq!(parse_timestamp("2024-04-25T10:00:00Z"), Some(ISSUED_AT));
+    assert_eq!(parse_timestamp("2024-04-25T12:30:00+02:00"), Some(1_714_041_000));
+    assert_eq!(parse_timestamp("2000-02-29T23:59:59.999Z"), Some(951_868_799));
+
+    assert_eq!(parse_timestamp("2001-02-29T00:00:00Z"), None);
+    assert_eq!(parse_timestamp("2024-04-25T10:00:00"), None);
+    assert_eq!(parse_timestamp("2024-04-25T10:00:00.Z"), None);
+    assert_eq!(parse_timestamp("2024-13-01T00:00:00Z"), None);
+    assert_eq!(parse_timestamp("+024-04-25T10:00:00Z"), None);
+  }
+}
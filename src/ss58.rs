@@ -0,0 +1,369 @@
+//! Allocation-free SS58 decoding for checking addresses on their own, and
+//! the encoding every export that returns an address uses.
+//!
+//! Verification decodes addresses through [`crate::backend`], which only
+//! accepts the payload length of the key type it is asked for. Validation has no
+//! key type to go by, so it decodes any supported payload and reports
+//! which one it found.
+
+use crate::abi::AddressInfo;
+use crate::api::{key_length_message, Ss58Issue, VerifyError};
+use crate::error::Error;
+use crate::{ADDRESS_KIND_ACCOUNT_ID, ADDRESS_KIND_ECDSA_PUBLIC_KEY};
+#[cfg(feature = "ffi")]
+use crate::VALID;
+use blake2::{Blake2b512, Digest};
+
+const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const CHECKSUM_LEN: usize = 2;
+/// Longest supported payload, a compressed ECDSA public key.
+const MAX_PAYLOAD_LEN: usize = 33;
+const MAX_DECODED_LEN: usize = 2 + MAX_PAYLOAD_LEN + CHECKSUM_LEN;
+
+/// A decoded SS58 address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Decoded {
+  pub prefix: u16,
+  /// `ADDRESS_KIND_*` of the payload, told apart by length.
+  pub kind: i32,
+  pub payload: [u8; MAX_PAYLOAD_LEN],
+  pub payload_len: usize,
+}
+
+fn digit(c: u8) -> Option<u32> {
+  ALPHABET.iter().position(|&a| a == c).map(|d| d as u32)
+}
+
+/// Why an address failed to decode. Unlike [`Error`] this does not
+/// allocate, so batches can classify many addresses cheaply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Failure {
+  BadCharacter { index: usize, c: char },
+  TooLong,
+  Empty,
+  TruncatedPrefix,
+  BadPrefixByte(u8),
+  /// A payload of unsupported length that also fails its checksum, as
+  /// when an address is truncated.
+  BadPayloadLength(usize),
+  /// A well-formed address whose payload no supported key type has.
+  WrongKeyLength(usize),
+  BadChecksum,
+  ReservedPrefix(u16),
+}
+
+impl Failure {
+  /// The failure as the Rust API reports it, which fixes its code.
+  fn detail(self) -> VerifyError {
+    let issue = match self {
+      Failure::BadCharacter { .. } => Ss58Issue::BadBase58,
+      Failure::TooLong | Failure::Empty | Failure::TruncatedPrefix | Failure::BadPayloadLength(_) => {
+        Ss58Issue::BadLength
+      }
+      Failure::WrongKeyLength(found) => return VerifyError::WrongKeyLength { found },
+      Failure::BadChecksum => Ss58Issue::BadChecksum,
+      Failure::BadPrefixByte(_) | Failure::ReservedPrefix(_) => Ss58Issue::BadPrefix,
+    };
+    VerifyError::InvalidSs58 { reason: issue }
+  }
+
+  #[cfg(feature = "ffi")]
+  fn code(self) -> i32 {
+    i32::from(&self.detail())
+  }
+}
+
+impl From<Failure> for Error {
+  fn from(failure: Failure) -> Self {
+    let message = match failure {
+      Failure::BadCharacter { index, c } => format!("address has invalid base58 character {c:?} at position {index}"),
+      Failure::TooLong => format!("address decodes to more than {MAX_DECODED_LEN} bytes"),
+      Failure::Empty => "address is empty".to_owned(),
+      Failure::TruncatedPrefix => "address is too short for its two-byte prefix".to_owned(),
+      Failure::BadPrefixByte(first) => format!("SS58 prefix byte {first:#04x} is invalid"),
+      Failure::BadPayloadLength(len) => {
+        format!("SS58 payload is {len} bytes and fails its checksum, the address may be truncated or mistyped")
+      }
+      Failure::WrongKeyLength(len) => key_length_message(len),
+      Failure::BadChecksum => "SS58 checksum mismatch".to_owned(),
+      Failure::ReservedPrefix(prefix) => format!("SS58 prefix {prefix} is reserved"),
+    };
+    // More detail than the variant gives, for get_last_error
+    Error { message, ..failure.detail().into() }
+  }
+}
+
+/// Base58-decode `address` into `out`, returning the decoded length, or
+/// `Failure::TooLong` if it does not fit.
+pub(crate) fn decode_base58(address: &str, out: &mut [u8]) -> Result<usize, Failure> {
+  if let Some((index, c)) = address.char_indices().find(|&(_, c)| !c.is_ascii() || digit(c as u8).is_none()) {
+    return Err(Failure::BadCharacter { index, c });
+  }
+
+  // Little-endian big number, reversed once complete
+  let mut len = 0;
+  for c in address.bytes() {
+    let mut carry = digit(c).expect("checked above");
+    for byte in &mut out[..len] {
+      carry += u32::from(*byte) * 58;
+      *byte = carry as u8;
+      carry >>= 8;
+    }
+    while carry > 0 {
+      if len == out.len() {
+        return Err(Failure::TooLong);
+      }
+      out[len] = carry as u8;
+      len += 1;
+      carry >>= 8;
+    }
+  }
+
+  let zeros = address.bytes().take_while(|&c| c == b'1').count();
+  if zeros + len > out.len() {
+    return Err(Failure::TooLong);
+  }
+  out[..len].reverse();
+  out.copy_within(..len, zeros);
+  out[..zeros].fill(0);
+  Ok(zeros + len)
+}
+
+/// Base58-encode `data`, the inverse of [`decode_base58`].
+fn encode_base58(data: &[u8]) -> String {
+  // Little-endian base-58 digits, reversed once complete
+  let mut digits: Vec<u8> = Vec::with_capacity(data.len() * 138 / 100 + 1);
+  for &byte in data {
+    let mut carry = u32::from(byte);
+    for digit in &mut digits {
+      carry += u32::from(*digit) << 8;
+      *digit = (carry % 58) as u8;
+      carry /= 58;
+    }
+    while carry > 0 {
+      digits.push((carry % 58) as u8);
+      carry /= 58;
+    }
+  }
+
+  let zeros = data.iter().take_while(|&&byte| byte == 0).count();
+  let mut address = "1".repeat(zeros);
+  address.extend(digits.iter().rev().map(|&digit| char::from(ALPHABET[usize::from(digit)])));
+  address
+}
+
+/// Encode a 32-byte account id or 33-byte compressed ECDSA public key as
+/// an SS58 address under `prefix`, as `Ss58Codec::to_ss58check_with_version`
+/// does. Callers check that `prefix` is at most 16383.
+pub(crate) fn encode(payload: &[u8], prefix: u16) -> String {
+  let mut data = Vec::with_capacity(2 + payload.len() + CHECKSUM_LEN);
+  match prefix {
+    0..=63 => data.push(prefix as u8),
+    _ => {
+      let first = ((prefix & 0b1111_1100) as u8) >> 2;
+      let second = ((prefix >> 8) as u8) | (((prefix & 0b11) as u8) << 6);
+      data.extend([first | 0b0100_0000, second]);
+    }
+  }
+  data.extend_from_slice(payload);
+  let hash = Blake2b512::new().chain_update(b"SS58PRE").chain_update(&data).finalize();
+  data.extend_from_slice(&hash[..CHECKSUM_LEN]);
+  encode_base58(&data)
+}
+
+fn decode_raw(address: &str) -> Result<Decoded, Failure> {
+  let mut data = [0u8; MAX_DECODED_LEN];
+  let len = decode_base58(address, &mut data)?;
+  let data = &data[..len];
+
+  let (prefix_len, prefix) = match data.first() {
+    None => return Err(Failure::Empty),
+    Some(0..=63) => (1, u16::from(data[0])),
+    Some(64..=127) if len >= 2 => {
+      let lower = (data[0] << 2) | (data[1] >> 6);
+      let upper = data[1] & 0b0011_1111;
+      (2, u16::from(lower) | (u16::from(upper) << 8))
+    }
+    Some(64..=127) => return Err(Failure::TruncatedPrefix),
+    // Dropping a character from an address shifts its first byte out of
+    // the prefix range, so no address length means a truncated paste
+    Some(_) if !(35..=37).contains(&len) => {
+      return Err(Failure::BadPayloadLength(len.saturating_sub(1 + CHECKSUM_LEN)))
+    }
+    Some(&first) => return Err(Failure::BadPrefixByte(first)),
+  };
+
+  if len < prefix_len + CHECKSUM_LEN {
+    return Err(Failure::BadPayloadLength(0));
+  }
+  let (body, checksum) = data.split_at(len - CHECKSUM_LEN);
+  let payload_len = body.len() - prefix_len;
+  let hash = Blake2b512::new().chain_update(b"SS58PRE").chain_update(body).finalize();
+  let checksum_ok = hash[..CHECKSUM_LEN] == *checksum;
+
+  // The checksum tells a truncated paste apart from a well-formed address
+  // of a key type this library does not handle
+  let kind = match (payload_len, checksum_ok) {
+    (32, true) => ADDRESS_KIND_ACCOUNT_ID,
+    (33, true) => ADDRESS_KIND_ECDSA_PUBLIC_KEY,
+    (32 | 33, false) => return Err(Failure::BadChecksum),
+    (_, true) => return Err(Failure::WrongKeyLength(payload_len)),
+    (_, false) => return Err(Failure::BadPayloadLength(payload_len)),
+  };
+  if matches!(prefix, 46 | 47) {
+    return Err(Failure::ReservedPrefix(prefix));
+  }
+
+  let mut payload = [0u8; MAX_PAYLOAD_LEN];
+  payload[..payload_len].copy_from_slice(&body[prefix_len..]);
+  Ok(Decoded { prefix, kind, payload, payload_len })
+}
+
+/// Decode an SS58 address with a 32-byte account id or 33-byte compressed
+/// ECDSA public key as its payload.
+///
+/// Rejects the same addresses as `sp_core`, including the reserved
+/// prefixes 46 and 47.
+pub(crate) fn decode(address: &str) -> Result<Decoded, Error> {
+  decode_raw(address).map_err(Error::from)
+}
+
+/// 1 (`VALID`) if `address` decodes, or the code `decode` would fail with,
+/// without allocating.
+#[cfg(feature = "ffi")]
+pub(crate) fn classify(address: &str) -> i32 {
+  decode_raw(address).map_or_else(Failure::code, |_| VALID)
+}
+
+impl From<Decoded> for AddressInfo {
+  fn from(decoded: Decoded) -> Self {
+    AddressInfo {
+      ss58_prefix: decoded.prefix,
+      payload_len: decoded.payload_len as u8,
+      kind: decoded.kind,
+      payload: decoded.payload,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::abi::AddressInfo;
+  use crate::{ERR_BAD_SS58, ERR_SS58_BAD_BASE58, ERR_SS58_BAD_CHECKSUM, ERR_SS58_BAD_LENGTH, ERR_WRONG_KEY_LENGTH, VALID};
+  use sp_core::crypto::{Ss58AddressFormat, Ss58Codec};
+  use sp_core::{ecdsa, Pair};
+
+  const ADDRESS: &str = "5HbqogsYaK54pN3QAgSi4t3Asqditt1X4P7cSBuDt2hPF2BR";
+  const PUBLIC_KEY_HEX: &str = "f4ec883f28db136cd98e3b553cab48ab4671111978a4660f129a45cb4df84d30";
+
+  fn validate(address: &str) -> (i32, AddressInfo) {
+    match decode(address) {
+      Ok(decoded) => {
+        #[cfg(feature = "ffi")]
+        assert_eq!(classify(address), VALID);
+        (VALID, AddressInfo::from(decoded))
+      }
+      Err(error) => {
+        #[cfg(feature = "ffi")]
+        assert_eq!(classify(address), error.code, "{address}");
+        (error.code, AddressInfo::default())
+      }
+    }
+  }
+
+  #[test]
+  fn test_account_id() {
+    let (code, info) = validate(ADDRESS);
+    assert_eq!(code, VALID);
+    assert_eq!(info.ss58_prefix, 42);
+    assert_eq!(info.payload_len, 32);
+    assert_eq!(info.kind, ADDRESS_KIND_ACCOUNT_ID);
+    assert_eq!(hex::encode(&info.payload[..32]), PUBLIC_KEY_HEX);
+    assert_eq!(info.payload[32], 0);
+
+    assert_eq!(validate("16Y8x28cS6LYFu3v8KViD2sKjTdNbBZf8sr6bUtaS7iuRDs5").1.ss58_prefix, 0);
+    assert_eq!(validate("J7TU1DRCg5za1rqwPFkxqQB2RuxhYphWkxMprBBMpusz4S5").1.ss58_prefix, 2);
+    assert_eq!(validate("yNasChxJYfNNbxHwGDmGzDbACwoLVyPkg14NJvo5HAWST8APZ").1.ss58_prefix, 16_383);
+  }
+
+  #[test]
+  fn test_ecdsa_public_key() {
+    let public = ecdsa::Pair::from_string("//Alice", None).unwrap().public();
+    let (code, info) = validate(&public.to_ss58check_with_version(Ss58AddressFormat::custom(1284)));
+    assert_eq!(code, VALID);
+    assert_eq!(info.ss58_prefix, 1284);
+    assert_eq!(info.payload_len, 33);
+    assert_eq!(info.kind, ADDRESS_KIND_ECDSA_PUBLIC_KEY);
+    assert_eq!(&info.payload[..], &public.0[..]);
+  }
+
+  #[test]
+  fn test_bad_base58() {
+    assert_eq!(validate("5Hbq0gsYaK54pN3QAgSi4t3Asqditt1X4P7cSBuDt2hPF2BR").0, ERR_SS58_BAD_BASE58);
+    assert_eq!(validate("5Hbqogs YaK54").0, ERR_SS58_BAD_BASE58);
+    assert_eq!(validate("5Hbqögs").0, ERR_SS58_BAD_BASE58);
+  }
+
+  #[test]
+  fn test_bad_length() {
+    // Truncated, so a 31-byte payload with a bad checksum
+    assert_eq!(validate(&ADDRESS[..ADDRESS.len() - 1]).0, ERR_SS58_BAD_LENGTH);
+    assert_eq!(
+      decode(&ADDRESS[..ADDRESS.len() - 1]).unwrap_err().message,
+      "SS58 payload is 31 bytes and fails its checksum, the address may be truncated or mistyped"
+    );
+    assert_eq!(validate("").0, ERR_SS58_BAD_LENGTH);
+    assert_eq!(validate(&ADDRESS.repeat(2)).0, ERR_SS58_BAD_LENGTH);
+    assert_eq!(validate(&"1".repeat(40)).0, ERR_SS58_BAD_LENGTH);
+  }
+
+  #[test]
+  fn test_wrong_key_length() {
+    // Valid checksums over 20-, 31- and 34-byte payloads
+    for (address, len) in [
+      ("tTgoSAJJvPreV4Q7jo514ZnuQoTkoYH", 20),
+      ("yKmb98NeuaYgH6XdBJ9rN5a3uhwoePKbJFBkKL5jNgWiGi", 31),
+      ("2SW8fTEazfpky5VgJBzo9Km5mGo16gsDaaocGuHdgLJXfU9rpZd", 34),
+    ] {
+      let error = decode(address).unwrap_err();
+      assert_eq!(error.code, ERR_WRONG_KEY_LENGTH, "{address}");
+      assert!(error.message.contains(&format!("{len}-byte")), "{}", error.message);
+    }
+    assert!(decode("tTgoSAJJvPreV4Q7jo514ZnuQoTkoYH").unwrap_err().message.contains("AccountId20"));
+  }
+
+  #[test]
+  fn test_bad_checksum() {
+    assert_eq!(validate(&ADDRESS.replace("hPF2BR", "hPF2BS")).0, ERR_SS58_BAD_CHECKSUM);
+  }
+
+  #[test]
+  fn test_bad_prefix() {
+    // Reserved prefix 46, and a first byte above the two-byte range
+    assert_eq!(validate("5gkUqf3A7UZySbfFnoxoaV6sTnCu7cXbp9L8tvURjT5LNAoF").0, ERR_BAD_SS58);
+    assert_eq!(validate("DmmS5zJcCEx2zWnNw9c3LM3Pj7Je2Tbd9PuokVKYGT7R2Mfm").0, ERR_BAD_SS58);
+  }
+
+  #[test]
+  fn test_agrees_with_sp_core() {
+    for address in [ADDRESS, "16Y8x28cS6LYFu3v8KViD2sKjTdNbBZf8sr6bUtaS7iuRDs5", "J7TU1DRCg5za1rqwPFkxqQB2RuxhYphWkxMprBBMpusz4S5"] {
+      let (public, version) = sp_core::sr25519::Public::from_ss58check_with_version(address).unwrap();
+      let decoded = decode(address).unwrap();
+      assert_eq!(&decoded.payload[..decoded.payload_len], &public.0[..]);
+      assert_eq!(decoded.prefix, u16::from(version));
+    }
+  }
+
+  #[test]
+  fn test_encode_agrees_with_sp_core() {
+    let sr25519 = sp_core::sr25519::Public::from_raw(hex::decode(PUBLIC_KEY_HEX).unwrap().try_into().unwrap());
+    let ecdsa = ecdsa::Pair::from_string("//Alice", None).unwrap().public();
+    for prefix in [0, 2, 42, 63, 64, 255, 1284, 16_383] {
+      let format = Ss58AddressFormat::custom(prefix);
+      assert_eq!(encode(sr25519.as_ref(), prefix), sr25519.to_ss58check_with_version(format), "{prefix}");
+      assert_eq!(encode(ecdsa.as_ref(), prefix), ecdsa.to_ss58check_with_version(format), "{prefix}");
+    }
+    assert_eq!(encode(&[0; 32], 0), sp_core::sr25519::Public::from_raw([0; 32]).to_ss58check_with_version(0u16.into()));
+  }
+}
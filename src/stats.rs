@@ -0,0 +1,100 @@
+//! Optional counters of verifications and the time they take.
+//!
+//! Counting is off until `stats_init` turns it on. While it is off, every
+//! exported function pays a single atomic load for it and no clock reads.
+//! The counters are relaxed atomics: each is exact, but a `get_stats` racing
+//! with verifications may see one counter updated before another.
+
+use crate::abi::Stats;
+use crate::log::{self, LOG_DEBUG};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Instant;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static TOTAL_VERIFICATIONS: AtomicU64 = AtomicU64::new(0);
+static FAILURES: AtomicU64 = AtomicU64::new(0);
+static TOTAL_NANOS: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+  /// When the outermost exported function running on this thread started,
+  /// if counting was on then.
+  static STARTED: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
+#[cfg(test)]
+thread_local! {
+  // Tests run in parallel, so under test only threads that opt in count.
+  pub(crate) static COUNTS_THIS_THREAD: Cell<bool> = const { Cell::new(false) };
+}
+
+fn enabled() -> bool {
+  #[cfg(test)]
+  if !COUNTS_THIS_THREAD.with(|counts| counts.get()) {
+    return false;
+  }
+  ENABLED.load(Ordering::Relaxed)
+}
+
+/// Whether an exported function running on this thread is being timed.
+#[cfg(test)]
+pub(crate) fn timing() -> bool {
+  STARTED.with(|started| started.get()).is_some()
+}
+
+/// Clears `STARTED` when the call that set it returns or unwinds.
+struct Started;
+
+impl Drop for Started {
+  fn drop(&mut self) {
+    STARTED.with(|started| started.set(None));
+  }
+}
+
+/// Run the body of an exported function, noting when it started so the
+/// verifications it records can be timed from entry. Calls made from within
+/// another exported function are timed as part of the outer one.
+pub(crate) fn timed<T>(body: impl FnOnce() -> T) -> T {
+  if !enabled() || STARTED.with(|started| started.get()).is_some() {
+    return body();
+  }
+  STARTED.with(|started| started.set(Some(Instant::now())));
+  let _started = Started;
+  body()
+}
+
+/// Count `count` verifications, `failures` of them not valid, and the time
+/// since the current exported function started.
+pub(crate) fn record(count: u64, failures: u64) {
+  if !enabled() {
+    return;
+  }
+  TOTAL_VERIFICATIONS.fetch_add(count, Ordering::Relaxed);
+  FAILURES.fetch_add(failures, Ordering::Relaxed);
+  if let Some(started) = STARTED.with(|started| started.get()) {
+    let nanos = u64::try_from(started.elapsed().as_nanos()).unwrap_or(u64::MAX);
+    TOTAL_NANOS.fetch_add(nanos, Ordering::Relaxed);
+  }
+}
+
+/// Turn counting on or off, keeping the counts.
+pub(crate) fn set_enabled(enabled: bool) {
+  ENABLED.store(enabled, Ordering::Relaxed);
+  log::log(LOG_DEBUG, || format!("verification counting {}", if enabled { "on" } else { "off" }));
+}
+
+/// The counters as they are now.
+pub(crate) fn snapshot() -> Stats {
+  Stats {
+    total_verifications: TOTAL_VERIFICATIONS.load(Ordering::Relaxed),
+    failures: FAILURES.load(Ordering::Relaxed),
+    total_nanos: TOTAL_NANOS.load(Ordering::Relaxed),
+  }
+}
+
+/// Set every counter back to 0.
+pub(crate) fn reset() {
+  for counter in [&TOTAL_VERIFICATIONS, &FAILURES, &TOTAL_NANOS] {
+    counter.store(0, Ordering::Relaxed);
+  }
+}
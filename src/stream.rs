@@ -0,0 +1,128 @@
+//! Incremental verification of messages too large for a single buffer.
+//!
+//! The message is fed in chunks into a blake2-256 hasher, following the
+//! polkadot-js convention of signing long messages as their hash.
+
+use crate::api::VerifyError;
+use crate::error::Error;
+use crate::message::MAX_UNHASHED_LEN;
+use crate::options::Options;
+use crate::parse::{decode_signature, parse_address, signature_array};
+use crate::verify;
+use crate::{VerifyOutcome, STREAM_PREHASH_ALWAYS, STREAM_PREHASH_LONG};
+use blake2::digest::consts::U32;
+use blake2::{Blake2b, Digest};
+
+pub(crate) struct StreamState {
+  address: String,
+  signature: String,
+  convention: i32,
+  /// The first bytes of the message, kept while it may still be short
+  /// enough to be signed directly.
+  head: Vec<u8>,
+  hasher: Blake2b<U32>,
+  len: u64,
+}
+
+impl StreamState {
+  /// Check the address, signature and convention before any message bytes
+  /// are read.
+  pub fn new(address: &str, signature: &str, convention: i32) -> Result<StreamState, Error> {
+    if convention != STREAM_PREHASH_LONG && convention != STREAM_PREHASH_ALWAYS {
+      return Err(VerifyError::InvalidOption { reason: format!("unknown stream convention {convention}") }.into());
+    }
+    parse_address(address)?.public_key()?;
+    signature_array::<64>(&decode_signature(signature, &Options::default())?)?;
+
+    Ok(StreamState {
+      address: address.to_owned(),
+      signature: signature.to_owned(),
+      convention,
+      head: Vec::new(),
+      hasher: Blake2b::new(),
+      len: 0,
+    })
+  }
+
+  pub fn update(&mut self, chunk: &[u8]) {
+    self.hasher.update(chunk);
+    self.len += chunk.len() as u64;
+    if self.len <= MAX_UNHASHED_LEN as u64 {
+      self.head.extend_from_slice(chunk);
+    }
+  }
+
+  /// Verify the signature over everything fed so far.
+  pub fn finish(self) -> Result<VerifyOutcome, Error> {
+    let message = if self.convention == STREAM_PREHASH_LONG && self.len <= MAX_UNHASHED_LEN as u64 {
+      self.head
+    } else {
+      self.hasher.finalize().to_vec()
+    };
+    verify::verify_sr25519(&self.address, &self.signature, &message, &Options::default())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::verify::verdict;
+  use crate::{INVALID, VALID};
+  use sp_core::hashing::blake2_256;
+  use sp_core::{crypto::Ss58Codec, sr25519, Pair};
+
+  /// //Alice's address and signature over `signed`.
+  fn alice(signed: &[u8]) -> (String, String) {
+    let pair = sr25519::Pair::from_string("//Alice", None).unwrap();
+    (pair.public().to_ss58check(), hex::encode(pair.sign(signed)))
+  }
+
+  fn stream(address: &str, signature: &str, convention: i32, message: &[u8], chunk_len: usize) -> i32 {
+    let mut state = StreamState::new(address, signature, convention).unwrap();
+    for chunk in message.chunks(chunk_len) {
+      state.update(chunk);
+    }
+    verdict(state.finish().map(|outcome| outcome.valid == 1))
+  }
+
+  #[test]
+  fn test_long_message() {
+    let message: Vec<u8> = (0..10 * 1024 * 1024).map(|i| (i % 251) as u8).collect();
+    let (address, signature) = alice(&blake2_256(&message));
+    assert_eq!(stream(&address, &signature, STREAM_PREHASH_LONG, &message, 4096), VALID);
+
+    let mut tampered = message;
+    tampered[5 * 1024 * 1024] ^= 1;
+    assert_eq!(stream(&address, &signature, STREAM_PREHASH_LONG, &tampered, 4096), INVALID);
+  }
+
+  #[test]
+  fn test_short_message() {
+    let message = b"short enough to be signed directly";
+    let (address, signature) = alice(message);
+    assert_eq!(stream(&address, &signature, STREAM_PREHASH_LONG, message, 5), VALID);
+    assert_eq!(stream(&address, &signature, STREAM_PREHASH_ALWAYS, message, 5), INVALID);
+
+    let (address, signature) = alice(&blake2_256(message));
+    assert_eq!(stream(&address, &signature, STREAM_PREHASH_ALWAYS, message, 5), VALID);
+  }
+
+  #[test]
+  fn test_boundary() {
+    let message = [9u8; MAX_UNHASHED_LEN];
+    let (address, signature) = alice(&message);
+    assert_eq!(stream(&address, &signature, STREAM_PREHASH_LONG, &message, 100), VALID);
+
+    let message = [9u8; MAX_UNHASHED_LEN + 1];
+    let (address, signature) = alice(&blake2_256(&message));
+    assert_eq!(stream(&address, &signature, STREAM_PREHASH_LONG, &message, 100), VALID);
+  }
+
+  #[test]
+  fn test_invalid_arguments() {
+    let (address, signature) = alice(b"");
+    let error = |signature: &str, convention| StreamState::new(&address, signature, convention).err().unwrap().message;
+    assert_eq!(error("0xabcd", STREAM_PREHASH_LONG), "signature is 2 bytes, expected 64");
+    assert_eq!(error(&signature, 7), "unknown stream convention 7");
+  }
+}
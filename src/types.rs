@@ -0,0 +1,60 @@
+//! Request and outcome shapes shared by every JSON entry point.
+//!
+//! [`VerificationRequest`], [`RequestOptions`] and [`VerificationOutcome`]
+//! are defined in `polkadot_sig_verifier_core::types`, so the browser build
+//! reads and reports the same JSON as `verify_json`, `verify_ndjson` and
+//! [`crate::verify_request`].
+
+use crate::api::VerifyError;
+use crate::error::Error;
+use crate::SignatureScheme;
+pub use polkadot_sig_verifier_core::types::{RequestOptions, VerificationOutcome, VerificationRequest};
+
+/// The scheme `options` names, sr25519 when it names none.
+pub(crate) fn scheme(options: &RequestOptions) -> Result<SignatureScheme, Error> {
+  match &options.scheme {
+    None => Ok(SignatureScheme::Sr25519),
+    Some(name) => SignatureScheme::from_name(name)
+      .ok_or_else(|| VerifyError::UnknownScheme { reason: format!("unknown signature scheme {name:?}") }.into()),
+  }
+}
+
+/// The outcome of a request verified under `scheme`.
+pub(crate) fn verified(scheme: SignatureScheme, valid: bool) -> VerificationOutcome {
+  VerificationOutcome::verified(scheme.name(), valid)
+}
+
+/// The outcome of a request that failed, under `scheme` if it got that far.
+pub(crate) fn failed(scheme: Option<SignatureScheme>, error: Error) -> VerificationOutcome {
+  VerificationOutcome::failed(scheme.map(SignatureScheme::name), error.code, error.message)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::ERR_UNKNOWN_SCHEME;
+  use serde_json::{json, Value};
+
+  #[test]
+  fn test_outcomes() {
+    let outcome = serde_json::to_value(verified(SignatureScheme::Ecdsa, true)).unwrap();
+    assert_eq!(outcome, json!({ "valid": true, "scheme": "ecdsa" }));
+    let options = RequestOptions { scheme: Some("rsa".to_owned()), ..RequestOptions::default() };
+    let outcome = serde_json::to_value(failed(None, scheme(&options).unwrap_err())).unwrap();
+    let message = "unknown signature scheme \"rsa\"";
+    assert_eq!(outcome, json!({ "valid": false, "error_code": ERR_UNKNOWN_SCHEME, "error_message": message }));
+  }
+
+  /// Requests and outcomes as clients of the first release wrote and read
+  /// them. Changing the expected JSON breaks those clients.
+  #[test]
+  fn test_pinned_fixture() {
+    let fixture: Value = serde_json::from_str(include_str!("../tests/fixtures/verification.json")).unwrap();
+    for case in fixture["cases"].as_array().unwrap() {
+      let request: VerificationRequest = serde_json::from_value(case["request"].clone()).unwrap();
+      assert_eq!(serde_json::to_value(&request).unwrap(), case["request"], "{}", case["name"]);
+      let outcome = crate::verify_request(&request);
+      assert_eq!(serde_json::to_value(&outcome).unwrap(), case["outcome"], "{}", case["name"]);
+    }
+  }
+}
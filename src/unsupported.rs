@@ -0,0 +1,25 @@
+//! Stand-ins for modules left out by a cargo feature.
+//!
+//! Without `ecdsa` there is no secp256k1 recovery, so Ethereum-style
+//! signatures fail with `ERR_SCHEME_NOT_SUPPORTED`. The C exports of the
+//! modules that need `sp-core` have their stand-ins in `ffi`.
+
+use crate::api::VerifyError;
+use crate::error::Error;
+
+/// Why `what` fails in a build without `feature`.
+pub(crate) fn unsupported(what: &str, feature: &str) -> Error {
+  let reason = format!("{what} is not supported by this build, which lacks the {feature} feature");
+  VerifyError::SchemeNotSupported { reason }.into()
+}
+
+#[cfg(not(feature = "ecdsa"))]
+pub(crate) mod ethereum {
+  use super::*;
+
+  /// `ensure_supported` rejects Ethereum-style signatures before this is
+  /// reached.
+  pub(crate) fn verify_ethereum(_: &str, _: &str, _: &[u8]) -> Result<bool, Error> {
+    Err(unsupported("ethereum signature verification", "ecdsa"))
+  }
+}
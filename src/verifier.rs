@@ -0,0 +1,64 @@
+//! Verifiers that parse an address once and check many signatures by it.
+//!
+//! The parsed address is read-only, so one verifier per address can be
+//! shared between any number of threads.
+
+use crate::error::Error;
+use crate::message;
+use crate::options::Options;
+use crate::verify::{self, Account};
+
+pub(crate) struct VerifierState {
+  account: Account,
+  options: Options<'static>,
+}
+
+impl VerifierState {
+  /// Parse `address` as `verify_polkadot_signature_v2` would.
+  pub(crate) fn new(address: &str) -> Result<VerifierState, Error> {
+    let options = Options::default();
+    Ok(VerifierState { account: verify::parse_account(address, &options)?, options })
+  }
+
+  /// Verify a signature by the address over `message`, as
+  /// `verify_polkadot_signature_bytes` would.
+  pub(crate) fn verify(&self, signature: &str, message: &[u8]) -> Result<bool, Error> {
+    let message = message::decode(message, &self.options)?;
+    let outcome = verify::verify_sr25519_account(&self.account, signature, &message, &self.options)?;
+    Ok(outcome.valid == 1)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::verify::verdict;
+  use crate::{ERR_BAD_HEX, ERR_SS58_BAD_CHECKSUM, INVALID, VALID};
+  use sp_core::crypto::Ss58Codec;
+  use sp_core::{sr25519, Pair};
+
+  #[test]
+  fn test_verify() {
+    let pair = sr25519::Pair::from_string("//Alice", None).unwrap();
+    let verifier = VerifierState::new(&pair.public().to_ss58check()).unwrap();
+    let signed = b"hello\0relay";
+    let signature = format!("0x{}", hex::encode(pair.sign(signed)));
+    let long = [7u8; 300];
+    let long_signature = format!("0x{}", hex::encode(pair.sign(&sp_core::hashing::blake2_256(&long))));
+
+    assert_eq!(verdict(verifier.verify(&signature, signed)), VALID);
+    assert_eq!(verdict(verifier.verify(&signature[2..], signed)), VALID);
+    assert_eq!(verdict(verifier.verify(&signature, b"hello")), INVALID);
+    assert_eq!(verdict(verifier.verify("0xzz", signed)), ERR_BAD_HEX);
+    assert_eq!(verdict(verifier.verify(&long_signature, &long)), VALID);
+  }
+
+  #[test]
+  fn test_bad_address() {
+    let error = VerifierState::new("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQZ");
+    assert_eq!(error.err().map(|error| error.code), Some(ERR_SS58_BAD_CHECKSUM));
+
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<VerifierState>();
+  }
+}
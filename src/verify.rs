@@ -0,0 +1,444 @@
+//! Signature verification for each supported scheme.
+
+use crate::address_cache;
+use crate::api;
+#[cfg(feature = "ffi")]
+use crate::backend::SR25519_SIGNING_CONTEXT;
+#[cfg(feature = "ffi")]
+use crate::canonical;
+use crate::error::Error;
+#[cfg(feature = "ffi")]
+use crate::error::set_last_error;
+use crate::log::{self, LOG_DEBUG};
+#[cfg(feature = "ffi")]
+use crate::log::{LOG_INFO, LOG_WARN};
+use crate::message;
+#[cfg(feature = "ffi")]
+use crate::message::blake2_256;
+use crate::options::Options;
+use crate::parse::decode_signature;
+#[cfg(feature = "ffi")]
+use crate::parse::signature_array;
+use crate::schemes::{self, Ecdsa, Ed25519, Scheme, Sr25519, SCHEMES};
+#[cfg(feature = "ffi")]
+use crate::stats;
+use crate::{SignatureScheme, VerifyOutcome, INVALID, VALID};
+#[cfg(feature = "ffi")]
+use crate::{
+  ADDRESS_KIND_ACCOUNT_ID, ADDRESS_KIND_ECDSA_PUBLIC_KEY, LEDGER_BLAKE2_256, LEDGER_NONE, LEDGER_RAW, MESSAGE_AS_GIVEN,
+  MESSAGE_NONE, MESSAGE_UNWRAPPED, MESSAGE_WRAPPED,
+};
+#[cfg(feature = "ffi")]
+use std::borrow::Cow;
+
+/// An SS58 address decoded and checked against the options.
+pub(crate) struct Account {
+  pub(crate) id: [u8; 32],
+  pub(crate) ss58_prefix: u16,
+  /// `ADDRESS_KIND_*` of the address payload.
+  pub(crate) kind: i32,
+}
+
+pub(crate) fn parse_account(address: &str, options: &Options) -> Result<Account, Error> {
+  let address = address_cache::parse_address_cached(address)?;
+  options.check_prefix(address.prefix)?;
+  Ok(Account { id: address.account_id, ss58_prefix: address.prefix.unwrap_or(0), kind: address.kind })
+}
+
+/// Apply `options` to the message and verify a decoded signature with
+/// `scheme`.
+fn verify_raw(
+  scheme: &dyn Scheme,
+  account: &Account,
+  signature: &[u8],
+  message: &[u8],
+  options: &Options,
+) -> Result<VerifyOutcome, Error> {
+  let expected = scheme.signature_len();
+  if signature.len() != expected {
+    return Err(api::VerifyError::WrongSignatureLength { found: signature.len(), expected }.into());
+  }
+  if !scheme.account_matches(account) {
+    // The address is well formed, it just holds the wrong kind of key
+    let reason = format!("address holds a 33-byte ECDSA public key, which a {expected}-byte signature cannot match");
+    return Err(api::VerifyError::NoMatchingScheme { reason }.into());
+  }
+
+  let message = message::canonicalize_message(message, options)?;
+  let valid = scheme.verify(signature, &message, account.id, options)?;
+
+  Ok(VerifyOutcome {
+    valid: valid as u8,
+    error_code: if valid { VALID } else { INVALID },
+    public_key: account.id,
+    ss58_prefix: account.ss58_prefix,
+  })
+}
+
+/// Parse the arguments, apply `options` and verify with `scheme`.
+fn verify_with(
+  scheme: &dyn Scheme,
+  address: &str,
+  signature_hex: &str,
+  message: &[u8],
+  options: &Options,
+) -> Result<VerifyOutcome, Error> {
+  let account = parse_account(address, options)?;
+  let signature = decode_signature(signature_hex, options)?;
+  verify_raw(scheme, &account, &signature, message, options)
+}
+
+/// Verify an sr25519 `message` against an SS58 address and a hex signature.
+///
+/// With `options.strip_scheme_prefix`, a 65-byte signature whose first
+/// byte is a `MultiSignature` tag is also accepted, as returned by some
+/// wallets' `signRaw`: 0x01 verifies the rest as sr25519, 0x00 as ed25519.
+pub(crate) fn verify_sr25519(
+  address: &str,
+  signature_hex: &str,
+  message: &[u8],
+  options: &Options,
+) -> Result<VerifyOutcome, Error> {
+  verify_sr25519_account(&parse_account(address, options)?, signature_hex, message, options)
+}
+
+/// Verify an sr25519 `message` against an address parsed beforehand, as
+/// `verify_sr25519` does once it has parsed the address.
+pub(crate) fn verify_sr25519_account(
+  account: &Account,
+  signature_hex: &str,
+  message: &[u8],
+  options: &Options,
+) -> Result<VerifyOutcome, Error> {
+  let signature = decode_signature(signature_hex, options)?;
+
+  if options.strip_scheme_prefix && signature.len() == 65 {
+    // Only the tags of 64-byte schemes leave 64 bytes to verify
+    let prefixed = SCHEMES.into_iter().filter(|scheme| scheme.signature_len() == 64);
+    let Some(scheme) = prefixed.clone().find(|scheme| scheme.multisignature_tag() == signature[0]) else {
+      let mut tags: Vec<_> = prefixed.map(|scheme| format!("{:#04x}", scheme.multisignature_tag())).collect();
+      tags.sort();
+      let reason =
+        format!("65-byte signature starts with {:#04x}, expected a {} scheme byte", signature[0], tags.join(" or "));
+      return Err(api::VerifyError::InvalidSignaturePrefix { reason }.into());
+    };
+    log::log(LOG_DEBUG, || format!("stripped scheme byte {:#04x} from signature", signature[0]));
+    return verify_scheme(scheme.scheme(), account, &signature[1..], message, options);
+  }
+
+  verify_raw(&Sr25519, account, &signature, message, options)
+}
+
+/// Verify an sr25519 `message` by a signature decoded beforehand, as
+/// `verify_sr25519_account` does once it has decoded the signature.
+#[cfg(feature = "ffi")]
+pub(crate) fn verify_sr25519_decoded(
+  account: &Account,
+  signature: [u8; 64],
+  message: &[u8],
+  options: &Options,
+) -> Result<VerifyOutcome, Error> {
+  verify_raw(&Sr25519, account, &signature, message, options)
+}
+
+/// An sr25519 signature ready for schnorrkel's batch verification.
+#[cfg(feature = "ffi")]
+pub(crate) struct Sr25519Item<'a> {
+  public_key: schnorrkel::PublicKey,
+  signature: schnorrkel::Signature,
+  /// The message as `verify_sr25519` checks it, after `canonicalize_message`.
+  message: Cow<'a, [u8]>,
+}
+
+/// Parse what `verify_sr25519` would check, for `check_sr25519_batch`.
+///
+/// `None` if any step before the final check would fail, or schnorrkel
+/// rejects the key or signature encoding; verifying such an item alone
+/// gives its result. `options.strip_scheme_prefix` is not supported.
+#[cfg(feature = "ffi")]
+pub(crate) fn prepare_sr25519<'a>(
+  address: &str,
+  signature_hex: &str,
+  message: &'a [u8],
+  options: &Options,
+) -> Option<Sr25519Item<'a>> {
+  debug_assert!(!options.strip_scheme_prefix, "prefixed signatures are verified alone");
+  let account = parse_account(address, options).ok()?;
+  if account.kind == ADDRESS_KIND_ECDSA_PUBLIC_KEY {
+    return None;
+  }
+  let signature = signature_array::<64>(&decode_signature(signature_hex, options).ok()?).ok()?;
+  canonical::check_sr25519_reduced_s(&signature).ok()?;
+  Some(Sr25519Item {
+    public_key: schnorrkel::PublicKey::from_bytes(&account.id).ok()?,
+    signature: schnorrkel::Signature::from_bytes(&signature).ok()?,
+    message: message::canonicalize_message(message, options).ok()?,
+  })
+}
+
+/// Check `items` in one schnorrkel batch, under the signing context
+/// `sr25519::Pair::verify` uses. True only if every signature is valid;
+/// which one is not is left to verifying them alone.
+#[cfg(feature = "ffi")]
+pub(crate) fn check_sr25519_batch(items: &[Sr25519Item]) -> bool {
+  if items.is_empty() {
+    return true;
+  }
+  let context = schnorrkel::signing_context(SR25519_SIGNING_CONTEXT);
+  let transcripts = items.iter().map(|item| context.bytes(&item.message));
+  let signatures: Vec<_> = items.iter().map(|item| item.signature).collect();
+  let public_keys: Vec<_> = items.iter().map(|item| item.public_key).collect();
+  schnorrkel::verify_batch(transcripts, &signatures, &public_keys, false).is_ok()
+}
+
+/// Verify an ed25519 `message` against an SS58 address and a hex signature.
+pub(crate) fn verify_ed25519(
+  address: &str,
+  signature_hex: &str,
+  message: &[u8],
+  options: &Options,
+) -> Result<VerifyOutcome, Error> {
+  SignatureScheme::Ed25519.ensure_supported()?;
+  verify_with(&Ed25519, address, signature_hex, message, options)
+}
+
+/// Verify an ECDSA `message` against an SS58 address and a 65-byte
+/// recoverable hex signature.
+pub(crate) fn verify_ecdsa(
+  address: &str,
+  signature_hex: &str,
+  message: &[u8],
+  options: &Options,
+) -> Result<VerifyOutcome, Error> {
+  SignatureScheme::Ecdsa.ensure_supported()?;
+  verify_with(&Ecdsa, address, signature_hex, message, options)
+}
+
+/// Verify a raw signature with the given scheme.
+pub(crate) fn verify_scheme(
+  scheme: SignatureScheme,
+  account: &Account,
+  signature: &[u8],
+  message: &[u8],
+  options: &Options,
+) -> Result<VerifyOutcome, Error> {
+  scheme.ensure_supported()?;
+  match schemes::lookup(scheme) {
+    Some(entry) => verify_raw(entry, account, signature, message, options),
+    // Only Ethereum-style signatures have no SS58 address
+    None => {
+      let reason = "ethereum signatures are verified against 20-byte addresses, not SS58".to_owned();
+      Err(api::VerifyError::UnknownScheme { reason }.into())
+    }
+  }
+}
+
+/// Verify a `message` against a key whose scheme is known from elsewhere,
+/// such as a `did:key`, and a hex signature.
+#[cfg(feature = "ffi")]
+pub(crate) fn verify_key(
+  scheme: SignatureScheme,
+  account_id: [u8; 32],
+  signature_hex: &str,
+  message: &[u8],
+  options: &Options,
+) -> Result<VerifyOutcome, Error> {
+  let account = Account { id: account_id, ss58_prefix: 0, kind: ADDRESS_KIND_ACCOUNT_ID };
+  let signature = decode_signature(signature_hex, options)?;
+  verify_scheme(scheme, &account, &signature, message, options)
+}
+
+/// Verify a `message` with an explicit scheme against an SS58 address and a
+/// hex signature.
+pub(crate) fn verify_as(
+  scheme: SignatureScheme,
+  address: &str,
+  signature_hex: &str,
+  message: &[u8],
+  options: &Options,
+) -> Result<VerifyOutcome, Error> {
+  match scheme {
+    SignatureScheme::Sr25519 => verify_sr25519(address, signature_hex, message, options),
+    scheme => {
+      let account = parse_account(address, options)?;
+      let signature = decode_signature(signature_hex, options)?;
+      verify_scheme(scheme, &account, &signature, message, options)
+    }
+  }
+}
+
+/// Verify with whichever scheme the signature length allows.
+///
+/// 64-byte signatures are tried as sr25519, then ed25519; 65-byte
+/// signatures can only be ECDSA. Returns the outcome together with the
+/// scheme that verified, if any.
+pub(crate) fn verify_any(
+  address: &str,
+  signature_hex: &str,
+  message: &[u8],
+  options: &Options,
+) -> Result<(VerifyOutcome, Option<SignatureScheme>), Error> {
+  let account = parse_account(address, options)?;
+  let signature = decode_signature(signature_hex, options)?;
+
+  let candidates: Vec<_> = SCHEMES.into_iter().filter(|scheme| scheme.signature_len() == signature.len()).collect();
+  if candidates.is_empty() {
+    let reason = format!("signature is {} bytes, expected {}", signature.len(), signature_lengths());
+    return Err(api::VerifyError::NoMatchingScheme { reason }.into());
+  }
+
+  let mut last = None;
+  for scheme in candidates {
+    let scheme = scheme.scheme();
+    // A scheme left out of this build only fails the call when no scheme
+    // of the same length was tried before it
+    if last.is_some() && !scheme.is_supported() {
+      continue;
+    }
+    let outcome = verify_scheme(scheme, &account, &signature, message, options)?;
+    if outcome.valid == 1 {
+      return Ok((outcome, Some(scheme)));
+    }
+    last = Some(outcome);
+  }
+  Ok((last.expect("every length has a candidate"), None))
+}
+
+/// The signature lengths of the registered schemes, such as
+/// `64 (sr25519, ed25519) or 65 (ecdsa)`.
+fn signature_lengths() -> String {
+  let mut lengths: Vec<(usize, Vec<&str>)> = Vec::new();
+  for scheme in SCHEMES {
+    match lengths.iter_mut().find(|(length, _)| *length == scheme.signature_len()) {
+      Some((_, names)) => names.push(scheme.scheme().name()),
+      None => lengths.push((scheme.signature_len(), vec![scheme.scheme().name()])),
+    }
+  }
+  let lengths: Vec<_> = lengths.iter().map(|(length, names)| format!("{length} ({})", names.join(", "))).collect();
+  lengths.join(" or ")
+}
+
+/// The scheme of a `MultiSignature` variant tag, and the length of the
+/// signature that follows it.
+pub(crate) fn multisignature_variant(tag: u8) -> Result<(SignatureScheme, usize), Error> {
+  let Some(entry) = SCHEMES.into_iter().find(|scheme| scheme.multisignature_tag() == tag) else {
+    let reason = format!("unknown MultiSignature variant {tag:#04x}");
+    return Err(api::VerifyError::InvalidMultiSignature { reason }.into());
+  };
+  Ok((entry.scheme(), entry.signature_len()))
+}
+
+/// Verify a SCALE-encoded `MultiSignature`: a variant tag (0 ed25519,
+/// 1 sr25519, 2 ecdsa) followed by the signature bytes.
+pub(crate) fn verify_multisignature(
+  address: &str,
+  multisignature_hex: &str,
+  message: &[u8],
+  options: &Options,
+) -> Result<VerifyOutcome, Error> {
+  let account = parse_account(address, options)?;
+  let encoded = decode_signature(multisignature_hex, options)?;
+
+  let Some(&tag) = encoded.first() else {
+    return Err(api::VerifyError::InvalidMultiSignature { reason: "MultiSignature is empty".to_owned() }.into());
+  };
+  let (scheme, expected_len) = multisignature_variant(tag)?;
+  let signature = &encoded[1..];
+  if signature.len() != expected_len {
+    let reason =
+      format!("MultiSignature variant {:#04x} carries {} bytes, expected {expected_len}", encoded[0], signature.len());
+    return Err(api::VerifyError::InvalidMultiSignature { reason }.into());
+  }
+
+  verify_scheme(scheme, &account, signature, message, options)
+}
+
+/// Verify an sr25519 `message`, retrying with the `<Bytes>` wrapper added
+/// or removed.
+///
+/// The message is tried as given first. A wrapped message is then retried
+/// without its wrapper, any other message with one. `options.wrap_bytes` is
+/// ignored. Returns the outcome together with the `MESSAGE_*` form that
+/// verified, or `MESSAGE_NONE`.
+#[cfg(feature = "ffi")]
+pub(crate) fn verify_auto(
+  address: &str,
+  signature_hex: &str,
+  message: &[u8],
+  options: &Options,
+) -> Result<(VerifyOutcome, i32), Error> {
+  let options = Options { wrap_bytes: false, ..*options };
+  let account = parse_account(address, &options)?;
+  let signature = decode_signature(signature_hex, &options)?;
+
+  let outcome = verify_raw(&Sr25519, &account, &signature, message, &options)?;
+  if outcome.valid == 1 {
+    return Ok((outcome, MESSAGE_AS_GIVEN));
+  }
+
+  let (form, retry) = if message::is_wrapped(message) {
+    (MESSAGE_UNWRAPPED, message::unwrap_bytes(message).into())
+  } else {
+    (MESSAGE_WRAPPED, message::wrap_bytes(message))
+  };
+  let outcome = verify_raw(&Sr25519, &account, &signature, &retry, &options)?;
+  if outcome.valid == 1 {
+    log::log(LOG_DEBUG, || format!("signature matches the message in form {form}"));
+    return Ok((outcome, form));
+  }
+  Ok((outcome, MESSAGE_NONE))
+}
+
+/// Verify an ed25519 signature made by the Polkadot Ledger app.
+///
+/// Conventions are tried in order: the raw message, then `blake2_256` of
+/// the message. Neither wraps the message in `<Bytes>` or applies the
+/// polkadot-js hashing of long messages. Returns the outcome together with
+/// the `LEDGER_*` convention that verified, or `LEDGER_NONE`.
+#[cfg(feature = "ffi")]
+pub(crate) fn verify_ledger(
+  address: &str,
+  signature_hex: &str,
+  message: &[u8],
+  options: &Options,
+) -> Result<(VerifyOutcome, i32), Error> {
+  SignatureScheme::Ed25519.ensure_supported()?;
+  let options = Options { wrap_bytes: false, skip_long_message_hash: true, ..*options };
+  let account = parse_account(address, &options)?;
+  let signature = decode_signature(signature_hex, &options)?;
+
+  let outcome = verify_raw(&Ed25519, &account, &signature, message, &options)?;
+  if outcome.valid == 1 {
+    return Ok((outcome, LEDGER_RAW));
+  }
+
+  let outcome = verify_raw(&Ed25519, &account, &signature, &blake2_256(message), &options)?;
+  if outcome.valid == 1 {
+    log::log(LOG_DEBUG, || "signature matches the blake2-256 hash of the message".to_owned());
+    return Ok((outcome, LEDGER_BLAKE2_256));
+  }
+  Ok((outcome, LEDGER_NONE))
+}
+
+/// Collapse a verification result into a return code, recording failures
+/// in the last-error slot.
+#[cfg(feature = "ffi")]
+pub(crate) fn verdict(result: Result<bool, Error>) -> i32 {
+  stats::record(1, !matches!(result, Ok(true)) as u64);
+  match result {
+    Ok(true) => {
+      log::log(LOG_INFO, || "signature is valid".to_owned());
+      VALID
+    }
+    Ok(false) => {
+      log::log(LOG_INFO, || "signature is invalid".to_owned());
+      set_last_error(&api::VerifyError::SignatureMismatch.into());
+      INVALID
+    }
+    Err(error) => {
+      log::log(LOG_WARN, || format!("verification failed with code {}: {}", error.code, error.message));
+      set_last_error(&error);
+      error.code
+    }
+  }
+}
@@ -0,0 +1,116 @@
+//! sr25519 VRF proof verification.
+
+use crate::error::Error;
+use crate::log::{self, LOG_DEBUG};
+use crate::parse::{decode_hex_array, parse_address};
+use schnorrkel::vrf::{VRFPreOut, VRFProof};
+use schnorrkel::{signing_context, PublicKey};
+
+/// Verify a schnorrkel VRF proof of `input` under the transcript `label`.
+///
+/// # Returns
+/// The 32 bytes of randomness derived from the VRF output, using `label`
+/// as the `make_bytes` context, or `None` if the proof does not verify.
+pub(crate) fn verify_vrf(
+  address: &str,
+  output_hex: &str,
+  proof_hex: &str,
+  input: &[u8],
+  label: &str,
+) -> Result<Option<[u8; 32]>, Error> {
+  let public_key = parse_address(address)?.public_key()?;
+  let output = decode_hex_array::<32>(output_hex, "VRF output")?;
+  let proof = decode_hex_array::<64>(proof_hex, "VRF proof")?;
+
+  // Keys, outputs and proofs that are not valid curve points or scalars
+  // can never verify, so they count as invalid rather than malformed
+  let (Ok(public_key), Ok(output), Ok(proof)) =
+    (PublicKey::from_bytes(&public_key), VRFPreOut::from_bytes(&output), VRFProof::from_bytes(&proof))
+  else {
+    log::log(LOG_DEBUG, || "VRF key, output or proof is not canonical".to_owned());
+    return Ok(None);
+  };
+
+  let context = signing_context(label.as_bytes());
+  match public_key.vrf_verify(context.bytes(input), &output, &proof) {
+    Ok((inout, _)) => Ok(Some(inout.make_bytes(label.as_bytes()))),
+    Err(_) => Ok(None),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{ERR_BAD_HEX, ERR_BAD_HEX_LENGTH};
+  use schnorrkel::{ExpansionMode, Keypair, MiniSecretKey};
+  use sp_core::crypto::Ss58Codec;
+  use sp_core::sr25519;
+
+  const LABEL: &str = "fair-draw";
+  const INPUT: &[u8] = b"round 7";
+
+  fn keypair() -> Keypair {
+    MiniSecretKey::from_bytes(&[7u8; 32]).unwrap().expand_to_keypair(ExpansionMode::Ed25519)
+  }
+
+  fn address(keypair: &Keypair) -> String {
+    sr25519::Public::from_raw(keypair.public.to_bytes()).to_ss58check()
+  }
+
+  /// Hex VRF output, hex proof and expected randomness.
+  fn sign(keypair: &Keypair, label: &str, input: &[u8]) -> (String, String, [u8; 32]) {
+    let (inout, proof, _) = keypair.vrf_sign(signing_context(label.as_bytes()).bytes(input));
+    let output = hex::encode(inout.to_preout().to_bytes());
+    (output, hex::encode(proof.to_bytes()), inout.make_bytes(label.as_bytes()))
+  }
+
+  #[test]
+  fn test_valid_proof() {
+    let keypair = keypair();
+    let (output, proof, randomness) = sign(&keypair, LABEL, INPUT);
+    assert_eq!(verify_vrf(&address(&keypair), &output, &proof, INPUT, LABEL), Ok(Some(randomness)));
+    assert_eq!(verify_vrf(&address(&keypair), &format!("0x{output}"), &proof, INPUT, LABEL), Ok(Some(randomness)));
+  }
+
+  #[test]
+  fn test_randomness_is_deterministic() {
+    // Proofs are randomized, the output is not
+    let keypair = keypair();
+    let (first_output, first_proof, first) = sign(&keypair, LABEL, INPUT);
+    let (second_output, second_proof, second) = sign(&keypair, LABEL, INPUT);
+    assert_eq!(first_output, second_output);
+    assert_ne!(first_proof, second_proof);
+    assert_eq!(first, second);
+  }
+
+  #[test]
+  fn test_tampered_proof() {
+    let keypair = keypair();
+    let (output, proof, _) = sign(&keypair, LABEL, INPUT);
+    let mut tampered = hex::decode(&proof).unwrap();
+    tampered[0] ^= 1;
+    assert_eq!(verify_vrf(&address(&keypair), &output, &hex::encode(tampered), INPUT, LABEL), Ok(None));
+  }
+
+  #[test]
+  fn test_wrong_input_label_or_key() {
+    let keypair = keypair();
+    let (output, proof, _) = sign(&keypair, LABEL, INPUT);
+    assert_eq!(verify_vrf(&address(&keypair), &output, &proof, b"round 8", LABEL), Ok(None));
+    assert_eq!(verify_vrf(&address(&keypair), &output, &proof, INPUT, "other-app"), Ok(None));
+
+    let alice = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+    assert_eq!(verify_vrf(alice, &output, &proof, INPUT, LABEL), Ok(None));
+  }
+
+  #[test]
+  fn test_malformed_hex() {
+    let keypair = keypair();
+    let (output, proof, _) = sign(&keypair, LABEL, INPUT);
+    let err = verify_vrf(&address(&keypair), &output[2..], &proof, INPUT, LABEL).unwrap_err();
+    assert_eq!((err.code, err.message.as_str()), (ERR_BAD_HEX_LENGTH, "VRF output is 31 bytes, expected 32"));
+
+    let err = verify_vrf(&address(&keypair), &output, &format!("zz{}", &proof[2..]), INPUT, LABEL).unwrap_err();
+    assert_eq!((err.code, err.message.as_str()), (ERR_BAD_HEX, "VRF proof hex has invalid character 'z' at position 0"));
+  }
+}
@@ -0,0 +1,42 @@
+import uniffi.polkadot_sig_verifier.*
+
+val ADDRESS = "5HbqogsYaK54pN3QAgSi4t3Asqditt1X4P7cSBuDt2hPF2BR"
+val SIGNATURE = "0xf8ce1b80e76bf48a30610d0d3c2c9c8dbfdcb28a9aa8f56ac038d520ff17445ff3ce94809f8479a1224ef0d823d035022a9db2e72bd6c0d2f0244f766dec908b"
+val MESSAGE = "<Bytes>c15335d817e3d3d912d30f5a18a30c30162abc364cd0876d3a94af568d3c8c14</Bytes>".toByteArray()
+val ALICE = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"
+// The `//Alice` development seed
+val SEED = "0xe5be9a5092b81bca64be81d212e7f2f9eba183bb7a90954f7b76361f6edb5c0a"
+
+val outcome = verifySignature(ADDRESS, SIGNATURE, MESSAGE)
+assert(outcome.valid)
+assert(outcome.scheme == "sr25519")
+assert(outcome.errorCode == null)
+assert(!verifySignature(ADDRESS, SIGNATURE, "tampered".toByteArray()).valid)
+
+try {
+    verifySignature(ADDRESS, SIGNATURE.substring(0, 128), MESSAGE)
+    throw RuntimeException("a short signature should throw")
+} catch (e: VerifyException.WrongSignatureLength) {
+    assert(e.found == 63uL && e.expected == 64uL)
+}
+
+try {
+    verifySignature(ADDRESS.replace("hPF2BR", "hPF2BS"), SIGNATURE, MESSAGE)
+    throw RuntimeException("a corrupted address should throw")
+} catch (e: VerifyException.InvalidSs58) {
+    assert(e.reason == Ss58Issue.BAD_CHECKSUM)
+}
+
+Keypair.fromSeed(SEED, SignatureScheme.SR25519).use { keypair ->
+    assert(keypair.scheme() == SignatureScheme.SR25519)
+    assert(keypair.ss58(42u) == ALICE)
+    val message = byteArrayOf(0, 1, 2, -1)
+    assert(verifySignature(ALICE, keypair.sign(message), message).valid)
+}
+
+try {
+    Keypair.fromSeed(SEED, SignatureScheme.ETHEREUM)
+    throw RuntimeException("an ethereum keypair should throw")
+} catch (e: VerifyException.Other) {
+    assert(e.code < 0)
+}
@@ -0,0 +1,43 @@
+import Foundation
+import polkadot_sig_verifier
+
+let ADDRESS = "5HbqogsYaK54pN3QAgSi4t3Asqditt1X4P7cSBuDt2hPF2BR"
+let SIGNATURE = "0xf8ce1b80e76bf48a30610d0d3c2c9c8dbfdcb28a9aa8f56ac038d520ff17445ff3ce94809f8479a1224ef0d823d035022a9db2e72bd6c0d2f0244f766dec908b"
+let MESSAGE = Data("<Bytes>c15335d817e3d3d912d30f5a18a30c30162abc364cd0876d3a94af568d3c8c14</Bytes>".utf8)
+let ALICE = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"
+// The `//Alice` development seed
+let SEED = "0xe5be9a5092b81bca64be81d212e7f2f9eba183bb7a90954f7b76361f6edb5c0a"
+
+let outcome = try! verifySignature(address: ADDRESS, signatureHex: SIGNATURE, message: MESSAGE)
+assert(outcome.valid)
+assert(outcome.scheme == "sr25519")
+assert(outcome.errorCode == nil)
+assert(!(try! verifySignature(address: ADDRESS, signatureHex: SIGNATURE, message: Data("tampered".utf8))).valid)
+
+do {
+    _ = try verifySignature(address: ADDRESS, signatureHex: String(SIGNATURE.prefix(128)), message: MESSAGE)
+    fatalError("a short signature should throw")
+} catch VerifyError.WrongSignatureLength(let found, let expected) {
+    assert(found == 63 && expected == 64)
+}
+
+do {
+    let corrupted = ADDRESS.replacingOccurrences(of: "hPF2BR", with: "hPF2BS")
+    _ = try verifySignature(address: corrupted, signatureHex: SIGNATURE, message: MESSAGE)
+    fatalError("a corrupted address should throw")
+} catch VerifyError.InvalidSs58(let reason) {
+    assert(reason == .badChecksum)
+}
+
+let keypair = try! Keypair.fromSeed(seedHex: SEED, scheme: .sr25519)
+assert(keypair.scheme() == .sr25519)
+assert(try! keypair.ss58(prefix: 42) == ALICE)
+let message = Data([0, 1, 2, 0xff])
+assert(try! verifySignature(address: ALICE, signatureHex: keypair.sign(message: message), message: message).valid)
+
+do {
+    _ = try Keypair.fromSeed(seedHex: SEED, scheme: .ethereum)
+    fatalError("an ethereum keypair should throw")
+} catch VerifyError.Other(let code, _) {
+    assert(code < 0)
+}
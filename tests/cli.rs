@@ -0,0 +1,160 @@
+//! Drive the `polkadot-verify` binary:
+//!
+//! ```text
+//! cargo test --features cli --test cli
+//! ```
+
+use assert_cmd::Command;
+use predicates::str::contains;
+use serde_json::{json, Value};
+
+const ADDRESS: &str = "5HbqogsYaK54pN3QAgSi4t3Asqditt1X4P7cSBuDt2hPF2BR";
+const SIGNATURE: &str = "0xf8ce1b80e76bf48a30610d0d3c2c9c8dbfdcb28a9aa8f56ac038d520ff17445ff3ce94809f8479a1224ef0d823d035022a9db2e72bd6c0d2f0244f766dec908b";
+const MESSAGE: &str = "<Bytes>c15335d817e3d3d912d30f5a18a30c30162abc364cd0876d3a94af568d3c8c14</Bytes>";
+const RAW_MESSAGE: &str = "c15335d817e3d3d912d30f5a18a30c30162abc364cd0876d3a94af568d3c8c14";
+
+fn polkadot_verify() -> Command {
+  Command::cargo_bin("polkadot-verify").unwrap()
+}
+
+fn verify(message: &str, extra: &[&str]) -> Command {
+  let mut command = polkadot_verify();
+  command.args(["--address", ADDRESS, "--signature", SIGNATURE, "--message", message]).args(extra);
+  command
+}
+
+fn parse_lines(output: &[u8]) -> Vec<Value> {
+  let output = std::str::from_utf8(output).unwrap();
+  output.lines().map(|line| serde_json::from_str(line).unwrap()).collect()
+}
+
+#[test]
+fn test_exit_codes() {
+  verify(MESSAGE, &[]).assert().success().stdout("valid sr25519\n");
+  verify("tampered", &[]).assert().code(1).stderr(contains("signature does not match address and message"));
+  verify(MESSAGE, &[]).arg("--signature=0x1234").assert().code(2);
+
+  // Malformed input is a failed verification, not a usage error
+  let mut command = polkadot_verify();
+  command.args(["--address", ADDRESS, "--signature", &SIGNATURE[..128], "--message", MESSAGE]);
+  command.assert().code(1).stderr(contains("signature is 63 bytes, expected 64"));
+
+  // Usage errors
+  polkadot_verify().args(["--address", ADDRESS, "--message", MESSAGE]).assert().code(2);
+  verify(MESSAGE, &["--message-file", "tests/fixtures/challenge.txt"]).assert().code(2);
+  verify(MESSAGE, &["--allow-prefix", "polkadot"]).assert().code(2);
+}
+
+#[test]
+fn test_message_file() {
+  let mut command = polkadot_verify();
+  command.args(["--address", ADDRESS, "--signature", SIGNATURE, "--message-file", "tests/fixtures/challenge.txt"]);
+  command.assert().success();
+
+  let mut command = polkadot_verify();
+  command.args(["--address", ADDRESS, "--signature", SIGNATURE, "--message-file", "tests/fixtures/missing.txt"]);
+  command.assert().code(2).stderr(contains("polkadot-verify: "));
+}
+
+#[test]
+fn test_options() {
+  verify(RAW_MESSAGE, &[]).assert().code(1);
+  verify(RAW_MESSAGE, &["--wrap-bytes"]).assert().success();
+  verify(MESSAGE, &["--scheme", "sr25519", "--allow-prefix", "0", "--allow-prefix", "42"]).assert().success();
+  verify(MESSAGE, &["--allow-prefix", "0"]).assert().code(1);
+  verify(MESSAGE, &["--scheme", "ed25519"]).assert().code(1);
+  verify(MESSAGE, &["--scheme", "rsa"]).assert().code(1);
+}
+
+#[test]
+fn test_batch() {
+  let request = |message: &str| json!({"address": ADDRESS, "signature": SIGNATURE, "message": message});
+  let input = format!("{}\n\n{}\r\nnot json\n", request(MESSAGE), request("tampered"));
+  let output = polkadot_verify().arg("batch").write_stdin(input).assert().code(1).get_output().stdout.clone();
+  let results = parse_lines(&output);
+  assert_eq!(results.len(), 3);
+  assert_eq!(results[0], json!({"index": 0, "valid": true, "scheme": "sr25519"}));
+  assert_eq!(results[1]["index"], 1);
+  assert_eq!(results[1]["valid"], false);
+  assert_eq!(results[2]["index"], 2);
+  assert!(results[2]["error_code"].is_i64());
+
+  // Every request valid, with the results in a file
+  let path = std::env::temp_dir().join(format!("polkadot-verify-{}.ndjson", std::process::id()));
+  let input = format!("{}\n{}\n", request(MESSAGE), request(MESSAGE));
+  polkadot_verify().arg("batch").arg("--ndjson").arg(&path).write_stdin(input).assert().success().stdout("");
+  let results = parse_lines(&std::fs::read(&path).unwrap());
+  std::fs::remove_file(&path).unwrap();
+  assert_eq!(results.len(), 2);
+  assert!(results.iter().all(|result| result["valid"] == true));
+
+  // Nothing to verify
+  polkadot_verify().arg("batch").write_stdin("").assert().success().stdout("");
+}
+
+#[test]
+fn test_address_inspect() {
+  let output = polkadot_verify().args(["address", "inspect", ADDRESS]).assert().success();
+  output.stdout(
+    "prefix: 42\nnetwork: substrate\npublic key: 0xf4ec883f28db136cd98e3b553cab48ab4671111978a4660f129a45cb4df84d30\n",
+  );
+  polkadot_verify().args(["address", "inspect", "5HbqogsYaK54pN3QAgSi4t3Asqditt1X4P7cSBuDt2hPF2BS"]).assert().code(1);
+  polkadot_verify().args(["address", "inspect"]).assert().code(2);
+}
+
+#[test]
+fn test_rpc() {
+  let call = |id: u32, method: &str, params: Value| {
+    json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params})
+  };
+  let request = json!({"address": ADDRESS, "signature": SIGNATURE, "message": MESSAGE});
+  let mut short = request.clone();
+  short["signature"] = json!(&SIGNATURE[..128]);
+  let input = [
+    call(1, "verify", request.clone()).to_string(),
+    call(2, "verify", json!({"address": ADDRESS, "signature": SIGNATURE, "message": "tampered"})).to_string(),
+    call(3, "verify", short.clone()).to_string(),
+    call(4, "verify_batch", json!([request, short])).to_string(),
+    call(5, "validate_address", json!({"address": ADDRESS})).to_string(),
+    call(6, "validate_address", json!({"address": "not an address"})).to_string(),
+    String::new(),
+    json!({"jsonrpc": "2.0", "method": "version"}).to_string(),
+    call(7, "version", Value::Null).to_string(),
+    call(8, "sign", json!({})).to_string(),
+    call(9, "verify", json!({"address": ADDRESS})).to_string(),
+    "{\"jsonrpc\": \"2.0\", \"id\": 10,".to_owned(),
+    json!({"id": 11, "method": "version"}).to_string(),
+  ];
+  let output = polkadot_verify().arg("rpc").write_stdin(input.join("\n")).assert().success().stderr("");
+  let responses = parse_lines(&output.get_output().stdout);
+
+  // The notification gets no response
+  let ids: Vec<_> = responses.iter().map(|response| response["id"].as_i64()).collect();
+  assert_eq!(ids, [Some(1), Some(2), Some(3), Some(4), Some(5), Some(6), Some(7), Some(8), Some(9), None, Some(11)]);
+  assert!(responses.iter().all(|response| response["jsonrpc"] == "2.0"));
+
+  assert_eq!(responses[0]["result"], json!({"valid": true, "scheme": "sr25519"}));
+  assert_eq!(responses[1]["result"]["valid"], false);
+  let error = &responses[2]["error"];
+  assert_eq!(error["code"], polkadot_sig_verifier::ERR_BAD_SIGNATURE_LENGTH);
+  assert_eq!(error["message"], "signature is 63 bytes, expected 64");
+  assert_eq!(error["data"], json!({"name": "ERR_BAD_SIGNATURE_LENGTH"}));
+
+  let outcomes = responses[3]["result"].as_array().unwrap();
+  assert_eq!(outcomes[0]["valid"], true);
+  assert_eq!(outcomes[1]["error_code"], polkadot_sig_verifier::ERR_BAD_SIGNATURE_LENGTH);
+
+  assert_eq!(responses[4]["result"]["prefix"], 42);
+  assert_eq!(responses[4]["result"]["network"], "substrate");
+  assert!(responses[5]["error"]["code"].as_i64().unwrap() < 0);
+  assert_eq!(responses[6]["result"]["version"], env!("CARGO_PKG_VERSION"));
+  assert_eq!(responses[7]["error"]["code"], -32601);
+  assert_eq!(responses[8]["error"]["code"], -32602);
+  assert_eq!(responses[9]["error"]["code"], -32700);
+  assert_eq!(responses[10]["error"]["code"], -32600);
+}
+
+#[test]
+fn test_rpc_eof() {
+  polkadot_verify().arg("rpc").write_stdin("").assert().success().stdout("").stderr("");
+}
@@ -0,0 +1,98 @@
+//! Run the HTTP service on an ephemeral port and call it:
+//!
+//! ```text
+//! cargo test --features service --test service
+//! ```
+
+use polkadot_sig_verifier::service::{self, ServiceConfig};
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+
+const ADDRESS: &str = "5HbqogsYaK54pN3QAgSi4t3Asqditt1X4P7cSBuDt2hPF2BR";
+const SIGNATURE: &str = "0xf8ce1b80e76bf48a30610d0d3c2c9c8dbfdcb28a9aa8f56ac038d520ff17445ff3ce94809f8479a1224ef0d823d035022a9db2e72bd6c0d2f0244f766dec908b";
+const MESSAGE: &str = "<Bytes>c15335d817e3d3d912d30f5a18a30c30162abc364cd0876d3a94af568d3c8c14</Bytes>";
+
+/// Start the service, returning its base URL.
+async fn start(config: ServiceConfig) -> String {
+  let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+  let url = format!("http://{}", listener.local_addr().unwrap());
+  tokio::spawn(service::serve(listener, config));
+  url
+}
+
+fn request(message: &str) -> Value {
+  json!({"address": ADDRESS, "signature": SIGNATURE, "message": message})
+}
+
+async fn post(url: &str, body: &Value) -> (StatusCode, Value) {
+  let response = reqwest::Client::new().post(url).json(body).send().await.unwrap();
+  (response.status(), response.json().await.unwrap())
+}
+
+#[tokio::test]
+async fn test_verify() {
+  let url = start(ServiceConfig::default()).await + "/verify";
+  assert_eq!(post(&url, &request(MESSAGE)).await, (StatusCode::OK, json!({"valid": true, "scheme": "sr25519"})));
+
+  let (status, outcome) = post(&url, &request("tampered")).await;
+  assert_eq!(status, StatusCode::OK);
+  assert_eq!(outcome["valid"], false);
+
+  let mut short = request(MESSAGE);
+  short["signature"] = json!(&SIGNATURE[..128]);
+  let (status, outcome) = post(&url, &short).await;
+  assert_eq!(status, StatusCode::OK);
+  assert_eq!(outcome["error_message"], "signature is 63 bytes, expected 64");
+}
+
+#[tokio::test]
+async fn test_malformed_json() {
+  let url = start(ServiceConfig::default()).await + "/verify";
+  let client = reqwest::Client::new();
+  let response = client.post(&url).header("content-type", "application/json").body("{\"address\":").send().await;
+  let response = response.unwrap();
+  assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+  let body: Value = response.json().await.unwrap();
+  assert!(body["error"].is_string());
+
+  // JSON, but not a request
+  let (status, body) = post(&url, &json!({"address": ADDRESS})).await;
+  assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+  assert!(body["error"].is_string());
+}
+
+#[tokio::test]
+async fn test_verify_batch() {
+  let config = ServiceConfig { max_batch_len: 3, concurrency: 2, ..ServiceConfig::default() };
+  let url = start(config).await + "/verify/batch";
+  let (status, outcomes) = post(&url, &json!([request(MESSAGE), request("tampered"), request(MESSAGE)])).await;
+  assert_eq!(status, StatusCode::OK);
+  let valid: Vec<_> = outcomes.as_array().unwrap().iter().map(|outcome| outcome["valid"].clone()).collect();
+  assert_eq!(valid, [true, false, true]);
+
+  assert_eq!(post(&url, &json!([])).await, (StatusCode::OK, json!([])));
+
+  let (status, body) = post(&url, &Value::Array(vec![request(MESSAGE); 4])).await;
+  assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+  assert_eq!(body["error"], "batch has 4 requests, at most 3 are accepted");
+}
+
+#[tokio::test]
+async fn test_body_limit() {
+  let url = start(ServiceConfig { max_body_bytes: 1024, ..ServiceConfig::default() }).await;
+  let (status, _) = post(&format!("{url}/verify"), &request(&"a".repeat(2048))).await;
+  assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+  assert_eq!(post(&format!("{url}/verify"), &request(MESSAGE)).await.0, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_healthz() {
+  let url = start(ServiceConfig::default()).await + "/healthz";
+  let response = reqwest::get(&url).await.unwrap();
+  assert_eq!(response.status(), StatusCode::OK);
+  let health: Value = response.json().await.unwrap();
+  assert_eq!(health["status"], "ok");
+  assert_eq!(health["version"], env!("CARGO_PKG_VERSION"));
+  assert!(health["features"].as_array().unwrap().contains(&json!("service")));
+}
@@ -0,0 +1,8 @@
+//! Run the Kotlin and Swift scripts in `tests/bindings` against freshly
+//! generated bindings. Needs `kotlinc` and `swiftc` on the PATH:
+//!
+//! ```text
+//! cargo test --features uniffi --test uniffi
+//! ```
+
+uniffi::build_foreign_language_testcases!("tests/bindings/test_bindings.kts", "tests/bindings/test_bindings.swift");
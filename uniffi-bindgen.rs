@@ -0,0 +1,6 @@
+//! Generate the Swift and Kotlin bindings of a library built with the
+//! `uniffi` feature. See `src/mobile.rs`.
+
+fn main() {
+  uniffi::uniffi_bindgen_main()
+}
@@ -0,0 +1,105 @@
+//! Maintenance tasks for the workspace, run as `cargo xtask <task>`.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The committed C header, relative to the workspace root.
+pub const HEADER_PATH: &str = "include/polkadot_signature_verifier.h";
+
+/// A copy of `ss58-registry.json` from paritytech/ss58-registry, relative to
+/// the workspace root. Replace it with a newer release to pick up networks
+/// registered since.
+pub const REGISTRY_JSON_PATH: &str = "xtask/ss58-registry.json";
+
+/// The network table generated from [`REGISTRY_JSON_PATH`], included by
+/// `src/registry.rs`.
+pub const REGISTRY_TABLE_PATH: &str = "src/registry_table.rs";
+
+/// The workspace root, which holds the main crate and `cbindgen.toml`.
+pub fn workspace_root() -> PathBuf {
+  Path::new(env!("CARGO_MANIFEST_DIR")).parent().expect("xtask lives inside the workspace").to_path_buf()
+}
+
+/// Generate the C header for the exported functions, `#[repr(C)]` types
+/// and constants of the main crate.
+///
+/// cbindgen reads the crate through `rustc -Zunpretty=expanded`, see the
+/// `[parse.expand]` section of `cbindgen.toml`. That flag needs a nightly
+/// compiler, or `RUSTC_BOOTSTRAP=1` on a stable one, which this sets.
+pub fn generate_header() -> Result<String, cbindgen::Error> {
+  std::env::set_var("RUSTC_BOOTSTRAP", "1");
+  let root = workspace_root();
+  let config = cbindgen::Config::from_file(root.join("cbindgen.toml")).expect("cbindgen.toml parses");
+  let mut header = Vec::new();
+  cbindgen::Builder::new().with_crate(&root).with_config(config).generate()?.write(&mut header);
+  Ok(String::from_utf8(header).expect("cbindgen writes UTF-8"))
+}
+
+/// Generate the SS58 network table from the vendored registry, one
+/// `prefix network display_name symbol decimals` row per entry, sorted by
+/// prefix. Networks with several tokens are listed with their first.
+pub fn generate_registry_table() -> String {
+  let json = std::fs::read_to_string(workspace_root().join(REGISTRY_JSON_PATH)).expect("registry is readable");
+  let registry: serde_json::Value = serde_json::from_str(&json).expect("registry is JSON");
+  let mut entries: Vec<_> = registry["registry"].as_array().expect("registry has a list of entries").iter().collect();
+  entries.sort_by_key(|entry| entry["prefix"].as_u64());
+
+  let mut table = format!("// Generated from {REGISTRY_JSON_PATH} by `cargo xtask registry`, do not edit.\n\n");
+  table.push_str("#[rustfmt::skip]\npub(crate) const NETWORKS: &[Network] = networks! {\n");
+  for entry in entries {
+    let text = |field: &str| entry[field].as_str().unwrap_or_else(|| panic!("{field} of {entry} is a string"));
+    let symbol = entry["symbols"][0].as_str().map_or("None".to_owned(), |symbol| format!("{symbol:?}"));
+    let decimals = entry["decimals"][0].as_u64().unwrap_or(0);
+    let (prefix, network, display_name) = (&entry["prefix"], text("network"), text("displayName"));
+    table.push_str(&format!("  {prefix} {network:?} {display_name:?} {symbol} {decimals};\n"));
+  }
+  table.push_str("};\n");
+  table
+}
+
+/// The crates the main crate links with `features` and no default
+/// features, one per line as `cargo tree` prints them. Dev-dependencies
+/// are left out.
+pub fn dependency_tree(features: &str) -> String {
+  let output = Command::new(env!("CARGO"))
+    .current_dir(workspace_root())
+    .args(["tree", "-p", "polkadot_sig_verifier", "--no-default-features", "--features", features])
+    .args(["--edges", "normal,build", "--prefix", "none"])
+    .output()
+    .expect("cargo runs");
+  assert!(output.status.success(), "cargo tree failed: {}", String::from_utf8_lossy(&output.stderr));
+  String::from_utf8(output.stdout).expect("cargo prints UTF-8")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Fails when an exported item changed without `cargo xtask header`
+  /// being run, so C callers never get a stale header.
+  #[test]
+  fn test_header_is_up_to_date() {
+    let committed = std::fs::read_to_string(workspace_root().join(HEADER_PATH)).unwrap();
+    let generated = generate_header().unwrap();
+    assert!(generated == committed, "{HEADER_PATH} is stale, run `cargo xtask header`");
+  }
+
+  /// Fails when the vendored registry changed without `cargo xtask
+  /// registry` being run.
+  #[test]
+  fn test_registry_table_is_up_to_date() {
+    let committed = std::fs::read_to_string(workspace_root().join(REGISTRY_TABLE_PATH)).unwrap();
+    let generated = generate_registry_table();
+    assert!(generated == committed, "{REGISTRY_TABLE_PATH} is stale, run `cargo xtask registry`");
+  }
+
+  /// `--features minimal` only saves the size and build time of sp-core if
+  /// nothing else pulls it in. The tests still check both backends against
+  /// sp-core, which is why dev-dependencies are not counted.
+  #[test]
+  fn test_minimal_build_has_no_sp_core() {
+    let tree = dependency_tree("minimal");
+    let sp_core = tree.lines().find(|line| line.starts_with("sp-core "));
+    assert!(sp_core.is_none(), "--no-default-features --features minimal builds {}", sp_core.unwrap_or_default());
+  }
+}
@@ -0,0 +1,30 @@
+//! `cargo xtask header` regenerates the committed C header, and `cargo
+//! xtask registry` the SS58 network table.
+
+use std::process::ExitCode;
+use xtask::{generate_header, generate_registry_table, workspace_root, HEADER_PATH, REGISTRY_TABLE_PATH};
+
+fn main() -> ExitCode {
+  match std::env::args().nth(1).as_deref() {
+    Some("header") => match generate_header() {
+      Ok(header) => {
+        std::fs::write(workspace_root().join(HEADER_PATH), header).expect("header is writable");
+        println!("wrote {HEADER_PATH}");
+        ExitCode::SUCCESS
+      }
+      Err(error) => {
+        eprintln!("cbindgen failed: {error}");
+        ExitCode::FAILURE
+      }
+    },
+    Some("registry") => {
+      std::fs::write(workspace_root().join(REGISTRY_TABLE_PATH), generate_registry_table()).expect("table is writable");
+      println!("wrote {REGISTRY_TABLE_PATH}");
+      ExitCode::SUCCESS
+    }
+    _ => {
+      eprintln!("usage: cargo xtask header|registry");
+      ExitCode::FAILURE
+    }
+  }
+}